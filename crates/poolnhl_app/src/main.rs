@@ -1,17 +1,34 @@
 use std::sync::Arc;
 
 use poolnhl_infrastructure::{
-    database_connection::DatabaseManager, jwt::CachedJwks, services::ServiceRegistry,
+    cache::init_pool_cache,
+    database_connection::DatabaseManager,
+    jwt::CachedJwks,
+    services::{draft_service::create_chat_collection, ServiceRegistry},
     settings::Settings,
 };
 
+use poolnhl_interface::pool::authorization::set_site_admins;
 use poolnhl_routing::router::ApplicationController;
+use tokio::signal;
+use tracing::{error, info};
 
 #[tokio::main]
 async fn main() {
+    // Log from the start, the failures of the startup steps are logged too.
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
     let settings = Settings::new().expect("Could not parse settings");
 
-    // Make the database connection.
+    // The site administrators can manage any pool for support purposes.
+    set_site_admins(&settings.auth.admins);
+
+    // Keep the pools read on every request in memory.
+    init_pool_cache(&settings.cache);
+
+    // Make the database connection, the indexes of the collections are created with it.
     let db = DatabaseManager::new_pool(
         settings.database.uri.as_str(),
         settings.database.name.as_str(),
@@ -19,6 +36,11 @@ async fn main() {
     .await
     .expect("Could not initialize the database");
 
+    // Create the capped collection keeping the chat history of the pools.
+    create_chat_collection(&db)
+        .await
+        .expect("Could not create the chat messages collection");
+
     // query and cached the JSON Web key set fetch from hanko.
     // This will allow to validate the JWT sent to the application.
     let cached_jwks = Arc::new(
@@ -26,8 +48,58 @@ async fn main() {
             .await
             .expect("Was not able to query the JWKS from hanko server."),
     );
-    let services = ServiceRegistry::new(db, cached_jwks);
 
-    // Run the application.
-    ApplicationController::run(settings, services).await;
+    // Keep the JWKS up to date when hanko rotates its keys.
+    cached_jwks.spawn_refresh();
+    let services = ServiceRegistry::new(db, cached_jwks, &settings);
+
+    // Run the scheduled jobs: the players synchronization with the NHL api,
+    // the nightly cumulation of the pools points and the execution of the reviewed trades.
+    services.jobs_service.start();
+
+    // Run the application until a SIGTERM or SIGINT is received.
+    // The draft sockets are notified before the server drains the connections,
+    // so they close instead of keeping the server alive.
+    let draft_server_info = services.draft_server_info.clone();
+    let shutdown = async move {
+        shutdown_signal().await;
+
+        // Complete the pool writes started by the draft sockets, then let them know.
+        if let Err(e) = draft_server_info
+            .shutdown("The server is restarting, reconnect in a few moments.")
+            .await
+        {
+            error!("Could not shut down the draft sockets: {}", e);
+        }
+    };
+    ApplicationController::run(settings, services, shutdown).await;
+
+    info!("The server stopped.");
+}
+
+// Completes once the process is asked to stop.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("Could not listen for the SIGINT signal");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Could not listen for the SIGTERM signal")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    info!("Shutting down the server.");
 }
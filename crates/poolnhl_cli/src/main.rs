@@ -0,0 +1,86 @@
+use std::env;
+use std::process;
+
+use poolnhl_infrastructure::{database_connection::DatabaseManager, settings::Settings};
+
+mod random;
+mod seed;
+
+use seed::{seed, SeedOptions};
+
+const USAGE: &str = "Usage: poolnhl_cli seed [--users <number>] [--players <number>] [--owner <user id>] [--random-seed <number>]
+
+Create fake poolers, fake players, a mid-season pool with its daily points and a pool in the middle of its draft.
+The database of the settings (config/debug.json or config/release.json) is used.
+
+Options:
+    --users <number>        The number of poolers of each pool, including the owner (default: 8).
+    --players <number>      The number of players created in the players collection (default: 600).
+    --owner <user id>       The user owning and participating in the pools, your hanko user id (default: a fake user).
+    --random-seed <number>  The seed of the generated data, the same seed generates the same data (default: 1).";
+
+#[tokio::main]
+async fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let options = match parse_seed_options(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(1);
+        }
+    };
+
+    let settings = Settings::new().expect("Could not parse settings");
+
+    let db = DatabaseManager::new_pool(
+        settings.database.uri.as_str(),
+        settings.database.name.as_str(),
+    )
+    .await
+    .expect("Could not initialize the database");
+
+    if let Err(e) = seed(&db, &options).await {
+        eprintln!("Could not seed the database: {}", e);
+        process::exit(1);
+    }
+}
+
+fn parse_seed_options(args: &[String]) -> Result<SeedOptions, String> {
+    let Some((command, options)) = args.split_first() else {
+        return Err("A command is required.".to_string());
+    };
+
+    if command != "seed" {
+        return Err(format!("The command '{}' does not exist.", command));
+    }
+
+    let mut seed_options = SeedOptions::default();
+    let mut options = options.iter();
+
+    while let Some(option) = options.next() {
+        let value = options
+            .next()
+            .ok_or_else(|| format!("The option '{}' requires a value.", option))?;
+
+        match option.as_str() {
+            "--users" => seed_options.number_users = parse_number(option, value)?,
+            "--players" => seed_options.number_players = parse_number(option, value)?,
+            "--owner" => seed_options.owner_id = Some(value.clone()),
+            "--random-seed" => seed_options.random_seed = parse_number(option, value)?,
+            _ => return Err(format!("The option '{}' does not exist.", option)),
+        }
+    }
+
+    if seed_options.number_users < 2 {
+        return Err("A pool needs at least 2 users.".to_string());
+    }
+
+    Ok(seed_options)
+}
+
+fn parse_number<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("The value of '{}' needs to be a number.", option))
+}
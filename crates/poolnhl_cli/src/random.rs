@@ -0,0 +1,52 @@
+// Small deterministic generator (xorshift64*), the same seed always generates the same data.
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // The state of a xorshift generator can never be 0.
+        Self {
+            state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1,
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // A number between 0 (inclusive) and 1 (exclusive).
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // A number between min and max (inclusive).
+    pub fn range(&mut self, min: u32, max: u32) -> u32 {
+        min + (self.next_u64() % u64::from(max - min + 1)) as u32
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.next_u64() as usize % items.len()]
+    }
+
+    // The number of events happening at an average rate (i.g., the goals of a player in a game).
+    pub fn poisson(&mut self, mean: f64) -> u8 {
+        let limit = (-mean).exp();
+        let mut product = self.next_f64();
+        let mut count = 0;
+
+        while product > limit && count < u8::MAX {
+            product *= self.next_f64();
+            count += 1;
+        }
+
+        count
+    }
+}
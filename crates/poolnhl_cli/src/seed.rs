@@ -0,0 +1,487 @@
+use chrono::Duration;
+use mongodb::bson::doc;
+use mongodb::options::ReplaceOptions;
+use uuid::Uuid;
+
+use poolnhl_infrastructure::database_connection::DatabaseConnection;
+use poolnhl_interface::draft::model::RoomUser;
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::players::model::PlayerInfo;
+use poolnhl_interface::pool::model::{
+    DailyPlayersPoints, GoalyPoints, Pool, PoolPlayerInfo, PoolSettings, PoolState, Position,
+    SeasonDates, SkaterPoints, POOL_CREATION_SEASON,
+};
+use poolnhl_interface::time_provider::time_provider;
+
+use crate::random::Random;
+
+// The fake players get ids far from the ids of the NHL api so they never replace a real player.
+const FIRST_PLAYER_ID: u32 = 9_000_000;
+
+const MID_SEASON_POOL_NAME: &str = "Seeded mid-season pool";
+const DRAFT_POOL_NAME: &str = "Seeded draft pool";
+
+// The seeded season started 60 days ago, the mid-season pool has a daily score for each of them.
+const DAYS_SINCE_SEASON_START: i64 = 60;
+const DAYS_BEFORE_SEASON_END: i64 = 120;
+
+// An NHL team plays 82 games in about 180 days.
+const TEAM_GAME_PROBABILITY: f64 = 0.45;
+const NUMBER_TEAMS: u32 = 32;
+
+const FIRST_NAMES: [&str; 20] = [
+    "Alex", "Connor", "Nathan", "Samuel", "Mathieu", "Olivier", "William", "Logan", "Jake", "Ryan",
+    "Mikko", "Elias", "Lucas", "Nikita", "Erik", "Jonathan", "Cole", "Dylan", "Owen", "Victor",
+];
+
+const LAST_NAMES: [&str; 20] = [
+    "Tremblay",
+    "Gagnon",
+    "Roy",
+    "Cote",
+    "Bouchard",
+    "Gauthier",
+    "Morin",
+    "Lavoie",
+    "Fortin",
+    "Gagne",
+    "Smith",
+    "Johnson",
+    "Brown",
+    "Miller",
+    "Larsson",
+    "Lindqvist",
+    "Petrov",
+    "Novak",
+    "Makar",
+    "Kowalski",
+];
+
+pub struct SeedOptions {
+    pub number_users: usize,
+    pub number_players: u32,
+    pub owner_id: Option<String>,
+    pub random_seed: u64,
+}
+
+impl Default for SeedOptions {
+    fn default() -> Self {
+        Self {
+            number_users: 8,
+            number_players: 600,
+            owner_id: None,
+            random_seed: 1,
+        }
+    }
+}
+
+// A generated player with the average it scores at.
+struct SeededPlayer {
+    info: PoolPlayerInfo,
+
+    // The points per game of a skater or the win percentage of a goalie.
+    quality: f64,
+}
+
+pub async fn seed(db: &DatabaseConnection, options: &SeedOptions) -> Result<()> {
+    let mut random = Random::new(options.random_seed);
+
+    let users = create_users(&mut random, options);
+    let players = create_players(db, &mut random, options.number_players).await?;
+
+    let mid_season_pool = create_mid_season_pool(&mut random, &users, &players)?;
+    let draft_pool = create_draft_pool(&mut random, &users, &players)?;
+
+    let collection = db.collection::<Pool>("pools");
+
+    // The pools seeded by a previous run are replaced.
+    collection
+        .delete_many(
+            doc! {"name": {"$in": [MID_SEASON_POOL_NAME, DRAFT_POOL_NAME]}},
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    collection
+        .insert_many([&mid_season_pool, &draft_pool], None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    println!("Created {} players.", players.len());
+    println!("Created the users:");
+    for user in &users {
+        println!("    {} ({})", user.name, user.id);
+    }
+    println!(
+        "Created the pool '{}' with {} days of points.",
+        mid_season_pool.name, DAYS_SINCE_SEASON_START
+    );
+    println!(
+        "Created the pool '{}' in the middle of its draft.",
+        draft_pool.name
+    );
+
+    Ok(())
+}
+
+fn create_users(random: &mut Random, options: &SeedOptions) -> Vec<RoomUser> {
+    // The owner is the first user, the users with an email are owned by an account.
+    let mut users = Vec::new();
+
+    if let Some(owner_id) = &options.owner_id {
+        users.push(RoomUser {
+            id: owner_id.clone(),
+            name: "Pool Owner".to_string(),
+            email: Some(String::new()),
+            is_ready: true,
+        });
+    }
+
+    while users.len() < options.number_users {
+        users.push(RoomUser {
+            id: Uuid::new_v4().to_string(),
+            name: format!(
+                "{} {}",
+                random.choose(&FIRST_NAMES),
+                random.choose(&LAST_NAMES)
+            ),
+            email: None,
+            is_ready: true,
+        });
+    }
+
+    users
+}
+
+async fn create_players(
+    db: &DatabaseConnection,
+    random: &mut Random,
+    number_players: u32,
+) -> Result<Vec<SeededPlayer>> {
+    // The players are distributed like an NHL roster: 60% forwards, 30% defenders and 10% goalies.
+    let collection = db.collection::<PlayerInfo>("players");
+    let mut players = Vec::new();
+
+    for index in 0..number_players {
+        let position = match index % 10 {
+            0..=5 => Position::F,
+            6..=8 => Position::D,
+            _ => Position::G,
+        };
+
+        let quality = match position {
+            Position::F => 0.2 + random.next_f64() * 1.1,
+            Position::D => 0.1 + random.next_f64() * 0.7,
+            Position::G => 0.35 + random.next_f64() * 0.3,
+        };
+
+        let game_played = random.range(20, 82);
+        let points = (quality * f64::from(game_played)).round() as u32;
+        let goals = points * random.range(25, 50) / 100;
+
+        let player = PlayerInfo {
+            active: true,
+            id: FIRST_PLAYER_ID + index,
+            name: format!(
+                "{} {}",
+                random.choose(&FIRST_NAMES),
+                random.choose(&LAST_NAMES)
+            ),
+            team: Some(random.range(1, NUMBER_TEAMS)),
+            position: position.as_str().to_string(),
+            age: Some(random.range(19, 38) as u8),
+            // The best players have the biggest contracts (in millions).
+            salary_cap: Some((0.775 + quality.min(1.0) * 11.0 * random.next_f64()).max(0.775)),
+            contract_expiration_season: Some(POOL_CREATION_SEASON + random.range(0, 5) * 10001),
+            game_played: Some(game_played),
+            goals: (!matches!(position, Position::G)).then_some(goals),
+            assists: (!matches!(position, Position::G)).then_some(points - goals),
+            points: (!matches!(position, Position::G)).then_some(points),
+            points_per_game: (!matches!(position, Position::G))
+                .then_some(points as f32 / game_played as f32),
+            goal_against_average: matches!(position, Position::G)
+                .then_some((3.6 - quality * 2.0) as f32),
+            save_percentage: matches!(position, Position::G)
+                .then_some((0.88 + quality * 0.05) as f32),
+            wins: matches!(position, Position::G).then_some(points / 2),
+            shutouts: matches!(position, Position::G).then_some(points / 20),
+            last_season_stats: None,
+            positions: None,
+            positions_season: None,
+            injury_status: None,
+        };
+
+        collection
+            .replace_one(
+                doc! {"id": player.id},
+                &player,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        players.push(SeededPlayer {
+            info: PoolPlayerInfo::try_from(player)?,
+            quality,
+        });
+    }
+
+    // The poolers draft the best players first.
+    players.sort_by(|a, b| b.quality.total_cmp(&a.quality));
+
+    Ok(players)
+}
+
+fn create_pool(
+    random: &mut Random,
+    name: &str,
+    users: &[RoomUser],
+    season_dates: &SeasonDates,
+) -> Result<Pool> {
+    let mut settings = PoolSettings::new();
+    settings.number_poolers = users.len() as u8;
+
+    let owner = &users[0].id;
+    let mut pool = Pool::new(name, owner, &settings, season_dates);
+
+    // Shuffle the draft order.
+    let mut draft_order: Vec<String> = users.iter().map(|user| user.id.clone()).collect();
+    for index in (1..draft_order.len()).rev() {
+        draft_order.swap(index, random.range(0, index as u32) as usize);
+    }
+
+    pool.start_draft(owner, &users.to_vec(), &draft_order)?;
+
+    Ok(pool)
+}
+
+fn create_mid_season_pool(
+    random: &mut Random,
+    users: &[RoomUser],
+    players: &[SeededPlayer],
+) -> Result<Pool> {
+    let today = time_provider().today();
+    let season_start = today - Duration::days(DAYS_SINCE_SEASON_START);
+
+    let season_dates = SeasonDates {
+        season: POOL_CREATION_SEASON,
+        season_start: season_start.to_string(),
+        season_end: (today + Duration::days(DAYS_BEFORE_SEASON_END)).to_string(),
+        trade_deadline: (today + Duration::days(DAYS_BEFORE_SEASON_END / 2)).to_string(),
+    };
+
+    let mut pool = create_pool(random, MID_SEASON_POOL_NAME, users, &season_dates)?;
+    let number_picks = users.len() * pool.settings.get_roster_size() as usize;
+
+    draft_players(random, &mut pool, players, number_picks)?;
+
+    if !matches!(pool.status, PoolState::InProgress) {
+        return Err(AppError::CustomError {
+            msg: "The draft of the mid-season pool is not completed.".to_string(),
+        });
+    }
+
+    let context = pool.context.as_mut().ok_or_else(|| AppError::CustomError {
+        msg: "pool context does not exist.".to_string(),
+    })?;
+
+    let mut date = season_start;
+    while date < today {
+        let daily_players_points = create_daily_players_points(random, players);
+        context.cumulate_daily_roster_points(
+            &date.to_string(),
+            &daily_players_points,
+            &pool.settings,
+        );
+
+        date += Duration::days(1);
+    }
+
+    Ok(pool)
+}
+
+fn create_draft_pool(
+    random: &mut Random,
+    users: &[RoomUser],
+    players: &[SeededPlayer],
+) -> Result<Pool> {
+    // The season of the draft pool starts in a week, half of the picks are made.
+    let today = time_provider().today();
+    let season_start = today + Duration::days(7);
+
+    let season_dates = SeasonDates {
+        season: POOL_CREATION_SEASON,
+        season_start: season_start.to_string(),
+        season_end: (season_start
+            + Duration::days(DAYS_SINCE_SEASON_START + DAYS_BEFORE_SEASON_END))
+        .to_string(),
+        trade_deadline: (season_start + Duration::days(DAYS_SINCE_SEASON_START * 2)).to_string(),
+    };
+
+    let mut pool = create_pool(random, DRAFT_POOL_NAME, users, &season_dates)?;
+    let number_picks = users.len() * pool.settings.get_roster_size() as usize / 2;
+
+    draft_players(random, &mut pool, players, number_picks)?;
+
+    Ok(pool)
+}
+
+fn draft_players(
+    random: &mut Random,
+    pool: &mut Pool,
+    players: &[SeededPlayer],
+    number_picks: usize,
+) -> Result<()> {
+    // The owner makes every pick, each pooler takes one of the best players available at the position it needs.
+    let owner = pool.owner.clone();
+
+    for _ in 0..number_picks {
+        let drafter = pool.get_next_drafter()?;
+        let position = get_needed_position(pool, &drafter)?;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let available_players: Vec<&PoolPlayerInfo> = players
+            .iter()
+            .map(|player| &player.info)
+            .filter(|player| {
+                position
+                    .as_ref()
+                    .map_or(!matches!(player.position, Position::G), |position| {
+                        position.as_str() == player.position.as_str()
+                    })
+            })
+            .filter(|player| !context.players.contains_key(&player.id.to_string()))
+            .take(3)
+            .collect();
+
+        if available_players.is_empty() {
+            return Err(AppError::CustomError {
+                msg: "There are not enough players to complete the draft, use more players."
+                    .to_string(),
+            });
+        }
+
+        let player = (*random.choose(&available_players)).clone();
+        pool.draft_player(&owner, &player)?;
+    }
+
+    Ok(())
+}
+
+fn get_needed_position(pool: &Pool, drafter: &str) -> Result<Option<Position>> {
+    // The position with the most empty spots (in proportion), None when only the reservists are left.
+    let pooler_roster = pool
+        .context
+        .as_ref()
+        .and_then(|context| context.pooler_roster.get(drafter))
+        .ok_or_else(|| AppError::CustomError {
+            msg: "Pooler roster does not exist.".to_string(),
+        })?;
+
+    let settings = &pool.settings;
+
+    let position = [
+        (
+            Position::F,
+            pooler_roster.chosen_forwards.len(),
+            settings.number_forwards,
+        ),
+        (
+            Position::D,
+            pooler_roster.chosen_defenders.len(),
+            settings.number_defenders,
+        ),
+        (
+            Position::G,
+            pooler_roster.chosen_goalies.len(),
+            settings.number_goalies,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, count, total)| *count < usize::from(*total))
+    .map(|(position, count, total)| {
+        let empty_spots = (usize::from(total) - count) as f64 / f64::from(total);
+        (position, empty_spots)
+    })
+    .max_by(|(_, a), (_, b)| a.total_cmp(b))
+    .map(|(position, _)| position);
+
+    Ok(position)
+}
+
+fn create_daily_players_points(
+    random: &mut Random,
+    players: &[SeededPlayer],
+) -> DailyPlayersPoints {
+    // Half of the teams play on a given day, the starting goalie of a team is picked at random.
+    let teams_playing: Vec<bool> = (0..=NUMBER_TEAMS)
+        .map(|_| random.chance(TEAM_GAME_PROBABILITY))
+        .collect();
+
+    let mut daily_players_points = DailyPlayersPoints::default();
+
+    for player in players {
+        let is_team_playing = player
+            .info
+            .team
+            .is_some_and(|team| teams_playing[team as usize]);
+
+        if !is_team_playing {
+            continue;
+        }
+
+        match player.info.position {
+            Position::F | Position::D => {
+                let goals = random.poisson(player.quality * 0.4);
+                let assists = random.poisson(player.quality * 0.6);
+
+                daily_players_points.skaters.insert(
+                    player.info.id.to_string(),
+                    SkaterPoints {
+                        G: goals,
+                        A: assists,
+                        SOG: Some(0),
+                        S: Some(random.poisson(2.5)),
+                        HIT: Some(random.poisson(1.5)),
+                        BLK: Some(random.poisson(1.0)),
+                        PIM: Some(if random.chance(0.1) { 2 } else { 0 }),
+                        PPP: Some((goals + assists).min(random.poisson(player.quality * 0.3))),
+                        SHP: Some(0),
+                        FOW: Some(if matches!(player.info.position, Position::F) {
+                            random.poisson(3.0)
+                        } else {
+                            0
+                        }),
+                    },
+                );
+            }
+            Position::G => {
+                if !random.chance(0.6) {
+                    continue;
+                }
+
+                let is_win = random.chance(player.quality);
+                let goals_against = random.poisson(if is_win { 2.0 } else { 3.5 });
+
+                daily_players_points.goalies.insert(
+                    player.info.id.to_string(),
+                    GoalyPoints {
+                        G: 0,
+                        A: random.poisson(0.05),
+                        W: is_win,
+                        SO: is_win && goals_against == 0,
+                        OT: !is_win && random.chance(0.2),
+                        SV: Some(random.range(20, 35) as u8),
+                        GA: Some(goals_against),
+                    },
+                );
+            }
+        }
+    }
+
+    daily_players_points
+}
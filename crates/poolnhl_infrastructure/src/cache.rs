@@ -0,0 +1,58 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use moka::future::Cache;
+
+use poolnhl_interface::pool::model::Pool;
+
+use crate::settings::CacheSettings;
+
+// Cache of the pools without their score_by_day member, the hot read of every websocket command and HTTP request.
+// The cached pool is replaced by every update made through this instance,
+// the time to live bounds the staleness of the updates made by other instances.
+// Only the read-only paths read from it, the updates always start from the database pool.
+#[derive(Clone)]
+pub struct PoolCache {
+    pools: Option<Cache<String, Pool>>,
+}
+
+static POOL_CACHE: OnceLock<PoolCache> = OnceLock::new();
+
+impl PoolCache {
+    pub fn new(settings: &CacheSettings) -> Self {
+        let pools = settings.enabled.then(|| {
+            Cache::builder()
+                .max_capacity(settings.max_pools)
+                .time_to_live(Duration::from_secs(settings.ttl_seconds))
+                .build()
+        });
+
+        Self { pools }
+    }
+
+    pub async fn get(&self, pool_name: &str) -> Option<Pool> {
+        self.pools.as_ref()?.get(pool_name).await
+    }
+
+    pub async fn insert(&self, pool: &Pool) {
+        if let Some(pools) = &self.pools {
+            pools.insert(pool.name.clone(), pool.clone()).await;
+        }
+    }
+
+    pub async fn invalidate(&self, pool_name: &str) {
+        if let Some(pools) = &self.pools {
+            pools.invalidate(pool_name).await;
+        }
+    }
+}
+
+pub fn init_pool_cache(settings: &CacheSettings) {
+    // Only the first initialization is kept.
+    let _ = POOL_CACHE.set(PoolCache::new(settings));
+}
+
+pub fn pool_cache() -> &'static PoolCache {
+    // The cache is disabled until it is initialized.
+    POOL_CACHE.get_or_init(|| PoolCache { pools: None })
+}
@@ -0,0 +1,46 @@
+use serde::Deserialize;
+
+use poolnhl_interface::errors::Result;
+
+use crate::http_client::HttpClient;
+use crate::settings::ContractsApi;
+
+// The current contract of a NHL player, as published by the contracts source.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PlayerContract {
+    // The NHL id of the player.
+    pub player_id: u32,
+
+    // The average annual value of the contract, in millions (i.g., 8.5).
+    pub cap_hit: f64,
+
+    // The last season of the contract (i.g., 20272028).
+    pub expiration_season: u32,
+}
+
+// Client of the external source of the players contracts (i.g., PuckPedia, CapWages).
+#[derive(Clone)]
+pub struct ContractsApiClient {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl ContractsApiClient {
+    pub fn new(contracts_api: &ContractsApi) -> Self {
+        Self {
+            client: HttpClient::new("contracts api"),
+            base_url: contracts_api.base_url.clone(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.base_url.is_empty()
+    }
+
+    pub async fn get_contracts(&self) -> Result<Vec<PlayerContract>> {
+        // Fetch the current contract of every NHL player.
+        self.client
+            .get_json(&format!("{}/contracts", self.base_url))
+            .await
+    }
+}
@@ -1,22 +1,86 @@
-use mongodb::bson::doc;
+use mongodb::bson::{doc, Document};
+use mongodb::error::{Error, ErrorKind, WriteFailure};
+use tracing::info;
 
 use poolnhl_interface::errors::{AppError, Result};
 
+use crate::idempotency::create_idempotency_indexes;
+use crate::jobs::create_jobs_indexes;
+use crate::services::achievements_service::create_achievements_indexes;
+use crate::services::archives_service::create_archives_indexes;
+use crate::services::invitations_service::create_invitations_indexes;
+use crate::services::notifications_service::create_notifications_indexes;
+use crate::services::players_service::create_players_indexes;
+use crate::services::pool_service::create_pool_indexes;
+use crate::services::users_service::create_users_indexes;
+use crate::services::webhooks_service::create_webhooks_indexes;
+use crate::transactions::set_client;
+
 pub type DatabaseConnection = mongodb::Database;
 
+// The code returned by MongoDB when a unique index is violated.
+const DUPLICATE_KEY_ERROR_CODE: i32 = 11000;
+
+pub fn is_duplicate_key_error(e: &Error) -> bool {
+    matches!(
+        *e.kind,
+        ErrorKind::Write(WriteFailure::WriteError(ref write_error))
+            if write_error.code == DUPLICATE_KEY_ERROR_CODE
+    )
+}
+
 pub struct DatabaseManager;
 
 impl DatabaseManager {
     pub async fn new_pool(database_uri: &str, database_name: &str) -> Result<DatabaseConnection> {
-        let db = mongodb::Client::with_uri_str(database_uri)
+        let client = mongodb::Client::with_uri_str(database_uri)
             .await
-            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
-            .database(database_name);
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        let db = client.database(database_name);
+        set_client(client);
 
         db.run_command(doc! {"ping": 1}, None)
             .await
             .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
+        Self::create_indexes(&db).await?;
+
         Ok(db)
     }
+
+    // Create the unique and query indexes of every collection, the existing indexes are left as is.
+    async fn create_indexes(db: &DatabaseConnection) -> Result<()> {
+        create_pool_indexes(db).await?;
+        create_players_indexes(db).await?;
+        create_invitations_indexes(db).await?;
+        create_notifications_indexes(db).await?;
+        create_idempotency_indexes(db).await?;
+        create_jobs_indexes(db).await?;
+        create_users_indexes(db).await?;
+        create_archives_indexes(db).await?;
+        create_achievements_indexes(db).await?;
+        create_webhooks_indexes(db).await?;
+
+        let collection_names = db
+            .list_collection_names(None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        for collection_name in collection_names {
+            let index_names = db
+                .collection::<Document>(&collection_name)
+                .list_index_names()
+                .await
+                .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+            info!(
+                collection = collection_name,
+                indexes = index_names.join(", "),
+                "Collection indexes created."
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,148 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+
+use poolnhl_interface::errors::{AppError, Result};
+
+// The number of seconds to establish a connection and to complete a request.
+const CONNECT_TIMEOUT_SECONDS: u64 = 5;
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+
+// Number of times a request is sent before failing, the delay between attempts doubles each time.
+const MAX_ATTEMPTS: u32 = 3;
+const INITIAL_BACKOFF_MILLIS: u64 = 200;
+
+// Number of consecutive failed requests opening the circuit,
+// no request is sent to the service while the circuit is open.
+const CIRCUIT_FAILURE_THRESHOLD: u32 = 5;
+const CIRCUIT_OPEN_SECONDS: u64 = 30;
+
+#[derive(Default)]
+struct CircuitState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+// Stop calling a service that keeps failing for a while, so the requests depending on it fail fast.
+// Once the delay passed, the next request is let through and the circuit closes when it succeeds.
+#[derive(Default)]
+struct CircuitBreaker {
+    state: Mutex<CircuitState>,
+}
+
+impl CircuitBreaker {
+    fn is_open(&self) -> bool {
+        self.state
+            .lock()
+            .map(|state| {
+                state
+                    .open_until
+                    .is_some_and(|open_until| Instant::now() < open_until)
+            })
+            .unwrap_or_default()
+    }
+
+    fn record_success(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = CircuitState::default();
+        }
+    }
+
+    fn record_failure(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.consecutive_failures += 1;
+
+            if state.consecutive_failures >= CIRCUIT_FAILURE_THRESHOLD {
+                state.open_until = Some(Instant::now() + Duration::from_secs(CIRCUIT_OPEN_SECONDS));
+            }
+        }
+    }
+}
+
+// Client of an external service (i.g., Hanko, the NHL api).
+// The requests time out, the transient failures are retried and a failing service is short circuited.
+#[derive(Clone)]
+pub struct HttpClient {
+    // The name of the service in the error messages.
+    service_name: String,
+    client: reqwest::Client,
+    circuit_breaker: Arc<CircuitBreaker>,
+}
+
+impl HttpClient {
+    pub fn new(service_name: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECONDS))
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            service_name: service_name.to_string(),
+            client,
+            circuit_breaker: Arc::new(CircuitBreaker::default()),
+        }
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let response = self.get(url).await?;
+
+        response
+            .json::<T>()
+            .await
+            .map_err(|e| AppError::ReqwestError { msg: e.to_string() })
+    }
+
+    async fn get(&self, url: &str) -> Result<reqwest::Response> {
+        if self.circuit_breaker.is_open() {
+            return Err(AppError::ReqwestError {
+                msg: format!(
+                    "The {} service is unavailable, retry later.",
+                    self.service_name
+                ),
+            });
+        }
+
+        let mut backoff = Duration::from_millis(INITIAL_BACKOFF_MILLIS);
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let result = self
+                .client
+                .get(url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            match result {
+                Ok(response) => {
+                    self.circuit_breaker.record_success();
+                    return Ok(response);
+                }
+                Err(e) if attempt < MAX_ATTEMPTS && is_transient(&e) => {
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    // The client errors are answered by a healthy service.
+                    if is_transient(&e) {
+                        self.circuit_breaker.record_failure();
+                    }
+                    return Err(AppError::ReqwestError { msg: e.to_string() });
+                }
+            }
+        }
+
+        Err(AppError::ReqwestError {
+            msg: format!("The {} service did not answer.", self.service_name),
+        })
+    }
+}
+
+// The failures that could succeed when retried.
+fn is_transient(e: &reqwest::Error) -> bool {
+    match e.status() {
+        Some(status) => status.is_server_error() || status.as_u16() == 429,
+        None => e.is_timeout() || e.is_connect() || e.is_request(),
+    }
+}
@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mongodb::bson::{doc, DateTime};
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+
+use poolnhl_interface::errors::{AppError, Result};
+
+use crate::database_connection::{is_duplicate_key_error, DatabaseConnection};
+
+// The number of seconds an idempotency key is kept, a retry after this delay is applied again.
+const IDEMPOTENCY_KEY_TTL_SECONDS: u64 = 24 * 60 * 60;
+
+// The number of seconds a request keeps its key reserved. Past this delay, a request that never
+// completed (i.g., its handler was dropped) no longer blocks the retries.
+const RESERVATION_TIMEOUT_SECONDS: i64 = 5 * 60;
+
+// Request made with an idempotency key and its response once it completed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotentRequest {
+    pub key: String,
+
+    // Hash of the request, the same key cannot be reused for another request.
+    pub fingerprint: String,
+
+    // None while the request is processed.
+    pub status: Option<u16>,
+    pub content_type: Option<String>,
+    pub body: Option<String>,
+
+    // The key expires from this date.
+    pub date_created: DateTime,
+
+    // While the request is processed, the retries are rejected until this date.
+    pub reserved_until: Option<DateTime>,
+}
+
+impl IdempotentRequest {
+    // A new request holding its key until the reservation times out.
+    pub fn reserve(key: &str, fingerprint: &str) -> Self {
+        let now = DateTime::now();
+
+        Self {
+            key: key.to_string(),
+            fingerprint: fingerprint.to_string(),
+            status: None,
+            content_type: None,
+            body: None,
+            date_created: now,
+            reserved_until: Some(DateTime::from_millis(
+                now.timestamp_millis() + RESERVATION_TIMEOUT_SECONDS * 1000,
+            )),
+        }
+    }
+}
+
+// Hash identifying a request from its parts (i.g., the route, the user and the body).
+pub fn fingerprint(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+
+    for part in parts {
+        hasher.update(part);
+        // Separate the parts so they cannot be shifted into each other.
+        hasher.update([0]);
+    }
+
+    hex::encode(hasher.finalize())
+}
+
+// Store the responses of the requests made with an Idempotency-Key header,
+// so the retries of a request are answered without applying it twice.
+#[async_trait]
+pub trait IdempotencyStore {
+    // Reserve the key for a new request.
+    // The request that already used the key is returned instead when the key is not new.
+    async fn reserve(&self, key: &str, fingerprint: &str) -> Result<Option<IdempotentRequest>>;
+
+    // Store the response of the request so it is replayed to the retries.
+    async fn complete(
+        &self,
+        key: &str,
+        status: u16,
+        content_type: Option<String>,
+        body: String,
+    ) -> Result<()>;
+
+    // Free the key of a request that failed, so it can be retried.
+    async fn release(&self, key: &str) -> Result<()>;
+}
+
+pub type IdempotencyStoreHandle = Arc<dyn IdempotencyStore + Send + Sync>;
+
+pub struct MongoIdempotencyStore {
+    db: DatabaseConnection,
+}
+
+impl MongoIdempotencyStore {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MongoIdempotencyStore {
+    async fn reserve(&self, key: &str, fingerprint: &str) -> Result<Option<IdempotentRequest>> {
+        let collection = self.db.collection::<IdempotentRequest>("idempotency_keys");
+
+        let request = IdempotentRequest::reserve(key, fingerprint);
+        let now = request.date_created;
+        let reserved_until = request.reserved_until;
+
+        match collection.insert_one(&request, None).await {
+            Ok(_) => return Ok(None),
+            Err(e) if is_duplicate_key_error(&e) => {}
+            Err(e) => return Err(AppError::MongoError { msg: e.to_string() }),
+        }
+
+        // A stale reservation is taken over by the new request.
+        let update_result = collection
+            .update_one(
+                doc! {"key": key, "status": null, "reserved_until": {"$lt": now}},
+                doc! {"$set": {
+                    "fingerprint": fingerprint,
+                    "date_created": now,
+                    "reserved_until": reserved_until,
+                }},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        if update_result.modified_count == 1 {
+            return Ok(None);
+        }
+
+        collection
+            .find_one(doc! {"key": key}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        status: u16,
+        content_type: Option<String>,
+        body: String,
+    ) -> Result<()> {
+        self.db
+            .collection::<IdempotentRequest>("idempotency_keys")
+            .update_one(
+                doc! {"key": key},
+                doc! {
+                    "$set": {
+                        "status": i32::from(status),
+                        "content_type": content_type,
+                        "body": body,
+                    },
+                    "$unset": {"reserved_until": ""},
+                },
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<()> {
+        self.db
+            .collection::<IdempotentRequest>("idempotency_keys")
+            .delete_one(doc! {"key": key, "status": null}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(())
+    }
+}
+
+pub async fn create_idempotency_indexes(db: &DatabaseConnection) -> Result<()> {
+    let collection = db.collection::<IdempotentRequest>("idempotency_keys");
+
+    collection
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"key": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    // The keys are removed by MongoDB once they expired.
+    collection
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"date_created": 1})
+                .options(
+                    IndexOptions::builder()
+                        .expire_after(Duration::from_secs(IDEMPOTENCY_KEY_TTL_SECONDS))
+                        .build(),
+                )
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+// In-memory services to test the routers and the draft sockets without MongoDB, Hanko or the NHL api.
+// Only the calls used by the tests are implemented, the other calls return an error.
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use chrono::Utc;
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+
+use poolnhl_interface::draft::model::DraftServerInfo;
+use poolnhl_interface::errors::AppError;
+use poolnhl_interface::pool::model::Pool;
+use poolnhl_interface::users::model::{EmailInfo, UserEmailJwtPayload};
+
+use crate::jwt::CachedJwks;
+use crate::services::ServiceRegistry;
+use crate::settings::Settings;
+
+pub mod draft_service;
+pub mod idempotency_store;
+pub mod pool_service;
+pub mod unsupported;
+pub mod users_service;
+
+use draft_service::InMemoryDraftService;
+use idempotency_store::InMemoryIdempotencyStore;
+use pool_service::InMemoryPoolService;
+use unsupported::UnsupportedService;
+use users_service::InMemoryUsersService;
+
+// The pools shared by the in-memory services, mapped by pool name.
+pub type PoolStore = Arc<RwLock<HashMap<String, Pool>>>;
+
+// The key signing the test tokens, trusted by the in-memory registry in place of the Hanko JWKS.
+const TEST_KEY_ID: &str = "test-key";
+const TEST_KEY_PEM: &str = include_str!("in_memory/test_key.pem");
+const TEST_KEY_MODULUS: &str = "oj9i0GaxPAxviEija1KMZ3Ge4m8kcuQuOf_evzxm8BjI7NTVdbg7A2-oiIUwBwAK5TMnGFR-SS_4u4DAOvWhKPT3vHudbAtw1QtkcrLVP2JGu4u__ga0T-0mK0o51v0k9ZmGa9JlqTerSejb4gaSZG9_NeVI1rrfNBfIAd7hY1ZF75Z9wwIJjSiXNB8nqCCPgV9o_o9ujbofOrWMl1itaFiMRF1QIu0PH529yBYZ6JwG4nals78vciza2JMVTrUcrhPqNbSVG5B1BLz6jf8TTX-TJcb_leKQ9p3YgaNYiNH4SdZbeO3C0hYH5xmdx979oyJDEwHVf1QnuerVsmECZQ";
+const TEST_KEY_EXPONENT: &str = "AQAB";
+const TEST_TOKEN_AUDIENCE: &str = "localhost";
+
+// The error returned by the calls the in-memory services do not implement.
+pub(crate) fn unsupported() -> AppError {
+    AppError::CustomError {
+        msg: "This call is not supported by the in-memory services.".to_string(),
+    }
+}
+
+// The settings of the tests, nothing is read from the config files.
+pub fn test_settings() -> Settings {
+    serde_json::from_value(json!({
+        "environment": "test",
+        "server": {"port": 0},
+        "logger": {"level": "debug"},
+        "database": {"uri": "mongodb://localhost:27017", "name": "in_memory"},
+        "auth": {
+            "jwks_url": "",
+            "token_audience": TEST_TOKEN_AUDIENCE,
+            "jwks_refresh_interval_seconds": 3600,
+            "jwks_min_refetch_seconds": 3600,
+        },
+        "nhl_api": {"base_url": "", "players_sync_interval_hours": 24, "daily_cumulation_hour": 9},
+        "contracts_api": {"base_url": "", "sync_interval_hours": 24},
+        "heartbeat": {"ping_interval_seconds": 15, "pong_timeout_seconds": 45},
+        "mailer": {"base_url": "", "api_key": "", "from_email": ""},
+        "cache": {"enabled": false, "max_pools": 0, "ttl_seconds": 0},
+        "room_store": {"redis_url": ""},
+        "rate_limit": {
+            "default": {"requests": 1000, "period_seconds": 60},
+            "routes": {},
+            "draft_commands": {"requests": 1000, "period_seconds": 60},
+        },
+    }))
+    .expect("The test settings should be valid")
+}
+
+// Sign a token of a user accepted by the in-memory registry for an hour.
+pub fn sign_test_token(user_id: &str, email: &str) -> String {
+    let now = Utc::now().timestamp();
+    let claims = UserEmailJwtPayload {
+        aud: vec![TEST_TOKEN_AUDIENCE.to_string()],
+        email: EmailInfo {
+            address: email.to_string(),
+            is_primary: true,
+            is_verified: true,
+        },
+        exp: now + 3600,
+        iat: now,
+        sub: user_id.to_string(),
+    };
+
+    let mut header = Header::new(jsonwebtoken::Algorithm::RS256);
+    header.kid = Some(TEST_KEY_ID.to_string());
+
+    let key =
+        EncodingKey::from_rsa_pem(TEST_KEY_PEM.as_bytes()).expect("The test key should be valid");
+    encode(&header, &claims, &key).expect("The test token should be signed")
+}
+
+impl ServiceRegistry {
+    // Create the services keeping their state in memory, the pools are shared through the given store.
+    pub fn new_in_memory(settings: &Settings, pools: PoolStore) -> Self {
+        let draft_server_info = Arc::new(DraftServerInfo::new());
+        let cached_jwks = Arc::new(CachedJwks::with_rsa_key(
+            &settings.auth,
+            TEST_KEY_ID,
+            TEST_KEY_MODULUS,
+            TEST_KEY_EXPONENT,
+        ));
+
+        let users_service = Arc::new(InMemoryUsersService::new(draft_server_info.clone()));
+        let unsupported = Arc::new(UnsupportedService);
+
+        Self {
+            pool_service: Arc::new(InMemoryPoolService::new(pools.clone())),
+            players_service: unsupported.clone(),
+            draft_service: Arc::new(InMemoryDraftService::new(
+                pools,
+                cached_jwks.clone(),
+                draft_server_info.clone(),
+                users_service.clone(),
+            )),
+            daily_leaders_service: unsupported.clone(),
+            invitations_service: unsupported.clone(),
+            notifications_service: unsupported.clone(),
+            webhooks_service: unsupported.clone(),
+            admin_service: unsupported.clone(),
+            health_service: unsupported.clone(),
+            jobs_service: unsupported.clone(),
+            users_service,
+            achievements_service: unsupported.clone(),
+            leaderboards_service: unsupported.clone(),
+            archives_service: unsupported,
+            draft_server_info,
+            cached_keys: cached_jwks,
+            idempotency_store: Arc::new(InMemoryIdempotencyStore::new()),
+        }
+    }
+}
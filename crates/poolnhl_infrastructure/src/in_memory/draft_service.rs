@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::broadcast;
+
+use poolnhl_interface::draft::{
+    model::{
+        ChatHistoryQuery, ChatMessage, DraftRecordedCommand, DraftServerInfo, DraftSuggestions,
+        RoomUser,
+    },
+    service::DraftService,
+};
+use poolnhl_interface::errors::{AppError, ErrorCode, Result};
+use poolnhl_interface::pool::authorization::{has_permission, Permission};
+use poolnhl_interface::pool::model::{
+    DraftOrderType, DraftQueue, Pool, PoolPlayerInfo, PoolSettings, TradeDraftPicksRequest,
+};
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+use poolnhl_interface::users::service::UsersServiceHandle;
+
+use super::{unsupported, PoolStore};
+use crate::jwt::{validate_user_token, CachedJwks};
+use crate::services::draft_service::{send_pool_info, send_users_info};
+
+// The draft rooms are handled like the mongo draft service, the commands update the shared
+// pools. The pick timers, the recordings and the notifications are left out.
+pub struct InMemoryDraftService {
+    pools: PoolStore,
+
+    draft_server_info: Arc<DraftServerInfo>,
+    cached_jwks: Arc<CachedJwks>,
+    users_service: UsersServiceHandle,
+}
+
+impl InMemoryDraftService {
+    pub fn new(
+        pools: PoolStore,
+        cached_jwks: Arc<CachedJwks>,
+        draft_server_info: Arc<DraftServerInfo>,
+        users_service: UsersServiceHandle,
+    ) -> Self {
+        Self {
+            pools,
+            draft_server_info,
+            cached_jwks,
+            users_service,
+        }
+    }
+
+    fn get_pool(&self, pool_name: &str) -> Result<Pool> {
+        let pools = self.pools.read().unwrap();
+
+        pools.get(pool_name).cloned().ok_or(AppError::CodedError {
+            code: ErrorCode::PoolNotFound,
+            msg: format!("no pool found with name '{}'", pool_name),
+        })
+    }
+
+    // Apply the command on a copy of the pool, a rejected command leaves the pool as it was.
+    fn update_pool(
+        &self,
+        pool_name: &str,
+        command: impl FnOnce(&mut Pool) -> Result<()>,
+    ) -> Result<Pool> {
+        let mut pool = self.get_pool(pool_name)?;
+        command(&mut pool)?;
+
+        self.pools
+            .write()
+            .unwrap()
+            .insert(pool_name.to_string(), pool.clone());
+        Ok(pool)
+    }
+
+    fn send_room_users(
+        &self,
+        pool_name: &str,
+        room_users: HashMap<String, RoomUser>,
+    ) -> Result<()> {
+        send_users_info(self.draft_server_info.get_room_tx(pool_name)?, room_users)
+    }
+}
+
+#[async_trait]
+impl DraftService for InMemoryDraftService {
+    async fn start_draft(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        draft_order: &Vec<String>,
+    ) -> Result<()> {
+        let room_users: Vec<RoomUser> = self
+            .list_room_users(pool_name)
+            .await?
+            .into_values()
+            .collect();
+
+        let pool = self.update_pool(pool_name, |pool| {
+            pool.start_draft(user_id, &room_users, draft_order)
+        })?;
+
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, pool)
+    }
+
+    async fn update_draft_order(
+        &self,
+        _pool_name: &str,
+        _user_id: &str,
+        _draft_order_type: DraftOrderType,
+    ) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn draft_player(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        player: PoolPlayerInfo,
+    ) -> Result<()> {
+        // The player information sent by the socket is trusted, there are no players in memory.
+        let pool = self.update_pool(pool_name, |pool| {
+            DraftRecordedCommand::DraftPlayer {
+                user_id: user_id.to_string(),
+                player,
+            }
+            .apply(pool)
+        })?;
+
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, pool)
+    }
+
+    async fn undo_draft_player(&self, _pool_name: &str, _user_id: &str) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn skip_pick(&self, _pool_name: &str, _user_id: &str) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn pause_draft(&self, _pool_name: &str, _user_id: &str, _is_paused: bool) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn rollback_draft(
+        &self,
+        _pool_name: &str,
+        _user_id: &str,
+        _pick_number: usize,
+    ) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn trade_draft_picks(
+        &self,
+        _pool_name: &str,
+        _user_id: &str,
+        _req: TradeDraftPicksRequest,
+    ) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn nominate_player(
+        &self,
+        _pool_name: &str,
+        _user_id: &str,
+        _player: PoolPlayerInfo,
+        _opening_bid: u32,
+    ) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn bid_player(
+        &self,
+        _pool_name: &str,
+        _user_id: &str,
+        _bidder_id: &str,
+        _amount: u32,
+    ) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn close_bid(&self, _pool_name: &str, _user_id: &str) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn cancel_nomination(&self, _pool_name: &str, _user_id: &str) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn get_draft_queue(&self, _pool_name: &str, _user_id: &str) -> Result<DraftQueue> {
+        Err(unsupported())
+    }
+
+    async fn update_draft_queue(
+        &self,
+        _pool_name: &str,
+        _user_id: &str,
+        _players: Vec<PoolPlayerInfo>,
+    ) -> Result<DraftQueue> {
+        Err(unsupported())
+    }
+
+    async fn toggle_auto_draft(&self, _pool_name: &str, _user_id: &str) -> Result<DraftQueue> {
+        Err(unsupported())
+    }
+
+    async fn update_pool_settings(
+        &self,
+        _use_id: &str,
+        _pool_name: &str,
+        _pool_settings: &PoolSettings,
+    ) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn join_room(
+        &self,
+        pool_name: &str,
+        number_poolers: u8,
+        socket_addr: SocketAddr,
+    ) -> Result<(broadcast::Receiver<String>, String)> {
+        // The invitations are not kept in memory, only the members join a private pool.
+        let pool = self.get_pool(pool_name)?;
+
+        if pool.is_private() {
+            let is_allowed = match self
+                .draft_server_info
+                .get_authenticated_user_with_socket(&socket_addr.to_string())?
+            {
+                Some(user) => has_permission(&user.sub, &pool, Permission::ViewPrivatePool),
+                None => false,
+            };
+
+            if !is_allowed {
+                return Err(AppError::CustomError {
+                    msg: "Only the invited users can join this pool.".to_string(),
+                });
+            }
+        }
+
+        let (rx, room_users) = self.draft_server_info.join_room(
+            pool_name,
+            number_poolers,
+            &socket_addr.to_string(),
+        )?;
+
+        self.send_room_users(pool_name, room_users)?;
+
+        let session_token = self.draft_server_info.create_session(
+            pool_name,
+            number_poolers,
+            &socket_addr.to_string(),
+        )?;
+
+        Ok((rx, session_token))
+    }
+
+    async fn resume_room(
+        &self,
+        session_token: &str,
+        socket_addr: SocketAddr,
+    ) -> Result<(broadcast::Receiver<String>, Pool)> {
+        let (rx, pool_name) = self
+            .draft_server_info
+            .resume_session(session_token, &socket_addr.to_string())?;
+
+        self.send_room_users(
+            &pool_name,
+            self.draft_server_info.list_room_users(&pool_name)?,
+        )?;
+
+        Ok((rx, self.get_pool(&pool_name)?))
+    }
+
+    async fn suspend_session(&self, socket_addr: SocketAddr) -> Result<()> {
+        self.draft_server_info
+            .suspend_session(&socket_addr.to_string())
+    }
+
+    async fn leave_room(&self, pool_name: &str, socket_addr: SocketAddr) -> Result<()> {
+        let room_users = self
+            .draft_server_info
+            .leave_room(pool_name, &socket_addr.to_string())?;
+
+        self.send_room_users(pool_name, room_users)
+    }
+
+    async fn on_ready(&self, pool_name: &str, socket_addr: SocketAddr) -> Result<()> {
+        let room_users = self
+            .draft_server_info
+            .on_ready(pool_name, &socket_addr.to_string())?;
+
+        self.send_room_users(pool_name, room_users)
+    }
+
+    async fn add_user(
+        &self,
+        pool_name: &str,
+        user_name: &str,
+        socket_addr: SocketAddr,
+    ) -> Result<()> {
+        let room_users =
+            self.draft_server_info
+                .add_user(pool_name, user_name, &socket_addr.to_string())?;
+
+        self.send_room_users(pool_name, room_users)
+    }
+
+    async fn remove_user(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        socket_addr: SocketAddr,
+    ) -> Result<()> {
+        let room_users =
+            self.draft_server_info
+                .remove_user(pool_name, user_id, &socket_addr.to_string())?;
+
+        self.send_room_users(pool_name, room_users)
+    }
+
+    async fn send_chat_message(
+        &self,
+        _pool_name: &str,
+        _socket_addr: SocketAddr,
+        _message: &str,
+    ) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn get_draft_suggestions(&self, _pool_name: &str) -> Result<DraftSuggestions> {
+        Err(unsupported())
+    }
+
+    async fn get_chat_messages(
+        &self,
+        _user_id: &str,
+        _pool_name: &str,
+        _query: ChatHistoryQuery,
+    ) -> Result<Vec<ChatMessage>> {
+        Err(unsupported())
+    }
+
+    async fn authenticate_web_socket(
+        &self,
+        token: &str,
+        socket_addr: SocketAddr,
+    ) -> Option<UserEmailJwtPayload> {
+        let user = validate_user_token(token, &self.cached_jwks, &self.users_service)
+            .await
+            .ok()?;
+
+        self.draft_server_info
+            .add_socket(&socket_addr.to_string(), user.clone())
+            .ok()?;
+
+        Some(user)
+    }
+
+    async fn unauthenticate_web_socket(&self, socket_addr: SocketAddr) -> Result<()> {
+        self.draft_server_info
+            .remove_socket(&socket_addr.to_string())
+    }
+
+    async fn list_rooms(&self) -> Result<Vec<String>> {
+        self.draft_server_info.list_rooms()
+    }
+
+    async fn list_room_users(&self, pool_name: &str) -> Result<HashMap<String, RoomUser>> {
+        self.draft_server_info.list_room_users(pool_name)
+    }
+
+    async fn list_authenticated_sockets(&self) -> Result<HashMap<String, UserEmailJwtPayload>> {
+        self.draft_server_info.list_authenticated_sockets()
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use mongodb::bson::DateTime;
+
+use poolnhl_interface::errors::Result;
+
+use crate::idempotency::{IdempotencyStore, IdempotentRequest};
+
+// Keep the requests made with an Idempotency-Key header in memory, mapped by key.
+// The keys never expire, the tests do not live long enough.
+#[derive(Default)]
+pub struct InMemoryIdempotencyStore {
+    requests: RwLock<HashMap<String, IdempotentRequest>>,
+}
+
+impl InMemoryIdempotencyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for InMemoryIdempotencyStore {
+    async fn reserve(&self, key: &str, fingerprint: &str) -> Result<Option<IdempotentRequest>> {
+        let mut requests = self.requests.write().unwrap();
+        let now = DateTime::now();
+
+        match requests.get(key) {
+            // A stale reservation is taken over by the new request.
+            Some(request)
+                if request.status.is_some()
+                    || request
+                        .reserved_until
+                        .is_none_or(|reserved_until| reserved_until >= now) =>
+            {
+                Ok(Some(request.clone()))
+            }
+            _ => {
+                requests.insert(
+                    key.to_string(),
+                    IdempotentRequest::reserve(key, fingerprint),
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    async fn complete(
+        &self,
+        key: &str,
+        status: u16,
+        content_type: Option<String>,
+        body: String,
+    ) -> Result<()> {
+        if let Some(request) = self.requests.write().unwrap().get_mut(key) {
+            request.status = Some(status);
+            request.content_type = content_type;
+            request.body = Some(body);
+            request.reserved_until = None;
+        }
+
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<()> {
+        let mut requests = self.requests.write().unwrap();
+
+        if requests
+            .get(key)
+            .is_some_and(|request| request.status.is_none())
+        {
+            requests.remove(key);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,491 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use poolnhl_interface::errors::{AppError, ErrorCode, Result};
+use poolnhl_interface::players::model::{GetPlayerQuery, PlayerInfo};
+use poolnhl_interface::pool::authorization::{authorize, Permission};
+use poolnhl_interface::pool::{
+    model::{
+        AddPlayerRequest, AdoptTeamRequest, AutoLineupRequest, CapReport, ClaimTeamRequest,
+        ClonePoolRequest, CompleteProtectionRequest, CreateTradeRequest, DeletePoolTemplateRequest,
+        DeleteTradeRequest, DraftRecapPick, FillSpotRequest, GenerateDynastyRequest,
+        GenerateTeamClaimRequest, ListPoolsQuery, MarkAsFinalRequest, MatchupSchedule,
+        ModifyRosterRequest, Pool, PoolCreationRequest, PoolDeletionRequest, PoolEvent,
+        PoolHistoryQuery, PoolPlayerStats, PoolPreview, PoolStandings, PoolTemplate,
+        ProjectedPoolShort, ProtectPlayersRequest, RemovePlayerRequest, RenamePoolRequest,
+        RespondTradeRequest, SavePoolTemplateRequest, SeasonDates, Standing, TeamClaim,
+        TestWebhookRequest, TradeAnalysis, TradeBlockListing, TransferOwnershipRequest,
+        UpdateOrphanedTeamRequest, UpdatePoolSettingsRequest, UpdateTeamManagersRequest,
+        UpdateTeamProfileRequest, UpdateTradeBlockRequest, UpdateWatchlistRequest,
+        VetoTradeRequest, Watchlist,
+    },
+    service::PoolService,
+};
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+
+use super::{unsupported, PoolStore};
+
+// The pools are kept in a map shared with the draft service. The pool reads, the standings,
+// the trades and the roster modifications are supported, they do not need the other collections.
+pub struct InMemoryPoolService {
+    pools: PoolStore,
+}
+
+impl InMemoryPoolService {
+    pub fn new(pools: PoolStore) -> Self {
+        Self { pools }
+    }
+
+    // Apply a modification to a copy of the pool, the stored pool is only replaced when it succeeds.
+    fn update_pool(
+        &self,
+        name: &str,
+        modify: impl FnOnce(&mut Pool) -> Result<()>,
+    ) -> Result<Pool> {
+        let mut pools = self.pools.write().unwrap();
+
+        let mut pool = pools.get(name).cloned().ok_or(AppError::CodedError {
+            code: ErrorCode::PoolNotFound,
+            msg: format!("no pool found with name '{}'", name),
+        })?;
+
+        modify(&mut pool)?;
+
+        pools.insert(pool.name.clone(), pool.clone());
+        Ok(pool)
+    }
+
+    // Preview a modification without storing it.
+    async fn preview_pool(
+        &self,
+        name: &str,
+        modify: impl FnOnce(&mut Pool) -> Result<()>,
+    ) -> Result<PoolPreview> {
+        let mut pool = self.get_pool_by_name(name).await?;
+
+        modify(&mut pool)?;
+        pool.get_preview()
+    }
+}
+
+#[async_trait]
+impl PoolService for InMemoryPoolService {
+    async fn get_pool_by_name(&self, name: &str) -> Result<Pool> {
+        let pools = self.pools.read().unwrap();
+
+        pools.get(name).cloned().ok_or(AppError::CodedError {
+            code: ErrorCode::PoolNotFound,
+            msg: format!("no pool found with name '{}'", name),
+        })
+    }
+
+    async fn get_partial_pool_by_name(&self, _name: &str, _paths: &[&str]) -> Result<Value> {
+        Err(unsupported())
+    }
+
+    async fn validate_pool_view(&self, user_id: Option<&str>, name: &str) -> Result<()> {
+        // The invitations are not kept in memory.
+        let pool = self.get_pool_by_name(name).await?;
+
+        if !pool.is_private() {
+            return Ok(());
+        }
+
+        let user_id = user_id.ok_or_else(|| AppError::AuthError {
+            msg: "You need to be logged in to see this pool.".to_string(),
+        })?;
+
+        authorize(user_id, &pool, Permission::ViewPrivatePool)
+    }
+
+    async fn get_pool_by_name_with_range(
+        &self,
+        _name: &str,
+        _start_season_date: &str,
+        _from_date: &str,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn list_pools(
+        &self,
+        _season: u32,
+        _query: ListPoolsQuery,
+    ) -> Result<Vec<ProjectedPoolShort>> {
+        Err(unsupported())
+    }
+
+    async fn get_matchups(&self, name: &str) -> Result<MatchupSchedule> {
+        let pool = self.get_pool_by_name(name).await?;
+
+        pool.context
+            .and_then(|context| context.matchup_schedule)
+            .ok_or_else(|| AppError::CustomError {
+                msg: "This pool does not have any head-to-head matchups.".to_string(),
+            })
+    }
+
+    async fn get_standings(&self, name: &str) -> Result<Vec<Standing>> {
+        let pool = self.get_pool_by_name(name).await?;
+
+        let head_to_head_settings =
+            pool.settings
+                .head_to_head_settings
+                .ok_or_else(|| AppError::CustomError {
+                    msg: "This pool is not a head-to-head pool.".to_string(),
+                })?;
+
+        let matchup_schedule = pool
+            .context
+            .and_then(|context| context.matchup_schedule)
+            .ok_or_else(|| AppError::CustomError {
+                msg: "This pool does not have any head-to-head matchups.".to_string(),
+            })?;
+
+        Ok(matchup_schedule.get_standings(&head_to_head_settings))
+    }
+
+    async fn get_pool_standings(&self, name: &str) -> Result<PoolStandings> {
+        // The totals are kept with the pool in memory.
+        self.get_pool_by_name(name).await?.get_pool_standings()
+    }
+
+    async fn get_players_stats(&self, _name: &str) -> Result<Vec<PoolPlayerStats>> {
+        Err(unsupported())
+    }
+
+    async fn test_webhook(&self, _user_id: &str, _req: TestWebhookRequest) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn get_cap_report(&self, _name: &str) -> Result<CapReport> {
+        Err(unsupported())
+    }
+
+    async fn get_trade_analysis(&self, _name: &str, _trade_id: u32) -> Result<TradeAnalysis> {
+        Err(unsupported())
+    }
+
+    async fn get_draft_recap(&self, _name: &str) -> Result<Vec<DraftRecapPick>> {
+        Err(unsupported())
+    }
+
+    async fn get_free_agents(
+        &self,
+        _name: &str,
+        _query: GetPlayerQuery,
+    ) -> Result<Vec<PlayerInfo>> {
+        Err(unsupported())
+    }
+
+    async fn get_starters_games(&self, _name: &str, _date: &str) -> Result<HashMap<String, u8>> {
+        Err(unsupported())
+    }
+
+    async fn create_pool(&self, user_id: &str, req: PoolCreationRequest) -> Result<Pool> {
+        // The templates are not kept in memory.
+        let settings = req.settings.ok_or_else(|| AppError::CustomError {
+            msg: "The settings are required to create a pool.".to_string(),
+        })?;
+
+        settings.validate()?;
+
+        let pool = Pool::new(&req.pool_name, user_id, &settings, &SeasonDates::default());
+        let mut pools = self.pools.write().unwrap();
+
+        if pools.contains_key(&pool.name) {
+            return Err(AppError::CodedError {
+                code: ErrorCode::PoolAlreadyExists,
+                msg: format!("a pool named '{}' already exists", pool.name),
+            });
+        }
+
+        pools.insert(pool.name.clone(), pool.clone());
+        Ok(pool)
+    }
+
+    async fn delete_pool(&self, user_id: &str, req: PoolDeletionRequest) -> Result<Pool> {
+        let pool = self.get_pool_by_name(&req.pool_name).await?;
+
+        authorize(user_id, &pool, Permission::ManagePool)?;
+
+        self.pools.write().unwrap().remove(&pool.name);
+        Ok(pool)
+    }
+
+    async fn clone_pool(&self, _user_id: &str, _req: ClonePoolRequest) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn list_pool_templates(&self, _user_id: &str) -> Result<Vec<PoolTemplate>> {
+        Err(unsupported())
+    }
+
+    async fn save_pool_template(
+        &self,
+        _user_id: &str,
+        _req: SavePoolTemplateRequest,
+    ) -> Result<PoolTemplate> {
+        Err(unsupported())
+    }
+
+    async fn delete_pool_template(
+        &self,
+        _user_id: &str,
+        _req: DeletePoolTemplateRequest,
+    ) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn add_player(&self, _user_id: &str, _req: AddPlayerRequest) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn remove_player(&self, _user_id: &str, _req: RemovePlayerRequest) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn create_trade(&self, user_id: &str, req: &mut CreateTradeRequest) -> Result<Pool> {
+        // The poolers are not notified in memory.
+        self.update_pool(&req.pool_name, |pool| {
+            pool.create_trade(&mut req.trade, user_id)
+        })
+    }
+
+    async fn delete_trade(&self, user_id: &str, req: DeleteTradeRequest) -> Result<Pool> {
+        self.update_pool(&req.pool_name, |pool| {
+            pool.delete_trade(user_id, req.trade_id)
+        })
+    }
+
+    async fn respond_trade(&self, user_id: &str, req: RespondTradeRequest) -> Result<Pool> {
+        self.update_pool(&req.pool_name, |pool| match req.counter_trade {
+            Some(mut counter_trade) => {
+                pool.counter_trade(user_id, req.trade_id, &mut counter_trade)
+            }
+            None => pool.respond_trade(user_id, req.is_accepted, req.trade_id),
+        })
+    }
+
+    async fn veto_trade(&self, user_id: &str, req: VetoTradeRequest) -> Result<Pool> {
+        self.update_pool(&req.pool_name, |pool| {
+            pool.veto_trade(user_id, req.trade_id)
+        })
+    }
+
+    async fn fill_spot(&self, _user_id: &str, _req: FillSpotRequest) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn modify_roster(&self, user_id: &str, req: ModifyRosterRequest) -> Result<Pool> {
+        self.update_pool(&req.pool_name, |pool| {
+            pool.modify_roster(
+                user_id,
+                &req.roster_modified_user_id,
+                &req.get_modified_roster(),
+            )
+        })
+    }
+
+    async fn preview_create_trade(
+        &self,
+        user_id: &str,
+        req: &mut CreateTradeRequest,
+    ) -> Result<PoolPreview> {
+        self.preview_pool(&req.pool_name, |pool| {
+            pool.create_trade(&mut req.trade, user_id)
+        })
+        .await
+    }
+
+    async fn preview_respond_trade(
+        &self,
+        user_id: &str,
+        req: RespondTradeRequest,
+    ) -> Result<PoolPreview> {
+        self.preview_pool(&req.pool_name, |pool| match req.counter_trade {
+            Some(mut counter_trade) => {
+                pool.counter_trade(user_id, req.trade_id, &mut counter_trade)
+            }
+            None => pool.respond_trade(user_id, req.is_accepted, req.trade_id),
+        })
+        .await
+    }
+
+    async fn preview_modify_roster(
+        &self,
+        user_id: &str,
+        req: ModifyRosterRequest,
+    ) -> Result<PoolPreview> {
+        self.preview_pool(&req.pool_name, |pool| {
+            pool.modify_roster(
+                user_id,
+                &req.roster_modified_user_id,
+                &req.get_modified_roster(),
+            )
+        })
+        .await
+    }
+
+    async fn set_auto_lineup(&self, _user_id: &str, _req: AutoLineupRequest) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn update_pool_settings(
+        &self,
+        _user_id: &str,
+        _req: UpdatePoolSettingsRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn protect_players(&self, _user_id: &str, _req: ProtectPlayersRequest) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn complete_protection(
+        &self,
+        _user_id: &str,
+        _req: CompleteProtectionRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn mark_as_final(&self, _user_id: &str, _req: MarkAsFinalRequest) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn generate_team_claim(
+        &self,
+        _user_id: &str,
+        _req: GenerateTeamClaimRequest,
+    ) -> Result<TeamClaim> {
+        Err(unsupported())
+    }
+
+    async fn claim_team(
+        &self,
+        _user: &UserEmailJwtPayload,
+        _req: ClaimTeamRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn update_team_profile(
+        &self,
+        _user_id: &str,
+        _pool_name: &str,
+        _req: UpdateTeamProfileRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn get_watchlist(&self, _user_id: &str, _pool_name: &str) -> Result<Watchlist> {
+        Err(unsupported())
+    }
+
+    async fn update_watchlist(
+        &self,
+        _user_id: &str,
+        _pool_name: &str,
+        _req: UpdateWatchlistRequest,
+    ) -> Result<Watchlist> {
+        Err(unsupported())
+    }
+
+    async fn get_trade_block(
+        &self,
+        _name: &str,
+    ) -> Result<HashMap<String, Vec<TradeBlockListing>>> {
+        Err(unsupported())
+    }
+
+    async fn update_trade_block(
+        &self,
+        _user_id: &str,
+        _pool_name: &str,
+        _req: UpdateTradeBlockRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn update_orphaned_team(
+        &self,
+        _user_id: &str,
+        _pool_name: &str,
+        _req: UpdateOrphanedTeamRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn adopt_team(
+        &self,
+        _user_id: &str,
+        _pool_name: &str,
+        _req: AdoptTeamRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn update_team_managers(
+        &self,
+        _user_id: &str,
+        _req: UpdateTeamManagersRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn transfer_ownership(
+        &self,
+        _user_id: &str,
+        _req: TransferOwnershipRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn rename_pool(&self, _user_id: &str, _req: RenamePoolRequest) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn generate_dynasty(&self, _user_id: &str, _req: GenerateDynastyRequest) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn get_pool_history(
+        &self,
+        user_id: &str,
+        name: &str,
+        _query: PoolHistoryQuery,
+    ) -> Result<Vec<PoolEvent>> {
+        // The pool events are not recorded in memory.
+        self.validate_pool_view(Some(user_id), name).await?;
+
+        Ok(Vec::new())
+    }
+
+    async fn cumulate_daily_points(&self) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn update_live_scores(&self) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn complete_trade_reviews(&self) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn expire_trades(&self) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn complete_late_protections(&self) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn reset_weekly_acquisitions(&self) -> Result<()> {
+        Err(unsupported())
+    }
+}
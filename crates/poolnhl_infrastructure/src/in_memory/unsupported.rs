@@ -0,0 +1,300 @@
+use async_trait::async_trait;
+
+use poolnhl_interface::achievements::{model::Achievement, service::AchievementsService};
+use poolnhl_interface::admin::{
+    model::{
+        AdminDeletePoolRequest, AdminDraftRecordingRequest, AdminRecordDraftRequest,
+        AdminRecumulatePointsRequest, AdminRenamePoolRequest, AdminSetVirtualTodayRequest,
+        AdminTransferOwnershipRequest, AdminUpdateContextRequest, PoolsMigrationReport,
+    },
+    service::AdminService,
+};
+use poolnhl_interface::archives::{model::SeasonArchive, service::ArchivesService};
+use poolnhl_interface::daily_leaders::{
+    model::{DailyLeaders, DailyLeadersRangeQuery},
+    service::DailyLeadersService,
+};
+use poolnhl_interface::draft::model::DraftRecording;
+use poolnhl_interface::errors::Result;
+use poolnhl_interface::health::{model::Readiness, service::HealthService};
+use poolnhl_interface::invitations::{
+    model::{
+        AcceptInvitationRequest, CreateInvitationRequest, DeleteInvitationRequest, Invitation,
+    },
+    service::InvitationsService,
+};
+use poolnhl_interface::jobs::{model::JobInfo, service::JobsService};
+use poolnhl_interface::leaderboards::{
+    model::{Leaderboards, LeaderboardsQuery},
+    service::LeaderboardsService,
+};
+use poolnhl_interface::notifications::{
+    model::{
+        MarkNotificationsReadRequest, Notification, NotificationPreferences, NotificationsQuery,
+        UpdateNotificationPreferencesRequest,
+    },
+    service::NotificationsService,
+};
+use poolnhl_interface::players::{
+    model::{DailySchedule, GetPlayerQuery, PlayerInfo},
+    service::PlayersService,
+};
+use poolnhl_interface::pool::model::Pool;
+use poolnhl_interface::time_provider::VirtualToday;
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+use poolnhl_interface::webhooks::{
+    model::{
+        CreateWebhookSubscriptionRequest, DeleteWebhookSubscriptionRequest, WebhookDelivery,
+        WebhookSubscription,
+    },
+    service::WebhooksService,
+};
+
+use super::unsupported;
+
+// The services the router tests do not go through, every call is rejected.
+pub struct UnsupportedService;
+
+#[async_trait]
+impl AchievementsService for UnsupportedService {
+    async fn list_achievements(&self, _user_id: &str) -> Result<Vec<Achievement>> {
+        Err(unsupported())
+    }
+}
+
+#[async_trait]
+impl AdminService for UnsupportedService {
+    async fn delete_pool(&self, _user_id: &str, _req: AdminDeletePoolRequest) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn transfer_ownership(
+        &self,
+        _user_id: &str,
+        _req: AdminTransferOwnershipRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn rename_pool(&self, _user_id: &str, _req: AdminRenamePoolRequest) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn update_context(
+        &self,
+        _user_id: &str,
+        _req: AdminUpdateContextRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn recumulate_points(
+        &self,
+        _user_id: &str,
+        _req: AdminRecumulatePointsRequest,
+    ) -> Result<Pool> {
+        Err(unsupported())
+    }
+
+    async fn migrate_pools(&self, _user_id: &str) -> Result<PoolsMigrationReport> {
+        Err(unsupported())
+    }
+
+    async fn set_virtual_today(
+        &self,
+        _user_id: &str,
+        _req: AdminSetVirtualTodayRequest,
+    ) -> Result<VirtualToday> {
+        Err(unsupported())
+    }
+
+    async fn record_draft(
+        &self,
+        _user_id: &str,
+        _req: AdminRecordDraftRequest,
+    ) -> Result<Option<DraftRecording>> {
+        Err(unsupported())
+    }
+
+    async fn get_draft_recording(
+        &self,
+        _user_id: &str,
+        _req: AdminDraftRecordingRequest,
+    ) -> Result<DraftRecording> {
+        Err(unsupported())
+    }
+}
+
+#[async_trait]
+impl ArchivesService for UnsupportedService {
+    async fn get_archive(&self, _season: u32, _pool_name: &str) -> Result<SeasonArchive> {
+        Err(unsupported())
+    }
+}
+
+#[async_trait]
+impl DailyLeadersService for UnsupportedService {
+    async fn get_daily_leaders(&self, _date: &str) -> Result<DailyLeaders> {
+        Err(unsupported())
+    }
+
+    async fn get_daily_leaders_range(
+        &self,
+        _query: DailyLeadersRangeQuery,
+    ) -> Result<Vec<DailyLeaders>> {
+        Err(unsupported())
+    }
+
+    async fn get_top_performers(&self) -> Result<DailyLeaders> {
+        Err(unsupported())
+    }
+}
+
+#[async_trait]
+impl HealthService for UnsupportedService {
+    // The in-memory services do not depend on anything.
+    async fn get_readiness(&self) -> Readiness {
+        Readiness::new(Vec::new())
+    }
+}
+
+#[async_trait]
+impl InvitationsService for UnsupportedService {
+    async fn get_invitation(&self, _token: &str) -> Result<Invitation> {
+        Err(unsupported())
+    }
+
+    async fn list_invitations(&self, _user_id: &str, _pool_name: &str) -> Result<Vec<Invitation>> {
+        Err(unsupported())
+    }
+
+    async fn create_invitation(
+        &self,
+        _user_id: &str,
+        _req: CreateInvitationRequest,
+    ) -> Result<Invitation> {
+        Err(unsupported())
+    }
+
+    async fn accept_invitation(
+        &self,
+        _user: &UserEmailJwtPayload,
+        _req: AcceptInvitationRequest,
+    ) -> Result<Invitation> {
+        Err(unsupported())
+    }
+
+    async fn delete_invitation(&self, _user_id: &str, _req: DeleteInvitationRequest) -> Result<()> {
+        Err(unsupported())
+    }
+}
+
+#[async_trait]
+impl JobsService for UnsupportedService {
+    // The jobs are not scheduled in memory.
+    fn start(&self) {}
+
+    async fn list_jobs(&self) -> Result<Vec<JobInfo>> {
+        Err(unsupported())
+    }
+
+    async fn trigger_job(&self, _name: &str) -> Result<JobInfo> {
+        Err(unsupported())
+    }
+}
+
+#[async_trait]
+impl LeaderboardsService for UnsupportedService {
+    async fn get_leaderboards(&self, _query: LeaderboardsQuery) -> Result<Leaderboards> {
+        Err(unsupported())
+    }
+}
+
+#[async_trait]
+impl NotificationsService for UnsupportedService {
+    async fn list_notifications(
+        &self,
+        _user_id: &str,
+        _query: NotificationsQuery,
+    ) -> Result<Vec<Notification>> {
+        Err(unsupported())
+    }
+
+    async fn mark_as_read(&self, _user_id: &str, _req: MarkNotificationsReadRequest) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn get_preferences(
+        &self,
+        _user_id: &str,
+        _pool_name: &str,
+    ) -> Result<NotificationPreferences> {
+        Err(unsupported())
+    }
+
+    async fn update_preferences(
+        &self,
+        _user: &UserEmailJwtPayload,
+        _req: UpdateNotificationPreferencesRequest,
+    ) -> Result<NotificationPreferences> {
+        Err(unsupported())
+    }
+}
+
+#[async_trait]
+impl PlayersService for UnsupportedService {
+    async fn get_players(&self, _date: GetPlayerQuery) -> Result<Vec<PlayerInfo>> {
+        Err(unsupported())
+    }
+
+    async fn get_players_with_name(&self, _name: &str) -> Result<Vec<PlayerInfo>> {
+        Err(unsupported())
+    }
+
+    async fn get_player(&self, _id: u32) -> Result<PlayerInfo> {
+        Err(unsupported())
+    }
+
+    async fn get_schedule(&self, _date: &str) -> Result<DailySchedule> {
+        Err(unsupported())
+    }
+
+    async fn sync_players(&self) -> Result<usize> {
+        Err(unsupported())
+    }
+
+    async fn sync_contracts(&self) -> Result<usize> {
+        Err(unsupported())
+    }
+}
+
+#[async_trait]
+impl WebhooksService for UnsupportedService {
+    async fn list_subscriptions(&self, _user_id: &str) -> Result<Vec<WebhookSubscription>> {
+        Err(unsupported())
+    }
+
+    async fn list_deliveries(
+        &self,
+        _user_id: &str,
+        _subscription_id: &str,
+    ) -> Result<Vec<WebhookDelivery>> {
+        Err(unsupported())
+    }
+
+    async fn create_subscription(
+        &self,
+        _user_id: &str,
+        _req: CreateWebhookSubscriptionRequest,
+    ) -> Result<WebhookSubscription> {
+        Err(unsupported())
+    }
+
+    async fn delete_subscription(
+        &self,
+        _user_id: &str,
+        _req: DeleteWebhookSubscriptionRequest,
+    ) -> Result<()> {
+        Err(unsupported())
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use chrono::Utc;
+
+use poolnhl_interface::draft::model::DraftServerInfo;
+use poolnhl_interface::errors::Result;
+use poolnhl_interface::users::{
+    model::{
+        AccountDeletion, AccountMerge, TokenRevocation, UserDataExport, UserEmailJwtPayload,
+        UserProfile,
+    },
+    service::UsersService,
+};
+
+use super::unsupported;
+
+// Keep the token revocations in memory, so the draft sockets can validate the test tokens.
+pub struct InMemoryUsersService {
+    // The tokens of a user issued before this timestamp are revoked, mapped by user id.
+    revocations: RwLock<HashMap<String, i64>>,
+    draft_server_info: Arc<DraftServerInfo>,
+}
+
+impl InMemoryUsersService {
+    pub fn new(draft_server_info: Arc<DraftServerInfo>) -> Self {
+        Self {
+            revocations: RwLock::new(HashMap::new()),
+            draft_server_info,
+        }
+    }
+}
+
+#[async_trait]
+impl UsersService for InMemoryUsersService {
+    async fn logout_everywhere(&self, user_id: &str) -> Result<TokenRevocation> {
+        let revocation = TokenRevocation {
+            user_id: user_id.to_string(),
+            revoked_before: Utc::now().timestamp(),
+        };
+
+        self.revocations
+            .write()
+            .unwrap()
+            .insert(user_id.to_string(), revocation.revoked_before);
+
+        self.draft_server_info.remove_user_sockets(user_id)?;
+
+        Ok(revocation)
+    }
+
+    async fn is_token_revoked(&self, token: &UserEmailJwtPayload) -> Result<bool> {
+        let revocations = self.revocations.read().unwrap();
+
+        Ok(revocations
+            .get(&token.sub)
+            .is_some_and(|revoked_before| token.iat < *revoked_before))
+    }
+
+    async fn get_user_profile(&self, _user_id: &str) -> Result<UserProfile> {
+        Err(unsupported())
+    }
+
+    async fn export_user_data(&self, _user: &UserEmailJwtPayload) -> Result<UserDataExport> {
+        Err(unsupported())
+    }
+
+    async fn request_account_deletion(&self, _user_id: &str) -> Result<AccountDeletion> {
+        Err(unsupported())
+    }
+
+    async fn cancel_account_deletion(&self, _user_id: &str) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn complete_account_deletions(&self) -> Result<()> {
+        Err(unsupported())
+    }
+
+    async fn merge_accounts(
+        &self,
+        _user: &UserEmailJwtPayload,
+        _merged_user: &UserEmailJwtPayload,
+    ) -> Result<AccountMerge> {
+        Err(unsupported())
+    }
+}
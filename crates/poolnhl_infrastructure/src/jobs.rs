@@ -0,0 +1,602 @@
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, Local, NaiveTime, Utc, Weekday};
+use futures::stream::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::options::{FindOptions, IndexOptions, UpdateOptions};
+use mongodb::IndexModel;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::jobs::{model::JobInfo, service::JobsService};
+use poolnhl_interface::players::service::PlayersServiceHandle;
+use poolnhl_interface::pool::service::PoolServiceHandle;
+use poolnhl_interface::users::service::UsersServiceHandle;
+
+use crate::database_connection::DatabaseConnection;
+use crate::settings::{ContractsApi, NhlApi};
+
+// The number of seconds between each check of the jobs that are due.
+const JOBS_POLL_SECONDS: u64 = 60;
+
+// When a job runs.
+pub enum Schedule {
+    // Every period, starting when the job is registered.
+    Interval(Duration),
+
+    // Every day at this local time.
+    Daily(NaiveTime),
+
+    // Every week on this day at this local time.
+    Weekly(Weekday, NaiveTime),
+}
+
+impl Schedule {
+    // The date of the first run of a newly registered job, in milliseconds.
+    fn get_first_run(&self, now: DateTime<Local>) -> i64 {
+        match self {
+            Schedule::Interval(_) => now.timestamp_millis(),
+            Schedule::Daily(_) | Schedule::Weekly(..) => self.get_next_run(now),
+        }
+    }
+
+    // The date of the run following the one starting now, in milliseconds.
+    fn get_next_run(&self, now: DateTime<Local>) -> i64 {
+        match self {
+            Schedule::Interval(period) => now.timestamp_millis() + period.as_millis() as i64,
+            Schedule::Daily(time) => {
+                let mut next_run = now.date_naive().and_time(*time);
+                if next_run <= now.naive_local() {
+                    next_run += chrono::Duration::days(1);
+                }
+
+                next_run
+                    .and_local_timezone(Local)
+                    .earliest()
+                    .unwrap_or(now + chrono::Duration::days(1))
+                    .timestamp_millis()
+            }
+            Schedule::Weekly(weekday, time) => {
+                let days_until =
+                    (7 + weekday.num_days_from_monday() - now.weekday().num_days_from_monday()) % 7;
+                let mut next_run =
+                    (now.date_naive() + chrono::Duration::days(days_until as i64)).and_time(*time);
+                if next_run <= now.naive_local() {
+                    next_run += chrono::Duration::weeks(1);
+                }
+
+                next_run
+                    .and_local_timezone(Local)
+                    .earliest()
+                    .unwrap_or(now + chrono::Duration::weeks(1))
+                    .timestamp_millis()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Schedule::Interval(period) => write!(f, "every {} seconds", period.as_secs()),
+            Schedule::Daily(time) => write!(f, "every day at {}", time.format("%H:%M")),
+            Schedule::Weekly(weekday, time) => {
+                write!(f, "every {} at {}", weekday, time.format("%H:%M"))
+            }
+        }
+    }
+}
+
+// Work run periodically by the job scheduler.
+#[async_trait]
+pub trait Job: Send + Sync {
+    fn get_name(&self) -> &str;
+    fn get_schedule(&self) -> &Schedule;
+
+    // The number of seconds the job stays locked while it runs.
+    // Another instance can run the job after that, in case this instance stopped during the run.
+    fn get_lock_seconds(&self) -> u64 {
+        3600
+    }
+
+    async fn run(&self) -> Result<()>;
+}
+
+// Synchronize the players collection with the NHL api.
+pub struct PlayersSyncJob {
+    players_service: PlayersServiceHandle,
+    schedule: Schedule,
+}
+
+impl PlayersSyncJob {
+    pub fn new(players_service: PlayersServiceHandle, nhl_api: &NhlApi) -> Self {
+        Self {
+            players_service,
+            schedule: Schedule::Interval(Duration::from_secs(
+                nhl_api.players_sync_interval_hours * 3600,
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for PlayersSyncJob {
+    fn get_name(&self) -> &str {
+        "players_sync"
+    }
+
+    fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    async fn run(&self) -> Result<()> {
+        let synced_players = self.players_service.sync_players().await?;
+        info!(
+            job = self.get_name(),
+            synced_players, "Players synced with the NHL api."
+        );
+
+        Ok(())
+    }
+}
+
+// Synchronize the contracts of the players with the contracts source.
+pub struct ContractsSyncJob {
+    players_service: PlayersServiceHandle,
+    schedule: Schedule,
+}
+
+impl ContractsSyncJob {
+    pub fn new(players_service: PlayersServiceHandle, contracts_api: &ContractsApi) -> Self {
+        Self {
+            players_service,
+            schedule: Schedule::Interval(Duration::from_secs(
+                contracts_api.sync_interval_hours * 3600,
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for ContractsSyncJob {
+    fn get_name(&self) -> &str {
+        "contracts_sync"
+    }
+
+    fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    async fn run(&self) -> Result<()> {
+        let updated_contracts = self.players_service.sync_contracts().await?;
+        info!(
+            job = self.get_name(),
+            updated_contracts, "Contracts updated from the contracts source."
+        );
+
+        Ok(())
+    }
+}
+
+// Execute the accepted trades whose review window passed without a veto.
+pub struct TradeReviewsJob {
+    pool_service: PoolServiceHandle,
+    schedule: Schedule,
+}
+
+impl TradeReviewsJob {
+    pub fn new(pool_service: PoolServiceHandle) -> Self {
+        Self {
+            pool_service,
+            schedule: Schedule::Interval(Duration::from_secs(3600)),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for TradeReviewsJob {
+    fn get_name(&self) -> &str {
+        "trade_reviews"
+    }
+
+    fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.pool_service.complete_trade_reviews().await
+    }
+}
+
+// Expire the trades that were not answered before their expiration.
+pub struct TradeExpirationsJob {
+    pool_service: PoolServiceHandle,
+    schedule: Schedule,
+}
+
+impl TradeExpirationsJob {
+    pub fn new(pool_service: PoolServiceHandle) -> Self {
+        Self {
+            pool_service,
+            schedule: Schedule::Interval(Duration::from_secs(3600)),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for TradeExpirationsJob {
+    fn get_name(&self) -> &str {
+        "trade_expirations"
+    }
+
+    fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.pool_service.expire_trades().await
+    }
+}
+
+// Protect the players of the poolers that missed the protection deadline and complete the protection.
+pub struct ProtectionDeadlinesJob {
+    pool_service: PoolServiceHandle,
+    schedule: Schedule,
+}
+
+impl ProtectionDeadlinesJob {
+    pub fn new(pool_service: PoolServiceHandle) -> Self {
+        Self {
+            pool_service,
+            schedule: Schedule::Interval(Duration::from_secs(3600)),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for ProtectionDeadlinesJob {
+    fn get_name(&self) -> &str {
+        "protection_deadlines"
+    }
+
+    fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.pool_service.complete_late_protections().await
+    }
+}
+
+// Cumulate the points made the previous day in every pool in progress.
+pub struct DailyCumulationJob {
+    pool_service: PoolServiceHandle,
+    schedule: Schedule,
+}
+
+impl DailyCumulationJob {
+    pub fn new(pool_service: PoolServiceHandle, nhl_api: &NhlApi) -> Self {
+        let cumulation_time = NaiveTime::from_hms_opt(nhl_api.daily_cumulation_hour, 0, 0)
+            .expect("The daily cumulation hour should be between 0 and 23.");
+
+        Self {
+            pool_service,
+            schedule: Schedule::Daily(cumulation_time),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for DailyCumulationJob {
+    fn get_name(&self) -> &str {
+        "daily_cumulation"
+    }
+
+    fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.pool_service.cumulate_daily_points().await
+    }
+}
+
+// Update the provisional points of the pools while NHL games are in progress.
+pub struct LiveScoringJob {
+    pool_service: PoolServiceHandle,
+    schedule: Schedule,
+}
+
+impl LiveScoringJob {
+    pub fn new(pool_service: PoolServiceHandle) -> Self {
+        Self {
+            pool_service,
+            schedule: Schedule::Interval(Duration::from_secs(60)),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for LiveScoringJob {
+    fn get_name(&self) -> &str {
+        "live_scoring"
+    }
+
+    fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    // The live scores are not stalled for an hour when an instance stops during a run.
+    fn get_lock_seconds(&self) -> u64 {
+        300
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.pool_service.update_live_scores().await
+    }
+}
+
+// Start a new week of acquisitions in every pool in progress, on monday at midnight.
+pub struct WeeklyAcquisitionsJob {
+    pool_service: PoolServiceHandle,
+    schedule: Schedule,
+}
+
+impl WeeklyAcquisitionsJob {
+    pub fn new(pool_service: PoolServiceHandle) -> Self {
+        Self {
+            pool_service,
+            schedule: Schedule::Weekly(Weekday::Mon, NaiveTime::MIN),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for WeeklyAcquisitionsJob {
+    fn get_name(&self) -> &str {
+        "weekly_acquisitions"
+    }
+
+    fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.pool_service.reset_weekly_acquisitions().await
+    }
+}
+
+// Anonymize the accounts whose deletion grace period is over.
+pub struct AccountDeletionsJob {
+    users_service: UsersServiceHandle,
+    schedule: Schedule,
+}
+
+impl AccountDeletionsJob {
+    pub fn new(users_service: UsersServiceHandle) -> Self {
+        Self {
+            users_service,
+            schedule: Schedule::Interval(Duration::from_secs(3600)),
+        }
+    }
+}
+
+#[async_trait]
+impl Job for AccountDeletionsJob {
+    fn get_name(&self) -> &str {
+        "account_deletions"
+    }
+
+    fn get_schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    async fn run(&self) -> Result<()> {
+        self.users_service.complete_account_deletions().await
+    }
+}
+
+// Run the registered jobs on their schedule.
+// The jobs collection locks each run so only one backend instance runs a job at a time.
+#[derive(Clone)]
+pub struct JobScheduler {
+    db: DatabaseConnection,
+    instance_id: String,
+    jobs: Arc<Vec<Arc<dyn Job>>>,
+}
+
+impl JobScheduler {
+    pub fn new(db: DatabaseConnection, jobs: Vec<Arc<dyn Job>>) -> Self {
+        Self {
+            db,
+            instance_id: Uuid::new_v4().simple().to_string(),
+            jobs: Arc::new(jobs),
+        }
+    }
+
+    fn get_job(&self, name: &str) -> Result<Arc<dyn Job>> {
+        self.jobs
+            .iter()
+            .find(|job| job.get_name() == name)
+            .cloned()
+            .ok_or_else(|| AppError::CustomError {
+                msg: format!("There is no job named {}.", name),
+            })
+    }
+
+    // Create the state of the job if it is new, the first run of the job is planned then.
+    async fn register(&self, job: &dyn Job) -> Result<()> {
+        let now = Local::now();
+
+        self.db
+            .collection::<JobInfo>("jobs")
+            .update_one(
+                doc! {"name": job.get_name()},
+                doc! {
+                    "$set": {"schedule": job.get_schedule().to_string()},
+                    "$setOnInsert": {"next_run": job.get_schedule().get_first_run(now)},
+                },
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(())
+    }
+
+    // Lock the job for this instance and plan its next run.
+    // False is returned when the job is running on an instance or, if only_if_due, when it is not due yet.
+    async fn try_lock(&self, job: &dyn Job, only_if_due: bool) -> Result<bool> {
+        let now = Local::now();
+        let now_millis = now.timestamp_millis();
+
+        let mut filter = doc! {
+            "name": job.get_name(),
+            "$or": [{"locked_until": null}, {"locked_until": {"$lt": now_millis}}],
+        };
+        if only_if_due {
+            filter.insert("next_run", doc! {"$lte": now_millis});
+        }
+
+        let result = self
+            .db
+            .collection::<JobInfo>("jobs")
+            .update_one(
+                filter,
+                doc! {"$set": {
+                    "locked_by": &self.instance_id,
+                    "locked_until": now_millis + job.get_lock_seconds() as i64 * 1000,
+                    "last_run_start": now_millis,
+                    "next_run": job.get_schedule().get_next_run(now),
+                }},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(result.modified_count == 1)
+    }
+
+    // Run a job locked by this instance, then release its lock.
+    async fn run_job(&self, job: &dyn Job) {
+        let result = job.run().await;
+
+        if let Err(e) = &result {
+            error!(job = job.get_name(), "The job failed: {}", e);
+        }
+
+        if let Err(e) = self
+            .db
+            .collection::<JobInfo>("jobs")
+            .update_one(
+                doc! {"name": job.get_name(), "locked_by": &self.instance_id},
+                doc! {"$set": {
+                    "locked_by": null,
+                    "locked_until": null,
+                    "last_run_end": Utc::now().timestamp_millis(),
+                    "last_error": result.err().map(|e| e.to_string()),
+                }},
+                None,
+            )
+            .await
+        {
+            warn!(
+                job = job.get_name(),
+                "Could not release the job lock: {}", e
+            );
+        }
+    }
+
+    async fn get_job_info(&self, name: &str) -> Result<JobInfo> {
+        self.db
+            .collection::<JobInfo>("jobs")
+            .find_one(doc! {"name": name}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .ok_or_else(|| AppError::CustomError {
+                msg: format!("The job {} was never registered.", name),
+            })
+    }
+}
+
+#[async_trait]
+impl JobsService for JobScheduler {
+    fn start(&self) {
+        for job in self.jobs.iter() {
+            let scheduler = self.clone();
+            let job = job.clone();
+
+            tokio::spawn(async move {
+                if let Err(e) = scheduler.register(job.as_ref()).await {
+                    warn!(job = job.get_name(), "Could not register the job: {}", e);
+                }
+
+                let mut interval = tokio::time::interval(Duration::from_secs(JOBS_POLL_SECONDS));
+
+                loop {
+                    interval.tick().await;
+
+                    match scheduler.try_lock(job.as_ref(), true).await {
+                        Ok(true) => scheduler.run_job(job.as_ref()).await,
+                        Ok(false) => {}
+                        Err(e) => warn!(job = job.get_name(), "Could not lock the job: {}", e),
+                    }
+                }
+            });
+        }
+    }
+
+    async fn list_jobs(&self) -> Result<Vec<JobInfo>> {
+        self.db
+            .collection::<JobInfo>("jobs")
+            .find(
+                doc! {},
+                FindOptions::builder().sort(doc! {"name": 1}).build(),
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    async fn trigger_job(&self, name: &str) -> Result<JobInfo> {
+        let job = self.get_job(name)?;
+
+        self.register(job.as_ref()).await?;
+
+        if !self.try_lock(job.as_ref(), false).await? {
+            return Err(AppError::CustomError {
+                msg: format!("The job {} is already running.", name),
+            });
+        }
+
+        // The job runs in the background, its state tells when it completed.
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            scheduler.run_job(job.as_ref()).await;
+        });
+
+        self.get_job_info(name).await
+    }
+}
+
+// Create the index used to find the state of a job.
+pub async fn create_jobs_indexes(db: &DatabaseConnection) -> Result<()> {
+    db.collection::<JobInfo>("jobs")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"name": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
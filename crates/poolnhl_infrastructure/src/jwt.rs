@@ -3,9 +3,14 @@ use std::fmt;
 use chrono::Utc;
 use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
 
-use poolnhl_interface::{errors::AppError, users::model::UserEmailJwtPayload};
+use poolnhl_interface::{
+    errors::AppError, users::model::UserEmailJwtPayload, users::service::UsersServiceHandle,
+};
 use serde::Deserialize;
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::warn;
 
 use axum::{async_trait, extract::FromRequestParts, http::request::Parts, RequestPartsExt};
 use axum_extra::{
@@ -13,7 +18,7 @@ use axum_extra::{
     TypedHeader,
 };
 
-use crate::{services::ServiceRegistry, settings::Auth};
+use crate::{http_client::HttpClient, services::ServiceRegistry, settings::Auth};
 
 #[derive(Debug, Deserialize, Clone)]
 struct Jwk {
@@ -32,37 +37,80 @@ struct Jwks {
 pub struct CachedJwks {
     jwks: RwLock<Jwks>,
     pub auth_info: Auth,
+
+    // Client of the Hanko server, a Hanko outage fails the authentication fast instead of hanging.
+    client: HttpClient,
+
+    // The date the JWKS was last fetched, in milliseconds.
+    date_fetched: RwLock<i64>,
+
+    // Held while the JWKS is fetched because of an unknown kid, so concurrent misses fetch it only once.
+    refetch_lock: tokio::sync::Mutex<()>,
 }
 
-async fn fetch_new_jwks(jwks_url: &str) -> Result<Jwks, AppError> {
+async fn fetch_new_jwks(client: &HttpClient, jwks_url: &str) -> Result<Jwks, AppError> {
     // Fetch the latest jwks stored into the Hanko server using the endpoints.
     // This is called when we discovered the jwks kid does not exist in the cache variable.
     // The key rotation is not that often so this function should not be called a lot.
-    let response = reqwest::get(jwks_url)
-        .await
-        .map_err(|e| AppError::ReqwestError { msg: e.to_string() })?;
-
-    let new_jwks = response
-        .json::<Jwks>()
-        .await
-        .map_err(|e| AppError::JwtError { msg: e.to_string() })?;
-
-    Ok(new_jwks)
+    client.get_json::<Jwks>(jwks_url).await
 }
 
 impl CachedJwks {
     pub async fn new(auth_info: &Auth) -> Result<Self, AppError> {
         // On the cached creation first fetch the JSON web key sets.
-        let jwks = fetch_new_jwks(&auth_info.jwks_url).await?;
+        let client = HttpClient::new("hanko");
+        let jwks = fetch_new_jwks(&client, &auth_info.jwks_url).await?;
 
         Ok(CachedJwks {
             jwks: RwLock::new(jwks),
             auth_info: auth_info.clone(),
+            client,
+            date_fetched: RwLock::new(Utc::now().timestamp_millis()),
+            refetch_lock: tokio::sync::Mutex::new(()),
         })
     }
 
+    // Trust a single RSA key instead of the Hanko JWKS, used by the in-memory services.
+    #[cfg(feature = "test-util")]
+    pub fn with_rsa_key(auth_info: &Auth, kid: &str, n: &str, e: &str) -> Self {
+        CachedJwks {
+            jwks: RwLock::new(Jwks {
+                keys: vec![Jwk {
+                    kty: "RSA".to_string(),
+                    kid: kid.to_string(),
+                    n: n.to_string(),
+                    e: e.to_string(),
+                    alg: "RS256".to_string(),
+                }],
+            }),
+            auth_info: auth_info.clone(),
+            client: HttpClient::new("hanko"),
+            date_fetched: RwLock::new(Utc::now().timestamp_millis()),
+            refetch_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    // Periodically fetch the JWKS so the rotated keys are known before a token signed with them is received.
+    pub fn spawn_refresh(self: &Arc<Self>) {
+        let cached_jwks = self.clone();
+        let period = Duration::from_secs(self.auth_info.jwks_refresh_interval_seconds);
+
+        tokio::spawn(async move {
+            // The JWKS was just fetched on creation.
+            let mut interval = tokio::time::interval_at(Instant::now() + period, period);
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = cached_jwks.update_jwks().await {
+                    warn!("Could not refresh the JWKS: {}", e);
+                }
+            }
+        });
+    }
+
     async fn update_jwks(&self) -> Result<(), AppError> {
-        let new_jwks = fetch_new_jwks(&self.auth_info.jwks_url).await?;
+        let new_jwks = fetch_new_jwks(&self.client, &self.auth_info.jwks_url).await?;
 
         // The following 2 lines lock the mutex to update its value.
         // It needs to be fast since the cached jwks is shared across thread.
@@ -72,6 +120,37 @@ impl CachedJwks {
             .map_err(|e| AppError::JwtError { msg: e.to_string() })?;
 
         *jwks_write_lock = new_jwks;
+
+        let mut date_fetched_write_lock = self
+            .date_fetched
+            .write()
+            .map_err(|e| AppError::JwtError { msg: e.to_string() })?;
+
+        *date_fetched_write_lock = Utc::now().timestamp_millis();
+        Ok(())
+    }
+
+    fn get_seconds_since_fetched(&self) -> Result<i64, AppError> {
+        let date_fetched_read_lock = self
+            .date_fetched
+            .read()
+            .map_err(|e| AppError::JwtError { msg: e.to_string() })?;
+
+        Ok((Utc::now().timestamp_millis() - *date_fetched_read_lock) / 1000)
+    }
+
+    // Fetch the JWKS again if it is empty or was fetched more than max_age_seconds ago.
+    pub async fn refresh_if_stale(&self, max_age_seconds: i64) -> Result<(), AppError> {
+        let is_empty = self
+            .jwks
+            .read()
+            .map_err(|e| AppError::JwtError { msg: e.to_string() })?
+            .keys
+            .is_empty();
+
+        if is_empty || self.get_seconds_since_fetched()? > max_age_seconds {
+            self.update_jwks().await?;
+        }
         Ok(())
     }
 
@@ -96,7 +175,20 @@ impl CachedJwks {
             None => {
                 // If the matching key is not found, it is probably due to the keys being rotated.
                 // We need to query the JWKS back again.
-                self.update_jwks().await?;
+                let _refetch = self.refetch_lock.lock().await;
+
+                // Another request could have fetched the rotated keys while waiting for the lock.
+                if let Some(matching_jwk) = self.get_matching_key(token_kid)? {
+                    return Ok(matching_jwk);
+                }
+
+                // Tokens with an unknown kid cannot make us query Hanko on every request.
+                if self.get_seconds_since_fetched()?
+                    >= self.auth_info.jwks_min_refetch_seconds as i64
+                {
+                    self.update_jwks().await?;
+                }
+
                 self.get_matching_key(token_kid)?
                     .ok_or(AppError::NonMatchingKid {
                         msg: format!("No json web token found with kid {}", token_kid),
@@ -137,17 +229,41 @@ where
                 msg: err.to_string(),
             })?;
 
-        let token_data = hanko_token_decode(bearer.token(), &state.cached_keys).await?;
+        validate_token(bearer.token(), state).await
+    }
+}
 
-        // Validate if the token is expired.
-        if token_data.exp < Utc::now().timestamp() {
-            return Err(AppError::AuthError {
-                msg: "The token is expired, please reconnect.".to_string(),
-            });
-        }
+// Decode a token and validate it is still accepted.
+pub async fn validate_token(
+    token: &str,
+    state: &ServiceRegistry,
+) -> Result<UserEmailJwtPayload, AppError> {
+    validate_user_token(token, &state.cached_keys, &state.users_service).await
+}
 
-        Ok(token_data)
+// Same validation as validate_token, for the services that do not hold the service registry (i.g., the draft sockets).
+pub async fn validate_user_token(
+    token: &str,
+    cached_jwks: &CachedJwks,
+    users_service: &UsersServiceHandle,
+) -> Result<UserEmailJwtPayload, AppError> {
+    let token_data = hanko_token_decode(token, cached_jwks).await?;
+
+    // Validate if the token is expired.
+    if token_data.exp < Utc::now().timestamp() {
+        return Err(AppError::AuthError {
+            msg: "The token is expired, please reconnect.".to_string(),
+        });
     }
+
+    // Validate that the user did not log out everywhere since the token was issued.
+    if users_service.is_token_revoked(&token_data).await? {
+        return Err(AppError::AuthError {
+            msg: "The token was revoked, please reconnect.".to_string(),
+        });
+    }
+
+    Ok(token_data)
 }
 
 pub async fn hanko_token_decode(
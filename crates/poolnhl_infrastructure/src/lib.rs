@@ -1,4 +1,17 @@
+pub mod cache;
+pub mod contracts_api;
 pub mod database_connection;
+pub mod http_client;
+pub mod idempotency;
+#[cfg(feature = "test-util")]
+pub mod in_memory;
+pub mod jobs;
 pub mod jwt;
+pub mod mailer;
+pub mod migrations;
+pub mod nhl_api;
+pub mod room_store;
 pub mod services;
 pub mod settings;
+pub mod transactions;
+pub mod webhooks;
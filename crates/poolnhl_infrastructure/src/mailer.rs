@@ -0,0 +1,78 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::json;
+use tracing::info;
+
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::mailer::{
+    model::Email,
+    service::{Mailer, MailerHandle},
+};
+
+use crate::settings::MailerSettings;
+
+// Send the emails through the SendGrid web api.
+pub struct SendGridMailer {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    from_email: String,
+}
+
+impl SendGridMailer {
+    pub fn new(settings: &MailerSettings) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: settings.base_url.clone(),
+            api_key: settings.api_key.clone(),
+            from_email: settings.from_email.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl Mailer for SendGridMailer {
+    async fn send_email(&self, email: Email) -> Result<()> {
+        let response = self
+            .client
+            .post(format!("{}/mail/send", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&json!({
+                "personalizations": [{"to": [{"email": email.to}]}],
+                "from": {"email": self.from_email},
+                "subject": email.subject,
+                "content": [{"type": "text/plain", "value": email.body}],
+            }))
+            .send()
+            .await
+            .map_err(|e| AppError::ReqwestError { msg: e.to_string() })?;
+
+        if !response.status().is_success() {
+            return Err(AppError::ReqwestError {
+                msg: format!("The email could not be sent ({}).", response.status()),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// Only log the emails, used when no mailer api key is configured.
+pub struct LogMailer;
+
+#[async_trait]
+impl Mailer for LogMailer {
+    async fn send_email(&self, email: Email) -> Result<()> {
+        info!(to = %email.to, subject = %email.subject, "Email not sent, no mailer is configured.");
+        Ok(())
+    }
+}
+
+pub fn new_mailer(settings: &MailerSettings) -> MailerHandle {
+    if settings.api_key.is_empty() {
+        Arc::new(LogMailer)
+    } else {
+        Arc::new(SendGridMailer::new(settings))
+    }
+}
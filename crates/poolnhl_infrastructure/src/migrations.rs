@@ -0,0 +1,154 @@
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::Collection;
+use tracing::warn;
+
+use poolnhl_interface::admin::model::PoolsMigrationReport;
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::pool::model::{Pool, POOL_SCHEMA_VERSION, TRADE_DEADLINE_DATE};
+
+use crate::cache::pool_cache;
+use crate::database_connection::DatabaseConnection;
+
+// Upgrade of the pool documents from the previous version to its version.
+struct Migration {
+    version: u32,
+    description: &'static str,
+    upgrade: fn(&mut Document) -> Result<()>,
+}
+
+// The migrations are applied in order, the last version needs to be POOL_SCHEMA_VERSION.
+const POOL_MIGRATIONS: [Migration; 1] = [Migration {
+    version: 1,
+    description: "store the default trade deadline in the pools created before it was stored",
+    upgrade: set_default_trade_deadline,
+}];
+
+fn set_default_trade_deadline(pool: &mut Document) -> Result<()> {
+    if !matches!(pool.get("trade_deadline"), Some(deadline) if deadline.as_str().is_some()) {
+        pool.insert("trade_deadline", TRADE_DEADLINE_DATE);
+    }
+
+    Ok(())
+}
+
+pub fn get_schema_version(pool: &Document) -> u32 {
+    // The documents created before the migrations were introduced are at version 0.
+    match pool.get("schema_version") {
+        Some(version) => version
+            .as_i32()
+            .map(|version| version as u32)
+            .or_else(|| version.as_i64().map(|version| version as u32))
+            .unwrap_or(0),
+        None => 0,
+    }
+}
+
+// Filter matching the pool documents that are not at the current version.
+fn outdated_pools_filter() -> Document {
+    doc! {
+        "$or": [
+            {"schema_version": {"$exists": false}},
+            {"schema_version": {"$lt": POOL_SCHEMA_VERSION}},
+        ]
+    }
+}
+
+// Apply the missing migrations to a pool document and stamp it with the current version.
+// Return true if the document was modified.
+pub fn migrate_pool_document(pool: &mut Document) -> Result<bool> {
+    let schema_version = get_schema_version(pool);
+
+    if schema_version >= POOL_SCHEMA_VERSION {
+        return Ok(false);
+    }
+
+    for migration in POOL_MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > schema_version)
+    {
+        (migration.upgrade)(pool).map_err(|e| AppError::CustomError {
+            msg: format!(
+                "the migration to version {} ({}) failed: {}",
+                migration.version, migration.description, e
+            ),
+        })?;
+    }
+
+    pool.insert("schema_version", POOL_SCHEMA_VERSION);
+    Ok(true)
+}
+
+async fn save_migrated_pool(collection: &Collection<Document>, mut pool: Document) -> Result<()> {
+    let id = pool
+        .get("_id")
+        .cloned()
+        .ok_or_else(|| AppError::CustomError {
+            msg: "the pool document has no _id.".to_string(),
+        })?;
+
+    if !migrate_pool_document(&mut pool)? {
+        return Ok(());
+    }
+
+    collection
+        .replace_one(doc! {"_id": id}, pool, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
+
+// Migrate a single pool when it is read, the complete document is needed to replace it.
+pub async fn migrate_pool(collection: &Collection<Pool>, pool_name: &str) -> Result<()> {
+    let collection = collection.clone_with_type::<Document>();
+
+    let Some(pool) = collection
+        .find_one(doc! {"name": pool_name}, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+    else {
+        return Ok(());
+    };
+
+    save_migrated_pool(&collection, pool).await?;
+    pool_cache().invalidate(pool_name).await;
+
+    Ok(())
+}
+
+// Migrate every outdated pool, the pools that fail are reported and left as is.
+pub async fn migrate_pools(db: &DatabaseConnection) -> Result<PoolsMigrationReport> {
+    let collection = db.collection::<Document>("pools");
+
+    let mut cursor = collection
+        .find(outdated_pools_filter(), None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    let mut report = PoolsMigrationReport {
+        schema_version: POOL_SCHEMA_VERSION,
+        migrated_pools: Vec::new(),
+        failed_pools: Vec::new(),
+    };
+
+    while let Some(pool) = cursor
+        .try_next()
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+    {
+        let pool_name = pool.get_str("name").unwrap_or_default().to_string();
+
+        match save_migrated_pool(&collection, pool).await {
+            Ok(()) => report.migrated_pools.push(pool_name.clone()),
+            Err(e) => {
+                warn!(pool_name, "Could not migrate the pool: {}", e);
+                report.failed_pools.push(pool_name.clone());
+            }
+        }
+
+        pool_cache().invalidate(&pool_name).await;
+    }
+
+    Ok(report)
+}
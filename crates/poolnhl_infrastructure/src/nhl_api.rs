@@ -0,0 +1,737 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use moka::future::Cache;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::players::model::{DailySchedule, PlayerSeasonStats, ScheduledGame};
+use poolnhl_interface::pool::model::{
+    DailyPlayersPoints, GoalyPoints, InjuryStatus, SeasonDates, SkaterPoints, TeamRemainingSchedule,
+};
+
+use crate::http_client::HttpClient;
+use crate::settings::NhlApi;
+
+// The final games of a night kept by the live scoring, and how long they are kept.
+const FINAL_GAMES_CACHE_CAPACITY: u64 = 64;
+const FINAL_GAMES_CACHE_TTL_SECONDS: u64 = 12 * 3600;
+
+// The NHL team abbreviations with their corresponding NHL team id.
+pub const NHL_TEAMS: [(&str, u32); 32] = [
+    ("NJD", 1),
+    ("NYI", 2),
+    ("NYR", 3),
+    ("PHI", 4),
+    ("PIT", 5),
+    ("BOS", 6),
+    ("BUF", 7),
+    ("MTL", 8),
+    ("OTT", 9),
+    ("TOR", 10),
+    ("CAR", 12),
+    ("FLA", 13),
+    ("TBL", 14),
+    ("WSH", 15),
+    ("CHI", 16),
+    ("DET", 17),
+    ("NSH", 18),
+    ("STL", 19),
+    ("CGY", 20),
+    ("COL", 21),
+    ("EDM", 22),
+    ("VAN", 23),
+    ("ANA", 24),
+    ("DAL", 25),
+    ("LAK", 26),
+    ("SJS", 28),
+    ("CBJ", 29),
+    ("MIN", 30),
+    ("WPG", 52),
+    ("VGK", 54),
+    ("SEA", 55),
+    ("UTA", 59),
+];
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LocalizedName {
+    pub default: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct RosterPlayer {
+    pub id: u32,
+    pub firstName: LocalizedName,
+    pub lastName: LocalizedName,
+    pub positionCode: String,
+    pub birthDate: Option<String>,
+    pub injuryStatus: Option<String>,
+}
+
+impl RosterPlayer {
+    pub fn get_name(&self) -> String {
+        format!("{} {}", self.firstName.default, self.lastName.default)
+    }
+
+    pub fn get_age(&self) -> Option<u8> {
+        // Compute the age of the player at today's date.
+        let birth_date = NaiveDate::parse_from_str(self.birthDate.as_ref()?, "%Y-%m-%d").ok()?;
+        let today = Local::now().date_naive();
+
+        // The ordinal days are shifted by the leap years, the birthday is compared by month and day.
+        let mut age = today.year() - birth_date.year();
+        if (today.month(), today.day()) < (birth_date.month(), birth_date.day()) {
+            age -= 1;
+        }
+
+        u8::try_from(age).ok()
+    }
+
+    pub fn get_injury_status(&self) -> Option<InjuryStatus> {
+        // The healthy players have no injury designation.
+        match self.injuryStatus.as_deref()? {
+            "IR" | "LTIR" => Some(InjuryStatus::InjuredReserve),
+            "DTD" => Some(InjuryStatus::DayToDay),
+            "OUT" => Some(InjuryStatus::Out),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TeamRoster {
+    pub forwards: Vec<RosterPlayer>,
+    pub defensemen: Vec<RosterPlayer>,
+    pub goalies: Vec<RosterPlayer>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct ScoreGame {
+    pub id: u32,
+    pub gameState: String,
+    pub awayTeam: ScoreTeam,
+    pub homeTeam: ScoreTeam,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ScoreTeam {
+    pub id: u32,
+}
+
+impl ScoreGame {
+    pub fn is_final(&self) -> bool {
+        matches!(self.gameState.as_str(), "OFF" | "FINAL")
+    }
+
+    pub fn is_live(&self) -> bool {
+        matches!(self.gameState.as_str(), "LIVE" | "CRIT")
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DailyScores {
+    pub games: Vec<ScoreGame>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct BoxscoreSkater {
+    pub playerId: u32,
+    #[serde(default)]
+    pub goals: u8,
+    #[serde(default)]
+    pub assists: u8,
+    pub sog: Option<u8>,
+    pub hits: Option<u8>,
+    pub blockedShots: Option<u8>,
+    pub pim: Option<u8>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct BoxscoreGoalie {
+    pub playerId: u32,
+    #[serde(default)]
+    pub goals: u8,
+    #[serde(default)]
+    pub assists: u8,
+    pub goalsAgainst: Option<u8>,
+    pub saves: Option<u8>,
+    pub decision: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct BoxscoreTeam {
+    pub forwards: Vec<BoxscoreSkater>,
+    pub defense: Vec<BoxscoreSkater>,
+    pub goalies: Vec<BoxscoreGoalie>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct PlayerByGameStats {
+    pub awayTeam: BoxscoreTeam,
+    pub homeTeam: BoxscoreTeam,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct Boxscore {
+    pub playerByGameStats: PlayerByGameStats,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct ShootoutAttempt {
+    pub playerId: u32,
+    pub result: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct GoalAssist {
+    pub playerId: u32,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct ScoringGoal {
+    pub playerId: u32,
+    pub strength: String,
+    #[serde(default)]
+    pub assists: Vec<GoalAssist>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PeriodScoring {
+    #[serde(default)]
+    pub goals: Vec<ScoringGoal>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LandingSummary {
+    #[serde(default)]
+    pub scoring: Vec<PeriodScoring>,
+    #[serde(default)]
+    pub shootout: Vec<ShootoutAttempt>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Landing {
+    pub summary: Option<LandingSummary>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct PlayDetails {
+    pub winningPlayerId: Option<u32>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct Play {
+    pub typeDescKey: String,
+    pub details: Option<PlayDetails>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct PlayByPlay {
+    pub plays: Vec<Play>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct Schedule {
+    pub regularSeasonStartDate: String,
+    pub regularSeasonEndDate: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ScheduleTeam {
+    pub abbrev: String,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClubScheduleGame {
+    pub id: u32,
+    pub gameType: u8, // 2 for the regular season.
+    pub gameDate: String,
+    pub startTimeUTC: Option<String>,
+    pub awayTeam: ScheduleTeam,
+    pub homeTeam: ScheduleTeam,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClubSchedule {
+    pub games: Vec<ClubScheduleGame>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct TeamStanding {
+    pub teamAbbrev: LocalizedName,
+    pub pointPctg: f64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Standings {
+    pub standings: Vec<TeamStanding>,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClubSkaterStats {
+    pub playerId: u32,
+    pub gamesPlayed: u32,
+    #[serde(default)]
+    pub goals: u32,
+    #[serde(default)]
+    pub assists: u32,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClubGoalieStats {
+    pub playerId: u32,
+    pub gamesPlayed: u32,
+    #[serde(default)]
+    pub goals: u32,
+    #[serde(default)]
+    pub assists: u32,
+    #[serde(default)]
+    pub wins: u32,
+    #[serde(default)]
+    pub shutouts: u32,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ClubStats {
+    pub skaters: Vec<ClubSkaterStats>,
+    pub goalies: Vec<ClubGoalieStats>,
+}
+
+// Count the number of occurrences of each player id.
+fn count_by_player(player_ids: impl Iterator<Item = u32>) -> HashMap<u32, u8> {
+    let mut count = HashMap::new();
+    for player_id in player_ids {
+        *count.entry(player_id).or_insert(0) += 1;
+    }
+    count
+}
+
+// Client of the NHL web api (https://api-web.nhle.com).
+#[derive(Clone)]
+pub struct NhlApiClient {
+    client: HttpClient,
+    base_url: String,
+
+    // The players points of the games already final, mapped by game id.
+    final_games_points: Cache<u32, DailyPlayersPoints>,
+}
+
+impl NhlApiClient {
+    pub fn new(nhl_api: &NhlApi) -> Self {
+        Self {
+            client: HttpClient::new("NHL api"),
+            base_url: nhl_api.base_url.clone(),
+            final_games_points: Cache::builder()
+                .max_capacity(FINAL_GAMES_CACHE_CAPACITY)
+                .time_to_live(std::time::Duration::from_secs(
+                    FINAL_GAMES_CACHE_TTL_SECONDS,
+                ))
+                .build(),
+        }
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        self.client
+            .get_json(&format!("{}/{}", self.base_url, path))
+            .await
+    }
+
+    pub async fn get_team_roster(&self, team_abbrev: &str) -> Result<TeamRoster> {
+        // Fetch the current roster of a team.
+        self.get(&format!("roster/{}/current", team_abbrev)).await
+    }
+
+    pub async fn get_daily_scores(&self, date: &str) -> Result<DailyScores> {
+        self.get(&format!("score/{}", date)).await
+    }
+
+    pub async fn get_teams_playing(&self, date: &str) -> Result<HashSet<u32>> {
+        // The NHL teams (team id) playing at a date.
+        Ok(self
+            .get_daily_scores(date)
+            .await?
+            .games
+            .iter()
+            .flat_map(|game| [game.awayTeam.id, game.homeTeam.id])
+            .collect())
+    }
+
+    pub async fn get_boxscore(&self, game_id: u32) -> Result<Boxscore> {
+        self.get(&format!("gamecenter/{}/boxscore", game_id)).await
+    }
+
+    pub async fn get_landing(&self, game_id: u32) -> Result<Landing> {
+        self.get(&format!("gamecenter/{}/landing", game_id)).await
+    }
+
+    pub async fn get_play_by_play(&self, game_id: u32) -> Result<PlayByPlay> {
+        self.get(&format!("gamecenter/{}/play-by-play", game_id))
+            .await
+    }
+
+    pub async fn get_schedule(&self) -> Result<Schedule> {
+        self.get("schedule/now").await
+    }
+
+    pub async fn get_club_schedule(&self, team_abbrev: &str) -> Result<ClubSchedule> {
+        // Fetch every game of the current season of a team.
+        self.get(&format!("club-schedule-season/{}/now", team_abbrev))
+            .await
+    }
+
+    pub async fn get_standings(&self) -> Result<Standings> {
+        self.get("standings/now").await
+    }
+
+    pub async fn get_club_stats(
+        &self,
+        team_abbrev: &str,
+        season: Option<u32>,
+    ) -> Result<ClubStats> {
+        // Fetch the regular season stats of a team, the current season when no season is provided.
+        match season {
+            Some(season) => {
+                self.get(&format!("club-stats/{}/{}/2", team_abbrev, season))
+                    .await
+            }
+            None => self.get(&format!("club-stats/{}/now", team_abbrev)).await,
+        }
+    }
+
+    // Regular season stats of every player for a season, the current season when no season is provided.
+    // The stats of a player traded during the season are cumulated over its teams.
+    pub async fn get_players_season_stats(
+        &self,
+        season: Option<u32>,
+    ) -> Result<HashMap<u32, PlayerSeasonStats>> {
+        let mut players_stats: HashMap<u32, PlayerSeasonStats> = HashMap::new();
+
+        for (team_abbrev, _) in NHL_TEAMS {
+            let club_stats = self.get_club_stats(team_abbrev, season).await?;
+
+            for skater in club_stats.skaters {
+                let stats = players_stats.entry(skater.playerId).or_default();
+                stats.game_played += skater.gamesPlayed;
+                stats.goals += skater.goals;
+                stats.assists += skater.assists;
+            }
+            for goalie in club_stats.goalies {
+                let stats = players_stats.entry(goalie.playerId).or_default();
+                stats.game_played += goalie.gamesPlayed;
+                stats.goals += goalie.goals;
+                stats.assists += goalie.assists;
+                stats.wins += goalie.wins;
+                stats.shutouts += goalie.shutouts;
+            }
+        }
+
+        Ok(players_stats)
+    }
+
+    // The regular season and playoffs games of the current season, grouped by date.
+    pub async fn get_season_schedule(&self) -> Result<Vec<DailySchedule>> {
+        let team_ids: HashMap<&str, u32> = NHL_TEAMS.iter().copied().collect();
+        let mut game_ids = HashSet::new();
+        let mut schedules: HashMap<String, DailySchedule> = HashMap::new();
+
+        for (team_abbrev, _) in NHL_TEAMS {
+            let schedule = self.get_club_schedule(team_abbrev).await?;
+
+            for game in schedule.games {
+                // Each game is listed in the schedule of both teams.
+                if !matches!(game.gameType, 2 | 3) || !game_ids.insert(game.id) {
+                    continue;
+                }
+
+                let (Some(home_team), Some(away_team)) = (
+                    team_ids.get(game.homeTeam.abbrev.as_str()),
+                    team_ids.get(game.awayTeam.abbrev.as_str()),
+                ) else {
+                    continue;
+                };
+
+                schedules
+                    .entry(game.gameDate.clone())
+                    .or_insert_with(|| DailySchedule::new(&game.gameDate))
+                    .games
+                    .push(ScheduledGame {
+                        id: game.id,
+                        home_team: *home_team,
+                        away_team: *away_team,
+                        start_time_utc: game.startTimeUTC,
+                    });
+            }
+        }
+
+        Ok(schedules.into_values().collect())
+    }
+
+    // Count the regular season games left after today for each team (NHL team id),
+    // with the average points percentage of their opponents.
+    pub async fn get_remaining_schedules(
+        &self,
+        team_ids: &HashSet<u32>,
+        today: NaiveDate,
+    ) -> Result<HashMap<u32, TeamRemainingSchedule>> {
+        let points_percentage: HashMap<String, f64> = self
+            .get_standings()
+            .await?
+            .standings
+            .into_iter()
+            .map(|standing| (standing.teamAbbrev.default, standing.pointPctg))
+            .collect();
+
+        let today = today.to_string();
+        let mut schedules = HashMap::new();
+
+        for (team_abbrev, team_id) in NHL_TEAMS.iter().filter(|(_, id)| team_ids.contains(id)) {
+            let schedule = self.get_club_schedule(team_abbrev).await?;
+
+            let opponents: Vec<&String> = schedule
+                .games
+                .iter()
+                .filter(|game| game.gameType == 2 && game.gameDate > today)
+                .map(|game| {
+                    if game.homeTeam.abbrev == *team_abbrev {
+                        &game.awayTeam.abbrev
+                    } else {
+                        &game.homeTeam.abbrev
+                    }
+                })
+                .collect();
+
+            let strength = if opponents.is_empty() {
+                0.5
+            } else {
+                opponents
+                    .iter()
+                    .map(|opponent| points_percentage.get(*opponent).copied().unwrap_or(0.5))
+                    .sum::<f64>()
+                    / opponents.len() as f64
+            };
+
+            schedules.insert(
+                *team_id,
+                TeamRemainingSchedule {
+                    games: opponents.len() as u16,
+                    strength,
+                },
+            );
+        }
+
+        Ok(schedules)
+    }
+
+    // Make sure the NHL api answers.
+    pub async fn ping(&self) -> Result<()> {
+        self.get_schedule().await.map(|_| ())
+    }
+
+    // Fetch the dates of the current NHL regular season.
+    pub async fn get_season_dates(&self) -> Result<SeasonDates> {
+        let schedule = self.get_schedule().await?;
+
+        let season_start = NaiveDate::parse_from_str(&schedule.regularSeasonStartDate, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+        let season_end = NaiveDate::parse_from_str(&schedule.regularSeasonEndDate, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        // The NHL api does not expose the trade deadline,
+        // it is usually 6 weeks before the end of the regular season.
+        let trade_deadline = season_end - Duration::weeks(6);
+
+        Ok(SeasonDates {
+            season: season_start.year() as u32 * 10000 + season_start.year() as u32 + 1,
+            season_start: season_start.to_string(),
+            season_end: season_end.to_string(),
+            trade_deadline: trade_deadline.to_string(),
+        })
+    }
+
+    // Compute the points made by every player that played on a given date.
+    // Return None if at least one game of that date is not completed yet.
+    pub async fn get_daily_players_points(&self, date: &str) -> Result<Option<DailyPlayersPoints>> {
+        let daily_scores = self.get_daily_scores(date).await?;
+
+        if daily_scores.games.iter().any(|game| !game.is_final()) {
+            return Ok(None);
+        }
+
+        self.get_games_players_points(&daily_scores.games, false)
+            .await
+            .map(Some)
+    }
+
+    // Compute the provisional points made by every player in the started games of a given date.
+    // Return None if no game of that date is in progress.
+    pub async fn get_live_players_points(&self, date: &str) -> Result<Option<DailyPlayersPoints>> {
+        let daily_scores = self.get_daily_scores(date).await?;
+
+        if !daily_scores.games.iter().any(|game| game.is_live()) {
+            return Ok(None);
+        }
+
+        let started_games: Vec<ScoreGame> = daily_scores
+            .games
+            .into_iter()
+            .filter(|game| game.is_live() || game.is_final())
+            .collect();
+
+        self.get_games_players_points(&started_games, true)
+            .await
+            .map(Some)
+    }
+
+    // The points of the final games can be read from the cache, the live scoring refreshes every minute.
+    // The cumulation reads them again so it gets the stats corrections.
+    async fn get_games_players_points(
+        &self,
+        games: &[ScoreGame],
+        use_final_games_cache: bool,
+    ) -> Result<DailyPlayersPoints> {
+        let mut daily_players_points = DailyPlayersPoints::default();
+
+        for game in games {
+            let is_cached = use_final_games_cache && game.is_final();
+
+            let cached_points = if is_cached {
+                self.final_games_points.get(&game.id).await
+            } else {
+                None
+            };
+
+            let game_players_points = match cached_points {
+                Some(game_players_points) => game_players_points,
+                None => {
+                    let game_players_points = self.get_game_players_points(game.id).await?;
+                    if is_cached {
+                        self.final_games_points
+                            .insert(game.id, game_players_points.clone())
+                            .await;
+                    }
+                    game_players_points
+                }
+            };
+
+            daily_players_points
+                .skaters
+                .extend(game_players_points.skaters);
+            daily_players_points
+                .goalies
+                .extend(game_players_points.goalies);
+        }
+
+        Ok(daily_players_points)
+    }
+
+    async fn get_game_players_points(&self, game_id: u32) -> Result<DailyPlayersPoints> {
+        let mut daily_players_points = DailyPlayersPoints::default();
+
+        let boxscore = self.get_boxscore(game_id).await?;
+
+        // The special teams points and the shootout goals are only available in the game summary.
+        let summary = self.get_landing(game_id).await?.summary;
+        let (scoring, shootout) = summary
+            .map(|summary| (summary.scoring, summary.shootout))
+            .unwrap_or_default();
+
+        let goals: Vec<&ScoringGoal> = scoring
+            .iter()
+            .flat_map(|period| period.goals.iter())
+            .collect();
+
+        // Every player that scored or assisted a goal with the given strength.
+        let get_strength_points = |strength: &str| {
+            count_by_player(
+                goals
+                    .iter()
+                    .filter(|goal| goal.strength == strength)
+                    .flat_map(|goal| {
+                        std::iter::once(goal.playerId)
+                            .chain(goal.assists.iter().map(|assist| assist.playerId))
+                    }),
+            )
+        };
+        let power_play_points = get_strength_points("pp");
+        let short_handed_points = get_strength_points("sh");
+
+        let shootout_goals = count_by_player(
+            shootout
+                .iter()
+                .filter(|attempt| attempt.result == "goal")
+                .map(|attempt| attempt.playerId),
+        );
+
+        // The faceoff wins are only available in the play by play.
+        let faceoff_wins = count_by_player(
+            self.get_play_by_play(game_id)
+                .await?
+                .plays
+                .iter()
+                .filter(|play| play.typeDescKey == "faceoff")
+                .filter_map(|play| play.details.as_ref()?.winningPlayerId),
+        );
+
+        for team in [
+            boxscore.playerByGameStats.awayTeam,
+            boxscore.playerByGameStats.homeTeam,
+        ] {
+            for skater in team.forwards.iter().chain(team.defense.iter()) {
+                let get_count =
+                    |count: &HashMap<u32, u8>| count.get(&skater.playerId).copied().unwrap_or(0);
+
+                daily_players_points.skaters.insert(
+                    skater.playerId.to_string(),
+                    SkaterPoints {
+                        G: skater.goals,
+                        A: skater.assists,
+                        SOG: Some(get_count(&shootout_goals)),
+                        S: skater.sog,
+                        HIT: skater.hits,
+                        BLK: skater.blockedShots,
+                        PIM: skater.pim,
+                        PPP: Some(get_count(&power_play_points)),
+                        SHP: Some(get_count(&short_handed_points)),
+                        FOW: Some(get_count(&faceoff_wins)),
+                    },
+                );
+            }
+
+            for goalie in &team.goalies {
+                let decision = goalie.decision.as_deref();
+
+                daily_players_points.goalies.insert(
+                    goalie.playerId.to_string(),
+                    GoalyPoints {
+                        G: goalie.goals,
+                        A: goalie.assists,
+                        W: decision == Some("W"),
+                        SO: decision == Some("W") && goalie.goalsAgainst == Some(0),
+                        OT: decision == Some("O"),
+                        SV: goalie.saves,
+                        GA: goalie.goalsAgainst,
+                    },
+                );
+            }
+        }
+
+        Ok(daily_players_points)
+    }
+}
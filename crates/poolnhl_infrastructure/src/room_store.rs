@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use poolnhl_interface::draft::model::{DraftServerInfo, RoomUser};
+use poolnhl_interface::draft::service::RoomStateStore;
+use poolnhl_interface::errors::{AppError, Result};
+
+use crate::cache::pool_cache;
+
+// Redis channel where the messages of a room are relayed (draft_room:<pool name>).
+const ROOM_CHANNEL_PREFIX: &str = "draft_room:";
+
+// Redis channel where the in-season updates of a pool are relayed (pool_updates:<pool name>).
+const POOL_CHANNEL_PREFIX: &str = "pool_updates:";
+
+// Redis hash keeping the users of a room, one field per instance (draft_room_users:<pool name>).
+const ROOM_USERS_PREFIX: &str = "draft_room_users:";
+
+// The room users of an instance that stopped without cleaning them are dropped after this delay.
+const ROOM_USERS_TTL_SECONDS: i64 = 24 * 60 * 60;
+
+// Redis key keeping the id of the pick timer started last in a room (draft_pick_timer:<pool name>).
+const PICK_TIMER_PREFIX: &str = "draft_pick_timer:";
+
+// Delete the pick timer key if it still holds the timer id, so it is claimed only once.
+const CLAIM_PICK_TIMER_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+#[derive(Serialize, Deserialize)]
+struct RelayedMessage {
+    instance_id: String,
+    message: String,
+}
+
+// Share the draft rooms between the backend instances with Redis.
+// The room broadcasts are relayed with pub/sub and the room users are kept in a hash per room.
+pub struct RedisRoomStore {
+    client: redis::Client,
+    instance_id: String,
+    tx: mpsc::UnboundedSender<(String, String)>,
+}
+
+impl RedisRoomStore {
+    pub fn spawn(redis_url: &str) -> Result<Self> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| AppError::RedisError { msg: e.to_string() })?;
+        let (tx, mut rx) = mpsc::unbounded_channel::<(String, String)>();
+
+        // A single worker publishes the messages so they keep their order.
+        let publisher_client = client.clone();
+        tokio::spawn(async move {
+            let mut connection = None;
+
+            while let Some((channel, payload)) = rx.recv().await {
+                if connection.is_none() {
+                    connection = publisher_client
+                        .get_multiplexed_async_connection()
+                        .await
+                        .map_err(|e| warn!("Could not connect to Redis: {}", e))
+                        .ok();
+                }
+
+                if let Some(publisher) = connection.as_mut() {
+                    if let Err(e) = publisher.publish::<_, _, ()>(&channel, &payload).await {
+                        warn!(%channel, "Could not publish the message: {}", e);
+                        connection = None;
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            client,
+            instance_id: Uuid::new_v4().simple().to_string(),
+            tx,
+        })
+    }
+
+    pub fn spawn_subscriber(&self, draft_server_info: Arc<DraftServerInfo>) {
+        // Deliver the messages of the other instances to the rooms of this instance.
+        // The subscription is restarted when the connection to Redis is lost.
+        let client = self.client.clone();
+        let instance_id = self.instance_id.clone();
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = relay_rooms(&client, &instance_id, &draft_server_info).await {
+                    warn!("The Redis subscription stopped, restarting it: {}", e);
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        });
+    }
+
+    async fn get_connection(&self) -> Result<redis::aio::MultiplexedConnection> {
+        self.client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| AppError::RedisError { msg: e.to_string() })
+    }
+}
+
+async fn relay_rooms(
+    client: &redis::Client,
+    instance_id: &str,
+    draft_server_info: &DraftServerInfo,
+) -> Result<()> {
+    let mut pubsub = client
+        .get_async_pubsub()
+        .await
+        .map_err(|e| AppError::RedisError { msg: e.to_string() })?;
+
+    for channel_prefix in [ROOM_CHANNEL_PREFIX, POOL_CHANNEL_PREFIX] {
+        pubsub
+            .psubscribe(format!("{}*", channel_prefix))
+            .await
+            .map_err(|e| AppError::RedisError { msg: e.to_string() })?;
+    }
+
+    let mut messages = pubsub.on_message();
+
+    while let Some(message) = messages.next().await {
+        let channel_name = message.get_channel_name();
+        let (pool_name, is_pool_update) = match channel_name.strip_prefix(ROOM_CHANNEL_PREFIX) {
+            Some(pool_name) => (pool_name, false),
+            None => match channel_name.strip_prefix(POOL_CHANNEL_PREFIX) {
+                Some(pool_name) => (pool_name, true),
+                None => continue,
+            },
+        };
+
+        let Ok(relayed_message) = message
+            .get_payload::<String>()
+            .map_err(|e| e.to_string())
+            .and_then(|payload| {
+                serde_json::from_str::<RelayedMessage>(&payload).map_err(|e| e.to_string())
+            })
+        else {
+            continue;
+        };
+
+        // The messages published by this instance were already sent to its rooms.
+        if relayed_message.instance_id == instance_id {
+            continue;
+        }
+
+        // The pool was updated by another instance, the cached copy of this instance is stale.
+        pool_cache().invalidate(pool_name).await;
+
+        if is_pool_update {
+            draft_server_info.relay_pool_update(pool_name, relayed_message.message)?;
+        } else {
+            draft_server_info.relay_room_message(pool_name, relayed_message.message)?;
+        }
+    }
+
+    Err(AppError::RedisError {
+        msg: "The rooms subscription was closed.".to_string(),
+    })
+}
+
+impl RedisRoomStore {
+    fn publish_to_channel(&self, channel: String, message: &str) {
+        match serde_json::to_string(&RelayedMessage {
+            instance_id: self.instance_id.clone(),
+            message: message.to_string(),
+        }) {
+            Ok(payload) => {
+                let _ = self.tx.send((channel, payload));
+            }
+            Err(e) => error!("Could not serialize the relayed message: {}", e),
+        }
+    }
+}
+
+#[async_trait]
+impl RoomStateStore for RedisRoomStore {
+    fn publish(&self, pool_name: &str, message: &str) {
+        self.publish_to_channel(format!("{}{}", ROOM_CHANNEL_PREFIX, pool_name), message);
+    }
+
+    fn publish_pool_update(&self, pool_name: &str, message: &str) {
+        self.publish_to_channel(format!("{}{}", POOL_CHANNEL_PREFIX, pool_name), message);
+    }
+
+    async fn save_room_users(
+        &self,
+        pool_name: &str,
+        room_users: &HashMap<String, RoomUser>,
+    ) -> Result<()> {
+        let key = format!("{}{}", ROOM_USERS_PREFIX, pool_name);
+        let mut connection = self.get_connection().await?;
+
+        if room_users.is_empty() {
+            return connection
+                .hdel::<_, _, ()>(&key, &self.instance_id)
+                .await
+                .map_err(|e| AppError::RedisError { msg: e.to_string() });
+        }
+
+        let room_users = serde_json::to_string(room_users)
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        connection
+            .hset::<_, _, _, ()>(&key, &self.instance_id, room_users)
+            .await
+            .map_err(|e| AppError::RedisError { msg: e.to_string() })?;
+
+        connection
+            .expire::<_, ()>(&key, ROOM_USERS_TTL_SECONDS)
+            .await
+            .map_err(|e| AppError::RedisError { msg: e.to_string() })
+    }
+
+    async fn list_room_users(&self, pool_name: &str) -> Result<HashMap<String, RoomUser>> {
+        let mut connection = self.get_connection().await?;
+
+        let users_by_instance: HashMap<String, String> = connection
+            .hgetall(format!("{}{}", ROOM_USERS_PREFIX, pool_name))
+            .await
+            .map_err(|e| AppError::RedisError { msg: e.to_string() })?;
+
+        let mut room_users = HashMap::new();
+
+        for instance_room_users in users_by_instance.values() {
+            let instance_room_users: HashMap<String, RoomUser> =
+                serde_json::from_str(instance_room_users)
+                    .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+            room_users.extend(instance_room_users);
+        }
+
+        Ok(room_users)
+    }
+
+    async fn save_pick_timer(&self, pool_name: &str, timer_id: &str) -> Result<()> {
+        let mut connection = self.get_connection().await?;
+
+        connection
+            .set_ex::<_, _, ()>(
+                format!("{}{}", PICK_TIMER_PREFIX, pool_name),
+                timer_id,
+                ROOM_USERS_TTL_SECONDS as u64,
+            )
+            .await
+            .map_err(|e| AppError::RedisError { msg: e.to_string() })
+    }
+
+    async fn claim_pick_timer(&self, pool_name: &str, timer_id: &str) -> Result<bool> {
+        let mut connection = self.get_connection().await?;
+
+        let deleted_keys: u32 = redis::Script::new(CLAIM_PICK_TIMER_SCRIPT)
+            .key(format!("{}{}", PICK_TIMER_PREFIX, pool_name))
+            .arg(timer_id)
+            .invoke_async(&mut connection)
+            .await
+            .map_err(|e| AppError::RedisError { msg: e.to_string() })?;
+
+        Ok(deleted_keys == 1)
+    }
+}
@@ -2,44 +2,201 @@ use std::sync::Arc;
 
 use axum::extract::FromRef;
 
-use crate::{database_connection::DatabaseConnection, jwt::CachedJwks};
+use crate::{
+    database_connection::DatabaseConnection,
+    idempotency::{IdempotencyStoreHandle, MongoIdempotencyStore},
+    jobs::{
+        AccountDeletionsJob, ContractsSyncJob, DailyCumulationJob, JobScheduler, LiveScoringJob,
+        PlayersSyncJob, ProtectionDeadlinesJob, TradeExpirationsJob, TradeReviewsJob,
+        WeeklyAcquisitionsJob,
+    },
+    jwt::CachedJwks,
+    mailer::new_mailer,
+    room_store::RedisRoomStore,
+    settings::Settings,
+    webhooks::WebhookSender,
+};
+use poolnhl_interface::achievements::service::AchievementsServiceHandle;
+use poolnhl_interface::admin::service::AdminServiceHandle;
+use poolnhl_interface::archives::service::ArchivesServiceHandle;
 use poolnhl_interface::daily_leaders::service::DailyLeadersServiceHandle;
+use poolnhl_interface::draft::model::DraftServerInfo;
 use poolnhl_interface::draft::service::DraftServiceHandle;
+use poolnhl_interface::health::service::HealthServiceHandle;
+use poolnhl_interface::invitations::service::InvitationsServiceHandle;
+use poolnhl_interface::jobs::service::JobsServiceHandle;
+use poolnhl_interface::leaderboards::service::LeaderboardsServiceHandle;
+use poolnhl_interface::notifications::service::NotificationsServiceHandle;
 use poolnhl_interface::players::service::PlayersServiceHandle;
 use poolnhl_interface::pool::service::PoolServiceHandle;
+use poolnhl_interface::users::service::UsersServiceHandle;
+use poolnhl_interface::webhooks::service::WebhooksServiceHandle;
 
+pub mod achievements_service;
+pub mod admin_service;
+pub mod archives_service;
 pub mod daily_leaders_service;
 pub mod draft_service;
+pub mod health_service;
+pub mod invitations_service;
+pub mod leaderboards_service;
+pub mod notifications_service;
 pub mod players_service;
 pub mod pool_service;
+pub mod users_service;
+pub mod webhooks_service;
 
+use achievements_service::{AchievementTracker, MongoAchievementsService};
+use admin_service::MongoAdminService;
+use archives_service::MongoArchivesService;
 use daily_leaders_service::MongoDailyLeadersService;
 use draft_service::MongoDraftService;
+use health_service::MongoHealthService;
+use invitations_service::MongoInvitationsService;
+use leaderboards_service::MongoLeaderboardsService;
+use notifications_service::{MongoNotificationsService, Notifier};
 use players_service::MongoPlayersService;
 use pool_service::MongoPoolService;
+use users_service::MongoUsersService;
+use webhooks_service::{LifecycleWebhooks, MongoWebhooksService};
 #[derive(FromRef, Clone)]
 pub struct ServiceRegistry {
     pub pool_service: PoolServiceHandle,
     pub players_service: PlayersServiceHandle,
     pub draft_service: DraftServiceHandle,
     pub daily_leaders_service: DailyLeadersServiceHandle,
+    pub invitations_service: InvitationsServiceHandle,
+    pub notifications_service: NotificationsServiceHandle,
+    pub webhooks_service: WebhooksServiceHandle,
+    pub admin_service: AdminServiceHandle,
+    pub health_service: HealthServiceHandle,
+    pub jobs_service: JobsServiceHandle,
+    pub users_service: UsersServiceHandle,
+    pub achievements_service: AchievementsServiceHandle,
+    pub leaderboards_service: LeaderboardsServiceHandle,
+    pub archives_service: ArchivesServiceHandle,
+
+    // The draft rooms, also used to stream the pool updates.
+    pub draft_server_info: Arc<DraftServerInfo>,
 
     pub cached_keys: Arc<CachedJwks>,
+
+    // The responses of the requests made with an Idempotency-Key header.
+    pub idempotency_store: IdempotencyStoreHandle,
 }
 
 impl ServiceRegistry {
-    pub fn new(db: DatabaseConnection, cached_jwks: Arc<CachedJwks>) -> Self {
-        let pool_service = Arc::new(MongoPoolService::new(db.clone()));
-        let players_service = Arc::new(MongoPlayersService::new(db.clone()));
-        let draft_service = Arc::new(MongoDraftService::new(db.clone(), cached_jwks.clone()));
-        let daily_leaders_service = Arc::new(MongoDailyLeadersService::new(db));
+    pub fn new(db: DatabaseConnection, cached_jwks: Arc<CachedJwks>, settings: &Settings) -> Self {
+        // The draft rooms are shared so that the pool service can also notify the rooms.
+        // With a Redis server, the rooms are also shared with the other backend instances.
+        let draft_server_info = if settings.room_store.redis_url.is_empty() {
+            Arc::new(DraftServerInfo::new())
+        } else {
+            let room_store = Arc::new(
+                RedisRoomStore::spawn(&settings.room_store.redis_url)
+                    .expect("Could not connect to the rooms Redis server"),
+            );
+            let draft_server_info = Arc::new(DraftServerInfo::with_room_store(room_store.clone()));
+            room_store.spawn_subscriber(draft_server_info.clone());
+
+            draft_server_info
+        };
+
+        // The notifications are written to the users feed and emailed to the users that opted in.
+        let notifier = Notifier::new(
+            db.clone(),
+            new_mailer(&settings.mailer),
+            WebhookSender::spawn(),
+            LifecycleWebhooks::new(db.clone()),
+            AchievementTracker::new(db.clone(), draft_server_info.clone()),
+        );
+
+        let pool_service = Arc::new(MongoPoolService::new(
+            db.clone(),
+            &settings.nhl_api,
+            draft_server_info.clone(),
+            notifier.clone(),
+        ));
+        let players_service = Arc::new(MongoPlayersService::new(
+            db.clone(),
+            &settings.nhl_api,
+            &settings.contracts_api,
+            notifier.clone(),
+        ));
+        let users_service = Arc::new(MongoUsersService::new(
+            db.clone(),
+            draft_server_info.clone(),
+        ));
+        let draft_service = Arc::new(MongoDraftService::new(
+            db.clone(),
+            cached_jwks.clone(),
+            draft_server_info.clone(),
+            notifier,
+            users_service.clone(),
+        ));
+        let daily_leaders_service =
+            Arc::new(MongoDailyLeadersService::new(db.clone(), &settings.cache));
+        let invitations_service = Arc::new(MongoInvitationsService::new(db.clone()));
+        let notifications_service = Arc::new(MongoNotificationsService::new(db.clone()));
+        let webhooks_service = Arc::new(MongoWebhooksService::new(db.clone()));
+        let admin_service = Arc::new(MongoAdminService::new(
+            db.clone(),
+            &settings.nhl_api,
+            settings.environment == "development",
+        ));
+
+        // The scheduled work, each run is locked so only one instance runs a job at a time.
+        let jobs_service = Arc::new(JobScheduler::new(
+            db.clone(),
+            vec![
+                Arc::new(PlayersSyncJob::new(
+                    players_service.clone(),
+                    &settings.nhl_api,
+                )),
+                Arc::new(ContractsSyncJob::new(
+                    players_service.clone(),
+                    &settings.contracts_api,
+                )),
+                Arc::new(TradeReviewsJob::new(pool_service.clone())),
+                Arc::new(TradeExpirationsJob::new(pool_service.clone())),
+                Arc::new(ProtectionDeadlinesJob::new(pool_service.clone())),
+                Arc::new(WeeklyAcquisitionsJob::new(pool_service.clone())),
+                Arc::new(DailyCumulationJob::new(
+                    pool_service.clone(),
+                    &settings.nhl_api,
+                )),
+                Arc::new(LiveScoringJob::new(pool_service.clone())),
+                Arc::new(AccountDeletionsJob::new(users_service.clone())),
+            ],
+        ));
+        let health_service = Arc::new(MongoHealthService::new(
+            db.clone(),
+            cached_jwks.clone(),
+            &settings.nhl_api,
+        ));
+        let achievements_service = Arc::new(MongoAchievementsService::new(db.clone()));
+        let leaderboards_service = Arc::new(MongoLeaderboardsService::new(db.clone()));
+        let archives_service = Arc::new(MongoArchivesService::new(db.clone()));
+        let idempotency_store = Arc::new(MongoIdempotencyStore::new(db));
 
         Self {
             pool_service,
             players_service,
             draft_service,
             daily_leaders_service,
+            invitations_service,
+            notifications_service,
+            webhooks_service,
+            admin_service,
+            health_service,
+            jobs_service,
+            users_service,
+            achievements_service,
+            leaderboards_service,
+            archives_service,
+            draft_server_info,
             cached_keys: cached_jwks.clone(),
+            idempotency_store,
         }
     }
 }
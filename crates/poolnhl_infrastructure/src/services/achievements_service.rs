@@ -0,0 +1,144 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, to_bson};
+use mongodb::options::{FindOptions, IndexOptions, UpdateOptions};
+use mongodb::IndexModel;
+use tracing::{warn, Instrument};
+
+use poolnhl_interface::achievements::{model::Achievement, service::AchievementsService};
+use poolnhl_interface::draft::model::DraftServerInfo;
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::pool::model::{Pool, PoolUpdate};
+
+use crate::database_connection::DatabaseConnection;
+
+#[derive(Clone)]
+pub struct MongoAchievementsService {
+    db: DatabaseConnection,
+}
+
+impl MongoAchievementsService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl AchievementsService for MongoAchievementsService {
+    async fn list_achievements(&self, user_id: &str) -> Result<Vec<Achievement>> {
+        self.db
+            .collection::<Achievement>("achievements")
+            .find(
+                doc! {"user_id": user_id},
+                FindOptions::builder()
+                    .sort(doc! {"date_earned": -1})
+                    .build(),
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+}
+
+// Record the achievements earned in a pool and announce the new ones to the pool listeners.
+#[derive(Clone)]
+pub struct AchievementTracker {
+    db: DatabaseConnection,
+    draft_server_info: Arc<DraftServerInfo>,
+}
+
+impl AchievementTracker {
+    pub fn new(db: DatabaseConnection, draft_server_info: Arc<DraftServerInfo>) -> Self {
+        Self {
+            db,
+            draft_server_info,
+        }
+    }
+
+    pub fn track(&self, pool: &Pool) {
+        // The achievements are recorded in the background so the request is not slowed down.
+        let tracker = self.clone();
+        let pool = pool.clone();
+
+        tokio::spawn(
+            async move {
+                if let Err(e) = tracker.record_achievements(&pool).await {
+                    warn!("{}", e);
+                }
+            }
+            .in_current_span(),
+        );
+    }
+
+    async fn record_achievements(&self, pool: &Pool) -> Result<()> {
+        let mut new_achievements = Vec::new();
+
+        for achievement in Achievement::list_earned(pool) {
+            // The achievements already earned are left untouched.
+            let result = self
+                .db
+                .collection::<Achievement>("achievements")
+                .update_one(
+                    doc! {
+                        "user_id": &achievement.user_id,
+                        "achievement_type": to_bson(&achievement.achievement_type)
+                            .map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                        "pool_name": &achievement.pool_name,
+                    },
+                    doc! {"$setOnInsert": {
+                        "season": achievement.season,
+                        "date_earned": achievement.date_earned,
+                    }},
+                    UpdateOptions::builder().upsert(true).build(),
+                )
+                .await
+                .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+            if result.upserted_id.is_some() {
+                new_achievements.push(achievement);
+            }
+        }
+
+        if new_achievements.is_empty() {
+            return Ok(());
+        }
+
+        let message = serde_json::to_string(&PoolUpdate::new_achievements(pool, new_achievements))
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        self.draft_server_info
+            .publish_pool_update(&pool.name, message)
+    }
+}
+
+// Create the indexes used to list the achievements of a user and to earn each achievement once per pool.
+pub async fn create_achievements_indexes(db: &DatabaseConnection) -> Result<()> {
+    let collection = db.collection::<Achievement>("achievements");
+
+    collection
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"user_id": 1, "achievement_type": 1, "pool_name": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    collection
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"user_id": 1, "date_earned": -1})
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
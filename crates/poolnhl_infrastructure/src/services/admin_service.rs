@@ -0,0 +1,324 @@
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, Utc};
+use mongodb::bson::{doc, to_bson};
+
+use poolnhl_interface::admin::{
+    model::{
+        AdminDeletePoolRequest, AdminDraftRecordingRequest, AdminRecordDraftRequest,
+        AdminRecumulatePointsRequest, AdminRenamePoolRequest, AdminSetVirtualTodayRequest,
+        AdminTransferOwnershipRequest, AdminUpdateContextRequest, PoolsMigrationReport,
+    },
+    service::AdminService,
+};
+use poolnhl_interface::draft::model::DraftRecording;
+use poolnhl_interface::errors::{AppError, ErrorCode, Result};
+use poolnhl_interface::pool::authorization::authorize_site_admin;
+use poolnhl_interface::pool::model::{Pool, PoolEventType, PoolState};
+use poolnhl_interface::time_provider::{time_provider, VirtualToday};
+
+use crate::database_connection::DatabaseConnection;
+use crate::migrations::migrate_pools;
+use crate::nhl_api::NhlApiClient;
+use crate::services::pool_service::{
+    delete_pool_by_name, get_short_pool_by_name, insert_pool_event, rename_pool,
+    transfer_pool_ownership, update_pool, update_pool_with_history,
+};
+use crate::settings::NhlApi;
+
+#[derive(Clone)]
+pub struct MongoAdminService {
+    db: DatabaseConnection,
+    nhl_api: NhlApiClient,
+
+    // The virtual today can only be set in a development environment.
+    is_development: bool,
+}
+
+impl MongoAdminService {
+    pub fn new(db: DatabaseConnection, nhl_api: &NhlApi, is_development: bool) -> Self {
+        Self {
+            db,
+            nhl_api: NhlApiClient::new(nhl_api),
+            is_development,
+        }
+    }
+
+    async fn get_pool_by_name(&self, pool_name: &str) -> Result<Pool> {
+        // The complete pool, including the score_by_day member.
+        self.db
+            .collection::<Pool>("pools")
+            .find_one(doc! {"name": pool_name}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .ok_or(AppError::CodedError {
+                code: ErrorCode::PoolNotFound,
+                msg: format!("no pool found with name '{}'", pool_name),
+            })
+    }
+}
+
+#[async_trait]
+impl AdminService for MongoAdminService {
+    async fn delete_pool(&self, user_id: &str, req: AdminDeletePoolRequest) -> Result<Pool> {
+        authorize_site_admin(user_id)?;
+
+        let pool =
+            get_short_pool_by_name(&self.db.collection::<Pool>("pools"), &req.pool_name).await?;
+
+        delete_pool_by_name(&self.db, &pool.name).await?;
+
+        insert_pool_event(
+            &self.db,
+            &pool.name,
+            user_id,
+            PoolEventType::DeletePool,
+            Vec::new(),
+        )
+        .await?;
+
+        Ok(pool)
+    }
+
+    async fn transfer_ownership(
+        &self,
+        user_id: &str,
+        req: AdminTransferOwnershipRequest,
+    ) -> Result<Pool> {
+        authorize_site_admin(user_id)?;
+
+        transfer_pool_ownership(&self.db, user_id, &req.pool_name, &req.new_owner_id).await
+    }
+
+    async fn rename_pool(&self, user_id: &str, req: AdminRenamePoolRequest) -> Result<Pool> {
+        authorize_site_admin(user_id)?;
+
+        let pool =
+            get_short_pool_by_name(&self.db.collection::<Pool>("pools"), &req.pool_name).await?;
+
+        rename_pool(&self.db, pool, &req.new_pool_name, user_id).await
+    }
+
+    async fn update_context(&self, user_id: &str, req: AdminUpdateContextRequest) -> Result<Pool> {
+        authorize_site_admin(user_id)?;
+
+        let pool = self.get_pool_by_name(&req.pool_name).await?;
+
+        // The daily scores are usually not part of the fix, keep the recorded ones in that case.
+        let mut context = req.context;
+        if context.score_by_day.is_none() {
+            context.score_by_day = pool
+                .context
+                .as_ref()
+                .and_then(|context| context.score_by_day.clone());
+        }
+
+        // The cumulative totals follow the daily scores of the fixed context.
+        context.rebuild_cumulative_totals(&pool.settings);
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context": to_bson(&context).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        update_pool_with_history(
+            &self.db,
+            updated_fields,
+            pool,
+            user_id,
+            PoolEventType::UpdateContext,
+        )
+        .await
+    }
+
+    async fn recumulate_points(
+        &self,
+        user_id: &str,
+        req: AdminRecumulatePointsRequest,
+    ) -> Result<Pool> {
+        authorize_site_admin(user_id)?;
+
+        let start_date = NaiveDate::parse_from_str(&req.start_date, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+        let end_date = NaiveDate::parse_from_str(&req.end_date, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        if end_date < start_date {
+            return Err(AppError::CustomError {
+                msg: "The end date needs to be after the start date.".to_string(),
+            });
+        }
+
+        let mut pool = self.get_pool_by_name(&req.pool_name).await?;
+        let context = pool.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let mut updated_fields = doc! {};
+        let mut date = start_date;
+
+        while date <= end_date {
+            let date_string = date.to_string();
+            date += Duration::days(1);
+
+            // The dates with games not completed yet are left untouched.
+            let Some(daily_players_points) =
+                self.nhl_api.get_daily_players_points(&date_string).await?
+            else {
+                continue;
+            };
+
+            context.cumulate_daily_roster_points(
+                &date_string,
+                &daily_players_points,
+                &pool.settings,
+            );
+
+            if let Some(daily_roster_points) = context
+                .score_by_day
+                .as_ref()
+                .and_then(|score_by_day| score_by_day.get(&date_string))
+            {
+                updated_fields.insert(
+                    format!("context.score_by_day.{}", date_string),
+                    to_bson(daily_roster_points)
+                        .map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                );
+            }
+        }
+
+        if updated_fields.is_empty() {
+            return Err(AppError::CustomError {
+                msg: "No completed games in this date range.".to_string(),
+            });
+        }
+
+        if context.are_totals_missing() {
+            context.rebuild_cumulative_totals(&pool.settings);
+        }
+
+        updated_fields.insert(
+            "context.cumulative_totals",
+            to_bson(&context.cumulative_totals)
+                .map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+        );
+        updated_fields.insert(
+            "context.players_totals",
+            to_bson(&context.players_totals)
+                .map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+        );
+
+        let updated_pool = update_pool(
+            doc! {"$set": updated_fields},
+            &self.db.collection::<Pool>("pools"),
+            &pool.name,
+        )
+        .await?;
+
+        insert_pool_event(
+            &self.db,
+            &pool.name,
+            user_id,
+            PoolEventType::RecumulatePoints,
+            Vec::new(),
+        )
+        .await?;
+
+        Ok(updated_pool)
+    }
+
+    async fn migrate_pools(&self, user_id: &str) -> Result<PoolsMigrationReport> {
+        authorize_site_admin(user_id)?;
+
+        migrate_pools(&self.db).await
+    }
+
+    async fn set_virtual_today(
+        &self,
+        user_id: &str,
+        req: AdminSetVirtualTodayRequest,
+    ) -> Result<VirtualToday> {
+        authorize_site_admin(user_id)?;
+
+        if !self.is_development {
+            return Err(AppError::CodedError {
+                code: ErrorCode::Forbidden,
+                msg: "The virtual today can only be set in a development environment.".to_string(),
+            });
+        }
+
+        let today = req
+            .today
+            .map(|today| NaiveDate::parse_from_str(&today, "%Y-%m-%d"))
+            .transpose()
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        time_provider().set_virtual_today(today);
+
+        Ok(time_provider().get_virtual_today())
+    }
+
+    async fn record_draft(
+        &self,
+        user_id: &str,
+        req: AdminRecordDraftRequest,
+    ) -> Result<Option<DraftRecording>> {
+        authorize_site_admin(user_id)?;
+
+        let collection = self.db.collection::<DraftRecording>("draft_recordings");
+
+        collection
+            .delete_one(doc! {"pool_name": &req.pool_name}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        if !req.is_recording {
+            return Ok(None);
+        }
+
+        let pool =
+            get_short_pool_by_name(&self.db.collection::<Pool>("pools"), &req.pool_name).await?;
+
+        // A draft in progress is recorded from its current state,
+        // otherwise the pool is stored once the draft is started.
+        let recording = DraftRecording {
+            pool_name: pool.name.clone(),
+            pool: match pool.status {
+                PoolState::Created => None,
+                PoolState::Draft => Some(pool),
+                _ => {
+                    return Err(AppError::CodedError {
+                        code: ErrorCode::InvalidPoolStatus,
+                        msg: "Only the pools that are not drafted yet can be recorded.".to_string(),
+                    })
+                }
+            },
+            steps: Vec::new(),
+            date_created: Utc::now().timestamp_millis(),
+        };
+
+        collection
+            .insert_one(&recording, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(Some(recording))
+    }
+
+    async fn get_draft_recording(
+        &self,
+        user_id: &str,
+        req: AdminDraftRecordingRequest,
+    ) -> Result<DraftRecording> {
+        authorize_site_admin(user_id)?;
+
+        self.db
+            .collection::<DraftRecording>("draft_recordings")
+            .find_one(doc! {"pool_name": &req.pool_name}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .ok_or_else(|| AppError::CustomError {
+                msg: format!("The draft of the pool '{}' is not recorded.", req.pool_name),
+            })
+    }
+}
@@ -0,0 +1,70 @@
+use async_trait::async_trait;
+use mongodb::bson::doc;
+use mongodb::options::IndexOptions;
+use mongodb::IndexModel;
+
+use poolnhl_interface::archives::{model::SeasonArchive, service::ArchivesService};
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::pool::model::Pool;
+
+use crate::database_connection::{is_duplicate_key_error, DatabaseConnection};
+
+#[derive(Clone)]
+pub struct MongoArchivesService {
+    db: DatabaseConnection,
+}
+
+impl MongoArchivesService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl ArchivesService for MongoArchivesService {
+    async fn get_archive(&self, season: u32, pool_name: &str) -> Result<SeasonArchive> {
+        self.db
+            .collection::<SeasonArchive>("season_archives")
+            .find_one(doc! {"season": season, "pool_name": pool_name}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .ok_or_else(|| AppError::CustomError {
+                msg: format!(
+                    "no archive found for the pool '{}' in the season {}",
+                    pool_name, season
+                ),
+            })
+    }
+}
+
+// Archive a pool that was just marked as final.
+// The first archive of a pool season is kept, it is never overwritten.
+pub async fn create_season_archive(db: &DatabaseConnection, pool: &Pool) -> Result<()> {
+    let archive = SeasonArchive::new(pool)?;
+
+    match db
+        .collection::<SeasonArchive>("season_archives")
+        .insert_one(&archive, None)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) if is_duplicate_key_error(&e) => Ok(()),
+        Err(e) => Err(AppError::MongoError { msg: e.to_string() }),
+    }
+}
+
+// Create the index used to find the archive of a pool season.
+pub async fn create_archives_indexes(db: &DatabaseConnection) -> Result<()> {
+    db.collection::<SeasonArchive>("season_archives")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"season": 1, "pool_name": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
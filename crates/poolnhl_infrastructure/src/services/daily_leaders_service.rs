@@ -1,51 +1,184 @@
+use std::collections::HashMap;
+use std::time::Duration as CacheDuration;
+
 use async_trait::async_trait;
 
-use chrono::{Duration, Local, Timelike};
+use chrono::{Duration, NaiveDate, Timelike};
+use futures::TryStreamExt;
+use moka::future::Cache;
 use mongodb::bson::doc;
+use mongodb::options::{FindOptions, ReplaceOptions};
 use poolnhl_interface::errors::AppError;
 
+use poolnhl_interface::daily_leaders::model::{
+    DailyLeadersRangeQuery, MAX_DAILY_LEADERS_RANGE_DAYS,
+};
 use poolnhl_interface::daily_leaders::{model::DailyLeaders, service::DailyLeadersService};
 use poolnhl_interface::errors::Result;
+use poolnhl_interface::players::model::PlayerInfo;
+use poolnhl_interface::pool::model::DailyPlayersPoints;
+use poolnhl_interface::time_provider::time_provider;
 
 use crate::database_connection::DatabaseConnection;
+use crate::settings::CacheSettings;
+
+// Number of dates kept in memory, a bit more than a season.
+const DAILY_LEADERS_CACHE_CAPACITY: u64 = 256;
 
 #[derive(Clone)]
 pub struct MongoDailyLeadersService {
     db: DatabaseConnection,
+
+    // The leaders of a date only change when the date is cumulated again.
+    cache: Option<Cache<String, DailyLeaders>>,
 }
 
 impl MongoDailyLeadersService {
-    pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+    pub fn new(db: DatabaseConnection, settings: &CacheSettings) -> Self {
+        let cache = settings.enabled.then(|| {
+            Cache::builder()
+                .max_capacity(DAILY_LEADERS_CACHE_CAPACITY)
+                .time_to_live(CacheDuration::from_secs(settings.ttl_seconds))
+                .build()
+        });
+
+        Self { db, cache }
     }
+
+    async fn cache_daily_leaders(&self, daily_leaders: &DailyLeaders) {
+        if let Some(cache) = &self.cache {
+            cache
+                .insert(daily_leaders.date.clone(), daily_leaders.clone())
+                .await;
+        }
+    }
+}
+
+// The date of last night's games, before 12PM the games of yesterday are the last ones.
+fn get_last_night_date() -> String {
+    let now = time_provider().now();
+    let mut today = now.date_naive();
+
+    if now.hour() < 12 {
+        today -= Duration::days(1);
+    }
+    today.format("%Y-%m-%d").to_string()
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| AppError::CustomError {
+        msg: format!("The date '{}' is not in the YYYY-MM-DD format.", date),
+    })
+}
+
+// Save the leaders of a date once its games are completed, called by the daily cumulation.
+pub async fn save_daily_leaders(
+    db: &DatabaseConnection,
+    date: &str,
+    daily_players_points: &DailyPlayersPoints,
+) -> Result<()> {
+    let player_ids: Vec<u32> = daily_players_points
+        .skaters
+        .keys()
+        .chain(daily_players_points.goalies.keys())
+        .filter_map(|player_id| player_id.parse().ok())
+        .collect();
+
+    let players: HashMap<u32, (String, u32)> = db
+        .collection::<PlayerInfo>("players")
+        .find(doc! {"id": {"$in": player_ids}}, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+        .try_collect::<Vec<PlayerInfo>>()
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+        .into_iter()
+        .filter_map(|player| Some((player.id, (player.name, player.team?))))
+        .collect();
+
+    db.collection::<DailyLeaders>("day_leaders")
+        .replace_one(
+            doc! {"date": date},
+            DailyLeaders::new(date, daily_players_points, &players),
+            ReplaceOptions::builder().upsert(true).build(),
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
 }
+
 #[async_trait]
 impl DailyLeadersService for MongoDailyLeadersService {
     async fn get_daily_leaders(&self, date: &str) -> Result<DailyLeaders> {
         let collection = self.db.collection::<DailyLeaders>("day_leaders");
 
-        let mut formatted_date = date.to_string();
+        let formatted_date = if date == "now" {
+            get_last_night_date()
+        } else {
+            date.to_string()
+        };
 
-        if date == "now" {
-            let mut today = Local::now().date_naive();
+        if let Some(daily_leaders) = match &self.cache {
+            Some(cache) => cache.get(&formatted_date).await,
+            None => None,
+        } {
+            return Ok(daily_leaders);
+        }
 
-            let time = Local::now().time();
+        let daily_leaders = collection
+            .find_one(doc! {"date": &formatted_date}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .ok_or_else(move || AppError::CustomError {
+                msg: format!("no daily leaders found for the date: {}", date),
+            })?;
+
+        self.cache_daily_leaders(&daily_leaders).await;
+        Ok(daily_leaders)
+    }
 
-            // Before 12PM fetch games of yesterday.
+    async fn get_daily_leaders_range(
+        &self,
+        query: DailyLeadersRangeQuery,
+    ) -> Result<Vec<DailyLeaders>> {
+        let from = parse_date(&query.from)?;
+        let to = parse_date(&query.to)?;
 
-            if time.hour() < 12 {
-                today -= Duration::days(1);
-            }
-            formatted_date = today.format("%Y-%m-%d").to_string();
+        if to < from || (to - from).num_days() >= MAX_DAILY_LEADERS_RANGE_DAYS {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "The range of dates should be between 1 and {} days.",
+                    MAX_DAILY_LEADERS_RANGE_DAYS
+                ),
+            });
         }
 
-        let daily_leaders = collection
-            .find_one(doc! {"date": &formatted_date}, None)
+        let find_options = FindOptions::builder().sort(doc! {"date": 1}).build();
+
+        let daily_leaders: Vec<DailyLeaders> = self
+            .db
+            .collection::<DailyLeaders>("day_leaders")
+            .find(
+                doc! {"date": {"$gte": &query.from, "$lte": &query.to}},
+                find_options,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
             .await
             .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
-        daily_leaders.ok_or_else(move || AppError::CustomError {
-            msg: format!("no daily leaders found for the date: {}", date),
-        })
+        for leaders in &daily_leaders {
+            self.cache_daily_leaders(leaders).await;
+        }
+
+        Ok(daily_leaders)
+    }
+
+    async fn get_top_performers(&self) -> Result<DailyLeaders> {
+        self.get_daily_leaders("now")
+            .await
+            .map(|daily_leaders| daily_leaders.get_top_performers())
     }
 }
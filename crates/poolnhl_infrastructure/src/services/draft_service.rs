@@ -1,32 +1,129 @@
 use async_trait::async_trait;
-use mongodb::bson::doc;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
 use mongodb::bson::to_bson;
+use mongodb::bson::{doc, Document};
+use mongodb::options::{CreateCollectionOptions, FindOneOptions, FindOptions};
+use mongodb::{Collection, IndexModel};
 use poolnhl_interface::draft::service::DraftService;
-use poolnhl_interface::errors::AppError;
+use poolnhl_interface::errors::{AppError, ErrorCode};
+use poolnhl_interface::players::model::PlayerInfo;
 use poolnhl_interface::users::model::UserEmailJwtPayload;
+use poolnhl_interface::users::service::UsersServiceHandle;
+use poolnhl_interface::webhooks::model::PoolLifecycleEvent;
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tracing::{warn, Instrument};
+use uuid::Uuid;
 
-use poolnhl_interface::draft::model::{CommandResponse, DraftServerInfo, RoomUser};
+use poolnhl_interface::draft::model::{
+    ChatHistoryQuery, ChatMessage, CommandResponse, DraftRecordedCommand, DraftRecordedStep,
+    DraftRecording, DraftServerInfo, DraftSuggestions, RoomSender, RoomUser,
+    CHAT_MESSAGE_MAX_LENGTH,
+};
 use poolnhl_interface::errors::Result;
-use poolnhl_interface::pool::model::{Pool, PoolPlayerInfo, PoolSettings};
+use poolnhl_interface::notifications::model::NotificationType;
+use poolnhl_interface::pool::authorization::{has_permission, Permission};
+use poolnhl_interface::pool::model::{
+    DraftOrderType, DraftQueue, DraftType, Pool, PoolEventType, PoolPlayerInfo, PoolSettings,
+    PoolState, Position, TradeDraftPicksRequest, SERVER_ACTOR,
+};
 
+use crate::cache::pool_cache;
 use crate::database_connection::DatabaseConnection;
-use crate::jwt::{hanko_token_decode, CachedJwks};
+use crate::jwt::{validate_user_token, CachedJwks};
 
-use crate::services::pool_service::{get_short_pool_by_name, update_pool};
+use crate::services::invitations_service::is_user_invited;
+use crate::services::notifications_service::Notifier;
+use crate::services::players_service::get_pool_player_info;
+use crate::services::pool_service::{
+    get_cached_short_pool_by_name, get_short_pool_by_name, update_pool_with_history,
+    update_pool_with_history_if,
+};
+
+// The chat history is capped to the most recent messages of all pools.
+const CHAT_COLLECTION_MAX_BYTES: u64 = 10_000_000;
+const CHAT_COLLECTION_MAX_MESSAGES: u64 = 50_000;
 
 pub struct MongoDraftService {
     db: DatabaseConnection,
 
-    draft_server_info: DraftServerInfo,
+    draft_server_info: Arc<DraftServerInfo>,
     cached_jwks: Arc<CachedJwks>,
+    pick_timers: Arc<PickTimers>,
+    notifier: Notifier,
+
+    // Used to reject the revoked tokens when a socket authenticates.
+    users_service: UsersServiceHandle,
+}
+
+// Message posted to the pool webhook when a player is drafted.
+fn get_draft_pick_message(pool: &Pool, player_id: u32) -> Option<String> {
+    let context = pool.context.as_ref()?;
+
+    let overall = context
+        .players_name_drafted
+        .iter()
+        .position(|id| *id == player_id)?
+        + 1;
+    let draft_pick = context
+        .draft_picks
+        .as_ref()?
+        .iter()
+        .find(|pick| pick.player_id == player_id)?;
+    let player = context.players.get(&player_id.to_string())?;
+
+    Some(format!(
+        "Pick {}: {} drafted {}.",
+        overall,
+        pool.get_participant_name(&draft_pick.drafter),
+        player.name
+    ))
+}
+
+// Fire the DraftCompleted lifecycle event once the last pick moved the pool in progress.
+fn fire_draft_completed(notifier: &Notifier, pool: &Pool) {
+    if !matches!(pool.status, PoolState::InProgress) {
+        return;
+    }
+
+    let draft_picks = pool
+        .context
+        .as_ref()
+        .and_then(|context| context.draft_picks.as_ref());
+
+    notifier.fire_pool_event(
+        pool,
+        PoolLifecycleEvent::DraftCompleted,
+        serde_json::json!({ "draft_picks": draft_picks }),
+    );
 }
 
 // Send the pool updated informations to the room.
-pub fn send_pool_info(tx: broadcast::Sender<String>, pool: Pool) -> Result<()> {
+// The auction commands are validated against the nomination that was read,
+// the pool is only updated if no other bid, nomination or close was made in between.
+fn get_auction_condition(pool: &Pool) -> Document {
+    match pool
+        .context
+        .as_ref()
+        .and_then(|context| context.auction.as_ref())
+        .and_then(|auction| auction.nomination.as_ref())
+    {
+        Some(nomination) => doc! {
+            "context.auction.nomination.player.id": nomination.player.id,
+            "context.auction.nomination.highest_bid.bidder": &nomination.highest_bid.bidder,
+            "context.auction.nomination.highest_bid.amount": nomination.highest_bid.amount,
+        },
+        None => doc! {"context.auction.nomination": null},
+    }
+}
+
+pub fn send_pool_info(tx: RoomSender, pool: Pool) -> Result<()> {
     let pool_string = serde_json::to_string(&CommandResponse::Pool { pool })
         .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
@@ -35,10 +132,7 @@ pub fn send_pool_info(tx: broadcast::Sender<String>, pool: Pool) -> Result<()> {
 }
 
 // Send the pool updated informations to the room.
-pub fn send_users_info(
-    tx: broadcast::Sender<String>,
-    room_users: HashMap<String, RoomUser>,
-) -> Result<()> {
+pub fn send_users_info(tx: RoomSender, room_users: HashMap<String, RoomUser>) -> Result<()> {
     let room_users = serde_json::to_string(&CommandResponse::Users { room_users })
         .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
@@ -46,14 +140,652 @@ pub fn send_users_info(
     Ok(())
 }
 
-impl MongoDraftService {
-    pub fn new(db: DatabaseConnection, cached_jwks: Arc<CachedJwks>) -> Self {
+// Create the capped collection keeping the most recent chat messages of every pool.
+pub async fn create_chat_collection(db: &DatabaseConnection) -> Result<()> {
+    let collection_names = db
+        .list_collection_names(doc! {"name": "chat_messages"})
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    if collection_names.is_empty() {
+        let options = CreateCollectionOptions::builder()
+            .capped(true)
+            .size(CHAT_COLLECTION_MAX_BYTES)
+            .max(CHAT_COLLECTION_MAX_MESSAGES)
+            .build();
+
+        db.create_collection("chat_messages", options)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    }
+
+    db.collection::<ChatMessage>("chat_messages")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"pool_name": 1, "date_created": -1})
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
+
+// Send the remaining time of the current pick to the room.
+pub fn send_pick_timer(tx: RoomSender, pick_number: usize, remaining_seconds: u32) -> Result<()> {
+    let pick_timer = serde_json::to_string(&CommandResponse::PickTimer {
+        pick_number,
+        remaining_seconds,
+    })
+    .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    let _ = tx.send(pick_timer);
+    Ok(())
+}
+
+// The draft queues are stored in the pool document but never deserialized into the Pool,
+// so they are not broadcasted to the room.
+#[derive(Deserialize)]
+struct PoolDraftQueues {
+    draft_queues: Option<HashMap<String, DraftQueue>>,
+}
+
+pub async fn get_draft_queues(
+    collection: &Collection<Pool>,
+    pool_name: &str,
+) -> Result<HashMap<String, DraftQueue>> {
+    let find_option = FindOneOptions::builder()
+        .projection(doc! {"draft_queues": 1})
+        .build();
+
+    let pool_draft_queues = collection
+        .clone_with_type::<PoolDraftQueues>()
+        .find_one(doc! {"name": pool_name}, find_option)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+        .ok_or(AppError::CodedError {
+            code: ErrorCode::PoolNotFound,
+            msg: format!("no pool found with name '{}'", pool_name),
+        })?;
+
+    Ok(pool_draft_queues.draft_queues.unwrap_or_default())
+}
+
+pub async fn update_draft_queue(
+    collection: &Collection<Pool>,
+    pool_name: &str,
+    user_id: &str,
+    draft_queue: &DraftQueue,
+) -> Result<()> {
+    collection
+        .update_one(
+            doc! {"name": pool_name},
+            doc! {
+                "$set": doc!{
+                    format!("draft_queues.{}", user_id): to_bson(draft_queue).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+                }
+            },
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    pool_cache().invalidate(pool_name).await;
+
+    Ok(())
+}
+
+// Record the draft commands of the pools for which an admin enabled the recording.
+// The recording is best effort, it never prevents a command from being made.
+pub struct DraftRecorder {
+    db: DatabaseConnection,
+    pool_name: String,
+    steps: Vec<DraftRecordedStep>,
+}
+
+impl DraftRecorder {
+    pub fn new(db: &DatabaseConnection, pool_name: &str) -> Self {
+        Self {
+            db: db.clone(),
+            pool_name: pool_name.to_string(),
+            steps: Vec::new(),
+        }
+    }
+
+    // Apply the command to the pool. A rejected command is saved right away.
+    pub async fn apply(&mut self, pool: &mut Pool, command: DraftRecordedCommand) -> Result<()> {
+        let (step, result) = DraftRecordedStep::apply(pool, command);
+        self.steps.push(step);
+
+        if result.is_err() {
+            self.save().await;
+        }
+
+        result
+    }
+
+    // Save the steps once the pool is updated, only if the draft of the pool is recorded.
+    pub async fn save(&mut self) {
+        if self.steps.is_empty() {
+            return;
+        }
+
+        let steps = match to_bson(&std::mem::take(&mut self.steps)) {
+            Ok(steps) => steps,
+            Err(e) => {
+                warn!("Could not record the draft of {}: {}", self.pool_name, e);
+                return;
+            }
+        };
+
+        if let Err(e) = self
+            .db
+            .collection::<DraftRecording>("draft_recordings")
+            .update_one(
+                doc! {"pool_name": &self.pool_name, "pool": {"$ne": null}},
+                doc! {"$push": {"steps": {"$each": steps}}},
+                None,
+            )
+            .await
+        {
+            warn!("Could not record the draft of {}: {}", self.pool_name, e);
+        }
+    }
+}
+
+// Store the pool at the start of its draft when an admin enabled the recording before the draft.
+pub async fn start_draft_recording(db: &DatabaseConnection, pool: &Pool) {
+    let pool_bson = match to_bson(pool) {
+        Ok(pool_bson) => pool_bson,
+        Err(e) => {
+            warn!("Could not record the draft of {}: {}", pool.name, e);
+            return;
+        }
+    };
+
+    if let Err(e) = db
+        .collection::<DraftRecording>("draft_recordings")
+        .update_one(
+            doc! {"pool_name": &pool.name, "pool": null},
+            doc! {"$set": {"pool": pool_bson}},
+            None,
+        )
+        .await
+    {
+        warn!("Could not record the draft of {}: {}", pool.name, e);
+    }
+}
+
+// Remove a queued player that cannot be drafted by the pooler, so it is not tried again.
+async fn drop_queued_player(
+    collection: &Collection<Pool>,
+    pool_name: &str,
+    user_id: &str,
+    draft_queue: &mut DraftQueue,
+    player_id: u32,
+) -> Result<()> {
+    draft_queue.players.retain(|player| player.id != player_id);
+    update_draft_queue(collection, pool_name, user_id, draft_queue).await
+}
+
+// Draft the queued players of the poolers that enabled the auto draft, as long as it is their turn.
+// The auto draft never fails the pick that triggered it. A queued player that cannot be drafted
+// is dropped from the queue and the pooler falls back to the pick timer.
+pub async fn draft_queued_players(
+    collection: &Collection<Pool>,
+    pool: &mut Pool,
+    recorder: &mut DraftRecorder,
+) {
+    if let Err(e) = try_draft_queued_players(collection, pool, recorder).await {
+        warn!(
+            "Could not auto draft the queued players of {}: {}",
+            pool.name, e
+        );
+    }
+}
+
+async fn try_draft_queued_players(
+    collection: &Collection<Pool>,
+    pool: &mut Pool,
+    recorder: &mut DraftRecorder,
+) -> Result<()> {
+    if matches!(pool.settings.draft_type, DraftType::Auction) {
+        return Ok(());
+    }
+
+    let mut draft_queues = get_draft_queues(collection, &pool.name).await?;
+
+    while matches!(pool.status, PoolState::Draft) {
+        let next_drafter = pool.get_next_drafter()?;
+
+        let Some(draft_queue) = draft_queues.get_mut(&next_drafter) else {
+            break;
+        };
+
+        if !draft_queue.auto_draft {
+            break;
+        }
+
+        let Some(player) = pool
+            .context
+            .as_ref()
+            .and_then(|context| context.find_first_available_player(&draft_queue.players))
+        else {
+            break;
+        };
+        let player_id = player.id;
+
+        if let Err(e) = recorder
+            .apply(pool, DraftRecordedCommand::AutoDraftPlayer { player })
+            .await
+        {
+            warn!(
+                "The queued player {} could not be drafted: {}",
+                player_id, e
+            );
+            drop_queued_player(
+                collection,
+                &pool.name,
+                &next_drafter,
+                draft_queue,
+                player_id,
+            )
+            .await?;
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+// Find the best available player (most points) that the next drafter can add to its roster.
+pub async fn find_best_available_player(
+    db: &DatabaseConnection,
+    pool: &mut Pool,
+) -> Result<Option<PoolPlayerInfo>> {
+    let next_drafter = pool.get_next_drafter()?;
+
+    let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+        msg: "pool context does not exist.".to_string(),
+    })?;
+
+    let roster = context
+        .pooler_roster
+        .get(&next_drafter)
+        .ok_or_else(|| AppError::CustomError {
+            msg: format!("Roster for user {} does not exist.", next_drafter),
+        })?;
+
+    // Prefer the positions that still have a starting spot available.
+    let mut positions = Vec::new();
+    if (roster.chosen_forwards.len() as u8) < pool.settings.number_forwards {
+        positions.push(Position::F.as_str());
+    }
+    if (roster.chosen_defenders.len() as u8) < pool.settings.number_defenders {
+        positions.push(Position::D.as_str());
+    }
+    if (roster.chosen_goalies.len() as u8) < pool.settings.number_goalies {
+        positions.push(Position::G.as_str());
+    }
+    // Any skater fills an open utility spot.
+    if (roster.get_utility().len() as u8) < pool.settings.get_number_utility() {
+        for position in [Position::F.as_str(), Position::D.as_str()] {
+            if !positions.contains(&position) {
+                positions.push(position);
+            }
+        }
+    }
+    if positions.is_empty() {
+        positions = vec![
+            Position::F.as_str(),
+            Position::D.as_str(),
+            Position::G.as_str(),
+        ];
+    }
+
+    let picked_players: Vec<u32> = context
+        .pooler_roster
+        .values()
+        .flat_map(|roster| roster.get_players().cloned())
+        .collect();
+
+    let find_options = FindOneOptions::builder()
+        .sort(doc! {"points": -1, "_id": 1})
+        .build();
+
+    let player = db
+        .collection::<PlayerInfo>("players")
+        .find_one(
+            doc! {
+                "active": true,
+                "id": {"$nin": picked_players},
+                "position": {"$in": positions}
+            },
+            find_options,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    player.map(PoolPlayerInfo::try_from).transpose()
+}
+
+// Suggest the best available players of each position with their current and last season stats.
+pub async fn get_draft_suggestions(
+    db: &DatabaseConnection,
+    pool: &Pool,
+) -> Result<DraftSuggestions> {
+    let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+        msg: "pool context does not exist.".to_string(),
+    })?;
+
+    let players: Vec<PlayerInfo> = db
+        .collection::<PlayerInfo>("players")
+        .find(doc! {"active": true}, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+        .try_collect()
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(DraftSuggestions::new(players, context, &pool.settings))
+}
+
+// Run the pick timer of the live drafts.
+// When the timer of a pick expires, the best available player is drafted or the pick is skipped.
+pub struct PickTimers {
+    db: DatabaseConnection,
+    draft_server_info: Arc<DraftServerInfo>,
+
+    // Map a pool name to its running pick timer.
+    timers: Mutex<HashMap<String, JoinHandle<()>>>,
+
+    notifier: Notifier,
+}
+
+impl PickTimers {
+    pub fn new(
+        db: DatabaseConnection,
+        draft_server_info: Arc<DraftServerInfo>,
+        notifier: Notifier,
+    ) -> Self {
         Self {
             db,
+            draft_server_info,
+            timers: Mutex::new(HashMap::new()),
+            notifier,
+        }
+    }
+
+    pub fn stop(&self, pool_name: &str) {
+        if let Ok(mut timers) = self.timers.lock() {
+            if let Some(timer) = timers.remove(pool_name) {
+                timer.abort();
+            }
+        }
+    }
+
+    // Restart the timer of the pool. This needs to be called after each pick.
+    pub fn restart(self: &Arc<Self>, pool: &Pool) {
+        self.stop(&pool.name);
+
+        // Let the next drafter know that its pick is up, the best available players are suggested to it.
+        if matches!(pool.status, PoolState::Draft) && pool.is_draft_paused != Some(true) {
+            let notifier = self.notifier.clone();
+            let pick_timers = self.clone();
+            let mut pool = pool.clone();
+
+            tokio::spawn(
+                async move {
+                    let Ok(next_drafter) = pool.get_next_drafter() else {
+                        return;
+                    };
+
+                    if let Err(e) = notifier
+                        .notify_users(
+                            std::slice::from_ref(&next_drafter),
+                            &pool.name,
+                            NotificationType::DraftPickUp,
+                        )
+                        .await
+                    {
+                        warn!("{}", e);
+                    }
+
+                    if let Err(e) = pick_timers
+                        .send_draft_suggestions(&pool, next_drafter)
+                        .await
+                    {
+                        warn!("{}", e);
+                    }
+                }
+                .in_current_span(),
+            );
+        }
+
+        let (Some(draft_timer_settings), Some(context)) =
+            (&pool.settings.draft_timer_settings, &pool.context)
+        else {
+            return;
+        };
+
+        // No timer is running while the draft is paused.
+        if !matches!(pool.status, PoolState::Draft) || pool.is_draft_paused == Some(true) {
+            return;
+        }
+
+        let pick_timers = self.clone();
+        let pool_name = pool.name.clone();
+        let pick_number = context.players_name_drafted.len();
+        let pick_time_limit = draft_timer_settings.pick_time_limit;
+        let auto_pick = draft_timer_settings.auto_pick;
+        let timer_id = Uuid::new_v4().simple().to_string();
+
+        let timer = tokio::spawn(async move {
+            // With several instances, the timers started on the other instances become stale.
+            if let Some(room_store) = pick_timers.draft_server_info.get_room_store() {
+                if let Err(e) = room_store.save_pick_timer(&pool_name, &timer_id).await {
+                    warn!("{}", e);
+                }
+            }
+
+            for remaining_seconds in (1..=pick_time_limit).rev() {
+                if let Ok(tx) = pick_timers.draft_server_info.get_room_tx(&pool_name) {
+                    let _ = send_pick_timer(tx, pick_number, remaining_seconds);
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+
+            // The pick is not made once the server is shutting down.
+            let Some(_pending_write) = pick_timers.draft_server_info.begin_write().await else {
+                return;
+            };
+
+            if let Some(room_store) = pick_timers.draft_server_info.get_room_store() {
+                match room_store.claim_pick_timer(&pool_name, &timer_id).await {
+                    Ok(true) => {}
+                    Ok(false) => return,
+                    Err(e) => warn!("{}", e),
+                }
+            }
+
+            if let Err(e) = pick_timers
+                .on_expiry(&pool_name, pick_number, auto_pick)
+                .await
+            {
+                warn!("{}", e);
+            }
+        });
+
+        if let Ok(mut timers) = self.timers.lock() {
+            timers.insert(pool.name.clone(), timer);
+        }
+    }
+
+    async fn send_draft_suggestions(&self, pool: &Pool, next_drafter: String) -> Result<()> {
+        // Auction drafts have no drafter turns.
+        if matches!(pool.settings.draft_type, DraftType::Auction) {
+            return Ok(());
+        }
+
+        let suggestions = get_draft_suggestions(&self.db, pool).await?;
+        let suggestions = serde_json::to_string(&CommandResponse::DraftSuggestions {
+            notified_user: next_drafter,
+            suggestions,
+        })
+        .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        let _ = self
+            .draft_server_info
+            .get_room_tx(&pool.name)?
+            .send(suggestions);
+        Ok(())
+    }
+
+    async fn on_expiry(
+        self: &Arc<Self>,
+        pool_name: &str,
+        pick_number: usize,
+        auto_pick: bool,
+    ) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+
+        // The timer is stale when the pick was already made or the draft was paused meanwhile.
+        let current_pick_number = pool
+            .context
+            .as_ref()
+            .map(|context| context.players_name_drafted.len());
+
+        if !matches!(pool.status, PoolState::Draft)
+            || pool.is_draft_paused == Some(true)
+            || current_pick_number != Some(pick_number)
+        {
+            return Ok(());
+        }
+
+        let previous_pool = pool.clone();
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        let mut is_drafted = false;
+
+        if auto_pick {
+            // The first available player of the pooler's draft queue is picked before the best available player.
+            let next_drafter = pool.get_next_drafter()?;
+            let mut draft_queue = get_draft_queues(&collection, pool_name)
+                .await?
+                .remove(&next_drafter);
+
+            let queued_player = draft_queue.as_ref().and_then(|draft_queue| {
+                pool.context
+                    .as_ref()
+                    .and_then(|context| context.find_first_available_player(&draft_queue.players))
+            });
+
+            if let (Some(player), Some(draft_queue)) = (queued_player, draft_queue.as_mut()) {
+                let player_id = player.id;
+
+                match recorder
+                    .apply(&mut pool, DraftRecordedCommand::AutoDraftPlayer { player })
+                    .await
+                {
+                    Ok(()) => is_drafted = true,
+                    Err(e) => {
+                        warn!(
+                            "The queued player {} could not be drafted: {}",
+                            player_id, e
+                        );
+                        drop_queued_player(
+                            &collection,
+                            pool_name,
+                            &next_drafter,
+                            draft_queue,
+                            player_id,
+                        )
+                        .await?;
+                    }
+                }
+            }
+
+            if !is_drafted {
+                if let Some(player) = find_best_available_player(&self.db, &mut pool).await? {
+                    recorder
+                        .apply(&mut pool, DraftRecordedCommand::AutoDraftPlayer { player })
+                        .await?;
+                    is_drafted = true;
+                }
+            }
+        }
+
+        if !is_drafted {
+            recorder
+                .apply(&mut pool, DraftRecordedCommand::SkipPick)
+                .await?;
+        }
+
+        draft_queued_players(&collection, &mut pool, &mut recorder).await;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context": to_bson(context).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "status": to_bson(&pool.status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        let updated_pool = update_pool_with_history(
+            &self.db,
+            updated_fields,
+            previous_pool,
+            SERVER_ACTOR,
+            PoolEventType::AutoDraftPlayer,
+        )
+        .await?;
+        recorder.save().await;
+        fire_draft_completed(&self.notifier, &updated_pool);
+        self.restart(&updated_pool);
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
+    }
+}
+
+impl MongoDraftService {
+    pub fn new(
+        db: DatabaseConnection,
+        cached_jwks: Arc<CachedJwks>,
+        draft_server_info: Arc<DraftServerInfo>,
+        notifier: Notifier,
+        users_service: UsersServiceHandle,
+    ) -> Self {
+        Self {
+            db: db.clone(),
             cached_jwks: cached_jwks,
-            draft_server_info: DraftServerInfo::new(),
+            draft_server_info: draft_server_info.clone(),
+            pick_timers: Arc::new(PickTimers::new(db, draft_server_info, notifier.clone())),
+            notifier,
+            users_service,
         }
     }
+
+    async fn send_room_users(
+        &self,
+        pool_name: &str,
+        room_users: HashMap<String, RoomUser>,
+    ) -> Result<()> {
+        // With a room store, the room users of this instance are saved
+        // and the users of every instance are sent to the room.
+        let room_users = match self.draft_server_info.get_room_store() {
+            Some(room_store) => {
+                room_store.save_room_users(pool_name, &room_users).await?;
+                room_store.list_room_users(pool_name).await?
+            }
+            None => room_users,
+        };
+
+        send_users_info(self.draft_server_info.get_room_tx(pool_name)?, room_users)
+    }
 }
 
 #[async_trait]
@@ -69,15 +801,18 @@ impl DraftService for MongoDraftService {
         let collection = self.db.collection::<Pool>("pools");
 
         let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
         // List all users that participate in the pool.
         // These will be added as official pool participants.
-        let room_users = self.draft_server_info.get_room_users(pool_name)?;
+        let room_users: Vec<RoomUser> = self
+            .list_room_users(pool_name)
+            .await?
+            .into_values()
+            .collect();
 
         pool.start_draft(user_id, &room_users, draft_order)?;
 
         // Update the whole pool information in database.
-        let collection = self.db.collection::<Pool>("pools");
-
         // Update the fields in the mongoDB pool document.
 
         let updated_fields = doc! {
@@ -87,7 +822,96 @@ impl DraftService for MongoDraftService {
         // TODO Add the new pool to the list so that we know in which pool each users participated in.
         // add_pool_to_users(&collection_users, &_pool_info.name, participants).await?;
 
-        let updated_pool = update_pool(updated_fields, &collection, pool_name).await?;
+        let updated_pool = update_pool_with_history(
+            &self.db,
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::StartDraft,
+        )
+        .await?;
+        start_draft_recording(&self.db, &updated_pool).await;
+
+        // Announce the start of the draft to every pooler.
+        let participants: Vec<String> = updated_pool
+            .participants
+            .iter()
+            .map(|participant| participant.id.clone())
+            .collect();
+
+        if let Err(e) = self
+            .notifier
+            .notify_users(&participants, pool_name, NotificationType::DraftStarted)
+            .await
+        {
+            warn!("{}", e);
+        }
+
+        self.pick_timers.restart(&updated_pool);
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
+    }
+
+    async fn update_draft_order(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        draft_order_type: DraftOrderType,
+    ) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        let room_users: Vec<RoomUser> = self
+            .list_room_users(pool_name)
+            .await?
+            .into_values()
+            .collect();
+
+        // The rank of a past season that was not marked as final is computed from its daily scores.
+        let past_season_rank = match (
+            &draft_order_type,
+            pool.settings
+                .dynasty_settings
+                .as_ref()
+                .and_then(|dynasty_settings| dynasty_settings.past_season_pool_name.first()),
+        ) {
+            (DraftOrderType::ReverseStandings, Some(past_season_pool_name)) => {
+                let past_season_pool = collection
+                    .find_one(doc! {"name": past_season_pool_name}, None)
+                    .await
+                    .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+                    .ok_or_else(|| AppError::CodedError {
+                        code: ErrorCode::PoolNotFound,
+                        msg: format!("no pool found with name '{}'", past_season_pool_name),
+                    })?;
+
+                Some(past_season_pool.get_season_rank()?)
+            }
+            _ => None,
+        };
+
+        pool.update_draft_order(
+            user_id,
+            &room_users,
+            &draft_order_type,
+            past_season_rank.as_deref(),
+        )?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "draft_order": to_bson(&pool.draft_order).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            }
+        };
+
+        let updated_pool = update_pool_with_history(
+            &self.db,
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::UpdateDraftOrder,
+        )
+        .await?;
         send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
     }
 
@@ -102,9 +926,26 @@ impl DraftService for MongoDraftService {
         let collection = self.db.collection::<Pool>("pools");
 
         let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        // Use the server side player information.
+        let player = get_pool_player_info(&self.db, player.id).await?;
+        let player_id = player.id;
 
         // Draft the player.
-        pool.draft_player(user_id, &player)?;
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        recorder
+            .apply(
+                &mut pool,
+                DraftRecordedCommand::DraftPlayer {
+                    user_id: user_id.to_string(),
+                    player,
+                },
+            )
+            .await?;
+
+        // Then draft for the next poolers that enabled the auto draft.
+        draft_queued_players(&collection, &mut pool, &mut recorder).await;
 
         let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
             msg: "pool context does not exist.".to_string(),
@@ -118,7 +959,22 @@ impl DraftService for MongoDraftService {
         };
         // Update the fields in the mongoDB pool document.
 
-        let updated_pool = update_pool(updated_fields, &collection, pool_name).await?;
+        let updated_pool = update_pool_with_history(
+            &self.db,
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::DraftPlayer,
+        )
+        .await?;
+        recorder.save().await;
+
+        if let Some(message) = get_draft_pick_message(&updated_pool, player_id) {
+            self.notifier.post_to_webhook(&updated_pool, &message);
+        }
+        fire_draft_completed(&self.notifier, &updated_pool);
+
+        self.pick_timers.restart(&updated_pool);
 
         // Get a copy of the pool tx than send the pool information.
         send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
@@ -129,9 +985,18 @@ impl DraftService for MongoDraftService {
         let collection = self.db.collection::<Pool>("pools");
 
         let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
 
         // Undo the last draft selection.
-        pool.undo_draft_player(user_id)?;
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        recorder
+            .apply(
+                &mut pool,
+                DraftRecordedCommand::UndoDraftPlayer {
+                    user_id: user_id.to_string(),
+                },
+            )
+            .await?;
 
         let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
             msg: "pool context does not exist.".to_string(),
@@ -141,13 +1006,498 @@ impl DraftService for MongoDraftService {
             "$set": doc!{
                 "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
                 "context.players_name_drafted": to_bson(&context.players_name_drafted).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.draft_picks": to_bson(&context.draft_picks).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.makeup_picks": to_bson(&context.makeup_picks).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.auction": to_bson(&context.auction).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
             }
         };
         // Update the fields in the mongoDB pool document.
-        let updated_pool = update_pool(updated_fields, &collection, &pool.name).await?;
+        let updated_pool = update_pool_with_history(
+            &self.db,
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::UndoDraftPlayer,
+        )
+        .await?;
+        recorder.save().await;
+        self.pick_timers.restart(&updated_pool);
         send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
     }
 
+    // Skip the pick of an absent pooler. This command can only be made by the pool owner.
+    async fn skip_pick(&self, pool_name: &str, user_id: &str) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        recorder
+            .apply(
+                &mut pool,
+                DraftRecordedCommand::SkipPickForMakeup {
+                    user_id: user_id.to_string(),
+                },
+            )
+            .await?;
+
+        // Then draft for the next poolers that enabled the auto draft.
+        draft_queued_players(&collection, &mut pool, &mut recorder).await;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context": to_bson(context).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "status": to_bson(&pool.status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+        // Update the fields in the mongoDB pool document.
+        let updated_pool = update_pool_with_history(
+            &self.db,
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::SkipPick,
+        )
+        .await?;
+        recorder.save().await;
+        fire_draft_completed(&self.notifier, &updated_pool);
+        self.pick_timers.restart(&updated_pool);
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
+    }
+
+    async fn pause_draft(&self, pool_name: &str, user_id: &str, is_paused: bool) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        // Pause or resume the draft.
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        recorder
+            .apply(
+                &mut pool,
+                DraftRecordedCommand::PauseDraft {
+                    user_id: user_id.to_string(),
+                    is_paused,
+                },
+            )
+            .await?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "is_draft_paused": pool.is_draft_paused,
+            }
+        };
+        // Update the fields in the mongoDB pool document.
+        let updated_pool = update_pool_with_history(
+            &self.db,
+            updated_fields,
+            previous_pool,
+            user_id,
+            if is_paused {
+                PoolEventType::PauseDraft
+            } else {
+                PoolEventType::ResumeDraft
+            },
+        )
+        .await?;
+        recorder.save().await;
+        // The pick timer is stopped on pause and restarted on resume.
+        self.pick_timers.restart(&updated_pool);
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
+    }
+
+    async fn rollback_draft(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        pick_number: usize,
+    ) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        // Unwind every pick made after the pick number.
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        recorder
+            .apply(
+                &mut pool,
+                DraftRecordedCommand::RollbackDraft {
+                    user_id: user_id.to_string(),
+                    pick_number,
+                },
+            )
+            .await?;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.players_name_drafted": to_bson(&context.players_name_drafted).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.draft_picks": to_bson(&context.draft_picks).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.makeup_picks": to_bson(&context.makeup_picks).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.players": to_bson(&context.players).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.auction": to_bson(&context.auction).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            }
+        };
+        // Update the fields in the mongoDB pool document.
+        let updated_pool = update_pool_with_history(
+            &self.db,
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::RollbackDraft,
+        )
+        .await?;
+        recorder.save().await;
+        self.pick_timers.restart(&updated_pool);
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
+    }
+
+    async fn trade_draft_picks(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        req: TradeDraftPicksRequest,
+    ) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        // Exchange the current draft picks between the two poolers.
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        recorder
+            .apply(
+                &mut pool,
+                DraftRecordedCommand::TradeDraftPicks {
+                    user_id: user_id.to_string(),
+                    trade: req,
+                },
+            )
+            .await?;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context.current_draft_pick_owners": to_bson(&context.current_draft_pick_owners).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            }
+        };
+        // Update the fields in the mongoDB pool document.
+        let updated_pool = update_pool_with_history(
+            &self.db,
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::TradeDraftPicks,
+        )
+        .await?;
+        recorder.save().await;
+        // The next pick could now belong to another pooler.
+        self.pick_timers.restart(&updated_pool);
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
+    }
+
+    // Auction draft: nominate a player to be auctioned.
+    async fn nominate_player(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        player: PoolPlayerInfo,
+        opening_bid: u32,
+    ) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        // Use the server side player information.
+        let player = get_pool_player_info(&self.db, player.id).await?;
+
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        recorder
+            .apply(
+                &mut pool,
+                DraftRecordedCommand::NominatePlayer {
+                    user_id: user_id.to_string(),
+                    player,
+                    opening_bid,
+                },
+            )
+            .await?;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context.auction": to_bson(&context.auction).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            }
+        };
+
+        let updated_pool = update_pool_with_history_if(
+            &self.db,
+            get_auction_condition(&previous_pool),
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::NominatePlayer,
+        )
+        .await?;
+        recorder.save().await;
+        self.pick_timers.restart(&updated_pool);
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
+    }
+
+    // Auction draft: bid on the nominated player.
+    async fn bid_player(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        bidder_id: &str,
+        amount: u32,
+    ) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        recorder
+            .apply(
+                &mut pool,
+                DraftRecordedCommand::PlaceBid {
+                    user_id: user_id.to_string(),
+                    bidder_id: bidder_id.to_string(),
+                    amount,
+                },
+            )
+            .await?;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context.auction": to_bson(&context.auction).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            }
+        };
+
+        let updated_pool = update_pool_with_history_if(
+            &self.db,
+            get_auction_condition(&previous_pool),
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::BidPlayer,
+        )
+        .await?;
+        recorder.save().await;
+        self.pick_timers.restart(&updated_pool);
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
+    }
+
+    // Auction draft: award the nominated player to the highest bidder. This command can only be made by the pool owner.
+    async fn close_bid(&self, pool_name: &str, user_id: &str) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        let nominated_player_id = pool
+            .context
+            .as_ref()
+            .and_then(|context| context.auction.as_ref())
+            .and_then(|auction| auction.nomination.as_ref())
+            .map(|nomination| nomination.player.id);
+
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        recorder
+            .apply(
+                &mut pool,
+                DraftRecordedCommand::CloseBid {
+                    user_id: user_id.to_string(),
+                },
+            )
+            .await?;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context": to_bson(context).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "status": to_bson(&pool.status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        let updated_pool = update_pool_with_history_if(
+            &self.db,
+            get_auction_condition(&previous_pool),
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::CloseBid,
+        )
+        .await?;
+        recorder.save().await;
+
+        if let Some(message) = nominated_player_id
+            .and_then(|player_id| get_draft_pick_message(&updated_pool, player_id))
+        {
+            self.notifier.post_to_webhook(&updated_pool, &message);
+        }
+        fire_draft_completed(&self.notifier, &updated_pool);
+
+        self.pick_timers.restart(&updated_pool);
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
+    }
+
+    async fn cancel_nomination(&self, pool_name: &str, user_id: &str) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        recorder
+            .apply(
+                &mut pool,
+                DraftRecordedCommand::CancelNomination {
+                    user_id: user_id.to_string(),
+                },
+            )
+            .await?;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context.auction": to_bson(&context.auction).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            }
+        };
+
+        let updated_pool = update_pool_with_history_if(
+            &self.db,
+            get_auction_condition(&previous_pool),
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::CancelNomination,
+        )
+        .await?;
+        recorder.save().await;
+
+        send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
+    }
+
+    // Get the draft queue of the user.
+    async fn get_draft_queue(&self, pool_name: &str, user_id: &str) -> Result<DraftQueue> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut draft_queues = get_draft_queues(&collection, pool_name).await?;
+
+        Ok(draft_queues.remove(user_id).unwrap_or_default())
+    }
+
+    // Replace the ranked list of players of the user draft queue.
+    async fn update_draft_queue(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        players: Vec<PoolPlayerInfo>,
+    ) -> Result<DraftQueue> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let pool = get_short_pool_by_name(&collection, pool_name).await?;
+
+        pool.can_update_draft_queue(user_id)?;
+
+        // Use the server side players information.
+        let mut queued_players = Vec::new();
+        for player in players {
+            queued_players.push(get_pool_player_info(&self.db, player.id).await?);
+        }
+
+        let mut draft_queue = self.get_draft_queue(pool_name, user_id).await?;
+        draft_queue.players = queued_players;
+
+        update_draft_queue(&collection, pool_name, user_id, &draft_queue).await?;
+        Ok(draft_queue)
+    }
+
+    // Enable/disable the auto draft of the user.
+    // When enabled during the user turn, the first available player of the queue is drafted right away.
+    async fn toggle_auto_draft(&self, pool_name: &str, user_id: &str) -> Result<DraftQueue> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        pool.can_update_draft_queue(user_id)?;
+
+        let mut draft_queue = self.get_draft_queue(pool_name, user_id).await?;
+        draft_queue.auto_draft = !draft_queue.auto_draft;
+
+        update_draft_queue(&collection, pool_name, user_id, &draft_queue).await?;
+
+        if !matches!(pool.status, PoolState::Draft) {
+            return Ok(draft_queue);
+        }
+
+        let nb_picks = pool
+            .context
+            .as_ref()
+            .map_or(0, |context| context.players_name_drafted.len());
+
+        let mut recorder = DraftRecorder::new(&self.db, pool_name);
+        draft_queued_players(&collection, &mut pool, &mut recorder).await;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        if context.players_name_drafted.len() != nb_picks {
+            let updated_fields = doc! {
+                "$set": doc!{
+                    "context": to_bson(context).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                    "status": to_bson(&pool.status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+                }
+            };
+
+            let updated_pool = update_pool_with_history(
+                &self.db,
+                updated_fields,
+                previous_pool,
+                user_id,
+                PoolEventType::AutoDraftPlayer,
+            )
+            .await?;
+            recorder.save().await;
+            self.pick_timers.restart(&updated_pool);
+            send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)?;
+        }
+
+        Ok(draft_queue)
+    }
+
     // Update pool settings, this command can only be made by the owner.
     // The pool needs to be into the status CREATED.
     async fn update_pool_settings(
@@ -159,7 +1509,9 @@ impl DraftService for MongoDraftService {
         let collection = self.db.collection::<Pool>("pools");
 
         let pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
 
+        pool_settings.validate()?;
         pool.can_update_pool_settings(use_id)?;
 
         let updated_fields = doc! {
@@ -169,7 +1521,14 @@ impl DraftService for MongoDraftService {
             }
         };
 
-        let updated_pool = update_pool(updated_fields, &collection, pool_name).await?;
+        let updated_pool = update_pool_with_history(
+            &self.db,
+            updated_fields,
+            previous_pool,
+            use_id,
+            PoolEventType::UpdatePoolSettings,
+        )
+        .await?;
         send_pool_info(self.draft_server_info.get_room_tx(pool_name)?, updated_pool)
     }
 
@@ -179,13 +1538,118 @@ impl DraftService for MongoDraftService {
     }
 
     async fn list_room_users(&self, pool_name: &str) -> Result<HashMap<String, RoomUser>> {
-        self.draft_server_info.list_room_users(pool_name)
+        match self.draft_server_info.get_room_store() {
+            Some(room_store) => room_store.list_room_users(pool_name).await,
+            None => self.draft_server_info.list_room_users(pool_name),
+        }
     }
 
     async fn list_authenticated_sockets(&self) -> Result<HashMap<String, UserEmailJwtPayload>> {
         self.draft_server_info.list_authenticated_sockets()
     }
 
+    // ChatMessage command.
+    async fn send_chat_message(
+        &self,
+        pool_name: &str,
+        socket_addr: SocketAddr,
+        message: &str,
+    ) -> Result<()> {
+        let message = message.trim();
+        if message.is_empty() || message.chars().count() > CHAT_MESSAGE_MAX_LENGTH {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "A chat message needs to have between 1 and {} characters.",
+                    CHAT_MESSAGE_MAX_LENGTH
+                ),
+            });
+        }
+
+        // Only the authenticated users in the room can chat.
+        let user = self
+            .draft_server_info
+            .get_authenticated_user_with_socket(&socket_addr.to_string())?
+            .ok_or_else(|| AppError::CustomError {
+                msg: "Only the authenticated users can chat.".to_string(),
+            })?;
+
+        let room_user = self
+            .draft_server_info
+            .list_room_users(pool_name)?
+            .remove(&user.sub)
+            .ok_or_else(|| AppError::CustomError {
+                msg: "Only the users in the room can chat.".to_string(),
+            })?;
+
+        let chat_message = ChatMessage {
+            pool_name: pool_name.to_string(),
+            user_id: user.sub,
+            user_name: room_user.name,
+            message: message.to_string(),
+            date_created: Utc::now().timestamp_millis(),
+        };
+
+        self.db
+            .collection::<ChatMessage>("chat_messages")
+            .insert_one(&chat_message, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        let chat_message = serde_json::to_string(&CommandResponse::ChatMessage { chat_message })
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        let _ = self
+            .draft_server_info
+            .get_room_tx(pool_name)?
+            .send(chat_message);
+        Ok(())
+    }
+
+    async fn get_draft_suggestions(&self, pool_name: &str) -> Result<DraftSuggestions> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, pool_name).await?;
+
+        get_draft_suggestions(&self.db, &pool).await
+    }
+
+    async fn get_chat_messages(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        query: ChatHistoryQuery,
+    ) -> Result<Vec<ChatMessage>> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, pool_name).await?;
+
+        if !has_permission(user_id, &pool, Permission::ViewPrivatePool)
+            && !is_user_invited(&self.db, pool_name, user_id).await?
+        {
+            return Err(AppError::CustomError {
+                msg: "Only the members and the invited users can read the chat of this pool."
+                    .to_string(),
+            });
+        }
+
+        let mut filter = doc! {"pool_name": pool_name};
+        if let Some(before) = query.before {
+            filter.insert("date_created", doc! {"$lt": before});
+        }
+
+        let find_options = FindOptions::builder()
+            .sort(doc! {"date_created": -1})
+            .limit(query.limit.unwrap_or(50))
+            .build();
+
+        self.db
+            .collection::<ChatMessage>("chat_messages")
+            .find(filter, find_options)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
     // Authenticate the token received as inputs.
     // This commands is only being made during the socket initial negociation.
     async fn authenticate_web_socket(
@@ -193,7 +1657,7 @@ impl DraftService for MongoDraftService {
         token: &str,
         socket_addr: SocketAddr,
     ) -> Option<UserEmailJwtPayload> {
-        match hanko_token_decode(token, &self.cached_jwks).await {
+        match validate_user_token(token, &self.cached_jwks, &self.users_service).await {
             Ok(user) => {
                 match self
                     .draft_server_info
@@ -204,7 +1668,7 @@ impl DraftService for MongoDraftService {
                 }
             }
             Err(e) => {
-                println!("{}", e);
+                warn!("{}", e);
                 None
             }
         }
@@ -221,17 +1685,73 @@ impl DraftService for MongoDraftService {
         pool_name: &str,
         number_poolers: u8,
         socket_addr: SocketAddr,
-    ) -> Result<broadcast::Receiver<String>> {
+    ) -> Result<(broadcast::Receiver<String>, String)> {
+        // Only the invited users can join the draft room of a private pool.
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, pool_name).await?;
+
+        if pool.is_private() {
+            let is_allowed = match self
+                .draft_server_info
+                .get_authenticated_user_with_socket(&socket_addr.to_string())?
+            {
+                Some(user) => {
+                    has_permission(&user.sub, &pool, Permission::ViewPrivatePool)
+                        || is_user_invited(&self.db, pool_name, &user.sub).await?
+                }
+                None => false,
+            };
+
+            if !is_allowed {
+                return Err(AppError::CustomError {
+                    msg: "Only the invited users can join this pool.".to_string(),
+                });
+            }
+        }
+
         let (rx, room_users) = self.draft_server_info.join_room(
             pool_name,
             number_poolers,
             &socket_addr.to_string(),
         )?;
 
-        let tx = self.draft_server_info.get_room_tx(pool_name)?;
-        send_users_info(tx, room_users)?;
+        self.send_room_users(pool_name, room_users).await?;
 
-        Ok(rx)
+        // Issue a session token so the socket can resume its room state if it disconnects.
+        let session_token = self.draft_server_info.create_session(
+            pool_name,
+            number_poolers,
+            &socket_addr.to_string(),
+        )?;
+
+        Ok((rx, session_token))
+    }
+
+    // ResumeRoom command.
+    async fn resume_room(
+        &self,
+        session_token: &str,
+        socket_addr: SocketAddr,
+    ) -> Result<(broadcast::Receiver<String>, Pool)> {
+        let (rx, pool_name) = self
+            .draft_server_info
+            .resume_session(session_token, &socket_addr.to_string())?;
+
+        self.send_room_users(
+            &pool_name,
+            self.draft_server_info.list_room_users(&pool_name)?,
+        )
+        .await?;
+
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, &pool_name).await?;
+
+        Ok((rx, pool))
+    }
+
+    async fn suspend_session(&self, socket_addr: SocketAddr) -> Result<()> {
+        self.draft_server_info
+            .suspend_session(&socket_addr.to_string())
     }
 
     // LeaveRoom command.
@@ -240,8 +1760,7 @@ impl DraftService for MongoDraftService {
             .draft_server_info
             .leave_room(pool_name, &socket_addr.to_string())?;
 
-        let tx = self.draft_server_info.get_room_tx(pool_name)?;
-        send_users_info(tx, room_users)
+        self.send_room_users(pool_name, room_users).await
     }
 
     // OnReady command. This command can only be made when the pool is into CREATED status.
@@ -250,8 +1769,7 @@ impl DraftService for MongoDraftService {
             .draft_server_info
             .on_ready(pool_name, &socket_addr.to_string())?;
 
-        let tx = self.draft_server_info.get_room_tx(pool_name)?;
-        send_users_info(tx, room_users)
+        self.send_room_users(pool_name, room_users).await
     }
 
     // AddUser command. This command can only be made when the pool is into CREATED status.
@@ -265,8 +1783,7 @@ impl DraftService for MongoDraftService {
             self.draft_server_info
                 .add_user(pool_name, user_name, &socket_addr.to_string())?;
 
-        let tx = self.draft_server_info.get_room_tx(pool_name)?;
-        send_users_info(tx, room_users)
+        self.send_room_users(pool_name, room_users).await
     }
 
     // RemoveUser command. This command can only be made when the pool is into CREATED status.
@@ -280,7 +1797,6 @@ impl DraftService for MongoDraftService {
             self.draft_server_info
                 .remove_user(pool_name, user_id, &socket_addr.to_string())?;
 
-        let tx = self.draft_server_info.get_room_tx(pool_name)?;
-        send_users_info(tx, room_users)
+        self.send_room_users(pool_name, room_users).await
     }
 }
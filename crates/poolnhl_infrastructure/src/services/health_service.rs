@@ -0,0 +1,84 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use mongodb::bson::doc;
+use tokio::time::timeout;
+
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::health::{
+    model::{DependencyStatus, Readiness},
+    service::HealthService,
+};
+
+use crate::database_connection::DatabaseConnection;
+use crate::jwt::CachedJwks;
+use crate::nhl_api::NhlApiClient;
+use crate::settings::NhlApi;
+
+// The number of seconds a dependency has to answer before it is considered not ready.
+const DEPENDENCY_TIMEOUT_SECONDS: u64 = 5;
+
+// The JWKS is fetched again by the readiness probe once it is older than this.
+const JWKS_MAX_AGE_SECONDS: i64 = 24 * 60 * 60;
+
+#[derive(Clone)]
+pub struct MongoHealthService {
+    db: DatabaseConnection,
+    cached_jwks: Arc<CachedJwks>,
+    nhl_api: NhlApiClient,
+}
+
+impl MongoHealthService {
+    pub fn new(db: DatabaseConnection, cached_jwks: Arc<CachedJwks>, nhl_api: &NhlApi) -> Self {
+        Self {
+            db,
+            cached_jwks,
+            nhl_api: NhlApiClient::new(nhl_api),
+        }
+    }
+
+    async fn ping_database(&self) -> Result<()> {
+        self.db
+            .run_command(doc! {"ping": 1}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(())
+    }
+}
+
+// Run a dependency check, a check taking too long is reported as not ready.
+async fn check<F: std::future::Future<Output = Result<()>>>(
+    name: &str,
+    dependency_check: F,
+) -> DependencyStatus {
+    let result = timeout(
+        Duration::from_secs(DEPENDENCY_TIMEOUT_SECONDS),
+        dependency_check,
+    )
+    .await
+    .unwrap_or_else(|_| {
+        Err(AppError::CustomError {
+            msg: "The dependency did not answer in time.".to_string(),
+        })
+    });
+
+    DependencyStatus::new(name, result)
+}
+
+#[async_trait]
+impl HealthService for MongoHealthService {
+    async fn get_readiness(&self) -> Readiness {
+        let (database, jwks, nhl_api) = tokio::join!(
+            check("mongodb", self.ping_database()),
+            check(
+                "jwks",
+                self.cached_jwks.refresh_if_stale(JWKS_MAX_AGE_SECONDS)
+            ),
+            check("nhl_api", self.nhl_api.ping()),
+        );
+
+        Readiness::new(vec![database, jwks, nhl_api])
+    }
+}
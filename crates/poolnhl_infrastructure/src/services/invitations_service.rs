@@ -0,0 +1,169 @@
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::bson::doc;
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::IndexModel;
+
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::invitations::{
+    model::{
+        AcceptInvitationRequest, CreateInvitationRequest, DeleteInvitationRequest, Invitation,
+    },
+    service::InvitationsService,
+};
+use poolnhl_interface::pool::authorization::{authorize, Permission};
+use poolnhl_interface::pool::model::Pool;
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+
+use crate::database_connection::DatabaseConnection;
+use crate::services::pool_service::{get_cached_short_pool_by_name, get_short_pool_by_name};
+
+#[derive(Clone)]
+pub struct MongoInvitationsService {
+    db: DatabaseConnection,
+}
+
+impl MongoInvitationsService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+// Create the indexes used to find an invitation from its token and the invitations of a pool.
+pub async fn create_invitations_indexes(db: &DatabaseConnection) -> Result<()> {
+    let indexes = vec![
+        IndexModel::builder()
+            .keys(doc! {"token": 1})
+            .options(IndexOptions::builder().unique(true).build())
+            .build(),
+        IndexModel::builder()
+            .keys(doc! {"pool_name": 1, "accepted_by": 1})
+            .build(),
+    ];
+
+    db.collection::<Invitation>("invitations")
+        .create_indexes(indexes, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
+
+// Tells if the user accepted an invitation to the pool.
+pub async fn is_user_invited(
+    db: &DatabaseConnection,
+    pool_name: &str,
+    user_id: &str,
+) -> Result<bool> {
+    let collection = db.collection::<Invitation>("invitations");
+
+    let invitation = collection
+        .find_one(doc! {"pool_name": pool_name, "accepted_by": user_id}, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(invitation.is_some())
+}
+
+async fn get_invitation_by_token(db: &DatabaseConnection, token: &str) -> Result<Invitation> {
+    let collection = db.collection::<Invitation>("invitations");
+
+    collection
+        .find_one(doc! {"token": token}, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+        .ok_or_else(|| AppError::CustomError {
+            msg: "The invitation does not exist.".to_string(),
+        })
+}
+
+#[async_trait]
+impl InvitationsService for MongoInvitationsService {
+    async fn get_invitation(&self, token: &str) -> Result<Invitation> {
+        get_invitation_by_token(&self.db, token).await
+    }
+
+    async fn list_invitations(&self, user_id: &str, pool_name: &str) -> Result<Vec<Invitation>> {
+        let pool =
+            get_cached_short_pool_by_name(&self.db.collection::<Pool>("pools"), pool_name).await?;
+        authorize(user_id, &pool, Permission::ManageInvitations)?;
+
+        let collection = self.db.collection::<Invitation>("invitations");
+
+        let find_option = FindOptions::builder()
+            .sort(doc! {"date_created": -1})
+            .build();
+
+        collection
+            .find(doc! {"pool_name": pool_name}, find_option)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    async fn create_invitation(
+        &self,
+        user_id: &str,
+        req: CreateInvitationRequest,
+    ) -> Result<Invitation> {
+        // Only the owner and the pool assistants can invite users to the pool.
+        let pool =
+            get_short_pool_by_name(&self.db.collection::<Pool>("pools"), &req.pool_name).await?;
+        authorize(user_id, &pool, Permission::ManageInvitations)?;
+
+        let invitation = Invitation::new(user_id, &req);
+
+        self.db
+            .collection::<Invitation>("invitations")
+            .insert_one(&invitation, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(invitation)
+    }
+
+    async fn accept_invitation(
+        &self,
+        user: &UserEmailJwtPayload,
+        req: AcceptInvitationRequest,
+    ) -> Result<Invitation> {
+        let mut invitation = get_invitation_by_token(&self.db, &req.token).await?;
+
+        invitation.accept(&user.sub, &user.email.address)?;
+
+        self.db
+            .collection::<Invitation>("invitations")
+            .update_one(
+                doc! {"token": &req.token},
+                doc! {"$addToSet": {"accepted_by": &user.sub}},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(invitation)
+    }
+
+    async fn delete_invitation(&self, user_id: &str, req: DeleteInvitationRequest) -> Result<()> {
+        let pool =
+            get_short_pool_by_name(&self.db.collection::<Pool>("pools"), &req.pool_name).await?;
+        authorize(user_id, &pool, Permission::ManageInvitations)?;
+
+        let delete_result = self
+            .db
+            .collection::<Invitation>("invitations")
+            .delete_one(doc! {"token": req.token, "pool_name": req.pool_name}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        if delete_result.deleted_count == 0 {
+            return Err(AppError::CustomError {
+                msg: "The invitation could not be deleted.".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+}
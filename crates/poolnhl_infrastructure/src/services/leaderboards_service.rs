@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use moka::future::Cache;
+use mongodb::bson::{doc, from_document, Document};
+
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::leaderboards::{
+    model::{LeaderboardEntry, Leaderboards, LeaderboardsQuery, DEFAULT_LEADERBOARD_LIMIT},
+    service::LeaderboardsService,
+};
+
+use crate::database_connection::DatabaseConnection;
+
+// The number of seconds the leaderboards are cached, the final ranks only change when a pool is marked as final.
+const LEADERBOARDS_CACHE_TTL_SECONDS: u64 = 3600;
+
+#[derive(Clone)]
+pub struct MongoLeaderboardsService {
+    db: DatabaseConnection,
+
+    // The results of every user, by season filter.
+    entries: Cache<Option<u32>, Vec<LeaderboardEntry>>,
+}
+
+impl MongoLeaderboardsService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            entries: Cache::builder()
+                .max_capacity(100)
+                .time_to_live(Duration::from_secs(LEADERBOARDS_CACHE_TTL_SECONDS))
+                .build(),
+        }
+    }
+
+    async fn aggregate_entries(&self, season: Option<u32>) -> Result<Vec<LeaderboardEntry>> {
+        let mut filter = doc! {"final_rank.0": {"$exists": true}};
+        if let Some(season) = season {
+            filter.insert("season", season);
+        }
+
+        // One document per user and completed pool, then grouped by user.
+        let pipeline = vec![
+            doc! {"$match": filter},
+            doc! {"$sort": {"season": 1}},
+            doc! {"$project": {
+                "_id": 0,
+                "participants": 1,
+                "totals": {"$objectToArray": {"$ifNull": ["$context.cumulative_totals", {}]}},
+                "ranks": {"$map": {
+                    "input": {"$range": [0, {"$size": "$final_rank"}]},
+                    "as": "index",
+                    "in": {
+                        "user_id": {"$arrayElemAt": ["$final_rank", "$$index"]},
+                        "rank": {"$add": ["$$index", 1]},
+                    },
+                }},
+            }},
+            doc! {"$unwind": "$ranks"},
+            doc! {"$project": {
+                "user_id": "$ranks.user_id",
+                "rank": "$ranks.rank",
+                "name": {"$first": {"$map": {
+                    "input": {"$filter": {
+                        "input": "$participants",
+                        "cond": {"$eq": ["$$this.id", "$ranks.user_id"]},
+                    }},
+                    "in": "$$this.name",
+                }}},
+                "points": {"$let": {
+                    "vars": {"totals": {"$first": {"$filter": {
+                        "input": "$totals",
+                        "cond": {"$eq": ["$$this.k", "$ranks.user_id"]},
+                    }}}},
+                    "in": {"$add": [
+                        {"$ifNull": ["$$totals.v.forwards.points", 0]},
+                        {"$ifNull": ["$$totals.v.defense.points", 0]},
+                        {"$ifNull": ["$$totals.v.goalies.points", 0]},
+                    ]},
+                }},
+            }},
+            doc! {"$group": {
+                "_id": "$user_id",
+                "name": {"$last": "$name"},
+                "pools_completed": {"$sum": 1},
+                "championships": {"$sum": {"$cond": [{"$eq": ["$rank", 1]}, 1, 0]}},
+                "average_finish": {"$avg": "$rank"},
+                "total_points": {"$sum": "$points"},
+            }},
+            doc! {"$project": {
+                "_id": 0,
+                "user_id": "$_id",
+                "name": {"$ifNull": ["$name", "$_id"]},
+                "pools_completed": 1,
+                "championships": 1,
+                "average_finish": {"$toDouble": "$average_finish"},
+                "total_points": {"$toDouble": "$total_points"},
+            }},
+        ];
+
+        let documents: Vec<Document> = self
+            .db
+            .collection::<Document>("pools")
+            .aggregate(pipeline, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        documents
+            .into_iter()
+            .map(|document| {
+                from_document(document).map_err(|e| AppError::MongoError { msg: e.to_string() })
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl LeaderboardsService for MongoLeaderboardsService {
+    async fn get_leaderboards(&self, query: LeaderboardsQuery) -> Result<Leaderboards> {
+        let entries = match self.entries.get(&query.season).await {
+            Some(entries) => entries,
+            None => {
+                let entries = self.aggregate_entries(query.season).await?;
+                self.entries.insert(query.season, entries.clone()).await;
+                entries
+            }
+        };
+
+        Ok(Leaderboards::new(
+            query.season,
+            &entries,
+            query.limit.unwrap_or(DEFAULT_LEADERBOARD_LIMIT),
+        ))
+    }
+}
@@ -0,0 +1,299 @@
+use async_trait::async_trait;
+use futures::stream::TryStreamExt;
+use mongodb::bson::{doc, to_bson};
+use mongodb::options::{FindOptions, IndexOptions, UpdateOptions};
+use mongodb::IndexModel;
+use tracing::warn;
+
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::mailer::{model::Email, service::MailerHandle};
+use poolnhl_interface::notifications::{
+    model::{
+        MarkNotificationsReadRequest, Notification, NotificationPreferences, NotificationType,
+        NotificationsQuery, UpdateNotificationPreferencesRequest, DEFAULT_NOTIFICATIONS_LIMIT,
+    },
+    service::NotificationsService,
+};
+use poolnhl_interface::pool::model::Pool;
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+use poolnhl_interface::webhooks::model::PoolLifecycleEvent;
+
+use crate::database_connection::DatabaseConnection;
+use crate::services::achievements_service::AchievementTracker;
+use crate::services::webhooks_service::LifecycleWebhooks;
+use crate::webhooks::WebhookSender;
+
+#[derive(Clone)]
+pub struct MongoNotificationsService {
+    db: DatabaseConnection,
+}
+
+impl MongoNotificationsService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+// Write the notifications to the users feed and email them to the users that opted in.
+// The pool activity is also posted to the pool webhook and the lifecycle events to the
+// owner webhook subscriptions. The lifecycle events also award the achievements earned in the pool.
+#[derive(Clone)]
+pub struct Notifier {
+    db: DatabaseConnection,
+    mailer: MailerHandle,
+    webhooks: WebhookSender,
+    lifecycle_webhooks: LifecycleWebhooks,
+    achievements: AchievementTracker,
+}
+
+impl Notifier {
+    pub fn new(
+        db: DatabaseConnection,
+        mailer: MailerHandle,
+        webhooks: WebhookSender,
+        lifecycle_webhooks: LifecycleWebhooks,
+        achievements: AchievementTracker,
+    ) -> Self {
+        Self {
+            db,
+            mailer,
+            webhooks,
+            lifecycle_webhooks,
+            achievements,
+        }
+    }
+
+    pub fn fire_pool_event(&self, pool: &Pool, event: PoolLifecycleEvent, data: serde_json::Value) {
+        self.lifecycle_webhooks.fire(pool, event, data);
+        self.achievements.track(pool);
+    }
+
+    pub fn track_achievements(&self, pool: &Pool) {
+        self.achievements.track(pool);
+    }
+
+    pub fn post_to_webhook(&self, pool: &Pool, content: &str) {
+        // Queue the message when the pool registered a webhook.
+        if let Some(webhook) = &pool.settings.webhook {
+            self.webhooks.post(webhook, content);
+        }
+    }
+
+    pub async fn test_webhook(&self, pool: &Pool) -> Result<()> {
+        let webhook = pool
+            .settings
+            .webhook
+            .as_ref()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "This pool does not have a webhook.".to_string(),
+            })?;
+
+        self.webhooks
+            .post_now(
+                webhook,
+                &format!("The pool {} is now connected to this channel.", pool.name),
+            )
+            .await
+    }
+
+    pub async fn notify_users(
+        &self,
+        user_ids: &[String],
+        pool_name: &str,
+        notification_type: NotificationType,
+    ) -> Result<()> {
+        // Write a notification to the feed of each user that did not disable this notification type for the pool.
+        let mut notifications = Vec::new();
+
+        for user_id in user_ids {
+            let preferences = find_preferences(&self.db, user_id, pool_name).await?;
+
+            if !preferences.is_enabled(&notification_type) {
+                continue;
+            }
+
+            let message = preferences
+                .language
+                .unwrap_or_default()
+                .get_notification_message(&notification_type, pool_name);
+
+            if let Some(email) = preferences.get_email() {
+                if let Err(e) = self
+                    .mailer
+                    .send_email(Email {
+                        to: email.to_string(),
+                        subject: format!("Pool {}", pool_name),
+                        body: message.clone(),
+                    })
+                    .await
+                {
+                    warn!("{}", e);
+                }
+            }
+
+            notifications.push(Notification::new(
+                user_id,
+                pool_name,
+                notification_type.clone(),
+                &message,
+            ));
+        }
+
+        if notifications.is_empty() {
+            return Ok(());
+        }
+
+        self.db
+            .collection::<Notification>("notifications")
+            .insert_many(notifications, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(())
+    }
+}
+
+// Create the indexes used to list the notifications and find the preferences of a user.
+pub async fn create_notifications_indexes(db: &DatabaseConnection) -> Result<()> {
+    db.collection::<Notification>("notifications")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"user_id": 1, "date_created": -1})
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    db.collection::<NotificationPreferences>("notification_preferences")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"user_id": 1, "pool_name": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
+
+async fn find_preferences(
+    db: &DatabaseConnection,
+    user_id: &str,
+    pool_name: &str,
+) -> Result<NotificationPreferences> {
+    // Every notification type is enabled until the user disables it.
+    let preferences = db
+        .collection::<NotificationPreferences>("notification_preferences")
+        .find_one(doc! {"user_id": user_id, "pool_name": pool_name}, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(preferences.unwrap_or(NotificationPreferences {
+        user_id: user_id.to_string(),
+        pool_name: pool_name.to_string(),
+        disabled_types: Vec::new(),
+        email_enabled: None,
+        email: None,
+        language: None,
+    }))
+}
+
+#[async_trait]
+impl NotificationsService for MongoNotificationsService {
+    async fn list_notifications(
+        &self,
+        user_id: &str,
+        query: NotificationsQuery,
+    ) -> Result<Vec<Notification>> {
+        let mut filter = doc! {"user_id": user_id};
+
+        if let Some(pool_name) = query.pool_name {
+            filter.insert("pool_name", pool_name);
+        }
+        if query.unread_only == Some(true) {
+            filter.insert("is_read", false);
+        }
+
+        let find_option = FindOptions::builder()
+            .sort(doc! {"date_created": -1})
+            .limit(query.limit.unwrap_or(DEFAULT_NOTIFICATIONS_LIMIT))
+            .build();
+
+        self.db
+            .collection::<Notification>("notifications")
+            .find(filter, find_option)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    async fn mark_as_read(&self, user_id: &str, req: MarkNotificationsReadRequest) -> Result<()> {
+        let mut filter = doc! {"user_id": user_id, "is_read": false};
+
+        if let Some(notification_ids) = req.notification_ids {
+            filter.insert("id", doc! {"$in": notification_ids});
+        }
+
+        self.db
+            .collection::<Notification>("notifications")
+            .update_many(filter, doc! {"$set": {"is_read": true}}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(())
+    }
+
+    async fn get_preferences(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+    ) -> Result<NotificationPreferences> {
+        find_preferences(&self.db, user_id, pool_name).await
+    }
+
+    async fn update_preferences(
+        &self,
+        user: &UserEmailJwtPayload,
+        req: UpdateNotificationPreferencesRequest,
+    ) -> Result<NotificationPreferences> {
+        // The email notifications are sent to the email address of the user token.
+        // Hanko verifies the addresses, the emails are not sent to an address the user does not own.
+        if req.email_enabled == Some(true) && !user.email.is_verified {
+            return Err(AppError::CustomError {
+                msg: "The email address needs to be verified before enabling the email notifications."
+                    .to_string(),
+            });
+        }
+
+        let preferences = NotificationPreferences {
+            user_id: user.sub.clone(),
+            pool_name: req.pool_name,
+            disabled_types: req.disabled_types,
+            email_enabled: req.email_enabled,
+            email: Some(user.email.address.clone()),
+            language: req.language,
+        };
+
+        self.db
+            .collection::<NotificationPreferences>("notification_preferences")
+            .update_one(
+                doc! {"user_id": &preferences.user_id, "pool_name": &preferences.pool_name},
+                doc! {"$set": {
+                    "disabled_types": to_bson(&preferences.disabled_types).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                    "email_enabled": preferences.email_enabled,
+                    "email": &preferences.email,
+                    "language": to_bson(&preferences.language).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                }},
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(preferences)
+    }
+}
@@ -1,73 +1,503 @@
+use std::collections::{HashMap, HashSet};
+
 use async_trait::async_trait;
+use chrono::NaiveDate;
 
 use futures::TryStreamExt;
-use mongodb::bson::doc;
-use mongodb::options::FindOptions;
+use mongodb::bson::{doc, to_bson};
+use mongodb::options::{FindOptions, IndexOptions, ReplaceOptions, UpdateOptions};
+use mongodb::IndexModel;
 use poolnhl_interface::errors::AppError;
 
 use poolnhl_interface::errors::Result;
+use poolnhl_interface::notifications::model::NotificationType;
 use poolnhl_interface::players::{
-    model::{GetPlayerQuery, PlayerInfo},
+    model::{DailySchedule, GetPlayerQuery, PlayerInfo},
     service::PlayersService,
 };
+use poolnhl_interface::pool::model::{InjuryStatus, Pool, PoolPlayerInfo, Position};
+use poolnhl_interface::time_provider::time_provider;
+use tracing::warn;
 
+use crate::contracts_api::{ContractsApiClient, PlayerContract};
 use crate::database_connection::DatabaseConnection;
+use crate::nhl_api::{NhlApiClient, RosterPlayer, NHL_TEAMS};
+use crate::services::notifications_service::Notifier;
+use crate::services::pool_service::update_pool;
+use crate::settings::{ContractsApi, NhlApi};
 
 #[derive(Clone)]
 pub struct MongoPlayersService {
     db: DatabaseConnection,
+    nhl_api: NhlApiClient,
+    contracts_api: ContractsApiClient,
+    notifier: Notifier,
 }
 
 impl MongoPlayersService {
-    pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+    pub fn new(
+        db: DatabaseConnection,
+        nhl_api: &NhlApi,
+        contracts_api: &ContractsApi,
+        notifier: Notifier,
+    ) -> Self {
+        Self {
+            db,
+            nhl_api: NhlApiClient::new(nhl_api),
+            contracts_api: ContractsApiClient::new(contracts_api),
+            notifier,
+        }
     }
-}
-#[async_trait]
-impl PlayersService for MongoPlayersService {
-    async fn get_players(&self, params: GetPlayerQuery) -> Result<Vec<PlayerInfo>> {
-        let mut filter = doc! {};
-        if let Some(active) = params.active {
-            filter.insert("active", active);
+
+    // Flag the poolers of the pools in progress whose starting roster is not valid anymore
+    // under the salary cap after the contracts of some of their players changed.
+    async fn flag_cap_invalid_pools(&self, updated_players: &[u32]) -> Result<()> {
+        if updated_players.is_empty() {
+            return Ok(());
         }
-        if let Some(positions) = params.positions {
-            filter.insert("position", doc! { "$in": positions });
+
+        let find_option = FindOptions::builder()
+            .projection(doc! {"context.score_by_day": 0})
+            .build();
+
+        let collection = self.db.collection::<Pool>("pools");
+        let pools: Vec<Pool> = collection
+            .find(
+                doc! {"status": "InProgress", "settings.salary_cap": {"$ne": null}},
+                find_option,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        for pool in pools {
+            let Some(context) = pool.context.as_ref() else {
+                continue;
+            };
+
+            if !updated_players
+                .iter()
+                .any(|player_id| context.players.contains_key(&player_id.to_string()))
+            {
+                continue;
+            }
+
+            let cap_invalid_participants = context.get_cap_invalid_participants(&pool.settings);
+            let previous_participants =
+                context.cap_invalid_participants.clone().unwrap_or_default();
+
+            if cap_invalid_participants == previous_participants {
+                continue;
+            }
+
+            update_pool(
+                doc! {"$set": {
+                    "context.cap_invalid_participants": to_bson(&cap_invalid_participants).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+                }},
+                &collection,
+                &pool.name,
+            )
+            .await?;
+
+            let notified_users: Vec<String> = cap_invalid_participants
+                .into_iter()
+                .filter(|participant| !previous_participants.contains(participant))
+                .collect();
+
+            if notified_users.is_empty() {
+                continue;
+            }
+
+            if let Err(e) = self
+                .notifier
+                .notify_users(&notified_users, &pool.name, NotificationType::RosterOverCap)
+                .await
+            {
+                warn!("{}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Notify the poolers starting a player that is newly ruled out for tonight's game.
+    async fn notify_ruled_out_starters(&self, ruled_out_players: &[(u32, u32)]) -> Result<()> {
+        if ruled_out_players.is_empty() {
+            return Ok(());
+        }
+
+        let today = time_provider().today().to_string();
+        let teams_playing = self.nhl_api.get_teams_playing(&today).await?;
+
+        let ruled_out_players: HashSet<u32> = ruled_out_players
+            .iter()
+            .filter(|(_, team_id)| teams_playing.contains(team_id))
+            .map(|(player_id, _)| *player_id)
+            .collect();
+
+        if ruled_out_players.is_empty() {
+            return Ok(());
         }
 
-        // Sorting options: default to sorting by `total_points` descending
-        let sort_field = params.sort.unwrap_or_else(|| "salary_cap".to_string());
-        let sort_value = if params.descending.unwrap_or(true) {
-            -1
-        } else {
-            1
-        };
-        let sort_order = doc! { sort_field: sort_value, "_id": 1 };
-
-        // Pagination: skip and limit
-        let skip = params.skip.unwrap_or(0);
-        let limit = params.limit.unwrap_or(20);
-
-        let find_options = FindOptions::builder()
-            .sort(sort_order)
-            .skip(Some(skip))
-            .limit(limit)
+        let find_option = FindOptions::builder()
+            .projection(doc! {"context.score_by_day": 0})
             .build();
 
-        let collection = self.db.collection::<PlayerInfo>("players");
-        let players = collection
-            .find(filter, find_options)
+        let pools: Vec<Pool> = self
+            .db
+            .collection::<Pool>("pools")
+            .find(doc! {"status": "InProgress"}, find_option)
             .await
             .map_err(|e| AppError::MongoError { msg: e.to_string() })?
             .try_collect()
             .await
             .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
-        Ok(players)
+        for pool in pools {
+            let Some(context) = pool.context.as_ref() else {
+                continue;
+            };
+
+            let notified_users: Vec<String> = context
+                .pooler_roster
+                .iter()
+                .filter(|(_, roster)| {
+                    roster
+                        .get_starting_players()
+                        .any(|player_id| ruled_out_players.contains(player_id))
+                })
+                .map(|(participant, _)| participant.clone())
+                .collect();
+
+            if notified_users.is_empty() {
+                continue;
+            }
+
+            if let Err(e) = self
+                .notifier
+                .notify_users(
+                    &notified_users,
+                    &pool.name,
+                    NotificationType::StarterRuledOut,
+                )
+                .await
+            {
+                warn!("{}", e);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Create the index used to find and synchronize the players.
+pub async fn create_players_indexes(db: &DatabaseConnection) -> Result<()> {
+    db.collection::<PlayerInfo>("players")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"id": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    db.collection::<DailySchedule>("schedules")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"date": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
+
+// The synced NHL games of a date, a date without games has an empty schedule.
+pub async fn get_daily_schedule(db: &DatabaseConnection, date: &str) -> Result<DailySchedule> {
+    let schedule = db
+        .collection::<DailySchedule>("schedules")
+        .find_one(doc! {"date": date}, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(schedule.unwrap_or_else(|| DailySchedule::new(date)))
+}
+
+pub async fn get_player_info(db: &DatabaseConnection, player_id: u32) -> Result<PlayerInfo> {
+    let collection = db.collection::<PlayerInfo>("players");
+
+    collection
+        .find_one(doc! {"id": player_id}, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+        .ok_or_else(|| AppError::CustomError {
+            msg: format!("no player found with id '{}'", player_id),
+        })
+}
+
+// Return the server side information of a player so that pools never trust
+// the player information sent by the clients.
+pub async fn get_pool_player_info(
+    db: &DatabaseConnection,
+    player_id: u32,
+) -> Result<PoolPlayerInfo> {
+    PoolPlayerInfo::try_from(get_player_info(db, player_id).await?)
+}
+
+// Upsert a player of a NHL team roster into the players collection.
+// Only the fields provided by the NHL api are updated, the others (i.e., salary cap) are kept.
+async fn upsert_roster_player(
+    db: &DatabaseConnection,
+    player: &RosterPlayer,
+    team_id: u32,
+    position: Position,
+    eligible_positions: &[Position],
+    season: u32,
+) -> Result<()> {
+    let collection = db.collection::<PlayerInfo>("players");
+
+    let mut fields = doc! {
+        "active": true,
+        "id": player.id,
+        "name": player.get_name(),
+        "team": team_id,
+        "position": position.as_str(),
+        "positions": to_bson(eligible_positions).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+        "positions_season": season,
+        "injury_status": to_bson(&player.get_injury_status()).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+    };
+    if let Some(age) = player.get_age() {
+        fields.insert("age", age as u32);
+    }
+
+    collection
+        .update_one(
+            doc! {"id": player.id},
+            doc! {"$set": fields},
+            UpdateOptions::builder().upsert(true).build(),
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
+// Update the current and last season stats of the synced players.
+// The stats are used to suggest the best available players during the drafts.
+async fn update_players_stats(
+    db: &DatabaseConnection,
+    nhl_api: &NhlApiClient,
+    season: u32,
+) -> Result<()> {
+    let collection = db.collection::<PlayerInfo>("players");
+
+    let last_season = season - 10001;
+    let current_season_stats = nhl_api.get_players_season_stats(None).await?;
+    let last_season_stats = nhl_api.get_players_season_stats(Some(last_season)).await?;
+
+    let player_ids: HashSet<&u32> = current_season_stats
+        .keys()
+        .chain(last_season_stats.keys())
+        .collect();
+
+    for player_id in player_ids {
+        let mut fields = doc! {};
+
+        if let Some(stats) = current_season_stats.get(player_id) {
+            let points = stats.goals + stats.assists;
+            fields.insert("game_played", stats.game_played);
+            fields.insert("goals", stats.goals);
+            fields.insert("assists", stats.assists);
+            fields.insert("points", points);
+            fields.insert("wins", stats.wins);
+            fields.insert("shutouts", stats.shutouts);
+            if stats.game_played > 0 {
+                fields.insert(
+                    "points_per_game",
+                    points as f64 / f64::from(stats.game_played),
+                );
+            }
+        }
+        if let Some(stats) = last_season_stats.get(player_id) {
+            fields.insert(
+                "last_season_stats",
+                to_bson(stats).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            );
+        }
+
+        collection
+            .update_one(doc! {"id": player_id}, doc! {"$set": fields}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    }
+
+    Ok(())
+}
+
+// Replace the synced schedule of every date of the season.
+async fn update_schedule(db: &DatabaseConnection, nhl_api: &NhlApiClient) -> Result<()> {
+    let collection = db.collection::<DailySchedule>("schedules");
+
+    for schedule in nhl_api.get_season_schedule().await? {
+        collection
+            .replace_one(
+                doc! {"date": &schedule.date},
+                &schedule,
+                ReplaceOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    }
+
+    Ok(())
+}
+
+// Update the eligible positions of the players in every pool they are part of.
+async fn update_pools_eligibility(
+    db: &DatabaseConnection,
+    players_positions: &[(u32, Vec<Position>)],
+) -> Result<()> {
+    let collection = db.collection::<Pool>("pools");
+
+    for (player_id, positions) in players_positions {
+        collection
+            .update_many(
+                doc! {format!("context.players.{}", player_id): {"$exists": true}},
+                doc! {"$set": {
+                    format!("context.players.{}.positions", player_id): to_bson(positions).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+                }},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    }
+
+    Ok(())
+}
+
+// Update the injury designation of the players in every pool they are part of.
+async fn update_pools_injuries(
+    db: &DatabaseConnection,
+    players_injuries: &[(u32, Option<InjuryStatus>)],
+) -> Result<()> {
+    let collection = db.collection::<Pool>("pools");
+
+    for (player_id, injury_status) in players_injuries {
+        collection
+            .update_many(
+                doc! {format!("context.players.{}", player_id): {"$exists": true}},
+                doc! {"$set": {
+                    format!("context.players.{}.injury_status", player_id): to_bson(injury_status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+                }},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    }
+
+    Ok(())
+}
+
+// Update the contract of the players in every pool they are part of.
+async fn update_pools_contracts(
+    db: &DatabaseConnection,
+    players_contracts: &[PlayerContract],
+) -> Result<()> {
+    let collection = db.collection::<Pool>("pools");
+
+    for contract in players_contracts {
+        collection
+            .update_many(
+                doc! {format!("context.players.{}", contract.player_id): {"$exists": true}},
+                doc! {"$set": {
+                    format!("context.players.{}.salary_cap", contract.player_id): contract.cap_hit,
+                    format!("context.players.{}.contract_expiration_season", contract.player_id): contract.expiration_season,
+                }},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    }
+
+    Ok(())
+}
+
+// Find the players matching the query, the excluded players are never returned.
+pub async fn find_players(
+    db: &DatabaseConnection,
+    params: GetPlayerQuery,
+    excluded_ids: &[u32],
+) -> Result<Vec<PlayerInfo>> {
+    let mut filter = doc! {};
+    if !excluded_ids.is_empty() {
+        filter.insert("id", doc! { "$nin": excluded_ids });
+    }
+    if let Some(active) = params.active {
+        filter.insert("active", active);
+    }
+    if let Some(positions) = params.positions {
+        filter.insert("position", doc! { "$in": positions });
+    }
+    if let Some(name) = params.name {
+        // The name is matched literally, its regex characters are escaped.
+        filter.insert(
+            "name",
+            doc! { "$regex": regex::escape(&name), "$options": "i" },
+        );
+    }
+    if let Some(team) = params.team {
+        filter.insert("team", team);
+    }
+
+    // Sorting options: default to sorting by `salary_cap` descending
+    let sort_field = params.sort.unwrap_or_else(|| "salary_cap".to_string());
+    let sort_value = if params.descending.unwrap_or(true) {
+        -1
+    } else {
+        1
+    };
+    let sort_order = doc! { sort_field: sort_value, "_id": 1 };
+
+    // Pagination: skip and limit
+    let skip = params.skip.unwrap_or(0);
+    let limit = params.limit.unwrap_or(20);
+
+    let find_options = FindOptions::builder()
+        .sort(sort_order)
+        .skip(Some(skip))
+        .limit(limit)
+        .build();
+
+    let collection = db.collection::<PlayerInfo>("players");
+    let players = collection
+        .find(filter, find_options)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+        .try_collect()
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(players)
+}
+
+#[async_trait]
+impl PlayersService for MongoPlayersService {
+    async fn get_players(&self, params: GetPlayerQuery) -> Result<Vec<PlayerInfo>> {
+        find_players(&self.db, params, &[]).await
     }
 
     async fn get_players_with_name(&self, name: &str) -> Result<Vec<PlayerInfo>> {
         let mut filter = doc! {};
-        filter.insert("name", doc! { "$regex": name, "$options": "i" });
+        filter.insert(
+            "name",
+            doc! { "$regex": regex::escape(name), "$options": "i" },
+        );
         let limit = 5;
 
         let find_options = FindOptions::builder().limit(limit).build();
@@ -83,4 +513,182 @@ impl PlayersService for MongoPlayersService {
 
         Ok(players)
     }
+
+    async fn get_player(&self, id: u32) -> Result<PlayerInfo> {
+        get_player_info(&self.db, id).await
+    }
+
+    async fn get_schedule(&self, date: &str) -> Result<DailySchedule> {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| AppError::CustomError {
+            msg: format!("The date '{}' is not in the YYYY-MM-DD format.", date),
+        })?;
+
+        get_daily_schedule(&self.db, date).await
+    }
+
+    // Pull the current roster of every NHL team and update the players collection.
+    // The players that are not part of a roster anymore are flagged as inactive.
+    async fn sync_players(&self) -> Result<usize> {
+        let mut synced_players: Vec<u32> = Vec::new();
+        let collection = self.db.collection::<PlayerInfo>("players");
+
+        let season = self.nhl_api.get_season_dates().await?.season;
+
+        // The eligible positions of the players are cumulated during the season.
+        let stored_players: HashMap<u32, PlayerInfo> = collection
+            .find(doc! {}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect::<Vec<PlayerInfo>>()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .into_iter()
+            .map(|player| (player.id, player))
+            .collect();
+        let mut updated_eligibilities = Vec::new();
+        let mut updated_injuries = Vec::new();
+        let mut ruled_out_players = Vec::new();
+        let mut failed_teams = Vec::new();
+
+        for (team_abbrev, team_id) in NHL_TEAMS {
+            // A team whose roster could not be fetched keeps its players until the next sync.
+            let roster = match self.nhl_api.get_team_roster(team_abbrev).await {
+                Ok(roster) => roster,
+                Err(e) => {
+                    warn!("Could not sync the roster of {}: {}", team_abbrev, e);
+                    failed_teams.push(team_id);
+                    continue;
+                }
+            };
+
+            let players = roster
+                .forwards
+                .iter()
+                .map(|player| (player, Position::F))
+                .chain(roster.defensemen.iter().map(|player| (player, Position::D)))
+                .chain(roster.goalies.iter().map(|player| (player, Position::G)));
+
+            for (player, position) in players {
+                let stored_player = stored_players.get(&player.id);
+                let eligible_positions = stored_player
+                    .map_or(vec![position.clone()], |stored_player| {
+                        stored_player.get_eligible_positions(&position, season)
+                    });
+
+                if stored_player.is_some_and(|stored_player| {
+                    stored_player.positions.as_ref() != Some(&eligible_positions)
+                }) {
+                    updated_eligibilities.push((player.id, eligible_positions.clone()));
+                }
+
+                let injury_status = player.get_injury_status();
+                let previous_injury_status =
+                    stored_player.and_then(|stored_player| stored_player.injury_status.as_ref());
+
+                if stored_player.is_some() && previous_injury_status != injury_status.as_ref() {
+                    updated_injuries.push((player.id, injury_status.clone()));
+                }
+                if injury_status
+                    .as_ref()
+                    .is_some_and(|injury_status| injury_status.is_ruled_out())
+                    && !previous_injury_status
+                        .is_some_and(|injury_status| injury_status.is_ruled_out())
+                {
+                    ruled_out_players.push((player.id, team_id));
+                }
+
+                upsert_roster_player(
+                    &self.db,
+                    player,
+                    team_id,
+                    position,
+                    &eligible_positions,
+                    season,
+                )
+                .await?;
+                synced_players.push(player.id);
+            }
+        }
+
+        collection
+            .update_many(
+                doc! {"id": doc!{"$nin": &synced_players}, "team": doc!{"$nin": &failed_teams}},
+                doc! {"$set": doc!{"active": false}},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        update_pools_eligibility(&self.db, &updated_eligibilities).await?;
+        update_pools_injuries(&self.db, &updated_injuries).await?;
+
+        // The sync is not failed when the warnings could not be sent.
+        if let Err(e) = self.notify_ruled_out_starters(&ruled_out_players).await {
+            warn!("{}", e);
+        }
+        update_players_stats(&self.db, &self.nhl_api, season).await?;
+        update_schedule(&self.db, &self.nhl_api).await?;
+
+        Ok(synced_players.len())
+    }
+
+    // Pull the contracts of the players from the contracts source and update the changed ones,
+    // in the players collection and in the pools. The poolers now over the salary cap are flagged.
+    async fn sync_contracts(&self) -> Result<usize> {
+        if !self.contracts_api.is_enabled() {
+            return Ok(0);
+        }
+
+        let collection = self.db.collection::<PlayerInfo>("players");
+        let contracts = self.contracts_api.get_contracts().await?;
+
+        let stored_players: HashMap<u32, PlayerInfo> = collection
+            .find(doc! {}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect::<Vec<PlayerInfo>>()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .into_iter()
+            .map(|player| (player.id, player))
+            .collect();
+
+        // Only the contracts of known players that changed are updated.
+        let updated_contracts: Vec<PlayerContract> = contracts
+            .into_iter()
+            .filter(|contract| {
+                stored_players
+                    .get(&contract.player_id)
+                    .is_some_and(|stored_player| {
+                        stored_player.salary_cap != Some(contract.cap_hit)
+                            || stored_player.contract_expiration_season
+                                != Some(contract.expiration_season)
+                    })
+            })
+            .collect();
+
+        for contract in &updated_contracts {
+            collection
+                .update_one(
+                    doc! {"id": contract.player_id},
+                    doc! {"$set": {
+                        "salary_cap": contract.cap_hit,
+                        "contract_expiration_season": contract.expiration_season,
+                    }},
+                    None,
+                )
+                .await
+                .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+        }
+
+        update_pools_contracts(&self.db, &updated_contracts).await?;
+
+        let updated_players: Vec<u32> = updated_contracts
+            .iter()
+            .map(|contract| contract.player_id)
+            .collect();
+        self.flag_cap_invalid_pools(&updated_players).await?;
+
+        Ok(updated_contracts.len())
+    }
 }
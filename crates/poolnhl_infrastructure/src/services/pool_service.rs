@@ -1,36 +1,71 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use async_trait::async_trait;
-use chrono::{Duration, NaiveDate};
+use chrono::{Duration, NaiveDate, Timelike, Utc};
 use futures::stream::TryStreamExt;
 use mongodb::bson::doc;
-use mongodb::bson::{to_bson, Document};
-use mongodb::options::{FindOneAndUpdateOptions, FindOneOptions, FindOptions, ReturnDocument};
-use mongodb::Collection;
-use poolnhl_interface::errors::AppError;
+use mongodb::bson::{from_document, to_bson, Bson, Document};
+use mongodb::options::{
+    FindOneAndUpdateOptions, FindOneOptions, FindOptions, IndexOptions, ReturnDocument,
+};
+use mongodb::{Collection, IndexModel};
+use poolnhl_interface::draft::model::{CommandResponse, DraftServerInfo};
+use poolnhl_interface::errors::{AppError, ErrorCode};
+use tracing::warn;
 
 use poolnhl_interface::errors::Result;
+use poolnhl_interface::notifications::model::NotificationType;
+use poolnhl_interface::players::model::{GetPlayerQuery, PlayerInfo};
+use poolnhl_interface::pool::authorization::{authorize, has_permission, Permission};
 use poolnhl_interface::pool::model::{
-    CompleteProtectionRequest, GenerateDynastyRequest, PoolContext, PoolState, END_SEASON_DATE,
-    POOL_CREATION_SEASON,
+    get_pool_changes, AdoptTeamRequest, CapReport, ClaimTeamRequest, CompleteProtectionRequest,
+    DailyPlayersPoints, DraftRecapPick, GenerateDynastyRequest, GenerateTeamClaimRequest,
+    ListPoolsQuery, MatchupSchedule, PoolContext, PoolEvent, PoolEventType, PoolFieldChange,
+    PoolHistoryQuery, PoolPlayerStats, PoolStandings, PoolState, PoolUpdate, PoolUpdateType,
+    PoolsSort, SeasonDates, Standing, TeamClaim, TestWebhookRequest, Trade, TradeAnalysis,
+    TradeBlockListing, TradeStatus, TransferOwnershipRequest, UpdateOrphanedTeamRequest,
+    UpdateTeamManagersRequest, UpdateTeamProfileRequest, UpdateTradeBlockRequest,
+    UpdateWatchlistRequest, VetoTradeRequest, Watchlist, MAX_POOL_TEMPLATES, POOL_SCHEMA_VERSION,
+    SERVER_ACTOR,
 };
 use poolnhl_interface::pool::{
     model::{
-        AddPlayerRequest, CreateTradeRequest, DeleteTradeRequest, FillSpotRequest,
-        MarkAsFinalRequest, ModifyRosterRequest, Pool, PoolCreationRequest, PoolDeletionRequest,
-        ProjectedPoolShort, ProtectPlayersRequest, RemovePlayerRequest, RespondTradeRequest,
-        UpdatePoolSettingsRequest, START_SEASON_DATE,
+        AddPlayerRequest, AutoLineupRequest, ClonePoolRequest, CreateTradeRequest,
+        DeletePoolTemplateRequest, DeleteTradeRequest, FillSpotRequest, MarkAsFinalRequest,
+        ModifyRosterRequest, Pool, PoolCreationRequest, PoolDeletionRequest, PoolPreview,
+        PoolTemplate, ProjectedPoolShort, ProtectPlayersRequest, RemovePlayerRequest,
+        RenamePoolRequest, RespondTradeRequest, SavePoolTemplateRequest, UpdatePoolSettingsRequest,
     },
     service::PoolService,
 };
-
-use crate::database_connection::DatabaseConnection;
+use poolnhl_interface::time_provider::time_provider;
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+use poolnhl_interface::webhooks::model::PoolLifecycleEvent;
+use serde_json::Value;
+
+use crate::cache::pool_cache;
+use crate::database_connection::{is_duplicate_key_error, DatabaseConnection};
+use crate::migrations::{get_schema_version, migrate_pool};
+use crate::nhl_api::NhlApiClient;
+use crate::services::archives_service::create_season_archive;
+use crate::services::daily_leaders_service::save_daily_leaders;
+use crate::services::invitations_service::is_user_invited;
+use crate::services::notifications_service::Notifier;
+use crate::services::players_service::{find_players, get_daily_schedule, get_pool_player_info};
+use crate::settings::NhlApi;
+use crate::transactions::Transaction;
 
 #[derive(Clone)]
 pub struct MongoPoolService {
     db: DatabaseConnection,
+    nhl_api: NhlApiClient,
+    draft_server_info: Arc<DraftServerInfo>,
+    notifier: Notifier,
 }
 
+// Read the pool from the database, the cache is refreshed with it.
+// The read-modify-write paths use it since they overwrite whole subdocuments of the pool.
 pub async fn get_optional_short_pool_by_name(
     collection: &Collection<Pool>,
     _name: &str,
@@ -39,276 +74,1622 @@ pub async fn get_optional_short_pool_by_name(
         .projection(doc! {"context.score_by_day": 0})
         .build();
 
-    let short_pool = collection
-        .find_one(doc! {"name": &_name}, find_option)
+    let documents = collection.clone_with_type::<Document>();
+
+    let mut short_pool = documents
+        .find_one(doc! {"name": &_name}, find_option.clone())
         .await
         .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
+    // The pools stored with an older schema are migrated before being deserialized.
+    if matches!(&short_pool, Some(pool) if get_schema_version(pool) < POOL_SCHEMA_VERSION) {
+        migrate_pool(collection, _name).await?;
+
+        short_pool = documents
+            .find_one(doc! {"name": &_name}, find_option)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    }
+
+    let short_pool = short_pool
+        .map(from_document::<Pool>)
+        .transpose()
+        .map_err(|e| AppError::BsonError { msg: e.to_string() })?;
+
+    if let Some(pool) = &short_pool {
+        pool_cache().insert(pool).await;
+    }
+
     Ok(short_pool)
 }
 
+fn get_update_pool_options() -> FindOneAndUpdateOptions {
+    FindOneAndUpdateOptions::builder()
+        .return_document(ReturnDocument::After)
+        .projection(doc! {"context.score_by_day": 0})
+        .build()
+}
+
 pub async fn update_pool(
     updated_field: Document,
     collection: &Collection<Pool>,
     pool_name: &str,
 ) -> Result<Pool> {
     // Update the fields in the mongoDB pool document.
-    let find_one_and_update_options = FindOneAndUpdateOptions::builder()
-        .return_document(ReturnDocument::After)
-        .projection(doc! {"context.score_by_day": 0})
-        .build();
+    // The pool name can be part of the update.
+    pool_cache().invalidate(pool_name).await;
 
-    collection
+    let updated_pool = collection
         .find_one_and_update(
             doc! {"name": pool_name},
             updated_field,
-            find_one_and_update_options,
+            get_update_pool_options(),
         )
         .await
         .map_err(|e| AppError::MongoError { msg: e.to_string() })?
-        .ok_or(AppError::CustomError {
+        .ok_or(AppError::CodedError {
+            code: ErrorCode::PoolNotFound,
             msg: format!("no pool found with name '{}'", pool_name),
-        })
+        })?;
+
+    pool_cache().insert(&updated_pool).await;
+    Ok(updated_pool)
 }
 
-pub async fn get_short_pool_by_name(
-    collection: &Collection<Pool>,
+// Append an event to the pool history.
+pub async fn insert_pool_event(
+    db: &DatabaseConnection,
     pool_name: &str,
-) -> Result<Pool> {
-    // Return the pool information without the score_by_day member
-    get_optional_short_pool_by_name(collection, pool_name)
-        .await?
-        .ok_or(AppError::CustomError {
-            msg: format!("no pool found with name '{}'", pool_name),
-        })
+    actor: &str,
+    event_type: PoolEventType,
+    changes: Vec<PoolFieldChange>,
+) -> Result<()> {
+    let collection = db.collection::<PoolEvent>("pool_events");
+
+    collection
+        .insert_one(PoolEvent::new(pool_name, actor, event_type, changes), None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
 }
 
-impl MongoPoolService {
-    pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
-    }
+// Update the fields in the mongoDB pool document
+// and record the modifications made by the actor in the pool history.
+pub async fn update_pool_with_history(
+    db: &DatabaseConnection,
+    updated_field: Document,
+    previous_pool: Pool,
+    actor: &str,
+    event_type: PoolEventType,
+) -> Result<Pool> {
+    update_pool_with_history_if(db, doc! {}, updated_field, previous_pool, actor, event_type).await
 }
 
-#[async_trait]
-impl PoolService for MongoPoolService {
-    async fn get_pool_by_name(&self, name: &str) -> Result<Pool> {
-        let collection = self.db.collection::<Pool>("pools");
+// Same as update_pool_with_history, but the pool is only updated if it still matches the condition
+// (i.g., the state read to validate the command). Otherwise, a PoolModified error is returned.
+pub async fn update_pool_with_history_if(
+    db: &DatabaseConnection,
+    condition: Document,
+    updated_field: Document,
+    previous_pool: Pool,
+    actor: &str,
+    event_type: PoolEventType,
+) -> Result<Pool> {
+    // The pool is not updated when its event could not be recorded.
+    let mut transaction = Transaction::start(db).await?;
+
+    let updated_pool = update_pool_with_history_in_transaction(
+        &mut transaction,
+        db,
+        condition,
+        updated_field,
+        previous_pool,
+        actor,
+        event_type,
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    pool_cache().insert(&updated_pool).await;
+    Ok(updated_pool)
+}
 
-        let pool = collection
-            .find_one(doc! {"name": name}, None)
-            .await
-            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+// Same as update_pool_with_history_if, as part of a transaction.
+// The cache needs to be refreshed by the caller once the transaction is committed.
+pub async fn update_pool_with_history_in_transaction(
+    transaction: &mut Transaction,
+    db: &DatabaseConnection,
+    condition: Document,
+    updated_field: Document,
+    mut previous_pool: Pool,
+    actor: &str,
+    event_type: PoolEventType,
+) -> Result<Pool> {
+    let updated_pool = update_pool_in_transaction_if(
+        transaction,
+        condition,
+        updated_field,
+        &db.collection::<Pool>("pools"),
+        &previous_pool.name,
+    )
+    .await?;
+
+    // In debug builds, an update breaking the invariants of the pool context is not committed.
+    if cfg!(debug_assertions) {
+        updated_pool.check_invariants()?;
+    }
 
-        pool.ok_or(AppError::CustomError {
-            msg: format!("no pool found with name '{}'", name),
-        })
+    // The score_by_day member is never part of the updated pool, it is not tracked in the history.
+    if let Some(context) = previous_pool.context.as_mut() {
+        context.score_by_day = None;
     }
 
-    async fn get_pool_by_name_with_range(
-        &self,
-        name: &str,
-        start_season_date: &str,
-        from_date_str: &str,
-    ) -> Result<Pool> {
-        let from_date = NaiveDate::parse_from_str(from_date_str, "%Y-%m-%d")
-            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+    transaction
+        .insert_one(
+            &db.collection::<PoolEvent>("pool_events"),
+            &PoolEvent::new(
+                &updated_pool.name,
+                actor,
+                event_type,
+                get_pool_changes(&previous_pool, &updated_pool)?,
+            ),
+        )
+        .await?;
 
-        let mut start_date = NaiveDate::parse_from_str(start_season_date, "%Y-%m-%d")
-            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+    Ok(updated_pool)
+}
 
-        // Projection will allow to filter all the date that the user did not want
-        // (All the date before the from date received will be ignore).
-        let mut projection = doc! {};
-        if from_date >= start_date {
-            loop {
-                let str_date = start_date.to_string();
+// Update the fields of the pool document as part of a transaction.
+// The cache needs to be refreshed by the caller once the transaction is committed.
+pub async fn update_pool_in_transaction(
+    transaction: &mut Transaction,
+    updated_field: Document,
+    collection: &Collection<Pool>,
+    pool_name: &str,
+) -> Result<Pool> {
+    update_pool_in_transaction_if(transaction, doc! {}, updated_field, collection, pool_name).await
+}
 
-                if str_date == *from_date_str {
-                    break;
-                }
-                projection.insert(format!("context.score_by_day.{}", str_date), 0);
-                start_date += Duration::days(1);
-            }
-        }
+async fn update_pool_in_transaction_if(
+    transaction: &mut Transaction,
+    condition: Document,
+    updated_field: Document,
+    collection: &Collection<Pool>,
+    pool_name: &str,
+) -> Result<Pool> {
+    pool_cache().invalidate(pool_name).await;
 
-        let find_option = FindOneOptions::builder().projection(projection).build();
-        let collection = self.db.collection::<Pool>("pools");
-        let pool = collection
-            .clone_with_type::<Pool>()
-            .find_one(doc! {"name": &name}, find_option)
-            .await
-            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    let is_conditional = !condition.is_empty();
+    let mut filter = doc! {"name": pool_name};
+    filter.extend(condition);
 
-        pool.ok_or(AppError::CustomError {
-            msg: format!("no pool found with name '{}'", name),
-        })
+    match transaction
+        .find_one_and_update(collection, filter, updated_field, get_update_pool_options())
+        .await?
+    {
+        Some(updated_pool) => Ok(updated_pool),
+        None if is_conditional => Err(AppError::CodedError {
+            code: ErrorCode::PoolModified,
+            msg: format!(
+                "the pool '{}' was modified by another request, retry.",
+                pool_name
+            ),
+        }),
+        None => Err(AppError::CodedError {
+            code: ErrorCode::PoolNotFound,
+            msg: format!("no pool found with name '{}'", pool_name),
+        }),
     }
+}
 
-    async fn list_pools(&self, season: u32) -> Result<Vec<ProjectedPoolShort>> {
-        let collection = self.db.collection::<Pool>("pools");
-        let find_option = FindOptions::builder()
-            .projection(doc! {"name": 1, "owner": 1, "status": 1, "season": 1})
-            .build();
+pub async fn delete_pool_by_name(db: &DatabaseConnection, pool_name: &str) -> Result<()> {
+    let delete_result = db
+        .collection::<Pool>("pools")
+        .delete_one(doc! {"name": pool_name}, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
-        let filter = doc! { "season": season };
+    pool_cache().invalidate(pool_name).await;
 
-        let cursor = collection
-            .clone_with_type::<ProjectedPoolShort>()
-            .find(filter, find_option)
-            .await
-            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    if delete_result.deleted_count == 0 {
+        return Err(AppError::CustomError {
+            msg: "The pool could not be deleted.".to_string(),
+        });
+    }
 
-        let pools = cursor
-            .try_collect()
-            .await
-            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    // The invitations to a deleted pool are not usable anymore.
+    db.collection::<Document>("invitations")
+        .delete_many(doc! {"pool_name": pool_name}, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
-        Ok(pools)
-    }
+    Ok(())
+}
 
-    async fn create_pool(&self, user_id: &str, req: PoolCreationRequest) -> Result<Pool> {
-        let collection = self.db.collection::<Pool>("pools");
+// Give the pool to another participant, the previous owner becomes an assistant.
+pub async fn transfer_pool_ownership(
+    db: &DatabaseConnection,
+    user_id: &str,
+    pool_name: &str,
+    new_owner_id: &str,
+) -> Result<Pool> {
+    let mut pool = get_short_pool_by_name(&db.collection::<Pool>("pools"), pool_name).await?;
+    let previous_pool = pool.clone();
 
-        // Create the default Pool class.
-        let pool = Pool::new(&req.pool_name, user_id, &req.settings);
+    pool.transfer_ownership(user_id, new_owner_id)?;
 
-        collection
-            .insert_one(&pool, None)
-            .await
-            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    let updated_fields = doc! {
+        "$set": doc!{
+            "owner": &pool.owner,
+            "settings.assistants": &pool.settings.assistants
+        }
+    };
+
+    update_pool_with_history(
+        db,
+        updated_fields,
+        previous_pool,
+        user_id,
+        PoolEventType::TransferOwnership,
+    )
+    .await
+}
 
-        Ok(pool)
+// The collections referencing a pool by its name.
+const POOL_NAME_REFERENCES: [&str; 11] = [
+    "invitations",
+    "notifications",
+    "notification_preferences",
+    "pool_events",
+    "team_claims",
+    "season_archives",
+    "achievements",
+    "webhook_subscriptions",
+    "webhook_deliveries",
+    "draft_recordings",
+    "watchlists",
+];
+
+// Rename a pool and the references to its name, the pool and its references are renamed in one transaction.
+pub async fn rename_pool(
+    db: &DatabaseConnection,
+    mut previous_pool: Pool,
+    new_pool_name: &str,
+    actor: &str,
+) -> Result<Pool> {
+    if new_pool_name.trim().is_empty() {
+        return Err(AppError::CustomError {
+            msg: "The pool name cannot be empty.".to_string(),
+        });
     }
 
-    async fn delete_pool(&self, user_id: &str, req: PoolDeletionRequest) -> Result<Pool> {
-        let collection = self.db.collection::<Pool>("pools");
-        let pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+    // The draft room of the pool is joined by its name, the connected users would lose it.
+    if matches!(previous_pool.status, PoolState::Draft) {
+        return Err(AppError::CodedError {
+            code: ErrorCode::InvalidPoolStatus,
+            msg: "The pool cannot be renamed during the draft.".to_string(),
+        });
+    }
 
-        pool.has_owner_privileges(user_id)?;
+    let collection = db.collection::<Pool>("pools");
 
-        let delete_result = collection
-            .delete_one(doc! {"name": req.pool_name}, None)
-            .await
-            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+    if get_optional_short_pool_by_name(&collection, new_pool_name)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::CodedError {
+            code: ErrorCode::PoolAlreadyExists,
+            msg: format!("a pool named '{}' already exists", new_pool_name),
+        });
+    }
 
-        if delete_result.deleted_count == 0 {
-            return Err(AppError::CustomError {
-                msg: "The pool could not be deleted.".to_string(),
-            });
-        }
+    let pool_name = previous_pool.name.clone();
+
+    // The dynasty seasons of the pool keep the name of their past and next seasons.
+    let dynasty_pool_names: Vec<String> = collection
+        .distinct(
+            "name",
+            doc! {"$or": [
+                {"settings.dynasty_settings.past_season_pool_name": &pool_name},
+                {"settings.dynasty_settings.next_season_pool_name": &pool_name},
+            ]},
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+        .iter()
+        .filter_map(|name| name.as_str().map(String::from))
+        .collect();
+
+    let mut transaction = Transaction::start(db).await?;
+
+    let updated_pool = update_pool_in_transaction(
+        &mut transaction,
+        doc! {"$set": {"name": new_pool_name}},
+        &collection,
+        &pool_name,
+    )
+    .await?;
+
+    transaction
+        .update_many(
+            &collection,
+            doc! {"settings.dynasty_settings.past_season_pool_name": &pool_name},
+            doc! {"$set": {"settings.dynasty_settings.past_season_pool_name.$": new_pool_name}},
+        )
+        .await?;
 
-        Ok(pool)
+    transaction
+        .update_many(
+            &collection,
+            doc! {"settings.dynasty_settings.next_season_pool_name": &pool_name},
+            doc! {"$set": {"settings.dynasty_settings.next_season_pool_name": new_pool_name}},
+        )
+        .await?;
+
+    for collection_name in POOL_NAME_REFERENCES {
+        transaction
+            .update_many(
+                &db.collection::<Document>(collection_name),
+                doc! {"pool_name": &pool_name},
+                doc! {"$set": {"pool_name": new_pool_name}},
+            )
+            .await?;
     }
 
-    async fn create_trade(&self, user_id: &str, req: &mut CreateTradeRequest) -> Result<Pool> {
-        // Create a trade and update the database
-        let collection = self.db.collection::<Pool>("pools");
-        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+    if let Some(context) = previous_pool.context.as_mut() {
+        context.score_by_day = None;
+    }
 
-        // Create the new trade in the pool
-        pool.create_trade(&mut req.trade, user_id)?;
+    transaction
+        .insert_one(
+            &db.collection::<PoolEvent>("pool_events"),
+            &PoolEvent::new(
+                &updated_pool.name,
+                actor,
+                PoolEventType::RenamePool,
+                get_pool_changes(&previous_pool, &updated_pool)?,
+            ),
+        )
+        .await?;
 
-        // Update the field in the pool
-        let updated_fields = doc! {
-            "$set": doc!{
-                "trades": to_bson(&pool.trades).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
-            }
-        };
+    transaction.commit().await?;
 
-        update_pool(updated_fields, &collection, &req.pool_name).await
+    // The old name could have been cached again by a read made during the transaction.
+    pool_cache().invalidate(&pool_name).await;
+    pool_cache().insert(&updated_pool).await;
+    for dynasty_pool_name in &dynasty_pool_names {
+        pool_cache().invalidate(dynasty_pool_name).await;
     }
 
-    async fn delete_trade(&self, user_id: &str, req: DeleteTradeRequest) -> Result<Pool> {
-        let collection = self.db.collection::<Pool>("pools");
+    // The chat collection is capped, its messages cannot be written in a transaction or change size.
+    // They are copied with the new pool name, the old ones are dropped when the collection is full.
+    if let Err(e) = copy_chat_messages(db, &pool_name, new_pool_name).await {
+        warn!(
+            "Could not copy the chat messages of the pool {}: {}",
+            pool_name, e
+        );
+    }
 
-        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+    Ok(updated_pool)
+}
 
-        // Delete the trade
-        pool.delete_trade(user_id, req.trade_id)?;
+async fn copy_chat_messages(
+    db: &DatabaseConnection,
+    pool_name: &str,
+    new_pool_name: &str,
+) -> Result<()> {
+    let collection = db.collection::<Document>("chat_messages");
 
-        // Update the field in the pool
-        let updated_fields = doc! {
-            "$set": doc!{
-                "trades": to_bson(&pool.trades).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
-            }
-        };
+    let find_option = FindOptions::builder()
+        .sort(doc! {"date_created": 1})
+        .projection(doc! {"_id": 0})
+        .build();
+
+    let mut messages: Vec<Document> = collection
+        .find(doc! {"pool_name": pool_name}, find_option)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+        .try_collect()
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
-        update_pool(updated_fields, &collection, &req.pool_name).await
+    if messages.is_empty() {
+        return Ok(());
     }
 
-    async fn respond_trade(&self, user_id: &str, req: RespondTradeRequest) -> Result<Pool> {
-        let collection = self.db.collection::<Pool>("pools");
+    for message in &mut messages {
+        message.insert("pool_name", new_pool_name);
+    }
 
-        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+    collection
+        .insert_many(messages, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
-        // repond the trade
-        pool.respond_trade(user_id, req.is_accepted, req.trade_id)?;
+    Ok(())
+}
 
-        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
-            msg: "pool context does not exist.".to_string(),
-        })?;
+// Create the indexes used to list and search the pools.
+pub async fn create_pool_indexes(db: &DatabaseConnection) -> Result<()> {
+    let collection = db.collection::<Pool>("pools");
+
+    let indexes = vec![
+        IndexModel::builder()
+            .keys(doc! {"name": 1})
+            .options(IndexOptions::builder().unique(true).build())
+            .build(),
+        IndexModel::builder().keys(doc! {"name": "text"}).build(),
+        IndexModel::builder()
+            .keys(doc! {"season": 1, "status": 1})
+            .build(),
+        IndexModel::builder().keys(doc! {"owner": 1}).build(),
+        IndexModel::builder()
+            .keys(doc! {"participants.id": 1, "season": -1})
+            .build(),
+    ];
 
-        // Update the field in the pool
-        let updated_fields = doc! {
-            "$set": doc!{
-                "trades": to_bson(&pool.trades).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
-                "context.pooler_roster": to_bson(&context.pooler_roster ).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
-                "context.tradable_picks": to_bson(&context.tradable_picks ).map_err(|e| AppError::MongoError { msg: e.to_string() })?
-            }
-        };
+    collection
+        .create_indexes(indexes, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
-        update_pool(updated_fields, &collection, &req.pool_name).await
-    }
+    // The history of a pool is listed from its most recent event.
+    db.collection::<PoolEvent>("pool_events")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"pool_name": 1, "date_created": -1})
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
-    async fn fill_spot(&self, user_id: &str, req: FillSpotRequest) -> Result<Pool> {
-        let collection = self.db.collection::<Pool>("pools");
-        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+    let claims_indexes = vec![
+        IndexModel::builder()
+            .keys(doc! {"code": 1})
+            .options(IndexOptions::builder().unique(true).build())
+            .build(),
+        IndexModel::builder()
+            .keys(doc! {"pool_name": 1, "participant_id": 1})
+            .build(),
+    ];
+
+    db.collection::<TeamClaim>("team_claims")
+        .create_indexes(claims_indexes, None)
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
-        // Fill the player into the starting roster.
-        pool.fill_spot(user_id, &req.filled_spot_user_id, req.player_id)?;
+    // A pooler has one watchlist per pool.
+    db.collection::<Watchlist>("watchlists")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"user_id": 1, "pool_name": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
-        // Update fields with the filled spot
+    // The templates of a user are listed by name.
+    db.collection::<PoolTemplate>("pool_templates")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"user_id": 1, "name": 1})
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
 
-        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
-            msg: "pool context does not exist.".to_string(),
-        })?;
+    Ok(())
+}
 
-        // Update the field in the pool
-        let updated_fields = doc! {
-            "$set": doc!{
-                "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?
-            }
-        };
+pub async fn get_short_pool_by_name(
+    collection: &Collection<Pool>,
+    pool_name: &str,
+) -> Result<Pool> {
+    // Return the pool information without the score_by_day member
+    get_optional_short_pool_by_name(collection, pool_name)
+        .await?
+        .ok_or(AppError::CodedError {
+            code: ErrorCode::PoolNotFound,
+            msg: format!("no pool found with name '{}'", pool_name),
+        })
+}
 
-        update_pool(updated_fields, &collection, &req.pool_name).await
+pub async fn get_cached_short_pool_by_name(
+    collection: &Collection<Pool>,
+    pool_name: &str,
+) -> Result<Pool> {
+    // Same as get_short_pool_by_name but the cached pool is returned when there is one.
+    // Only the read-only paths use it, the cached pool can be stale by up to its time to live.
+    if let Some(pool) = pool_cache().get(pool_name).await {
+        return Ok(pool);
     }
 
-    async fn add_player(&self, user_id: &str, req: AddPlayerRequest) -> Result<Pool> {
-        let collection = self.db.collection::<Pool>("pools");
-        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
-
-        // Add the player into the reservist of a pooler
-        pool.add_player(user_id, &req.added_player_user_id, &req.player)?;
+    get_short_pool_by_name(collection, pool_name).await
+}
 
-        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+impl MongoPoolService {
+    pub fn new(
+        db: DatabaseConnection,
+        nhl_api: &NhlApi,
+        draft_server_info: Arc<DraftServerInfo>,
+        notifier: Notifier,
+    ) -> Self {
+        Self {
+            db,
+            nhl_api: NhlApiClient::new(nhl_api),
+            draft_server_info,
+            notifier,
+        }
+    }
+
+    async fn get_pool_template(&self, user_id: &str, template_id: &str) -> Result<PoolTemplate> {
+        // The templates are private to the user that saved them.
+        self.db
+            .collection::<PoolTemplate>("pool_templates")
+            .find_one(doc! {"id": template_id, "user_id": user_id}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .ok_or_else(|| AppError::CustomError {
+                msg: format!("no template found with id '{}'", template_id),
+            })
+    }
+
+    async fn notify_watchers(&self, pool_name: &str, player_id: u32, excluded_user_id: &str) {
+        // Notify the poolers watching a player that just became a free agent.
+        let watchlists: Vec<Watchlist> = match self
+            .db
+            .collection::<Watchlist>("watchlists")
+            .find(
+                doc! {"pool_name": pool_name, "player_ids": player_id, "user_id": {"$ne": excluded_user_id}},
+                None,
+            )
+            .await
+        {
+            Ok(cursor) => cursor.try_collect().await.unwrap_or_default(),
+            Err(e) => {
+                warn!("{}", e);
+                return;
+            }
+        };
+
+        let notified_users: Vec<String> = watchlists
+            .into_iter()
+            .map(|watchlist| watchlist.user_id)
+            .collect();
+
+        if notified_users.is_empty() {
+            return;
+        }
+
+        if let Err(e) = self
+            .notifier
+            .notify_users(
+                &notified_users,
+                pool_name,
+                NotificationType::WatchedPlayerAvailable,
+            )
+            .await
+        {
+            warn!("{}", e);
+        }
+    }
+
+    async fn get_pool_with_totals(&self, name: &str) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_cached_short_pool_by_name(&collection, name).await?;
+
+        // The totals are rebuilt by the next cumulation, until then compute them from the daily scores.
+        if pool
+            .context
+            .as_ref()
+            .is_some_and(|context| context.are_totals_missing())
+        {
+            pool = self.get_pool_by_name(name).await?;
+
+            if let Some(context) = pool.context.as_mut() {
+                context.rebuild_cumulative_totals(&pool.settings);
+            }
+        }
+
+        Ok(pool)
+    }
+
+    fn post_accepted_trades(&self, previous_trades: &Option<Vec<Trade>>, updated_pool: &Pool) {
+        // Post the trades that were just accepted to the pool webhook.
+        for trade in updated_pool.trades.iter().flatten() {
+            let was_accepted = previous_trades.iter().flatten().any(|previous_trade| {
+                previous_trade.id == trade.id
+                    && matches!(previous_trade.status, TradeStatus::ACCEPTED)
+            });
+
+            if !matches!(trade.status, TradeStatus::ACCEPTED) || was_accepted {
+                continue;
+            }
+
+            let participants: Vec<String> = trade
+                .get_participants()
+                .iter()
+                .map(|participant| updated_pool.get_participant_name(participant))
+                .collect();
+
+            self.notifier.post_to_webhook(
+                updated_pool,
+                &format!("Trade accepted between {}.", participants.join(", ")),
+            );
+            self.notifier.fire_pool_event(
+                updated_pool,
+                PoolLifecycleEvent::TradeAccepted,
+                serde_json::json!({ "trade": trade }),
+            );
+        }
+    }
+
+    // Cumulate the points of the dates not cumulated yet for a pool in progress.
+    // The players points fetched for a date are kept for the next pools.
+    async fn cumulate_pool_daily_points(
+        &self,
+        collection: &Collection<Pool>,
+        mut pool: Pool,
+        today: &str,
+        yesterday: NaiveDate,
+        players_points_by_day: &mut HashMap<String, Option<DailyPlayersPoints>>,
+    ) -> Result<()> {
+        // Each pool is cumulated during its own season dates.
+        let start_season_date = NaiveDate::parse_from_str(&pool.season_start, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+        let end_season_date = NaiveDate::parse_from_str(&pool.season_end, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+        let is_yesterday_in_season = start_season_date <= yesterday && yesterday <= end_season_date;
+
+        let Some(context) = pool.context.as_mut() else {
+            return Ok(());
+        };
+
+        let previous_cumulative_totals = to_bson(&context.cumulative_totals)
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+        let previous_players_totals = to_bson(&context.players_totals)
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        let mut dates = context.get_dates_to_cumulate(today);
+        if is_yesterday_in_season && !dates.contains(&yesterday.to_string()) {
+            dates.push(yesterday.to_string());
+        }
+
+        let mut updated_fields = doc! {};
+
+        for date in dates {
+            if !players_points_by_day.contains_key(&date) {
+                let daily_players_points = self.nhl_api.get_daily_players_points(&date).await?;
+
+                // The leaders of the date are saved once its games are completed.
+                if let Some(daily_players_points) = &daily_players_points {
+                    if let Err(e) = save_daily_leaders(&self.db, &date, daily_players_points).await
+                    {
+                        warn!("{}", e);
+                    }
+                }
+                players_points_by_day.insert(date.clone(), daily_players_points);
+            }
+
+            let Some(Some(daily_players_points)) = players_points_by_day.get(&date) else {
+                continue;
+            };
+
+            let get_daily_roster_points = |context: &PoolContext| {
+                to_bson(
+                    &context
+                        .score_by_day
+                        .as_ref()
+                        .and_then(|score_by_day| score_by_day.get(&date)),
+                )
+                .map_err(|e| AppError::MongoError { msg: e.to_string() })
+            };
+
+            // A date cumulated again with the same points is not written.
+            let previous_daily_roster_points = get_daily_roster_points(context)?;
+            context.cumulate_daily_roster_points(&date, daily_players_points, &pool.settings);
+            let daily_roster_points = get_daily_roster_points(context)?;
+
+            if daily_roster_points != previous_daily_roster_points {
+                updated_fields.insert(
+                    format!("context.score_by_day.{}", date),
+                    daily_roster_points,
+                );
+            }
+        }
+
+        // The totals of the pools created before they existed or with updated scoring settings are rebuilt once.
+        if context.are_totals_missing() {
+            context.rebuild_cumulative_totals(&pool.settings);
+        }
+
+        // The totals are only written when they changed.
+        let cumulative_totals = to_bson(&context.cumulative_totals)
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+        let players_totals = to_bson(&context.players_totals)
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        if cumulative_totals != previous_cumulative_totals {
+            updated_fields.insert("context.cumulative_totals", cumulative_totals);
+        }
+        if players_totals != previous_players_totals {
+            updated_fields.insert("context.players_totals", players_totals);
+        }
+
+        // Record the results of the head-to-head weeks that are now completed.
+        let is_week_completed = context.update_matchup_results(&pool.settings, today);
+        if is_week_completed {
+            updated_fields.insert(
+                "context.matchup_schedule",
+                to_bson(&context.matchup_schedule)
+                    .map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            );
+        }
+
+        if !updated_fields.is_empty() {
+            // Update the fields in the mongoDB pool document.
+            update_pool(doc! {"$set": updated_fields}, collection, &pool.name).await?;
+            self.publish_pool_update(&pool, PoolUpdate::new(&pool, PoolUpdateType::Score));
+        }
+
+        // Post the standings to the pool webhook once a head-to-head week is completed.
+        if is_week_completed {
+            self.notifier.track_achievements(&pool);
+
+            if let (Some(head_to_head_settings), Some(matchup_schedule)) = (
+                &pool.settings.head_to_head_settings,
+                pool.context
+                    .as_ref()
+                    .and_then(|context| context.matchup_schedule.as_ref()),
+            ) {
+                let standings: Vec<String> = matchup_schedule
+                    .get_standings(head_to_head_settings)
+                    .iter()
+                    .enumerate()
+                    .map(|(rank, standing)| {
+                        format!(
+                            "{}. {} {}-{}-{} ({} pts)",
+                            rank + 1,
+                            pool.get_participant_name(&standing.participant),
+                            standing.wins,
+                            standing.losses,
+                            standing.ties,
+                            standing.points
+                        )
+                    })
+                    .collect();
+
+                self.notifier.post_to_webhook(
+                    &pool,
+                    &format!(
+                        "Standings of the pool {}:\n{}",
+                        pool.name,
+                        standings.join("\n")
+                    ),
+                );
+            }
+        }
+
+        // Promote the reservists of the poolers with the auto lineup enabled for the coming day.
+        let previous_pool = pool.clone();
+
+        if pool.auto_fill_lineups()? {
+            let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+                msg: "pool context does not exist.".to_string(),
+            })?;
+
+            let updated_fields = doc! {
+                "$set": doc!{
+                    "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                }
+            };
+
+            self.update_pool_and_publish(
+                updated_fields,
+                previous_pool,
+                SERVER_ACTOR,
+                PoolEventType::AutoFillLineup,
+            )
+            .await?;
+        }
+
+        // Remind the poolers the day before a roster modification date.
+        let tomorrow = (time_provider().today() + Duration::days(1)).to_string();
+
+        if pool.settings.roster_modification_date.contains(&tomorrow) {
+            let participants: Vec<String> = pool
+                .participants
+                .iter()
+                .map(|participant| participant.id.clone())
+                .collect();
+
+            if let Err(e) = self
+                .notifier
+                .notify_users(&participants, &pool.name, NotificationType::RosterDeadline)
+                .await
+            {
+                warn!("{}", e);
+            }
+        }
+
+        // Keep track of the cap usage of the poolers for the coming day.
+        if let Some(context) = pool.context.as_mut() {
+            if context.record_cap_snapshot(today, &pool.settings) {
+                if let Some(daily_cap) = context
+                    .cap_by_day
+                    .as_ref()
+                    .and_then(|cap_by_day| cap_by_day.get(today))
+                {
+                    update_pool(
+                        doc! {"$set": {
+                            format!("context.cap_by_day.{}", today): to_bson(daily_cap).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+                        }},
+                        collection,
+                        &pool.name,
+                    )
+                    .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Complete the trades of a pool whose review period is over.
+    async fn complete_pool_trade_reviews(&self, mut pool: Pool, now: i64) -> Result<()> {
+        let previous_pool = pool.clone();
+        let previous_trades = pool.trades.clone();
+
+        if !pool.complete_trade_reviews(now) {
+            return Ok(());
+        }
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "trades": to_bson(&pool.trades).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.pooler_roster": to_bson(&context.pooler_roster ).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.tradable_picks": to_bson(&context.tradable_picks ).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.future_tradable_picks": to_bson(&context.future_tradable_picks).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.conditional_picks": to_bson(&context.conditional_picks).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        let updated_pool = self
+            .update_pool_and_publish(
+                updated_fields,
+                previous_pool,
+                SERVER_ACTOR,
+                PoolEventType::CompleteTradeReview,
+            )
+            .await?;
+
+        self.post_accepted_trades(&previous_trades, &updated_pool);
+
+        Ok(())
+    }
+
+    // Expire the trades of a pool not answered in time and notify their poolers.
+    async fn expire_pool_trades(&self, mut pool: Pool, now: i64) -> Result<()> {
+        let previous_pool = pool.clone();
+
+        let expired_trades = pool.expire_trades(now);
+        if expired_trades.is_empty() {
+            return Ok(());
+        }
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "trades": to_bson(&pool.trades).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            }
+        };
+
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            SERVER_ACTOR,
+            PoolEventType::ExpireTrades,
+        )
+        .await?;
+
+        // Notify every pooler involved in the expired trades.
+        let mut notified_users: Vec<String> = expired_trades
+            .iter()
+            .flat_map(|trade| trade.get_participants())
+            .collect();
+        notified_users.sort();
+        notified_users.dedup();
+
+        if let Err(e) = self
+            .notifier
+            .notify_users(&notified_users, &pool.name, NotificationType::TradeExpired)
+            .await
+        {
+            warn!("{}", e);
+        }
+
+        Ok(())
+    }
+
+    async fn update_pool_and_publish(
+        &self,
+        updated_fields: Document,
+        previous_pool: Pool,
+        actor: &str,
+        event_type: PoolEventType,
+    ) -> Result<Pool> {
+        // Update the pool with its history and push the trade and roster updates to the pool listeners.
+        let update_type = event_type.get_update_type();
+        let updated_pool =
+            update_pool_with_history(&self.db, updated_fields, previous_pool, actor, event_type)
+                .await?;
+
+        if let Some(update_type) = update_type {
+            self.publish_pool_update(&updated_pool, PoolUpdate::new(&updated_pool, update_type));
+        }
+
+        Ok(updated_pool)
+    }
+
+    fn publish_pool_update(&self, pool: &Pool, pool_update: PoolUpdate) {
+        match serde_json::to_string(&pool_update) {
+            Ok(message) => {
+                if let Err(e) = self
+                    .draft_server_info
+                    .publish_pool_update(&pool.name, message)
+                {
+                    warn!("{}", e);
+                }
+            }
+            Err(e) => warn!("{}", e),
+        }
+    }
+
+    async fn get_season_dates(&self) -> Result<SeasonDates> {
+        // Use the current NHL season dates, a pool is not created with guessed dates if the NHL api is not available.
+        self.nhl_api
+            .get_season_dates()
+            .await
+            .map_err(|e| AppError::CodedError {
+                code: ErrorCode::ExternalServiceError,
+                msg: format!("Could not fetch the NHL season dates: {}", e),
+            })
+    }
+}
+
+#[async_trait]
+impl PoolService for MongoPoolService {
+    async fn get_pool_by_name(&self, name: &str) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let pool = collection
+            .find_one(doc! {"name": name}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        pool.ok_or(AppError::CodedError {
+            code: ErrorCode::PoolNotFound,
+            msg: format!("no pool found with name '{}'", name),
+        })
+    }
+
+    async fn get_partial_pool_by_name(&self, name: &str, paths: &[&str]) -> Result<Value> {
+        // Only fetch the requested paths of the pool document.
+        let mut projection = doc! {"_id": 0};
+        for path in paths {
+            projection.insert(*path, 1);
+        }
+
+        let find_option = FindOneOptions::builder().projection(projection).build();
+        let collection = self.db.collection::<Document>("pools");
+
+        let pool = collection
+            .find_one(doc! {"name": name}, find_option)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .ok_or(AppError::CodedError {
+                code: ErrorCode::PoolNotFound,
+                msg: format!("no pool found with name '{}'", name),
+            })?;
+
+        serde_json::to_value(pool).map_err(|e| AppError::ParseError { msg: e.to_string() })
+    }
+
+    async fn get_pool_by_name_with_range(
+        &self,
+        name: &str,
+        start_season_date: &str,
+        from_date_str: &str,
+    ) -> Result<Pool> {
+        let from_date = NaiveDate::parse_from_str(from_date_str, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        let mut start_date = NaiveDate::parse_from_str(start_season_date, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        // Projection will allow to filter all the date that the user did not want
+        // (All the date before the from date received will be ignore).
+        let mut projection = doc! {};
+        if from_date >= start_date {
+            loop {
+                let str_date = start_date.to_string();
+
+                if str_date == *from_date_str {
+                    break;
+                }
+                projection.insert(format!("context.score_by_day.{}", str_date), 0);
+                start_date += Duration::days(1);
+            }
+        }
+
+        let find_option = FindOneOptions::builder().projection(projection).build();
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = collection
+            .clone_with_type::<Pool>()
+            .find_one(doc! {"name": &name}, find_option)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        pool.ok_or(AppError::CodedError {
+            code: ErrorCode::PoolNotFound,
+            msg: format!("no pool found with name '{}'", name),
+        })
+    }
+
+    async fn list_pools(
+        &self,
+        season: u32,
+        query: ListPoolsQuery,
+    ) -> Result<Vec<ProjectedPoolShort>> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        // The private and unlisted pools are never listed.
+        let mut filter = doc! {
+            "season": season,
+            "settings.visibility": {"$nin": ["Private", "Unlisted"]},
+        };
+
+        if let Some(status) = &query.status {
+            filter.insert(
+                "status",
+                to_bson(status).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            );
+        }
+
+        if let Some(owner) = &query.owner {
+            filter.insert("owner", owner.to_string());
+        }
+
+        if let Some(has_space) = query.has_space {
+            let number_participants = doc! {"$size": "$participants"};
+            filter.insert(
+                "$expr",
+                if has_space {
+                    doc! {"$lt": [number_participants, "$settings.number_poolers"]}
+                } else {
+                    doc! {"$gte": [number_participants, "$settings.number_poolers"]}
+                },
+            );
+        }
+
+        if let Some(draft_date) = &query.draft_date {
+            filter.insert("settings.draft_date", draft_date);
+        }
+
+        if let Some(search) = &query.search {
+            filter.insert("$text", doc! {"$search": search});
+        }
+
+        // The pool name breaks the ties so the pagination cursor is always unique.
+        let sort = query.sort.unwrap_or(PoolsSort::Name);
+        let sort_doc = match sort {
+            PoolsSort::CreatedDate => doc! {"_id": -1},
+            PoolsSort::Name => doc! {"name": 1},
+            PoolsSort::Season => doc! {"season": -1, "name": 1},
+        };
+
+        // Only return the pools that come after the last pool of the previous page.
+        if let Some(after) = &query.after {
+            let after_pool = collection
+                .clone_with_type::<Document>()
+                .find_one(
+                    doc! {"name": after},
+                    FindOneOptions::builder()
+                        .projection(doc! {"_id": 1, "season": 1})
+                        .build(),
+                )
+                .await
+                .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+                .ok_or_else(|| AppError::CodedError {
+                    code: ErrorCode::PoolNotFound,
+                    msg: format!("no pool found with name '{}'", after),
+                })?;
+
+            match sort {
+                PoolsSort::CreatedDate => {
+                    let after_id = after_pool
+                        .get_object_id("_id")
+                        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+                    filter.insert("_id", doc! {"$lt": after_id});
+                }
+                PoolsSort::Name => {
+                    filter.insert("name", doc! {"$gt": after});
+                }
+                PoolsSort::Season => {
+                    let after_season = after_pool.get("season").cloned().unwrap_or_default();
+                    filter.insert(
+                        "$or",
+                        vec![
+                            doc! {"season": {"$lt": after_season.clone()}},
+                            doc! {"season": after_season, "name": {"$gt": after}},
+                        ],
+                    );
+                }
+            }
+        }
+
+        let find_option = FindOptions::builder()
+            .projection(doc! {"name": 1, "owner": 1, "status": 1, "season": 1})
+            .sort(sort_doc)
+            .limit(query.limit.unwrap_or(50).clamp(1, 200))
+            .build();
+
+        let cursor = collection
+            .clone_with_type::<ProjectedPoolShort>()
+            .find(filter, find_option)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        let pools = cursor
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(pools)
+    }
+
+    async fn create_pool(&self, user_id: &str, req: PoolCreationRequest) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        // Create the default Pool class.
+        let settings = match (&req.template_id, req.settings) {
+            (Some(template_id), _) => self.get_pool_template(user_id, template_id).await?.settings,
+            (None, Some(settings)) => settings,
+            (None, None) => {
+                return Err(AppError::CustomError {
+                    msg: "The settings or a template are required to create a pool.".to_string(),
+                })
+            }
+        };
+
+        settings.validate()?;
+
+        let season_dates = self.get_season_dates().await?;
+        let pool = Pool::new(&req.pool_name, user_id, &settings, &season_dates);
+
+        // The unique index on the pool name rejects a pool named like an existing one.
+        collection.insert_one(&pool, None).await.map_err(|e| {
+            if is_duplicate_key_error(&e) {
+                AppError::CodedError {
+                    code: ErrorCode::PoolAlreadyExists,
+                    msg: format!("a pool named '{}' already exists", pool.name),
+                }
+            } else {
+                AppError::MongoError { msg: e.to_string() }
+            }
+        })?;
+
+        insert_pool_event(
+            &self.db,
+            &pool.name,
+            user_id,
+            PoolEventType::CreatePool,
+            Vec::new(),
+        )
+        .await?;
+
+        self.notifier.fire_pool_event(
+            &pool,
+            PoolLifecycleEvent::PoolCreated,
+            serde_json::json!({ "settings": pool.settings }),
+        );
+
+        Ok(pool)
+    }
+
+    async fn clone_pool(&self, user_id: &str, req: ClonePoolRequest) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+
+        // Only the owner can reuse the settings of the pool.
+        authorize(user_id, &pool, Permission::ManagePool)?;
+
+        if get_optional_short_pool_by_name(&collection, &req.new_pool_name)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::CodedError {
+                code: ErrorCode::PoolAlreadyExists,
+                msg: format!("a pool named '{}' already exists", req.new_pool_name),
+            });
+        }
+
+        self.create_pool(
+            user_id,
+            PoolCreationRequest {
+                pool_name: req.new_pool_name,
+                settings: Some(pool.settings.get_reusable_settings()),
+                template_id: None,
+            },
+        )
+        .await
+    }
+
+    async fn list_pool_templates(&self, user_id: &str) -> Result<Vec<PoolTemplate>> {
+        self.db
+            .collection::<PoolTemplate>("pool_templates")
+            .find(
+                doc! {"user_id": user_id},
+                FindOptions::builder().sort(doc! {"name": 1}).build(),
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    async fn save_pool_template(
+        &self,
+        user_id: &str,
+        req: SavePoolTemplateRequest,
+    ) -> Result<PoolTemplate> {
+        req.settings.validate()?;
+
+        let collection = self.db.collection::<PoolTemplate>("pool_templates");
+
+        let Some(template_id) = &req.template_id else {
+            let number_templates = collection
+                .count_documents(doc! {"user_id": user_id}, None)
+                .await
+                .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+            if number_templates >= MAX_POOL_TEMPLATES {
+                return Err(AppError::CustomError {
+                    msg: format!(
+                        "A user cannot save more than {} templates.",
+                        MAX_POOL_TEMPLATES
+                    ),
+                });
+            }
+
+            let template = PoolTemplate::new(user_id, &req);
+
+            collection
+                .insert_one(&template, None)
+                .await
+                .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+            return Ok(template);
+        };
+
+        let mut template = self.get_pool_template(user_id, template_id).await?;
+        template.name = req.name;
+        template.settings = req.settings.get_reusable_settings();
+        template.date_updated = Utc::now().timestamp_millis();
+
+        collection
+            .update_one(
+                doc! {"id": template_id, "user_id": user_id},
+                doc! {"$set": {
+                    "name": &template.name,
+                    "settings": to_bson(&template.settings).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                    "date_updated": template.date_updated,
+                }},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(template)
+    }
+
+    async fn delete_pool_template(
+        &self,
+        user_id: &str,
+        req: DeletePoolTemplateRequest,
+    ) -> Result<()> {
+        let result = self
+            .db
+            .collection::<PoolTemplate>("pool_templates")
+            .delete_one(doc! {"id": &req.template_id, "user_id": user_id}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        if result.deleted_count == 0 {
+            return Err(AppError::CustomError {
+                msg: format!("no template found with id '{}'", req.template_id),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn delete_pool(&self, user_id: &str, req: PoolDeletionRequest) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+
+        authorize(user_id, &pool, Permission::ManagePool)?;
+
+        delete_pool_by_name(&self.db, &pool.name).await?;
+
+        insert_pool_event(
+            &self.db,
+            &pool.name,
+            user_id,
+            PoolEventType::DeletePool,
+            Vec::new(),
+        )
+        .await?;
+
+        Ok(pool)
+    }
+
+    async fn create_trade(&self, user_id: &str, req: &mut CreateTradeRequest) -> Result<Pool> {
+        // Create a trade and update the database
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
+
+        // Create the new trade in the pool
+        pool.create_trade(&mut req.trade, user_id)?;
+
+        // Update the field in the pool
+        let updated_fields = doc! {
+            "$set": doc!{
+                "trades": to_bson(&pool.trades).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            }
+        };
+
+        let updated_pool = self
+            .update_pool_and_publish(
+                updated_fields,
+                previous_pool,
+                user_id,
+                PoolEventType::CreateTrade,
+            )
+            .await?;
+
+        // Notify the other poolers involved in the trade.
+        let notified_users: Vec<String> = req
+            .trade
+            .get_participants()
+            .into_iter()
+            .filter(|participant| *participant != req.trade.proposed_by)
+            .collect();
+
+        if let Err(e) = self
+            .notifier
+            .notify_users(
+                &notified_users,
+                &req.pool_name,
+                NotificationType::TradeProposed,
+            )
+            .await
+        {
+            warn!("{}", e);
+        }
+
+        Ok(updated_pool)
+    }
+
+    async fn delete_trade(&self, user_id: &str, req: DeleteTradeRequest) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
+
+        // Delete the trade
+        pool.delete_trade(user_id, req.trade_id)?;
+
+        // Update the field in the pool
+        let updated_fields = doc! {
+            "$set": doc!{
+                "trades": to_bson(&pool.trades).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            }
+        };
+
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::DeleteTrade,
+        )
+        .await
+    }
+
+    async fn respond_trade(&self, user_id: &str, req: RespondTradeRequest) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
+
+        if let Some(mut counter_trade) = req.counter_trade {
+            // Counter the trade with a new trade.
+            pool.counter_trade(user_id, req.trade_id, &mut counter_trade)?;
+
+            let notified_user = pool
+                .trades
+                .iter()
+                .flatten()
+                .find(|trade| trade.id == req.trade_id)
+                .map(|trade| trade.proposed_by.clone())
+                .unwrap_or_default();
+
+            let updated_fields = doc! {
+                "$set": doc!{
+                    "trades": to_bson(&pool.trades).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                }
+            };
+
+            let updated_pool = self
+                .update_pool_and_publish(
+                    updated_fields,
+                    previous_pool,
+                    user_id,
+                    PoolEventType::CounterTrade,
+                )
+                .await?;
+
+            // Notify the pooler that proposed the original trade if the pool room is opened.
+            if let Ok(tx) = self.draft_server_info.get_room_tx(&req.pool_name) {
+                let counter_trade = serde_json::to_string(&CommandResponse::CounterTrade {
+                    notified_user,
+                    trade: counter_trade,
+                })
+                .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+                let _ = tx.send(counter_trade);
+            }
+
+            return Ok(updated_pool);
+        }
+
+        // repond the trade
+        let previous_trades = pool.trades.clone();
+        pool.respond_trade(user_id, req.is_accepted, req.trade_id)?;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        // Update the field in the pool
+        let updated_fields = doc! {
+            "$set": doc!{
+                "trades": to_bson(&pool.trades).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.pooler_roster": to_bson(&context.pooler_roster ).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.tradable_picks": to_bson(&context.tradable_picks ).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.future_tradable_picks": to_bson(&context.future_tradable_picks).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.conditional_picks": to_bson(&context.conditional_picks).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        let updated_pool = self
+            .update_pool_and_publish(
+                updated_fields,
+                previous_pool,
+                user_id,
+                PoolEventType::RespondTrade,
+            )
+            .await?;
+
+        self.post_accepted_trades(&previous_trades, &updated_pool);
+        Ok(updated_pool)
+    }
+
+    async fn veto_trade(&self, user_id: &str, req: VetoTradeRequest) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
+
+        // vote to veto the trade
+        pool.veto_trade(user_id, req.trade_id)?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "trades": to_bson(&pool.trades).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            }
+        };
+
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::VetoTrade,
+        )
+        .await
+    }
+
+    async fn fill_spot(&self, user_id: &str, req: FillSpotRequest) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
+
+        // Fill the player into the starting roster.
+        pool.fill_spot(user_id, &req.filled_spot_user_id, req.player_id)?;
+
+        // Update fields with the filled spot
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        // Update the field in the pool
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::FillSpot,
+        )
+        .await
+    }
+
+    async fn add_player(&self, user_id: &str, req: AddPlayerRequest) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
+
+        // Use the server side player information.
+        let player = get_pool_player_info(&self.db, req.player.id).await?;
+
+        // Add the player into the reservist of a pooler
+        pool.add_player(user_id, &req.added_player_user_id, &player)?;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
             msg: "pool context does not exist.".to_string(),
         })?;
 
         let updated_fields = doc! {
             "$set": doc!{
                 "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
-                "context.players": to_bson(&context.players).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+                "context.players": to_bson(&context.players).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.weekly_acquisitions": to_bson(&context.weekly_acquisitions).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.season_acquisitions": to_bson(&context.season_acquisitions).map_err(|e| AppError::MongoError { msg: e.to_string() })?
             }
         };
 
         // Update the fields in the mongoDB pool document.
 
-        update_pool(updated_fields, &collection, &req.pool_name).await
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::AddPlayer,
+        )
+        .await
     }
 
     async fn remove_player(&self, user_id: &str, req: RemovePlayerRequest) -> Result<Pool> {
         let collection = self.db.collection::<Pool>("pools");
         let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
 
         // Remove the player from the roster.
         pool.remove_player(user_id, &req.removed_player_user_id, req.player_id)?;
@@ -326,7 +1707,19 @@ impl PoolService for MongoPoolService {
 
         // Update the fields in the mongoDB pool document.
 
-        update_pool(updated_fields, &collection, &req.pool_name).await
+        let updated_pool = self
+            .update_pool_and_publish(
+                updated_fields,
+                previous_pool,
+                user_id,
+                PoolEventType::RemovePlayer,
+            )
+            .await?;
+
+        self.notify_watchers(&req.pool_name, req.player_id, &req.removed_player_user_id)
+            .await;
+
+        Ok(updated_pool)
     }
 
     async fn update_pool_settings(
@@ -337,30 +1730,47 @@ impl PoolService for MongoPoolService {
         let collection = self.db.collection::<Pool>("pools");
 
         let pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
 
+        req.pool_settings.validate()?;
         pool.can_update_in_progress_pool_settings(user_id, &req.pool_settings)?;
 
-        let updated_fields = doc! {
+        let mut updated_fields = doc! {
             "$set": doc!{
                 "settings": to_bson(&req.pool_settings).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
 
             }
         };
 
-        update_pool(updated_fields, &collection, &req.pool_name).await
+        // The cumulative totals are rebuilt with the new scoring settings by the next cumulation.
+        if req.pool_settings.forwards_settings != previous_pool.settings.forwards_settings
+            || req.pool_settings.defense_settings != previous_pool.settings.defense_settings
+            || req.pool_settings.goalies_settings != previous_pool.settings.goalies_settings
+        {
+            updated_fields.insert(
+                "$unset",
+                doc! {"context.cumulative_totals": "", "context.players_totals": ""},
+            );
+        }
+
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::UpdatePoolSettings,
+        )
+        .await
     }
 
     async fn modify_roster(&self, user_id: &str, req: ModifyRosterRequest) -> Result<Pool> {
         let collection = self.db.collection::<Pool>("pools");
         let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
 
         pool.modify_roster(
             user_id,
             &req.roster_modified_user_id,
-            &req.forw_list,
-            &req.def_list,
-            &req.goal_list,
-            &req.reserv_list,
+            &req.get_modified_roster(),
         )?;
         // Modify the all the pooler_roster (we could update only the pooler_roster[userId] if necessary)
 
@@ -371,17 +1781,92 @@ impl PoolService for MongoPoolService {
         let updated_fields = doc! {
             "$set": doc!{
                 "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.cap_invalid_participants": to_bson(&context.cap_invalid_participants).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
             }
         };
 
         // Update the fields in the mongoDB pool document.
 
-        update_pool(updated_fields, &collection, &req.pool_name).await
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::ModifyRoster,
+        )
+        .await
+    }
+
+    async fn preview_create_trade(
+        &self,
+        user_id: &str,
+        req: &mut CreateTradeRequest,
+    ) -> Result<PoolPreview> {
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_cached_short_pool_by_name(&collection, &req.pool_name).await?;
+
+        pool.create_trade(&mut req.trade, user_id)?;
+        pool.get_preview()
+    }
+
+    async fn preview_respond_trade(
+        &self,
+        user_id: &str,
+        req: RespondTradeRequest,
+    ) -> Result<PoolPreview> {
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_cached_short_pool_by_name(&collection, &req.pool_name).await?;
+
+        match req.counter_trade {
+            Some(mut counter_trade) => {
+                pool.counter_trade(user_id, req.trade_id, &mut counter_trade)?
+            }
+            None => pool.respond_trade(user_id, req.is_accepted, req.trade_id)?,
+        }
+        pool.get_preview()
+    }
+
+    async fn preview_modify_roster(
+        &self,
+        user_id: &str,
+        req: ModifyRosterRequest,
+    ) -> Result<PoolPreview> {
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_cached_short_pool_by_name(&collection, &req.pool_name).await?;
+
+        pool.modify_roster(
+            user_id,
+            &req.roster_modified_user_id,
+            &req.get_modified_roster(),
+        )?;
+        pool.get_preview()
+    }
+
+    async fn set_auto_lineup(&self, user_id: &str, req: AutoLineupRequest) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
+
+        pool.set_auto_lineup(user_id, &req.auto_lineup_user_id, req.is_enabled)?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "participants": to_bson(&pool.participants).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+            }
+        };
+
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::SetAutoLineup,
+        )
+        .await
     }
 
     async fn protect_players(&self, user_id: &str, req: ProtectPlayersRequest) -> Result<Pool> {
         let collection = self.db.collection::<Pool>("pools");
         let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
 
         pool.protect_players(
             user_id,
@@ -393,68 +1878,442 @@ impl PoolService for MongoPoolService {
             msg: "pool context does not exist.".to_string(),
         })?;
 
-        let updated_fields = doc! {
-            "$set": doc!{
-                "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
-                "context.protected_players": to_bson(&context.protected_players).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
-                "status":  to_bson(&pool.status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
-            }
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.protected_players": to_bson(&context.protected_players).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "status":  to_bson(&pool.status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        // Update the fields in the mongoDB pool document.
+
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::ProtectPlayers,
+        )
+        .await
+    }
+
+    async fn complete_protection(
+        &self,
+        user_id: &str,
+        req: CompleteProtectionRequest,
+    ) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
+
+        pool.complete_protection(user_id)?;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.players": to_bson(&context.players).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "status":  to_bson(&pool.status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        // Update the fields in the mongoDB pool document.
+
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::CompleteProtection,
+        )
+        .await
+    }
+
+    async fn mark_as_final(&self, user_id: &str, req: MarkAsFinalRequest) -> Result<Pool> {
+        let mut pool = self.get_pool_by_name(&req.pool_name).await?;
+        let previous_pool = pool.clone();
+
+        pool.mark_as_final(user_id)?;
+
+        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context.tradable_picks": to_bson(&context.tradable_picks).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "context.conditional_picks": to_bson(&context.conditional_picks).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "draft_order": to_bson(&pool.draft_order).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "final_rank": to_bson(&pool.final_rank).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                "status":  to_bson(&pool.status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        let updated_pool = self
+            .update_pool_and_publish(
+                updated_fields,
+                previous_pool,
+                user_id,
+                PoolEventType::MarkAsFinal,
+            )
+            .await?;
+
+        // The season results are kept apart from the live pool, which the dynasty generation keeps updating.
+        create_season_archive(&self.db, &pool).await?;
+
+        self.notifier.fire_pool_event(
+            &updated_pool,
+            PoolLifecycleEvent::MarkedFinal,
+            serde_json::json!({ "final_rank": updated_pool.final_rank }),
+        );
+
+        Ok(updated_pool)
+    }
+
+    async fn generate_team_claim(
+        &self,
+        user_id: &str,
+        req: GenerateTeamClaimRequest,
+    ) -> Result<TeamClaim> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+
+        pool.validate_team_claim(user_id, &req.participant_id)?;
+
+        // A new claim code replaces the previous one of the team.
+        let claims = self.db.collection::<TeamClaim>("team_claims");
+        claims
+            .delete_many(
+                doc! {"pool_name": &req.pool_name, "participant_id": req.participant_id.to_string()},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        let team_claim = TeamClaim::new(user_id, &req);
+
+        claims
+            .insert_one(&team_claim, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(team_claim)
+    }
+
+    async fn claim_team(&self, user: &UserEmailJwtPayload, req: ClaimTeamRequest) -> Result<Pool> {
+        let claims = self.db.collection::<TeamClaim>("team_claims");
+
+        let team_claim = claims
+            .find_one(doc! {"code": &req.code}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .ok_or_else(|| AppError::CustomError {
+                msg: "The claim code does not exist.".to_string(),
+            })?;
+
+        if team_claim.is_expired() {
+            return Err(AppError::CustomError {
+                msg: "The claim code has expired.".to_string(),
+            });
+        }
+
+        // The participant id is referenced in the daily scores too, the complete pool is needed.
+        let mut pool = self.get_pool_by_name(&team_claim.pool_name).await?;
+        let previous_pool = pool.clone();
+
+        pool.claim_team(&team_claim.participant_id, &user.sub, &user.email.address)?;
+
+        let Bson::Document(pool_document) =
+            to_bson(&pool).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+        else {
+            return Err(AppError::CustomError {
+                msg: "The pool could not be serialized.".to_string(),
+            });
+        };
+
+        let updated_pool = self
+            .update_pool_and_publish(
+                doc! {"$set": pool_document},
+                previous_pool,
+                &user.sub,
+                PoolEventType::ClaimTeam,
+            )
+            .await?;
+
+        claims
+            .delete_one(doc! {"code": &req.code}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(updated_pool)
+    }
+
+    async fn update_team_profile(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        req: UpdateTeamProfileRequest,
+    ) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        pool.update_team_profile(user_id, &req)?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "participants": to_bson(&pool.participants).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::UpdateTeamProfile,
+        )
+        .await
+    }
+
+    async fn get_watchlist(&self, user_id: &str, pool_name: &str) -> Result<Watchlist> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, pool_name).await?;
+        pool.validate_participant(user_id)?;
+
+        let watchlist = self
+            .db
+            .collection::<Watchlist>("watchlists")
+            .find_one(doc! {"user_id": user_id, "pool_name": pool_name}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(watchlist.unwrap_or_else(|| Watchlist::new(user_id, pool_name)))
+    }
+
+    async fn update_watchlist(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        req: UpdateWatchlistRequest,
+    ) -> Result<Watchlist> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_short_pool_by_name(&collection, pool_name).await?;
+        pool.validate_participant(user_id)?;
+
+        let update = if req.is_watched {
+            doc! {"$addToSet": {"player_ids": req.player_id}}
+        } else {
+            doc! {"$pull": {"player_ids": req.player_id}}
+        };
+
+        // The watchlist is created with its first watched player.
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(req.is_watched)
+            .return_document(ReturnDocument::After)
+            .build();
+
+        let watchlist = self
+            .db
+            .collection::<Watchlist>("watchlists")
+            .find_one_and_update(
+                doc! {"user_id": user_id, "pool_name": pool_name},
+                update,
+                options,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(watchlist.unwrap_or_else(|| Watchlist::new(user_id, pool_name)))
+    }
+
+    async fn get_trade_block(&self, name: &str) -> Result<HashMap<String, Vec<TradeBlockListing>>> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, name).await?;
+
+        Ok(pool
+            .context
+            .map(|context| context.get_trade_block())
+            .unwrap_or_default())
+    }
+
+    async fn update_trade_block(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        req: UpdateTradeBlockRequest,
+    ) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        pool.update_trade_block(user_id, &req)?;
+
+        let trade_block = pool
+            .context
+            .as_ref()
+            .and_then(|context| context.trade_block.as_ref());
+        let updated_fields = doc! {
+            "$set": doc!{
+                "context.trade_block": to_bson(&trade_block).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        let updated_pool = self
+            .update_pool_and_publish(
+                updated_fields,
+                previous_pool,
+                user_id,
+                PoolEventType::UpdateTradeBlock,
+            )
+            .await?;
+
+        // Notify the other poolers of the new listing.
+        if req.is_on_block {
+            let notified_users: Vec<String> = updated_pool
+                .participants
+                .iter()
+                .map(|participant| participant.id.clone())
+                .filter(|participant| *participant != req.participant_id)
+                .collect();
+
+            if let Err(e) = self
+                .notifier
+                .notify_users(
+                    &notified_users,
+                    pool_name,
+                    NotificationType::TradeBlockListing,
+                )
+                .await
+            {
+                warn!("{}", e);
+            }
+        }
+
+        Ok(updated_pool)
+    }
+
+    async fn update_orphaned_team(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        req: UpdateOrphanedTeamRequest,
+    ) -> Result<Pool> {
+        let collection = self.db.collection::<Pool>("pools");
+        let mut pool = get_short_pool_by_name(&collection, pool_name).await?;
+        let previous_pool = pool.clone();
+
+        pool.update_orphaned_team(user_id, &req)?;
+
+        let updated_fields = doc! {
+            "$set": doc!{
+                "participants": to_bson(&pool.participants).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            }
+        };
+
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::UpdateOrphanedTeam,
+        )
+        .await
+    }
+
+    async fn adopt_team(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        req: AdoptTeamRequest,
+    ) -> Result<Pool> {
+        // The participant id is referenced in the daily scores too, the complete pool is needed.
+        let mut pool = self.get_pool_by_name(pool_name).await?;
+        let previous_pool = pool.clone();
+
+        pool.adopt_team(user_id, &req)?;
+
+        // The whole pool is replaced at once so the team is never split between the 2 ids.
+        let Bson::Document(pool_document) =
+            to_bson(&pool).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+        else {
+            return Err(AppError::CustomError {
+                msg: "The pool could not be serialized.".to_string(),
+            });
         };
 
-        // Update the fields in the mongoDB pool document.
+        let updated_pool = self
+            .update_pool_and_publish(
+                doc! {"$set": pool_document},
+                previous_pool,
+                user_id,
+                PoolEventType::AdoptTeam,
+            )
+            .await?;
+
+        if let Err(e) = self
+            .notifier
+            .notify_users(
+                &[req.new_user_id.to_string()],
+                pool_name,
+                NotificationType::TeamAdopted,
+            )
+            .await
+        {
+            warn!("{}", e);
+        }
 
-        update_pool(updated_fields, &collection, &req.pool_name).await
+        Ok(updated_pool)
     }
 
-    async fn complete_protection(
+    async fn update_team_managers(
         &self,
         user_id: &str,
-        req: CompleteProtectionRequest,
+        req: UpdateTeamManagersRequest,
     ) -> Result<Pool> {
         let collection = self.db.collection::<Pool>("pools");
         let mut pool = get_short_pool_by_name(&collection, &req.pool_name).await?;
+        let previous_pool = pool.clone();
 
-        pool.complete_protection(user_id)?;
-
-        let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
-            msg: "pool context does not exist.".to_string(),
-        })?;
+        pool.update_team_managers(user_id, &req.participant_id, &req.managers)?;
 
         let updated_fields = doc! {
             "$set": doc!{
-                "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
-                "context.players": to_bson(&context.players).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
-                "status":  to_bson(&pool.status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+                "participants": to_bson(&pool.participants).map_err(|e| AppError::MongoError { msg: e.to_string() })?
             }
         };
 
-        // Update the fields in the mongoDB pool document.
-
-        update_pool(updated_fields, &collection, &req.pool_name).await
+        self.update_pool_and_publish(
+            updated_fields,
+            previous_pool,
+            user_id,
+            PoolEventType::UpdateTeamManagers,
+        )
+        .await
     }
 
-    async fn mark_as_final(&self, user_id: &str, req: MarkAsFinalRequest) -> Result<Pool> {
-        let collection = self.db.collection::<Pool>("pools");
-        let mut pool = self.get_pool_by_name(&req.pool_name).await?;
+    async fn transfer_ownership(
+        &self,
+        user_id: &str,
+        req: TransferOwnershipRequest,
+    ) -> Result<Pool> {
+        transfer_pool_ownership(&self.db, user_id, &req.pool_name, &req.new_owner_id).await
+    }
 
-        pool.mark_as_final(user_id)?;
+    async fn rename_pool(&self, user_id: &str, req: RenamePoolRequest) -> Result<Pool> {
+        let pool =
+            get_short_pool_by_name(&self.db.collection::<Pool>("pools"), &req.pool_name).await?;
 
-        let updated_fields = doc! {
-            "$set": doc!{
-                "draft_order": to_bson(&pool.draft_order).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
-                "final_rank": to_bson(&pool.final_rank).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
-                "status":  to_bson(&pool.status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
-            }
-        };
+        authorize(user_id, &pool, Permission::ManagePool)?;
 
-        update_pool(updated_fields, &collection, &req.pool_name).await
+        rename_pool(&self.db, pool, &req.new_pool_name, user_id).await
     }
 
     async fn generate_dynasty(&self, user_id: &str, req: GenerateDynastyRequest) -> Result<Pool> {
         let collection = self.db.collection::<Pool>("pools");
         let pool = self.get_pool_by_name(&req.pool_name).await?;
 
-        pool.has_privileges(user_id)?;
+        authorize(user_id, &pool, Permission::UpdateSettings)?;
         pool.validate_pool_status(&PoolState::Final)?;
 
         let mut new_settings = pool.settings.clone();
@@ -478,7 +2337,8 @@ impl PoolService for MongoPoolService {
         // If the pool is dynasty type, we need to create a new pool in dynasty status.
         // With almost everying thing from the last pool save into it.
         let pool_context = &pool.context.expect("The pool should have a pool context.");
-        let new_dynasty_pool = Pool {
+        let season_dates = self.get_season_dates().await?;
+        let mut new_dynasty_pool = Pool {
             name: req.new_pool_name,
             owner: pool.owner,
             participants: pool.participants,
@@ -496,19 +2356,43 @@ impl PoolService for MongoPoolService {
                 score_by_day: Some(HashMap::new()),
                 tradable_picks: Some(Vec::new()),
                 past_tradable_picks: pool_context.tradable_picks.clone(),
+                future_tradable_picks: pool_context.future_tradable_picks.clone(),
                 protected_players: Some(protected_players),
                 players: pool_context.players.clone(),
+                conditional_picks: None,
+                auction: None,
+                matchup_schedule: None,
+                draft_picks: Some(Vec::new()),
+                current_draft_pick_owners: None,
+                cap_by_day: None,
+                cumulative_totals: Some(HashMap::new()),
+                players_totals: Some(HashMap::new()),
+                trade_block: None,
+                makeup_picks: None,
+                weekly_acquisitions: None,
+                season_acquisitions: None,
+                cap_invalid_participants: None,
             }),
             date_updated: 0,
-            season_start: START_SEASON_DATE.to_string(),
-            season_end: END_SEASON_DATE.to_string(),
-            season: POOL_CREATION_SEASON,
+            season_start: season_dates.season_start,
+            season_end: season_dates.season_end,
+            season: season_dates.season,
+            trade_deadline: Some(season_dates.trade_deadline),
+            is_draft_paused: None,
+            schema_version: Some(POOL_SCHEMA_VERSION),
         };
 
-        collection
-            .insert_one(&new_dynasty_pool, None)
-            .await
-            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+        new_dynasty_pool.update_dynasty_participants(
+            req.added_participants.as_deref().unwrap_or_default(),
+            req.removed_participants.as_deref().unwrap_or_default(),
+        )?;
+
+        // The new season is not created without the link from the past season, and the reverse.
+        let mut transaction = Transaction::start(&self.db).await?;
+
+        transaction
+            .insert_one(&collection, &new_dynasty_pool)
+            .await?;
 
         let updated_fields = doc! {
             "$set": doc!{
@@ -516,6 +2400,599 @@ impl PoolService for MongoPoolService {
             }
         };
 
-        update_pool(updated_fields, &collection, &req.pool_name).await
+        let updated_pool = update_pool_in_transaction(
+            &mut transaction,
+            updated_fields,
+            &collection,
+            &req.pool_name,
+        )
+        .await?;
+
+        for pool_name in [&req.pool_name, &new_dynasty_pool.name] {
+            transaction
+                .insert_one(
+                    &self.db.collection::<PoolEvent>("pool_events"),
+                    &PoolEvent::new(
+                        pool_name,
+                        user_id,
+                        PoolEventType::GenerateDynasty,
+                        Vec::new(),
+                    ),
+                )
+                .await?;
+        }
+
+        transaction.commit().await?;
+
+        pool_cache().insert(&updated_pool).await;
+        Ok(updated_pool)
+    }
+
+    async fn get_pool_history(
+        &self,
+        user_id: &str,
+        name: &str,
+        query: PoolHistoryQuery,
+    ) -> Result<Vec<PoolEvent>> {
+        // Return the pool events, the most recent first.
+        // Only the owner and the assistants see who made the changes.
+        self.validate_pool_view(Some(user_id), name).await?;
+
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, name).await?;
+        let is_commissioner = has_permission(user_id, &pool, Permission::UpdateSettings);
+
+        let find_options = FindOptions::builder()
+            .sort(doc! {"date_created": -1, "_id": -1})
+            .skip(query.skip.unwrap_or(0))
+            .limit(query.limit.unwrap_or(20).clamp(1, 200))
+            .build();
+
+        let collection = self.db.collection::<PoolEvent>("pool_events");
+        let mut events: Vec<PoolEvent> = collection
+            .find(doc! {"pool_name": name}, find_options)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        if !is_commissioner {
+            for event in events.iter_mut() {
+                event.actor.clear();
+            }
+        }
+
+        Ok(events)
+    }
+
+    async fn get_matchups(&self, name: &str) -> Result<MatchupSchedule> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, name).await?;
+
+        pool.context
+            .and_then(|context| context.matchup_schedule)
+            .ok_or_else(|| AppError::CustomError {
+                msg: "This pool does not have any head-to-head matchups.".to_string(),
+            })
+    }
+
+    async fn get_standings(&self, name: &str) -> Result<Vec<Standing>> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, name).await?;
+
+        let head_to_head_settings =
+            pool.settings
+                .head_to_head_settings
+                .ok_or_else(|| AppError::CustomError {
+                    msg: "This pool is not a head-to-head pool.".to_string(),
+                })?;
+
+        let matchup_schedule = pool
+            .context
+            .and_then(|context| context.matchup_schedule)
+            .ok_or_else(|| AppError::CustomError {
+                msg: "This pool does not have any head-to-head matchups.".to_string(),
+            })?;
+
+        Ok(matchup_schedule.get_standings(&head_to_head_settings))
+    }
+
+    async fn get_pool_standings(&self, name: &str) -> Result<PoolStandings> {
+        let pool = self.get_pool_with_totals(name).await?;
+
+        pool.get_pool_standings()
+    }
+
+    async fn get_players_stats(&self, name: &str) -> Result<Vec<PoolPlayerStats>> {
+        let pool = self.get_pool_with_totals(name).await?;
+
+        let context = pool.context.ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        Ok(context.get_players_stats())
+    }
+
+    async fn test_webhook(&self, user_id: &str, req: TestWebhookRequest) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, &req.pool_name).await?;
+
+        // Only the owner can test the webhook of the pool.
+        authorize(user_id, &pool, Permission::ManagePool)?;
+
+        self.notifier.test_webhook(&pool).await
+    }
+
+    async fn get_cap_report(&self, name: &str) -> Result<CapReport> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, name).await?;
+
+        let context = pool.context.ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        context.get_cap_report(&pool.settings)
+    }
+
+    async fn get_trade_analysis(&self, name: &str, trade_id: u32) -> Result<TradeAnalysis> {
+        // The recent form needs the daily scores, so the complete pool is fetched.
+        let mut pool = self.get_pool_by_name(name).await?;
+
+        let context = pool.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        if context.are_totals_missing() {
+            context.rebuild_cumulative_totals(&pool.settings);
+        }
+
+        let trade = pool.get_trade(trade_id)?;
+        let team_ids: HashSet<u32> = trade
+            .get_legs()
+            .iter()
+            .flat_map(|leg| leg.items.players.clone())
+            .filter_map(|player_id| {
+                pool.context
+                    .as_ref()?
+                    .players
+                    .get(&player_id.to_string())?
+                    .team
+            })
+            .collect();
+
+        let today = time_provider().today();
+
+        // Without the NHL schedules, the remaining games are estimated from the season dates.
+        let schedules = match self.nhl_api.get_remaining_schedules(&team_ids, today).await {
+            Ok(schedules) => schedules,
+            Err(e) => {
+                warn!("Could not fetch the remaining schedules: {}", e);
+                HashMap::new()
+            }
+        };
+
+        pool.get_trade_analysis(trade_id, &schedules, today)
+    }
+
+    async fn validate_pool_view(&self, user_id: Option<&str>, name: &str) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, name).await?;
+
+        if !pool.is_private() {
+            return Ok(());
+        }
+
+        let user_id = user_id.ok_or_else(|| AppError::AuthError {
+            msg: "You need to be logged in to see this pool.".to_string(),
+        })?;
+
+        if is_user_invited(&self.db, name, user_id).await? {
+            return Ok(());
+        }
+
+        authorize(user_id, &pool, Permission::ViewPrivatePool)
+    }
+
+    async fn get_draft_recap(&self, name: &str) -> Result<Vec<DraftRecapPick>> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, name).await?;
+
+        let context = pool.context.ok_or_else(|| AppError::CustomError {
+            msg: "This pool has not been drafted yet.".to_string(),
+        })?;
+
+        Ok(context.get_draft_recap(&pool.draft_order.unwrap_or_default()))
+    }
+
+    async fn get_starters_games(&self, name: &str, date: &str) -> Result<HashMap<String, u8>> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, name).await?;
+        let schedule = get_daily_schedule(&self.db, date).await?;
+
+        Ok(pool
+            .context
+            .map(|context| context.get_starters_games(&schedule.get_teams_playing()))
+            .unwrap_or_default())
+    }
+
+    async fn get_free_agents(
+        &self,
+        name: &str,
+        mut query: GetPlayerQuery,
+    ) -> Result<Vec<PlayerInfo>> {
+        let collection = self.db.collection::<Pool>("pools");
+        let pool = get_cached_short_pool_by_name(&collection, name).await?;
+
+        let owned_players: Vec<u32> = pool
+            .context
+            .map(|context| context.get_owned_players().into_iter().collect())
+            .unwrap_or_default();
+
+        // By default, the active players with the most points this season are listed first.
+        query.active.get_or_insert(true);
+        query.sort.get_or_insert_with(|| "points".to_string());
+
+        find_players(&self.db, query, &owned_players).await
+    }
+
+    async fn cumulate_daily_points(&self) -> Result<()> {
+        // Cumulate the points of the previous day for every pool in progress.
+        // The previous days that were never cumulated are also cumulated so the final rank can be computed.
+        let collection = self.db.collection::<Pool>("pools");
+
+        let today = time_provider().today();
+        let yesterday = today - Duration::days(1);
+
+        let today = today.to_string();
+
+        let pools: Vec<Pool> = collection
+            .find(doc! {"status": "InProgress"}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        // The players points of each date are only fetched once for all pools.
+        // None means that the games of that date are not all completed.
+        let mut players_points_by_day = HashMap::new();
+
+        for pool in pools {
+            // A pool that could not be cumulated does not prevent the cumulation of the others.
+            let pool_name = pool.name.clone();
+
+            if let Err(e) = self
+                .cumulate_pool_daily_points(
+                    &collection,
+                    pool,
+                    &today,
+                    yesterday,
+                    &mut players_points_by_day,
+                )
+                .await
+            {
+                warn!(pool_name, "Could not cumulate the daily points: {}", e);
+                continue;
+            }
+        }
+
+        // Remind the poolers that did not protect their players yet, the day before the draft.
+        let tomorrow = (time_provider().today() + Duration::days(1)).to_string();
+
+        let dynasty_pools: Vec<Pool> = collection
+            .find(
+                doc! {"status": "Dynasty", "settings.draft_date": &tomorrow},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        for pool in dynasty_pools {
+            let protected_players = pool
+                .context
+                .as_ref()
+                .and_then(|context| context.protected_players.as_ref());
+
+            let participants: Vec<String> = pool
+                .participants
+                .iter()
+                .filter(|participant| {
+                    !protected_players.is_some_and(|protected_players| {
+                        protected_players.contains_key(&participant.id)
+                    })
+                })
+                .map(|participant| participant.id.clone())
+                .collect();
+
+            if let Err(e) = self
+                .notifier
+                .notify_users(
+                    &participants,
+                    &pool.name,
+                    NotificationType::ProtectionDeadline,
+                )
+                .await
+            {
+                warn!("{}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn update_live_scores(&self) -> Result<()> {
+        // Push the provisional points of the games in progress to the pools in progress.
+        // The games finishing after midnight are still part of the previous date.
+        let now = time_provider().now();
+        let mut date = now.date_naive();
+        if now.hour() < 12 {
+            date -= Duration::days(1);
+        }
+
+        let Some(daily_players_points) = self
+            .nhl_api
+            .get_live_players_points(&date.to_string())
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let collection = self.db.collection::<Pool>("pools");
+
+        let pools: Vec<Pool> = collection
+            .find(doc! {"status": "InProgress"}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        let date = date.to_string();
+
+        for mut pool in pools {
+            if date < pool.season_start || date > pool.season_end {
+                continue;
+            }
+
+            let Some(context) = pool.context.as_mut() else {
+                continue;
+            };
+
+            if !context.update_live_roster_points(&date, &daily_players_points) {
+                continue;
+            }
+
+            let Some(daily_roster_points) = context
+                .score_by_day
+                .as_ref()
+                .and_then(|score_by_day| score_by_day.get(&date))
+            else {
+                continue;
+            };
+
+            // The live points are not part of the pool history, they are replaced by the cumulation.
+            // A pool that could not be updated does not prevent the update of the others.
+            let updated_fields = match to_bson(daily_roster_points) {
+                Ok(daily_roster_points) => {
+                    doc! {"$set": {format!("context.score_by_day.{}", date): daily_roster_points}}
+                }
+                Err(e) => {
+                    warn!(
+                        pool_name = pool.name,
+                        "Could not serialize the live points: {}", e
+                    );
+                    continue;
+                }
+            };
+
+            if let Err(e) = update_pool(updated_fields, &collection, &pool.name).await {
+                warn!(
+                    pool_name = pool.name,
+                    "Could not update the live points: {}", e
+                );
+                continue;
+            }
+            self.publish_pool_update(&pool, PoolUpdate::new_live_score(&pool, &date));
+        }
+
+        Ok(())
+    }
+
+    async fn complete_trade_reviews(&self) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let find_option = FindOptions::builder()
+            .projection(doc! {"context.score_by_day": 0})
+            .build();
+
+        let pools: Vec<Pool> = collection
+            .find(
+                doc! {"status": "InProgress", "trades.status": "REVIEWING"},
+                find_option,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        let now = Utc::now().timestamp_millis();
+
+        for pool in pools {
+            let pool_name = pool.name.clone();
+            if let Err(e) = self.complete_pool_trade_reviews(pool, now).await {
+                warn!(pool_name, "Could not complete the trade reviews: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn complete_late_protections(&self) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let find_option = FindOptions::builder()
+            .projection(doc! {"context.score_by_day": 0})
+            .build();
+
+        let pools: Vec<Pool> = collection
+            .find(
+                doc! {"status": "Dynasty", "settings.dynasty_settings.protection_deadline": {"$ne": null}},
+                find_option,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        let today = time_provider().today();
+
+        for mut pool in pools {
+            if !pool.is_protection_deadline_passed(today) {
+                continue;
+            }
+
+            // The players are ranked with their points of the last season.
+            let players_totals = match pool
+                .settings
+                .dynasty_settings
+                .as_ref()
+                .and_then(|dynasty_settings| dynasty_settings.past_season_pool_name.first())
+            {
+                Some(past_season_pool_name) => self
+                    .get_pool_with_totals(past_season_pool_name)
+                    .await?
+                    .context
+                    .and_then(|context| context.players_totals)
+                    .unwrap_or_default(),
+                None => HashMap::new(),
+            };
+
+            let previous_pool = pool.clone();
+
+            let late_poolers = match pool.auto_complete_protection(&players_totals) {
+                Ok(late_poolers) => late_poolers,
+                Err(e) => {
+                    warn!("Could not complete the protection of {}: {}", pool.name, e);
+                    continue;
+                }
+            };
+
+            let context = pool.context.as_ref().ok_or_else(|| AppError::CustomError {
+                msg: "pool context does not exist.".to_string(),
+            })?;
+
+            let updated_fields = doc! {
+                "$set": doc!{
+                    "context.protected_players": to_bson(&context.protected_players).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                    "context.pooler_roster": to_bson(&context.pooler_roster).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                    "context.players": to_bson(&context.players).map_err(|e| AppError::MongoError { msg: e.to_string() })?,
+                    "status":  to_bson(&pool.status).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+                }
+            };
+
+            let updated_pool = self
+                .update_pool_and_publish(
+                    updated_fields,
+                    previous_pool,
+                    SERVER_ACTOR,
+                    PoolEventType::CompleteProtection,
+                )
+                .await?;
+
+            if !late_poolers.is_empty() {
+                let late_poolers: Vec<String> = late_poolers
+                    .iter()
+                    .map(|participant| updated_pool.get_participant_name(participant))
+                    .collect();
+
+                self.notifier.post_to_webhook(
+                    &updated_pool,
+                    &format!(
+                        "The players of {} were protected automatically after the protection deadline.",
+                        late_poolers.join(", ")
+                    ),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn reset_weekly_acquisitions(&self) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let find_option = FindOptions::builder()
+            .projection(doc! {"context.score_by_day": 0})
+            .build();
+
+        let pools: Vec<Pool> = collection
+            .find(
+                doc! {"status": "InProgress", "context.weekly_acquisitions": {"$ne": null}},
+                find_option,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        for pool in pools {
+            let updated_fields = doc! {
+                "$unset": doc!{
+                    "context.weekly_acquisitions": "",
+                }
+            };
+
+            let pool_name = pool.name.clone();
+            if let Err(e) = self
+                .update_pool_and_publish(
+                    updated_fields,
+                    pool,
+                    SERVER_ACTOR,
+                    PoolEventType::ResetWeeklyAcquisitions,
+                )
+                .await
+            {
+                warn!(pool_name, "Could not reset the weekly acquisitions: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn expire_trades(&self) -> Result<()> {
+        let collection = self.db.collection::<Pool>("pools");
+
+        let find_option = FindOptions::builder()
+            .projection(doc! {"context.score_by_day": 0})
+            .build();
+
+        let pools: Vec<Pool> = collection
+            .find(
+                doc! {"status": "InProgress", "trades.status": "NEW"},
+                find_option,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        let now = Utc::now().timestamp_millis();
+
+        for pool in pools {
+            let pool_name = pool.name.clone();
+            if let Err(e) = self.expire_pool_trades(pool, now).await {
+                warn!(pool_name, "Could not expire the trades: {}", e);
+            }
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,701 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use moka::future::Cache;
+use mongodb::bson::{doc, from_document, to_bson, Bson, Document};
+use mongodb::options::{FindOptions, IndexOptions, UpdateOptions};
+use mongodb::IndexModel;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tracing::warn;
+
+use poolnhl_interface::draft::model::{ChatMessage, DraftServerInfo};
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::invitations::model::Invitation;
+use poolnhl_interface::notifications::model::{Notification, NotificationPreferences};
+use poolnhl_interface::pool::model::{Matchup, Pool, PoolEventType, PoolUser, Trade};
+use poolnhl_interface::users::{
+    model::{
+        AccountDeletion, AccountMerge, PoolParticipation, TokenRevocation, UserDataExport,
+        UserEmailJwtPayload, UserProfile, UserRecord, ACCOUNT_DELETION_GRACE_DAYS,
+        DELETED_USER_NAME,
+    },
+    service::UsersService,
+};
+use poolnhl_interface::webhooks::model::WebhookSubscription;
+
+use crate::cache::pool_cache;
+use crate::database_connection::DatabaseConnection;
+use crate::services::pool_service::{
+    update_pool_with_history, update_pool_with_history_in_transaction,
+};
+use crate::transactions::Transaction;
+
+// The number of seconds a revocation is cached, it bounds the delay before a logout reaches the other instances.
+const REVOCATIONS_CACHE_TTL_SECONDS: u64 = 60;
+
+// The members of a pool read to export the participation of a user.
+#[derive(Deserialize)]
+struct ParticipatedPool {
+    name: String,
+    owner: String,
+    season: u32,
+    participants: Vec<PoolUser>,
+    trades: Option<Vec<Trade>>,
+}
+
+// The members of a pool computed by the profile aggregation.
+#[derive(Deserialize)]
+struct PoolProfileSummary {
+    name: String,
+    participant: PoolUser,
+    is_champion: bool,
+
+    // The completed matchups played by the user.
+    matchups: Vec<Matchup>,
+
+    // The NHL team of every player in the roster of the user.
+    drafted_teams: Vec<Option<u32>>,
+}
+
+#[derive(Clone)]
+pub struct MongoUsersService {
+    db: DatabaseConnection,
+
+    // The authenticated draft sockets, dropped when the tokens of a user are revoked.
+    draft_server_info: Arc<DraftServerInfo>,
+
+    // The revocation date of the users, 0 when the user never logged out everywhere.
+    // It is read on every authenticated request.
+    revocations: Cache<String, i64>,
+}
+
+impl MongoUsersService {
+    pub fn new(db: DatabaseConnection, draft_server_info: Arc<DraftServerInfo>) -> Self {
+        Self {
+            db,
+            draft_server_info,
+            revocations: Cache::builder()
+                .max_capacity(100_000)
+                .time_to_live(Duration::from_secs(REVOCATIONS_CACHE_TTL_SECONDS))
+                .build(),
+        }
+    }
+
+    async fn get_revoked_before(&self, user_id: &str) -> Result<i64> {
+        if let Some(revoked_before) = self.revocations.get(user_id).await {
+            return Ok(revoked_before);
+        }
+
+        let revoked_before = self
+            .db
+            .collection::<TokenRevocation>("token_revocations")
+            .find_one(doc! {"user_id": user_id}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .map_or(0, |revocation| revocation.revoked_before);
+
+        self.revocations
+            .insert(user_id.to_string(), revoked_before)
+            .await;
+
+        Ok(revoked_before)
+    }
+
+    async fn find_all<T: DeserializeOwned + Unpin + Send + Sync>(
+        &self,
+        collection_name: &str,
+        filter: Document,
+    ) -> Result<Vec<T>> {
+        self.db
+            .collection::<T>(collection_name)
+            .find(filter, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    async fn get_account_deletion(&self, user_id: &str) -> Result<Option<AccountDeletion>> {
+        self.db
+            .collection::<AccountDeletion>("account_deletions")
+            .find_one(doc! {"user_id": user_id}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    async fn get_pool_participations(&self, user_id: &str) -> Result<Vec<PoolParticipation>> {
+        let pools: Vec<ParticipatedPool> = self
+            .db
+            .collection::<ParticipatedPool>("pools")
+            .find(
+                doc! {"participants.id": user_id},
+                FindOptions::builder()
+                    .projection(
+                        doc! {"name": 1, "owner": 1, "season": 1, "participants": 1, "trades": 1},
+                    )
+                    .build(),
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(pools
+            .into_iter()
+            .filter_map(|pool| {
+                let participant = pool
+                    .participants
+                    .into_iter()
+                    .find(|participant| participant.id == user_id)?;
+
+                Some(PoolParticipation {
+                    is_owner: pool.owner == user_id,
+                    trades_proposed: pool
+                        .trades
+                        .unwrap_or_default()
+                        .into_iter()
+                        .filter(|trade| trade.proposed_by == user_id)
+                        .collect(),
+                    pool_name: pool.name,
+                    season: pool.season,
+                    participant,
+                })
+            })
+            .collect())
+    }
+
+    // Remove the personal data of a user.
+    // The participations are kept under an anonymous name so the standings, rosters and trades stay consistent.
+    async fn delete_user_data(&self, user_id: &str) -> Result<()> {
+        let pool_names: Vec<String> = self
+            .get_pool_participations(user_id)
+            .await?
+            .into_iter()
+            .map(|participation| participation.pool_name)
+            .collect();
+
+        let pools = self.db.collection::<Document>("pools");
+
+        pools
+            .update_many(
+                doc! {"participants.id": user_id},
+                doc! {"$set": {
+                    "participants.$[participant].name": DELETED_USER_NAME,
+                    "participants.$[participant].team_name": null,
+                    "participants.$[participant].team_abbreviation": null,
+                    "participants.$[participant].avatar_url": null,
+                }},
+                UpdateOptions::builder()
+                    .array_filters(vec![doc! {"participant.id": user_id}])
+                    .build(),
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        // The user no longer co-manages the teams of the other poolers.
+        pools
+            .update_many(
+                doc! {"participants.managers": user_id},
+                doc! {"$pull": {"participants.$[].managers": user_id}},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        for pool_name in &pool_names {
+            pool_cache().invalidate(pool_name).await;
+        }
+
+        // The pools owned or co-managed by the user are handed over to the other members.
+        let managed_pools: Vec<Pool> = self
+            .find_all(
+                "pools",
+                doc! {"$or": [{"owner": user_id}, {"settings.assistants": user_id}]},
+            )
+            .await?;
+
+        for previous_pool in managed_pools {
+            let mut pool = previous_pool.clone();
+            pool.remove_deleted_user(user_id);
+
+            update_pool_with_history(
+                &self.db,
+                doc! {"$set": {
+                    "owner": &pool.owner,
+                    "settings.assistants": &pool.settings.assistants,
+                }},
+                previous_pool,
+                user_id,
+                PoolEventType::DeleteAccount,
+            )
+            .await?;
+        }
+
+        for (collection_name, filter) in [
+            ("notifications", doc! {"user_id": user_id}),
+            ("notification_preferences", doc! {"user_id": user_id}),
+            ("invitations", doc! {"invited_by": user_id}),
+        ] {
+            self.db
+                .collection::<Document>(collection_name)
+                .delete_many(filter, None)
+                .await
+                .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+        }
+
+        let subscription_ids: Vec<String> = self
+            .find_all::<WebhookSubscription>("webhook_subscriptions", doc! {"user_id": user_id})
+            .await?
+            .into_iter()
+            .map(|subscription| subscription.id)
+            .collect();
+
+        self.db
+            .collection::<Document>("webhook_deliveries")
+            .delete_many(doc! {"subscription_id": {"$in": &subscription_ids}}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+        self.db
+            .collection::<Document>("webhook_subscriptions")
+            .delete_many(doc! {"user_id": user_id}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        // The chat messages are in a capped collection, they cannot be removed and expire with the newer messages.
+        // Until then, they are kept under the anonymous name. Older servers reject the updates
+        // changing the size of a capped document, the deletion is completed anyway.
+        if let Err(e) = self
+            .db
+            .collection::<Document>("chat_messages")
+            .update_many(
+                doc! {"user_id": user_id},
+                doc! {"$set": {"user_id": "", "user_name": DELETED_USER_NAME}},
+                None,
+            )
+            .await
+        {
+            warn!("Could not anonymize the chat messages: {}", e);
+        }
+
+        // The credentials are managed by Hanko, the tokens still valid are revoked.
+        self.logout_everywhere(user_id).await?;
+
+        self.db
+            .collection::<AccountDeletion>("account_deletions")
+            .delete_one(doc! {"user_id": user_id}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(())
+    }
+}
+
+// Create the index used to find the revocation of a user.
+pub async fn create_users_indexes(db: &DatabaseConnection) -> Result<()> {
+    db.collection::<TokenRevocation>("token_revocations")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"user_id": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    db.collection::<AccountDeletion>("account_deletions")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"user_id": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl UsersService for MongoUsersService {
+    async fn logout_everywhere(&self, user_id: &str) -> Result<TokenRevocation> {
+        let revocation = TokenRevocation {
+            user_id: user_id.to_string(),
+            // The tokens issued before the logout second are revoked, so a login right after it is still accepted.
+            revoked_before: Utc::now().timestamp(),
+        };
+
+        self.db
+            .collection::<TokenRevocation>("token_revocations")
+            .update_one(
+                doc! {"user_id": user_id},
+                doc! {"$set": {"revoked_before": revocation.revoked_before}},
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        self.revocations
+            .insert(user_id.to_string(), revocation.revoked_before)
+            .await;
+
+        // The sockets authenticated with the revoked tokens cannot send draft commands anymore.
+        self.draft_server_info.remove_user_sockets(user_id)?;
+
+        Ok(revocation)
+    }
+
+    async fn is_token_revoked(&self, token: &UserEmailJwtPayload) -> Result<bool> {
+        Ok(token.iat < self.get_revoked_before(&token.sub).await?)
+    }
+
+    async fn get_user_profile(&self, user_id: &str) -> Result<UserProfile> {
+        let roster_players = |position: &str| {
+            doc! {"$ifNull": [format!("$$roster.{}", position), []]}
+        };
+
+        // Only the parts of the pools concerning the user are returned, the newest season first.
+        let pipeline = vec![
+            doc! {"$match": {"participants.id": user_id}},
+            doc! {"$sort": {"season": -1}},
+            doc! {"$project": {
+                "_id": 0,
+                "name": 1,
+                "participant": {"$first": {"$filter": {
+                    "input": "$participants",
+                    "cond": {"$eq": ["$$this.id", user_id]},
+                }}},
+                "is_champion": {"$eq": [{"$first": {"$ifNull": ["$final_rank", []]}}, user_id]},
+                "matchups": {"$filter": {
+                    "input": {"$reduce": {
+                        "input": {"$ifNull": ["$context.matchup_schedule.weeks", []]},
+                        "initialValue": [],
+                        "in": {"$concatArrays": ["$$value", "$$this.matchups"]},
+                    }},
+                    "cond": {"$and": [
+                        {"$ne": [{"$ifNull": ["$$this.result", null]}, null]},
+                        {"$in": [user_id, ["$$this.home", "$$this.away"]]},
+                    ]},
+                }},
+                "drafted_teams": {"$let": {
+                    "vars": {"roster": {"$getField": {
+                        "field": {"$literal": user_id},
+                        "input": {"$ifNull": ["$context.pooler_roster", {}]},
+                    }}},
+                    "in": {"$let": {
+                        "vars": {"player_ids": {"$map": {
+                            "input": {"$concatArrays": [
+                                roster_players("chosen_forwards"),
+                                roster_players("chosen_defenders"),
+                                roster_players("chosen_goalies"),
+                                roster_players("chosen_reservists"),
+                                roster_players("chosen_utility"),
+                                roster_players("chosen_injured_reserve"),
+                            ]},
+                            "in": {"$toString": "$$this"},
+                        }}},
+                        "in": {"$map": {
+                            "input": {"$filter": {
+                                "input": {"$objectToArray": {"$ifNull": ["$context.players", {}]}},
+                                "cond": {"$in": ["$$this.k", "$$player_ids"]},
+                            }},
+                            "in": "$$this.v.team",
+                        }},
+                    }},
+                }},
+            }},
+        ];
+
+        let summaries: Vec<Document> = self
+            .db
+            .collection::<Document>("pools")
+            .aggregate(pipeline, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        let mut profile = UserProfile {
+            user_id: user_id.to_string(),
+            name: None,
+            avatar_url: None,
+            pools_joined: 0,
+            championships: Vec::new(),
+            record: UserRecord::default(),
+            favorite_team: None,
+        };
+        let mut drafted_by_team: HashMap<u32, u32> = HashMap::new();
+
+        for summary in summaries {
+            let summary: PoolProfileSummary =
+                from_document(summary).map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+            if profile.pools_joined == 0 {
+                profile.name = Some(summary.participant.name);
+                profile.avatar_url = summary.participant.avatar_url;
+            }
+            profile.pools_joined += 1;
+
+            if summary.is_champion {
+                profile.championships.push(summary.name);
+            }
+
+            for matchup in summary.matchups {
+                let Some(result) = matchup.result else {
+                    continue;
+                };
+
+                let (points_for, points_against) = if matchup.home == user_id {
+                    (result.home_points, result.away_points)
+                } else {
+                    (result.away_points, result.home_points)
+                };
+
+                if points_for > points_against {
+                    profile.record.wins += 1;
+                } else if points_for < points_against {
+                    profile.record.losses += 1;
+                } else {
+                    profile.record.ties += 1;
+                }
+            }
+
+            for team in summary.drafted_teams.into_iter().flatten() {
+                *drafted_by_team.entry(team).or_default() += 1;
+            }
+        }
+
+        // The lowest team id breaks the ties so the favorite team is stable.
+        profile.favorite_team = drafted_by_team
+            .into_iter()
+            .max_by(|(team_a, count_a), (team_b, count_b)| {
+                count_a.cmp(count_b).then_with(|| team_b.cmp(team_a))
+            })
+            .map(|(team, _)| team);
+
+        Ok(profile)
+    }
+
+    async fn export_user_data(&self, user: &UserEmailJwtPayload) -> Result<UserDataExport> {
+        let user_id = &user.sub;
+
+        Ok(UserDataExport {
+            user_id: user_id.clone(),
+            email: user.email.address.clone(),
+            pools: self.get_pool_participations(user_id).await?,
+            notification_preferences: self
+                .find_all::<NotificationPreferences>(
+                    "notification_preferences",
+                    doc! {"user_id": user_id},
+                )
+                .await?,
+            notifications: self
+                .find_all::<Notification>("notifications", doc! {"user_id": user_id})
+                .await?,
+            webhook_subscriptions: self
+                .find_all::<WebhookSubscription>("webhook_subscriptions", doc! {"user_id": user_id})
+                .await?,
+            invitations_sent: self
+                .find_all::<Invitation>("invitations", doc! {"invited_by": user_id})
+                .await?,
+            chat_messages: self
+                .find_all::<ChatMessage>("chat_messages", doc! {"user_id": user_id})
+                .await?,
+            account_deletion: self.get_account_deletion(user_id).await?,
+        })
+    }
+
+    async fn request_account_deletion(&self, user_id: &str) -> Result<AccountDeletion> {
+        if let Some(account_deletion) = self.get_account_deletion(user_id).await? {
+            return Ok(account_deletion);
+        }
+
+        let now = Utc::now().timestamp_millis();
+        let account_deletion = AccountDeletion {
+            user_id: user_id.to_string(),
+            date_requested: now,
+            date_deletion: now
+                + chrono::Duration::days(ACCOUNT_DELETION_GRACE_DAYS).num_milliseconds(),
+        };
+
+        self.db
+            .collection::<AccountDeletion>("account_deletions")
+            .insert_one(&account_deletion, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(account_deletion)
+    }
+
+    async fn cancel_account_deletion(&self, user_id: &str) -> Result<()> {
+        let result = self
+            .db
+            .collection::<AccountDeletion>("account_deletions")
+            .delete_one(doc! {"user_id": user_id}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        if result.deleted_count == 0 {
+            return Err(AppError::CustomError {
+                msg: "There is no pending deletion of this account.".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn complete_account_deletions(&self) -> Result<()> {
+        let account_deletions = self
+            .find_all::<AccountDeletion>(
+                "account_deletions",
+                doc! {"date_deletion": {"$lte": Utc::now().timestamp_millis()}},
+            )
+            .await?;
+
+        for account_deletion in account_deletions {
+            self.delete_user_data(&account_deletion.user_id).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn merge_accounts(
+        &self,
+        user: &UserEmailJwtPayload,
+        merged_user: &UserEmailJwtPayload,
+    ) -> Result<AccountMerge> {
+        let user_id = &user.sub;
+        let merged_user_id = &merged_user.sub;
+
+        if user_id == merged_user_id {
+            return Err(AppError::CustomError {
+                msg: "An account cannot be merged into itself.".to_string(),
+            });
+        }
+
+        // The participant ids are referenced in the daily scores too, the complete pools are needed.
+        let pools: Vec<Pool> = self
+            .find_all(
+                "pools",
+                doc! {"$or": [
+                    {"owner": merged_user_id},
+                    {"participants.id": merged_user_id},
+                    {"participants.managers": merged_user_id},
+                    {"settings.assistants": merged_user_id},
+                ]},
+            )
+            .await?;
+
+        // Every pool is validated before the first one is rewritten, so a conflict leaves the accounts untouched.
+        let mut merged_pools = Vec::new();
+        for pool in pools {
+            let mut merged_pool = pool.clone();
+            merged_pool.merge_user(merged_user_id, user_id)?;
+            merged_pools.push((pool, merged_pool));
+        }
+
+        // The pools and the references to the merged account are rewritten in one transaction.
+        let mut transaction = Transaction::start(&self.db).await?;
+
+        let mut updated_pools = Vec::new();
+        for (previous_pool, merged_pool) in merged_pools {
+            let Bson::Document(pool_document) =
+                to_bson(&merged_pool).map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            else {
+                return Err(AppError::CustomError {
+                    msg: "The pool could not be serialized.".to_string(),
+                });
+            };
+
+            // Each pool is rewritten by a single update.
+            let updated_pool = update_pool_with_history_in_transaction(
+                &mut transaction,
+                &self.db,
+                doc! {},
+                doc! {"$set": pool_document},
+                previous_pool,
+                user_id,
+                PoolEventType::MergeAccounts,
+            )
+            .await?;
+
+            updated_pools.push(updated_pool);
+        }
+
+        // The preferences of the merged account for a pool already configured by the user are dropped.
+        let configured_pool_names: Vec<String> = self
+            .find_all::<NotificationPreferences>(
+                "notification_preferences",
+                doc! {"user_id": user_id},
+            )
+            .await?
+            .into_iter()
+            .map(|preferences| preferences.pool_name)
+            .collect();
+
+        transaction
+            .delete_many(
+                &self.db.collection::<Document>("notification_preferences"),
+                doc! {"user_id": merged_user_id, "pool_name": {"$in": &configured_pool_names}},
+            )
+            .await?;
+
+        for (collection_name, field) in [
+            ("notifications", "user_id"),
+            ("notification_preferences", "user_id"),
+            ("webhook_subscriptions", "user_id"),
+            ("invitations", "invited_by"),
+            ("invitations", "invited_user_id"),
+            ("team_claims", "created_by"),
+        ] {
+            transaction
+                .update_many(
+                    &self.db.collection::<Document>(collection_name),
+                    doc! {field: merged_user_id},
+                    doc! {"$set": {field: user_id}},
+                )
+                .await?;
+        }
+
+        transaction
+            .update_many(
+                &self.db.collection::<Document>("invitations"),
+                doc! {"accepted_by": merged_user_id},
+                doc! {"$set": {"accepted_by.$": user_id}},
+            )
+            .await?;
+
+        transaction
+            .delete_many(
+                &self.db.collection::<AccountDeletion>("account_deletions"),
+                doc! {"user_id": merged_user_id},
+            )
+            .await?;
+
+        transaction.commit().await?;
+
+        let mut pool_names = Vec::new();
+        for updated_pool in updated_pools {
+            pool_cache().insert(&updated_pool).await;
+            pool_names.push(updated_pool.name);
+        }
+
+        // The merged account is no longer used, its tokens are revoked.
+        self.logout_everywhere(merged_user_id).await?;
+
+        Ok(AccountMerge {
+            user_id: user_id.clone(),
+            merged_user_id: merged_user_id.clone(),
+            pools: pool_names,
+        })
+    }
+}
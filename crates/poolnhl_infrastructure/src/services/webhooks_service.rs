@@ -0,0 +1,298 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::stream::TryStreamExt;
+use hmac::{Hmac, Mac};
+use mongodb::bson::doc;
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::IndexModel;
+use sha2::Sha256;
+use tracing::{warn, Instrument};
+
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::pool::model::Pool;
+use poolnhl_interface::webhooks::{
+    model::{
+        CreateWebhookSubscriptionRequest, DeleteWebhookSubscriptionRequest, PoolLifecycleEvent,
+        WebhookDelivery, WebhookPayload, WebhookSubscription, WEBHOOK_DELIVERIES_LIMIT,
+        WEBHOOK_DELIVERY_MAX_ATTEMPTS,
+    },
+    service::WebhooksService,
+};
+
+use crate::database_connection::DatabaseConnection;
+
+#[derive(Clone)]
+pub struct MongoWebhooksService {
+    db: DatabaseConnection,
+}
+
+impl MongoWebhooksService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+// Create the indexes used to list the subscriptions and the deliveries of a user.
+pub async fn create_webhooks_indexes(db: &DatabaseConnection) -> Result<()> {
+    db.collection::<WebhookSubscription>("webhook_subscriptions")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"id": 1})
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    db.collection::<WebhookSubscription>("webhook_subscriptions")
+        .create_index(
+            IndexModel::builder().keys(doc! {"user_id": 1}).build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    db.collection::<WebhookDelivery>("webhook_deliveries")
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! {"subscription_id": 1, "date_created": -1})
+                .build(),
+            None,
+        )
+        .await
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+    Ok(())
+}
+
+// Deliver the pool lifecycle events to the webhook subscriptions of the pool owner.
+#[derive(Clone)]
+pub struct LifecycleWebhooks {
+    db: DatabaseConnection,
+    client: reqwest::Client,
+}
+
+impl LifecycleWebhooks {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self {
+            db,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub fn fire(&self, pool: &Pool, event: PoolLifecycleEvent, data: serde_json::Value) {
+        // The deliveries are made in the background so the request is not slowed down.
+        let lifecycle_webhooks = self.clone();
+        let owner = pool.owner.clone();
+        let payload = WebhookPayload {
+            event,
+            pool_name: pool.name.clone(),
+            date_created: Utc::now().timestamp_millis(),
+            data,
+        };
+
+        // The deliveries keep the request id of the request that fired them in their logs.
+        tokio::spawn(
+            async move {
+                if let Err(e) = lifecycle_webhooks.deliver_all(&owner, &payload).await {
+                    warn!("{}", e);
+                }
+            }
+            .in_current_span(),
+        );
+    }
+
+    async fn deliver_all(&self, owner: &str, payload: &WebhookPayload) -> Result<()> {
+        let subscriptions: Vec<WebhookSubscription> = self
+            .db
+            .collection::<WebhookSubscription>("webhook_subscriptions")
+            .find(doc! {"user_id": owner}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        let body = serde_json::to_string(payload)
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        for subscription in subscriptions
+            .into_iter()
+            .filter(|subscription| subscription.is_subscribed(&payload.pool_name, &payload.event))
+        {
+            let lifecycle_webhooks = self.clone();
+            let payload = payload.clone();
+            let body = body.clone();
+
+            tokio::spawn(async move {
+                lifecycle_webhooks
+                    .deliver(&subscription, &payload, &body)
+                    .await;
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn deliver(
+        &self,
+        subscription: &WebhookSubscription,
+        payload: &WebhookPayload,
+        body: &str,
+    ) {
+        // Retry with an exponential backoff until the subscription endpoint accepts the event.
+        for attempt in 1..=WEBHOOK_DELIVERY_MAX_ATTEMPTS {
+            let result = self.post(subscription, body).await;
+
+            let delivery = WebhookDelivery {
+                subscription_id: subscription.id.clone(),
+                event: payload.event.clone(),
+                pool_name: payload.pool_name.clone(),
+                attempt,
+                status_code: result.as_ref().ok().copied(),
+                error: result.as_ref().err().map(|e| e.to_string()),
+                is_success: result
+                    .as_ref()
+                    .is_ok_and(|status_code| (200..300).contains(status_code)),
+                date_created: Utc::now().timestamp_millis(),
+            };
+            let is_success = delivery.is_success;
+
+            if let Err(e) = self
+                .db
+                .collection::<WebhookDelivery>("webhook_deliveries")
+                .insert_one(delivery, None)
+                .await
+            {
+                warn!("{}", e);
+            }
+
+            if is_success {
+                return;
+            }
+
+            tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+        }
+    }
+
+    async fn post(&self, subscription: &WebhookSubscription, body: &str) -> Result<u16> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(subscription.secret.as_bytes())
+            .map_err(|e| AppError::CustomError { msg: e.to_string() })?;
+        mac.update(body.as_bytes());
+        let signature = hex::encode(mac.finalize().into_bytes());
+
+        let response = self
+            .client
+            .post(&subscription.url)
+            .header("Content-Type", "application/json")
+            .header("X-Pool-Signature", format!("sha256={}", signature))
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| AppError::ReqwestError { msg: e.to_string() })?;
+
+        Ok(response.status().as_u16())
+    }
+}
+
+#[async_trait]
+impl WebhooksService for MongoWebhooksService {
+    async fn list_subscriptions(&self, user_id: &str) -> Result<Vec<WebhookSubscription>> {
+        self.db
+            .collection::<WebhookSubscription>("webhook_subscriptions")
+            .find(doc! {"user_id": user_id}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    async fn list_deliveries(
+        &self,
+        user_id: &str,
+        subscription_id: &str,
+    ) -> Result<Vec<WebhookDelivery>> {
+        // Only the user that created the subscription can see its deliveries.
+        self.db
+            .collection::<WebhookSubscription>("webhook_subscriptions")
+            .find_one(doc! {"id": subscription_id, "user_id": user_id}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .ok_or_else(|| AppError::CustomError {
+                msg: "The webhook subscription does not exist.".to_string(),
+            })?;
+
+        let find_option = FindOptions::builder()
+            .sort(doc! {"date_created": -1})
+            .limit(WEBHOOK_DELIVERIES_LIMIT)
+            .build();
+
+        self.db
+            .collection::<WebhookDelivery>("webhook_deliveries")
+            .find(doc! {"subscription_id": subscription_id}, find_option)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    async fn create_subscription(
+        &self,
+        user_id: &str,
+        req: CreateWebhookSubscriptionRequest,
+    ) -> Result<WebhookSubscription> {
+        if !req.url.starts_with("https://") {
+            return Err(AppError::CustomError {
+                msg: "The webhook url needs to use https.".to_string(),
+            });
+        }
+
+        if req.events.is_empty() {
+            return Err(AppError::CustomError {
+                msg: "The webhook needs to subscribe to at least one event.".to_string(),
+            });
+        }
+
+        let subscription = WebhookSubscription::new(user_id, &req);
+
+        self.db
+            .collection::<WebhookSubscription>("webhook_subscriptions")
+            .insert_one(&subscription, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(subscription)
+    }
+
+    async fn delete_subscription(
+        &self,
+        user_id: &str,
+        req: DeleteWebhookSubscriptionRequest,
+    ) -> Result<()> {
+        let delete_result = self
+            .db
+            .collection::<WebhookSubscription>("webhook_subscriptions")
+            .delete_one(doc! {"id": &req.subscription_id, "user_id": user_id}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        if delete_result.deleted_count == 0 {
+            return Err(AppError::CustomError {
+                msg: "The webhook subscription could not be deleted.".to_string(),
+            });
+        }
+
+        self.db
+            .collection::<WebhookDelivery>("webhook_deliveries")
+            .delete_many(doc! {"subscription_id": req.subscription_id}, None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use config::{Config, ConfigError, File};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fmt;
 
 #[derive(Debug, Clone, Deserialize)]
@@ -25,6 +26,99 @@ pub struct Auth {
 
     // The token audience to be able to validate the token (i.g., hockeypool.live).
     pub token_audience: String,
+
+    // The user ids of the site administrators, they can manage any pool for support purposes.
+    #[serde(default)]
+    pub admins: Vec<String>,
+
+    // The number of seconds between each fetch of the JWKS, so the rotated keys are picked up.
+    pub jwks_refresh_interval_seconds: u64,
+
+    // The minimum number of seconds between two fetches caused by a token with an unknown kid.
+    pub jwks_min_refetch_seconds: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NhlApi {
+    // The base url of the NHL web api (i.g., https://api-web.nhle.com/v1).
+    pub base_url: String,
+
+    // The number of hours between each synchronization of the players collection.
+    pub players_sync_interval_hours: u64,
+
+    // The local hour of the day at which the previous day points are cumulated.
+    pub daily_cumulation_hour: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ContractsApi {
+    // The base url of the players contracts source (i.g., a PuckPedia or CapWages export).
+    // When empty, the contracts are not synchronized.
+    pub base_url: String,
+
+    // The number of hours between each synchronization of the players contracts.
+    pub sync_interval_hours: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Heartbeat {
+    // The number of seconds between each ping sent to the draft sockets.
+    pub ping_interval_seconds: u64,
+
+    // The number of seconds without any message received before a draft socket is evicted.
+    pub pong_timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MailerSettings {
+    // The base url of the SendGrid web api (i.g., https://api.sendgrid.com/v3).
+    pub base_url: String,
+
+    // The SendGrid api key. When empty, the emails are only logged.
+    pub api_key: String,
+
+    // The address the emails are sent from (i.g., no-reply@hockeypool.live).
+    pub from_email: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CacheSettings {
+    // When false, the pools are always read from the database.
+    pub enabled: bool,
+
+    // The maximum number of pools kept in memory.
+    pub max_pools: u64,
+
+    // The number of seconds a pool stays cached, it bounds how stale a pool updated by another instance can be.
+    pub ttl_seconds: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RoomStoreSettings {
+    // The Redis server sharing the draft rooms between the backend instances (i.g., redis://localhost:6379).
+    // When empty, the draft rooms only exist in the process.
+    pub redis_url: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateBudget {
+    // The number of requests allowed during a period.
+    pub requests: u32,
+
+    // The number of seconds of a period.
+    pub period_seconds: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitSettings {
+    // The budget of each client on the routes without their own budget.
+    pub default: RateBudget,
+
+    // The budget of each client on a route, keyed by the route path (i.g., /create-pool).
+    pub routes: HashMap<String, RateBudget>,
+
+    // The budget of the commands sent by each draft socket.
+    pub draft_commands: RateBudget,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -34,6 +128,13 @@ pub struct Settings {
     pub logger: Logger,
     pub database: Database,
     pub auth: Auth,
+    pub nhl_api: NhlApi,
+    pub contracts_api: ContractsApi,
+    pub heartbeat: Heartbeat,
+    pub mailer: MailerSettings,
+    pub cache: CacheSettings,
+    pub room_store: RoomStoreSettings,
+    pub rate_limit: RateLimitSettings,
 }
 
 impl Settings {
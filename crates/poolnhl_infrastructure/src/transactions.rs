@@ -0,0 +1,160 @@
+use std::sync::OnceLock;
+
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOneAndUpdateOptions;
+use mongodb::{Client, ClientSession, Collection};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tracing::{info, warn};
+
+use poolnhl_interface::errors::{AppError, Result};
+
+use crate::database_connection::DatabaseConnection;
+
+// The client starting the sessions of the transactions.
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+// Tells if the deployment supports the transactions, only the replica sets and sharded clusters do.
+static TRANSACTIONS_SUPPORTED: OnceLock<bool> = OnceLock::new();
+
+// Register the client of the database, this should be called once when connecting to the database.
+pub fn set_client(client: Client) {
+    if CLIENT.set(client).is_err() {
+        warn!("The database client was already registered.");
+    }
+}
+
+async fn is_transactions_supported(db: &DatabaseConnection) -> bool {
+    if let Some(supported) = TRANSACTIONS_SUPPORTED.get() {
+        return *supported;
+    }
+
+    let supported = match db.run_command(doc! {"hello": 1}, None).await {
+        Ok(hello) => hello.contains_key("setName") || hello.get_str("msg") == Ok("isdbgrid"),
+        Err(e) => {
+            warn!("Could not tell if the transactions are supported: {}", e);
+            false
+        }
+    };
+
+    if !supported {
+        info!("The database does not support the transactions, the writes are applied one by one.");
+    }
+
+    *TRANSACTIONS_SUPPORTED.get_or_init(|| supported)
+}
+
+// Apply multi-document writes atomically, they are all committed or none of them is.
+// On a standalone server, the writes are applied directly without the atomicity guarantee.
+// The transaction is aborted when it is dropped without being committed (i.g., a write failed).
+pub struct Transaction {
+    session: Option<ClientSession>,
+}
+
+impl Transaction {
+    pub async fn start(db: &DatabaseConnection) -> Result<Self> {
+        let Some(client) = CLIENT.get() else {
+            return Ok(Self { session: None });
+        };
+
+        if !is_transactions_supported(db).await {
+            return Ok(Self { session: None });
+        }
+
+        let mut session = client
+            .start_session(None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        session
+            .start_transaction(None)
+            .await
+            .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+
+        Ok(Self {
+            session: Some(session),
+        })
+    }
+
+    pub async fn insert_one<T: Serialize>(
+        &mut self,
+        collection: &Collection<T>,
+        document: &T,
+    ) -> Result<()> {
+        match self.session.as_mut() {
+            Some(session) => collection
+                .insert_one_with_session(document, None, session)
+                .await
+                .map(|_| ()),
+            None => collection.insert_one(document, None).await.map(|_| ()),
+        }
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    pub async fn find_one_and_update<T: Serialize + DeserializeOwned>(
+        &mut self,
+        collection: &Collection<T>,
+        filter: Document,
+        update: Document,
+        options: FindOneAndUpdateOptions,
+    ) -> Result<Option<T>> {
+        match self.session.as_mut() {
+            Some(session) => {
+                collection
+                    .find_one_and_update_with_session(filter, update, options, session)
+                    .await
+            }
+            None => {
+                collection
+                    .find_one_and_update(filter, update, options)
+                    .await
+            }
+        }
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    pub async fn update_many<T>(
+        &mut self,
+        collection: &Collection<T>,
+        filter: Document,
+        update: Document,
+    ) -> Result<()> {
+        match self.session.as_mut() {
+            Some(session) => collection
+                .update_many_with_session(filter, update, None, session)
+                .await
+                .map(|_| ()),
+            None => collection
+                .update_many(filter, update, None)
+                .await
+                .map(|_| ()),
+        }
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    pub async fn delete_many<T>(
+        &mut self,
+        collection: &Collection<T>,
+        filter: Document,
+    ) -> Result<()> {
+        match self.session.as_mut() {
+            Some(session) => collection
+                .delete_many_with_session(filter, None, session)
+                .await
+                .map(|_| ()),
+            None => collection.delete_many(filter, None).await.map(|_| ()),
+        }
+        .map_err(|e| AppError::MongoError { msg: e.to_string() })
+    }
+
+    pub async fn commit(mut self) -> Result<()> {
+        if let Some(session) = self.session.as_mut() {
+            session
+                .commit_transaction()
+                .await
+                .map_err(|e| AppError::MongoError { msg: e.to_string() })?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use serde_json::json;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::pool::model::{PoolWebhook, WebhookKind};
+
+// Number of times a message is posted before it is dropped.
+pub const WEBHOOK_MAX_ATTEMPTS: u32 = 3;
+
+struct WebhookMessage {
+    webhook: PoolWebhook,
+    content: String,
+}
+
+// Queue the messages posted to the pools Discord/Slack webhooks.
+// A worker posts them in the background and retries the failed ones.
+#[derive(Clone)]
+pub struct WebhookSender {
+    client: reqwest::Client,
+    tx: mpsc::UnboundedSender<WebhookMessage>,
+}
+
+impl WebhookSender {
+    pub fn spawn() -> Self {
+        let client = reqwest::Client::new();
+        let (tx, mut rx) = mpsc::unbounded_channel::<WebhookMessage>();
+
+        let worker_client = client.clone();
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                for attempt in 1..=WEBHOOK_MAX_ATTEMPTS {
+                    match post_webhook(&worker_client, &message.webhook, &message.content).await {
+                        Ok(()) => break,
+                        Err(e) if attempt == WEBHOOK_MAX_ATTEMPTS => {
+                            warn!(attempt, "Could not post the webhook: {}", e)
+                        }
+                        Err(_) => {
+                            // Wait a bit longer after each failed attempt.
+                            tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+                        }
+                    }
+                }
+            }
+        });
+
+        Self { client, tx }
+    }
+
+    pub fn post(&self, webhook: &PoolWebhook, content: &str) {
+        let _ = self.tx.send(WebhookMessage {
+            webhook: webhook.clone(),
+            content: content.to_string(),
+        });
+    }
+
+    pub async fn post_now(&self, webhook: &PoolWebhook, content: &str) -> Result<()> {
+        // Post the message without going through the queue, the error is returned to the caller.
+        post_webhook(&self.client, webhook, content).await
+    }
+}
+
+async fn post_webhook(
+    client: &reqwest::Client,
+    webhook: &PoolWebhook,
+    content: &str,
+) -> Result<()> {
+    let body = match webhook.kind {
+        WebhookKind::Discord => json!({ "content": content }),
+        WebhookKind::Slack => json!({ "text": content }),
+    };
+
+    let response = client
+        .post(&webhook.url)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| AppError::ReqwestError { msg: e.to_string() })?;
+
+    if !response.status().is_success() {
+        return Err(AppError::ReqwestError {
+            msg: format!(
+                "The webhook message could not be posted ({}).",
+                response.status()
+            ),
+        });
+    }
+
+    Ok(())
+}
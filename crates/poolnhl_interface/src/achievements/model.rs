@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::pool::model::{Pool, TradeStatus};
+
+// Number of accepted trades in a pool to earn the TenTradesInSeason achievement.
+pub const TRADES_IN_SEASON_ACHIEVEMENT: usize = 10;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
+pub enum AchievementType {
+    // Made the first overall pick of a draft.
+    FirstDraftPick,
+
+    // Finished first of a pool.
+    Champion,
+
+    // Scored the most points of the pool during a head-to-head week.
+    PerfectWeek,
+
+    // Took part in 10 accepted trades during the season of a pool.
+    TenTradesInSeason,
+}
+
+// An achievement earned by a user in a pool, a user earns each achievement once per pool.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct Achievement {
+    pub user_id: String,
+    pub achievement_type: AchievementType,
+    pub pool_name: String,
+    pub season: u32,
+    pub date_earned: i64,
+}
+
+impl Achievement {
+    pub fn new(user_id: &str, achievement_type: AchievementType, pool: &Pool) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            achievement_type,
+            pool_name: pool.name.clone(),
+            season: pool.season,
+            date_earned: Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn list_earned(pool: &Pool) -> Vec<Self> {
+        // The achievements earned by the participants given the current state of the pool.
+        let mut achievements = Vec::new();
+
+        if let Some(first_pick) = pool
+            .context
+            .as_ref()
+            .and_then(|context| context.draft_picks.as_ref())
+            .and_then(|draft_picks| draft_picks.first())
+        {
+            achievements.push(Self::new(
+                &first_pick.drafter,
+                AchievementType::FirstDraftPick,
+                pool,
+            ));
+        }
+
+        if let Some(champion) = pool
+            .final_rank
+            .as_ref()
+            .and_then(|final_rank| final_rank.first())
+        {
+            achievements.push(Self::new(champion, AchievementType::Champion, pool));
+        }
+
+        // The best score of every completed head-to-head week.
+        if let Some(matchup_schedule) = pool
+            .context
+            .as_ref()
+            .and_then(|context| context.matchup_schedule.as_ref())
+        {
+            let mut perfect_week_users: Vec<&String> = Vec::new();
+
+            for week in &matchup_schedule.weeks {
+                let scores: Vec<(&String, f64)> = week
+                    .matchups
+                    .iter()
+                    .filter_map(|matchup| {
+                        let result = matchup.result.as_ref()?;
+                        Some([
+                            (&matchup.home, result.home_points),
+                            (&matchup.away, result.away_points),
+                        ])
+                    })
+                    .flatten()
+                    .collect();
+
+                let best_score = scores.iter().map(|(_, points)| *points).fold(0.0, f64::max);
+
+                for (user_id, points) in scores {
+                    if best_score > 0.0
+                        && points == best_score
+                        && !perfect_week_users.contains(&user_id)
+                    {
+                        perfect_week_users.push(user_id);
+                    }
+                }
+            }
+
+            for user_id in perfect_week_users {
+                achievements.push(Self::new(user_id, AchievementType::PerfectWeek, pool));
+            }
+        }
+
+        let mut accepted_trades_by_user: HashMap<String, usize> = HashMap::new();
+        for trade in pool
+            .trades
+            .iter()
+            .flatten()
+            .filter(|trade| matches!(trade.status, TradeStatus::ACCEPTED))
+        {
+            for participant in trade.get_participants() {
+                *accepted_trades_by_user.entry(participant).or_default() += 1;
+            }
+        }
+
+        for (user_id, accepted_trades) in accepted_trades_by_user {
+            if accepted_trades >= TRADES_IN_SEASON_ACHIEVEMENT {
+                achievements.push(Self::new(
+                    &user_id,
+                    AchievementType::TenTradesInSeason,
+                    pool,
+                ));
+            }
+        }
+
+        achievements
+    }
+}
@@ -0,0 +1,14 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::achievements::model::Achievement;
+use crate::errors::Result;
+
+#[async_trait]
+pub trait AchievementsService {
+    // The achievements earned by a user, the most recent first.
+    async fn list_achievements(&self, user_id: &str) -> Result<Vec<Achievement>>;
+}
+
+pub type AchievementsServiceHandle = Arc<dyn AchievementsService + Send + Sync>;
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::pool::model::PoolContext;
+use crate::users::model::UserId;
+
+// payload to sent when force deleting a pool.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct AdminDeletePoolRequest {
+    pub pool_name: String,
+}
+
+// payload to sent when transferring the ownership of a pool to one of its participants.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct AdminTransferOwnershipRequest {
+    pub pool_name: String,
+    pub new_owner_id: UserId,
+}
+
+// payload to sent when renaming a pool.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct AdminRenamePoolRequest {
+    pub pool_name: String,
+    pub new_pool_name: String,
+}
+
+// payload to sent when replacing a corrupted pool context.
+// The score_by_day member is kept when it is not provided.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct AdminUpdateContextRequest {
+    pub pool_name: String,
+    pub context: PoolContext,
+}
+
+// payload to sent when cumulating again the points of a date range (inclusive, %Y-%m-%d).
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct AdminRecumulatePointsRequest {
+    pub pool_name: String,
+    pub start_date: String,
+    pub end_date: String,
+}
+
+// payload to sent when setting the virtual today of a development environment (%Y-%m-%d).
+// Without a date, the real today is used again.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct AdminSetVirtualTodayRequest {
+    pub today: Option<String>,
+}
+
+// payload to sent when enabling the recording of the draft of a pool (the previous recording is replaced)
+// or disabling it (the recording is deleted).
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct AdminRecordDraftRequest {
+    pub pool_name: String,
+    pub is_recording: bool,
+}
+
+// payload to sent when exporting the recorded draft of a pool as a replay fixture.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct AdminDraftRecordingRequest {
+    pub pool_name: String,
+}
+
+// The result of migrating the pools stored with an older schema version.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct PoolsMigrationReport {
+    pub schema_version: u32,
+    pub migrated_pools: Vec<String>,
+    pub failed_pools: Vec<String>,
+}
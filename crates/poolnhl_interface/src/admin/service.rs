@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::admin::model::{
+    AdminDeletePoolRequest, AdminDraftRecordingRequest, AdminRecordDraftRequest,
+    AdminRecumulatePointsRequest, AdminRenamePoolRequest, AdminSetVirtualTodayRequest,
+    AdminTransferOwnershipRequest, AdminUpdateContextRequest, PoolsMigrationReport,
+};
+use crate::draft::model::DraftRecording;
+use crate::errors::Result;
+use crate::pool::model::Pool;
+use crate::time_provider::VirtualToday;
+
+// Support operations, only the site administrators can call them.
+#[async_trait]
+pub trait AdminService {
+    async fn delete_pool(&self, user_id: &str, req: AdminDeletePoolRequest) -> Result<Pool>;
+    async fn transfer_ownership(
+        &self,
+        user_id: &str,
+        req: AdminTransferOwnershipRequest,
+    ) -> Result<Pool>;
+    async fn rename_pool(&self, user_id: &str, req: AdminRenamePoolRequest) -> Result<Pool>;
+    async fn update_context(&self, user_id: &str, req: AdminUpdateContextRequest) -> Result<Pool>;
+    async fn recumulate_points(
+        &self,
+        user_id: &str,
+        req: AdminRecumulatePointsRequest,
+    ) -> Result<Pool>;
+    async fn migrate_pools(&self, user_id: &str) -> Result<PoolsMigrationReport>;
+    async fn set_virtual_today(
+        &self,
+        user_id: &str,
+        req: AdminSetVirtualTodayRequest,
+    ) -> Result<VirtualToday>;
+    async fn record_draft(
+        &self,
+        user_id: &str,
+        req: AdminRecordDraftRequest,
+    ) -> Result<Option<DraftRecording>>;
+    async fn get_draft_recording(
+        &self,
+        user_id: &str,
+        req: AdminDraftRecordingRequest,
+    ) -> Result<DraftRecording>;
+}
+
+pub type AdminServiceHandle = Arc<dyn AdminService + Send + Sync>;
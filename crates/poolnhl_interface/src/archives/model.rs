@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::errors::AppError;
+use crate::pool::model::{
+    DraftRecapPick, Pool, PoolPlayerInfo, PoolSettings, PoolStandings, PoolUser, PoolerRoster,
+    Trade,
+};
+
+// Snapshot of a pool taken when it is marked as final.
+// It is never updated, so the season results survive the dynasty generation and the later edits of the pool.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct SeasonArchive {
+    pub pool_name: String,
+    pub season: u32,
+    pub owner: String,
+    pub participants: Vec<PoolUser>,
+    pub settings: PoolSettings,
+
+    pub final_rank: Vec<String>,
+    pub standings: PoolStandings,
+
+    // The roster of each pooler at the end of the season (pooler id -> roster).
+    pub rosters: HashMap<String, PoolerRoster>,
+
+    // The players of the pool (player id -> player).
+    pub players: HashMap<String, PoolPlayerInfo>,
+
+    pub trades: Vec<Trade>,
+    pub draft_recap: Vec<DraftRecapPick>,
+
+    pub date_archived: i64,
+}
+
+impl SeasonArchive {
+    pub fn new(pool: &Pool) -> Result<Self, AppError> {
+        let final_rank = pool
+            .final_rank
+            .clone()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "The pool must be final to be archived.".to_string(),
+            })?;
+
+        // The standings need the totals, they are rebuilt from the daily scores when missing.
+        let mut pool = pool.clone();
+        if let Some(context) = pool.context.as_mut() {
+            if context.are_totals_missing() {
+                context.rebuild_cumulative_totals(&pool.settings);
+            }
+        }
+
+        let standings = pool.get_pool_standings()?;
+        let context = pool.context.ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        Ok(Self {
+            draft_recap: context.get_draft_recap(pool.draft_order.as_deref().unwrap_or_default()),
+            pool_name: pool.name,
+            season: pool.season,
+            owner: pool.owner,
+            participants: pool.participants,
+            settings: pool.settings,
+            final_rank,
+            standings,
+            rosters: context.pooler_roster,
+            players: context.players,
+            trades: pool.trades.unwrap_or_default(),
+            date_archived: Utc::now().timestamp_millis(),
+        })
+    }
+}
@@ -0,0 +1,13 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::archives::model::SeasonArchive;
+use crate::errors::Result;
+
+#[async_trait]
+pub trait ArchivesService {
+    async fn get_archive(&self, season: u32, pool_name: &str) -> Result<SeasonArchive>;
+}
+
+pub type ArchivesServiceHandle = Arc<dyn ArchivesService + Send + Sync>;
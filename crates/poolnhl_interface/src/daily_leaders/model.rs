@@ -1,7 +1,18 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::pool::model::DailyPlayersPoints;
+
+// Number of skaters and goalies listed in the top performers of a night.
+pub const TOP_PERFORMERS_LIMIT: usize = 10;
+
+// The longest range of dates that can be fetched at once.
+pub const MAX_DAILY_LEADERS_RANGE_DAYS: i64 = 31;
 
 #[allow(non_snake_case)]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct SkaterStats {
     pub assists: u8,
     pub goals: u8,
@@ -9,7 +20,7 @@ pub struct SkaterStats {
 }
 
 #[allow(non_snake_case)]
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct GoalyStats {
     pub assists: u8,
     pub goals: u8,
@@ -18,7 +29,7 @@ pub struct GoalyStats {
     pub OT: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct DailySkater {
     pub name: String,
     pub id: u32,
@@ -26,8 +37,7 @@ pub struct DailySkater {
     pub stats: SkaterStats,
 }
 
-
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct DailyGoaly {
     pub name: String,
     pub id: u32,
@@ -35,10 +45,130 @@ pub struct DailyGoaly {
     pub stats: GoalyStats,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct DailyLeaders {
     pub date: String,
     pub goalies: Vec<DailyGoaly>,
     pub skaters: Vec<DailySkater>,
     pub played: Vec<u32>,
-}
\ No newline at end of file
+}
+
+impl DailyLeaders {
+    // Build the leaders of a date from the points cumulated for the pools.
+    // The players name and NHL team id are mapped by player id.
+    pub fn new(
+        date: &str,
+        daily_players_points: &DailyPlayersPoints,
+        players: &HashMap<u32, (String, u32)>,
+    ) -> Self {
+        let get_player = |player_id: &String| {
+            let id: u32 = player_id.parse().ok()?;
+            let (name, team) = players.get(&id)?;
+            Some((id, name.clone(), *team))
+        };
+
+        let skaters = daily_players_points
+            .skaters
+            .iter()
+            .filter(|(_, points)| points.G > 0 || points.A > 0 || points.SOG.unwrap_or(0) > 0)
+            .filter_map(|(player_id, points)| {
+                let (id, name, team) = get_player(player_id)?;
+                Some(DailySkater {
+                    name,
+                    id,
+                    team,
+                    stats: SkaterStats {
+                        assists: points.A,
+                        goals: points.G,
+                        shootoutGoals: points.SOG.unwrap_or(0),
+                    },
+                })
+            })
+            .collect();
+
+        let goalies = daily_players_points
+            .goalies
+            .iter()
+            .filter_map(|(player_id, points)| {
+                let (id, name, team) = get_player(player_id)?;
+                let shots = u16::from(points.SV.unwrap_or(0)) + u16::from(points.GA.unwrap_or(0));
+                let decision = if points.W {
+                    Some("W".to_string())
+                } else if points.OT {
+                    Some("O".to_string())
+                } else {
+                    None
+                };
+
+                Some(DailyGoaly {
+                    name,
+                    id,
+                    team,
+                    stats: GoalyStats {
+                        assists: points.A,
+                        goals: points.G,
+                        decision,
+                        savePercentage: (shots > 0)
+                            .then(|| f32::from(points.SV.unwrap_or(0)) / f32::from(shots)),
+                        OT: Some(points.OT),
+                    },
+                })
+            })
+            .collect();
+
+        let played = daily_players_points
+            .skaters
+            .keys()
+            .chain(daily_players_points.goalies.keys())
+            .filter_map(|player_id| player_id.parse().ok())
+            .collect();
+
+        Self {
+            date: date.to_string(),
+            goalies,
+            skaters,
+            played,
+        }
+    }
+
+    pub fn get_top_performers(&self) -> Self {
+        // The skaters with the most points and the winning goalies with the best save percentage.
+        let mut skaters = self.skaters.clone();
+        skaters.sort_by_key(|skater| {
+            std::cmp::Reverse((
+                skater.stats.goals + skater.stats.assists,
+                skater.stats.goals,
+            ))
+        });
+        skaters.truncate(TOP_PERFORMERS_LIMIT);
+
+        let mut goalies: Vec<DailyGoaly> = self
+            .goalies
+            .iter()
+            .filter(|goalie| goalie.stats.decision.as_deref() == Some("W"))
+            .cloned()
+            .collect();
+        goalies.sort_by(|a, b| {
+            b.stats
+                .savePercentage
+                .unwrap_or(0.0)
+                .total_cmp(&a.stats.savePercentage.unwrap_or(0.0))
+        });
+        goalies.truncate(TOP_PERFORMERS_LIMIT);
+
+        Self {
+            date: self.date.clone(),
+            goalies,
+            skaters,
+            played: self.played.clone(),
+        }
+    }
+}
+
+// query parameters to list the daily leaders of a range of dates (YYYY-MM-DD), both dates included.
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DailyLeadersRangeQuery {
+    pub from: String,
+    pub to: String,
+}
@@ -2,12 +2,18 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 
-use crate::daily_leaders::model::DailyLeaders;
+use crate::daily_leaders::model::{DailyLeaders, DailyLeadersRangeQuery};
 use crate::errors::Result;
 
 #[async_trait]
 pub trait DailyLeadersService {
     async fn get_daily_leaders(&self, date: &str) -> Result<DailyLeaders>;
+    async fn get_daily_leaders_range(
+        &self,
+        query: DailyLeadersRangeQuery,
+    ) -> Result<Vec<DailyLeaders>>;
+    // The best skaters and goalies of last night.
+    async fn get_top_performers(&self) -> Result<DailyLeaders>;
 }
 
 pub type DailyLeadersServiceHandle = Arc<dyn DailyLeadersService + Send + Sync>;
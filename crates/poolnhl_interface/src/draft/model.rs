@@ -1,11 +1,24 @@
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::RwLock};
-use tokio::sync::broadcast;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+};
+use tokio::sync::{broadcast, watch, RwLock as AsyncRwLock, RwLockReadGuard};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::draft::service::RoomStateStoreHandle;
 use crate::{
     errors::AppError,
-    pool::model::{Pool, PoolPlayerInfo, PoolSettings},
+    players::model::PlayerInfo,
+    pool::model::{
+        DraftOrderType, DraftQueue, Pool, PoolContext, PoolPlayerInfo, PoolSettings, PoolState,
+        Position, Trade, TradeDraftPicksRequest,
+    },
     users::model::UserEmailJwtPayload,
 };
 
@@ -69,13 +82,74 @@ impl RoomState {
     }
 }
 
-#[derive(Debug)]
+// Number of milliseconds a disconnected socket has to resume its room session.
+pub const SESSION_RESUME_WINDOW_MS: i64 = 300000;
+
+#[derive(Debug, Clone)]
+pub struct RoomSession {
+    pub pool_name: String,
+    pub number_poolers: u8,
+    pub socket_id: String,
+
+    // The authenticated user of the socket, None for unauthenticated sockets.
+    pub user_id: Option<String>,
+
+    // State of the room user when the socket disconnected.
+    pub room_user: Option<RoomUser>,
+
+    // Date in milliseconds when the socket disconnected, None while the socket is connected.
+    pub date_disconnected: Option<i64>,
+}
+
 pub struct DraftServerInfo {
     // Mapping of pool names to its corresponding room informations.
     pub rooms: RwLock<HashMap<String, RoomState>>,
 
     // Map a socket id to the user information, these list only authenticated users are authenticated.
     pub authenticated_sockets: RwLock<HashMap<String, UserEmailJwtPayload>>,
+
+    // Map a session token to the room session of a socket, so a reconnecting socket can resume it.
+    pub sessions: RwLock<HashMap<String, RoomSession>>,
+
+    // Map a pool name to the channel of its in-season updates.
+    // Unlike the rooms, the channels are kept alive after the draft while someone listens.
+    pub pool_channels: RwLock<HashMap<String, broadcast::Sender<String>>>,
+
+    // Relay the rooms to the other backend instances, None when only one instance is running.
+    room_store: Option<RoomStateStoreHandle>,
+
+    // Set once the server received a shutdown signal, no new pool write is started after it.
+    shutting_down: AtomicBool,
+
+    // Held by the draft commands and the pick timers while they write the pools,
+    // the shutdown waits for them to complete before exiting.
+    pending_writes: AsyncRwLock<()>,
+
+    // Hold the ServerRestarting message once the pending writes completed,
+    // every socket sends it then closes.
+    restarting: watch::Sender<Option<String>>,
+}
+
+// Sender of a room, the messages are also relayed to the other instances through the room store.
+#[derive(Clone)]
+pub struct RoomSender {
+    pool_name: String,
+    tx: broadcast::Sender<String>,
+    room_store: Option<RoomStateStoreHandle>,
+}
+
+impl RoomSender {
+    pub fn send(&self, message: String) -> Result<usize, broadcast::error::SendError<String>> {
+        if let Some(room_store) = &self.room_store {
+            room_store.publish(&self.pool_name, &message);
+        }
+
+        self.tx.send(message)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<String> {
+        self.tx.subscribe()
+    }
 }
 
 impl DraftServerInfo {
@@ -84,9 +158,262 @@ impl DraftServerInfo {
         Self {
             rooms: RwLock::new(HashMap::new()),
             authenticated_sockets: RwLock::new(HashMap::new()),
+            sessions: RwLock::new(HashMap::new()),
+            pool_channels: RwLock::new(HashMap::new()),
+            room_store: None,
+            shutting_down: AtomicBool::new(false),
+            pending_writes: AsyncRwLock::new(()),
+            restarting: watch::channel(None).0,
+        }
+    }
+
+    // Start a pool write, None is returned once the server is shutting down.
+    // The write is pending until the returned guard is dropped.
+    pub async fn begin_write(&self) -> Option<RwLockReadGuard<'_, ()>> {
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        let guard = self.pending_writes.read().await;
+
+        // The shutdown could have started while waiting for the guard.
+        (!self.shutting_down.load(Ordering::SeqCst)).then_some(guard)
+    }
+
+    // Wait for the pending pool writes to complete, then notify the sockets
+    // of this instance that the server is restarting so they close.
+    pub async fn shutdown(&self, message: &str) -> Result<(), AppError> {
+        self.shutting_down.store(true, Ordering::SeqCst);
+
+        let response = serde_json::to_string(&CommandResponse::ServerRestarting {
+            message: message.to_string(),
+        })
+        .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        let _pending_writes = self.pending_writes.write().await;
+
+        // The rooms of the other instances are not restarting, so the message is not relayed.
+        self.restarting.send_replace(Some(response));
+        Ok(())
+    }
+
+    // Completes with the ServerRestarting message once the server is shutting down.
+    pub fn subscribe_shutdown(&self) -> watch::Receiver<Option<String>> {
+        self.restarting.subscribe()
+    }
+
+    pub fn with_room_store(room_store: RoomStateStoreHandle) -> Self {
+        Self {
+            room_store: Some(room_store),
+            ..Self::new()
         }
     }
 
+    pub fn get_room_store(&self) -> Option<&RoomStateStoreHandle> {
+        self.room_store.as_ref()
+    }
+
+    pub fn relay_room_message(&self, pool_name: &str, message: String) -> Result<(), AppError> {
+        // Deliver a message broadcasted by another instance to the sockets of this instance.
+        if let Some(room) = self
+            .rooms
+            .read()
+            .map_err(|e| AppError::RwLockError { msg: e.to_string() })?
+            .get(pool_name)
+        {
+            let _ = room.tx.send(message);
+        }
+
+        Ok(())
+    }
+
+    pub fn subscribe_pool_updates(
+        &self,
+        pool_name: &str,
+    ) -> Result<broadcast::Receiver<String>, AppError> {
+        // Subscribe to the in-season updates of a pool, the channel is created on the first subscription.
+        let mut pool_channels = self
+            .pool_channels
+            .write()
+            .map_err(|e| AppError::RwLockError { msg: e.to_string() })?;
+
+        Ok(pool_channels
+            .entry(pool_name.to_string())
+            .or_insert_with(|| broadcast::channel(100).0)
+            .subscribe())
+    }
+
+    pub fn unsubscribe_pool_updates(&self, pool_name: &str) -> Result<(), AppError> {
+        // Drop the channel of the pool once its last receiver is dropped.
+        let mut pool_channels = self
+            .pool_channels
+            .write()
+            .map_err(|e| AppError::RwLockError { msg: e.to_string() })?;
+
+        if pool_channels
+            .get(pool_name)
+            .is_some_and(|tx| tx.receiver_count() == 0)
+        {
+            pool_channels.remove(pool_name);
+        }
+
+        Ok(())
+    }
+
+    pub fn publish_pool_update(&self, pool_name: &str, message: String) -> Result<(), AppError> {
+        // Send an update to the listeners of the pool on every instance.
+        if let Some(room_store) = &self.room_store {
+            room_store.publish_pool_update(pool_name, &message);
+        }
+
+        self.relay_pool_update(pool_name, message)
+    }
+
+    pub fn relay_pool_update(&self, pool_name: &str, message: String) -> Result<(), AppError> {
+        // Send an update to the listeners of the pool on this instance,
+        // the channel is dropped once nobody listens anymore.
+        let mut pool_channels = self
+            .pool_channels
+            .write()
+            .map_err(|e| AppError::RwLockError { msg: e.to_string() })?;
+
+        if let Some(tx) = pool_channels.get(pool_name) {
+            if tx.send(message).is_err() {
+                pool_channels.remove(pool_name);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn create_session(
+        &self,
+        pool_name: &str,
+        number_poolers: u8,
+        socket_id: &str,
+    ) -> Result<String, AppError> {
+        // Issue a session token to a socket that joined a room.
+        let user_id = self
+            .get_authenticated_user_with_socket(socket_id)?
+            .map(|user| user.sub);
+        let session_token = Uuid::new_v4().simple().to_string();
+
+        self.sessions
+            .write()
+            .map_err(|e| AppError::RwLockError { msg: e.to_string() })?
+            .insert(
+                session_token.clone(),
+                RoomSession {
+                    pool_name: pool_name.to_string(),
+                    number_poolers,
+                    socket_id: socket_id.to_string(),
+                    user_id,
+                    room_user: None,
+                    date_disconnected: None,
+                },
+            );
+
+        Ok(session_token)
+    }
+
+    pub fn suspend_session(&self, socket_id: &str) -> Result<(), AppError> {
+        // Keep the room user state of a disconnected socket so it can be resumed.
+        // This needs to be called before the user leaves the room.
+        let mut sessions = self
+            .sessions
+            .write()
+            .map_err(|e| AppError::RwLockError { msg: e.to_string() })?;
+
+        let now = Utc::now().timestamp_millis();
+
+        // Forget the sessions that can no longer be resumed.
+        sessions.retain(|_, session| {
+            session
+                .date_disconnected
+                .is_none_or(|date| date + SESSION_RESUME_WINDOW_MS > now)
+        });
+
+        if let Some(session) = sessions
+            .values_mut()
+            .find(|session| session.socket_id == socket_id && session.date_disconnected.is_none())
+        {
+            let rooms = self
+                .rooms
+                .read()
+                .map_err(|e| AppError::RwLockError { msg: e.to_string() })?;
+
+            session.room_user = session.user_id.as_ref().and_then(|user_id| {
+                rooms
+                    .get(&session.pool_name)
+                    .and_then(|room| room.users.get(user_id).cloned())
+            });
+            session.date_disconnected = Some(now);
+        }
+
+        Ok(())
+    }
+
+    pub fn resume_session(
+        &self,
+        session_token: &str,
+        socket_id: &str,
+    ) -> Result<(broadcast::Receiver<String>, String), AppError> {
+        // Socket command: Resume the room session of a disconnected socket.
+        // The room user state is restored and the receiver and the room name are returned.
+        let user = self.get_authenticated_user_with_socket(socket_id)?;
+
+        let mut sessions = self
+            .sessions
+            .write()
+            .map_err(|e| AppError::RwLockError { msg: e.to_string() })?;
+
+        let session = sessions
+            .get_mut(session_token)
+            .ok_or_else(|| AppError::CustomError {
+                msg: "The session does not exist.".to_string(),
+            })?;
+
+        let now = Utc::now().timestamp_millis();
+        if session
+            .date_disconnected
+            .is_none_or(|date| date + SESSION_RESUME_WINDOW_MS <= now)
+        {
+            return Err(AppError::CustomError {
+                msg: "The session cannot be resumed.".to_string(),
+            });
+        }
+
+        if session.user_id.is_some()
+            && session.user_id != user.as_ref().map(|user| user.sub.clone())
+        {
+            return Err(AppError::CustomError {
+                msg: "The session belongs to another user.".to_string(),
+            });
+        }
+
+        let mut rooms = self
+            .rooms
+            .write()
+            .map_err(|e| AppError::RwLockError { msg: e.to_string() })?;
+
+        let room = rooms
+            .entry(session.pool_name.clone())
+            .or_insert_with(|| RoomState::new(&session.pool_name, session.number_poolers));
+
+        match (session.room_user.take(), &user) {
+            (Some(room_user), _) => {
+                room.users.insert(room_user.id.clone(), room_user);
+            }
+            (None, Some(user)) => room.add_user(user),
+            (None, None) => (),
+        }
+
+        session.socket_id = socket_id.to_string();
+        session.date_disconnected = None;
+
+        Ok((room.tx.subscribe(), session.pool_name.clone()))
+    }
+
     pub fn is_user_in_room(&self, user_id: &str, pool_name: &str) -> Result<bool, AppError> {
         // Tells us if the user is in room. Read lock without copy.
         Ok(self
@@ -130,7 +457,7 @@ impl DraftServerInfo {
             .clone())
     }
 
-    pub fn get_room_tx(&self, pool_name: &str) -> Result<broadcast::Sender<String>, AppError> {
+    pub fn get_room_tx(&self, pool_name: &str) -> Result<RoomSender, AppError> {
         // Return the room tx sender as copy to avoid locking readlock the room to long.
         // The tx is very lightweight it contains an Arc. The goal to limit the amount of time read locking whole rooms.
         let rooms = self
@@ -142,7 +469,11 @@ impl DraftServerInfo {
             msg: format!("Room '{}' could not be found.", pool_name),
         })?;
 
-        Ok(room.tx.clone())
+        Ok(RoomSender {
+            pool_name: pool_name.to_string(),
+            tx: room.tx.clone(),
+            room_store: self.room_store.clone(),
+        })
     }
 
     pub fn get_room_users(&self, pool_name: &str) -> Result<Vec<RoomUser>, AppError> {
@@ -263,6 +594,15 @@ impl DraftServerInfo {
         Ok(())
     }
 
+    pub fn remove_user_sockets(&self, user_id: &str) -> Result<(), AppError> {
+        // Unauthenticate every socket of a user on this instance, their next commands are rejected.
+        self.authenticated_sockets
+            .write()
+            .map_err(|e| AppError::RwLockError { msg: e.to_string() })?
+            .retain(|_, user| user.sub != user_id);
+        Ok(())
+    }
+
     pub fn remove_socket(&self, socket_id: &str) -> Result<(), AppError> {
         // Remove the socket id to the list of authenticated sockets.
         if self.is_socket_authenticated(socket_id)? {
@@ -414,7 +754,7 @@ impl DraftServerInfo {
 }
 
 // A room authenticated users, There users can make some socket commands.
-#[derive(Debug, Serialize, Deserialize, Eq, Clone)]
+#[derive(Debug, Serialize, Deserialize, Eq, Clone, ToSchema)]
 pub struct RoomUser {
     pub id: String,
     pub name: String,
@@ -429,7 +769,7 @@ impl PartialEq for RoomUser {
 }
 
 // Commands that the soket server can receive.
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub enum Command {
     JoinRoom {
         pool_name: String,
@@ -444,19 +784,60 @@ pub enum Command {
         user_id: String,
     },
     OnPoolSettingChanges {
-        pool_settings: PoolSettings,
+        pool_settings: Box<PoolSettings>,
     },
+    // An empty draft order starts the draft with the order set in the lobby.
     StartDraft {
         draft_order: Vec<String>,
     },
+    // Set the draft order in the lobby, the updated pool is sent to the room.
+    UpdateDraftOrder {
+        draft_order_type: DraftOrderType,
+    },
     UndoDraftPlayer,
+    // Skip the pick of an absent pooler, the pooler can make it up later out of turn.
+    SkipPick,
+    PauseDraft,
+    ResumeDraft,
+    // Unwind every pick made after the pick number.
+    RollbackDraft {
+        pick_number: usize,
+    },
+    TradeDraftPicks {
+        trade: TradeDraftPicksRequest,
+    },
     DraftPlayer {
         player: PoolPlayerInfo,
     },
+    NominatePlayer {
+        player: PoolPlayerInfo,
+        opening_bid: u32,
+    },
+    PlaceBid {
+        bidder_id: String,
+        amount: u32,
+    },
+    CloseBid,
+    // Cancel the nomination in progress, only the draft managers can.
+    CancelNomination,
+    GetDraftQueue,
+    // Previously named UpdateDraftQueue, the old name is still accepted.
+    #[serde(alias = "UpdateDraftQueue")]
+    UpdateQueue {
+        players: Vec<PoolPlayerInfo>,
+    },
+    ToggleAutoDraft,
+    ChatMessage {
+        message: String,
+    },
+    // Sent instead of JoinRoom by a reconnecting socket.
+    ResumeRoom {
+        session_token: String,
+    },
 }
 
 // Response return to the sockets clients as commands response.
-#[derive(Deserialize, Serialize)]
+#[derive(Deserialize, Serialize, ToSchema)]
 pub enum CommandResponse {
     Pool {
         pool: Pool,
@@ -467,4 +848,288 @@ pub enum CommandResponse {
     Error {
         message: String,
     },
+    PickTimer {
+        pick_number: usize,
+        remaining_seconds: u32,
+    },
+    DraftQueue {
+        draft_queue: DraftQueue,
+    },
+    // Notify the pooler that proposed a trade that a counter offer was made.
+    CounterTrade {
+        notified_user: String,
+        trade: Trade,
+    },
+    // Best available players suggested to the next drafter when its pick is up.
+    DraftSuggestions {
+        notified_user: String,
+        suggestions: DraftSuggestions,
+    },
+    ChatMessage {
+        chat_message: ChatMessage,
+    },
+    // Session token issued to the socket that joined a room.
+    Session {
+        session_token: String,
+    },
+    // Full room state sent to a socket that resumed its session.
+    Snapshot {
+        session_token: String,
+        pool: Pool,
+        room_users: HashMap<String, RoomUser>,
+    },
+    // Sent to every socket when the server is shutting down, the client should reconnect later.
+    ServerRestarting {
+        message: String,
+    },
+}
+
+// Number of players suggested at each position during the draft.
+pub const DRAFT_SUGGESTIONS_PER_POSITION: usize = 5;
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct DraftSuggestion {
+    pub player: PoolPlayerInfo,
+    pub projected_points_per_game: f64,
+    pub game_played: u32,
+    pub last_season_game_played: u32,
+}
+
+// Best undrafted players of each position, ranked by their projected pool points per game.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, ToSchema)]
+pub struct DraftSuggestions {
+    pub forwards: Vec<DraftSuggestion>,
+    pub defenders: Vec<DraftSuggestion>,
+    pub goalies: Vec<DraftSuggestion>,
+}
+
+impl DraftSuggestions {
+    pub fn new(players: Vec<PlayerInfo>, context: &PoolContext, settings: &PoolSettings) -> Self {
+        // The players already drafted or owned by a pooler (i.g., protected players) are not suggested.
+        let mut suggestions: Vec<DraftSuggestion> = players
+            .into_iter()
+            .filter(|player| {
+                !context.players_name_drafted.contains(&player.id)
+                    && !context
+                        .pooler_roster
+                        .values()
+                        .any(|roster| roster.validate_player_possession(player.id))
+            })
+            .filter_map(|player| {
+                let projected_points_per_game = player.get_projected_points_per_game(settings);
+                let game_played = player.game_played.unwrap_or(0);
+                let last_season_game_played = player
+                    .last_season_stats
+                    .as_ref()
+                    .map_or(0, |stats| stats.game_played);
+
+                PoolPlayerInfo::try_from(player)
+                    .ok()
+                    .map(|player| DraftSuggestion {
+                        player,
+                        projected_points_per_game,
+                        game_played,
+                        last_season_game_played,
+                    })
+            })
+            .collect();
+
+        suggestions.sort_by(|a, b| {
+            b.projected_points_per_game
+                .total_cmp(&a.projected_points_per_game)
+        });
+
+        let mut draft_suggestions = Self::default();
+        for suggestion in suggestions {
+            let position_suggestions = match suggestion.player.position {
+                Position::F => &mut draft_suggestions.forwards,
+                Position::D => &mut draft_suggestions.defenders,
+                Position::G => &mut draft_suggestions.goalies,
+            };
+            if position_suggestions.len() < DRAFT_SUGGESTIONS_PER_POSITION {
+                position_suggestions.push(suggestion);
+            }
+        }
+
+        draft_suggestions
+    }
+}
+
+// Maximum number of characters of a chat message.
+pub const CHAT_MESSAGE_MAX_LENGTH: usize = 500;
+
+// Message sent in the chat of a pool room.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct ChatMessage {
+    pub pool_name: String,
+    pub user_id: String,
+    pub user_name: String,
+    pub message: String,
+    pub date_created: i64,
+}
+
+// query parameters to paginate the chat history, before is a date in milliseconds.
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChatHistoryQuery {
+    pub before: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+// A command that modified (or tried to modify) the draft of a pool, recorded to replay the draft.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub enum DraftRecordedCommand {
+    DraftPlayer {
+        user_id: String,
+        player: PoolPlayerInfo,
+    },
+    // Pick made by the server (pick timer expired or auto draft from the draft queue).
+    AutoDraftPlayer {
+        player: PoolPlayerInfo,
+    },
+    SkipPick,
+    // Pick skipped by the owner, its drafter can make it up later.
+    SkipPickForMakeup {
+        user_id: String,
+    },
+    UndoDraftPlayer {
+        user_id: String,
+    },
+    PauseDraft {
+        user_id: String,
+        is_paused: bool,
+    },
+    RollbackDraft {
+        user_id: String,
+        pick_number: usize,
+    },
+    TradeDraftPicks {
+        user_id: String,
+        trade: TradeDraftPicksRequest,
+    },
+    NominatePlayer {
+        user_id: String,
+        player: PoolPlayerInfo,
+        opening_bid: u32,
+    },
+    PlaceBid {
+        user_id: String,
+        bidder_id: String,
+        amount: u32,
+    },
+    CloseBid {
+        user_id: String,
+    },
+    CancelNomination {
+        user_id: String,
+    },
+}
+
+impl DraftRecordedCommand {
+    pub fn apply(&self, pool: &mut Pool) -> Result<(), AppError> {
+        match self {
+            DraftRecordedCommand::DraftPlayer { user_id, player } => {
+                pool.draft_player(user_id, player)
+            }
+            DraftRecordedCommand::AutoDraftPlayer { player } => pool.auto_draft_player(player),
+            DraftRecordedCommand::SkipPick => pool.skip_pick(),
+            DraftRecordedCommand::SkipPickForMakeup { user_id } => {
+                pool.skip_pick_for_makeup(user_id)
+            }
+            DraftRecordedCommand::UndoDraftPlayer { user_id } => pool.undo_draft_player(user_id),
+            DraftRecordedCommand::PauseDraft { user_id, is_paused } => {
+                pool.pause_draft(user_id, *is_paused)
+            }
+            DraftRecordedCommand::RollbackDraft {
+                user_id,
+                pick_number,
+            } => pool.rollback_draft(user_id, *pick_number),
+            DraftRecordedCommand::TradeDraftPicks { user_id, trade } => {
+                pool.trade_draft_picks(user_id, trade)
+            }
+            DraftRecordedCommand::NominatePlayer {
+                user_id,
+                player,
+                opening_bid,
+            } => pool.nominate_player(user_id, player, *opening_bid),
+            DraftRecordedCommand::PlaceBid {
+                user_id,
+                bidder_id,
+                amount,
+            } => pool.bid_player(user_id, bidder_id, *amount),
+            DraftRecordedCommand::CloseBid { user_id } => pool.close_bid(user_id),
+            DraftRecordedCommand::CancelNomination { user_id } => pool.cancel_nomination(user_id),
+        }
+    }
+}
+
+// A recorded command with the draft state it resulted in.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct DraftRecordedStep {
+    pub command: DraftRecordedCommand,
+    // The error of a rejected command (i.g., it was not the turn of the user).
+    pub error: Option<String>,
+    pub status: PoolState,
+    // None once the draft is completed.
+    pub next_drafter: Option<String>,
+    pub players_name_drafted: Vec<u32>,
+}
+
+impl DraftRecordedStep {
+    // Apply the command to the pool and record the result.
+    // A rejected command leaves the pool as it was.
+    pub fn apply(pool: &mut Pool, command: DraftRecordedCommand) -> (Self, Result<(), AppError>) {
+        let mut updated_pool = pool.clone();
+        let result = command.apply(&mut updated_pool);
+
+        if result.is_ok() {
+            *pool = updated_pool;
+        }
+
+        let next_drafter = if matches!(pool.status, PoolState::Draft) {
+            pool.get_next_drafter().ok()
+        } else {
+            None
+        };
+
+        let step = Self {
+            command,
+            error: result.as_ref().err().map(|e| e.to_string()),
+            status: pool.status.clone(),
+            next_drafter,
+            players_name_drafted: pool
+                .context
+                .as_ref()
+                .map(|context| context.players_name_drafted.clone())
+                .unwrap_or_default(),
+        };
+
+        (step, result)
+    }
+}
+
+// The draft of a pool recorded from its start (or from the moment the recording was enabled).
+// The recording exported by the admins is a fixture that can be replayed by the tests.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct DraftRecording {
+    pub pool_name: String,
+    // The pool when the recording started, None until the draft is started.
+    pub pool: Option<Pool>,
+    pub steps: Vec<DraftRecordedStep>,
+    pub date_created: i64,
+}
+
+impl DraftRecording {
+    // Apply every recorded command again, starting from the recorded pool.
+    pub fn replay(&self) -> Result<Vec<DraftRecordedStep>, AppError> {
+        let mut pool = self.pool.clone().ok_or_else(|| AppError::CustomError {
+            msg: "The draft of the recording was never started.".to_string(),
+        })?;
+
+        Ok(self
+            .steps
+            .iter()
+            .map(|step| DraftRecordedStep::apply(&mut pool, step.command.clone()).0)
+            .collect())
+    }
 }
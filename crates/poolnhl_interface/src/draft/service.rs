@@ -4,12 +4,14 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::errors::Result;
-use crate::pool::model::{PoolPlayerInfo, PoolSettings};
+use crate::pool::model::{
+    DraftOrderType, DraftQueue, Pool, PoolPlayerInfo, PoolSettings, TradeDraftPicksRequest,
+};
 use crate::users::model::UserEmailJwtPayload;
 use std::net::SocketAddr;
 use tokio::sync::broadcast;
 
-use super::model::RoomUser;
+use super::model::{ChatHistoryQuery, ChatMessage, DraftSuggestions, RoomUser};
 
 #[async_trait]
 pub trait DraftService {
@@ -20,6 +22,12 @@ pub trait DraftService {
         user_id: &str,
         draft_order: &Vec<String>,
     ) -> Result<()>;
+    async fn update_draft_order(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        draft_order_type: DraftOrderType,
+    ) -> Result<()>;
     async fn draft_player(
         &self,
         pool_name: &str,
@@ -27,6 +35,46 @@ pub trait DraftService {
         player: PoolPlayerInfo,
     ) -> Result<()>;
     async fn undo_draft_player(&self, pool_name: &str, user_id: &str) -> Result<()>;
+    async fn skip_pick(&self, pool_name: &str, user_id: &str) -> Result<()>;
+    async fn pause_draft(&self, pool_name: &str, user_id: &str, is_paused: bool) -> Result<()>;
+    async fn rollback_draft(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        pick_number: usize,
+    ) -> Result<()>;
+    async fn trade_draft_picks(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        req: TradeDraftPicksRequest,
+    ) -> Result<()>;
+    async fn nominate_player(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        player: PoolPlayerInfo,
+        opening_bid: u32,
+    ) -> Result<()>;
+    async fn bid_player(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        bidder_id: &str,
+        amount: u32,
+    ) -> Result<()>;
+    async fn close_bid(&self, pool_name: &str, user_id: &str) -> Result<()>;
+    async fn cancel_nomination(&self, pool_name: &str, user_id: &str) -> Result<()>;
+
+    // Socket draft queue commands (only answered to the socket making the command):
+    async fn get_draft_queue(&self, pool_name: &str, user_id: &str) -> Result<DraftQueue>;
+    async fn update_draft_queue(
+        &self,
+        pool_name: &str,
+        user_id: &str,
+        players: Vec<PoolPlayerInfo>,
+    ) -> Result<DraftQueue>;
+    async fn toggle_auto_draft(&self, pool_name: &str, user_id: &str) -> Result<DraftQueue>;
     async fn update_pool_settings(
         &self,
         use_id: &str,
@@ -40,7 +88,13 @@ pub trait DraftService {
         pool_name: &str,
         number_poolers: u8,
         socket_addr: SocketAddr,
-    ) -> Result<broadcast::Receiver<String>>;
+    ) -> Result<(broadcast::Receiver<String>, String)>;
+    async fn resume_room(
+        &self,
+        session_token: &str,
+        socket_addr: SocketAddr,
+    ) -> Result<(broadcast::Receiver<String>, Pool)>;
+    async fn suspend_session(&self, socket_addr: SocketAddr) -> Result<()>;
     async fn leave_room(&self, pool_name: &str, socket_addr: SocketAddr) -> Result<()>;
     async fn on_ready(&self, pool_name: &str, socket_addr: SocketAddr) -> Result<()>;
     async fn add_user(
@@ -56,6 +110,23 @@ pub trait DraftService {
         socket_addr: SocketAddr,
     ) -> Result<()>;
 
+    // Socket chat command, the message is broadcasted to the room and stored in the chat history.
+    async fn send_chat_message(
+        &self,
+        pool_name: &str,
+        socket_addr: SocketAddr,
+        message: &str,
+    ) -> Result<()>;
+    // Best available players of each position for the draft of a pool.
+    async fn get_draft_suggestions(&self, pool_name: &str) -> Result<DraftSuggestions>;
+    // Chat history of a pool, the most recent message first. Only read by the members and the invited users.
+    async fn get_chat_messages(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        query: ChatHistoryQuery,
+    ) -> Result<Vec<ChatMessage>>;
+
     // Socket jwt token authentications (called only on socket connection)
     async fn authenticate_web_socket(
         &self,
@@ -71,3 +142,33 @@ pub trait DraftService {
 }
 
 pub type DraftServiceHandle = Arc<dyn DraftService + Send + Sync>;
+
+// Share the draft rooms between the backend instances, the sockets stay on the instance they are connected to.
+// Without a store, the rooms only exist in the process.
+#[async_trait]
+pub trait RoomStateStore {
+    // Relay a message broadcasted to a room of this instance to the same room on the other instances.
+    fn publish(&self, pool_name: &str, message: &str);
+
+    // Relay an in-season update of a pool to the listeners of the other instances.
+    fn publish_pool_update(&self, pool_name: &str, message: &str);
+
+    // Save the users connected to a room through this instance.
+    async fn save_room_users(
+        &self,
+        pool_name: &str,
+        room_users: &HashMap<String, RoomUser>,
+    ) -> Result<()>;
+
+    // List the users connected to a room through every instance.
+    async fn list_room_users(&self, pool_name: &str) -> Result<HashMap<String, RoomUser>>;
+
+    // Save the pick timer started last in a room, on any instance.
+    async fn save_pick_timer(&self, pool_name: &str, timer_id: &str) -> Result<()>;
+
+    // Claim the expiry of a pick timer. Only the timer started last in the room can be claimed,
+    // and only once, so a single instance handles the expired pick.
+    async fn claim_pick_timer(&self, pool_name: &str, timer_id: &str) -> Result<bool>;
+}
+
+pub type RoomStateStoreHandle = Arc<dyn RoomStateStore + Send + Sync>;
@@ -1,11 +1,96 @@
 use std::fmt;
 
-use axum::http::StatusCode;
+use axum::http::{header::RETRY_AFTER, StatusCode};
 use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use serde_json::{json, Value};
+use utoipa::ToSchema;
+
+use crate::localization::Language;
+
+// Stable code of an error, the clients branch on it instead of the message.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    // The request breaks a rule of the pool (i.g., the player is already drafted).
+    InvalidRequest,
+    // Some fields of the request are invalid, they are listed in the details.
+    ValidationFailed,
+    Unauthorized,
+    Forbidden,
+    InvalidUserId,
+    NotAParticipant,
+    PoolNotFound,
+    PoolAlreadyExists,
+    TradeNotFound,
+    InvalidPoolStatus,
+    NotYourTurn,
+    SalaryCapExceeded,
+    // The Idempotency-Key was used for another request or its request is still processed.
+    IdempotencyKeyReused,
+    RequestInProgress,
+    // The pool was modified by another request since it was read.
+    PoolModified,
+    RateLimited,
+    ExternalServiceError,
+    InternalError,
+}
+
+impl ErrorCode {
+    pub fn get_status(&self) -> StatusCode {
+        match self {
+            ErrorCode::InvalidRequest | ErrorCode::ValidationFailed | ErrorCode::InvalidUserId => {
+                StatusCode::BAD_REQUEST
+            }
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden | ErrorCode::NotAParticipant => StatusCode::FORBIDDEN,
+            ErrorCode::PoolNotFound | ErrorCode::TradeNotFound => StatusCode::NOT_FOUND,
+            ErrorCode::PoolAlreadyExists
+            | ErrorCode::InvalidPoolStatus
+            | ErrorCode::NotYourTurn
+            | ErrorCode::RequestInProgress
+            | ErrorCode::PoolModified => StatusCode::CONFLICT,
+            ErrorCode::SalaryCapExceeded | ErrorCode::IdempotencyKeyReused => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::ExternalServiceError => StatusCode::BAD_GATEWAY,
+            ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+// Body of every error response.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ErrorResponse {
+    pub code: ErrorCode,
+    pub message: String,
+    // Data specific to the code (i.g., the invalid fields or the seconds before retrying).
+    pub details: Option<Value>,
+}
+
+// Invalid value of a request field (i.g., settings.number_forwards).
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub msg: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, msg: &str) -> Self {
+        Self {
+            field: field.to_string(),
+            msg: msg.to_string(),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub enum AppError {
     CustomError { msg: String },
+    // Error that the clients need to identify (i.g., the pool does not exist).
+    CodedError { code: ErrorCode, msg: String },
     AuthError { msg: String },
     MongoError { msg: String },
     ParseError { msg: String },
@@ -18,16 +103,54 @@ pub enum AppError {
     ReqwestError { msg: String },
     NonMatchingKid { msg: String },
     RwLockError { msg: String },
+    RedisError { msg: String },
+    RateLimitError { retry_after_seconds: u64 },
+    ValidationError { errors: Vec<FieldError> },
 }
 
 pub type Result<T> = std::result::Result<T, AppError>;
 
 impl std::error::Error for AppError {} // TODO: why?
 
+impl AppError {
+    pub fn get_code(&self) -> ErrorCode {
+        match self {
+            AppError::CustomError { .. }
+            | AppError::ParseError { .. }
+            | AppError::HexError { .. }
+            | AppError::ObjectIdError { .. } => ErrorCode::InvalidRequest,
+            AppError::CodedError { code, .. } => *code,
+            AppError::AuthError { .. }
+            | AppError::JwtError { .. }
+            | AppError::NonMatchingKid { .. } => ErrorCode::Unauthorized,
+            AppError::ReqwestError { .. } => ErrorCode::ExternalServiceError,
+            AppError::RateLimitError { .. } => ErrorCode::RateLimited,
+            AppError::ValidationError { .. } => ErrorCode::ValidationFailed,
+            AppError::MongoError { .. }
+            | AppError::BcryptError { .. }
+            | AppError::RecoveryError { .. }
+            | AppError::BsonError { .. }
+            | AppError::RwLockError { .. }
+            | AppError::RedisError { .. } => ErrorCode::InternalError,
+        }
+    }
+
+    pub fn get_details(&self) -> Option<Value> {
+        match self {
+            AppError::RateLimitError {
+                retry_after_seconds,
+            } => Some(json!({ "retry_after_seconds": retry_after_seconds })),
+            AppError::ValidationError { errors } => Some(json!({ "errors": errors })),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             AppError::CustomError { msg } => write!(f, "Custom Error: '{}'", msg),
+            AppError::CodedError { msg, .. } => write!(f, "{}", msg),
             AppError::AuthError { msg } => write!(f, "Authentication Error: '{}'", msg),
             AppError::MongoError { msg } => write!(f, "MongoDB Error: '{}'", msg),
             AppError::ParseError { msg } => write!(f, "Parse Error: '{}'", msg),
@@ -40,16 +163,57 @@ impl fmt::Display for AppError {
             AppError::ReqwestError { msg } => write!(f, "Reqwest Error: '{}'", msg),
             AppError::NonMatchingKid { msg } => write!(f, "Non matching kid Error: '{}'", msg),
             AppError::RwLockError { msg } => write!(f, "Mutex locking error '{}'", msg),
+            AppError::RedisError { msg } => write!(f, "Redis Error: '{}'", msg),
+            AppError::RateLimitError {
+                retry_after_seconds,
+            } => write!(
+                f,
+                "Rate Limit Error: 'Too many requests, retry in {} seconds.'",
+                retry_after_seconds
+            ),
+            AppError::ValidationError { errors } => write!(
+                f,
+                "Validation Error: '{}'",
+                errors
+                    .iter()
+                    .map(|error| format!("{}: {}", error.field, error.msg))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
         }
     }
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        //
-        // Convert object to json
-        let body = self.to_string();
+        let code = self.get_code();
+
+        // Every router answers the errors with the same json body.
+        // The message of the catalog is used when the code has one, the localization middleware translates it.
+        let error = ErrorResponse {
+            code,
+            message: Language::En
+                .get_error_message(code)
+                .map(|message| message.to_string())
+                .unwrap_or_else(|| self.to_string()),
+            details: self.get_details(),
+        };
+
+        let mut response = if let AppError::RateLimitError {
+            retry_after_seconds,
+        } = self
+        {
+            (
+                code.get_status(),
+                [(RETRY_AFTER, retry_after_seconds.to_string())],
+                Json(error.clone()),
+            )
+                .into_response()
+        } else {
+            (code.get_status(), Json(error.clone())).into_response()
+        };
 
-        (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
+        response.extensions_mut().insert(error);
+        response
     }
 }
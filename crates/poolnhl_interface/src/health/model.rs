@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Result of the check of a dependency of the server.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct DependencyStatus {
+    pub name: String,
+    pub ready: bool,
+
+    // The reason why the dependency is not ready.
+    pub error: Option<String>,
+}
+
+impl DependencyStatus {
+    pub fn new<E: ToString>(name: &str, result: Result<(), E>) -> Self {
+        Self {
+            name: name.to_string(),
+            ready: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
+}
+
+// The server is ready to receive traffic once all its dependencies are ready.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct Readiness {
+    pub ready: bool,
+    pub dependencies: Vec<DependencyStatus>,
+}
+
+impl Readiness {
+    pub fn new(dependencies: Vec<DependencyStatus>) -> Self {
+        Self {
+            ready: dependencies.iter().all(|dependency| dependency.ready),
+            dependencies,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::health::model::Readiness;
+
+#[async_trait]
+pub trait HealthService {
+    // Check that the database, the JSON web key set and the NHL api can be used.
+    async fn get_readiness(&self) -> Readiness;
+}
+
+pub type HealthServiceHandle = Arc<dyn HealthService + Send + Sync>;
@@ -0,0 +1,107 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::errors::AppError;
+use crate::users::model::UserId;
+
+// Number of hours an invitation is valid when no validity is provided.
+pub const DEFAULT_INVITATION_VALIDITY_HOURS: u32 = 72;
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct Invitation {
+    // Random token identifying the invite link.
+    pub token: String,
+    pub pool_name: String,
+    pub invited_by: String,
+
+    // When one of these is set, only the invited user can accept the invitation.
+    // Otherwise, anyone with the invite link can accept it.
+    pub invited_user_id: Option<String>,
+    pub invited_email: Option<String>,
+
+    pub date_created: i64,
+    pub date_expiration: i64,
+
+    // Users that accepted the invitation.
+    pub accepted_by: Vec<String>,
+}
+
+impl Invitation {
+    pub fn new(invited_by: &str, req: &CreateInvitationRequest) -> Self {
+        let now = Utc::now().timestamp_millis();
+        let validity_hours = req
+            .validity_hours
+            .unwrap_or(DEFAULT_INVITATION_VALIDITY_HOURS);
+
+        Self {
+            token: Uuid::new_v4().simple().to_string(),
+            pool_name: req.pool_name.clone(),
+            invited_by: invited_by.to_string(),
+            invited_user_id: req.invited_user_id.clone().map(String::from),
+            invited_email: req.invited_email.clone(),
+            date_created: now,
+            date_expiration: now + validity_hours as i64 * 3600000,
+            accepted_by: Vec::new(),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.date_expiration <= Utc::now().timestamp_millis()
+    }
+
+    pub fn accept(&mut self, user_id: &str, user_email: &str) -> Result<(), AppError> {
+        if self.is_expired() {
+            return Err(AppError::CustomError {
+                msg: "The invitation has expired.".to_string(),
+            });
+        }
+
+        // Validate that the invitation was sent to this user.
+        let is_invited_user = match (&self.invited_user_id, &self.invited_email) {
+            (None, None) => true,
+            (invited_user_id, invited_email) => {
+                invited_user_id.as_deref() == Some(user_id)
+                    || invited_email
+                        .as_deref()
+                        .is_some_and(|email| email.eq_ignore_ascii_case(user_email))
+            }
+        };
+
+        if !is_invited_user {
+            return Err(AppError::CustomError {
+                msg: "The invitation was sent to another user.".to_string(),
+            });
+        }
+
+        if !self.accepted_by.iter().any(|id| id == user_id) {
+            self.accepted_by.push(user_id.to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// payload to sent when inviting a user to a pool.
+// Without invited user id or email, an invite link usable by anyone is generated.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct CreateInvitationRequest {
+    pub pool_name: String,
+    pub invited_user_id: Option<UserId>,
+    pub invited_email: Option<String>,
+    pub validity_hours: Option<u32>,
+}
+
+// payload to sent when accepting an invitation.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct AcceptInvitationRequest {
+    pub token: String,
+}
+
+// payload to sent when deleting an invitation.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct DeleteInvitationRequest {
+    pub pool_name: String,
+    pub token: String,
+}
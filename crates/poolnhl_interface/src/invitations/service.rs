@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::errors::Result;
+use crate::invitations::model::{
+    AcceptInvitationRequest, CreateInvitationRequest, DeleteInvitationRequest, Invitation,
+};
+use crate::users::model::UserEmailJwtPayload;
+
+#[async_trait]
+pub trait InvitationsService {
+    async fn get_invitation(&self, token: &str) -> Result<Invitation>;
+    async fn list_invitations(&self, user_id: &str, pool_name: &str) -> Result<Vec<Invitation>>;
+    async fn create_invitation(
+        &self,
+        user_id: &str,
+        req: CreateInvitationRequest,
+    ) -> Result<Invitation>;
+    async fn accept_invitation(
+        &self,
+        user: &UserEmailJwtPayload,
+        req: AcceptInvitationRequest,
+    ) -> Result<Invitation>;
+    async fn delete_invitation(&self, user_id: &str, req: DeleteInvitationRequest) -> Result<()>;
+}
+
+pub type InvitationsServiceHandle = Arc<dyn InvitationsService + Send + Sync>;
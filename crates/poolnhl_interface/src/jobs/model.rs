@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// State of a scheduled job, shared by the backend instances through the jobs collection.
+// Dates are in milliseconds.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct JobInfo {
+    pub name: String,
+
+    // Human readable schedule of the job (i.g., every 1 hours).
+    pub schedule: String,
+
+    // The date from which the job can run again.
+    pub next_run: Option<i64>,
+
+    // The instance running the job and the date its lock expires.
+    pub locked_by: Option<String>,
+    pub locked_until: Option<i64>,
+
+    pub last_run_start: Option<i64>,
+    pub last_run_end: Option<i64>,
+
+    // The error returned by the last run, None when it succeeded.
+    pub last_error: Option<String>,
+}
+
+// payload to sent when an administrator runs a job outside of its schedule.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct TriggerJobRequest {
+    pub name: String,
+}
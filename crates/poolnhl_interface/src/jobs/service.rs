@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::errors::Result;
+use crate::jobs::model::JobInfo;
+
+#[async_trait]
+pub trait JobsService {
+    // Start running the registered jobs on their schedule.
+    fn start(&self);
+
+    async fn list_jobs(&self) -> Result<Vec<JobInfo>>;
+
+    // Run a job now, it fails if the job is already running on an instance.
+    async fn trigger_job(&self, name: &str) -> Result<JobInfo>;
+}
+
+pub type JobsServiceHandle = Arc<dyn JobsService + Send + Sync>;
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+// Number of users returned in each leaderboard when no limit is provided.
+pub const DEFAULT_LEADERBOARD_LIMIT: usize = 25;
+
+// query parameters of the leaderboards, only the pools of the season are ranked when it is provided.
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct LeaderboardsQuery {
+    pub season: Option<u32>,
+    pub limit: Option<usize>,
+}
+
+// The results of a user in the completed pools.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct LeaderboardEntry {
+    pub user_id: String,
+
+    // The name of the user in its most recent pool.
+    pub name: String,
+
+    pub pools_completed: u32,
+    pub championships: u32,
+
+    // The average rank of the user, 1 being the best.
+    pub average_finish: f64,
+    pub total_points: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct Leaderboards {
+    pub season: Option<u32>,
+    pub most_championships: Vec<LeaderboardEntry>,
+    pub best_average_finish: Vec<LeaderboardEntry>,
+    pub most_total_points: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboards {
+    pub fn new(season: Option<u32>, entries: &[LeaderboardEntry], limit: usize) -> Self {
+        let ranked = |compare: fn(&LeaderboardEntry, &LeaderboardEntry) -> std::cmp::Ordering| {
+            let mut entries = entries.to_vec();
+            entries.sort_by(compare);
+            entries.truncate(limit);
+            entries
+        };
+
+        Self {
+            season,
+            most_championships: ranked(|a, b| {
+                b.championships
+                    .cmp(&a.championships)
+                    .then_with(|| a.average_finish.total_cmp(&b.average_finish))
+            }),
+            best_average_finish: ranked(|a, b| {
+                a.average_finish
+                    .total_cmp(&b.average_finish)
+                    .then_with(|| b.pools_completed.cmp(&a.pools_completed))
+            }),
+            most_total_points: ranked(|a, b| b.total_points.total_cmp(&a.total_points)),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::errors::Result;
+use crate::leaderboards::model::{Leaderboards, LeaderboardsQuery};
+
+#[async_trait]
+pub trait LeaderboardsService {
+    async fn get_leaderboards(&self, query: LeaderboardsQuery) -> Result<Leaderboards>;
+}
+
+pub type LeaderboardsServiceHandle = Arc<dyn LeaderboardsService + Send + Sync>;
@@ -1,6 +1,18 @@
+pub mod achievements;
+pub mod admin;
+pub mod archives;
 pub mod daily_leaders;
 pub mod draft;
 pub mod errors;
+pub mod health;
+pub mod invitations;
+pub mod jobs;
+pub mod leaderboards;
+pub mod localization;
+pub mod mailer;
+pub mod notifications;
 pub mod players;
 pub mod pool;
+pub mod time_provider;
 pub mod users;
+pub mod webhooks;
@@ -0,0 +1,232 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::errors::ErrorCode;
+use crate::notifications::model::NotificationType;
+
+// The languages of the user-facing messages.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, ToSchema)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    En,
+    #[serde(rename = "fr-CA")]
+    FrCa,
+}
+
+impl Language {
+    // The first supported language of an Accept-Language header (i.g., "fr-CA,fr;q=0.9,en;q=0.8").
+    pub fn from_accept_language(accept_language: &str) -> Self {
+        accept_language
+            .split(',')
+            .filter_map(|language| {
+                let tag = language.split(';').next()?.trim().to_lowercase();
+
+                if tag.starts_with("fr") {
+                    Some(Language::FrCa)
+                } else if tag.starts_with("en") {
+                    Some(Language::En)
+                } else {
+                    None
+                }
+            })
+            .next()
+            .unwrap_or_default()
+    }
+
+    pub fn get_tag(&self) -> &'static str {
+        match self {
+            Language::En => "en",
+            Language::FrCa => "fr-CA",
+        }
+    }
+
+    // The message of an error code.
+    // The generic codes have no message, the message of the error describes the broken rule.
+    pub fn get_error_message(&self, code: ErrorCode) -> Option<&'static str> {
+        let message = match (self, code) {
+            (_, ErrorCode::InvalidRequest | ErrorCode::InternalError) => return None,
+            (Language::En, ErrorCode::ValidationFailed) => {
+                "Some fields of the request are invalid."
+            }
+            (Language::FrCa, ErrorCode::ValidationFailed) => {
+                "Certains champs de la requête sont invalides."
+            }
+            (Language::En, ErrorCode::Unauthorized) => "You need to be logged in.",
+            (Language::FrCa, ErrorCode::Unauthorized) => "Vous devez être connecté.",
+            (Language::En, ErrorCode::Forbidden) => "You do not have the rights for this action.",
+            (Language::FrCa, ErrorCode::Forbidden) => {
+                "Vous n'avez pas les droits pour cette action."
+            }
+            (Language::En, ErrorCode::InvalidUserId) => "The user id is not valid.",
+            (Language::FrCa, ErrorCode::InvalidUserId) => {
+                "L'identifiant de l'utilisateur est invalide."
+            }
+            (Language::En, ErrorCode::NotAParticipant) => "You are not a participant of this pool.",
+            (Language::FrCa, ErrorCode::NotAParticipant) => {
+                "Vous n'êtes pas un participant de ce pool."
+            }
+            (Language::En, ErrorCode::PoolNotFound) => "The pool does not exist.",
+            (Language::FrCa, ErrorCode::PoolNotFound) => "Le pool n'existe pas.",
+            (Language::En, ErrorCode::PoolAlreadyExists) => "A pool with this name already exists.",
+            (Language::FrCa, ErrorCode::PoolAlreadyExists) => "Un pool avec ce nom existe déjà.",
+            (Language::En, ErrorCode::TradeNotFound) => "The trade does not exist.",
+            (Language::FrCa, ErrorCode::TradeNotFound) => "L'échange n'existe pas.",
+            (Language::En, ErrorCode::InvalidPoolStatus) => {
+                "This action is not allowed at this stage of the pool."
+            }
+            (Language::FrCa, ErrorCode::InvalidPoolStatus) => {
+                "Cette action n'est pas permise à cette étape du pool."
+            }
+            (Language::En, ErrorCode::NotYourTurn) => "It is not your turn.",
+            (Language::FrCa, ErrorCode::NotYourTurn) => "Ce n'est pas votre tour.",
+            (Language::En, ErrorCode::SalaryCapExceeded) => "The salary cap would be exceeded.",
+            (Language::FrCa, ErrorCode::SalaryCapExceeded) => "Le plafond salarial serait dépassé.",
+            (Language::En, ErrorCode::IdempotencyKeyReused) => {
+                "The Idempotency-Key was already used for another request."
+            }
+            (Language::FrCa, ErrorCode::IdempotencyKeyReused) => {
+                "La clé Idempotency-Key a déjà été utilisée pour une autre requête."
+            }
+            (Language::En, ErrorCode::RequestInProgress) => {
+                "The request made with this Idempotency-Key is still being processed."
+            }
+            (Language::FrCa, ErrorCode::RequestInProgress) => {
+                "La requête faite avec cette clé Idempotency-Key est toujours en traitement."
+            }
+            (Language::En, ErrorCode::PoolModified) => {
+                "The pool was modified by another request, retry."
+            }
+            (Language::FrCa, ErrorCode::PoolModified) => {
+                "Le pool a été modifié par une autre requête, réessayez."
+            }
+            (Language::En, ErrorCode::RateLimited) => "Too many requests, retry later.",
+            (Language::FrCa, ErrorCode::RateLimited) => "Trop de requêtes, réessayez plus tard.",
+            (Language::En, ErrorCode::ExternalServiceError) => {
+                "An external service is unavailable, retry later."
+            }
+            (Language::FrCa, ErrorCode::ExternalServiceError) => {
+                "Un service externe est indisponible, réessayez plus tard."
+            }
+        };
+
+        Some(message)
+    }
+
+    pub fn get_notification_message(
+        &self,
+        notification_type: &NotificationType,
+        pool_name: &str,
+    ) -> String {
+        match (self, notification_type) {
+            (Language::En, NotificationType::TradeProposed) => {
+                format!("A trade was proposed to you in the pool {}.", pool_name)
+            }
+            (Language::FrCa, NotificationType::TradeProposed) => {
+                format!("Un échange vous a été proposé dans le pool {}.", pool_name)
+            }
+            (Language::En, NotificationType::DraftStarted) => {
+                format!("The draft of the pool {} has started.", pool_name)
+            }
+            (Language::FrCa, NotificationType::DraftStarted) => {
+                format!("Le repêchage du pool {} est commencé.", pool_name)
+            }
+            (Language::En, NotificationType::DraftPickUp) => {
+                format!("Your pick is up in the draft of the pool {}.", pool_name)
+            }
+            (Language::FrCa, NotificationType::DraftPickUp) => {
+                format!("C'est votre tour au repêchage du pool {}.", pool_name)
+            }
+            (Language::En, NotificationType::RosterDeadline) => {
+                format!(
+                    "Tomorrow is a roster modification day in the pool {}.",
+                    pool_name
+                )
+            }
+            (Language::FrCa, NotificationType::RosterDeadline) => {
+                format!(
+                    "Demain est une journée de modification des alignements dans le pool {}.",
+                    pool_name
+                )
+            }
+            (Language::En, NotificationType::ProtectionDeadline) => {
+                format!(
+                    "The players of the pool {} need to be protected before the draft tomorrow.",
+                    pool_name
+                )
+            }
+            (Language::FrCa, NotificationType::ProtectionDeadline) => {
+                format!(
+                    "Les joueurs du pool {} doivent être protégés avant le repêchage de demain.",
+                    pool_name
+                )
+            }
+            (Language::En, NotificationType::TradeBlockListing) => {
+                format!(
+                    "A player was put on the trade block in the pool {}.",
+                    pool_name
+                )
+            }
+            (Language::FrCa, NotificationType::TradeBlockListing) => {
+                format!(
+                    "Un joueur a été mis sur le bloc des échanges dans le pool {}.",
+                    pool_name
+                )
+            }
+            (Language::En, NotificationType::TradeExpired) => {
+                format!(
+                    "A trade expired without an answer in the pool {}.",
+                    pool_name
+                )
+            }
+            (Language::FrCa, NotificationType::TradeExpired) => {
+                format!(
+                    "Un échange a expiré sans réponse dans le pool {}.",
+                    pool_name
+                )
+            }
+            (Language::En, NotificationType::TeamAdopted) => {
+                format!("You took over a team in the pool {}.", pool_name)
+            }
+            (Language::FrCa, NotificationType::TeamAdopted) => {
+                format!("Vous avez repris une équipe dans le pool {}.", pool_name)
+            }
+            (Language::En, NotificationType::WatchedPlayerAvailable) => {
+                format!(
+                    "A player of your watchlist became a free agent in the pool {}.",
+                    pool_name
+                )
+            }
+            (Language::FrCa, NotificationType::WatchedPlayerAvailable) => {
+                format!(
+                    "Un joueur de votre liste de surveillance est devenu agent libre dans le pool {}.",
+                    pool_name
+                )
+            }
+            (Language::En, NotificationType::StarterRuledOut) => {
+                format!(
+                    "A player of your starting roster is ruled out tonight in the pool {}.",
+                    pool_name
+                )
+            }
+            (Language::FrCa, NotificationType::StarterRuledOut) => {
+                format!(
+                    "Un joueur de votre alignement partant est exclu ce soir dans le pool {}.",
+                    pool_name
+                )
+            }
+            (Language::En, NotificationType::RosterOverCap) => {
+                format!(
+                    "Your roster is over the salary cap after contract changes in the pool {}.",
+                    pool_name
+                )
+            }
+            (Language::FrCa, NotificationType::RosterOverCap) => {
+                format!(
+                    "Votre alignement dépasse le plafond salarial à la suite de changements de contrats dans le pool {}.",
+                    pool_name
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::errors::Result;
+use crate::mailer::model::Email;
+
+#[async_trait]
+pub trait Mailer {
+    async fn send_email(&self, email: Email) -> Result<()>;
+}
+
+pub type MailerHandle = Arc<dyn Mailer + Send + Sync>;
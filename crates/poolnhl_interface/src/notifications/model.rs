@@ -0,0 +1,108 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::localization::Language;
+
+// Number of notifications returned when no limit is provided.
+pub const DEFAULT_NOTIFICATIONS_LIMIT: i64 = 50;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
+pub enum NotificationType {
+    TradeProposed,
+    DraftStarted,
+    DraftPickUp,
+    RosterDeadline,
+    ProtectionDeadline,
+    TradeBlockListing,
+    TradeExpired,
+    TeamAdopted,
+    WatchedPlayerAvailable,
+    StarterRuledOut,
+    RosterOverCap,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct Notification {
+    pub id: String,
+    pub user_id: String,
+    pub pool_name: String,
+    pub notification_type: NotificationType,
+    pub message: String,
+    pub date_created: i64,
+    pub is_read: bool,
+}
+
+impl Notification {
+    pub fn new(
+        user_id: &str,
+        pool_name: &str,
+        notification_type: NotificationType,
+        message: &str,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4().simple().to_string(),
+            user_id: user_id.to_string(),
+            pool_name: pool_name.to_string(),
+            notification_type,
+            message: message.to_string(),
+            date_created: Utc::now().timestamp_millis(),
+            is_read: false,
+        }
+    }
+}
+
+// Notification types a user does not want to receive for a pool.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct NotificationPreferences {
+    pub user_id: String,
+    pub pool_name: String,
+    pub disabled_types: Vec<NotificationType>,
+
+    // When enabled, the notifications are also sent by email to this address.
+    pub email_enabled: Option<bool>,
+    pub email: Option<String>,
+
+    // Language of the notifications and emails, english when not set.
+    pub language: Option<Language>,
+}
+
+impl NotificationPreferences {
+    pub fn is_enabled(&self, notification_type: &NotificationType) -> bool {
+        !self.disabled_types.contains(notification_type)
+    }
+
+    pub fn get_email(&self) -> Option<&str> {
+        // The email address of the user if it opted in to the email notifications.
+        if self.email_enabled == Some(true) {
+            return self.email.as_deref();
+        }
+        None
+    }
+}
+
+// query parameters to list the notifications of a user, the newest first.
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct NotificationsQuery {
+    pub pool_name: Option<String>,
+    pub unread_only: Option<bool>,
+    pub limit: Option<i64>,
+}
+
+// payload to sent when marking notifications as read.
+// Without notification ids, every notification of the user is marked as read.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct MarkNotificationsReadRequest {
+    pub notification_ids: Option<Vec<String>>,
+}
+
+// payload to sent when updating the notification preferences of a pool.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct UpdateNotificationPreferencesRequest {
+    pub pool_name: String,
+    pub disabled_types: Vec<NotificationType>,
+    pub email_enabled: Option<bool>,
+    pub language: Option<Language>,
+}
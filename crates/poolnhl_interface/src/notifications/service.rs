@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::errors::Result;
+use crate::notifications::model::{
+    MarkNotificationsReadRequest, Notification, NotificationPreferences, NotificationsQuery,
+    UpdateNotificationPreferencesRequest,
+};
+use crate::users::model::UserEmailJwtPayload;
+
+#[async_trait]
+pub trait NotificationsService {
+    async fn list_notifications(
+        &self,
+        user_id: &str,
+        query: NotificationsQuery,
+    ) -> Result<Vec<Notification>>;
+    async fn mark_as_read(&self, user_id: &str, req: MarkNotificationsReadRequest) -> Result<()>;
+    async fn get_preferences(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+    ) -> Result<NotificationPreferences>;
+    async fn update_preferences(
+        &self,
+        user: &UserEmailJwtPayload,
+        req: UpdateNotificationPreferencesRequest,
+    ) -> Result<NotificationPreferences>;
+}
+
+pub type NotificationsServiceHandle = Arc<dyn NotificationsService + Send + Sync>;
@@ -1,10 +1,19 @@
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::errors::AppError;
+use crate::pool::model::{InjuryStatus, PoolPlayerInfo, PoolSettings, Position};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct GetPlayerQuery {
     pub active: Option<bool>,
     #[serde(deserialize_with = "comma_separated")]
     pub positions: Option<Vec<String>>,
+    pub name: Option<String>,
+    pub team: Option<u32>,
     pub sort: Option<String>,
     pub descending: Option<bool>,
     pub skip: Option<u64>,
@@ -21,7 +30,7 @@ where
     Ok(Some(s.split(',').map(|s| s.to_string()).collect()))
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct PlayerInfo {
     pub active: bool,
     pub id: u32, // ID from the NHL API.
@@ -38,4 +47,156 @@ pub struct PlayerInfo {
     pub points_per_game: Option<f32>,
     pub goal_against_average: Option<f32>,
     pub save_percentage: Option<f32>,
+    pub wins: Option<u32>,
+    pub shutouts: Option<u32>,
+
+    // Stats of the last regular season, updated by the players sync.
+    pub last_season_stats: Option<PlayerSeasonStats>,
+
+    // Positions the player was listed at on a NHL roster during the season positions_season.
+    pub positions: Option<Vec<Position>>,
+    pub positions_season: Option<u32>,
+
+    // Injury designation listed on the NHL roster, not set when the player is healthy.
+    pub injury_status: Option<InjuryStatus>,
+}
+
+impl PlayerInfo {
+    pub fn get_eligible_positions(&self, roster_position: &Position, season: u32) -> Vec<Position> {
+        // The player keeps the positions of the current season and becomes eligible to its roster position.
+        // The eligibility starts over with a new season.
+        let mut positions = match &self.positions {
+            Some(positions) if self.positions_season == Some(season) => positions.clone(),
+            _ => Vec::new(),
+        };
+        if !positions.contains(roster_position) {
+            positions.push(roster_position.clone());
+        }
+        positions
+    }
+
+    pub fn get_current_season_stats(&self) -> PlayerSeasonStats {
+        PlayerSeasonStats {
+            game_played: self.game_played.unwrap_or(0),
+            goals: self.goals.unwrap_or(0),
+            assists: self.assists.unwrap_or(0),
+            wins: self.wins.unwrap_or(0),
+            shutouts: self.shutouts.unwrap_or(0),
+        }
+    }
+
+    // Pool points per game expected from the player with the scoring settings of the pool.
+    // A game of the current season weights twice as much as a game of the last season.
+    pub fn get_projected_points_per_game(&self, settings: &PoolSettings) -> f64 {
+        let current_season = self.get_current_season_stats();
+        let last_season = self.last_season_stats.clone().unwrap_or_default();
+
+        let games = 2 * current_season.game_played + last_season.game_played;
+        if games == 0 {
+            return 0.0;
+        }
+
+        (2.0 * current_season.get_pool_points(&self.position, settings)
+            + last_season.get_pool_points(&self.position, settings))
+            / f64::from(games)
+    }
+}
+
+// Regular season stats of a player, cumulated over every team of the player.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, ToSchema)]
+pub struct PlayerSeasonStats {
+    pub game_played: u32,
+    pub goals: u32,
+    pub assists: u32,
+    pub wins: u32,
+    pub shutouts: u32,
+}
+
+impl PlayerSeasonStats {
+    // Pool points made during the season. Only the goals, assists, wins and shutouts
+    // are known for a whole season, the other scoring categories are not counted.
+    pub fn get_pool_points(&self, position: &str, settings: &PoolSettings) -> f64 {
+        let goals = f64::from(self.goals);
+        let assists = f64::from(self.assists);
+
+        match position {
+            "G" => {
+                let goalies_settings = &settings.goalies_settings;
+                goals * goalies_settings.points_per_goals
+                    + assists * goalies_settings.points_per_assists
+                    + f64::from(self.wins) * goalies_settings.points_per_wins
+                    + f64::from(self.shutouts) * goalies_settings.points_per_shutouts
+            }
+            "D" => {
+                goals * settings.defense_settings.points_per_goals
+                    + assists * settings.defense_settings.points_per_assists
+            }
+            _ => {
+                goals * settings.forwards_settings.points_per_goals
+                    + assists * settings.forwards_settings.points_per_assists
+            }
+        }
+    }
+}
+
+impl TryFrom<PlayerInfo> for PoolPlayerInfo {
+    type Error = AppError;
+
+    fn try_from(player: PlayerInfo) -> Result<Self, Self::Error> {
+        // Convert a player of the players collection to a player that can be added to a pool.
+        let position = match player.position.as_str() {
+            "F" => Position::F,
+            "D" => Position::D,
+            "G" => Position::G,
+            position => {
+                return Err(AppError::ParseError {
+                    msg: format!("The position '{}' is not supported.", position),
+                })
+            }
+        };
+
+        Ok(PoolPlayerInfo {
+            id: player.id,
+            name: player.name,
+            team: player.team,
+            position,
+            age: player.age,
+            salary_cap: player.salary_cap,
+            contract_expiration_season: player.contract_expiration_season,
+            positions: player.positions,
+            injury_status: player.injury_status,
+        })
+    }
+}
+
+// A NHL game of the synced schedule.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct ScheduledGame {
+    pub id: u32,
+    pub home_team: u32, // NHL team id.
+    pub away_team: u32,
+    pub start_time_utc: Option<String>,
+}
+
+// The NHL games of a date, synced with the players.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct DailySchedule {
+    pub date: String,
+    pub games: Vec<ScheduledGame>,
+}
+
+impl DailySchedule {
+    pub fn new(date: &str) -> Self {
+        Self {
+            date: date.to_string(),
+            games: Vec::new(),
+        }
+    }
+
+    pub fn get_teams_playing(&self) -> HashSet<u32> {
+        self.games
+            .iter()
+            .flat_map(|game| [game.home_team, game.away_team])
+            .collect()
+    }
 }
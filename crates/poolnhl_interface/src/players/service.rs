@@ -3,12 +3,21 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::errors::Result;
-use crate::players::model::{GetPlayerQuery, PlayerInfo};
+use crate::players::model::{DailySchedule, GetPlayerQuery, PlayerInfo};
 
 #[async_trait]
 pub trait PlayersService {
     async fn get_players(&self, date: GetPlayerQuery) -> Result<Vec<PlayerInfo>>;
     async fn get_players_with_name(&self, name: &str) -> Result<Vec<PlayerInfo>>;
+    async fn get_player(&self, id: u32) -> Result<PlayerInfo>;
+    // The NHL games of a date (YYYY-MM-DD).
+    async fn get_schedule(&self, date: &str) -> Result<DailySchedule>;
+
+    // Synchronize the players collection with the NHL api, return the number of synced players.
+    async fn sync_players(&self) -> Result<usize>;
+
+    // Synchronize the contracts of the players with the contracts source, return the number of updated players.
+    async fn sync_contracts(&self) -> Result<usize>;
 }
 
 pub type PlayersServiceHandle = Arc<dyn PlayersService + Send + Sync>;
@@ -1,2 +1,3 @@
+pub mod authorization;
 pub mod model;
 pub mod service;
@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use tracing::warn;
+
+use crate::errors::{AppError, ErrorCode};
+use crate::pool::model::Pool;
+
+// The site administrators can manage any pool for support purposes.
+static SITE_ADMINS: OnceLock<HashSet<String>> = OnceLock::new();
+
+// The actions restricted to some roles of a pool.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Permission {
+    // Delete the pool, protect the dynasty players and configure the integrations.
+    ManagePool,
+    // Start, pause and rollback the draft, or draft on behalf of the poolers.
+    ManageDraft,
+    // Update the pool settings, mark it as final and generate the next dynasty season.
+    UpdateSettings,
+    // Modify the roster of any pooler.
+    ManageRosters,
+    // Create, respond, veto or delete the trades on behalf of the poolers.
+    ManageTrades,
+    // Invite users to the pool.
+    ManageInvitations,
+    // Access a private pool.
+    ViewPrivatePool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    Admin,
+    Owner,
+    Assistant,
+    Participant,
+    Visitor,
+}
+
+impl Role {
+    pub fn is_granted(&self, permission: Permission) -> bool {
+        match self {
+            Role::Admin | Role::Owner => true,
+            Role::Assistant => {
+                !matches!(permission, Permission::ManagePool | Permission::ManageDraft)
+            }
+            Role::Participant => matches!(permission, Permission::ViewPrivatePool),
+            Role::Visitor => false,
+        }
+    }
+}
+
+// Register the site administrators, this should be called once at startup.
+pub fn set_site_admins(admins: &[String]) {
+    if SITE_ADMINS.set(admins.iter().cloned().collect()).is_err() {
+        warn!("The site administrators were already registered.");
+    }
+}
+
+pub fn is_site_admin(user_id: &str) -> bool {
+    SITE_ADMINS
+        .get()
+        .is_some_and(|admins| admins.contains(user_id))
+}
+
+// Guard of the support operations.
+pub fn authorize_site_admin(user_id: &str) -> Result<(), AppError> {
+    if !is_site_admin(user_id) {
+        return Err(AppError::CodedError {
+            code: ErrorCode::Forbidden,
+            msg: "This action requires site administrator rights.".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn get_role(user_id: &str, pool: &Pool) -> Role {
+    if is_site_admin(user_id) {
+        Role::Admin
+    } else if pool.owner == user_id {
+        Role::Owner
+    } else if pool.settings.assistants.iter().any(|id| id == user_id) {
+        Role::Assistant
+    } else if pool
+        .participants
+        .iter()
+        .any(|participant| participant.is_managed_by(user_id))
+    {
+        Role::Participant
+    } else {
+        Role::Visitor
+    }
+}
+
+pub fn has_permission(user_id: &str, pool: &Pool, permission: Permission) -> bool {
+    get_role(user_id, pool).is_granted(permission)
+}
+
+// Central privilege check used by every pool and draft action.
+pub fn authorize(user_id: &str, pool: &Pool, permission: Permission) -> Result<(), AppError> {
+    if !has_permission(user_id, pool, permission) {
+        let msg = match permission {
+            Permission::ManagePool | Permission::ManageDraft => {
+                "This action requires owner rights."
+            }
+            Permission::ViewPrivatePool => "This action requires to be a member of the pool.",
+            _ => "This action requires privileged rights.",
+        };
+
+        return Err(AppError::CodedError {
+            code: ErrorCode::Forbidden,
+            msg: msg.to_string(),
+        });
+    }
+
+    Ok(())
+}
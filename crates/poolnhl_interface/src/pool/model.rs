@@ -1,10 +1,22 @@
-use crate::{draft::model::RoomUser, errors::AppError};
-use chrono::{Duration, Local, NaiveDate, Timelike, Utc};
+use crate::achievements::model::Achievement;
+use crate::pool::authorization::{authorize, has_permission, Permission};
+use crate::time_provider::time_provider;
+use crate::users::model::UserId;
+use crate::{
+    draft::model::RoomUser,
+    errors::{AppError, ErrorCode, FieldError},
+};
+use chrono::{Duration, NaiveDate, Timelike, Utc};
+use rand::seq::SliceRandom;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::{
     collections::{HashMap, HashSet},
     fmt,
 };
+use tracing::warn;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
 // Date for season
 //
 
@@ -14,7 +26,56 @@ pub const POOL_CREATION_SEASON: u32 = 20242025;
 
 pub const TRADE_DEADLINE_DATE: &str = "2025-03-07";
 
-#[derive(Deserialize, Serialize, Clone)]
+// The version of the pool documents, the older documents are migrated when they are read.
+pub const POOL_SCHEMA_VERSION: u32 = 1;
+
+// Number of hours an accepted trade stays under review before being executed.
+pub const TRADE_REVIEW_WINDOW_HOURS: i64 = 48;
+
+// Number of days a trade can wait for an answer before it expires, when the pool does not set it.
+pub const DEFAULT_TRADE_EXPIRATION_DAYS: u16 = 7;
+
+// Number of trades a pooler can wait an answer for at the same time, when the pool does not set it.
+pub const DEFAULT_MAX_ACTIVE_TRADES_PER_POOLER: u8 = 1;
+
+// Maximum percentage of a player cap hit that can be retained in a trade.
+pub const MAX_RETAINED_SALARY_PERCENTAGE: u8 = 50;
+
+// Maximum length of a team name and of its abbreviation.
+pub const TEAM_NAME_MAX_LENGTH: usize = 30;
+pub const TEAM_ABBREVIATION_MAX_LENGTH: usize = 4;
+
+pub const TRADE_BLOCK_NOTE_MAX_LENGTH: usize = 280;
+
+// Number of hours a team claim code can be used.
+pub const TEAM_CLAIM_VALIDITY_HOURS: i64 = 168;
+
+// Bounds of the pool settings.
+pub const MIN_NUMBER_POOLERS: u8 = 2;
+pub const MAX_NUMBER_POOLERS: u8 = 30;
+pub const MAX_ROSTER_SIZE: u8 = 50;
+
+// Dates of the NHL season a pool is played on.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct SeasonDates {
+    pub season: u32, // 20242025
+    pub season_start: String,
+    pub season_end: String,
+    pub trade_deadline: String,
+}
+
+impl Default for SeasonDates {
+    fn default() -> Self {
+        Self {
+            season: POOL_CREATION_SEASON,
+            season_start: START_SEASON_DATE.to_string(),
+            season_end: END_SEASON_DATE.to_string(),
+            trade_deadline: TRADE_DEADLINE_DATE.to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, ToSchema)]
 pub struct ProjectedPoolShort {
     pub name: String, // the name of the pool.
     pub owner: String,
@@ -22,7 +83,7 @@ pub struct ProjectedPoolShort {
     pub season: u32,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct PlayerTypeSettings {
     // Other pool configuration
     pub forwards: u8,
@@ -30,11 +91,64 @@ pub struct PlayerTypeSettings {
     pub goalies: u8,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+// Once a pooler reached the limit of a position, the players of that position stop making points.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, ToSchema)]
+pub struct GamesPlayedLimits {
+    pub forwards: Option<u16>,
+    pub defense: Option<u16>,
+    pub goalies: Option<u16>,
+}
+
+impl GamesPlayedLimits {
+    pub fn get_remaining_games(&self, totals: &PoolerTotals) -> GamesPlayedLimits {
+        // Games left to play at each position after the games already played.
+        GamesPlayedLimits {
+            forwards: self
+                .forwards
+                .map(|limit| limit.saturating_sub(totals.forwards.games)),
+            defense: self
+                .defense
+                .map(|limit| limit.saturating_sub(totals.defense.games)),
+            goalies: self
+                .goalies
+                .map(|limit| limit.saturating_sub(totals.goalies.games)),
+        }
+    }
+
+    pub fn get_capped_positions(&self, totals: &PoolerTotals) -> Vec<Position> {
+        // The positions whose limit is reached.
+        [
+            (Position::F, self.forwards, &totals.forwards),
+            (Position::D, self.defense, &totals.defense),
+            (Position::G, self.goalies, &totals.goalies),
+        ]
+        .into_iter()
+        .filter(|(_, limit, position_totals)| {
+            limit.is_some_and(|limit| position_totals.games >= limit)
+        })
+        .map(|(position, _, _)| position)
+        .collect()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct AcquisitionLimits {
+    pub max_per_week: Option<u16>,
+    pub max_per_season: Option<u16>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct DynastySettings {
     // Other pool configuration
     pub next_season_number_players_protected: u8,
     pub tradable_picks: u8, // numbers of the next season picks participants are able to trade with each other.
+
+    // Number of future seasons whose picks can be traded, only the next season when not set.
+    pub tradable_seasons: Option<u8>,
+
+    // Date (i.e, 2025-09-25) after which the players of the poolers that did not protect them are protected automatically.
+    pub protection_deadline: Option<String>,
+
     pub past_season_pool_name: Vec<String>,
     pub next_season_pool_name: Option<String>,
 }
@@ -43,47 +157,187 @@ impl PartialEq<DynastySettings> for DynastySettings {
     fn eq(&self, other: &DynastySettings) -> bool {
         self.next_season_number_players_protected == other.next_season_number_players_protected
             && self.tradable_picks == other.tradable_picks
+            && self.tradable_seasons == other.tradable_seasons
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
 pub struct SkaterSettings {
-    pub points_per_goals: u8,
-    pub points_per_assists: u8,
-    pub points_per_hattricks: u8,
-    pub points_per_shootout_goals: u8,
+    pub points_per_goals: f64,
+    pub points_per_assists: f64,
+    pub points_per_hattricks: f64,
+    pub points_per_shootout_goals: f64,
+
+    // Optional scoring categories, not counted when not set.
+    pub points_per_shots: Option<f64>,
+    pub points_per_hits: Option<f64>,
+    pub points_per_blocked_shots: Option<f64>,
+    pub points_per_penalty_minutes: Option<f64>,
+    pub points_per_power_play_points: Option<f64>,
+    pub points_per_short_handed_points: Option<f64>,
+    pub points_per_faceoff_wins: Option<f64>,
+}
+
+impl SkaterSettings {
+    pub fn new(
+        points_per_goals: f64,
+        points_per_assists: f64,
+        points_per_hattricks: f64,
+        points_per_shootout_goals: f64,
+    ) -> Self {
+        Self {
+            points_per_goals,
+            points_per_assists,
+            points_per_hattricks,
+            points_per_shootout_goals,
+            points_per_shots: None,
+            points_per_hits: None,
+            points_per_blocked_shots: None,
+            points_per_penalty_minutes: None,
+            points_per_power_play_points: None,
+            points_per_short_handed_points: None,
+            points_per_faceoff_wins: None,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
 pub struct GoaliesSettings {
-    pub points_per_wins: u8,
-    pub points_per_shutouts: u8,
-    pub points_per_overtimes: u8,
-    pub points_per_goals: u8,
-    pub points_per_assists: u8,
+    pub points_per_wins: f64,
+    pub points_per_shutouts: f64,
+    pub points_per_overtimes: f64,
+    pub points_per_goals: f64,
+    pub points_per_assists: f64,
+
+    // Optional scoring categories, not counted when not set.
+    pub points_per_saves: Option<f64>,
+    // Points removed for each goal against.
+    pub points_per_goals_against: Option<f64>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub enum DraftType {
     Serpentine,
     Standard,
+    Auction,
+}
+
+// How the owner sets the draft order in the lobby, before the draft starts.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub enum DraftOrderType {
+    Custom { draft_order: Vec<String> },
+    // A new random order every time it is requested.
+    Random,
+    // The last team of the past season drafts first, the new teams draft before everyone.
+    ReverseStandings,
+}
+
+// The order of the poolers in each round of a serpentine or standard draft.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub enum DraftOrderStrategy {
+    // The order is reversed every round.
+    Snake,
+    // Every round follows the draft order.
+    Linear,
+    // The third round repeats the reversed order of the second round, then the order is reversed every round.
+    ThirdRoundReversal,
+    // The rounds follow the pattern over and over, true for a reversed round (i.g., [false, true, true, false]).
+    Pattern { reversed_rounds: Vec<bool> },
+}
+
+impl DraftOrderStrategy {
+    pub fn is_round_reversed(&self, round: usize) -> bool {
+        match self {
+            DraftOrderStrategy::Snake => round % 2 == 1,
+            DraftOrderStrategy::Linear => false,
+            DraftOrderStrategy::ThirdRoundReversal => {
+                round == 1 || (round > 1 && round.is_multiple_of(2))
+            }
+            DraftOrderStrategy::Pattern { reversed_rounds } => {
+                !reversed_rounds.is_empty() && reversed_rounds[round % reversed_rounds.len()]
+            }
+        }
+    }
+
+    pub fn get_drafter_index(&self, pick_number: usize, number_drafters: usize) -> usize {
+        // The index in the draft order of the pooler making the pick.
+        let round = pick_number / number_drafters;
+
+        if self.is_round_reversed(round) {
+            number_drafters - 1 - (pick_number % number_drafters)
+        } else {
+            pick_number % number_drafters
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct AuctionSettings {
+    // Budget that every pooler starts the auction draft with.
+    pub budget: u32,
+    // Minimum amount of a bid. Each empty roster spot needs to be fillable at that price.
+    pub min_bid: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct DraftTimerSettings {
+    // Number of seconds a pooler has to make its pick.
+    pub pick_time_limit: u32,
+    // When the pick timer expires, draft the best available player. Otherwise, the pick is skipped.
+    pub auto_pick: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct HeadToHeadSettings {
+    // Standings points given for a weekly matchup win or tie.
+    pub points_per_win: u8,
+    pub points_per_tie: u8,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub enum TradeReview {
+    // The owner and the pool assistants can veto an accepted trade.
+    Owner,
+    // The poolers not involved in the trade vote to veto an accepted trade.
+    LeagueVote { votes_to_veto: u8 },
+    None,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub enum PoolVisibility {
+    Public,
+    // Only the invited users can join the draft room of the pool.
+    Private,
+    // Not listed in the pools discovery, anyone knowing the pool name can join it.
+    Unlisted,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct PoolSettings {
     pub assistants: Vec<String>, // Participants that are allowed to make some pool modifications.
 
     pub number_poolers: u8,
     pub draft_type: DraftType,
 
+    // The order of the rounds of the draft, a snake draft when not set.
+    pub draft_order_strategy: Option<DraftOrderStrategy>,
+
     // Roster configuration.
     pub number_forwards: u8,
     pub number_defenders: u8,
     pub number_goalies: u8,
     pub number_reservists: u8,
 
+    // Starting spots that any skater can fill (none when not set).
+    pub number_utility: Option<u8>,
+    // Injured reserve spots, in addition to the reservists (none when not set).
+    pub number_injured_reserve: Option<u8>,
+
     pub salary_cap: Option<f64>,
 
+    // Number of players on which a team can retain salary in trades (no retention when not set).
+    pub max_retained_salaries: Option<u8>,
+
     // Date where where roster modification are allowed to everyone.
     pub roster_modification_date: Vec<String>,
 
@@ -93,6 +347,51 @@ pub struct PoolSettings {
 
     pub ignore_x_worst_players: Option<PlayerTypeSettings>,
     pub dynasty_settings: Option<DynastySettings>,
+
+    // Only used when the draft type is Auction.
+    pub auction_settings: Option<AuctionSettings>,
+
+    // When set, each pick of the draft is timed.
+    pub draft_timer_settings: Option<DraftTimerSettings>,
+
+    // When set, poolers face each other every week instead of being ranked by total points.
+    pub head_to_head_settings: Option<HeadToHeadSettings>,
+
+    // When set, accepted trades are executed only after a review window without a veto.
+    pub trade_review: Option<TradeReview>,
+
+    // Number of days a trade can wait for an answer (DEFAULT_TRADE_EXPIRATION_DAYS when not set).
+    pub trade_expiration_days: Option<u16>,
+
+    // Number of NEW trades a pooler can have proposed at the same time (DEFAULT_MAX_ACTIVE_TRADES_PER_POOLER when not set).
+    pub max_active_trades_per_pooler: Option<u8>,
+
+    // Pools without visibility are public.
+    pub visibility: Option<PoolVisibility>,
+
+    // Planned date of the draft (i.e, 2024-10-01).
+    pub draft_date: Option<String>,
+
+    // Discord or Slack webhook where the pool activity is posted.
+    pub webhook: Option<PoolWebhook>,
+
+    // Games played allowed per position during the season (no limit when not set).
+    pub games_played_limits: Option<GamesPlayedLimits>,
+
+    // Players a pooler can acquire (i.e., free agents added) per week and per season (no limit when not set).
+    pub acquisition_limits: Option<AcquisitionLimits>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
+pub enum WebhookKind {
+    Discord,
+    Slack,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
+pub struct PoolWebhook {
+    pub url: String,
+    pub kind: WebhookKind,
 }
 
 impl PoolSettings {
@@ -100,45 +399,424 @@ impl PoolSettings {
         Self {
             number_poolers: 6,
             draft_type: DraftType::Serpentine,
+            draft_order_strategy: None,
             assistants: Vec::new(),
             number_forwards: 9,
             number_defenders: 4,
             number_goalies: 2,
             number_reservists: 2,
+            number_utility: None,
+            number_injured_reserve: None,
             salary_cap: None,
+            max_retained_salaries: None,
             roster_modification_date: Vec::new(),
-            forwards_settings: SkaterSettings {
-                points_per_goals: 2,
-                points_per_assists: 1,
-                points_per_hattricks: 3,
-                points_per_shootout_goals: 1,
-            },
-            defense_settings: SkaterSettings {
-                points_per_goals: 3,
-                points_per_assists: 2,
-                points_per_hattricks: 2,
-                points_per_shootout_goals: 1,
-            },
+            forwards_settings: SkaterSettings::new(2.0, 1.0, 3.0, 1.0),
+            defense_settings: SkaterSettings::new(3.0, 2.0, 2.0, 1.0),
             goalies_settings: GoaliesSettings {
-                points_per_wins: 2,
-                points_per_shutouts: 3,
-                points_per_goals: 3,
-                points_per_assists: 2,
-                points_per_overtimes: 1,
+                points_per_wins: 2.0,
+                points_per_shutouts: 3.0,
+                points_per_goals: 3.0,
+                points_per_assists: 2.0,
+                points_per_overtimes: 1.0,
+                points_per_saves: None,
+                points_per_goals_against: None,
             },
             ignore_x_worst_players: None,
             dynasty_settings: None,
+            auction_settings: None,
+            draft_timer_settings: None,
+            head_to_head_settings: None,
+            trade_review: None,
+            trade_expiration_days: None,
+            max_active_trades_per_pooler: None,
+            visibility: None,
+            draft_date: None,
+            webhook: None,
+            games_played_limits: None,
+            acquisition_limits: None,
+        }
+    }
+
+    pub fn get_trade_expiration_days(&self) -> u16 {
+        self.trade_expiration_days
+            .unwrap_or(DEFAULT_TRADE_EXPIRATION_DAYS)
+    }
+
+    pub fn get_max_active_trades_per_pooler(&self) -> u8 {
+        self.max_active_trades_per_pooler
+            .unwrap_or(DEFAULT_MAX_ACTIVE_TRADES_PER_POOLER)
+    }
+
+    pub fn get_draft_order_strategy(&self) -> DraftOrderStrategy {
+        // The drafts snake by default, keeping the same order every round is opt-in.
+        self.draft_order_strategy
+            .clone()
+            .unwrap_or(DraftOrderStrategy::Snake)
+    }
+
+    pub fn get_number_utility(&self) -> u8 {
+        self.number_utility.unwrap_or(0)
+    }
+
+    pub fn get_number_injured_reserve(&self) -> u8 {
+        self.number_injured_reserve.unwrap_or(0)
+    }
+
+    pub fn get_roster_size(&self) -> u32 {
+        // The injured reserve is not part of the roster size.
+        self.number_forwards as u32
+            + self.number_defenders as u32
+            + self.number_goalies as u32
+            + self.get_number_utility() as u32
+            + self.number_reservists as u32
+    }
+
+    // Validate the settings of a pool, every invalid field is reported at once.
+    pub fn validate(&self) -> Result<(), AppError> {
+        let mut errors = Vec::new();
+
+        if !(MIN_NUMBER_POOLERS..=MAX_NUMBER_POOLERS).contains(&self.number_poolers) {
+            errors.push(FieldError::new(
+                "number_poolers",
+                &format!(
+                    "The number of poolers needs to be between {} and {}.",
+                    MIN_NUMBER_POOLERS, MAX_NUMBER_POOLERS
+                ),
+            ));
+        }
+
+        for (field, number_players) in [
+            ("number_forwards", self.number_forwards),
+            ("number_defenders", self.number_defenders),
+            ("number_goalies", self.number_goalies),
+        ] {
+            if number_players == 0 {
+                errors.push(FieldError::new(
+                    field,
+                    "The roster needs at least one player at each position.",
+                ));
+            }
+        }
+
+        let roster_size = self.get_roster_size();
+        if roster_size > MAX_ROSTER_SIZE as u32 {
+            errors.push(FieldError::new(
+                "number_reservists",
+                &format!(
+                    "The roster cannot hold more than {} players.",
+                    MAX_ROSTER_SIZE
+                ),
+            ));
+        }
+
+        if let Some(salary_cap) = self.salary_cap {
+            if !salary_cap.is_finite() || salary_cap <= 0.0 {
+                errors.push(FieldError::new(
+                    "salary_cap",
+                    "The salary cap needs to be a positive amount.",
+                ));
+            }
+        }
+
+        if self
+            .max_retained_salaries
+            .is_some_and(|max_retained_salaries| max_retained_salaries as u32 > roster_size)
+        {
+            errors.push(FieldError::new(
+                "max_retained_salaries",
+                "The number of retained salaries cannot exceed the roster size.",
+            ));
+        }
+
+        if self.trade_expiration_days == Some(0) {
+            errors.push(FieldError::new(
+                "trade_expiration_days",
+                "A trade needs to wait at least 1 day for an answer.",
+            ));
+        }
+
+        if self.max_active_trades_per_pooler == Some(0) {
+            errors.push(FieldError::new(
+                "max_active_trades_per_pooler",
+                "A pooler needs to be able to propose at least 1 trade at a time.",
+            ));
+        }
+
+        let mut roster_modification_dates = HashSet::new();
+        for date in &self.roster_modification_date {
+            if NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+                errors.push(FieldError::new(
+                    "roster_modification_date",
+                    &format!("'{}' is not a valid date (i.e, 2024-12-01).", date),
+                ));
+            } else if !roster_modification_dates.insert(date) {
+                errors.push(FieldError::new(
+                    "roster_modification_date",
+                    &format!("The date '{}' is listed twice.", date),
+                ));
+            }
+        }
+
+        for (field, points) in self.get_scoring_points() {
+            if !points.is_finite() {
+                errors.push(FieldError::new(
+                    field,
+                    "The points of a scoring category need to be a number.",
+                ));
+            }
+        }
+
+        if let Some(ignore_x_worst_players) = &self.ignore_x_worst_players {
+            if ignore_x_worst_players.forwards > self.number_forwards
+                || ignore_x_worst_players.defense > self.number_defenders
+                || ignore_x_worst_players.goalies > self.number_goalies
+            {
+                errors.push(FieldError::new(
+                    "ignore_x_worst_players",
+                    "More players cannot be ignored than the number of players at a position.",
+                ));
+            }
+        }
+
+        if let Some(dynasty_settings) = &self.dynasty_settings {
+            if dynasty_settings.next_season_number_players_protected as u32 > roster_size {
+                errors.push(FieldError::new(
+                    "dynasty_settings.next_season_number_players_protected",
+                    "More players cannot be protected than the roster size.",
+                ));
+            }
+
+            if dynasty_settings
+                .protection_deadline
+                .as_ref()
+                .is_some_and(|deadline| NaiveDate::parse_from_str(deadline, "%Y-%m-%d").is_err())
+            {
+                errors.push(FieldError::new(
+                    "dynasty_settings.protection_deadline",
+                    "The protection deadline is not a valid date (i.e, 2025-09-25).",
+                ));
+            }
+
+            if dynasty_settings.tradable_seasons == Some(0) {
+                errors.push(FieldError::new(
+                    "dynasty_settings.tradable_seasons",
+                    "The picks of at least 1 season need to be tradable.",
+                ));
+            }
+        }
+
+        if let Some(DraftOrderStrategy::Pattern { reversed_rounds }) = &self.draft_order_strategy {
+            if reversed_rounds.is_empty() {
+                errors.push(FieldError::new(
+                    "draft_order_strategy.reversed_rounds",
+                    "The pattern needs to contain at least 1 round.",
+                ));
+            }
+        }
+
+        if let Some(games_played_limits) = &self.games_played_limits {
+            for (field, limit) in [
+                ("games_played_limits.forwards", games_played_limits.forwards),
+                ("games_played_limits.defense", games_played_limits.defense),
+                ("games_played_limits.goalies", games_played_limits.goalies),
+            ] {
+                if limit == Some(0) {
+                    errors.push(FieldError::new(
+                        field,
+                        "The games played limit needs to be at least 1.",
+                    ));
+                }
+            }
+        }
+
+        match (&self.draft_type, &self.auction_settings) {
+            (DraftType::Auction, None) => errors.push(FieldError::new(
+                "auction_settings",
+                "The auction settings are required for an auction draft.",
+            )),
+            (_, Some(auction_settings)) => {
+                if auction_settings.min_bid == 0 {
+                    errors.push(FieldError::new(
+                        "auction_settings.min_bid",
+                        "The minimum bid needs to be at least 1.",
+                    ));
+                }
+                // Every roster spot needs to be fillable at the minimum bid.
+                if (auction_settings.budget as u64)
+                    < auction_settings.min_bid as u64 * roster_size as u64
+                {
+                    errors.push(FieldError::new(
+                        "auction_settings.budget",
+                        "The budget cannot fill every roster spot at the minimum bid.",
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        if self
+            .draft_timer_settings
+            .as_ref()
+            .is_some_and(|draft_timer_settings| draft_timer_settings.pick_time_limit == 0)
+        {
+            errors.push(FieldError::new(
+                "draft_timer_settings.pick_time_limit",
+                "The pick time limit needs to be at least 1 second.",
+            ));
+        }
+
+        if self
+            .head_to_head_settings
+            .as_ref()
+            .is_some_and(|head_to_head_settings| {
+                head_to_head_settings.points_per_tie > head_to_head_settings.points_per_win
+            })
+        {
+            errors.push(FieldError::new(
+                "head_to_head_settings.points_per_tie",
+                "A tie cannot give more points than a win.",
+            ));
+        }
+
+        if let Some(TradeReview::LeagueVote { votes_to_veto }) = &self.trade_review {
+            // The poolers involved in a trade cannot vote, the votes needed by a trade between more
+            // than 2 poolers are clamped to its voters.
+            if *votes_to_veto == 0 || *votes_to_veto > self.number_poolers.saturating_sub(2) {
+                errors.push(FieldError::new(
+                    "trade_review.votes_to_veto",
+                    "The votes to veto need to be between 1 and the number of poolers not involved in a trade.",
+                ));
+            }
+        }
+
+        if self
+            .draft_date
+            .as_ref()
+            .is_some_and(|draft_date| NaiveDate::parse_from_str(draft_date, "%Y-%m-%d").is_err())
+        {
+            errors.push(FieldError::new(
+                "draft_date",
+                "The draft date is not a valid date (i.e, 2024-10-01).",
+            ));
+        }
+
+        if self
+            .webhook
+            .as_ref()
+            .is_some_and(|webhook| !webhook.url.starts_with("https://"))
+        {
+            errors.push(FieldError::new(
+                "webhook.url",
+                "The webhook url needs to use https.",
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::ValidationError { errors })
+        }
+    }
+
+    // The points given by each scoring category, with the name of its field.
+    fn get_scoring_points(&self) -> Vec<(&'static str, f64)> {
+        let mut scoring_points = Vec::new();
+
+        for (field, skater_settings) in [
+            ("forwards_settings", &self.forwards_settings),
+            ("defense_settings", &self.defense_settings),
+        ] {
+            scoring_points.extend(
+                [
+                    Some(skater_settings.points_per_goals),
+                    Some(skater_settings.points_per_assists),
+                    Some(skater_settings.points_per_hattricks),
+                    Some(skater_settings.points_per_shootout_goals),
+                    skater_settings.points_per_shots,
+                    skater_settings.points_per_hits,
+                    skater_settings.points_per_blocked_shots,
+                    skater_settings.points_per_penalty_minutes,
+                    skater_settings.points_per_power_play_points,
+                    skater_settings.points_per_short_handed_points,
+                    skater_settings.points_per_faceoff_wins,
+                ]
+                .into_iter()
+                .flatten()
+                .map(|points| (field, points)),
+            );
+        }
+
+        scoring_points.extend(
+            [
+                Some(self.goalies_settings.points_per_wins),
+                Some(self.goalies_settings.points_per_shutouts),
+                Some(self.goalies_settings.points_per_overtimes),
+                Some(self.goalies_settings.points_per_goals),
+                Some(self.goalies_settings.points_per_assists),
+                self.goalies_settings.points_per_saves,
+                self.goalies_settings.points_per_goals_against,
+            ]
+            .into_iter()
+            .flatten()
+            .map(|points| ("goalies_settings", points)),
+        );
+
+        scoring_points
+    }
+
+    pub fn get_reusable_settings(&self) -> Self {
+        // The settings to copy in another pool, without the ones bound to the participants or the season.
+        Self {
+            assistants: Vec::new(),
+            roster_modification_date: Vec::new(),
+            draft_date: None,
+            ..self.clone()
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct PoolUser {
     pub id: String,
     pub name: String,
 
     // tells if the user is owned by an app users or manage by the pool owner
     pub is_owned: bool,
+
+    // When enabled, the reservists are promoted into the empty starting spots every night.
+    pub auto_lineup: Option<bool>,
+
+    // The other users sharing the management of this team (roster and trade actions).
+    pub managers: Option<Vec<String>>,
+
+    // Team branding displayed instead of the user name.
+    pub team_name: Option<String>,
+    pub team_abbreviation: Option<String>,
+    pub avatar_url: Option<String>,
+
+    // The team was abandoned by its user, its trades are paused until it is adopted.
+    pub is_orphaned: Option<bool>,
+    // The owner of the pool manages the orphaned team until it is adopted.
+    pub is_owner_managed: Option<bool>,
+}
+
+impl PoolUser {
+    pub fn is_managed_by(&self, user_id: &str) -> bool {
+        // The user and the managers of an orphaned team cannot act for it anymore.
+        if self.is_orphaned() {
+            return false;
+        }
+
+        self.id == user_id
+            || self
+                .managers
+                .as_ref()
+                .is_some_and(|managers| managers.iter().any(|manager| manager == user_id))
+    }
+
+    pub fn is_orphaned(&self) -> bool {
+        self.is_orphaned.unwrap_or(false)
+    }
 }
 
 impl From<RoomUser> for PoolUser {
@@ -147,11 +825,18 @@ impl From<RoomUser> for PoolUser {
             id: room_user.id,
             name: room_user.name,
             is_owned: room_user.email.is_some(),
+            auto_lineup: None,
+            managers: None,
+            team_name: None,
+            team_abbreviation: None,
+            avatar_url: None,
+            is_orphaned: None,
+            is_owner_managed: None,
         }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct Pool {
     pub name: String, // the name of the pool.
     pub owner: String,
@@ -177,10 +862,24 @@ pub struct Pool {
     pub season_start: String,
     pub season_end: String,
     pub season: u32, // 20232024
+
+    // Date after which trades cannot be created anymore.
+    pub trade_deadline: Option<String>,
+
+    // When true, no pick can be made until the draft is resumed.
+    pub is_draft_paused: Option<bool>,
+
+    // The pools created before the migrations were introduced have no version.
+    pub schema_version: Option<u32>,
 }
 
 impl Pool {
-    pub fn new(pool_name: &str, owner: &str, pool_settings: &PoolSettings) -> Self {
+    pub fn new(
+        pool_name: &str,
+        owner: &str,
+        pool_settings: &PoolSettings,
+        season_dates: &SeasonDates,
+    ) -> Self {
         Self {
             name: pool_name.to_string(),
             owner: owner.to_string(),
@@ -192,19 +891,29 @@ impl Pool {
             trades: None,
             context: None,
             date_updated: 0,
-            season_start: START_SEASON_DATE.to_string(),
-            season_end: END_SEASON_DATE.to_string(),
-            season: POOL_CREATION_SEASON,
+            season_start: season_dates.season_start.clone(),
+            season_end: season_dates.season_end.clone(),
+            season: season_dates.season,
+            trade_deadline: Some(season_dates.trade_deadline.clone()),
+            is_draft_paused: None,
+            schema_version: Some(POOL_SCHEMA_VERSION),
         }
     }
 
+    pub fn get_trade_deadline(&self) -> &str {
+        // The pools created before the trade deadline was stored use the default one.
+        self.trade_deadline
+            .as_deref()
+            .unwrap_or(TRADE_DEADLINE_DATE)
+    }
+
     pub fn create_trade(&mut self, trade: &mut Trade, user_id: &str) -> Result<(), AppError> {
         self.validate_pool_status(&PoolState::InProgress)?;
         // Create a trade in the pool if it is valid to do so..
-        let trade_deadline_date = NaiveDate::parse_from_str(TRADE_DEADLINE_DATE, "%Y-%m-%d")
+        let trade_deadline_date = NaiveDate::parse_from_str(self.get_trade_deadline(), "%Y-%m-%d")
             .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
 
-        let today = Local::now().date_naive();
+        let today = time_provider().today();
 
         if today > trade_deadline_date {
             return Err(AppError::CustomError {
@@ -213,20 +922,28 @@ impl Pool {
         }
 
         // If the user is not the one who proposed the trade it needs to have privileges.
-        if user_id != trade.proposed_by {
-            self.has_privileges(user_id)?;
+        if !self.is_manager_of(user_id, &trade.proposed_by) {
+            authorize(user_id, self, Permission::ManageTrades)?;
         }
 
         let context = self.context.as_ref().ok_or_else(|| AppError::CustomError {
             msg: "pool context does not exist.".to_string(),
         })?;
 
-        context.validate_trade(trade)?;
+        context.validate_trade(trade, &self.settings)?;
+
+        if trade.is_paused(&self.get_orphaned_teams()) {
+            return Err(AppError::CustomError {
+                msg: "The trades of an orphaned team are paused until it is adopted.".to_string(),
+            });
+        }
 
-        // does the proposedBy and askTo field are valid
+        // does every pooler involved in the trade are valid
 
-        if !context.pooler_roster.contains_key(&trade.proposed_by)
-            || !context.pooler_roster.contains_key(&trade.ask_to)
+        if trade
+            .get_participants()
+            .iter()
+            .any(|participant| !context.pooler_roster.contains_key(participant))
         {
             return Err(AppError::CustomError {
                 msg: "The users in the trade are not in the pool.".to_string(),
@@ -237,20 +954,29 @@ impl Pool {
         }
 
         if let Some(trades) = &mut self.trades {
-            // Make sure that user can only have 1 active trade at a time.
-            //return an error if already one trade active in this pool. (Active trade = NEW )
-            for trade in trades.iter() {
-                if (matches!(trade.status, TradeStatus::NEW))
-                    && (trade.proposed_by == trade.proposed_by)
-                {
-                    return Err(AppError::CustomError {
-                        msg: "User can only have one active trade at a time.".to_string(),
-                    });
-                }
+            // Make sure that the pooler proposing the trade does not exceed its number of active trades.
+            // (Active trade = NEW)
+            let max_active_trades = self.settings.get_max_active_trades_per_pooler();
+            let active_trades = trades
+                .iter()
+                .filter(|active_trade| {
+                    matches!(active_trade.status, TradeStatus::NEW)
+                        && active_trade.proposed_by == trade.proposed_by
+                })
+                .count();
+
+            if active_trades >= usize::from(max_active_trades) {
+                return Err(AppError::CustomError {
+                    msg: format!(
+                        "User can only have {} active trade(s) at a time.",
+                        max_active_trades
+                    ),
+                });
             }
 
             trade.date_created = Utc::now().timestamp_millis();
             trade.status = TradeStatus::NEW;
+            trade.accepted_by = Some(Vec::new());
             trade.id = trades.len() as u32;
             trades.push(trade.clone());
         }
@@ -262,8 +988,8 @@ impl Pool {
         self.validate_pool_status(&PoolState::InProgress)?;
 
         // Owner and pool assistant can delete any new trade.
-        let priviledge_right =
-            self.has_owner_rights(user_id) || self.has_assistants_rights(user_id);
+        let priviledge_right = has_permission(user_id, self, Permission::ManageTrades);
+        let managed_participants = self.get_managed_participants(user_id);
 
         let trades = self.trades.as_mut().ok_or_else(|| AppError::CustomError {
             msg: "There is no trade to the pool yet.".to_string(),
@@ -272,7 +998,8 @@ impl Pool {
         let trade_index = trades
             .iter()
             .position(|trade| trade.id == trade_id)
-            .ok_or_else(|| AppError::CustomError {
+            .ok_or_else(|| AppError::CodedError {
+                code: ErrorCode::TradeNotFound,
                 msg: "The trade does not exist.".to_string(),
             })?;
 
@@ -287,7 +1014,7 @@ impl Pool {
         // validate that only the one that create the trade or the
         // owner/assistants can delete it.
 
-        if !priviledge_right && trades[trade_index].proposed_by != *user_id {
+        if !priviledge_right && !managed_participants.contains(&trades[trade_index].proposed_by) {
             return Err(AppError::CustomError {
                 msg: "Only the one that created the trade can cancel it.".to_string(),
             });
@@ -306,8 +1033,9 @@ impl Pool {
         self.validate_pool_status(&PoolState::InProgress)?;
 
         // Owner and pool assistant can respond any new trade.
-        let priviledge_right =
-            self.has_owner_rights(user_id) || self.has_assistants_rights(user_id);
+        let priviledge_right = has_permission(user_id, self, Permission::ManageTrades);
+        let managed_participants = self.get_managed_participants(user_id);
+        let orphaned_teams = self.get_orphaned_teams();
 
         let trades = self.trades.as_mut().ok_or_else(|| AppError::CustomError {
             msg: "There is no trade to the pool yet.".to_string(),
@@ -316,7 +1044,8 @@ impl Pool {
         let trade_index = trades
             .iter()
             .position(|trade| trade.id == trade_id)
-            .ok_or_else(|| AppError::CustomError {
+            .ok_or_else(|| AppError::CodedError {
+                code: ErrorCode::TradeNotFound,
                 msg: "The trade does not exist.".to_string(),
             })?;
 
@@ -328,11 +1057,26 @@ impl Pool {
             });
         }
 
-        // validate that only the one that was ask for the trade or the owner can accept it.
+        if trades[trade_index].is_paused(&orphaned_teams) {
+            return Err(AppError::CustomError {
+                msg: "The trades of an orphaned team are paused until it is adopted.".to_string(),
+            });
+        }
+
+        // validate that only the poolers that were ask for the trade or the owner can accept it.
 
-        if !priviledge_right && trades[trade_index].ask_to != *user_id {
+        let trade_participants = trades[trade_index].get_participants();
+        let responding_participant = trade_participants
+            .iter()
+            .find(|participant| {
+                **participant != trades[trade_index].proposed_by
+                    && managed_participants.contains(participant)
+            })
+            .cloned();
+
+        if !priviledge_right && responding_participant.is_none() {
             return Err(AppError::CustomError {
-                msg: "Only the one that was ask for the trade or the owner can accept it."
+                msg: "Only the ones that were ask for the trade or the owner can accept it."
                     .to_string(),
             });
         }
@@ -347,12 +1091,38 @@ impl Pool {
             });
         }
         if is_accepted {
+            // Every pooler involved in the trade needs to accept it before the items are traded.
+            // The owner and the pool assistants can accept it for everyone.
+            if !priviledge_right {
+                let responding_participant =
+                    responding_participant.unwrap_or_else(|| user_id.to_string());
+                let accepted_by = trades[trade_index].accepted_by.get_or_insert_with(Vec::new);
+                if !accepted_by.contains(&responding_participant) {
+                    accepted_by.push(responding_participant);
+                }
+
+                if !trades[trade_index].is_accepted_by_everyone() {
+                    return Ok(());
+                }
+            }
+
+            // The accepted trade enters its review window, the items are traded once it passes.
+            if matches!(
+                self.settings.trade_review,
+                Some(TradeReview::Owner) | Some(TradeReview::LeagueVote { .. })
+            ) {
+                trades[trade_index].status = TradeStatus::REVIEWING;
+                trades[trade_index].date_accepted = Utc::now().timestamp_millis();
+                trades[trade_index].veto_votes = Some(Vec::new());
+                return Ok(());
+            }
+
             match &mut self.context {
                 None => Err(AppError::CustomError {
                     msg: "The pool has no context yet.".to_string(),
                 }),
                 Some(pool_context) => {
-                    pool_context.trade_roster_items(&trades[trade_index])?;
+                    pool_context.trade_roster_items(&trades[trade_index], &self.settings)?;
                     trades[trade_index].status = TradeStatus::ACCEPTED;
                     trades[trade_index].date_accepted = Utc::now().timestamp_millis();
                     Ok(())
@@ -364,23 +1134,245 @@ impl Pool {
         }
     }
 
-    pub fn fill_spot(
-        &mut self,
-        user_id: &str,
-        filled_spot_user_id: &str,
-        player_id: u32,
-    ) -> Result<(), AppError> {
+    pub fn veto_trade(&mut self, user_id: &str, trade_id: u32) -> Result<(), AppError> {
         self.validate_pool_status(&PoolState::InProgress)?;
-        self.validate_participant(filled_spot_user_id)?;
-        if user_id != filled_spot_user_id {
-            self.has_privileges(user_id)?;
-        }
 
-        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
-            msg: "Pool context does not exist.".to_string(),
+        let priviledge_right = has_permission(user_id, self, Permission::ManageTrades);
+        let managed_participants = self.get_managed_participants(user_id);
+        let voting_participant = managed_participants
+            .iter()
+            .find(|participant_id| {
+                self.participants
+                    .iter()
+                    .any(|participant| participant.id == **participant_id)
+            })
+            .cloned();
+        let number_participants = self.participants.len();
+
+        let trades = self.trades.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "There is no trade to the pool yet.".to_string(),
         })?;
 
-        // Is the player in the pool?
+        let trade = trades
+            .iter_mut()
+            .find(|trade| trade.id == trade_id)
+            .ok_or_else(|| AppError::CodedError {
+                code: ErrorCode::TradeNotFound,
+                msg: "The trade does not exist.".to_string(),
+            })?;
+
+        // validate that the trade is still in its review window.
+        let now = Utc::now().timestamp_millis();
+
+        if !matches!(trade.status, TradeStatus::REVIEWING)
+            || trade.date_accepted + TRADE_REVIEW_WINDOW_HOURS * 3600000 <= now
+        {
+            return Err(AppError::CustomError {
+                msg: "The trade is not under review.".to_string(),
+            });
+        }
+
+        match &self.settings.trade_review {
+            Some(TradeReview::Owner) => {
+                if !priviledge_right {
+                    return Err(AppError::CustomError {
+                        msg: "Only the owner or the pool assistants can veto a trade.".to_string(),
+                    });
+                }
+                trade.status = TradeStatus::VETOED;
+            }
+            Some(TradeReview::LeagueVote { votes_to_veto }) => {
+                // Only the poolers not involved in the trade can vote to veto it.
+                let trade_participants = trade.get_participants();
+                let voting_participant =
+                    match voting_participant {
+                        Some(voting_participant)
+                            if !trade_participants
+                                .iter()
+                                .any(|p| managed_participants.contains(p)) =>
+                        {
+                            voting_participant
+                        }
+                        _ => return Err(AppError::CustomError {
+                            msg: "Only the poolers not involved in the trade can vote to veto it."
+                                .to_string(),
+                        }),
+                    };
+
+                let veto_votes = trade.veto_votes.get_or_insert_with(Vec::new);
+                if veto_votes.contains(&voting_participant) {
+                    return Err(AppError::CustomError {
+                        msg: "You already voted to veto this trade.".to_string(),
+                    });
+                }
+                veto_votes.push(voting_participant);
+
+                // A trade between more poolers leaves fewer voters, the votes needed are clamped to them.
+                let eligible_voters = number_participants.saturating_sub(trade_participants.len());
+                if veto_votes.len() >= (*votes_to_veto as usize).min(eligible_voters.max(1)) {
+                    trade.status = TradeStatus::VETOED;
+                }
+            }
+            _ => {
+                return Err(AppError::CustomError {
+                    msg: "The trades of this pool cannot be vetoed.".to_string(),
+                })
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn complete_trade_reviews(&mut self, now: i64) -> bool {
+        // Trade the items of every trade whose review window passed without enough veto votes.
+        // Returns true if a trade was completed.
+        let orphaned_teams = self.get_orphaned_teams();
+        let (Some(trades), Some(pool_context)) = (self.trades.as_mut(), self.context.as_mut())
+        else {
+            return false;
+        };
+
+        let mut is_updated = false;
+
+        for trade in trades.iter_mut() {
+            if !matches!(trade.status, TradeStatus::REVIEWING)
+                || trade.date_accepted + TRADE_REVIEW_WINDOW_HOURS * 3600000 > now
+                || trade.is_paused(&orphaned_teams)
+            {
+                continue;
+            }
+
+            // The items could have changed hands during the review window.
+            match pool_context.trade_roster_items(trade, &self.settings) {
+                Ok(()) => {
+                    trade.status = TradeStatus::ACCEPTED;
+                    trade.date_accepted = now;
+                }
+                Err(_) => trade.status = TradeStatus::CANCELLED,
+            }
+            is_updated = true;
+        }
+
+        is_updated
+    }
+
+    pub fn expire_trades(&mut self, now: i64) -> Vec<Trade> {
+        // Expire the trades that were not answered in time, the expired trades are returned.
+        let expiration_millis = i64::from(self.settings.get_trade_expiration_days()) * 86400000;
+        let orphaned_teams = self.get_orphaned_teams();
+        let mut expired_trades = Vec::new();
+
+        for trade in self.trades.iter_mut().flatten() {
+            if matches!(trade.status, TradeStatus::NEW)
+                && trade.date_created + expiration_millis <= now
+                && !trade.is_paused(&orphaned_teams)
+            {
+                trade.status = TradeStatus::EXPIRED;
+                expired_trades.push(trade.clone());
+            }
+        }
+
+        expired_trades
+    }
+
+    pub fn counter_trade(
+        &mut self,
+        user_id: &str,
+        trade_id: u32,
+        counter_trade: &mut Trade,
+    ) -> Result<(), AppError> {
+        // Answer a trade with a counter offer instead of refusing it.
+        // The counter offer is a new trade linked to the original one.
+        self.validate_pool_status(&PoolState::InProgress)?;
+
+        let priviledge_right = has_permission(user_id, self, Permission::ManageTrades);
+        let managed_participants = self.get_managed_participants(user_id);
+        let orphaned_teams = self.get_orphaned_teams();
+
+        let trades = self.trades.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "There is no trade to the pool yet.".to_string(),
+        })?;
+
+        let trade_index = trades
+            .iter()
+            .position(|trade| trade.id == trade_id)
+            .ok_or_else(|| AppError::CodedError {
+                code: ErrorCode::TradeNotFound,
+                msg: "The trade does not exist.".to_string(),
+            })?;
+
+        let original_trade = &trades[trade_index];
+
+        if !matches!(original_trade.status, TradeStatus::NEW) {
+            return Err(AppError::CustomError {
+                msg: "The trade is not in a valid state to be countered.".to_string(),
+            });
+        }
+
+        if original_trade.is_paused(&orphaned_teams) {
+            return Err(AppError::CustomError {
+                msg: "The trades of an orphaned team are paused until it is adopted.".to_string(),
+            });
+        }
+
+        // Only a pooler that was ask for the trade can make a counter offer.
+        if original_trade.proposed_by == counter_trade.proposed_by
+            || !original_trade
+                .get_participants()
+                .contains(&counter_trade.proposed_by)
+        {
+            return Err(AppError::CustomError {
+                msg: "Only the ones that were ask for the trade can counter it.".to_string(),
+            });
+        }
+
+        if !priviledge_right && !managed_participants.contains(&counter_trade.proposed_by) {
+            return Err(AppError::CustomError {
+                msg: "Only the ones that were ask for the trade or the owner can counter it."
+                    .to_string(),
+            });
+        }
+
+        // The counter offer needs to be made to the pooler that proposed the original trade.
+        if !counter_trade
+            .get_participants()
+            .contains(&original_trade.proposed_by)
+        {
+            return Err(AppError::CustomError {
+                msg: "The counter offer needs to include the pooler that proposed the trade."
+                    .to_string(),
+            });
+        }
+
+        // Keep the negotiation thread, oldest trade first.
+        let mut negotiation = original_trade.negotiation.clone().unwrap_or_default();
+        negotiation.push(original_trade.id);
+
+        counter_trade.counter_of = Some(original_trade.id);
+        counter_trade.negotiation = Some(negotiation);
+
+        trades[trade_index].status = TradeStatus::COUNTERED;
+
+        self.create_trade(counter_trade, user_id)
+    }
+
+    pub fn fill_spot(
+        &mut self,
+        user_id: &str,
+        filled_spot_user_id: &str,
+        player_id: u32,
+    ) -> Result<(), AppError> {
+        self.validate_pool_status(&PoolState::InProgress)?;
+        self.validate_participant(filled_spot_user_id)?;
+        if !self.is_manager_of(user_id, filled_spot_user_id) {
+            authorize(user_id, self, Permission::ManageRosters)?;
+        }
+
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "Pool context does not exist.".to_string(),
+        })?;
+
+        // Is the player in the pool?
         let player = context
             .players
             .get(&player_id.to_string())
@@ -418,44 +1410,9 @@ impl Pool {
 
         let mut is_added = false;
 
-        // Add the player in the roster in its position.
-        match player.position {
-            Position::F => {
-                if (context.pooler_roster[filled_spot_user_id]
-                    .chosen_forwards
-                    .len() as u8)
-                    < self.settings.number_forwards
-                {
-                    if let Some(x) = context.pooler_roster.get_mut(filled_spot_user_id) {
-                        x.chosen_forwards.push(player.id);
-                        is_added = true;
-                    }
-                }
-            }
-            Position::D => {
-                if (context.pooler_roster[filled_spot_user_id]
-                    .chosen_defenders
-                    .len() as u8)
-                    < self.settings.number_defenders
-                {
-                    if let Some(x) = context.pooler_roster.get_mut(filled_spot_user_id) {
-                        x.chosen_defenders.push(player.id);
-                        is_added = true;
-                    }
-                }
-            }
-            Position::G => {
-                if (context.pooler_roster[filled_spot_user_id]
-                    .chosen_goalies
-                    .len() as u8)
-                    < self.settings.number_goalies
-                {
-                    if let Some(x) = context.pooler_roster.get_mut(filled_spot_user_id) {
-                        x.chosen_goalies.push(player.id);
-                        is_added = true;
-                    }
-                }
-            }
+        // Add the player in the roster in a position it is eligible to.
+        if let Some(x) = context.pooler_roster.get_mut(filled_spot_user_id) {
+            is_added = x.add_starting_player(player, &self.settings);
         }
 
         if !is_added {
@@ -471,6 +1428,53 @@ impl Pool {
 
         Ok(())
     }
+
+    pub fn set_auto_lineup(
+        &mut self,
+        user_id: &str,
+        auto_lineup_user_id: &str,
+        is_enabled: bool,
+    ) -> Result<(), AppError> {
+        self.validate_participant(auto_lineup_user_id)?;
+        if !self.is_manager_of(user_id, auto_lineup_user_id) {
+            authorize(user_id, self, Permission::ManageRosters)?;
+        }
+
+        if let Some(participant) = self
+            .participants
+            .iter_mut()
+            .find(|participant| participant.id == auto_lineup_user_id)
+        {
+            participant.auto_lineup = Some(is_enabled);
+        }
+
+        Ok(())
+    }
+
+    pub fn auto_fill_lineups(&mut self) -> Result<bool, AppError> {
+        // Fill the empty starting spots of the poolers that enabled the auto lineup.
+        // Returns true if at least one roster was modified.
+        if !matches!(self.status, PoolState::InProgress) {
+            return Ok(false);
+        }
+
+        let Some(context) = self.context.as_mut() else {
+            return Ok(false);
+        };
+
+        let mut is_filled = false;
+
+        for participant in self
+            .participants
+            .iter()
+            .filter(|participant| participant.auto_lineup == Some(true))
+        {
+            is_filled |= context.fill_empty_spots(&participant.id, &self.settings)?;
+        }
+
+        Ok(is_filled)
+    }
+
     pub fn add_player(
         &mut self,
         user_id: &str,
@@ -479,7 +1483,7 @@ impl Pool {
     ) -> Result<(), AppError> {
         self.validate_pool_status(&PoolState::InProgress)?;
         // Add a player new player into the reservists of a participant.
-        self.has_privileges(user_id)?;
+        authorize(user_id, self, Permission::ManageRosters)?;
 
         let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
             msg: "Pool context does not exist.".to_string(),
@@ -501,7 +1505,10 @@ impl Pool {
             }
         }
 
+        context.validate_acquisition(added_to_user_id, &self.settings)?;
+
         context.add_player_to_reservists(player.id, added_to_user_id)?;
+        context.record_acquisition(added_to_user_id);
 
         context
             .players
@@ -517,7 +1524,7 @@ impl Pool {
         player_id: u32,
     ) -> Result<(), AppError> {
         self.validate_pool_status(&PoolState::InProgress)?;
-        self.has_privileges(user_id)?;
+        authorize(user_id, self, Permission::ManageRosters)?;
 
         let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
             msg: "Pool context does not exist.".to_string(),
@@ -543,10 +1550,7 @@ impl Pool {
         &mut self,
         user_id: &str,
         roster_modified_user_id: &str,
-        forw_list: &Vec<u32>,
-        def_list: &Vec<u32>,
-        goal_list: &Vec<u32>,
-        reserv_list: &Vec<u32>,
+        modified_roster: &PoolerRoster,
     ) -> Result<(), AppError> {
         // Apply a roster modification. This action can only be done during the start and
         // end season on the days that the users are allowed to make roster modifications.
@@ -555,17 +1559,18 @@ impl Pool {
         self.validate_pool_status(&PoolState::InProgress)?;
         self.validate_participant(roster_modified_user_id)?;
 
-        if user_id != roster_modified_user_id {
+        if !self.is_manager_of(user_id, roster_modified_user_id) {
             // If the user making the request is not the roster asking to be modified, the user need to have privilege.
-            self.has_privileges(user_id)?;
+            authorize(user_id, self, Permission::ManageRosters)?;
         }
 
         let start_season_date = NaiveDate::parse_from_str(&self.season_start, "%Y-%m-%d")
             .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
 
-        let mut today = Local::now().date_naive();
+        let now = time_provider().now();
+        let mut today = now.date_naive();
 
-        let time = Local::now().time();
+        let time = now.time();
 
         // At 12PM we start to count the action for the next day.
 
@@ -602,6 +1607,13 @@ impl Pool {
             msg: "Pool context does not exist.".to_string(),
         })?;
 
+        let forw_list = &modified_roster.chosen_forwards;
+        let def_list = &modified_roster.chosen_defenders;
+        let goal_list = &modified_roster.chosen_goalies;
+        let reserv_list = &modified_roster.chosen_reservists;
+        let util_list = modified_roster.get_utility();
+        let injured_reserve_list = modified_roster.get_injured_reserve();
+
         // Validate the total amount of forwards selected
         if forw_list.len() > self.settings.number_forwards as usize {
             return Err(AppError::CustomError {
@@ -632,6 +1644,30 @@ impl Pool {
             });
         }
 
+        // Validate the total amount of utility players selected
+        if util_list.len() > self.settings.get_number_utility() as usize {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "The amount of utility players selected is higher than the limit {}",
+                    self.settings.get_number_utility()
+                ),
+            });
+        }
+
+        // Validate the total amount of players on the injured reserve
+        if injured_reserve_list.len() > self.settings.get_number_injured_reserve() as usize {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "The amount of players on the injured reserve is higher than the limit {}",
+                    self.settings.get_number_injured_reserve()
+                ),
+            });
+        }
+
+        // Salary retained in trades, by the other poolers on the players and by this pooler.
+        let retained_salaries = context.get_retained_salaries();
+        let dead_cap = context.get_dead_cap(roster_modified_user_id);
+
         let roster = context
             .pooler_roster
             .get_mut(roster_modified_user_id)
@@ -643,13 +1679,8 @@ impl Pool {
             })?;
 
         // Validate the total amount of players selected (It should be the same as before)
-        let amount_selected_players =
-            forw_list.len() + def_list.len() + goal_list.len() + reserv_list.len();
-
-        let amount_players_before = roster.chosen_forwards.len()
-            + roster.chosen_defenders.len()
-            + roster.chosen_goalies.len()
-            + roster.chosen_reservists.len();
+        let amount_selected_players = modified_roster.get_players().count();
+        let amount_players_before = roster.get_players().count();
 
         if amount_players_before != amount_selected_players {
             return Err(AppError::CustomError {
@@ -662,12 +1693,9 @@ impl Pool {
         let mut selected_player_map = HashSet::new(); // used to validate dupplication
 
         // Validate that the salary cap limit is respeced.
-        let mut total_salary_cap = 0.0;
+        let mut total_salary_cap = dead_cap;
         if let Some(team_salary_cap) = self.settings.salary_cap {
-            for player_id in forw_list
-                .iter()
-                .chain(def_list.iter().chain(goal_list.iter()))
-            {
+            for player_id in modified_roster.get_starting_players() {
                 let player =
                     context
                         .players
@@ -683,9 +1711,11 @@ impl Pool {
                     ),
                 })?;
 
-                total_salary_cap += player_salary;
+                total_salary_cap +=
+                    player_salary - retained_salaries.get(player_id).unwrap_or(&0.0);
                 if total_salary_cap > team_salary_cap {
-                    return Err(AppError::CustomError {
+                    return Err(AppError::CodedError {
+                        code: ErrorCode::SalaryCapExceeded,
                         msg: format!("The selected players for the alignment are over the salary cap limit '{}$'.", team_salary_cap),
                     });
                 }
@@ -694,12 +1724,7 @@ impl Pool {
 
         // validate each selected players possession by the user asking the modification.
         // Also validate dupplication in the new list.
-        for player_id in forw_list.iter().chain(
-            def_list
-                .iter()
-                .chain(goal_list.iter())
-                .chain(reserv_list.iter()),
-        ) {
+        for player_id in modified_roster.get_players() {
             let player =
                 context
                     .players
@@ -721,11 +1746,51 @@ impl Pool {
             }
         }
 
+        // Validate that the starting players are eligible to the position they fill.
+        for (list, position) in [
+            (forw_list, Position::F),
+            (def_list, Position::D),
+            (goal_list, Position::G),
+        ] {
+            for player_id in list {
+                if let Some(player) = context.players.get(&player_id.to_string()) {
+                    if !player.is_eligible(&position) {
+                        return Err(AppError::CustomError {
+                            msg: format!(
+                                "'{}' is not eligible at the position {}.",
+                                player.name,
+                                position.as_str()
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+
+        // Any skater can fill a utility spot.
+        for player_id in util_list {
+            if let Some(player) = context.players.get(&player_id.to_string()) {
+                if !player.is_skater() {
+                    return Err(AppError::CustomError {
+                        msg: format!("'{}' cannot fill a utility spot.", player.name),
+                    });
+                }
+            }
+        }
+
         // Finally update the roster of the player if everything went well.
         roster.chosen_forwards = forw_list.clone();
         roster.chosen_defenders = def_list.clone();
         roster.chosen_goalies = goal_list.clone();
         roster.chosen_reservists = reserv_list.clone();
+        roster.chosen_utility = (!util_list.is_empty()).then(|| util_list.to_vec());
+        roster.chosen_injured_reserve =
+            (!injured_reserve_list.is_empty()).then(|| injured_reserve_list.to_vec());
+
+        // The new roster respects the salary cap.
+        if let Some(cap_invalid_participants) = context.cap_invalid_participants.as_mut() {
+            cap_invalid_participants.retain(|participant| participant != roster_modified_user_id);
+        }
         Ok(())
     }
 
@@ -738,9 +1803,9 @@ impl Pool {
         // make sure the user making the resquest is a pool participants.
         self.validate_pool_status(&PoolState::Dynasty)?;
         self.validate_participant(protected_players_user_id)?;
-        if user_id != protected_players_user_id {
+        if !self.is_manager_of(user_id, protected_players_user_id) {
             // If the user making the request is not the roster asking to be modified, the user need to have privilege.
-            self.has_privileges(user_id)?;
+            authorize(user_id, self, Permission::ManageRosters)?;
         }
 
         let dynasty_settings =
@@ -751,21 +1816,25 @@ impl Pool {
                     msg: "Dynasty settings does not exist.".to_string(),
                 })?;
 
-        if protected_players.len() != dynasty_settings.next_season_number_players_protected as usize
-        {
+        // Validate that the players protection list does not contains dupplication and also validate that the user possess those players.
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "Pool context does not exist.".to_string(),
+        })?;
+
+        let number_protected = context.get_number_players_to_protect(
+            protected_players_user_id,
+            dynasty_settings.next_season_number_players_protected,
+        )?;
+
+        if protected_players.len() != number_protected {
             return Err(AppError::CustomError {
                 msg: format!(
                     "The amount of players protected should be {}.",
-                    dynasty_settings.next_season_number_players_protected
+                    number_protected
                 ),
             });
         }
 
-        // Validate that the players protection list does not contains dupplication and also validate that the user possess those players.
-        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
-            msg: "Pool context does not exist.".to_string(),
-        })?;
-
         let ref mut user_protected_players =
             context.protected_players.get_or_insert_with(HashMap::new);
 
@@ -798,8 +1867,87 @@ impl Pool {
     pub fn complete_protection(&mut self, user_id: &str) -> Result<(), AppError> {
         // Make sure the user making the request is the owner.
         self.validate_pool_status(&PoolState::Dynasty)?;
-        self.has_owner_privileges(user_id)?;
+        authorize(user_id, self, Permission::ManagePool)?;
+
+        self.apply_protection()
+    }
+
+    pub fn is_protection_deadline_passed(&self, today: NaiveDate) -> bool {
+        matches!(self.status, PoolState::Dynasty)
+            && self
+                .settings
+                .dynasty_settings
+                .as_ref()
+                .and_then(|dynasty_settings| dynasty_settings.protection_deadline.as_ref())
+                .and_then(|deadline| NaiveDate::parse_from_str(deadline, "%Y-%m-%d").ok())
+                .is_some_and(|deadline| today > deadline)
+    }
+
+    pub fn auto_complete_protection(
+        &mut self,
+        players_totals: &HashMap<String, PlayerTotals>,
+    ) -> Result<Vec<String>, AppError> {
+        // Once the protection deadline passed, protect the players with the most points last season
+        // for every pooler that did not protect its players, then complete the protection.
+        // Returns the poolers whose players were protected automatically.
+        self.validate_pool_status(&PoolState::Dynasty)?;
+
+        let number_protected = self
+            .settings
+            .dynasty_settings
+            .as_ref()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "Dynasty settings does not exist.".to_string(),
+            })?
+            .next_season_number_players_protected;
+
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "Pool context does not exist.".to_string(),
+        })?;
+
+        let mut protected_players = context.protected_players.clone().unwrap_or_default();
+        let mut delinquent_poolers = Vec::new();
+
+        for participant in &self.participants {
+            let number_protected =
+                context.get_number_players_to_protect(&participant.id, number_protected)?;
+
+            // The dynasty generation starts every pooler with an empty protection list.
+            if protected_players
+                .get(&participant.id)
+                .is_some_and(|players| players.len() == number_protected)
+            {
+                continue;
+            }
+
+            let roster = context.pooler_roster.get(&participant.id).ok_or_else(|| {
+                AppError::CustomError {
+                    msg: "The user ID does not exist in the pool.".to_string(),
+                }
+            })?;
+
+            let get_points = |player_id: &u32| {
+                players_totals
+                    .get(&player_id.to_string())
+                    .map_or(0.0, |totals| totals.points)
+            };
+
+            let mut roster_players: Vec<u32> = roster.get_players().copied().collect();
+            roster_players.sort_by(|a, b| cmp_points(get_points(b), get_points(a)).then(a.cmp(b)));
+            roster_players.truncate(number_protected);
+
+            protected_players.insert(participant.id.clone(), roster_players);
+            delinquent_poolers.push(participant.id.clone());
+        }
 
+        context.protected_players = Some(protected_players);
+        self.apply_protection()?;
+
+        Ok(delinquent_poolers)
+    }
+
+    fn apply_protection(&mut self) -> Result<(), AppError> {
+        // Keep only the protected players of every pooler, the pool is then ready for its draft.
         let dynasty_settings =
             self.settings
                 .dynasty_settings
@@ -835,7 +1983,10 @@ impl Pool {
 
         for (pooler_user_id, protected_players) in protected_players_map {
             if protected_players.len()
-                != dynasty_settings.next_season_number_players_protected as usize
+                != context.get_number_players_to_protect(
+                    &pooler_user_id,
+                    dynasty_settings.next_season_number_players_protected,
+                )?
             {
                 return Err(AppError::CustomError {
                     msg: "The number of players protected is not valid.".to_string(),
@@ -855,6 +2006,8 @@ impl Pool {
             pooler_roster.chosen_defenders.clear();
             pooler_roster.chosen_goalies.clear();
             pooler_roster.chosen_reservists.clear();
+            pooler_roster.chosen_utility = None;
+            pooler_roster.chosen_injured_reserve = None;
 
             // The list of added players.
             let mut added_player_ids = HashSet::new();
@@ -895,10 +2048,10 @@ impl Pool {
     }
 
     pub fn mark_as_final(&mut self, user_id: &str) -> Result<(), AppError> {
-        self.has_privileges(user_id)?;
+        authorize(user_id, self, Permission::UpdateSettings)?;
         self.validate_pool_status(&PoolState::InProgress)?;
 
-        let context = self.context.as_ref().ok_or_else(|| AppError::CustomError {
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
             msg: "Pool context does not exist.".to_string(),
         })?;
 
@@ -906,7 +2059,7 @@ impl Pool {
         let end_season_date = NaiveDate::parse_from_str(&self.season_end, "%Y-%m-%d")
             .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
 
-        let today = Local::now().date_naive();
+        let today = time_provider().today();
 
         if today <= end_season_date {
             return Err(AppError::CustomError {
@@ -915,7 +2068,10 @@ impl Pool {
         }
 
         // Get the final ranking of the pool. For dynasty pool, this will be use as draft order for the next season.
-        self.final_rank = Some(context.get_final_rank(&self.settings)?);
+        let final_rank = context.get_final_rank(&self.settings)?;
+        context.resolve_conditional_picks(&final_rank);
+
+        self.final_rank = Some(final_rank);
         self.status = PoolState::Final;
 
         Ok(())
@@ -926,7 +2082,7 @@ impl Pool {
         user_id: &str,
         settings: &PoolSettings,
     ) -> Result<(), AppError> {
-        self.has_privileges(user_id)?;
+        authorize(user_id, &self, Permission::UpdateSettings)?;
         self.validate_pool_status(&PoolState::InProgress)?;
 
         if settings.number_forwards != self.settings.number_forwards
@@ -943,1313 +2099,5933 @@ impl Pool {
         Ok(())
     }
 
-    pub fn can_update_pool_settings(self, user_id: &str) -> Result<(), AppError> {
-        self.has_privileges(user_id)?;
-        self.validate_pool_status(&PoolState::Created)?;
+    pub fn get_pool_standings(&self) -> Result<PoolStandings, AppError> {
+        // The cumulative standings come from the totals maintained by the daily cumulation.
+        let context = self.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
 
-        Ok(())
+        let participants: Vec<String> = self
+            .participants
+            .iter()
+            .map(|participant| participant.id.clone())
+            .collect();
+
+        let head_to_head = match (
+            &context.matchup_schedule,
+            &self.settings.head_to_head_settings,
+        ) {
+            (Some(matchup_schedule), Some(head_to_head_settings)) => {
+                Some(matchup_schedule.get_standings(head_to_head_settings))
+            }
+            _ => None,
+        };
+
+        Ok(PoolStandings {
+            cumulative: context.get_cumulative_standings(&participants, &self.settings),
+            head_to_head,
+        })
     }
 
-    pub fn start_draft(
+    pub fn transfer_ownership(
         &mut self,
         user_id: &str,
-        room_users: &Vec<RoomUser>,
-        draft_order: &Vec<String>,
+        new_owner_id: &str,
     ) -> Result<(), AppError> {
-        self.validate_pool_status(&PoolState::Created)?;
-        self.has_owner_privileges(user_id)?;
-
-        // Shuffle the pool participants. so the draft order is
-        let room_users = room_users.clone();
+        authorize(user_id, self, Permission::ManagePool)?;
 
-        let user_ids: Vec<String> = room_users.iter().map(|user| user.id.clone()).collect();
+        if self.owner == new_owner_id {
+            return Err(AppError::CustomError {
+                msg: "This user is already the owner of the pool.".to_string(),
+            });
+        }
 
-        // Set the draft order with the shuffle list.
-        if !draft_order.iter().all(|user_id| user_ids.contains(user_id)) {
+        // The new owner needs to be a participant with a user account.
+        if !self
+            .participants
+            .iter()
+            .any(|participant| participant.id == new_owner_id && participant.is_owned)
+        {
             return Err(AppError::CustomError {
-                msg: "The draft order list provided is not valid.".to_string(),
+                msg: "The new owner needs to be a participant of the pool.".to_string(),
             });
         }
 
-        self.status = PoolState::Draft;
-        self.context = Some(PoolContext::new(&user_ids));
-        self.settings.number_poolers = user_ids.len() as u8;
-        self.participants = room_users.into_iter().map(PoolUser::from).collect();
-        self.draft_order = Some(draft_order.clone());
+        // The new owner does not need the assistant rights anymore,
+        // the previous owner keeps the assistant rights if still participating in the pool.
+        let previous_owner = std::mem::replace(&mut self.owner, new_owner_id.to_string());
+        self.settings.assistants.retain(|id| id != new_owner_id);
+
+        if self
+            .participants
+            .iter()
+            .any(|participant| participant.id == previous_owner)
+            && !self.settings.assistants.contains(&previous_owner)
+        {
+            self.settings.assistants.push(previous_owner);
+        }
 
         Ok(())
     }
 
-    pub fn draft_player(&mut self, user_id: &str, player: &PoolPlayerInfo) -> Result<(), AppError> {
-        // Match against
-
-        let has_privileges = self.has_owner_rights(user_id);
+    pub fn update_team_managers(
+        &mut self,
+        user_id: &str,
+        participant_id: &str,
+        managers: &[UserId],
+    ) -> Result<(), AppError> {
+        // A pooler shares its own team, the owner and the assistants can share any team.
+        if user_id != participant_id {
+            authorize(user_id, self, Permission::ManageRosters)?;
+        }
 
-        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
-            msg: "pool context does not exist.".to_string(),
-        })?;
+        if managers.iter().any(|manager| &**manager == participant_id) {
+            return Err(AppError::CustomError {
+                msg: "A pooler cannot be a manager of its own team.".to_string(),
+            });
+        }
 
-        let draft_order = self
-            .draft_order
-            .as_ref()
+        let participant = self
+            .participants
+            .iter_mut()
+            .find(|participant| participant.id == participant_id)
             .ok_or_else(|| AppError::CustomError {
-                msg: "draft order does not exist.".to_string(),
+                msg: format!("User {} is not a participant of the pool.", participant_id),
             })?;
 
-        let mut is_done = false;
+        let mut managers: Vec<String> =
+            managers.iter().map(|manager| manager.to_string()).collect();
+        managers.sort();
+        managers.dedup();
 
-        if self.settings.dynasty_settings.is_some() && context.past_tradable_picks.is_some() {
-            // This is a dynasty draft context.
-            // The final rank is being used as draft order.
-            is_done = context.draft_player_dynasty(
-                user_id,
-                player,
-                draft_order,
-                &self.settings,
-                has_privileges,
-            )?;
-        } else {
-            // This is a dynasty draft context.
-            // The participant order is being used as draft order.
-            is_done = context.draft_player(
-                user_id,
-                player,
-                draft_order,
-                &self.settings,
-                has_privileges,
-            )?;
+        participant.managers = (!managers.is_empty()).then_some(managers);
+
+        Ok(())
+    }
+
+    pub fn update_team_profile(
+        &mut self,
+        user_id: &str,
+        req: &UpdateTeamProfileRequest,
+    ) -> Result<(), AppError> {
+        if !self.is_manager_of(user_id, &req.participant_id) {
+            authorize(user_id, self, Permission::ManageRosters)?;
         }
 
-        if is_done {
-            // The draft is done.
-            self.status = PoolState::InProgress;
+        let team_name = req.team_name.as_deref().map(str::trim);
+        if team_name.is_some_and(|team_name| {
+            team_name.is_empty() || team_name.chars().count() > TEAM_NAME_MAX_LENGTH
+        }) {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "The team name needs to contain between 1 and {} characters.",
+                    TEAM_NAME_MAX_LENGTH
+                ),
+            });
         }
 
+        let team_abbreviation = req
+            .team_abbreviation
+            .as_deref()
+            .map(|abbreviation| abbreviation.trim().to_uppercase());
+        if team_abbreviation.as_ref().is_some_and(|abbreviation| {
+            abbreviation.len() < 2
+                || abbreviation.len() > TEAM_ABBREVIATION_MAX_LENGTH
+                || !abbreviation.chars().all(|c| c.is_ascii_alphanumeric())
+        }) {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "The team abbreviation needs to contain between 2 and {} letters or digits.",
+                    TEAM_ABBREVIATION_MAX_LENGTH
+                ),
+            });
+        }
+
+        if req
+            .avatar_url
+            .as_ref()
+            .is_some_and(|avatar_url| !avatar_url.starts_with("https://"))
+        {
+            return Err(AppError::CustomError {
+                msg: "The avatar url needs to use https.".to_string(),
+            });
+        }
+
+        let participant = self
+            .participants
+            .iter_mut()
+            .find(|participant| participant.id == req.participant_id)
+            .ok_or_else(|| AppError::CustomError {
+                msg: format!(
+                    "User {} is not a participant of the pool.",
+                    req.participant_id
+                ),
+            })?;
+
+        participant.team_name = team_name.map(str::to_string);
+        participant.team_abbreviation = team_abbreviation;
+        participant.avatar_url = req.avatar_url.clone();
+
         Ok(())
     }
 
-    pub fn undo_draft_player(&mut self, user_id: &str) -> Result<(), AppError> {
-        // Undo the last draft selection.
-        // This call can only be made if the user id is the owner.
-        self.has_owner_privileges(user_id)?;
-        self.validate_pool_status(&PoolState::Draft)?;
+    pub fn update_trade_block(
+        &mut self,
+        user_id: &str,
+        req: &UpdateTradeBlockRequest,
+    ) -> Result<(), AppError> {
+        // Add or remove a player of a roster from the trade block.
+        if !self.is_manager_of(user_id, &req.participant_id) {
+            authorize(user_id, self, Permission::ManageRosters)?;
+        }
+
+        if !matches!(self.status, PoolState::InProgress | PoolState::Dynasty) {
+            return Err(AppError::CodedError {
+                code: ErrorCode::InvalidPoolStatus,
+                msg: "The trade block is only available once the pool is drafted.".to_string(),
+            });
+        }
+
+        let note = req.note.as_deref().map(str::trim);
+        if note.is_some_and(|note| note.chars().count() > TRADE_BLOCK_NOTE_MAX_LENGTH) {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "The note needs to contain at most {} characters.",
+                    TRADE_BLOCK_NOTE_MAX_LENGTH
+                ),
+            });
+        }
 
         let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
             msg: "pool context does not exist.".to_string(),
         })?;
 
-        let draft_order = self
-            .draft_order
-            .as_ref()
+        let roster = context
+            .pooler_roster
+            .get(&*req.participant_id)
             .ok_or_else(|| AppError::CustomError {
-                msg: "draft order does not exist.".to_string(),
+                msg: format!(
+                    "User {} is not a participant of the pool.",
+                    req.participant_id
+                ),
             })?;
 
-        context.undo_draft_player(draft_order, &self.settings)
+        if req.is_on_block && !roster.validate_player_possession(req.player_id) {
+            return Err(AppError::CustomError {
+                msg: "The player is not in the roster of the pooler.".to_string(),
+            });
+        }
+
+        // The stale listings are dropped every time the trade block is modified.
+        let mut trade_block = context.get_trade_block();
+        let listings = trade_block
+            .entry(req.participant_id.to_string())
+            .or_default();
+        listings.retain(|listing| listing.player_id != req.player_id);
+
+        if req.is_on_block {
+            listings.push(TradeBlockListing {
+                player_id: req.player_id,
+                note: note.filter(|note| !note.is_empty()).map(str::to_string),
+                wanted_positions: req.wanted_positions.clone().unwrap_or_default(),
+                date_listed: Utc::now().timestamp_millis(),
+            });
+        }
+
+        trade_block.retain(|_, listings| !listings.is_empty());
+        context.trade_block = Some(trade_block);
+
+        Ok(())
     }
 
-    pub fn validate_participant(&self, user_id: &str) -> Result<(), AppError> {
-        // Validate that the user is a pool participant.
-        if !self.participants.iter().any(|user| user.id == user_id) {
+    pub fn validate_team_claim(&self, user_id: &str, participant_id: &str) -> Result<(), AppError> {
+        // Only the owner can hand a team managed by the pool to a user account.
+        authorize(user_id, self, Permission::ManagePool)?;
+
+        let participant = self
+            .participants
+            .iter()
+            .find(|participant| participant.id == participant_id)
+            .ok_or_else(|| AppError::CustomError {
+                msg: format!("User {} is not a participant of the pool.", participant_id),
+            })?;
+
+        if participant.is_owned {
             return Err(AppError::CustomError {
-                msg: format!("User {} is not a pool participants.", user_id),
+                msg: "This team is already owned by a user.".to_string(),
             });
         }
 
         Ok(())
     }
 
-    pub fn validate_pool_status(&self, expected_status: &PoolState) -> Result<(), AppError> {
-        // Validate that the pool is in the expected status.
+    pub fn claim_team(
+        &mut self,
+        participant_id: &str,
+        user_id: &str,
+        user_name: &str,
+    ) -> Result<(), AppError> {
+        // Bind the user account to a team that was managed by the pool.
+        // Every reference to the previous participant id is rewritten with the user id.
+        if self
+            .participants
+            .iter()
+            .any(|participant| participant.id == user_id)
+        {
+            return Err(AppError::CustomError {
+                msg: "You are already a participant of this pool.".to_string(),
+            });
+        }
 
-        // ignore the unused_variables warning since the warning is false positive and is
-        // caused by the compiler not recognizing the matches! patterns.
-        #[allow(unused_variables)]
-        if !matches!(&self.status, expected_status) {
+        if self
+            .participants
+            .iter()
+            .any(|participant| participant.id == participant_id && participant.is_owned)
+        {
             return Err(AppError::CustomError {
-                msg: format!(
-                    "The expected pool status '{}', current pool status '{}'.",
-                    expected_status, self.status
-                ),
+                msg: "This team is already owned by a user.".to_string(),
             });
         }
+
+        let mut pool_value = serde_json::to_value(&*self)
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+        replace_participant_id(&mut pool_value, participant_id, user_id);
+        *self = serde_json::from_value(pool_value)
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        let participant = self
+            .participants
+            .iter_mut()
+            .find(|participant| participant.id == user_id)
+            .ok_or_else(|| AppError::CustomError {
+                msg: format!("User {} is not a participant of the pool.", participant_id),
+            })?;
+
+        participant.is_owned = true;
+        participant.name = user_name.to_string();
+
         Ok(())
     }
 
-    pub fn has_assistants_rights(&self, user_id: &str) -> bool {
-        self.settings.assistants.contains(&user_id.to_string())
-    }
+    pub fn update_orphaned_team(
+        &mut self,
+        user_id: &str,
+        req: &UpdateOrphanedTeamRequest,
+    ) -> Result<(), AppError> {
+        // Mark a team abandoned by its user as orphaned, its trades are paused until it is adopted.
+        authorize(user_id, self, Permission::ManagePool)?;
+
+        if !matches!(
+            self.status,
+            PoolState::Draft | PoolState::InProgress | PoolState::Dynasty
+        ) {
+            return Err(AppError::CodedError {
+                code: ErrorCode::InvalidPoolStatus,
+                msg: "A team can only be orphaned while the pool is active.".to_string(),
+            });
+        }
+
+        if self.owner == *req.participant_id {
+            return Err(AppError::CustomError {
+                msg: "The team of the owner cannot be orphaned.".to_string(),
+            });
+        }
+
+        let participant = self
+            .participants
+            .iter_mut()
+            .find(|participant| participant.id == req.participant_id)
+            .ok_or_else(|| AppError::CustomError {
+                msg: format!(
+                    "User {} is not a participant of the pool.",
+                    req.participant_id
+                ),
+            })?;
 
-    pub fn has_owner_rights(&self, user_id: &str) -> bool {
-        self.owner == user_id
+        participant.is_orphaned = req.is_orphaned.then_some(true);
+        participant.is_owner_managed =
+            (req.is_orphaned && req.is_owner_managed.unwrap_or(false)).then_some(true);
+
+        Ok(())
     }
 
-    pub fn has_privileges(&self, user_id: &str) -> Result<(), AppError> {
-        if !self.has_assistants_rights(user_id) && !self.has_owner_rights(user_id) {
+    pub fn adopt_team(&mut self, user_id: &str, req: &AdoptTeamRequest) -> Result<(), AppError> {
+        // Hand an orphaned team to a new user.
+        // Every reference to the previous participant id is rewritten with the new user id.
+        authorize(user_id, self, Permission::ManagePool)?;
+
+        if !self
+            .participants
+            .iter()
+            .any(|participant| participant.id == req.participant_id && participant.is_orphaned())
+        {
+            return Err(AppError::CustomError {
+                msg: "Only an orphaned team can be adopted.".to_string(),
+            });
+        }
+
+        if self
+            .participants
+            .iter()
+            .any(|participant| participant.id == req.new_user_id)
+        {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "User {} is already a participant of the pool.",
+                    req.new_user_id
+                ),
+            });
+        }
+
+        let new_user_name = req.new_user_name.trim();
+        if new_user_name.is_empty() {
             return Err(AppError::CustomError {
-                msg: "This action require privileged rights.".to_string(),
+                msg: "The name of the new user cannot be empty.".to_string(),
             });
         }
 
+        // The rights given to the previous user are not passed to the new user.
+        self.settings
+            .assistants
+            .retain(|assistant| *assistant != req.participant_id);
+
+        let mut pool_value = serde_json::to_value(&*self)
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+        replace_participant_id(&mut pool_value, &req.participant_id, &req.new_user_id);
+        *self = serde_json::from_value(pool_value)
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
+        let participant = self
+            .participants
+            .iter_mut()
+            .find(|participant| participant.id == req.new_user_id)
+            .ok_or_else(|| AppError::CustomError {
+                msg: format!(
+                    "User {} is not a participant of the pool.",
+                    req.participant_id
+                ),
+            })?;
+
+        participant.name = new_user_name.to_string();
+        participant.is_owned = true;
+        participant.managers = None;
+        participant.is_orphaned = None;
+        participant.is_owner_managed = None;
+
         Ok(())
     }
 
-    pub fn has_owner_privileges(&self, user_id: &str) -> Result<(), AppError> {
-        if !self.has_owner_rights(user_id) {
+    pub fn merge_user(&mut self, merged_user_id: &str, user_id: &str) -> Result<(), AppError> {
+        // Move the references of an account merged into another account of the same user.
+        // The 2 accounts cannot both own a team of the pool.
+        if self
+            .participants
+            .iter()
+            .any(|participant| participant.id == user_id)
+            && self
+                .participants
+                .iter()
+                .any(|participant| participant.id == merged_user_id)
+        {
             return Err(AppError::CustomError {
-                msg: "This action require owner rights.".to_string(),
+                msg: format!(
+                    "Both accounts are participants of the pool '{}', they cannot be merged.",
+                    self.name
+                ),
             });
         }
 
+        let mut pool_value = serde_json::to_value(&*self)
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+        replace_participant_id(&mut pool_value, merged_user_id, user_id);
+        *self = serde_json::from_value(pool_value)
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+
         Ok(())
     }
-}
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub enum PoolState {
-    Final,
-    InProgress,
-    Dynasty,
-    Draft,
-    Created,
-}
+    pub fn remove_deleted_user(&mut self, user_id: &str) {
+        // Remove the management rights of a deleted account.
+        // The ownership goes to an assistant, or else to another participant with a user account.
+        // Without any other user, the deleted account stays the owner and the pool can no longer be managed.
+        self.settings.assistants.retain(|id| id != user_id);
 
-impl fmt::Display for PoolState {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // To be able to print out the PoolState enum.
-        match self {
-            PoolState::Final => write!(f, "Final"),
-            PoolState::InProgress => write!(f, "In progress"),
-            PoolState::Dynasty => write!(f, "Dynasty"),
-            PoolState::Draft => write!(f, "Draft"),
-            PoolState::Created => write!(f, "Created"),
+        if self.owner != user_id {
+            return;
         }
-    }
-}
 
-#[derive(Debug, Deserialize, Serialize, Clone)] // Copy
-pub struct PoolContext {
-    pub pooler_roster: HashMap<String, PoolerRoster>,
-    pub players_name_drafted: Vec<u32>,
-    pub score_by_day: Option<HashMap<String, HashMap<String, DailyRosterPoints>>>,
-    pub tradable_picks: Option<Vec<HashMap<String, String>>>,
-    pub past_tradable_picks: Option<Vec<HashMap<String, String>>>,
-    pub protected_players: Option<HashMap<String, Vec<u32>>>,
-    pub players: HashMap<String, PoolPlayerInfo>,
-}
+        let is_user = |id: &String| {
+            self.participants
+                .iter()
+                .any(|participant| &participant.id == id && participant.is_owned)
+        };
 
-impl PoolContext {
-    pub fn new(participants: &[String]) -> Self {
-        let mut pooler_roster = HashMap::new();
+        let new_owner = self
+            .settings
+            .assistants
+            .iter()
+            .find(|id| is_user(id))
+            .cloned()
+            .or_else(|| {
+                self.participants
+                    .iter()
+                    .find(|participant| participant.id != user_id && participant.is_owned)
+                    .map(|participant| participant.id.clone())
+            });
 
-        // Initialize all participants roster object.
-        for participant in participants.iter() {
-            pooler_roster.insert(participant.to_string(), PoolerRoster::new());
+        if let Some(new_owner) = new_owner {
+            self.settings.assistants.retain(|id| id != &new_owner);
+            self.owner = new_owner;
         }
+    }
 
-        Self {
-            pooler_roster,
-            score_by_day: Some(HashMap::new()),
-            tradable_picks: Some(Vec::new()),
-            past_tradable_picks: Some(Vec::new()),
-            players_name_drafted: Vec::new(),
-            protected_players: None,
-            players: HashMap::new(),
-        }
+    pub fn can_update_pool_settings(self, user_id: &str) -> Result<(), AppError> {
+        authorize(user_id, &self, Permission::UpdateSettings)?;
+        self.validate_pool_status(&PoolState::Created)?;
+
+        Ok(())
     }
 
-    pub fn get_final_rank(&self, pool_settings: &PoolSettings) -> Result<Vec<String>, AppError> {
-        let Some(score_by_day) = &self.score_by_day else {
+    pub fn start_draft(
+        &mut self,
+        user_id: &str,
+        room_users: &Vec<RoomUser>,
+        draft_order: &Vec<String>,
+    ) -> Result<(), AppError> {
+        self.validate_pool_status(&PoolState::Created)?;
+        authorize(user_id, self, Permission::ManageDraft)?;
+
+        let room_users = room_users.clone();
+
+        let user_ids: Vec<String> = room_users.iter().map(|user| user.id.clone()).collect();
+
+        // Without a draft order, the order set in the lobby is used, or the poolers are shuffled.
+        let draft_order = if !draft_order.is_empty() {
+            draft_order.clone()
+        } else if let Some(lobby_draft_order) = &self.draft_order {
+            lobby_draft_order.clone()
+        } else {
+            let mut shuffled_user_ids = user_ids.clone();
+            shuffled_user_ids.shuffle(&mut rand::thread_rng());
+            shuffled_user_ids
+        };
+
+        if !is_valid_draft_order(&draft_order, &user_ids) {
+            return Err(AppError::CustomError {
+                msg: "The draft order needs to contain every pooler of the room once.".to_string(),
+            });
+        }
+
+        let mut context = PoolContext::new(&user_ids);
+
+        if matches!(self.settings.draft_type, DraftType::Auction) {
+            // Every pooler starts the auction draft with the full budget.
+            let auction_settings =
+                self.settings
+                    .auction_settings
+                    .as_ref()
+                    .ok_or_else(|| AppError::CustomError {
+                        msg: "Auction settings does not exist.".to_string(),
+                    })?;
+
+            context.auction = Some(AuctionContext::new(&user_ids, auction_settings));
+        }
+
+        self.status = PoolState::Draft;
+        self.context = Some(context);
+        self.settings.number_poolers = user_ids.len() as u8;
+        self.participants = room_users.into_iter().map(PoolUser::from).collect();
+        self.draft_order = Some(draft_order);
+
+        Ok(())
+    }
+
+    pub fn update_draft_order(
+        &mut self,
+        user_id: &str,
+        room_users: &[RoomUser],
+        draft_order_type: &DraftOrderType,
+        past_season_rank: Option<&[String]>,
+    ) -> Result<(), AppError> {
+        // Set the draft order in the lobby, the draft starts with it.
+        // The order of a dynasty pool can also be set while the players are protected.
+        if !matches!(self.status, PoolState::Created | PoolState::Dynasty) {
+            self.validate_pool_status(&PoolState::Created)?;
+        }
+        authorize(user_id, self, Permission::ManageDraft)?;
+
+        let user_ids: Vec<String> = room_users.iter().map(|user| user.id.clone()).collect();
+
+        let draft_order = match draft_order_type {
+            DraftOrderType::Custom { draft_order } => draft_order.clone(),
+            DraftOrderType::Random => {
+                let mut shuffled_user_ids = user_ids.clone();
+                shuffled_user_ids.shuffle(&mut rand::thread_rng());
+                shuffled_user_ids
+            }
+            DraftOrderType::ReverseStandings => {
+                let past_season_rank = past_season_rank.ok_or_else(|| AppError::CustomError {
+                    msg: "The pool has no past season to order the draft with.".to_string(),
+                })?;
+
+                let mut draft_order: Vec<String> = user_ids
+                    .iter()
+                    .filter(|user_id| !past_season_rank.contains(user_id))
+                    .cloned()
+                    .collect();
+                draft_order.extend(
+                    past_season_rank
+                        .iter()
+                        .rev()
+                        .filter(|participant| user_ids.contains(participant))
+                        .cloned(),
+                );
+                draft_order
+            }
+        };
+
+        if !is_valid_draft_order(&draft_order, &user_ids) {
+            return Err(AppError::CustomError {
+                msg: "The draft order needs to contain every pooler of the room once.".to_string(),
+            });
+        }
+
+        self.draft_order = Some(draft_order);
+        Ok(())
+    }
+
+    pub fn get_season_rank(&self) -> Result<Vec<String>, AppError> {
+        // The final rank of a pool, or its current rank if it was not marked as final.
+        if let Some(final_rank) = &self.final_rank {
+            return Ok(final_rank.clone());
+        }
+
+        let context = self.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        context.get_final_rank(&self.settings)
+    }
+
+    pub fn draft_player(&mut self, user_id: &str, player: &PoolPlayerInfo) -> Result<(), AppError> {
+        // Match against
+        self.validate_draft_not_paused()?;
+
+        if matches!(self.settings.draft_type, DraftType::Auction) {
+            return Err(AppError::CustomError {
+                msg: "Players are drafted through bids in an auction draft.".to_string(),
+            });
+        }
+
+        // The managers of the next drafter can also pick for the team.
+        let has_privileges = has_permission(user_id, self, Permission::ManageDraft)
+            || self.manages_next_drafter(user_id);
+
+        // Out of turn, a pooler with a skipped pick makes it up.
+        let makeup_drafter = if self.manages_next_drafter(user_id) {
+            None
+        } else {
+            self.context.as_ref().and_then(|context| {
+                self.get_managed_participants(user_id)
+                    .into_iter()
+                    .find(|participant| context.has_makeup_pick(participant))
+            })
+        };
+
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let draft_order = self
+            .draft_order
+            .as_ref()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "draft order does not exist.".to_string(),
+            })?;
+
+        let mut is_done = false;
+
+        if let Some(makeup_drafter) = makeup_drafter {
+            is_done = context.make_up_pick(&makeup_drafter, player, &self.settings)?;
+        } else if self.settings.dynasty_settings.is_some() && context.past_tradable_picks.is_some()
+        {
+            // This is a dynasty draft context.
+            // The final rank is being used as draft order.
+            is_done = context.draft_player_dynasty(
+                user_id,
+                player,
+                draft_order,
+                &self.settings,
+                has_privileges,
+            )?;
+        } else {
+            // This is a dynasty draft context.
+            // The participant order is being used as draft order.
+            is_done = context.draft_player(
+                user_id,
+                player,
+                draft_order,
+                &self.settings,
+                has_privileges,
+            )?;
+        }
+
+        if is_done {
+            // The draft is done.
+            self.start_season()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn undo_draft_player(&mut self, user_id: &str) -> Result<(), AppError> {
+        // Undo the last draft selection.
+        // This call can only be made if the user id is the owner.
+        authorize(user_id, self, Permission::ManageDraft)?;
+        self.validate_pool_status(&PoolState::Draft)?;
+
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let draft_order = self
+            .draft_order
+            .as_ref()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "draft order does not exist.".to_string(),
+            })?;
+
+        context.undo_draft_player(draft_order, &self.settings)
+    }
+
+    pub fn trade_draft_picks(
+        &mut self,
+        user_id: &str,
+        req: &TradeDraftPicksRequest,
+    ) -> Result<(), AppError> {
+        // Trade current draft picks between two poolers during a dynasty draft.
+        // This call can only be made if the user id is the owner, once both poolers agreed on the trade.
+        authorize(user_id, self, Permission::ManageDraft)?;
+
+        if !matches!(self.status, PoolState::Draft) || self.settings.dynasty_settings.is_none() {
+            return Err(AppError::CustomError {
+                msg: "Current draft picks can only be traded during a dynasty draft.".to_string(),
+            });
+        }
+
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let draft_order = self
+            .draft_order
+            .as_ref()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "draft order does not exist.".to_string(),
+            })?;
+
+        context.trade_draft_picks(
+            &req.from_user_id,
+            &req.to_user_id,
+            &req.from_picks,
+            &req.to_picks,
+            draft_order,
+            &self.settings,
+        )
+    }
+
+    pub fn pause_draft(&mut self, user_id: &str, is_paused: bool) -> Result<(), AppError> {
+        // Pause or resume the draft. This call can only be made if the user id is the owner.
+        authorize(user_id, self, Permission::ManageDraft)?;
+
+        if !matches!(self.status, PoolState::Draft) {
+            return Err(AppError::CustomError {
+                msg: "The draft can only be paused or resumed during the draft.".to_string(),
+            });
+        }
+
+        self.is_draft_paused = Some(is_paused);
+        Ok(())
+    }
+
+    pub fn rollback_draft(&mut self, user_id: &str, pick_number: usize) -> Result<(), AppError> {
+        // Unwind every pick made after the pick number (the pick number is kept).
+        // Either all the picks are unwound or none of them.
+        // This call can only be made if the user id is the owner.
+        authorize(user_id, self, Permission::ManageDraft)?;
+
+        if !matches!(self.status, PoolState::Draft) {
+            return Err(AppError::CustomError {
+                msg: "The draft can only be rolled back during the draft.".to_string(),
+            });
+        }
+
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let draft_order = self
+            .draft_order
+            .as_ref()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "draft order does not exist.".to_string(),
+            })?;
+
+        if pick_number >= context.players_name_drafted.len() {
+            return Err(AppError::CustomError {
+                msg: format!("There is no pick made after the pick {}.", pick_number),
+            });
+        }
+
+        let mut rolled_back_context = context.clone();
+
+        // An auction nomination in progress is cancelled.
+        if let Some(auction) = &mut rolled_back_context.auction {
+            auction.nomination = None;
+        }
+
+        while rolled_back_context.players_name_drafted.len() > pick_number {
+            if rolled_back_context.players_name_drafted.last() == Some(&0) {
+                // A skipped pick, there is no player to remove from the rosters.
+                rolled_back_context.players_name_drafted.pop();
+            } else {
+                rolled_back_context.undo_draft_player(draft_order, &self.settings)?;
+            }
+        }
+        rolled_back_context.drop_unwound_makeup_picks();
+
+        *context = rolled_back_context;
+        Ok(())
+    }
+
+    pub fn get_preview(&self) -> Result<PoolPreview, AppError> {
+        // The rosters and the cap numbers of the pool, once a request was applied without being saved.
+        let context = self.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let teams_cap = self
+            .participants
+            .iter()
+            .filter_map(|participant| {
+                let roster = context.pooler_roster.get(&participant.id)?;
+                let current_cap_usage = context
+                    .calculate_cumulated_salary_cap(roster, &context.players)
+                    .ok();
+
+                Some(TeamCapPreview {
+                    participant: participant.id.clone(),
+                    current_cap_usage,
+                    dead_cap: context.get_dead_cap(&participant.id),
+                    cap_space: self
+                        .settings
+                        .salary_cap
+                        .zip(current_cap_usage)
+                        .map(|(salary_cap, cap_usage)| salary_cap - cap_usage),
+                })
+            })
+            .collect();
+
+        Ok(PoolPreview {
+            pooler_roster: context.pooler_roster.clone(),
+            trades: self.trades.clone(),
+            salary_cap: self.settings.salary_cap,
+            teams_cap,
+        })
+    }
+
+    pub fn get_trade(&self, trade_id: u32) -> Result<&Trade, AppError> {
+        self.trades
+            .iter()
+            .flatten()
+            .find(|trade| trade.id == trade_id)
+            .ok_or_else(|| AppError::CodedError {
+                code: ErrorCode::TradeNotFound,
+                msg: "The trade does not exist.".to_string(),
+            })
+    }
+
+    pub fn get_trade_analysis(
+        &self,
+        trade_id: u32,
+        schedules: &HashMap<u32, TeamRemainingSchedule>,
+        today: NaiveDate,
+    ) -> Result<TradeAnalysis, AppError> {
+        // Compare the value of the items exchanged on every leg of a trade.
+        let trade = self.get_trade(trade_id)?;
+
+        let context = self.context.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        // Without the schedule of a team, its remaining games are estimated from the remaining days of the season.
+        let season_start = NaiveDate::parse_from_str(&self.season_start, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+        let season_end = NaiveDate::parse_from_str(&self.season_end, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+        let season_days = (season_end - season_start).num_days().max(1);
+        let remaining_days = (season_end - today).num_days().clamp(0, season_days);
+        let estimated_remaining_games =
+            (i64::from(NHL_REGULAR_SEASON_GAMES) * remaining_days / season_days) as u16;
+
+        let recent_points = context.get_recent_players_points(&self.settings, today);
+
+        let get_player_value = |player_id: u32| -> Result<TradePlayerValue, AppError> {
+            let player = context.players.get(&player_id.to_string()).ok_or_else(|| {
+                AppError::CustomError {
+                    msg: "Player does not exist.".to_string(),
+                }
+            })?;
+            let totals = context
+                .players_totals
+                .as_ref()
+                .and_then(|players_totals| players_totals.get(&player_id.to_string()))
+                .cloned()
+                .unwrap_or_default();
+            let (recent_games, recent_total) = recent_points
+                .get(&player_id.to_string())
+                .copied()
+                .unwrap_or((0, 0.0));
+            let schedule = player.team.and_then(|team| schedules.get(&team));
+
+            let points_per_game = if totals.games > 0 {
+                totals.points / f64::from(totals.games)
+            } else {
+                0.0
+            };
+            let recent_points_per_game = if recent_games > 0 {
+                recent_total / f64::from(recent_games)
+            } else {
+                points_per_game
+            };
+
+            // The season pace weights more than the recent form,
+            // an easier schedule than the league average (0.5) raises the projection.
+            let pace = 0.6 * points_per_game + 0.4 * recent_points_per_game;
+            let remaining_games =
+                schedule.map_or(estimated_remaining_games, |schedule| schedule.games);
+            let schedule_factor = schedule.map_or(1.0, |schedule| 1.5 - schedule.strength);
+
+            Ok(TradePlayerValue {
+                player: player.clone(),
+                games_played: totals.games,
+                total_points: totals.points,
+                points_per_game,
+                recent_games,
+                recent_points_per_game,
+                remaining_games,
+                remaining_schedule_strength: schedule.map(|schedule| schedule.strength),
+                projected_points: pace * f64::from(remaining_games) * schedule_factor,
+            })
+        };
+
+        // A pick is worth the average projection of the players drafted in that round of the pool draft.
+        let draft_recap = context.get_draft_recap(self.draft_order.as_deref().unwrap_or_default());
+        let mut round_values: HashMap<usize, (f64, u16)> = HashMap::new();
+        for recap_pick in &draft_recap {
+            if let Some(player) = &recap_pick.player {
+                let value = get_player_value(player.id)?;
+                let round_value = round_values.entry(recap_pick.round).or_default();
+                round_value.0 += value.projected_points;
+                round_value.1 += 1;
+            }
+        }
+
+        let mut legs = Vec::new();
+        let mut poolers: Vec<TradePoolerBalance> = trade
+            .get_participants()
+            .iter()
+            .map(|participant| TradePoolerBalance {
+                participant: participant.clone(),
+                value_given: 0.0,
+                value_received: 0.0,
+                net_value: 0.0,
+            })
+            .collect();
+
+        for leg in trade.get_legs() {
+            let players = leg
+                .items
+                .players
+                .iter()
+                .map(|player_id| get_player_value(*player_id))
+                .collect::<Result<Vec<TradePlayerValue>, AppError>>()?;
+            let picks: Vec<TradePickValue> = leg
+                .items
+                .get_picks()
+                .into_iter()
+                .map(|pick| TradePickValue {
+                    pick: pick.clone(),
+                    projected_points: round_values
+                        .get(&(usize::from(pick.round) + 1))
+                        .map_or(0.0, |(total, count)| total / f64::from(*count)),
+                })
+                .collect();
+
+            let value = players
+                .iter()
+                .map(|player| player.projected_points)
+                .chain(picks.iter().map(|pick| pick.projected_points))
+                .sum();
+
+            for balance in poolers.iter_mut() {
+                if balance.participant == leg.from {
+                    balance.value_given += value;
+                }
+                if balance.participant == leg.to {
+                    balance.value_received += value;
+                }
+            }
+
+            legs.push(TradeLegAnalysis {
+                from: leg.from,
+                to: leg.to,
+                players,
+                picks,
+                value,
+            });
+        }
+
+        for balance in poolers.iter_mut() {
+            balance.net_value = balance.value_received - balance.value_given;
+        }
+
+        // The gap between the best and the worst balance, relative to the biggest side of the trade.
+        let max_net = poolers.iter().map(|b| b.net_value).fold(f64::MIN, f64::max);
+        let min_net = poolers.iter().map(|b| b.net_value).fold(f64::MAX, f64::min);
+        let max_side = poolers
+            .iter()
+            .map(|b| b.value_given.max(b.value_received))
+            .fold(0.0, f64::max);
+
+        let fairness = if max_side > 0.0 {
+            (1.0 - (max_net - min_net) / (2.0 * max_side)).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+
+        let favored_pooler = poolers
+            .iter()
+            .filter(|balance| balance.net_value > 0.0)
+            .max_by(|a, b| a.net_value.total_cmp(&b.net_value))
+            .map(|balance| balance.participant.clone());
+
+        Ok(TradeAnalysis {
+            trade_id,
+            legs,
+            poolers,
+            fairness,
+            favored_pooler,
+        })
+    }
+
+    pub fn check_invariants(&self) -> Result<(), AppError> {
+        // The pools without context (not drafted yet) have no invariant to check.
+        match &self.context {
+            Some(context) => context.check_invariants(&self.settings, &self.status),
+            None => Ok(()),
+        }
+    }
+
+    pub fn validate_draft_not_paused(&self) -> Result<(), AppError> {
+        if self.is_draft_paused == Some(true) {
+            return Err(AppError::CustomError {
+                msg: "The draft is paused.".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn nominate_player(
+        &mut self,
+        user_id: &str,
+        player: &PoolPlayerInfo,
+        opening_bid: u32,
+    ) -> Result<(), AppError> {
+        // Auction draft: nominate a player to be auctioned, the nominator opens the bid.
+        self.validate_pool_status(&PoolState::Draft)?;
+        self.validate_draft_not_paused()?;
+        // The managers of the next drafter can also pick for the team.
+        let has_privileges = has_permission(user_id, self, Permission::ManageDraft)
+            || self.manages_next_drafter(user_id);
+
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let draft_order = self
+            .draft_order
+            .as_ref()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "draft order does not exist.".to_string(),
+            })?;
+
+        context.nominate_player(
+            user_id,
+            player,
+            opening_bid,
+            draft_order,
+            &self.settings,
+            has_privileges,
+        )
+    }
+
+    pub fn bid_player(
+        &mut self,
+        user_id: &str,
+        bidder_id: &str,
+        amount: u32,
+    ) -> Result<(), AppError> {
+        // Auction draft: bid on the nominated player.
+        // The owner can bid on the behalf of the poolers that are not owned.
+        self.validate_pool_status(&PoolState::Draft)?;
+        self.validate_participant(bidder_id)?;
+
+        if !self.is_manager_of(user_id, bidder_id) {
+            authorize(user_id, self, Permission::ManageDraft)?;
+        }
+
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        context.bid_player(bidder_id, amount, &self.settings)
+    }
+
+    pub fn close_bid(&mut self, user_id: &str) -> Result<(), AppError> {
+        // Auction draft: award the nominated player to the highest bidder.
+        self.validate_pool_status(&PoolState::Draft)?;
+        self.validate_draft_not_paused()?;
+        authorize(user_id, self, Permission::ManageDraft)?;
+
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        if context.close_bid(&self.settings)? {
+            // The draft is done.
+            self.start_season()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel_nomination(&mut self, user_id: &str) -> Result<(), AppError> {
+        // Auction draft: cancel the nomination in progress, i.g., when the highest bidder cannot roster the player anymore.
+        // The nominator can nominate again.
+        self.validate_pool_status(&PoolState::Draft)?;
+        authorize(user_id, self, Permission::ManageDraft)?;
+
+        self.context
+            .as_mut()
+            .and_then(|context| context.auction.as_mut())
+            .and_then(|auction| auction.nomination.take())
+            .ok_or_else(|| AppError::CustomError {
+                msg: "There is no player being auctioned.".to_string(),
+            })?;
+
+        Ok(())
+    }
+
+    pub fn can_update_draft_queue(&self, user_id: &str) -> Result<(), AppError> {
+        // The draft queue can be prepared before the draft and updated during the draft, by the participants only.
+        match self.status {
+            PoolState::Created | PoolState::Dynasty | PoolState::Draft => {
+                self.validate_participant(user_id)
+            }
+            _ => Err(AppError::CustomError {
+                msg: format!(
+                    "The draft queue cannot be updated when the pool status is '{}'.",
+                    self.status
+                ),
+            }),
+        }
+    }
+
+    pub fn get_next_drafter(&mut self) -> Result<String, AppError> {
+        // Return the pooler that needs to make the next pick (or the next nomination in an auction draft).
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let draft_order = self
+            .draft_order
+            .as_ref()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "draft order does not exist.".to_string(),
+            })?;
+
+        if let Some(auction) = &context.auction {
+            return match &auction.nomination {
+                Some(nomination) => Ok(nomination.highest_bid.bidder.clone()),
+                None => context.find_auction_next_nominator(draft_order, &self.settings),
+            };
+        }
+
+        if self.settings.dynasty_settings.is_some() && context.past_tradable_picks.is_some() {
+            return context.find_dynasty_next_drafter(draft_order);
+        }
+
+        Ok(context.find_next_drafter(draft_order, &self.settings))
+    }
+
+    pub fn auto_draft_player(&mut self, player: &PoolPlayerInfo) -> Result<(), AppError> {
+        // Draft a player for the pooler whose pick timer expired.
+        // In an auction draft, the open bid is closed or the player is nominated at the minimum bid.
+        self.validate_draft_not_paused()?;
+        let owner = self.owner.clone();
+
+        if matches!(self.settings.draft_type, DraftType::Auction) {
+            let is_nominated = self
+                .context
+                .as_ref()
+                .and_then(|context| context.auction.as_ref())
+                .is_some_and(|auction| auction.nomination.is_some());
+
+            if is_nominated {
+                return self.close_bid(&owner);
+            }
+
+            let min_bid = self
+                .settings
+                .auction_settings
+                .as_ref()
+                .map(|auction_settings| auction_settings.min_bid)
+                .ok_or_else(|| AppError::CustomError {
+                    msg: "Auction settings does not exist.".to_string(),
+                })?;
+
+            return self.nominate_player(&owner, player, min_bid);
+        }
+
+        self.draft_player(&owner, player)
+    }
+
+    pub fn skip_pick(&mut self) -> Result<(), AppError> {
+        // Skip the pick of the pooler whose pick timer expired.
+        self.validate_pool_status(&PoolState::Draft)?;
+        self.validate_draft_not_paused()?;
+
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let draft_order = self
+            .draft_order
+            .as_ref()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "draft order does not exist.".to_string(),
+            })?;
+
+        if context.auction.is_some() || self.settings.dynasty_settings.is_some() {
+            return Err(AppError::CustomError {
+                msg: "Picks can only be skipped in a serpentine draft.".to_string(),
+            });
+        }
+
+        if context.skip_pick(draft_order, &self.settings)? {
+            // The draft is done.
+            self.start_season()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn skip_pick_for_makeup(&mut self, user_id: &str) -> Result<(), AppError> {
+        // Skip the pick of an absent pooler, the pooler can make it up later out of turn.
+        // This call can only be made if the user id is the owner.
+        authorize(user_id, self, Permission::ManageDraft)?;
+        self.validate_pool_status(&PoolState::Draft)?;
+        self.validate_draft_not_paused()?;
+
+        if matches!(self.settings.draft_type, DraftType::Auction) {
+            return Err(AppError::CustomError {
+                msg: "Picks cannot be skipped in an auction draft.".to_string(),
+            });
+        }
+
+        let next_drafter = self.get_next_drafter()?;
+
+        let context = self.context.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "pool context does not exist.".to_string(),
+        })?;
+
+        let draft_order = self
+            .draft_order
+            .as_ref()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "draft order does not exist.".to_string(),
+            })?;
+
+        if context.skip_pick_for_makeup(&next_drafter, draft_order, &self.settings)? {
+            // The draft is done.
+            self.start_season()?;
+        }
+
+        Ok(())
+    }
+
+    fn start_season(&mut self) -> Result<(), AppError> {
+        // The draft is done, the pool is now in progress.
+        // Head-to-head pools get their weekly matchups generated.
+        if self.settings.head_to_head_settings.is_some() {
+            let participants: Vec<String> = self
+                .participants
+                .iter()
+                .map(|participant| participant.id.clone())
+                .collect();
+
+            let matchup_schedule =
+                MatchupSchedule::new(&participants, &self.season_start, &self.season_end)?;
+
+            if let Some(context) = self.context.as_mut() {
+                context.matchup_schedule = Some(matchup_schedule);
+            }
+        }
+
+        self.status = PoolState::InProgress;
+        Ok(())
+    }
+
+    pub fn update_dynasty_participants(
+        &mut self,
+        added_participants: &[PoolUser],
+        removed_participants: &[String],
+    ) -> Result<(), AppError> {
+        // Expand or contract a dynasty league before its protection and its draft.
+        self.validate_pool_status(&PoolState::Dynasty)?;
+
+        for participant in removed_participants {
+            self.validate_participant(participant)?;
+
+            if *participant == self.owner {
+                return Err(AppError::CustomError {
+                    msg: "The owner of the pool cannot be removed.".to_string(),
+                });
+            }
+        }
+
+        let mut added_ids = HashSet::new();
+        for participant in added_participants {
+            if !added_ids.insert(participant.id.as_str())
+                || self
+                    .participants
+                    .iter()
+                    .any(|user| user.id == participant.id)
+            {
+                return Err(AppError::CustomError {
+                    msg: format!("User {} is already a pool participant.", participant.id),
+                });
+            }
+        }
+
+        let added_ids: Vec<String> = added_participants
+            .iter()
+            .map(|participant| participant.id.clone())
+            .collect();
+
+        self.participants
+            .retain(|participant| !removed_participants.contains(&participant.id));
+        self.participants.extend(added_participants.iter().cloned());
+
+        self.settings.number_poolers = self.participants.len() as u8;
+        self.settings
+            .assistants
+            .retain(|assistant| !removed_participants.contains(assistant));
+        self.settings.validate()?;
+
+        // The new teams pick first, the others keep their order.
+        if let Some(draft_order) = self.draft_order.as_mut() {
+            draft_order.retain(|participant| !removed_participants.contains(participant));
+            draft_order.splice(0..0, added_ids.iter().cloned());
+        }
+
+        if let Some(context) = self.context.as_mut() {
+            context.update_participants(&added_ids, removed_participants);
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_participant(&self, user_id: &str) -> Result<(), AppError> {
+        // Validate that the user is a pool participant.
+        if !self.participants.iter().any(|user| user.id == user_id) {
+            return Err(AppError::CodedError {
+                code: ErrorCode::NotAParticipant,
+                msg: format!("User {} is not a pool participant.", user_id),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_pool_status(&self, expected_status: &PoolState) -> Result<(), AppError> {
+        // Validate that the pool is in the expected status.
+        if self.status != *expected_status {
+            return Err(AppError::CodedError {
+                code: ErrorCode::InvalidPoolStatus,
+                msg: format!(
+                    "The expected pool status '{}', current pool status '{}'.",
+                    expected_status, self.status
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn is_private(&self) -> bool {
+        matches!(self.settings.visibility, Some(PoolVisibility::Private))
+    }
+
+    pub fn is_manager_of(&self, user_id: &str, participant_id: &str) -> bool {
+        // The participant itself or one of the users sharing the management of its team.
+        // An orphaned team is only managed by the owner, when the owner took it in charge.
+        match self
+            .participants
+            .iter()
+            .find(|participant| participant.id == participant_id)
+        {
+            Some(participant) if participant.is_orphaned() => {
+                participant.is_owner_managed.unwrap_or(false) && self.owner == user_id
+            }
+            Some(participant) => participant.is_managed_by(user_id),
+            None => user_id == participant_id,
+        }
+    }
+
+    pub fn get_managed_participants(&self, user_id: &str) -> Vec<String> {
+        // The teams a user can act for, the user itself is included unless its team is orphaned.
+        let mut managed_participants = Vec::new();
+
+        if self.is_manager_of(user_id, user_id) {
+            managed_participants.push(user_id.to_string());
+        }
+
+        managed_participants.extend(
+            self.participants
+                .iter()
+                .filter(|participant| {
+                    participant.id != user_id && self.is_manager_of(user_id, &participant.id)
+                })
+                .map(|participant| participant.id.clone()),
+        );
+
+        managed_participants
+    }
+
+    pub fn get_orphaned_teams(&self) -> Vec<String> {
+        self.participants
+            .iter()
+            .filter(|participant| participant.is_orphaned())
+            .map(|participant| participant.id.clone())
+            .collect()
+    }
+
+    fn manages_next_drafter(&mut self, user_id: &str) -> bool {
+        self.get_next_drafter()
+            .is_ok_and(|next_drafter| self.is_manager_of(user_id, &next_drafter))
+    }
+
+    pub fn get_participant_name(&self, user_id: &str) -> String {
+        // The team name of the participant, its user name, or its id if the user is not a participant.
+        self.participants
+            .iter()
+            .find(|participant| participant.id == user_id)
+            .map(|participant| {
+                participant
+                    .team_name
+                    .clone()
+                    .unwrap_or_else(|| participant.name.clone())
+            })
+            .unwrap_or_else(|| user_id.to_string())
+    }
+}
+
+fn is_valid_draft_order(draft_order: &[String], user_ids: &[String]) -> bool {
+    // Every pooler drafts in the order, once.
+    draft_order.len() == user_ids.len()
+        && user_ids.iter().all(|user_id| draft_order.contains(user_id))
+}
+
+fn replace_participant_id(value: &mut Value, previous_id: &str, new_id: &str) {
+    // Rewrite the participant id in every value and map key of a serialized pool.
+    match value {
+        Value::String(id) if id == previous_id => *id = new_id.to_string(),
+        Value::Array(values) => {
+            for value in values {
+                replace_participant_id(value, previous_id, new_id);
+            }
+        }
+        Value::Object(map) => {
+            if let Some(previous_value) = map.remove(previous_id) {
+                map.insert(new_id.to_string(), previous_value);
+            }
+            for value in map.values_mut() {
+                replace_participant_id(value, previous_id, new_id);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, ToSchema)]
+pub enum PoolState {
+    Final,
+    InProgress,
+    Dynasty,
+    Draft,
+    Created,
+}
+
+impl fmt::Display for PoolState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // To be able to print out the PoolState enum.
+        match self {
+            PoolState::Final => write!(f, "Final"),
+            PoolState::InProgress => write!(f, "In progress"),
+            PoolState::Dynasty => write!(f, "Dynasty"),
+            PoolState::Draft => write!(f, "Draft"),
+            PoolState::Created => write!(f, "Created"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)] // Copy
+pub struct PoolContext {
+    pub pooler_roster: HashMap<String, PoolerRoster>,
+    pub players_name_drafted: Vec<u32>,
+    pub score_by_day: Option<HashMap<String, HashMap<String, DailyRosterPoints>>>,
+    pub tradable_picks: Option<Vec<HashMap<String, String>>>,
+    pub past_tradable_picks: Option<Vec<HashMap<String, String>>>,
+
+    // Picks of the seasons after the next one, the first element is the season after the next one.
+    // They become the tradable picks once the next season is drafted.
+    pub future_tradable_picks: Option<Vec<Vec<HashMap<String, String>>>>,
+
+    pub protected_players: Option<HashMap<String, Vec<u32>>>,
+    pub players: HashMap<String, PoolPlayerInfo>,
+
+    // Conditional picks traded this season, resolved into the tradable picks when the pool is marked as final.
+    pub conditional_picks: Option<Vec<ConditionalPick>>,
+
+    // Only filled when the pool is drafted with an auction.
+    pub auction: Option<AuctionContext>,
+
+    // Only filled for head-to-head pools.
+    pub matchup_schedule: Option<MatchupSchedule>,
+
+    // Every player drafted with its drafter and pick timestamp, in pick order.
+    pub draft_picks: Option<Vec<DraftPick>>,
+
+    // Owner of the current draft picks traded during a dynasty draft (overall pick number -> owner).
+    pub current_draft_pick_owners: Option<HashMap<String, String>>,
+
+    // Only filled for salary cap pools, cap usage of each pooler by day (date -> pooler -> cap usage).
+    pub cap_by_day: Option<HashMap<String, HashMap<String, f64>>>,
+
+    // Cumulative points of each pooler, updated every time a day is cumulated (pooler -> totals).
+    // None until they are rebuilt from the score_by_day member.
+    pub cumulative_totals: Option<HashMap<String, PoolerTotals>>,
+
+    // Season totals of each pooled player, maintained the same way (player id -> totals).
+    pub players_totals: Option<HashMap<String, PlayerTotals>>,
+
+    // Players that the poolers are willing to trade (pooler -> listings).
+    pub trade_block: Option<HashMap<String, Vec<TradeBlockListing>>>,
+
+    // Picks skipped by the owner while their drafter was absent, the oldest first.
+    pub makeup_picks: Option<Vec<MakeupPick>>,
+
+    // Players acquired by each pooler during the current week and the season (pooler -> count).
+    // The weekly counts are reset every week by the scheduler.
+    pub weekly_acquisitions: Option<HashMap<String, u16>>,
+    pub season_acquisitions: Option<HashMap<String, u16>>,
+
+    // Poolers whose starting roster went over the salary cap after a contracts sync.
+    // A pooler is removed once its roster is modified under the salary cap.
+    pub cap_invalid_participants: Option<Vec<String>>,
+}
+
+impl PoolContext {
+    pub fn new(participants: &[String]) -> Self {
+        let mut pooler_roster = HashMap::new();
+
+        // Initialize all participants roster object.
+        for participant in participants.iter() {
+            pooler_roster.insert(participant.to_string(), PoolerRoster::new());
+        }
+
+        Self {
+            pooler_roster,
+            score_by_day: Some(HashMap::new()),
+            tradable_picks: Some(Vec::new()),
+            past_tradable_picks: Some(Vec::new()),
+            future_tradable_picks: None,
+            players_name_drafted: Vec::new(),
+            protected_players: None,
+            players: HashMap::new(),
+            conditional_picks: None,
+            auction: None,
+            matchup_schedule: None,
+            draft_picks: Some(Vec::new()),
+            current_draft_pick_owners: None,
+            cap_by_day: None,
+            cumulative_totals: Some(HashMap::new()),
+            players_totals: Some(HashMap::new()),
+            trade_block: None,
+            makeup_picks: None,
+            weekly_acquisitions: None,
+            season_acquisitions: None,
+            cap_invalid_participants: None,
+        }
+    }
+
+    pub fn get_cap_invalid_participants(&self, settings: &PoolSettings) -> Vec<String> {
+        // The poolers over the salary cap or starting a player without contract.
+        let Some(salary_cap) = settings.salary_cap else {
+            return Vec::new();
+        };
+
+        let mut participants: Vec<String> = self
+            .pooler_roster
+            .iter()
+            .filter(|(_, roster)| {
+                self.calculate_cumulated_salary_cap(roster, &self.players)
+                    .map_or(true, |cap_usage| cap_usage > salary_cap)
+            })
+            .map(|(participant, _)| participant.clone())
+            .collect();
+        participants.sort();
+        participants
+    }
+
+    pub fn get_weekly_acquisitions(&self, participant: &str) -> u16 {
+        self.weekly_acquisitions
+            .as_ref()
+            .and_then(|weekly_acquisitions| weekly_acquisitions.get(participant))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn get_season_acquisitions(&self, participant: &str) -> u16 {
+        self.season_acquisitions
+            .as_ref()
+            .and_then(|season_acquisitions| season_acquisitions.get(participant))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn validate_acquisition(
+        &self,
+        participant: &str,
+        settings: &PoolSettings,
+    ) -> Result<(), AppError> {
+        // Validate that the pooler did not reach its acquisition limits.
+        let Some(acquisition_limits) = &settings.acquisition_limits else {
+            return Ok(());
+        };
+
+        if acquisition_limits
+            .max_per_week
+            .is_some_and(|max_per_week| self.get_weekly_acquisitions(participant) >= max_per_week)
+        {
+            return Err(AppError::CustomError {
+                msg: "The pooler reached the maximum number of acquisitions this week.".to_string(),
+            });
+        }
+
+        if acquisition_limits
+            .max_per_season
+            .is_some_and(|max_per_season| {
+                self.get_season_acquisitions(participant) >= max_per_season
+            })
+        {
+            return Err(AppError::CustomError {
+                msg: "The pooler reached the maximum number of acquisitions this season."
+                    .to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn record_acquisition(&mut self, participant: &str) {
+        for acquisitions in [&mut self.weekly_acquisitions, &mut self.season_acquisitions] {
+            *acquisitions
+                .get_or_insert_with(HashMap::new)
+                .entry(participant.to_string())
+                .or_insert(0) += 1;
+        }
+    }
+
+    pub fn get_trade_block(&self) -> HashMap<String, Vec<TradeBlockListing>> {
+        // The listings of the players that were traded or released since they were listed are ignored.
+        self.trade_block
+            .iter()
+            .flatten()
+            .filter_map(|(participant, listings)| {
+                let roster = self.pooler_roster.get(participant)?;
+                let listings: Vec<TradeBlockListing> = listings
+                    .iter()
+                    .filter(|listing| roster.validate_player_possession(listing.player_id))
+                    .cloned()
+                    .collect();
+
+                (!listings.is_empty()).then(|| (participant.clone(), listings))
+            })
+            .collect()
+    }
+
+    pub fn get_dates_to_cumulate(&self, today: &str) -> Vec<String> {
+        // Return the past dates where at least one pooler daily points were not cumulated yet.
+        let Some(score_by_day) = &self.score_by_day else {
+            return Vec::new();
+        };
+
+        score_by_day
+            .iter()
+            .filter(|(date, daily_roster_points)| {
+                date.as_str() < today
+                    && daily_roster_points
+                        .values()
+                        .any(|roster_daily_points| !roster_daily_points.is_cumulated)
+            })
+            .map(|(date, _)| date.clone())
+            .collect()
+    }
+
+    pub fn cumulate_daily_roster_points(
+        &mut self,
+        date: &str,
+        daily_players_points: &DailyPlayersPoints,
+        pool_settings: &PoolSettings,
+    ) {
+        // Cumulate the points of every pooler for a given date.
+        // The roster recorded for that date is kept if it exists,
+        // otherwise the current starting lineup of the pooler is used.
+        let remaining_games: HashMap<String, GamesPlayedLimits> =
+            match &pool_settings.games_played_limits {
+                Some(games_played_limits) => self
+                    .pooler_roster
+                    .keys()
+                    .map(|participant| {
+                        let totals = self.get_totals_before(participant, date, pool_settings);
+                        (
+                            participant.clone(),
+                            games_played_limits.get_remaining_games(&totals),
+                        )
+                    })
+                    .collect(),
+                None => HashMap::new(),
+            };
+
+        let daily_roster_points = self
+            .score_by_day
+            .get_or_insert_with(HashMap::new)
+            .entry(date.to_string())
+            .or_default();
+
+        for (participant, pooler_roster) in &self.pooler_roster {
+            let roster_daily_points = daily_roster_points
+                .entry(participant.clone())
+                .or_insert_with(|| DailyRosterPoints::new(pooler_roster, &self.players));
+
+            // A date can be cumulated again, its previous points are replaced in the totals.
+            let previous_points = roster_daily_points.is_cumulated.then(|| {
+                (
+                    roster_daily_points.get_pooler_totals(pool_settings),
+                    roster_daily_points.get_players_points(pool_settings),
+                )
+            });
+
+            roster_daily_points.cumulate(
+                daily_players_points,
+                remaining_games.get(participant),
+                pool_settings,
+            );
+
+            if let Some(cumulative_totals) = self.cumulative_totals.as_mut() {
+                let pooler_totals = cumulative_totals.entry(participant.clone()).or_default();
+
+                if let Some((previous_pooler_totals, _)) = &previous_points {
+                    pooler_totals.remove(previous_pooler_totals);
+                }
+                pooler_totals.add(&roster_daily_points.get_pooler_totals(pool_settings));
+            }
+
+            if let Some(players_totals) = self.players_totals.as_mut() {
+                if let Some((_, previous_players_points)) = &previous_points {
+                    for (player_id, points) in previous_players_points {
+                        if let Some(player_totals) = players_totals.get_mut(player_id) {
+                            player_totals.remove(date, *points);
+                        }
+                    }
+                }
+
+                for (player_id, points) in roster_daily_points.get_players_points(pool_settings) {
+                    players_totals
+                        .entry(player_id)
+                        .or_default()
+                        .add(date, points);
+                }
+            }
+        }
+    }
+
+    pub fn update_live_roster_points(
+        &mut self,
+        date: &str,
+        daily_players_points: &DailyPlayersPoints,
+    ) -> bool {
+        // Fill the provisional points of the games in progress, the date stays to be cumulated.
+        // The games played limits are only applied when the date is cumulated.
+        let daily_roster_points = self
+            .score_by_day
+            .get_or_insert_with(HashMap::new)
+            .entry(date.to_string())
+            .or_default();
+
+        let mut is_updated = false;
+
+        for (participant, pooler_roster) in &self.pooler_roster {
+            let roster_daily_points = daily_roster_points
+                .entry(participant.clone())
+                .or_insert_with(|| DailyRosterPoints::new(pooler_roster, &self.players));
+
+            if roster_daily_points.is_cumulated {
+                continue;
+            }
+
+            roster_daily_points.fill_points(daily_players_points);
+            is_updated = true;
+        }
+
+        is_updated
+    }
+
+    pub fn get_live_totals(
+        &self,
+        date: &str,
+        pool_settings: &PoolSettings,
+    ) -> HashMap<String, PoolerTotals> {
+        // The cumulated totals of every pooler with the provisional points of a date.
+        let mut totals = self.cumulative_totals.clone().unwrap_or_default();
+
+        for (participant, roster_daily_points) in self
+            .score_by_day
+            .iter()
+            .flatten()
+            .filter(|(day, _)| day.as_str() == date)
+            .flat_map(|(_, daily_roster_points)| daily_roster_points)
+            .filter(|(_, roster_daily_points)| !roster_daily_points.is_cumulated)
+        {
+            totals
+                .entry(participant.clone())
+                .or_default()
+                .add(&roster_daily_points.get_pooler_totals(pool_settings));
+        }
+
+        totals
+    }
+
+    fn get_totals_before(
+        &self,
+        participant: &str,
+        date: &str,
+        pool_settings: &PoolSettings,
+    ) -> PoolerTotals {
+        // Totals of a pooler over the cumulated dates before a date.
+        let mut totals = PoolerTotals::default();
+
+        for (_, daily_roster_points) in self
+            .score_by_day
+            .iter()
+            .flatten()
+            .filter(|(day, _)| day.as_str() < date)
+        {
+            if let Some(roster_daily_points) = daily_roster_points
+                .get(participant)
+                .filter(|roster_daily_points| roster_daily_points.is_cumulated)
+            {
+                totals.add(&roster_daily_points.get_pooler_totals(pool_settings));
+            }
+        }
+
+        totals
+    }
+
+    pub fn are_totals_missing(&self) -> bool {
+        self.cumulative_totals.is_none() || self.players_totals.is_none()
+    }
+
+    pub fn rebuild_cumulative_totals(&mut self, pool_settings: &PoolSettings) {
+        // Compute the totals of every pooler and player from all the cumulated days.
+        // Needed when the totals were never computed or when the scoring settings changed.
+        let mut cumulative_totals: HashMap<String, PoolerTotals> = self
+            .pooler_roster
+            .keys()
+            .map(|participant| (participant.clone(), PoolerTotals::default()))
+            .collect();
+        let mut players_totals: HashMap<String, PlayerTotals> = HashMap::new();
+
+        for (date, daily_roster_points) in self.score_by_day.iter().flatten() {
+            for (participant, roster_daily_points) in daily_roster_points {
+                if !roster_daily_points.is_cumulated {
+                    continue;
+                }
+
+                cumulative_totals
+                    .entry(participant.clone())
+                    .or_default()
+                    .add(&roster_daily_points.get_pooler_totals(pool_settings));
+
+                for (player_id, points) in roster_daily_points.get_players_points(pool_settings) {
+                    players_totals
+                        .entry(player_id)
+                        .or_default()
+                        .add(date, points);
+                }
+            }
+        }
+
+        self.cumulative_totals = Some(cumulative_totals);
+        self.players_totals = Some(players_totals);
+    }
+
+    pub fn get_recent_players_points(
+        &self,
+        pool_settings: &PoolSettings,
+        today: NaiveDate,
+    ) -> HashMap<String, (u16, f64)> {
+        // Games and points of each player over the last RECENT_FORM_DAYS cumulated days (player id -> (games, points)).
+        let from_date = today - Duration::days(RECENT_FORM_DAYS);
+        let mut recent_points: HashMap<String, (u16, f64)> = HashMap::new();
+
+        for (date, daily_roster_points) in self.score_by_day.iter().flatten() {
+            let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+                continue;
+            };
+
+            if date < from_date || date > today {
+                continue;
+            }
+
+            for roster_daily_points in daily_roster_points.values() {
+                if !roster_daily_points.is_cumulated {
+                    continue;
+                }
+
+                for (player_id, points) in roster_daily_points.get_players_points(pool_settings) {
+                    let player_points = recent_points.entry(player_id).or_default();
+                    player_points.0 += 1;
+                    player_points.1 += points;
+                }
+            }
+        }
+
+        recent_points
+    }
+
+    pub fn get_players_stats(&self) -> Vec<PoolPlayerStats> {
+        // Season totals of every player of the pool, the best players first.
+        // The players that did not play yet are listed with 0 points.
+        let mut players_stats: Vec<PoolPlayerStats> = self
+            .players
+            .iter()
+            .map(|(player_id, player)| {
+                let totals = self
+                    .players_totals
+                    .as_ref()
+                    .and_then(|players_totals| players_totals.get(player_id))
+                    .cloned()
+                    .unwrap_or_default();
+
+                PoolPlayerStats {
+                    player: player.clone(),
+                    games_played: totals.games,
+                    total_points: totals.points,
+                    points_by_month: totals.points_by_month,
+                }
+            })
+            .collect();
+
+        players_stats.sort_by(|a, b| cmp_points(b.total_points, a.total_points));
+        players_stats
+    }
+
+    pub fn get_cumulative_standings(
+        &self,
+        participants: &[String],
+        pool_settings: &PoolSettings,
+    ) -> Vec<PoolerStanding> {
+        // Rank the poolers by their cumulative points, poolers without points are listed with 0.
+        let mut standings: Vec<PoolerStanding> = participants
+            .iter()
+            .map(|participant| {
+                let totals = self
+                    .cumulative_totals
+                    .as_ref()
+                    .and_then(|cumulative_totals| cumulative_totals.get(participant))
+                    .cloned()
+                    .unwrap_or_default();
+
+                let capped_positions = pool_settings
+                    .games_played_limits
+                    .as_ref()
+                    .map(|games_played_limits| games_played_limits.get_capped_positions(&totals))
+                    .filter(|capped_positions| !capped_positions.is_empty());
+
+                PoolerStanding::new(participant, totals, capped_positions)
+            })
+            .collect();
+
+        standings.sort_by(|a, b| cmp_points(b.total_points, a.total_points));
+        standings
+    }
+
+    pub fn update_matchup_results(&mut self, pool_settings: &PoolSettings, today: &str) -> bool {
+        // Record the results of the completed weeks. Return true if a new result was recorded.
+        let (Some(matchup_schedule), Some(score_by_day)) =
+            (self.matchup_schedule.as_mut(), self.score_by_day.as_ref())
+        else {
+            return false;
+        };
+
+        matchup_schedule.update_results(score_by_day, pool_settings, today)
+    }
+
+    pub fn get_final_rank(&self, pool_settings: &PoolSettings) -> Result<Vec<String>, AppError> {
+        // Head-to-head pools are ranked using the matchups standings.
+        if let (Some(matchup_schedule), Some(head_to_head_settings)) =
+            (&self.matchup_schedule, &pool_settings.head_to_head_settings)
+        {
+            return Ok(matchup_schedule
+                .get_standings(head_to_head_settings)
+                .into_iter()
+                .map(|standing| standing.participant)
+                .collect());
+        }
+
+        let Some(score_by_day) = &self.score_by_day else {
+            return Err(AppError::CustomError {
+                msg: "No score is being recorded in this pool yet.".to_string(),
+            });
+        };
+
+        // Map the user to its total points, total number of games
+        // and for each player type, a hashmap of the player id with their corresponding total number of points, total number of games.
+        let mut user_total_points: HashMap<
+            String,
+            (
+                f64,                         // Total points.
+                u16,                         // Total number of games.
+                HashMap<String, (f64, u16)>, // Forwards
+                HashMap<String, (f64, u16)>, // Defense
+                HashMap<String, (f64, u16)>, // Goalies
+            ),
+        > = HashMap::new();
+
+        for (date, daily_roster_points) in score_by_day {
+            for (participant, roster_daily_points) in daily_roster_points {
+                // Initialize the participant with 0 points and 0 games and no players.
+                if !user_total_points.contains_key(participant) {
+                    user_total_points.insert(
+                        participant.clone(),
+                        (0.0, 0, HashMap::new(), HashMap::new(), HashMap::new()),
+                    );
+                }
+
+                // Return an error if at least one day have not been cumulated yet.
+                if !roster_daily_points.is_cumulated {
+                    return Err(AppError::CustomError {
+                        msg: format!(
+                            "There are no cumulative data on the {date} for the user {participant}"
+                        ),
+                    });
+                }
+
+                if let Some((
+                    total_points,
+                    number_of_games,
+                    forwards_points,
+                    defenders_points,
+                    goalies_points,
+                )) = user_total_points.get_mut(participant)
+                {
+                    let (daily_points, daily_games) = roster_daily_points.get_total_points(
+                        pool_settings,
+                        forwards_points,
+                        defenders_points,
+                        goalies_points,
+                    );
+
+                    *total_points += daily_points;
+                    *number_of_games += daily_games;
+                }
+            }
+        }
+
+        // Convert the HashMap into a Vec of tuples
+        if let Some(ignore_x_worst_players) = &pool_settings.ignore_x_worst_players {
+            for (
+                total_points,
+                total_number_of_games,
+                forwards_points,
+                defenders_points,
+                goalies_points,
+            ) in user_total_points.values_mut()
+            {
+                // Find the x worst forwards that points should be ignored.
+                let mut forwards_vec: Vec<(&String, &(f64, u16))> =
+                    forwards_points.iter().collect();
+
+                // Sort the vector by total points in ascending order
+                forwards_vec
+                    .sort_by(|a, b| cmp_points(a.1 .0, b.1 .0).then_with(|| a.1 .1.cmp(&b.1 .1)));
+
+                // Take the first x elements
+                let least_points_players = forwards_vec
+                    .iter()
+                    .take(ignore_x_worst_players.forwards as usize);
+
+                // Print the players with the least total points
+                for (_, (points, number_of_games)) in least_points_players {
+                    *total_points -= points;
+                    *total_number_of_games -= number_of_games;
+                }
+
+                // Find the x worst defenders that points should be ignored.
+                let mut defenders_vec: Vec<(&String, &(f64, u16))> =
+                    defenders_points.iter().collect();
+
+                // Sort the vector by total points in ascending order
+                defenders_vec
+                    .sort_by(|a, b| cmp_points(a.1 .0, b.1 .0).then_with(|| a.1 .1.cmp(&b.1 .1)));
+
+                // Take the first x elements
+                let least_points_players = defenders_vec
+                    .iter()
+                    .take(ignore_x_worst_players.defense as usize);
+
+                // Print the players with the least total points
+                for (_, (points, number_of_games)) in least_points_players {
+                    *total_points -= points;
+                    *total_number_of_games -= number_of_games;
+                }
+
+                // Find the x worst goalies that points should be ignored.
+                let mut goalies_vec: Vec<(&String, &(f64, u16))> = goalies_points.iter().collect();
+
+                // Sort the vector by total points in ascending order
+                goalies_vec
+                    .sort_by(|a, b| cmp_points(a.1 .0, b.1 .0).then_with(|| a.1 .1.cmp(&b.1 .1)));
+
+                // Take the first x elements
+                let least_points_players = goalies_vec
+                    .iter()
+                    .take(ignore_x_worst_players.goalies as usize);
+
+                // Print the players with the least total points
+                for (_, (points, number_of_games)) in least_points_players {
+                    *total_points -= points;
+                    *total_number_of_games -= number_of_games;
+                }
+            }
+        }
+
+        let mut user_points_vec: Vec<(
+            &String,
+            &(
+                f64,
+                u16,
+                HashMap<String, (f64, u16)>,
+                HashMap<String, (f64, u16)>,
+                HashMap<String, (f64, u16)>,
+            ),
+        )> = user_total_points.iter().collect();
+
+        // Sort the total points vector. And fill the final_rank list with it.
+        // Sort the vector by total points and then by total games in descending order
+        user_points_vec.sort_by(|a, b| {
+            cmp_points(b.1 .0, a.1 .0) // Compare total points
+                .then_with(|| a.1 .1.cmp(&b.1 .1)) // If points are equal, compare total games (The pooler with less games wins)
+        });
+
+        let mut final_rank = Vec::new();
+        for participant in user_points_vec {
+            final_rank.push(participant.0.clone())
+        }
+
+        Ok(final_rank)
+    }
+
+    pub fn calculate_cumulated_salary_cap(
+        &self,
+        pooler_roster: &PoolerRoster,
+        players: &HashMap<String, PoolPlayerInfo>,
+    ) -> Result<f64, AppError> {
+        // The salary retained by the other poolers is not counted for the players of the roster,
+        // while the salary retained by this pooler on traded players (dead cap) is.
+        let retained_salaries = self.get_retained_salaries();
+
+        let cumulated_salary_cap = pooler_roster
+            .get_starting_players()
+            .map(|player_id| {
+                players
+                    .get(&player_id.to_string())
+                    .ok_or_else(|| AppError::CustomError {
+                        msg: "Player does not exist.".to_string(),
+                    })
+                    .and_then(|player| {
+                        player
+                            .salary_cap
+                            .map(|salary_cap| {
+                                salary_cap - retained_salaries.get(player_id).unwrap_or(&0.0)
+                            })
+                            .ok_or_else(|| AppError::CustomError {
+                                msg: "Player salary cap not available.".to_string(),
+                            })
+                    })
+            })
+            .try_fold(0.0, |acc, salary_cap| salary_cap.map(|sc| acc + sc));
+
+        let dead_cap: f64 = pooler_roster
+            .retained_salaries
+            .iter()
+            .flat_map(|retained_salaries| retained_salaries.values())
+            .sum();
+
+        cumulated_salary_cap.map(|salary_cap| salary_cap + dead_cap)
+    }
+
+    pub fn record_cap_snapshot(&mut self, date: &str, settings: &PoolSettings) -> bool {
+        // Record the cap usage of every pooler for the date, only for salary cap pools.
+        // Returns true if the snapshot was recorded.
+        if settings.salary_cap.is_none() {
+            return false;
+        }
+
+        let mut daily_cap = HashMap::new();
+
+        for (participant, roster) in &self.pooler_roster {
+            match self.calculate_cumulated_salary_cap(roster, &self.players) {
+                Ok(cap_usage) => {
+                    daily_cap.insert(participant.clone(), cap_usage);
+                }
+                Err(e) => warn!(%participant, "Could not compute the cap usage: {}", e),
+            }
+        }
+
+        self.cap_by_day
+            .get_or_insert_with(HashMap::new)
+            .insert(date.to_string(), daily_cap);
+        true
+    }
+
+    pub fn get_cap_report(&self, settings: &PoolSettings) -> Result<CapReport, AppError> {
+        // Cap usage of every pooler over time, the days over the salary cap are flagged.
+        let salary_cap = settings.salary_cap.ok_or_else(|| AppError::CustomError {
+            msg: "This pool does not have a salary cap.".to_string(),
+        })?;
+
+        let mut dates: Vec<&String> = self.cap_by_day.iter().flat_map(|cap| cap.keys()).collect();
+        dates.sort();
+
+        let mut teams = Vec::new();
+
+        for (participant, roster) in &self.pooler_roster {
+            let mut cap_by_day = Vec::new();
+            let mut violations = Vec::new();
+
+            for date in &dates {
+                let Some(cap_usage) = self
+                    .cap_by_day
+                    .as_ref()
+                    .and_then(|cap| cap.get(*date))
+                    .and_then(|daily_cap| daily_cap.get(participant))
+                else {
+                    continue;
+                };
+
+                if *cap_usage > salary_cap {
+                    violations.push(date.to_string());
+                }
+
+                cap_by_day.push(DailyCapUsage {
+                    date: date.to_string(),
+                    cap_usage: *cap_usage,
+                });
+            }
+
+            teams.push(TeamCapReport {
+                participant: participant.clone(),
+                current_cap_usage: self
+                    .calculate_cumulated_salary_cap(roster, &self.players)
+                    .ok(),
+                dead_cap: self.get_dead_cap(participant),
+                cap_by_day,
+                violations,
+            });
+        }
+
+        Ok(CapReport { salary_cap, teams })
+    }
+
+    pub fn get_retained_salaries(&self) -> HashMap<u32, f64> {
+        // Total salary retained in trades for each player, across every pooler.
+        let mut retained_salaries = HashMap::new();
+
+        for roster in self.pooler_roster.values() {
+            for (player_id, amount) in roster.retained_salaries.iter().flatten() {
+                if let Ok(player_id) = player_id.parse::<u32>() {
+                    *retained_salaries.entry(player_id).or_insert(0.0) += amount;
+                }
+            }
+        }
+
+        retained_salaries
+    }
+
+    pub fn get_dead_cap(&self, user_id: &str) -> f64 {
+        // Salary retained by a pooler on the players it traded.
+        self.pooler_roster
+            .get(user_id)
+            .and_then(|roster| roster.retained_salaries.as_ref())
+            .map(|retained_salaries| retained_salaries.values().sum())
+            .unwrap_or(0.0)
+    }
+
+    pub fn can_add_player_to_roster(
+        &self,
+        player: &PoolPlayerInfo,
+        pool_user_id: &str,
+        settings: &PoolSettings,
+    ) -> Result<bool, AppError> {
+        // If there is salary cap management, don't add to the starting roster players without contracts or if the user doesn't have enough space.
+        if let Some(team_salary_cap) = settings.salary_cap {
+            let pooler_roster =
+                self.pooler_roster
+                    .get(pool_user_id)
+                    .ok_or_else(|| AppError::CustomError {
+                        msg: "Pooler roster does not exist.".to_string(),
+                    })?;
+
+            let cumulated_salary_cap =
+                self.calculate_cumulated_salary_cap(pooler_roster, &self.players)?;
+
+            if let Some(player_salary_cap) = player.salary_cap {
+                if cumulated_salary_cap + player_salary_cap <= team_salary_cap {
+                    return Ok(true);
+                }
+                return Ok(false);
+            }
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    pub fn fill_empty_spots(
+        &mut self,
+        pool_user_id: &str,
+        settings: &PoolSettings,
+    ) -> Result<bool, AppError> {
+        // Promote the reservists into the empty starting spots of their position, in the reservists order.
+        // The reservists that would exceed the salary cap stay in the reservists.
+        let reservists = self
+            .pooler_roster
+            .get(pool_user_id)
+            .ok_or_else(|| AppError::CustomError {
+                msg: "Pooler roster does not exist.".to_string(),
+            })?
+            .chosen_reservists
+            .clone();
+
+        let mut is_filled = false;
+
+        for player_id in reservists {
+            let Some(player) = self.players.get(&player_id.to_string()) else {
+                continue;
+            };
+
+            if !self.can_add_player_to_roster(player, pool_user_id, settings)? {
+                continue;
+            }
+
+            let Some(pooler_roster) = self.pooler_roster.get_mut(pool_user_id) else {
+                continue;
+            };
+
+            if pooler_roster.add_starting_player(player, settings) {
+                pooler_roster
+                    .chosen_reservists
+                    .retain(|reservist_id| *reservist_id != player_id);
+                is_filled = true;
+            }
+        }
+
+        Ok(is_filled)
+    }
+
+    pub fn add_drafted_player(
+        &mut self,
+        player: &PoolPlayerInfo,
+        next_drafter: &str,
+        settings: &PoolSettings,
+    ) -> Result<(), AppError> {
+        // Then, Add the chosen player in its right spot.
+        // When there is no place in the position of the player we will add it to the reservists.
+
+        let can_add_player_to_roster =
+            self.can_add_player_to_roster(player, next_drafter, settings)?;
+
+        if let Some(pooler_roster) = self.pooler_roster.get_mut(next_drafter) {
+            let mut is_added = false;
+            if can_add_player_to_roster {
+                is_added = pooler_roster.add_starting_player(player, settings);
+            }
+
+            // If the there is not enough place in the roster, try to add the player in the reservists.
+            if !is_added {
+                // Return an error when the player could not be added.
+                if settings.number_reservists == 0 {
+                    return Err(AppError::CustomError {
+                        msg: format!("There is no space for {} in the roster.", player.name),
+                    });
+                }
+                pooler_roster.chosen_reservists.push(player.id);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn resolve_conditional_picks(&mut self, final_rank: &[String]) {
+        // The owner of a conditional pick whose condition is met exchanges it for the pick of the upgraded round,
+        // the owner of the upgraded pick receives the conditional pick instead.
+        for conditional_pick in self.conditional_picks.take().into_iter().flatten() {
+            let Some(rank) = final_rank
+                .iter()
+                .position(|participant| *participant == conditional_pick.pick.from)
+            else {
+                continue;
+            };
+
+            if !conditional_pick
+                .condition
+                .is_met(rank + 1, final_rank.len())
+            {
+                continue;
+            }
+
+            let Some(tradable_picks) = self.tradable_picks.as_mut() else {
+                continue;
+            };
+
+            let from = &conditional_pick.pick.from;
+            let round = conditional_pick.pick.round as usize;
+            let upgraded_round = conditional_pick.upgraded_round as usize;
+
+            let (Some(owner), Some(upgraded_owner)) = (
+                tradable_picks
+                    .get(round)
+                    .and_then(|picks| picks.get(from))
+                    .cloned(),
+                tradable_picks
+                    .get(upgraded_round)
+                    .and_then(|picks| picks.get(from))
+                    .cloned(),
+            ) else {
+                continue;
+            };
+
+            tradable_picks[round].insert(from.clone(), upgraded_owner);
+            tradable_picks[upgraded_round].insert(from.clone(), owner);
+        }
+    }
+
+    pub fn is_draft_done(&mut self, settings: &PoolSettings) -> Result<bool, AppError> {
+        // the status change to InProgress when the draft is completed.
+        // The draft is completed when all participants has a complete roster.
+
+        let mut is_done = true;
+
+        for participant in self.pooler_roster.keys() {
+            if self.get_roster_count(participant)?
+                < (settings.number_forwards
+                    + settings.number_defenders
+                    + settings.number_goalies
+                    + settings.number_reservists) as usize
+            {
+                is_done = false;
+                break; // The Draft phase is not done.
+            }
+        }
+
+        if is_done {
+            // Every roster is complete, the picks left to make up are not needed anymore.
+            self.makeup_picks = None;
+        }
+
+        // generate the list of tradable_picks for the next season, only once per season.
+
+        if is_done
+            && self
+                .tradable_picks
+                .as_ref()
+                .is_none_or(|tradable_picks| tradable_picks.is_empty())
+        {
+            self.start_tradable_seasons(settings);
+        }
+        Ok(is_done)
+    }
+
+    fn start_tradable_seasons(&mut self, settings: &PoolSettings) {
+        // The picks of the season after the next one, already traded, become the tradable picks of the next season
+        // and the picks of a new season are added at the end.
+        let Some(dynasty_settings) = &settings.dynasty_settings else {
+            self.tradable_picks = Some(Vec::new());
+            return;
+        };
+
+        let get_new_season = || -> Vec<HashMap<String, String>> {
+            (0..dynasty_settings.tradable_picks)
+                .map(|_| {
+                    self.pooler_roster
+                        .keys()
+                        .map(|participant| (participant.clone(), participant.clone()))
+                        .collect()
+                })
+                .collect()
+        };
+
+        let mut future_tradable_picks = self.future_tradable_picks.clone().unwrap_or_default();
+        let next_season = if future_tradable_picks.is_empty() {
+            get_new_season()
+        } else {
+            future_tradable_picks.remove(0)
+        };
+
+        let number_future_seasons =
+            dynasty_settings.tradable_seasons.unwrap_or(1).max(1) as usize - 1;
+        future_tradable_picks.truncate(number_future_seasons);
+        while future_tradable_picks.len() < number_future_seasons {
+            future_tradable_picks.push(get_new_season());
+        }
+
+        self.tradable_picks = Some(next_season);
+        self.future_tradable_picks =
+            (!future_tradable_picks.is_empty()).then_some(future_tradable_picks);
+    }
+
+    pub fn get_pick_owner(&self, pick: &Pick) -> Option<&String> {
+        // The current owner of a pick of the next season or of a season after.
+        let season_picks = match pick.get_season_offset() {
+            0 => self.tradable_picks.as_ref(),
+            offset => self.future_tradable_picks.as_ref()?.get(offset - 1),
+        };
+
+        season_picks?.get(pick.round as usize)?.get(&pick.from)
+    }
+
+    fn get_pick_owner_mut(&mut self, pick: &Pick) -> Option<&mut String> {
+        let season_picks = match pick.get_season_offset() {
+            0 => self.tradable_picks.as_mut(),
+            offset => self.future_tradable_picks.as_mut()?.get_mut(offset - 1),
+        };
+
+        season_picks?
+            .get_mut(pick.round as usize)?
+            .get_mut(&pick.from)
+    }
+
+    pub fn draft_player_dynasty(
+        &mut self,
+        user_id: &str,
+        player: &PoolPlayerInfo,
+        draft_order: &Vec<String>, // being used as draft order.
+        settings: &PoolSettings,
+        has_privileges: bool,
+    ) -> Result<bool, AppError> {
+        // First, validate that the player selected is not already picked by any of the other poolers.
+
+        for (id, roster) in &self.pooler_roster {
+            if roster.validate_player_possession(player.id) {
+                return Err(AppError::CustomError {
+                    msg: format!("{} is already picked by {}.", player.name, id),
+                });
+            }
+        }
+        // Find the next draft id for dynasty type pool.
+        let next_drafter = self.find_dynasty_next_drafter(draft_order)?;
+
+        if !has_privileges && next_drafter != user_id {
+            return Err(AppError::CodedError {
+                code: ErrorCode::NotYourTurn,
+                msg: format!("It is {}'s turn.", next_drafter),
+            });
+        }
+
+        // Add the drafted player if everything goes right.
+        self.add_drafted_player(player, &next_drafter, settings)?;
+        self.record_pick(player, &next_drafter);
+        self.skip_completed_dynasty_drafters(draft_order, settings)
+    }
+
+    fn skip_completed_dynasty_drafters(
+        &mut self,
+        draft_order: &Vec<String>,
+        settings: &PoolSettings,
+    ) -> Result<bool, AppError> {
+        // Get the maximum number of player a user can draft.
+        let mut continue_count = 0;
+        let max_player_count = settings.number_forwards
+            + settings.number_defenders
+            + settings.number_goalies
+            + settings.number_reservists;
+
+        // Fill the drafters that have completed with 0.
+        loop {
+            let new_next_drafter = self.find_dynasty_next_drafter(draft_order)?;
+            if self.get_roster_count(&new_next_drafter)? >= max_player_count as usize {
+                if self.is_draft_done(settings)? {
+                    return Ok(true);
+                }
+                self.players_name_drafted.push(0); // Id 0 means the players did not draft because his roster is already full
+
+                continue_count += 1;
+
+                if continue_count >= draft_order.len() {
+                    break;
+                }
+                continue;
+            }
+            break;
+        }
+
+        self.is_draft_done(settings)
+    }
+
+    pub fn find_dynasty_next_drafter(
+        &mut self,
+        draft_order: &Vec<String>, // being used as draft order.
+    ) -> Result<String, AppError> {
+        // Draft the right player in dynasty mode.
+        // A pick skipped for makeup keeps its place (id 0) and is made up in place,
+        // so the owners of the next picks are not shifted by the makeup queue.
+        self.find_dynasty_pick_owner(self.players_name_drafted.len(), draft_order)
+    }
+
+    pub fn find_dynasty_pick_owner(
+        &self,
+        pick_number: usize,
+        draft_order: &[String], // being used as draft order.
+    ) -> Result<String, AppError> {
+        // Return the owner of a pick in dynasty mode.
+        // The picks traded during the live draft (current_draft_pick_owners) take precedence,
+        // then the trade that have been traded during last season (past_tradable_picks).
+
+        if let Some(owner) = self
+            .current_draft_pick_owners
+            .as_ref()
+            .and_then(|owners| owners.get(&pick_number.to_string()))
+        {
+            return Ok(owner.clone());
+        }
+
+        let past_tradable_picks =
+            self.past_tradable_picks
+                .as_ref()
+                .ok_or_else(|| AppError::CustomError {
+                    msg: "Pool context does not exist.".to_string(),
+                })?;
+
+        let index_draft = pick_number % draft_order.len();
+        // Fetch the drafter without considering if the trade has been traded yet.
+        let mut owner = &draft_order[index_draft];
+
+        if pick_number < (past_tradable_picks.len() * draft_order.len()) {
+            // use the tradable_picks to see if the pick got traded so it is to the person owning the pick to draft.
+
+            owner = &past_tradable_picks[pick_number / draft_order.len()][owner];
+        }
+
+        Ok(owner.clone())
+    }
+
+    pub fn trade_draft_picks(
+        &mut self,
+        from_user_id: &str,
+        to_user_id: &str,
+        from_picks: &[usize],
+        to_picks: &[usize],
+        draft_order: &[String],
+        settings: &PoolSettings,
+    ) -> Result<(), AppError> {
+        // Trade picks of the current dynasty draft between two poolers.
+        // Only the picks that were not made yet can be traded.
+        if from_user_id == to_user_id {
+            return Err(AppError::CustomError {
+                msg: "A pooler cannot trade picks with himself.".to_string(),
+            });
+        }
+
+        if from_picks.is_empty() && to_picks.is_empty() {
+            return Err(AppError::CustomError {
+                msg: "The trade needs to include at least one pick.".to_string(),
+            });
+        }
+
+        let total_picks = draft_order.len()
+            * (settings.number_forwards
+                + settings.number_defenders
+                + settings.number_goalies
+                + settings.number_reservists) as usize;
+
+        for (picks, owner) in [(from_picks, from_user_id), (to_picks, to_user_id)] {
+            for pick_number in picks {
+                if *pick_number < self.players_name_drafted.len() || *pick_number >= total_picks {
+                    return Err(AppError::CustomError {
+                        msg: format!("The pick {} cannot be traded.", pick_number),
+                    });
+                }
+
+                if self.find_dynasty_pick_owner(*pick_number, draft_order)? != owner {
+                    return Err(AppError::CustomError {
+                        msg: format!("The pick {} is not owned by {}.", pick_number, owner),
+                    });
+                }
+            }
+        }
+
+        let owners = self
+            .current_draft_pick_owners
+            .get_or_insert_with(HashMap::new);
+
+        for pick_number in from_picks {
+            owners.insert(pick_number.to_string(), to_user_id.to_string());
+        }
+        for pick_number in to_picks {
+            owners.insert(pick_number.to_string(), from_user_id.to_string());
+        }
+
+        Ok(())
+    }
+
+    pub fn draft_player(
+        &mut self,
+        user_id: &str,
+        player: &PoolPlayerInfo,
+        draft_order: &Vec<String>, // being used as draft order.
+        settings: &PoolSettings,
+        has_privileges: bool,
+    ) -> Result<bool, AppError> {
+        // Draft the right player in normal mode.
+        // Taking only into account the draft order
+
+        for roster in self.pooler_roster.values() {
+            if roster.validate_player_possession(player.id) {
+                return Err(AppError::CustomError {
+                    msg: "This player is already picked.".to_string(),
+                });
+            }
+        }
+
+        // there is no final rank so this is the newly created draft logic.
+        let next_drafter = self.find_next_drafter(draft_order, settings);
+
+        if !has_privileges && next_drafter != user_id {
+            return Err(AppError::CodedError {
+                code: ErrorCode::NotYourTurn,
+                msg: format!("It is {}'s turn.", next_drafter),
+            });
+        }
+
+        // Add the drafted player if everything goes right.
+        self.add_drafted_player(player, &next_drafter, settings)?;
+        self.record_pick(player, &next_drafter);
+        self.skip_completed_drafters(draft_order, settings)
+    }
+
+    fn record_pick(&mut self, player: &PoolPlayerInfo, drafter: &str) {
+        self.players_name_drafted.push(player.id);
+        self.record_draft_pick(player, drafter);
+    }
+
+    fn record_draft_pick(&mut self, player: &PoolPlayerInfo, drafter: &str) {
+        // Keep the drafted player in the pool players list and timestamp the pick.
+        self.players.insert(player.id.to_string(), player.clone());
+        self.draft_picks
+            .get_or_insert_with(Vec::new)
+            .push(DraftPick {
+                player_id: player.id,
+                drafter: drafter.to_string(),
+                date_drafted: Utc::now().timestamp_millis(),
+            });
+    }
+
+    pub fn get_starters_games(&self, teams_playing: &HashSet<u32>) -> HashMap<String, u8> {
+        // The number of starters of each pooler whose team plays.
+        self.pooler_roster
+            .iter()
+            .map(|(participant, roster)| {
+                let starters_playing = roster
+                    .get_starting_players()
+                    .filter(|player_id| {
+                        self.players
+                            .get(&player_id.to_string())
+                            .and_then(|player| player.team)
+                            .is_some_and(|team| teams_playing.contains(&team))
+                    })
+                    .count();
+
+                (participant.clone(), starters_playing as u8)
+            })
+            .collect()
+    }
+
+    pub fn get_owned_players(&self) -> HashSet<u32> {
+        // The pool players currently possessed by a pooler, the released players are free agents.
+        self.pooler_roster
+            .values()
+            .flat_map(|roster| roster.get_players())
+            .filter(|player_id| self.players.contains_key(&player_id.to_string()))
+            .copied()
+            .collect()
+    }
+
+    pub fn get_draft_recap(&self, draft_order: &[String]) -> Vec<DraftRecapPick> {
+        // Return every pick made with its round and overall number.
+        // The skipped picks (id 0) keep their overall number but are not part of the recap.
+        let mut recap = Vec::new();
+
+        for (index, player_id) in self.players_name_drafted.iter().enumerate() {
+            if *player_id == 0 {
+                continue;
+            }
+
+            let draft_pick = self.draft_picks.as_ref().and_then(|draft_picks| {
+                draft_picks.iter().find(|pick| pick.player_id == *player_id)
+            });
+
+            // The pools drafted before the picks were recorded fall back on the roster owning the player.
+            let drafter = match draft_pick {
+                Some(draft_pick) => Some(draft_pick.drafter.clone()),
+                None => self
+                    .pooler_roster
+                    .iter()
+                    .find(|(_, roster)| roster.validate_player_possession(*player_id))
+                    .map(|(participant, _)| participant.clone()),
+            };
+
+            recap.push(DraftRecapPick {
+                round: if draft_order.is_empty() {
+                    1
+                } else {
+                    index / draft_order.len() + 1
+                },
+                overall: index + 1,
+                drafter,
+                player: self.players.get(&player_id.to_string()).cloned(),
+                date_drafted: draft_pick.map(|pick| pick.date_drafted),
+            });
+        }
+
+        recap
+    }
+
+    pub fn find_first_available_player(
+        &self,
+        players: &[PoolPlayerInfo],
+    ) -> Option<PoolPlayerInfo> {
+        // Return the first player of the list that is not picked by any of the poolers.
+        players
+            .iter()
+            .find(|player| {
+                !self
+                    .pooler_roster
+                    .values()
+                    .any(|roster| roster.validate_player_possession(player.id))
+            })
+            .cloned()
+    }
+
+    pub fn find_next_drafter(&self, draft_order: &[String], settings: &PoolSettings) -> String {
+        // Find the next drafter in normal mode, the rounds are ordered by the draft order strategy.
+        let index = settings
+            .get_draft_order_strategy()
+            .get_drafter_index(self.players_name_drafted.len(), draft_order.len());
+
+        draft_order[index].clone()
+    }
+
+    pub fn skip_pick(
+        &mut self,
+        draft_order: &[String],
+        settings: &PoolSettings,
+    ) -> Result<bool, AppError> {
+        // Skip the current pick in normal mode. The pooler will be able to draft at the end of the draft.
+        self.players_name_drafted.push(0);
+        self.skip_completed_drafters(draft_order, settings)
+    }
+
+    pub fn skip_pick_for_makeup(
+        &mut self,
+        drafter: &str,
+        draft_order: &Vec<String>,
+        settings: &PoolSettings,
+    ) -> Result<bool, AppError> {
+        // Skip the current pick, it stays empty until its drafter makes it up out of turn.
+        self.makeup_picks
+            .get_or_insert_with(Vec::new)
+            .push(MakeupPick {
+                pick_number: self.players_name_drafted.len(),
+                drafter: drafter.to_string(),
+                date_skipped: Utc::now().timestamp_millis(),
+            });
+        self.players_name_drafted.push(0);
+
+        if settings.dynasty_settings.is_some() && self.past_tradable_picks.is_some() {
+            self.skip_completed_dynasty_drafters(draft_order, settings)
+        } else {
+            self.skip_completed_drafters(draft_order, settings)
+        }
+    }
+
+    pub fn has_makeup_pick(&self, drafter: &str) -> bool {
+        self.makeup_picks
+            .iter()
+            .flatten()
+            .any(|makeup_pick| makeup_pick.drafter == drafter)
+    }
+
+    pub fn make_up_pick(
+        &mut self,
+        drafter: &str,
+        player: &PoolPlayerInfo,
+        settings: &PoolSettings,
+    ) -> Result<bool, AppError> {
+        // Draft a player in the oldest pick skipped of the drafter.
+        // The skipped pick keeps its overall number so the order of the next picks does not change.
+        for roster in self.pooler_roster.values() {
+            if roster.validate_player_possession(player.id) {
+                return Err(AppError::CustomError {
+                    msg: "This player is already picked.".to_string(),
+                });
+            }
+        }
+
+        if self.get_roster_count(drafter)? >= settings.get_roster_size() as usize {
+            return Err(AppError::CustomError {
+                msg: format!("The roster of {} is already complete.", drafter),
+            });
+        }
+
+        let makeup_picks = self
+            .makeup_picks
+            .as_mut()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "There is no pick to make up.".to_string(),
+            })?;
+
+        let index = makeup_picks
+            .iter()
+            .position(|makeup_pick| makeup_pick.drafter == drafter)
+            .ok_or_else(|| AppError::CustomError {
+                msg: format!("{} has no pick to make up.", drafter),
+            })?;
+        let makeup_pick = makeup_picks.remove(index);
+
+        self.add_drafted_player(player, drafter, settings)?;
+        self.players_name_drafted[makeup_pick.pick_number] = player.id;
+        self.record_draft_pick(player, drafter);
+
+        self.is_draft_done(settings)
+    }
+
+    pub fn drop_unwound_makeup_picks(&mut self) {
+        // The makeup picks of the skipped picks that were undone or rolled back are dropped.
+        let number_picks = self.players_name_drafted.len();
+
+        if let Some(makeup_picks) = &mut self.makeup_picks {
+            makeup_picks.retain(|makeup_pick| makeup_pick.pick_number < number_picks);
+        }
+    }
+
+    fn skip_completed_drafters(
+        &mut self,
+        draft_order: &[String],
+        settings: &PoolSettings,
+    ) -> Result<bool, AppError> {
+        // Once some picks were skipped, the draft continues after the last round.
+        // The drafters that have completed their roster are filled with 0.
+        let max_player_count = (settings.number_forwards
+            + settings.number_defenders
+            + settings.number_goalies
+            + settings.number_reservists) as usize;
+
+        if self.is_draft_done(settings)? {
+            return Ok(true);
+        }
+
+        for _ in 0..draft_order.len() {
+            let next_drafter = self.find_next_drafter(draft_order, settings);
+            if self.get_roster_count(&next_drafter)? < max_player_count {
+                break;
+            }
+            self.players_name_drafted.push(0); // Id 0 means the players did not draft because his roster is already full
+        }
+
+        Ok(false)
+    }
+
+    pub fn find_auction_next_nominator(
+        &self,
+        draft_order: &[String], // being used as nomination order.
+        settings: &PoolSettings,
+    ) -> Result<String, AppError> {
+        // The nomination rotates through the draft order.
+        // The poolers that already have a complete roster are skipped.
+        let max_player_count = (settings.number_forwards
+            + settings.number_defenders
+            + settings.number_goalies
+            + settings.number_reservists) as usize;
+
+        let nb_players_drafted = self.players_name_drafted.len();
+
+        for offset in 0..draft_order.len() {
+            let nominator = &draft_order[(nb_players_drafted + offset) % draft_order.len()];
+            if self.get_roster_count(nominator)? < max_player_count {
+                return Ok(nominator.clone());
+            }
+        }
+
+        Err(AppError::CustomError {
+            msg: "All the poolers have a complete roster.".to_string(),
+        })
+    }
+
+    pub fn validate_auction_bid(
+        &self,
+        bidder_id: &str,
+        amount: u32,
+        player: &PoolPlayerInfo,
+        settings: &PoolSettings,
+    ) -> Result<(), AppError> {
+        // Validate that the bidder can afford the bid while still being able to fill the rest of its roster,
+        // and that the player fits in its roster so the bid can be closed.
+        let auction_settings =
+            settings
+                .auction_settings
+                .as_ref()
+                .ok_or_else(|| AppError::CustomError {
+                    msg: "Auction settings does not exist.".to_string(),
+                })?;
+
+        let auction = self.auction.as_ref().ok_or_else(|| AppError::CustomError {
+            msg: "The auction has not been started.".to_string(),
+        })?;
+
+        let remaining_budget =
+            auction
+                .remaining_budgets
+                .get(bidder_id)
+                .ok_or_else(|| AppError::CustomError {
+                    msg: format!("{} does not have an auction budget.", bidder_id),
+                })?;
+
+        let max_player_count = (settings.number_forwards
+            + settings.number_defenders
+            + settings.number_goalies
+            + settings.number_reservists) as u32;
+
+        let roster_count = self.get_roster_count(bidder_id)? as u32;
+
+        if roster_count >= max_player_count {
+            return Err(AppError::CustomError {
+                msg: format!("{} already has a complete roster.", bidder_id),
+            });
+        }
+
+        if amount < auction_settings.min_bid {
+            return Err(AppError::CustomError {
+                msg: format!("The minimum bid is {}.", auction_settings.min_bid),
+            });
+        }
+
+        // Keep the minimum bid for every other empty roster spot.
+        let empty_spots = max_player_count - roster_count;
+        let max_bid = remaining_budget
+            .saturating_sub(auction_settings.min_bid.saturating_mul(empty_spots - 1));
+
+        if amount > max_bid {
+            return Err(AppError::CustomError {
+                msg: format!("The maximum bid {} can make is {}.", bidder_id, max_bid),
+            });
+        }
+
+        self.validate_roster_spot(player, bidder_id, settings)
+    }
+
+    pub fn validate_roster_spot(
+        &self,
+        player: &PoolPlayerInfo,
+        pool_user_id: &str,
+        settings: &PoolSettings,
+    ) -> Result<(), AppError> {
+        // Validate that add_drafted_player would find a spot for the player, a starting spot or the reservists.
+        if settings.number_reservists > 0 {
+            return Ok(());
+        }
+
+        let mut pooler_roster = self
+            .pooler_roster
+            .get(pool_user_id)
+            .ok_or_else(|| AppError::CustomError {
+                msg: "Pooler roster does not exist.".to_string(),
+            })?
+            .clone();
+
+        if self.can_add_player_to_roster(player, pool_user_id, settings)?
+            && pooler_roster.add_starting_player(player, settings)
+        {
+            return Ok(());
+        }
+
+        Err(AppError::CustomError {
+            msg: format!("There is no space for {} in the roster.", player.name),
+        })
+    }
+
+    pub fn nominate_player(
+        &mut self,
+        user_id: &str,
+        player: &PoolPlayerInfo,
+        opening_bid: u32,
+        draft_order: &[String],
+        settings: &PoolSettings,
+        has_privileges: bool,
+    ) -> Result<(), AppError> {
+        // Nominate a player to be auctioned. The nominator opens the bid.
+        for roster in self.pooler_roster.values() {
+            if roster.validate_player_possession(player.id) {
+                return Err(AppError::CustomError {
+                    msg: "This player is already picked.".to_string(),
+                });
+            }
+        }
+
+        let next_nominator = self.find_auction_next_nominator(draft_order, settings)?;
+
+        if !has_privileges && next_nominator != user_id {
+            return Err(AppError::CodedError {
+                code: ErrorCode::NotYourTurn,
+                msg: format!("It is {}'s turn to nominate.", next_nominator),
+            });
+        }
+
+        self.validate_auction_bid(&next_nominator, opening_bid, player, settings)?;
+
+        let auction = self.auction.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "The auction has not been started.".to_string(),
+        })?;
+
+        if auction.nomination.is_some() {
+            return Err(AppError::CustomError {
+                msg: "A player is already being auctioned.".to_string(),
+            });
+        }
+
+        auction.nomination = Some(AuctionNomination {
+            player: player.clone(),
+            nominated_by: next_nominator.clone(),
+            highest_bid: AuctionBid {
+                bidder: next_nominator,
+                amount: opening_bid,
+            },
+        });
+
+        Ok(())
+    }
+
+    pub fn bid_player(
+        &mut self,
+        bidder_id: &str,
+        amount: u32,
+        settings: &PoolSettings,
+    ) -> Result<(), AppError> {
+        // Bid on the nominated player. The bid needs to be higher than the current highest bid.
+        let player = self
+            .auction
+            .as_ref()
+            .and_then(|auction| auction.nomination.as_ref())
+            .map(|nomination| nomination.player.clone())
+            .ok_or_else(|| AppError::CustomError {
+                msg: "There is no player being auctioned.".to_string(),
+            })?;
+        self.validate_auction_bid(bidder_id, amount, &player, settings)?;
+
+        let auction = self.auction.as_mut().ok_or_else(|| AppError::CustomError {
+            msg: "The auction has not been started.".to_string(),
+        })?;
+
+        let nomination = auction
+            .nomination
+            .as_mut()
+            .ok_or_else(|| AppError::CustomError {
+                msg: "There is no player being auctioned.".to_string(),
+            })?;
+
+        if amount <= nomination.highest_bid.amount {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "The bid needs to be higher than {}.",
+                    nomination.highest_bid.amount
+                ),
+            });
+        }
+
+        nomination.highest_bid = AuctionBid {
+            bidder: bidder_id.to_string(),
+            amount,
+        };
+
+        Ok(())
+    }
+
+    pub fn close_bid(&mut self, settings: &PoolSettings) -> Result<bool, AppError> {
+        // Award the nominated player to the highest bidder and charge its budget.
+        let nomination = self
+            .auction
+            .as_mut()
+            .and_then(|auction| auction.nomination.take())
+            .ok_or_else(|| AppError::CustomError {
+                msg: "There is no player being auctioned.".to_string(),
+            })?;
+
+        let winning_bid = nomination.highest_bid;
+
+        // Add the drafted player if everything goes right.
+        self.add_drafted_player(&nomination.player, &winning_bid.bidder, settings)?;
+        self.record_pick(&nomination.player, &winning_bid.bidder);
+
+        if let Some(auction) = &mut self.auction {
+            if let Some(remaining_budget) = auction.remaining_budgets.get_mut(&winning_bid.bidder) {
+                *remaining_budget -= winning_bid.amount;
+            }
+            auction
+                .winning_bids
+                .insert(nomination.player.id.to_string(), winning_bid);
+        }
+
+        self.is_draft_done(settings)
+    }
+
+    pub fn undo_draft_player(
+        &mut self,
+        participants: &Vec<String>,
+        settings: &PoolSettings,
+    ) -> Result<(), AppError> {
+        // validate there is something to undo.
+
+        let latest_pick_id;
+
+        loop {
+            match self.players_name_drafted.pop() {
+                Some(player_id) => {
+                    if player_id > 0 {
+                        latest_pick_id = player_id; // found the last drafted player.
+                        break;
+                    }
+                }
+                None => {
+                    return Err(AppError::CustomError {
+                        msg: "Ther is nothing to undo yet.".to_string(),
+                    })
+                }
+            }
+        }
+        self.drop_unwound_makeup_picks();
+
+        if let Some(draft_picks) = &mut self.draft_picks {
+            draft_picks.retain(|pick| pick.player_id != latest_pick_id);
+        }
+
+        if let Some(auction) = &mut self.auction {
+            // In an auction draft, the drafter is the winner of the bid. Refund the winning bid.
+            let winning_bid = auction
+                .winning_bids
+                .remove(&latest_pick_id.to_string())
+                .ok_or_else(|| AppError::CustomError {
+                    msg: "The winning bid of the last pick could not be found.".to_string(),
+                })?;
+
+            *auction
+                .remaining_budgets
+                .entry(winning_bid.bidder.clone())
+                .or_insert(0) += winning_bid.amount;
+
+            self.remove_player_from_roster(latest_pick_id, &winning_bid.bidder)?;
+            self.players.remove(&latest_pick_id.to_string());
+            return Ok(());
+        }
+
+        let pick_number = self.players_name_drafted.len();
+        let latest_drafter;
+
+        match (&settings.dynasty_settings, &self.past_tradable_picks) {
+            (Some(dynasty_settings), Some(past_tradable_picks)) => {
+                // This comes from a Dynasty draft.
+
+                let nb_tradable_picks = dynasty_settings.tradable_picks;
+
+                let index = pick_number % participants.len();
+
+                let next_drafter = &participants[index];
+
+                if let Some(owner) = self
+                    .current_draft_pick_owners
+                    .as_ref()
+                    .and_then(|owners| owners.get(&pick_number.to_string()))
+                {
+                    // The pick was traded during the draft.
+                    latest_drafter = owner.clone();
+                } else if pick_number < nb_tradable_picks as usize * participants.len() {
+                    // use the tradable_picks to see who will draft next.
+                    latest_drafter =
+                        past_tradable_picks[pick_number / participants.len()][next_drafter].clone();
+                } else {
+                    // Use the draft order to see who draft next.
+                    latest_drafter = next_drafter.clone();
+                }
+            }
+            _ => {
+                // this comes from a newly created draft.
+                let index = settings
+                    .get_draft_order_strategy()
+                    .get_drafter_index(pick_number, participants.len());
+
+                latest_drafter = participants[index].clone();
+            }
+        }
+
+        // Remove the player from the player roster.
+
+        self.remove_player_from_roster(latest_pick_id, &latest_drafter)?;
+        self.players.remove(&latest_pick_id.to_string()); // Also remove the player from the pool players list.
+        Ok(())
+    }
+
+    pub fn remove_player_from_roster(
+        &mut self,
+        player_id: u32,
+        user_id: &str,
+    ) -> Result<(), AppError> {
+        // Remove a player from the roster.
+        if let Some(roster) = self.pooler_roster.get_mut(user_id) {
+            if roster.remove_forward(player_id) {
+                return Ok(());
+            };
+            if roster.remove_defender(player_id) {
+                return Ok(());
+            };
+            if roster.remove_goalie(player_id) {
+                return Ok(());
+            };
+            if roster.remove_reservist(player_id) {
+                return Ok(());
+            };
+            if roster.remove_utility(player_id) {
+                return Ok(());
+            };
+            if roster.remove_injured_reserve(player_id) {
+                return Ok(());
+            };
+        }
+
+        Err(AppError::CustomError {
+            msg: "The player could not be removed".to_string(),
+        }) // could not be removed
+    }
+
+    pub fn add_player_to_reservists(
+        &mut self,
+        player_id: u32,
+        user_id: &str,
+    ) -> Result<(), AppError> {
+        // Add a player to the reservist of a pooler.
+        if let Some(roster) = self.pooler_roster.get_mut(user_id) {
+            roster.chosen_reservists.push(player_id);
+            return Ok(());
+        }
+
+        Err(AppError::CustomError {
+            msg: "The player could not be added".to_string(),
+        }) // could not be added
+    }
+
+    pub fn trade_roster_player(
+        &mut self,
+        player_id: u32,
+        user_giver: &str,
+        user_receiver: &str,
+    ) -> Result<(), AppError> {
+        // Trade 1 player.
+        self.remove_player_from_roster(player_id, user_giver)?;
+        self.add_player_to_reservists(player_id, user_receiver)
+    }
+
+    pub fn trade_roster_items(
+        &mut self,
+        trade: &Trade,
+        settings: &PoolSettings,
+    ) -> Result<(), AppError> {
+        // Make sure the trade is valid before executing it.
+        self.validate_trade(trade, settings)?;
+
+        for leg in trade.get_legs() {
+            // The giver keeps a percentage of the cap hit of the players it retains salary on.
+            for (player_id, percentage) in leg.items.retained_salary.iter().flatten() {
+                let salary_cap = self
+                    .players
+                    .get(player_id)
+                    .and_then(|player| player.salary_cap)
+                    .unwrap_or(0.0);
+
+                if let Some(roster) = self.pooler_roster.get_mut(&leg.from) {
+                    roster
+                        .retained_salaries
+                        .get_or_insert_with(HashMap::new)
+                        .insert(player_id.clone(), salary_cap * *percentage as f64 / 100.0);
+                }
+            }
+
+            // Migrate players "from" -> "to"
+            for player_id in leg.items.players.iter() {
+                self.trade_roster_player(*player_id, &leg.from, &leg.to)?;
+            }
+
+            // Migrate picks "from" -> "to"
+            for pick in leg.items.get_picks() {
+                if let Some(owner) = self.get_pick_owner_mut(pick) {
+                    *owner = leg.to.clone();
+                }
+            }
+
+            // The conditions follow the picks until the pool is marked as final.
+            if let Some(conditional_picks) = &leg.items.conditional_picks {
+                self.conditional_picks
+                    .get_or_insert_with(Vec::new)
+                    .extend(conditional_picks.iter().cloned());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_trade_items(
+        &self,
+        trade_items: &TradeItems,
+        user_id: &str,
+        settings: &PoolSettings,
+    ) -> Result<(), AppError> {
+        // Validate that the trade items are valid for a trade side.
+        if let Some(from_pooler_roster) = self.pooler_roster.get(user_id) {
+            if let Some(retained_salary) = &trade_items.retained_salary {
+                self.validate_retained_salary(
+                    retained_salary,
+                    trade_items,
+                    from_pooler_roster,
+                    settings,
+                )?;
+            }
+
+            for player_id in &trade_items.players {
+                if !from_pooler_roster.validate_player_possession(*player_id) {
+                    return Err(AppError::CustomError {
+                        msg: "ther user does not possess one of the traded player!".to_string(),
+                    });
+                }
+            }
+
+            if let Some(tradable_picks) = &self.tradable_picks {
+                for pick in trade_items.get_picks() {
+                    if self
+                        .get_pick_owner(pick)
+                        .is_none_or(|owner| owner != user_id)
+                    {
+                        return Err(AppError::CustomError {
+                            msg: "ther user does not possess the traded pick!".to_string(),
+                        });
+                    }
+                }
+
+                for conditional_pick in trade_items.conditional_picks.iter().flatten() {
+                    self.validate_conditional_pick(conditional_pick, tradable_picks, settings)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_conditional_pick(
+        &self,
+        conditional_pick: &ConditionalPick,
+        tradable_picks: &[HashMap<String, String>],
+        settings: &PoolSettings,
+    ) -> Result<(), AppError> {
+        // The conditions are evaluated on the final rank of the current season, so only the next season picks can have one.
+        if conditional_pick.pick.get_season_offset() != 0 {
             return Err(AppError::CustomError {
-                msg: "No score is being recorded in this pool yet.".to_string(),
+                msg: "Only the picks of the next season can be conditional.".to_string(),
+            });
+        }
+
+        if conditional_pick.upgraded_round == conditional_pick.pick.round
+            || conditional_pick.upgraded_round as usize >= tradable_picks.len()
+        {
+            return Err(AppError::CustomError {
+                msg: "The upgraded round of a conditional pick needs to be another tradable round."
+                    .to_string(),
+            });
+        }
+
+        if self
+            .conditional_picks
+            .iter()
+            .flatten()
+            .any(|pending_pick| pending_pick.pick == conditional_pick.pick)
+        {
+            return Err(AppError::CustomError {
+                msg: "This pick already has a condition.".to_string(),
+            });
+        }
+
+        conditional_pick.condition.validate(settings.number_poolers)
+    }
+
+    fn validate_retained_salary(
+        &self,
+        retained_salary: &HashMap<String, u8>,
+        trade_items: &TradeItems,
+        from_pooler_roster: &PoolerRoster,
+        settings: &PoolSettings,
+    ) -> Result<(), AppError> {
+        // Validate the salary retained by the giver on the traded players.
+        if retained_salary.is_empty() {
+            return Ok(());
+        }
+
+        let (Some(_), Some(max_retained_salaries)) =
+            (settings.salary_cap, settings.max_retained_salaries)
+        else {
+            return Err(AppError::CustomError {
+                msg: "Salary cannot be retained in the trades of this pool.".to_string(),
             });
         };
 
-        // Map the user to its total points, total number of games
-        // and for each player type, a hashmap of the player id with their corresponding total number of points, total number of games.
-        let mut user_total_points: HashMap<
-            String,
-            (
-                u16,                         // Total points.
-                u16,                         // Total number of games.
-                HashMap<String, (u16, u16)>, // Forwards
-                HashMap<String, (u16, u16)>, // Defense
-                HashMap<String, (u16, u16)>, // Goalies
-            ),
-        > = HashMap::new();
+        let already_retained = from_pooler_roster
+            .retained_salaries
+            .as_ref()
+            .map(|retained_salaries| retained_salaries.len())
+            .unwrap_or(0);
+
+        if already_retained + retained_salary.len() > max_retained_salaries as usize {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "A team can only retain salary on {} players.",
+                    max_retained_salaries
+                ),
+            });
+        }
+
+        for (player_id, percentage) in retained_salary {
+            if !trade_items
+                .players
+                .iter()
+                .any(|traded_player_id| traded_player_id.to_string() == *player_id)
+            {
+                return Err(AppError::CustomError {
+                    msg: "Salary can only be retained on a traded player.".to_string(),
+                });
+            }
+
+            if *percentage == 0 || *percentage > MAX_RETAINED_SALARY_PERCENTAGE {
+                return Err(AppError::CustomError {
+                    msg: format!(
+                        "The retained salary needs to be between 1% and {}%.",
+                        MAX_RETAINED_SALARY_PERCENTAGE
+                    ),
+                });
+            }
+
+            if self
+                .players
+                .get(player_id)
+                .and_then(|player| player.salary_cap)
+                .is_none()
+            {
+                return Err(AppError::CustomError {
+                    msg: "Salary cannot be retained on a player without contract.".to_string(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_trade(&self, trade: &Trade, settings: &PoolSettings) -> Result<(), AppError> {
+        // Validate if the full trade is valid, across all the legs of the trade.
+        let legs = trade.get_legs();
+
+        if legs.iter().any(|leg| leg.from == leg.to) {
+            return Err(AppError::CustomError {
+                msg: "A pooler cannot trade items with himself.".to_string(),
+            });
+        }
+
+        for participant in trade.get_participants() {
+            let number_items: usize = legs
+                .iter()
+                .filter(|leg| leg.from == participant)
+                .map(|leg| leg.items.get_picks().len() + leg.items.players.len())
+                .sum();
+
+            // does every side has items in the trade ?
+
+            if number_items == 0 {
+                return Err(AppError::CustomError {
+                    msg: "There is no items traded on one of the sides.".to_string(),
+                });
+            }
+
+            // Maximum of 5 items traded on each side ?
+
+            if number_items > 5 {
+                return Err(AppError::CustomError {
+                    msg: "There is to much items in the trade.".to_string(),
+                });
+            }
+        }
+
+        // The same item cannot be traded twice.
+        let players: Vec<u32> = legs
+            .iter()
+            .flat_map(|leg| leg.items.players.iter().copied())
+            .collect();
+        let picks: Vec<&Pick> = legs.iter().flat_map(|leg| leg.items.get_picks()).collect();
+
+        if players.iter().collect::<HashSet<_>>().len() != players.len()
+            || picks
+                .iter()
+                .enumerate()
+                .any(|(i, pick)| picks[..i].contains(pick))
+        {
+            return Err(AppError::CustomError {
+                msg: "An item cannot be traded more than once in the same trade.".to_string(),
+            });
+        }
+
+        for leg in &legs {
+            self.validate_trade_items(&leg.items, &leg.from, settings)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn get_forwards_count(&self, user_id: &str) -> Result<usize, AppError> {
+        // Get the count of forward for a pooler.
+        match self.pooler_roster.get(user_id) {
+            None => Err(AppError::CustomError {
+                msg: "The user does not exist.".to_string(),
+            }),
+            Some(roster) => Ok(roster.chosen_forwards.len()),
+        }
+    }
+
+    pub fn get_defenders_count(&self, user_id: &str) -> Result<usize, AppError> {
+        // Get the count of defender for a pooler.
+        match self.pooler_roster.get(user_id) {
+            None => Err(AppError::CustomError {
+                msg: "The user does not exist.".to_string(),
+            }),
+            Some(roster) => Ok(roster.chosen_defenders.len()),
+        }
+    }
+
+    pub fn get_goalies_count(&self, user_id: &str) -> Result<usize, AppError> {
+        // Get the count of goalies for a pooler.
+        match self.pooler_roster.get(user_id) {
+            None => Err(AppError::CustomError {
+                msg: "The user does not exist.".to_string(),
+            }),
+            Some(roster) => Ok(roster.chosen_goalies.len()),
+        }
+    }
+
+    pub fn get_reservists_count(&self, user_id: &str) -> Result<usize, AppError> {
+        // Get the count of reservist for a pooler.
+        match self.pooler_roster.get(user_id) {
+            None => Err(AppError::CustomError {
+                msg: "The user does not exist.".to_string(),
+            }),
+            Some(roster) => Ok(roster.chosen_reservists.len()),
+        }
+    }
+
+    pub fn get_number_players_to_protect(
+        &self,
+        user_id: &str,
+        number_players_protected: u8,
+    ) -> Result<usize, AppError> {
+        // The poolers with fewer players than the number to protect (i.e, an expansion team) protect all of them.
+        Ok(self
+            .get_roster_count(user_id)?
+            .min(number_players_protected as usize))
+    }
+
+    pub fn update_participants(
+        &mut self,
+        added_participants: &[String],
+        removed_participants: &[String],
+    ) {
+        // Add the rosters of the new poolers and release the rosters of the departing ones.
+        // The departing poolers lose their picks, the picks they acquired go back to their original owner.
+        for participant in removed_participants {
+            self.pooler_roster.remove(participant);
+            if let Some(protected_players) = self.protected_players.as_mut() {
+                protected_players.remove(participant);
+            }
+        }
 
-        for (date, daily_roster_points) in score_by_day {
-            for (participant, roster_daily_points) in daily_roster_points {
-                // Initialize the participant with 0 points and 0 games and no players.
-                if !user_total_points.contains_key(participant) {
-                    user_total_points.insert(
-                        participant.clone(),
-                        (0, 0, HashMap::new(), HashMap::new(), HashMap::new()),
-                    );
+        for participant in added_participants {
+            self.pooler_roster
+                .insert(participant.clone(), PoolerRoster::new());
+            if let Some(protected_players) = self.protected_players.as_mut() {
+                protected_players.insert(participant.clone(), Vec::new());
+            }
+        }
+
+        let rounds = self
+            .past_tradable_picks
+            .iter_mut()
+            .chain(self.tradable_picks.iter_mut())
+            .flatten()
+            .chain(self.future_tradable_picks.iter_mut().flatten().flatten());
+
+        for round in rounds {
+            round.retain(|from, _| !removed_participants.contains(from));
+
+            for (from, owner) in round.iter_mut() {
+                if removed_participants.contains(owner) {
+                    *owner = from.clone();
                 }
+            }
 
-                // Return an error if at least one day have not been cumulated yet.
-                if !roster_daily_points.is_cumulated {
+            for participant in added_participants {
+                round.insert(participant.clone(), participant.clone());
+            }
+        }
+    }
+
+    pub fn get_roster_count(&self, user_id: &str) -> Result<usize, AppError> {
+        // Get the count of the full roster for a pooler.
+        // The players on the injured reserve do not count in the roster.
+        let utility_count = self
+            .pooler_roster
+            .get(user_id)
+            .map_or(0, |roster| roster.get_utility().len());
+
+        Ok(self.get_forwards_count(user_id)?
+            + self.get_defenders_count(user_id)?
+            + self.get_goalies_count(user_id)?
+            + utility_count
+            + self.get_reservists_count(user_id)?)
+    }
+
+    pub fn check_invariants(
+        &self,
+        settings: &PoolSettings,
+        status: &PoolState,
+    ) -> Result<(), AppError> {
+        // Validate the rules that every mutation of the context needs to keep:
+        // - A player is never on two rosters (or twice on the same roster).
+        // - The starting positions never exceed the settings, and neither does the roster during the draft.
+        // - During the draft, the rosters contain the drafted players (and the players protected last season).
+        let mut roster_players = HashSet::new();
+
+        for (user_id, roster) in &self.pooler_roster {
+            if roster.chosen_forwards.len() > settings.number_forwards as usize
+                || roster.chosen_defenders.len() > settings.number_defenders as usize
+                || roster.chosen_goalies.len() > settings.number_goalies as usize
+                || roster.get_utility().len() > settings.get_number_utility() as usize
+                || roster.get_injured_reserve().len()
+                    > settings.get_number_injured_reserve() as usize
+            {
+                return Err(AppError::CustomError {
+                    msg: format!("The starting roster of {} exceeds the settings.", user_id),
+                });
+            }
+
+            let roster_count = self.get_roster_count(user_id)?;
+
+            if matches!(status, PoolState::Draft)
+                && roster_count > settings.get_roster_size() as usize
+            {
+                return Err(AppError::CustomError {
+                    msg: format!("The roster of {} exceeds the roster size.", user_id),
+                });
+            }
+
+            for player_id in roster.get_players() {
+                if !roster_players.insert(*player_id) {
                     return Err(AppError::CustomError {
-                        msg: format!(
-                            "There are no cumulative data on the {date} for the user {participant}"
-                        ),
+                        msg: format!("The player {} is on more than one roster.", player_id),
                     });
                 }
+            }
+        }
 
-                if let Some((
-                    total_points,
-                    number_of_games,
-                    forwards_points,
-                    defenders_points,
-                    goalies_points,
-                )) = user_total_points.get_mut(participant)
-                {
-                    let (daily_points, daily_games) = roster_daily_points.get_total_points(
-                        pool_settings,
-                        forwards_points,
-                        defenders_points,
-                        goalies_points,
-                    );
+        if !matches!(status, PoolState::Draft) {
+            // After the draft, the rosters change through trades and roster modifications.
+            return Ok(());
+        }
 
-                    *total_points += daily_points;
-                    *number_of_games += daily_games;
-                }
+        let mut expected_players = HashSet::new();
+
+        // Id 0 is a skipped pick (or a pooler with a complete roster).
+        for player_id in self.players_name_drafted.iter().filter(|id| **id != 0) {
+            if !expected_players.insert(*player_id) {
+                return Err(AppError::CustomError {
+                    msg: format!("The player {} was drafted more than once.", player_id),
+                });
             }
         }
 
-        // Convert the HashMap into a Vec of tuples
-        if let Some(ignore_x_worst_players) = &pool_settings.ignore_x_worst_players {
-            for (
-                total_points,
-                total_number_of_games,
-                forwards_points,
-                defenders_points,
-                goalies_points,
-            ) in user_total_points.values_mut()
-            {
-                // Find the x worst forwards that points should be ignored.
-                let mut forwards_vec: Vec<(&String, &(u16, u16))> =
-                    forwards_points.iter().collect();
+        for protected_players in self.protected_players.iter().flat_map(|map| map.values()) {
+            expected_players.extend(protected_players.iter().copied());
+        }
 
-                // Sort the vector by total points in ascending order
-                forwards_vec.sort_by(|a, b| a.1 .0.cmp(&b.1 .0).then_with(|| a.1 .1.cmp(&b.1 .1)));
+        if let Some(player_id) = expected_players
+            .iter()
+            .find(|player_id| !roster_players.contains(player_id))
+        {
+            return Err(AppError::CustomError {
+                msg: format!("The drafted player {} is not on a roster.", player_id),
+            });
+        }
 
-                // Take the first x elements
-                let least_points_players = forwards_vec
-                    .iter()
-                    .take(ignore_x_worst_players.forwards as usize);
+        if let Some(player_id) = roster_players
+            .iter()
+            .find(|player_id| !expected_players.contains(player_id))
+        {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "The player {} is on a roster without being drafted.",
+                    player_id
+                ),
+            });
+        }
 
-                // Print the players with the least total points
-                for (_, (points, number_of_games)) in least_points_players {
-                    *total_points -= points;
-                    *total_number_of_games -= number_of_games;
-                }
+        Ok(())
+    }
+}
 
-                // Find the x worst defenders that points should be ignored.
-                let mut defenders_vec: Vec<(&String, &(u16, u16))> =
-                    defenders_points.iter().collect();
+// A pick skipped by the owner of the pool, it stays empty in the draft until its drafter makes it up.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct MakeupPick {
+    // The overall number of the skipped pick.
+    pub pick_number: usize,
+    pub drafter: String,
+    pub date_skipped: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)] // Copy
+pub struct PoolerRoster {
+    pub chosen_forwards: Vec<u32>,
+    pub chosen_defenders: Vec<u32>,
+    pub chosen_goalies: Vec<u32>,
+    pub chosen_reservists: Vec<u32>,
+
+    // Starting skaters of the utility spots.
+    pub chosen_utility: Option<Vec<u32>>,
+    // Players on the injured reserve, they do not make points and do not count in the roster.
+    pub chosen_injured_reserve: Option<Vec<u32>>,
+
+    // Cap hit retained on the players traded away (player id -> amount).
+    pub retained_salaries: Option<HashMap<String, f64>>,
+}
+impl PoolerRoster {
+    pub fn new() -> Self {
+        Self {
+            chosen_forwards: Vec::new(),
+            chosen_defenders: Vec::new(),
+            chosen_goalies: Vec::new(),
+            chosen_reservists: Vec::new(),
+            chosen_utility: None,
+            chosen_injured_reserve: None,
+            retained_salaries: None,
+        }
+    }
+
+    pub fn get_utility(&self) -> &[u32] {
+        self.chosen_utility.as_deref().unwrap_or_default()
+    }
+
+    pub fn get_injured_reserve(&self) -> &[u32] {
+        self.chosen_injured_reserve.as_deref().unwrap_or_default()
+    }
+
+    // The players making points, the forwards, defenders, goalies and utility players.
+    pub fn get_starting_players(&self) -> impl Iterator<Item = &u32> {
+        self.chosen_forwards
+            .iter()
+            .chain(self.chosen_defenders.iter())
+            .chain(self.chosen_goalies.iter())
+            .chain(self.get_utility().iter())
+    }
+
+    // Every player possessed by the pooler.
+    pub fn get_players(&self) -> impl Iterator<Item = &u32> {
+        self.get_starting_players()
+            .chain(self.chosen_reservists.iter())
+            .chain(self.get_injured_reserve().iter())
+    }
+
+    pub fn remove_forward(&mut self, player_id: u32) -> bool {
+        // Remove a forward from a pooler roster
+        self.chosen_forwards
+            .iter()
+            .position(|id| id == &player_id)
+            .map(|index| self.chosen_forwards.remove(index))
+            .is_some()
+    }
+
+    pub fn remove_defender(&mut self, player_id: u32) -> bool {
+        // Remove a defenders from a pooler roster
+        self.chosen_defenders
+            .iter()
+            .position(|id| id == &player_id)
+            .map(|index| self.chosen_defenders.remove(index))
+            .is_some()
+    }
+
+    pub fn remove_goalie(&mut self, player_id: u32) -> bool {
+        // Remove a goalies from a pooler roster
+        self.chosen_goalies
+            .iter()
+            .position(|id| id == &player_id)
+            .map(|index| self.chosen_goalies.remove(index))
+            .is_some()
+    }
+
+    pub fn remove_reservist(&mut self, player_id: u32) -> bool {
+        // Remove a reservist from a pooler roster
+        self.chosen_reservists
+            .iter()
+            .position(|id| id == &player_id)
+            .map(|index| self.chosen_reservists.remove(index))
+            .is_some()
+    }
+
+    pub fn remove_utility(&mut self, player_id: u32) -> bool {
+        // Remove a utility player from a pooler roster
+        let Some(chosen_utility) = self.chosen_utility.as_mut() else {
+            return false;
+        };
+        chosen_utility
+            .iter()
+            .position(|id| id == &player_id)
+            .map(|index| chosen_utility.remove(index))
+            .is_some()
+    }
+
+    pub fn remove_injured_reserve(&mut self, player_id: u32) -> bool {
+        // Remove a player of the injured reserve from a pooler roster
+        let Some(chosen_injured_reserve) = self.chosen_injured_reserve.as_mut() else {
+            return false;
+        };
+        chosen_injured_reserve
+            .iter()
+            .position(|id| id == &player_id)
+            .map(|index| chosen_injured_reserve.remove(index))
+            .is_some()
+    }
+
+    pub fn validate_player_possession(&self, player_id: u32) -> bool {
+        self.get_players().any(|id| *id == player_id)
+    }
+
+    pub fn add_starting_player(
+        &mut self,
+        player: &PoolPlayerInfo,
+        settings: &PoolSettings,
+    ) -> bool {
+        // Add the player in the first eligible position with an open starting spot, its primary position first.
+        for position in player.get_eligible_positions() {
+            let (starting_spots, number_spots) = match position {
+                Position::F => (&mut self.chosen_forwards, settings.number_forwards),
+                Position::D => (&mut self.chosen_defenders, settings.number_defenders),
+                Position::G => (&mut self.chosen_goalies, settings.number_goalies),
+            };
+
+            if (starting_spots.len() as u8) < number_spots {
+                starting_spots.push(player.id);
+                return true;
+            }
+        }
+
+        // The skaters can also fill a utility spot.
+        if player.is_skater() && (self.get_utility().len() as u8) < settings.get_number_utility() {
+            self.chosen_utility
+                .get_or_insert_with(Vec::new)
+                .push(player.id);
+            return true;
+        }
+
+        false
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct AuctionBid {
+    pub bidder: String,
+    pub amount: u32,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct AuctionNomination {
+    pub player: PoolPlayerInfo,
+    pub nominated_by: String,
+    pub highest_bid: AuctionBid,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct AuctionContext {
+    // Map a pooler id to the budget left to bid with.
+    pub remaining_budgets: HashMap<String, u32>,
+
+    // The player currently being auctioned.
+    pub nomination: Option<AuctionNomination>,
+
+    // Map a drafted player id to the bid that won it.
+    pub winning_bids: HashMap<String, AuctionBid>,
+}
+
+impl AuctionContext {
+    pub fn new(participants: &[String], auction_settings: &AuctionSettings) -> Self {
+        Self {
+            remaining_budgets: participants
+                .iter()
+                .map(|participant| (participant.clone(), auction_settings.budget))
+                .collect(),
+            nomination: None,
+            winning_bids: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct MatchupResult {
+    pub home_points: f64,
+    pub away_points: f64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct Matchup {
+    pub home: String,
+    pub away: String,
+
+    // Filled once every day of the week have been cumulated.
+    pub result: Option<MatchupResult>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct MatchupWeek {
+    pub start_date: String,
+    pub end_date: String,
+    pub matchups: Vec<Matchup>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct MatchupSchedule {
+    pub weeks: Vec<MatchupWeek>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct CapReport {
+    pub salary_cap: f64,
+    pub teams: Vec<TeamCapReport>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct TeamCapReport {
+    pub participant: String,
+    // None when a player of the roster does not have a contract.
+    pub current_cap_usage: Option<f64>,
+    pub dead_cap: f64,
+    pub cap_by_day: Vec<DailyCapUsage>,
+    // Dates where the cap usage was over the salary cap.
+    pub violations: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct DailyCapUsage {
+    pub date: String,
+    pub cap_usage: f64,
+}
+
+// The rosters and the cap numbers that a request would result in (?dry_run=true), nothing is saved.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct PoolPreview {
+    pub pooler_roster: HashMap<String, PoolerRoster>,
+    pub trades: Option<Vec<Trade>>,
+    pub salary_cap: Option<f64>,
+    pub teams_cap: Vec<TeamCapPreview>,
+}
+
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct TeamCapPreview {
+    pub participant: String,
+    // None when a player of the roster does not have a contract.
+    pub current_cap_usage: Option<f64>,
+    pub dead_cap: f64,
+    // None when the pool has no salary cap or the cap usage is not known.
+    pub cap_space: Option<f64>,
+}
+
+// Number of days of cumulated stats used to measure the recent form of a player.
+pub const RECENT_FORM_DAYS: i64 = 14;
+
+// Number of games of an NHL team during the regular season.
+pub const NHL_REGULAR_SEASON_GAMES: u16 = 82;
+
+// Remaining regular season games of an NHL team.
+#[derive(Debug, Clone)]
+pub struct TeamRemainingSchedule {
+    pub games: u16,
+    // Average points percentage of the remaining opponents (0 to 1), higher is harder.
+    pub strength: f64,
+}
 
-                // Sort the vector by total points in ascending order
-                defenders_vec.sort_by(|a, b| a.1 .0.cmp(&b.1 .0).then_with(|| a.1 .1.cmp(&b.1 .1)));
+// Server computed comparison of what each pooler gives and receives in a trade.
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct TradeAnalysis {
+    pub trade_id: u32,
+    pub legs: Vec<TradeLegAnalysis>,
+    pub poolers: Vec<TradePoolerBalance>,
+    // 1 when every pooler receives as much value as they give, 0 when one side receives everything.
+    pub fairness: f64,
+    // The pooler receiving the most value, None when the trade is even.
+    pub favored_pooler: Option<String>,
+}
 
-                // Take the first x elements
-                let least_points_players = defenders_vec
-                    .iter()
-                    .take(ignore_x_worst_players.defense as usize);
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct TradeLegAnalysis {
+    pub from: String,
+    pub to: String,
+    pub players: Vec<TradePlayerValue>,
+    pub picks: Vec<TradePickValue>,
+    // Projected points of the players and picks of the leg for the rest of the season.
+    pub value: f64,
+}
 
-                // Print the players with the least total points
-                for (_, (points, number_of_games)) in least_points_players {
-                    *total_points -= points;
-                    *total_number_of_games -= number_of_games;
-                }
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct TradePlayerValue {
+    pub player: PoolPlayerInfo,
+    pub games_played: u16,
+    pub total_points: f64,
+    pub points_per_game: f64,
+    // Points per game over the last RECENT_FORM_DAYS days of cumulated stats.
+    pub recent_games: u16,
+    pub recent_points_per_game: f64,
+    pub remaining_games: u16,
+    // None when the schedule of the player team is not available.
+    pub remaining_schedule_strength: Option<f64>,
+    pub projected_points: f64,
+}
 
-                // Find the x worst goalies that points should be ignored.
-                let mut goalies_vec: Vec<(&String, &(u16, u16))> = goalies_points.iter().collect();
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct TradePickValue {
+    pub pick: Pick,
+    // Average projected points of the players drafted in that round of the pool draft.
+    pub projected_points: f64,
+}
 
-                // Sort the vector by total points in ascending order
-                goalies_vec.sort_by(|a, b| a.1 .0.cmp(&b.1 .0).then_with(|| a.1 .1.cmp(&b.1 .1)));
+#[derive(Debug, Serialize, Clone, ToSchema)]
+pub struct TradePoolerBalance {
+    pub participant: String,
+    pub value_given: f64,
+    pub value_received: f64,
+    pub net_value: f64,
+}
 
-                // Take the first x elements
-                let least_points_players = goalies_vec
-                    .iter()
-                    .take(ignore_x_worst_players.goalies as usize);
+// Picks of the current dynasty draft exchanged between two poolers (overall pick numbers).
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct TradeDraftPicksRequest {
+    pub from_user_id: String,
+    pub to_user_id: String,
+    pub from_picks: Vec<usize>,
+    pub to_picks: Vec<usize>,
+}
 
-                // Print the players with the least total points
-                for (_, (points, number_of_games)) in least_points_players {
-                    *total_points -= points;
-                    *total_number_of_games -= number_of_games;
-                }
-            }
-        }
+// A pick made during the draft.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct DraftPick {
+    pub player_id: u32,
+    pub drafter: String,
+    pub date_drafted: i64,
+}
 
-        let mut user_points_vec: Vec<(
-            &String,
-            &(
-                u16,
-                u16,
-                HashMap<String, (u16, u16)>,
-                HashMap<String, (u16, u16)>,
-                HashMap<String, (u16, u16)>,
-            ),
-        )> = user_total_points.iter().collect();
+// One pick of the draft recap.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct DraftRecapPick {
+    pub round: usize,
+    pub overall: usize,
+    pub drafter: Option<String>,
+    pub player: Option<PoolPlayerInfo>,
+    pub date_drafted: Option<i64>,
+}
 
-        // Sort the total points vector. And fill the final_rank list with it.
-        // Sort the vector by total points and then by total games in descending order
-        user_points_vec.sort_by(|a, b| {
-            b.1 .0
-                .cmp(&a.1 .0) // Compare total points
-                .then_with(|| a.1 .1.cmp(&b.1 .1)) // If points are equal, compare total games (The pooler with less games wins)
-        });
+// Cumulative points of a pooler with the per position breakdown.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct PoolerStanding {
+    pub participant: String,
+    pub total_points: f64,
+    pub number_of_games: u16,
+    pub forwards: PositionTotals,
+    pub defense: PositionTotals,
+    pub goalies: PositionTotals,
+
+    // Positions whose games played limit is reached, their players do not make points anymore.
+    pub capped_positions: Option<Vec<Position>>,
+}
 
-        let mut final_rank = Vec::new();
-        for participant in user_points_vec {
-            final_rank.push(participant.0.clone())
+impl PoolerStanding {
+    fn new(
+        participant: &str,
+        totals: PoolerTotals,
+        capped_positions: Option<Vec<Position>>,
+    ) -> Self {
+        Self {
+            participant: participant.to_string(),
+            total_points: totals.forwards.points + totals.defense.points + totals.goalies.points,
+            number_of_games: totals.forwards.games + totals.defense.games + totals.goalies.games,
+            forwards: totals.forwards,
+            defense: totals.defense,
+            goalies: totals.goalies,
+            capped_positions,
         }
-
-        Ok(final_rank)
     }
+}
 
-    pub fn calculate_cumulated_salary_cap(
-        &self,
-        pooler_roster: &PoolerRoster,
-        players: &HashMap<String, PoolPlayerInfo>,
-    ) -> Result<f64, AppError> {
-        let cumulated_salary_cap = pooler_roster
-            .chosen_forwards
-            .iter()
-            .chain(pooler_roster.chosen_defenders.iter()) // Chain defenders
-            .chain(pooler_roster.chosen_goalies.iter())
-            .map(|player_id| {
-                players
-                    .get(&player_id.to_string())
-                    .ok_or_else(|| AppError::CustomError {
-                        msg: "Player does not exist.".to_string(),
-                    })
-                    .and_then(|player| {
-                        player.salary_cap.ok_or_else(|| AppError::CustomError {
-                            msg: "Player salary cap not available.".to_string(),
-                        })
-                    })
-            })
-            .try_fold(0.0, |acc, salary_cap| salary_cap.map(|sc| acc + sc));
+// Standings of a pool, the head-to-head standings are only filled for head-to-head pools.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct PoolStandings {
+    pub cumulative: Vec<PoolerStanding>,
+    pub head_to_head: Option<Vec<Standing>>,
+}
 
-        cumulated_salary_cap
-    }
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct Standing {
+    pub participant: String,
+    pub wins: u16,
+    pub losses: u16,
+    pub ties: u16,
+    pub points: u16,
+    pub points_for: f64,
+    pub points_against: f64,
+}
 
-    pub fn can_add_player_to_roster(
-        &self,
-        player: &PoolPlayerInfo,
-        pool_user_id: &str,
-        settings: &PoolSettings,
-    ) -> Result<bool, AppError> {
-        // If there is salary cap management, don't add to the starting roster players without contracts or if the user doesn't have enough space.
-        if let Some(team_salary_cap) = settings.salary_cap {
-            let pooler_roster =
-                self.pooler_roster
-                    .get(pool_user_id)
-                    .ok_or_else(|| AppError::CustomError {
-                        msg: "Pooler roster does not exist.".to_string(),
-                    })?;
+impl Standing {
+    fn new(participant: &str) -> Self {
+        Self {
+            participant: participant.to_string(),
+            wins: 0,
+            losses: 0,
+            ties: 0,
+            points: 0,
+            points_for: 0.0,
+            points_against: 0.0,
+        }
+    }
 
-            let cumulated_salary_cap =
-                self.calculate_cumulated_salary_cap(pooler_roster, &self.players)?;
+    fn record(&mut self, points_for: f64, points_against: f64, settings: &HeadToHeadSettings) {
+        self.points_for += points_for;
+        self.points_against += points_against;
 
-            if let Some(player_salary_cap) = player.salary_cap {
-                if cumulated_salary_cap + player_salary_cap <= team_salary_cap {
-                    return Ok(true);
-                }
-                return Ok(false);
+        match cmp_points(points_for, points_against) {
+            std::cmp::Ordering::Greater => {
+                self.wins += 1;
+                self.points += settings.points_per_win as u16;
+            }
+            std::cmp::Ordering::Less => self.losses += 1,
+            std::cmp::Ordering::Equal => {
+                self.ties += 1;
+                self.points += settings.points_per_tie as u16;
             }
-            return Ok(false);
         }
-        Ok(true)
     }
+}
 
-    pub fn add_drafted_player(
-        &mut self,
-        player: &PoolPlayerInfo,
-        next_drafter: &str,
-        settings: &PoolSettings,
-    ) -> Result<(), AppError> {
-        // Then, Add the chosen player in its right spot.
-        // When there is no place in the position of the player we will add it to the reservists.
-
-        let can_add_player_to_roster =
-            self.can_add_player_to_roster(player, next_drafter, settings)?;
+impl MatchupSchedule {
+    pub fn new(
+        participants: &[String],
+        season_start: &str,
+        season_end: &str,
+    ) -> Result<Self, AppError> {
+        // Generate a round robin schedule where each week every pooler faces another pooler.
+        // With an odd number of poolers, one of them does not play each week.
+        if participants.len() < 2 {
+            return Err(AppError::CustomError {
+                msg: "At least 2 participants are needed to generate the matchups.".to_string(),
+            });
+        }
 
-        if let Some(pooler_roster) = self.pooler_roster.get_mut(next_drafter) {
-            let mut is_added = false;
-            if can_add_player_to_roster {
-                match player.position {
-                    Position::F => {
-                        if (pooler_roster.chosen_forwards.len() as u8) < settings.number_forwards {
-                            pooler_roster.chosen_forwards.push(player.id);
-                            is_added = true;
-                        }
-                    }
-                    Position::D => {
-                        if (pooler_roster.chosen_defenders.len() as u8) < settings.number_defenders
-                        {
-                            pooler_roster.chosen_defenders.push(player.id);
-                            is_added = true;
-                        }
-                    }
-                    Position::G => {
-                        if (pooler_roster.chosen_goalies.len() as u8) < settings.number_goalies {
-                            pooler_roster.chosen_goalies.push(player.id);
-                            is_added = true;
-                        }
-                    }
-                }
-            }
+        let season_start = NaiveDate::parse_from_str(season_start, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+        let season_end = NaiveDate::parse_from_str(season_end, "%Y-%m-%d")
+            .map_err(|e| AppError::ParseError { msg: e.to_string() })?;
 
-            // If the there is not enough place in the roster, try to add the player in the reservists.
-            if !is_added {
-                // Return an error when the player could not be added.
-                if settings.number_reservists == 0 {
-                    return Err(AppError::CustomError {
-                        msg: format!("There is no space for {} in the roster.", player.name),
+        let mut rotation: Vec<Option<&String>> = participants.iter().map(Some).collect();
+        if rotation.len() % 2 == 1 {
+            rotation.push(None);
+        }
+        let number_rounds = rotation.len() - 1;
+
+        let mut weeks = Vec::new();
+        let mut week_start = season_start;
+
+        while week_start <= season_end {
+            let week_end = std::cmp::min(week_start + Duration::days(6), season_end);
+
+            // Swap home and away every time all the poolers faced each other.
+            let is_swapped = (weeks.len() / number_rounds) % 2 == 1;
+
+            let mut matchups = Vec::new();
+            for i in 0..rotation.len() / 2 {
+                if let (Some(home), Some(away)) = (rotation[i], rotation[rotation.len() - 1 - i]) {
+                    let (home, away) = if is_swapped {
+                        (away, home)
+                    } else {
+                        (home, away)
+                    };
+
+                    matchups.push(Matchup {
+                        home: home.clone(),
+                        away: away.clone(),
+                        result: None,
                     });
                 }
-                pooler_roster.chosen_reservists.push(player.id);
             }
+
+            weeks.push(MatchupWeek {
+                start_date: week_start.to_string(),
+                end_date: week_end.to_string(),
+                matchups,
+            });
+
+            // The first pooler stays in place while the others rotate.
+            rotation[1..].rotate_right(1);
+            week_start += Duration::days(7);
         }
-        Ok(())
-    }
 
-    pub fn is_draft_done(&mut self, settings: &PoolSettings) -> Result<bool, AppError> {
-        // the status change to InProgress when the draft is completed.
-        // The draft is completed when all participants has a complete roster.
+        Ok(Self { weeks })
+    }
 
-        let mut is_done = true;
+    pub fn update_results(
+        &mut self,
+        score_by_day: &HashMap<String, HashMap<String, DailyRosterPoints>>,
+        pool_settings: &PoolSettings,
+        today: &str,
+    ) -> bool {
+        let mut is_updated = false;
 
-        for participant in self.pooler_roster.keys() {
-            if self.get_roster_count(participant)?
-                < (settings.number_forwards
-                    + settings.number_defenders
-                    + settings.number_goalies
-                    + settings.number_reservists) as usize
+        for week in self.weeks.iter_mut() {
+            if week.end_date.as_str() >= today
+                || week.matchups.iter().all(|matchup| matchup.result.is_some())
             {
-                is_done = false;
-                break; // The Draft phase is not done.
+                continue;
             }
-        }
 
-        // generate the list of tradable_picks for the next season
-
-        if is_done {
-            // If done, clone the tradable picks, into the past_tradable_picks and reset the tradable picks.
-            let mut new_tradable_picks = vec![];
+            let (Ok(start_date), Ok(end_date), Ok(today_date)) = (
+                NaiveDate::parse_from_str(&week.start_date, "%Y-%m-%d"),
+                NaiveDate::parse_from_str(&week.end_date, "%Y-%m-%d"),
+                NaiveDate::parse_from_str(today, "%Y-%m-%d"),
+            ) else {
+                continue;
+            };
 
-            if let Some(dynasty_settings) = &settings.dynasty_settings {
-                for _ in 0..dynasty_settings.tradable_picks {
-                    let mut round = HashMap::new();
+            // Wait for every day of the week to be cumulated, a day missing from the scores is not cumulated yet.
+            let last_date = end_date.min(today_date - Duration::days(1));
+            let is_week_cumulated = start_date
+                .iter_days()
+                .take_while(|date| *date <= last_date)
+                .all(|date| {
+                    score_by_day
+                        .get(&date.to_string())
+                        .is_some_and(|daily_roster_points| {
+                            daily_roster_points
+                                .values()
+                                .all(|roster_daily_points| roster_daily_points.is_cumulated)
+                        })
+                });
+            if !is_week_cumulated {
+                continue;
+            }
 
-                    for participant in self.pooler_roster.keys() {
-                        round.insert(participant.clone(), participant.clone());
-                    }
+            let week_score_by_day: Vec<&HashMap<String, DailyRosterPoints>> = score_by_day
+                .iter()
+                .filter(|(date, _)| {
+                    week.start_date.as_str() <= date.as_str()
+                        && date.as_str() <= week.end_date.as_str()
+                })
+                .map(|(_, daily_roster_points)| daily_roster_points)
+                .collect();
+
+            let get_week_points = |participant: &str| {
+                week_score_by_day
+                    .iter()
+                    .filter_map(|daily_roster_points| daily_roster_points.get(participant))
+                    .map(|roster_daily_points| {
+                        roster_daily_points
+                            .get_total_points(
+                                pool_settings,
+                                &mut HashMap::new(),
+                                &mut HashMap::new(),
+                                &mut HashMap::new(),
+                            )
+                            .0
+                    })
+                    .sum::<f64>()
+            };
 
-                    new_tradable_picks.push(round);
-                }
+            for matchup in week.matchups.iter_mut() {
+                matchup.result = Some(MatchupResult {
+                    home_points: get_week_points(&matchup.home),
+                    away_points: get_week_points(&matchup.away),
+                });
             }
 
-            self.tradable_picks = Some(new_tradable_picks);
+            is_updated = true;
         }
-        Ok(is_done)
+
+        is_updated
     }
 
-    pub fn draft_player_dynasty(
-        &mut self,
-        user_id: &str,
-        player: &PoolPlayerInfo,
-        draft_order: &Vec<String>, // being used as draft order.
-        settings: &PoolSettings,
-        has_privileges: bool,
-    ) -> Result<bool, AppError> {
-        // First, validate that the player selected is not already picked by any of the other poolers.
+    pub fn get_standings(&self, head_to_head_settings: &HeadToHeadSettings) -> Vec<Standing> {
+        // Compute the standings from the matchups results,
+        // sorted by standings points and then by points made.
+        let mut standings: HashMap<String, Standing> = HashMap::new();
 
-        for (id, roster) in &self.pooler_roster {
-            if roster.validate_player_possession(player.id) {
-                return Err(AppError::CustomError {
-                    msg: format!("{} is already picked by {}.", player.name, id),
-                });
+        for matchup in self.weeks.iter().flat_map(|week| week.matchups.iter()) {
+            for participant in [&matchup.home, &matchup.away] {
+                standings
+                    .entry(participant.clone())
+                    .or_insert_with(|| Standing::new(participant));
             }
-        }
-        // Find the next draft id for dynasty type pool.
-        let next_drafter = self.find_dynasty_next_drafter(draft_order)?;
 
-        if !has_privileges && next_drafter != user_id {
-            return Err(AppError::CustomError {
-                msg: format!("It is {}'s turn.", next_drafter),
-            });
+            let Some(result) = &matchup.result else {
+                continue;
+            };
+
+            if let Some(standing) = standings.get_mut(&matchup.home) {
+                standing.record(
+                    result.home_points,
+                    result.away_points,
+                    head_to_head_settings,
+                );
+            }
+            if let Some(standing) = standings.get_mut(&matchup.away) {
+                standing.record(
+                    result.away_points,
+                    result.home_points,
+                    head_to_head_settings,
+                );
+            }
         }
 
-        // Add the drafted player if everything goes right.
-        self.add_drafted_player(player, &next_drafter, settings)?;
+        let mut standings: Vec<Standing> = standings.into_values().collect();
+        standings.sort_by(|a, b| {
+            b.points
+                .cmp(&a.points)
+                .then_with(|| cmp_points(b.points_for, a.points_for))
+        });
 
-        self.players.insert(player.id.to_string(), player.clone());
-        self.players_name_drafted.push(player.id);
+        standings
+    }
+}
 
-        // Get the maximum number of player a user can draft.
-        let mut continue_count = 0;
-        let max_player_count = settings.number_forwards
-            + settings.number_defenders
-            + settings.number_goalies
-            + settings.number_reservists;
+// Private ranked list of players a pooler wants to draft.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, ToSchema)]
+pub struct DraftQueue {
+    pub players: Vec<PoolPlayerInfo>,
 
-        // Fill the drafters that have completed with 0.
-        loop {
-            let new_next_drafter = self.find_dynasty_next_drafter(draft_order)?;
-            if self.get_roster_count(&new_next_drafter)? >= max_player_count as usize {
-                if self.is_draft_done(settings)? {
-                    return Ok(true);
-                }
-                self.players_name_drafted.push(0); // Id 0 means the players did not draft because his roster is already full
+    // Draft the first available player of the queue as soon as it is the pooler's turn.
+    pub auto_draft: bool,
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct DailyRosterPoints {
+    pub roster: Roster,
+    pub is_cumulated: bool,
+
+    // Players that played once the games played limit of their position was reached, they make no points.
+    pub capped_players: Option<Vec<String>>,
+}
+
+// Remove the points of the players that played beyond the games left at their position.
+// The best performances of the day are kept first.
+fn cap_games_played<T>(
+    players: &mut HashMap<String, Option<T>>,
+    remaining_games: Option<u16>,
+    get_points: impl Fn(&T) -> f64,
+) -> Vec<String> {
+    let Some(remaining_games) = remaining_games else {
+        return Vec::new();
+    };
+
+    let mut played: Vec<(String, f64)> = players
+        .iter()
+        .filter_map(|(player_id, points)| {
+            points
+                .as_ref()
+                .map(|points| (player_id.clone(), get_points(points)))
+        })
+        .collect();
+    played.sort_by(|a, b| cmp_points(b.1, a.1).then(a.0.cmp(&b.0)));
+
+    played
+        .into_iter()
+        .skip(remaining_games as usize)
+        .map(|(player_id, _)| {
+            players.insert(player_id.clone(), None);
+            player_id
+        })
+        .collect()
+}
+
+// The points made on a given day by every NHL players that played, mapped by player id.
+#[derive(Debug, Clone, Default)]
+pub struct DailyPlayersPoints {
+    pub skaters: HashMap<String, SkaterPoints>,
+    pub goalies: HashMap<String, GoalyPoints>,
+}
+
+impl DailyRosterPoints {
+    pub fn new(pooler_roster: &PoolerRoster, players: &HashMap<String, PoolPlayerInfo>) -> Self {
+        // Create the daily roster from the players currently in the pooler starting lineup.
+        // The utility players make the points of their primary position.
+        let (utility_defenders, utility_forwards): (Vec<&u32>, Vec<&u32>) =
+            pooler_roster.get_utility().iter().partition(|id| {
+                players
+                    .get(&id.to_string())
+                    .is_some_and(|player| matches!(player.position, Position::D))
+            });
+
+        Self {
+            roster: Roster {
+                F: pooler_roster
+                    .chosen_forwards
+                    .iter()
+                    .chain(utility_forwards)
+                    .map(|id| (id.to_string(), None))
+                    .collect(),
+                D: pooler_roster
+                    .chosen_defenders
+                    .iter()
+                    .chain(utility_defenders)
+                    .map(|id| (id.to_string(), None))
+                    .collect(),
+                G: pooler_roster
+                    .chosen_goalies
+                    .iter()
+                    .map(|id| (id.to_string(), None))
+                    .collect(),
+            },
+            is_cumulated: false,
+            capped_players: None,
+        }
+    }
 
-                continue_count += 1;
+    pub fn cumulate(
+        &mut self,
+        daily_players_points: &DailyPlayersPoints,
+        remaining_games: Option<&GamesPlayedLimits>,
+        pool_settings: &PoolSettings,
+    ) {
+        self.fill_points(daily_players_points);
+
+        // The players beyond the games played limits do not make points.
+        let remaining_games = remaining_games.cloned().unwrap_or_default();
+        let mut capped_players =
+            cap_games_played(&mut self.roster.F, remaining_games.forwards, |points| {
+                points.get_total_points(&pool_settings.forwards_settings)
+            });
+        capped_players.extend(cap_games_played(
+            &mut self.roster.D,
+            remaining_games.defense,
+            |points| points.get_total_points(&pool_settings.defense_settings),
+        ));
+        capped_players.extend(cap_games_played(
+            &mut self.roster.G,
+            remaining_games.goalies,
+            |points| points.get_total_points(&pool_settings.goalies_settings),
+        ));
+        self.capped_players = (!capped_players.is_empty()).then_some(capped_players);
+
+        self.is_cumulated = true;
+    }
 
-                if continue_count >= draft_order.len() {
-                    break;
-                }
-                continue;
-            }
-            break;
+    pub fn fill_points(&mut self, daily_players_points: &DailyPlayersPoints) {
+        // Fill the points of every player of the roster, players that did not play keep None.
+        for (player_id, skater_points) in self.roster.F.iter_mut().chain(self.roster.D.iter_mut()) {
+            *skater_points = daily_players_points.skaters.get(player_id).cloned();
         }
 
-        self.is_draft_done(settings)
+        for (player_id, goalie_points) in self.roster.G.iter_mut() {
+            *goalie_points = daily_players_points.goalies.get(player_id).cloned();
+        }
     }
 
-    pub fn find_dynasty_next_drafter(
-        &mut self,
-        draft_order: &Vec<String>, // being used as draft order.
-    ) -> Result<String, AppError> {
-        // Draft the right player in dynasty mode.
-        // This takes into account the trade that have been traded during last season (past_tradable_picks).
+    pub fn get_total_points(
+        &self,
+        pool_settings: &PoolSettings,
+        forwards_points: &mut HashMap<String, (f64, u16)>,
+        defenders_points: &mut HashMap<String, (f64, u16)>,
+        goalies_points: &mut HashMap<String, (f64, u16)>,
+    ) -> (f64, u16) {
+        let mut total_points = 0.0;
+        let mut number_of_games = 0;
 
-        let past_tradable_picks =
-            self.past_tradable_picks
-                .as_ref()
-                .ok_or_else(|| AppError::CustomError {
-                    msg: "Pool context does not exist.".to_string(),
-                })?;
+        // Forwards
+        for (player_id, skater_points) in &self.roster.F {
+            if let Some(skater_points) = skater_points {
+                let daily_points = skater_points.get_total_points(&pool_settings.forwards_settings);
+                total_points += daily_points;
+                number_of_games += 1;
+                if let Some((points, number_of_games)) = forwards_points.get_mut(player_id) {
+                    *points += daily_points;
+                    *number_of_games += 1;
+                } else {
+                    forwards_points.insert(player_id.clone(), (daily_points, 1));
+                }
+            }
+        }
 
-        // To make sure the program never go into an infinite loop. we use a counter.
-        let mut next_drafter;
-        let nb_players_drafted = self.players_name_drafted.len();
+        // Defenders
+        for (player_id, skater_points) in &self.roster.D {
+            if let Some(skater_points) = skater_points {
+                let daily_points = skater_points.get_total_points(&pool_settings.defense_settings);
+                total_points += daily_points;
+                number_of_games += 1;
 
-        let index_draft = nb_players_drafted % draft_order.len();
-        // Fetch the next drafter without considering if the trade has been traded yet.
-        next_drafter = &draft_order[index_draft];
+                if let Some((points, number_of_games)) = defenders_points.get_mut(player_id) {
+                    *points += daily_points;
+                    *number_of_games += 1;
+                } else {
+                    defenders_points.insert(player_id.clone(), (daily_points, 1));
+                }
+            }
+        }
 
-        if nb_players_drafted < (past_tradable_picks.len() * draft_order.len()) {
-            // use the tradable_picks to see if the pick got traded so it is to the person owning the pick to draft.
+        // Goalies
+        for (player_id, goalie_points) in &self.roster.G {
+            if let Some(goalie_points) = goalie_points {
+                let daily_points = goalie_points.get_total_points(&pool_settings.goalies_settings);
+                total_points += daily_points;
+                number_of_games += 1;
 
-            next_drafter =
-                &past_tradable_picks[nb_players_drafted / draft_order.len()][next_drafter];
+                if let Some((points, number_of_games)) = goalies_points.get_mut(player_id) {
+                    *points += daily_points;
+                    *number_of_games += 1;
+                } else {
+                    goalies_points.insert(player_id.clone(), (daily_points, 1));
+                }
+            }
         }
 
-        Ok(next_drafter.clone())
+        (total_points, number_of_games)
     }
 
-    pub fn draft_player(
-        &mut self,
-        user_id: &str,
-        player: &PoolPlayerInfo,
-        draft_order: &Vec<String>, // being used as draft order.
-        settings: &PoolSettings,
-        has_privileges: bool,
-    ) -> Result<bool, AppError> {
-        // Draft the right player in normal mode.
-        // Taking only into account the draft order
+    pub fn get_pooler_totals(&self, pool_settings: &PoolSettings) -> PoolerTotals {
+        // Points made by the roster on that day, by position.
+        let mut totals = PoolerTotals::default();
 
-        for roster in self.pooler_roster.values() {
-            if roster.validate_player_possession(player.id) {
-                return Err(AppError::CustomError {
-                    msg: "This player is already picked.".to_string(),
-                });
-            }
+        for skater_points in self.roster.F.values().flatten() {
+            totals.forwards.points +=
+                skater_points.get_total_points(&pool_settings.forwards_settings);
+            totals.forwards.games += 1;
         }
 
-        // there is no final rank so this is the newly created draft logic.
-
-        let players_drafted = self.players_name_drafted.len();
+        for skater_points in self.roster.D.values().flatten() {
+            totals.defense.points +=
+                skater_points.get_total_points(&pool_settings.defense_settings);
+            totals.defense.games += 1;
+        }
 
-        // Snake draft, reverse draft order each round.
-        let round = players_drafted / draft_order.len();
+        for goalie_points in self.roster.G.values().flatten() {
+            totals.goalies.points +=
+                goalie_points.get_total_points(&pool_settings.goalies_settings);
+            totals.goalies.games += 1;
+        }
 
-        let index = if round % 2 == 1 {
-            draft_order.len() - 1 - (players_drafted % draft_order.len())
-        } else {
-            players_drafted % draft_order.len()
-        };
+        totals
+    }
 
-        let next_drafter = &draft_order[index];
+    pub fn get_players_points(&self, pool_settings: &PoolSettings) -> Vec<(String, f64)> {
+        // Points made on that day by each player of the roster that played.
+        let forwards = self
+            .roster
+            .F
+            .iter()
+            .filter_map(|(player_id, skater_points)| {
+                skater_points.as_ref().map(|skater_points| {
+                    (
+                        player_id.clone(),
+                        skater_points.get_total_points(&pool_settings.forwards_settings),
+                    )
+                })
+            });
 
-        if !has_privileges && next_drafter != user_id {
-            return Err(AppError::CustomError {
-                msg: format!("It is {}'s turn.", next_drafter),
+        let defenders = self
+            .roster
+            .D
+            .iter()
+            .filter_map(|(player_id, skater_points)| {
+                skater_points.as_ref().map(|skater_points| {
+                    (
+                        player_id.clone(),
+                        skater_points.get_total_points(&pool_settings.defense_settings),
+                    )
+                })
             });
-        }
 
-        // Add the drafted player if everything goes right.
-        self.add_drafted_player(player, &next_drafter, settings)?;
+        let goalies = self
+            .roster
+            .G
+            .iter()
+            .filter_map(|(player_id, goalie_points)| {
+                goalie_points.as_ref().map(|goalie_points| {
+                    (
+                        player_id.clone(),
+                        goalie_points.get_total_points(&pool_settings.goalies_settings),
+                    )
+                })
+            });
 
-        self.players.insert(player.id.to_string(), player.clone());
-        self.players_name_drafted.push(player.id);
-        self.is_draft_done(settings)
+        forwards.chain(defenders).chain(goalies).collect()
     }
+}
 
-    pub fn undo_draft_player(
-        &mut self,
-        participants: &Vec<String>,
-        settings: &PoolSettings,
-    ) -> Result<(), AppError> {
-        // validate there is something to undo.
-
-        let latest_pick_id;
+// Season totals of a player in a pool, the months are formatted as YYYY-MM.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, ToSchema)]
+pub struct PlayerTotals {
+    pub games: u16,
+    pub points: f64,
+    pub points_by_month: HashMap<String, f64>,
+}
 
-        loop {
-            match self.players_name_drafted.pop() {
-                Some(player_id) => {
-                    if player_id > 0 {
-                        latest_pick_id = player_id; // found the last drafted player.
-                        break;
-                    }
-                }
-                None => {
-                    return Err(AppError::CustomError {
-                        msg: "Ther is nothing to undo yet.".to_string(),
-                    })
-                }
-            }
-        }
+impl PlayerTotals {
+    pub fn add(&mut self, date: &str, points: f64) {
+        self.games += 1;
+        self.points += points;
+        *self
+            .points_by_month
+            .entry(date.chars().take(7).collect())
+            .or_default() += points;
+    }
 
-        let pick_number = self.players_name_drafted.len();
-        let latest_drafter;
+    pub fn remove(&mut self, date: &str, points: f64) {
+        self.games = self.games.saturating_sub(1);
+        self.points -= points;
+        *self
+            .points_by_month
+            .entry(date.chars().take(7).collect())
+            .or_default() -= points;
+    }
+}
 
-        match (&settings.dynasty_settings, &self.past_tradable_picks) {
-            (Some(dynasty_settings), Some(past_tradable_picks)) => {
-                // This comes from a Dynasty draft.
+// Season totals of a pooled player under the pool scoring settings.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct PoolPlayerStats {
+    pub player: PoolPlayerInfo,
+    pub games_played: u16,
+    pub total_points: f64,
+    pub points_by_month: HashMap<String, f64>,
+}
 
-                let nb_tradable_picks = dynasty_settings.tradable_picks;
+// Points and number of games cumulated by the players of one position.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, ToSchema)]
+pub struct PositionTotals {
+    pub points: f64,
+    pub games: u16,
+}
 
-                let index = pick_number % participants.len();
+// Cumulative points of a pooler by position.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, ToSchema)]
+pub struct PoolerTotals {
+    pub forwards: PositionTotals,
+    pub defense: PositionTotals,
+    pub goalies: PositionTotals,
+}
 
-                let next_drafter = &participants[index];
+impl PoolerTotals {
+    pub fn add(&mut self, other: &PoolerTotals) {
+        for (position, other_position) in [
+            (&mut self.forwards, &other.forwards),
+            (&mut self.defense, &other.defense),
+            (&mut self.goalies, &other.goalies),
+        ] {
+            position.points += other_position.points;
+            position.games += other_position.games;
+        }
+    }
 
-                if pick_number < nb_tradable_picks as usize * participants.len() {
-                    // use the tradable_picks to see who will draft next.
-                    latest_drafter =
-                        past_tradable_picks[pick_number / participants.len()][next_drafter].clone();
-                } else {
-                    // Use the draft order to see who draft next.
-                    latest_drafter = next_drafter.clone();
-                }
-            }
-            _ => {
-                // this comes from a newly created draft.
+    pub fn remove(&mut self, other: &PoolerTotals) {
+        for (position, other_position) in [
+            (&mut self.forwards, &other.forwards),
+            (&mut self.defense, &other.defense),
+            (&mut self.goalies, &other.goalies),
+        ] {
+            position.points -= other_position.points;
+            position.games = position.games.saturating_sub(other_position.games);
+        }
+    }
+}
 
-                let round = pick_number / participants.len();
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct Roster {
+    #[schema(value_type = HashMap<String, SkaterPoints>)]
+    pub F: HashMap<String, Option<SkaterPoints>>,
+    #[schema(value_type = HashMap<String, SkaterPoints>)]
+    pub D: HashMap<String, Option<SkaterPoints>>,
+    #[schema(value_type = HashMap<String, GoalyPoints>)]
+    pub G: HashMap<String, Option<GoalyPoints>>,
+}
 
-                // Snake draft, reverse draft order each round.
-                let index = if round % 2 == 1 {
-                    participants.len() - 1 - (pick_number % participants.len()) // reversed
-                } else {
-                    pick_number % participants.len() // Original
-                };
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct SkaterPoints {
+    pub G: u8,
+    pub A: u8,
+    pub SOG: Option<u8>,
 
-                latest_drafter = participants[index].clone();
-            }
-        }
+    // Optional scoring categories.
+    pub S: Option<u8>,   // Shots.
+    pub HIT: Option<u8>, // Hits.
+    pub BLK: Option<u8>, // Blocked shots.
+    pub PIM: Option<u8>, // Penalty minutes.
+    pub PPP: Option<u8>, // Power-play points.
+    pub SHP: Option<u8>, // Short-handed points.
+    pub FOW: Option<u8>, // Faceoff wins.
+}
 
-        // Remove the player from the player roster.
+// Compare points rounded to the hundredth so the floating point errors
+// accumulated by the decimal scoring do not break the ties.
+pub fn cmp_points(a: f64, b: f64) -> std::cmp::Ordering {
+    let round = |points: f64| (points * 100.0).round() as i64;
+    round(a).cmp(&round(b))
+}
 
-        self.remove_player_from_roster(latest_pick_id, &latest_drafter)?;
-        self.players.remove(&latest_pick_id.to_string()); // Also remove the player from the pool players list.
-        Ok(())
+// Points made in an optional scoring category.
+fn get_category_points(stat: Option<u8>, points_per_stat: Option<f64>) -> f64 {
+    match (stat, points_per_stat) {
+        (Some(stat), Some(points_per_stat)) => stat as f64 * points_per_stat,
+        _ => 0.0,
     }
+}
 
-    pub fn remove_player_from_roster(
-        &mut self,
-        player_id: u32,
-        user_id: &str,
-    ) -> Result<(), AppError> {
-        // Remove a player from the roster.
-        if let Some(roster) = self.pooler_roster.get_mut(user_id) {
-            if roster.remove_forward(player_id) {
-                return Ok(());
-            };
-            if roster.remove_defender(player_id) {
-                return Ok(());
-            };
-            if roster.remove_goalie(player_id) {
-                return Ok(());
-            };
-            if roster.remove_reservist(player_id) {
-                return Ok(());
-            };
-        }
+impl SkaterPoints {
+    pub fn get_total_points(&self, skater_settings: &SkaterSettings) -> f64 {
+        let mut total_points = 0.0;
 
-        Err(AppError::CustomError {
-            msg: "The player could not be removed".to_string(),
-        }) // could not be removed
-    }
+        total_points += self.G as f64 * skater_settings.points_per_goals
+            + self.A as f64 * skater_settings.points_per_assists;
 
-    pub fn add_player_to_reservists(
-        &mut self,
-        player_id: u32,
-        user_id: &str,
-    ) -> Result<(), AppError> {
-        // Add a player to the reservist of a pooler.
-        if let Some(roster) = self.pooler_roster.get_mut(user_id) {
-            roster.chosen_reservists.push(player_id);
-            return Ok(());
+        if let Some(shootout_goal) = self.SOG {
+            total_points += shootout_goal as f64 * skater_settings.points_per_shootout_goals;
         }
 
-        Err(AppError::CustomError {
-            msg: "The player could not be added".to_string(),
-        }) // could not be added
-    }
+        if self.G >= 3 {
+            total_points += skater_settings.points_per_hattricks;
+        }
 
-    pub fn trade_roster_player(
-        &mut self,
-        player_id: u32,
-        user_giver: &str,
-        user_receiver: &str,
-    ) -> Result<(), AppError> {
-        // Trade 1 player.
-        self.remove_player_from_roster(player_id, user_giver)?;
-        self.add_player_to_reservists(player_id, user_receiver)
+        total_points += get_category_points(self.S, skater_settings.points_per_shots)
+            + get_category_points(self.HIT, skater_settings.points_per_hits)
+            + get_category_points(self.BLK, skater_settings.points_per_blocked_shots)
+            + get_category_points(self.PIM, skater_settings.points_per_penalty_minutes)
+            + get_category_points(self.PPP, skater_settings.points_per_power_play_points)
+            + get_category_points(self.SHP, skater_settings.points_per_short_handed_points)
+            + get_category_points(self.FOW, skater_settings.points_per_faceoff_wins);
+
+        total_points
     }
+}
 
-    pub fn trade_roster_items(&mut self, trade: &Trade) -> Result<(), AppError> {
-        // Make sure the trade is valid before executing it.
-        self.validate_trade(trade)?;
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct GoalyPoints {
+    pub G: u8,
+    pub A: u8,
+    pub W: bool,
+    pub SO: bool,
+    pub OT: bool,
+
+    // Optional scoring categories.
+    pub SV: Option<u8>, // Saves.
+    pub GA: Option<u8>, // Goals against.
+}
+
+impl GoalyPoints {
+    pub fn get_total_points(&self, goalies_settings: &GoaliesSettings) -> f64 {
+        let mut total_points = 0.0;
+        total_points += self.G as f64 * goalies_settings.points_per_goals
+            + self.A as f64 * goalies_settings.points_per_assists;
 
-        // Migrate players "from" -> "to"
-        for player_id in trade.from_items.players.iter() {
-            self.trade_roster_player(*player_id, &trade.proposed_by, &trade.ask_to)?;
+        if self.W {
+            total_points += goalies_settings.points_per_wins;
         }
 
-        // Migrate players "to" -> "from"
-        for player_id in trade.to_items.players.iter() {
-            self.trade_roster_player(*player_id, &trade.ask_to, &trade.proposed_by)?;
+        if self.SO {
+            total_points += goalies_settings.points_per_shutouts;
         }
 
-        // Migrate picks "from" -> "to"
-        for pick in trade.from_items.picks.iter() {
-            if let Some(tradable_picks) = &mut self.tradable_picks {
-                if let Some(owner) = tradable_picks[pick.round as usize].get_mut(&pick.from) {
-                    *owner = trade.ask_to.clone();
-                }
-            }
+        if self.OT {
+            total_points += goalies_settings.points_per_overtimes;
         }
 
-        // Migrate picks "to" -> "from"
-        for pick in trade.to_items.picks.iter() {
-            if let Some(tradable_picks) = &mut self.tradable_picks {
-                if let Some(owner) = tradable_picks[pick.round as usize].get_mut(&pick.from) {
-                    *owner = trade.proposed_by.clone();
-                }
-            }
-        }
+        total_points += get_category_points(self.SV, goalies_settings.points_per_saves);
 
-        Ok(())
+        // Goals against remove points, a goalie never has negative points for a game.
+        total_points -= get_category_points(self.GA, goalies_settings.points_per_goals_against);
+
+        total_points.max(0.0)
     }
+}
 
-    pub fn validate_trade_items(
-        &self,
-        trade_items: &TradeItems,
-        user_id: &str,
-    ) -> Result<(), AppError> {
-        // Validate that the trade items are valid for a trade side.
-        if let Some(from_pooler_roster) = self.pooler_roster.get(user_id) {
-            for player_id in &trade_items.players {
-                if !from_pooler_roster.validate_player_possession(*player_id) {
-                    return Err(AppError::CustomError {
-                        msg: "ther user does not possess one of the traded player!".to_string(),
-                    });
-                }
-            }
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct SkaterPoolPoints {
+    pub G: u8,
+    pub A: u8,
+    pub HT: u8,
+    pub SOG: u8,
+}
 
-            if let Some(tradable_picks) = &self.tradable_picks {
-                for pick in &trade_items.picks {
-                    if tradable_picks[pick.round as usize][&pick.from] != user_id {
-                        return Err(AppError::CustomError {
-                            msg: "ther user does not possess the traded pick!".to_string(),
-                        });
-                    }
-                }
-            }
-        }
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct GoalyPoolPoints {
+    pub G: u8,
+    pub A: u8,
+    pub W: u8,
+    pub SO: u8,
+    pub OT: u8,
+}
 
-        Ok(())
+impl PartialEq<PoolPlayerInfo> for PoolPlayerInfo {
+    fn eq(&self, other: &PoolPlayerInfo) -> bool {
+        self.id == other.id
     }
+}
 
-    pub fn validate_trade(&self, trade: &Trade) -> Result<(), AppError> {
-        // Validate if the full trade is valid
-
-        // does the the from or to side has items in the trade ?
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct PoolPlayerInfo {
+    pub id: u32, // ID from the NHL API.
+    pub name: String,
+    pub team: Option<u32>,
+    pub position: Position,
+    pub age: Option<u8>,
+    pub salary_cap: Option<f64>,
+    pub contract_expiration_season: Option<u32>,
 
-        if (trade.from_items.picks.len() + trade.from_items.players.len()) == 0
-            || (trade.to_items.picks.len() + trade.to_items.players.len()) == 0
-        {
-            return Err(AppError::CustomError {
-                msg: "There is no items traded on one of the 2 sides.".to_string(),
-            });
-        }
+    // Other positions the player is eligible to, updated by the players sync.
+    pub positions: Option<Vec<Position>>,
 
-        // Maximum of 5 items traded on each side ?
+    // Injury designation of the player, updated by the players sync. Not set when the player is healthy.
+    pub injury_status: Option<InjuryStatus>,
+}
 
-        if (trade.from_items.picks.len() + trade.from_items.players.len()) > 5
-            || (trade.to_items.picks.len() + trade.to_items.players.len()) > 5
-        {
-            return Err(AppError::CustomError {
-                msg: "There is to much items in the trade.".to_string(),
-            });
+impl PoolPlayerInfo {
+    pub fn get_eligible_positions(&self) -> Vec<Position> {
+        // The primary position of the player is always eligible and comes first.
+        let mut positions = vec![self.position.clone()];
+        for position in self.positions.iter().flatten() {
+            if !positions.contains(position) {
+                positions.push(position.clone());
+            }
         }
+        positions
+    }
 
-        self.validate_trade_items(&trade.from_items, &trade.proposed_by)?;
-        self.validate_trade_items(&trade.to_items, &trade.ask_to)
+    pub fn is_eligible(&self, position: &Position) -> bool {
+        self.get_eligible_positions().contains(position)
     }
 
-    pub fn get_forwards_count(&self, user_id: &str) -> Result<usize, AppError> {
-        // Get the count of forward for a pooler.
-        match self.pooler_roster.get(user_id) {
-            None => Err(AppError::CustomError {
-                msg: "The user does not exist.".to_string(),
-            }),
-            Some(roster) => Ok(roster.chosen_forwards.len()),
-        }
+    pub fn is_skater(&self) -> bool {
+        self.is_eligible(&Position::F) || self.is_eligible(&Position::D)
     }
+}
 
-    pub fn get_defenders_count(&self, user_id: &str) -> Result<usize, AppError> {
-        // Get the count of defender for a pooler.
-        match self.pooler_roster.get(user_id) {
-            None => Err(AppError::CustomError {
-                msg: "The user does not exist.".to_string(),
-            }),
-            Some(roster) => Ok(roster.chosen_defenders.len()),
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
+pub enum Position {
+    F,
+    D,
+    G,
+}
+
+impl Position {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Position::F => "F",
+            Position::D => "D",
+            Position::G => "G",
         }
     }
+}
 
-    pub fn get_goalies_count(&self, user_id: &str) -> Result<usize, AppError> {
-        // Get the count of goalies for a pooler.
-        match self.pooler_roster.get(user_id) {
-            None => Err(AppError::CustomError {
-                msg: "The user does not exist.".to_string(),
-            }),
-            Some(roster) => Ok(roster.chosen_goalies.len()),
-        }
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
+pub enum InjuryStatus {
+    InjuredReserve,
+    DayToDay,
+    Out,
+}
+
+impl InjuryStatus {
+    pub fn is_ruled_out(&self) -> bool {
+        // A day-to-day player could still play tonight.
+        !matches!(self, InjuryStatus::DayToDay)
     }
+}
 
-    pub fn get_reservists_count(&self, user_id: &str) -> Result<usize, AppError> {
-        // Get the count of reservist for a pooler.
-        match self.pooler_roster.get(user_id) {
-            None => Err(AppError::CustomError {
-                msg: "The user does not exist.".to_string(),
-            }),
-            Some(roster) => Ok(roster.chosen_reservists.len()),
-        }
+impl PartialEq<Pick> for Pick {
+    fn eq(&self, other: &Pick) -> bool {
+        self.round == other.round
+            && self.from == other.from
+            && self.get_season_offset() == other.get_season_offset()
     }
+}
 
-    pub fn get_roster_count(&self, user_id: &str) -> Result<usize, AppError> {
-        // Get the count of the full roster for a pooler.
-        Ok(self.get_forwards_count(user_id)?
-            + self.get_defenders_count(user_id)?
-            + self.get_goalies_count(user_id)?
-            + self.get_reservists_count(user_id)?)
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct Pick {
+    pub round: u8,
+    pub from: String,
+
+    // Number of seasons after the next one (the next season when not set).
+    pub season_offset: Option<u8>,
+}
+
+impl Pick {
+    pub fn get_season_offset(&self) -> usize {
+        self.season_offset.unwrap_or(0) as usize
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)] // Copy
-pub struct PoolerRoster {
-    pub chosen_forwards: Vec<u32>,
-    pub chosen_defenders: Vec<u32>,
-    pub chosen_goalies: Vec<u32>,
-    pub chosen_reservists: Vec<u32>,
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct Trade {
+    pub proposed_by: String,
+    pub ask_to: String,
+    pub from_items: TradeItems,
+    pub to_items: TradeItems,
+    pub status: TradeStatus,
+    pub id: u32,
+    pub date_created: i64,
+    pub date_accepted: i64,
+
+    // Additional legs of a multi-team trade (i.e, a 3-way trade).
+    pub extra_legs: Option<Vec<TradeLeg>>,
+
+    // Poolers involved in the trade that accepted it.
+    pub accepted_by: Option<Vec<String>>,
+
+    // When the trade is a counter offer, the id of the trade that was countered.
+    pub counter_of: Option<u32>,
+
+    // Ids of the previous trades of the negotiation, the oldest first.
+    pub negotiation: Option<Vec<u32>>,
+
+    // Poolers that voted to veto the trade during its review window.
+    pub veto_votes: Option<Vec<String>>,
 }
-impl PoolerRoster {
-    pub fn new() -> Self {
-        Self {
-            chosen_forwards: Vec::new(),
-            chosen_defenders: Vec::new(),
-            chosen_goalies: Vec::new(),
-            chosen_reservists: Vec::new(),
+
+impl Trade {
+    pub fn get_legs(&self) -> Vec<TradeLeg> {
+        // Every transfer of items between 2 poolers of the trade.
+        let mut legs = vec![
+            TradeLeg {
+                from: self.proposed_by.clone(),
+                to: self.ask_to.clone(),
+                items: self.from_items.clone(),
+            },
+            TradeLeg {
+                from: self.ask_to.clone(),
+                to: self.proposed_by.clone(),
+                items: self.to_items.clone(),
+            },
+        ];
+
+        if let Some(extra_legs) = &self.extra_legs {
+            legs.extend(extra_legs.iter().cloned());
         }
-    }
 
-    pub fn remove_forward(&mut self, player_id: u32) -> bool {
-        // Remove a forward from a pooler roster
-        self.chosen_forwards
-            .iter()
-            .position(|id| id == &player_id)
-            .map(|index| self.chosen_forwards.remove(index))
-            .is_some()
+        legs
     }
 
-    pub fn remove_defender(&mut self, player_id: u32) -> bool {
-        // Remove a defenders from a pooler roster
-        self.chosen_defenders
-            .iter()
-            .position(|id| id == &player_id)
-            .map(|index| self.chosen_defenders.remove(index))
-            .is_some()
+    pub fn get_participants(&self) -> Vec<String> {
+        // Every pooler involved in the trade, the one that proposed it first.
+        let mut participants = vec![self.proposed_by.clone(), self.ask_to.clone()];
+
+        for leg in self.extra_legs.iter().flatten() {
+            for participant in [&leg.from, &leg.to] {
+                if !participants.contains(participant) {
+                    participants.push(participant.clone());
+                }
+            }
+        }
+
+        participants
     }
 
-    pub fn remove_goalie(&mut self, player_id: u32) -> bool {
-        // Remove a goalies from a pooler roster
-        self.chosen_goalies
+    pub fn is_paused(&self, orphaned_teams: &[String]) -> bool {
+        // The trades involving an orphaned team wait for the team to be adopted.
+        self.get_participants()
             .iter()
-            .position(|id| id == &player_id)
-            .map(|index| self.chosen_goalies.remove(index))
-            .is_some()
+            .any(|participant| orphaned_teams.contains(participant))
     }
 
-    pub fn remove_reservist(&mut self, player_id: u32) -> bool {
-        // Remove a reservist from a pooler roster
-        self.chosen_reservists
+    pub fn is_accepted_by_everyone(&self) -> bool {
+        // The pooler that proposed the trade does not need to accept it.
+        let accepted_by = self.accepted_by.as_deref().unwrap_or_default();
+
+        self.get_participants()
             .iter()
-            .position(|id| id == &player_id)
-            .map(|index| self.chosen_reservists.remove(index))
-            .is_some()
+            .filter(|participant| **participant != self.proposed_by)
+            .all(|participant| accepted_by.contains(participant))
     }
+}
 
-    pub fn validate_player_possession(&self, player_id: u32) -> bool {
-        self.chosen_forwards.contains(&player_id)
-            || self.chosen_defenders.contains(&player_id)
-            || self.chosen_goalies.contains(&player_id)
-            || self.chosen_reservists.contains(&player_id)
+// A player that a pooler is willing to trade.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct TradeBlockListing {
+    pub player_id: u32,
+    pub note: Option<String>,
+    // Positions the pooler would like to receive in exchange.
+    pub wanted_positions: Vec<Position>,
+    pub date_listed: i64,
+}
+
+// The players followed by a pooler in a pool, only visible to this pooler.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct Watchlist {
+    pub user_id: String,
+    pub pool_name: String,
+    pub player_ids: Vec<u32>,
+}
+
+impl Watchlist {
+    pub fn new(user_id: &str, pool_name: &str) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            pool_name: pool_name.to_string(),
+            player_ids: Vec::new(),
+        }
     }
 }
 
-#[allow(non_snake_case)]
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct DailyRosterPoints {
-    pub roster: Roster,
-    pub is_cumulated: bool,
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct TradeLeg {
+    pub from: String,
+    pub to: String,
+    pub items: TradeItems,
 }
 
-impl DailyRosterPoints {
-    pub fn get_total_points(
-        &self,
-        pool_settings: &PoolSettings,
-        forwards_points: &mut HashMap<String, (u16, u16)>,
-        defenders_points: &mut HashMap<String, (u16, u16)>,
-        goalies_points: &mut HashMap<String, (u16, u16)>,
-    ) -> (u16, u16) {
-        let mut total_points = 0;
-        let mut number_of_games = 0;
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct TradeItems {
+    pub players: Vec<u32>, // Id of the player
+    pub picks: Vec<Pick>,
 
-        // Forwards
-        for (player_id, skater_points) in &self.roster.F {
-            if let Some(skater_points) = skater_points {
-                let daily_points = skater_points.get_total_points(&pool_settings.forwards_settings);
-                total_points += daily_points;
-                number_of_games += 1;
-                if let Some((points, number_of_games)) = forwards_points.get_mut(player_id) {
-                    *points += daily_points;
-                    *number_of_games += 1;
-                } else {
-                    forwards_points.insert(player_id.clone(), (daily_points, 1));
-                }
-            }
-        }
+    // Percentage of the cap hit retained by the giver for some of the traded players (player id -> percentage).
+    pub retained_salary: Option<HashMap<String, u8>>,
 
-        // Defenders
-        for (player_id, skater_points) in &self.roster.D {
-            if let Some(skater_points) = skater_points {
-                let daily_points = skater_points.get_total_points(&pool_settings.defense_settings);
-                total_points += daily_points;
-                number_of_games += 1;
+    // Picks that upgrade to another round depending on the final rank of their original owner.
+    pub conditional_picks: Option<Vec<ConditionalPick>>,
+}
 
-                if let Some((points, number_of_games)) = defenders_points.get_mut(player_id) {
-                    *points += daily_points;
-                    *number_of_games += 1;
-                } else {
-                    defenders_points.insert(player_id.clone(), (daily_points, 1));
-                }
+impl TradeItems {
+    pub fn get_picks(&self) -> Vec<&Pick> {
+        // The picks traded, including the conditional ones.
+        self.picks
+            .iter()
+            .chain(
+                self.conditional_picks
+                    .iter()
+                    .flatten()
+                    .map(|conditional_pick| &conditional_pick.pick),
+            )
+            .collect()
+    }
+}
+
+// A pick traded like the others, whose owner receives the pick of the upgraded round instead
+// if the condition is met once the pool is marked as final (i.e, a 2nd round pick that upgrades to a 1st).
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct ConditionalPick {
+    pub pick: Pick,
+    pub condition: PickCondition,
+    pub upgraded_round: u8,
+}
+
+// Condition on the final rank (1 = first) of the original owner of a conditional pick.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub enum PickCondition {
+    // Finishes in the first n poolers.
+    FinishesTop(u8),
+    // Finishes in the last n poolers.
+    FinishesBottom(u8),
+    // Finishes between these ranks (inclusive).
+    FinishesBetween {
+        from: u8,
+        to: u8,
+    },
+    // The nested conditions are not expanded again in the api schema.
+    #[schema(no_recursion)]
+    All(Vec<PickCondition>),
+    #[schema(no_recursion)]
+    Any(Vec<PickCondition>),
+}
+
+impl PickCondition {
+    pub fn is_met(&self, rank: usize, number_poolers: usize) -> bool {
+        match self {
+            PickCondition::FinishesTop(n) => rank <= *n as usize,
+            PickCondition::FinishesBottom(n) => rank + (*n as usize) > number_poolers,
+            PickCondition::FinishesBetween { from, to } => {
+                (*from as usize..=*to as usize).contains(&rank)
             }
+            PickCondition::All(conditions) => conditions
+                .iter()
+                .all(|condition| condition.is_met(rank, number_poolers)),
+            PickCondition::Any(conditions) => conditions
+                .iter()
+                .any(|condition| condition.is_met(rank, number_poolers)),
         }
+    }
 
-        // Goalies
-        for (player_id, goalie_points) in &self.roster.G {
-            if let Some(goalie_points) = goalie_points {
-                let daily_points = goalie_points.get_total_points(&pool_settings.goalies_settings);
-                total_points += daily_points;
-                number_of_games += 1;
+    pub fn validate(&self, number_poolers: u8) -> Result<(), AppError> {
+        let is_valid_rank = |rank: u8| (1..=number_poolers).contains(&rank);
 
-                if let Some((points, number_of_games)) = goalies_points.get_mut(player_id) {
-                    *points += daily_points;
-                    *number_of_games += 1;
-                } else {
-                    goalies_points.insert(player_id.clone(), (daily_points, 1));
+        let is_valid = match self {
+            PickCondition::FinishesTop(n) | PickCondition::FinishesBottom(n) => is_valid_rank(*n),
+            PickCondition::FinishesBetween { from, to } => {
+                is_valid_rank(*from) && is_valid_rank(*to) && from <= to
+            }
+            PickCondition::All(conditions) | PickCondition::Any(conditions) => {
+                for condition in conditions {
+                    condition.validate(number_poolers)?;
                 }
+                !conditions.is_empty()
             }
+        };
+
+        if !is_valid {
+            return Err(AppError::CustomError {
+                msg: format!(
+                    "The condition of a pick needs ranks between 1 and {}.",
+                    number_poolers
+                ),
+            });
         }
 
-        (total_points, number_of_games)
+        Ok(())
     }
 }
-#[allow(non_snake_case)]
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct Roster {
-    pub F: HashMap<String, Option<SkaterPoints>>,
-    pub D: HashMap<String, Option<SkaterPoints>>,
-    pub G: HashMap<String, Option<GoalyPoints>>,
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub enum TradeStatus {
+    NEW,       // trade created by a requester (not yet ACCEPTED/CANCELLED/REFUSED)
+    ACCEPTED,  // trade accepted items were officially traded
+    CANCELLED, // items were not traded cancelled by the requester
+    REFUSED,   // items were not traded cancelled by the one requested for the traded
+    COUNTERED, // items were not traded, the one requested for the trade made a counter offer
+    REVIEWING, // trade accepted by everyone, items will be traded if it is not vetoed during the review window
+    VETOED,    // items were not traded, the trade was vetoed during its review window
+    EXPIRED,   // items were not traded, the trade was not answered before its expiration
 }
 
-#[allow(non_snake_case)]
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct SkaterPoints {
-    pub G: u8,
-    pub A: u8,
-    pub SOG: Option<u8>,
+// Actor of the pool events that are triggered by the server (i.e, pick timer expiry).
+pub const SERVER_ACTOR: &str = "server";
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub enum PoolEventType {
+    CreatePool,
+    DeletePool,
+    UpdatePoolSettings,
+    StartDraft,
+    DraftPlayer,
+    UndoDraftPlayer,
+    AutoDraftPlayer,
+    PauseDraft,
+    ResumeDraft,
+    RollbackDraft,
+    TradeDraftPicks,
+    NominatePlayer,
+    BidPlayer,
+    CloseBid,
+    CancelNomination,
+    AddPlayer,
+    RemovePlayer,
+    CreateTrade,
+    DeleteTrade,
+    RespondTrade,
+    CounterTrade,
+    VetoTrade,
+    CompleteTradeReview,
+    ExpireTrades,
+    FillSpot,
+    SetAutoLineup,
+    AutoFillLineup,
+    ModifyRoster,
+    ProtectPlayers,
+    CompleteProtection,
+    MarkAsFinal,
+    GenerateDynasty,
+    TransferOwnership,
+    RenamePool,
+    UpdateContext,
+    RecumulatePoints,
+    UpdateTeamManagers,
+    UpdateTeamProfile,
+    UpdateTradeBlock,
+    ClaimTeam,
+    MergeAccounts,
+    DeleteAccount,
+    SkipPick,
+    UpdateDraftOrder,
+    UpdateOrphanedTeam,
+    AdoptTeam,
+    ResetWeeklyAcquisitions,
 }
 
-impl SkaterPoints {
-    pub fn get_total_points(&self, skater_settings: &SkaterSettings) -> u16 {
-        let mut total_points = 0;
+impl PoolEventType {
+    pub fn get_update_type(&self) -> Option<PoolUpdateType> {
+        // The events pushed live to the season dashboard.
+        match self {
+            PoolEventType::CreateTrade
+            | PoolEventType::DeleteTrade
+            | PoolEventType::RespondTrade
+            | PoolEventType::CounterTrade
+            | PoolEventType::VetoTrade
+            | PoolEventType::CompleteTradeReview
+            | PoolEventType::ExpireTrades => Some(PoolUpdateType::Trade),
+            PoolEventType::AddPlayer
+            | PoolEventType::RemovePlayer
+            | PoolEventType::FillSpot
+            | PoolEventType::SetAutoLineup
+            | PoolEventType::AutoFillLineup
+            | PoolEventType::ModifyRoster
+            | PoolEventType::ProtectPlayers
+            | PoolEventType::CompleteProtection => Some(PoolUpdateType::Roster),
+            PoolEventType::UpdateTradeBlock => Some(PoolUpdateType::TradeBlock),
+            _ => None,
+        }
+    }
+}
 
-        total_points += self.G as u16 * skater_settings.points_per_goals as u16
-            + self.A as u16 * skater_settings.points_per_assists as u16;
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub enum PoolUpdateType {
+    Trade,
+    Roster,
+    Score,
+    Achievement,
+    TradeBlock,
+    LiveScore,
+}
 
-        if let Some(shootout_goal) = self.SOG {
-            total_points += shootout_goal as u16 * skater_settings.points_per_shootout_goals as u16;
-        }
+// Live update of an in-season pool, pushed to the clients listening to the pool events.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct PoolUpdate {
+    pub pool_name: String,
+    pub update_type: PoolUpdateType,
+    pub date_created: i64,
+    pub trades: Option<Vec<Trade>>,
+    pub pooler_roster: Option<HashMap<String, PoolerRoster>>,
+    pub cumulative_totals: Option<HashMap<String, PoolerTotals>>,
+    pub matchup_schedule: Option<MatchupSchedule>,
 
-        if self.G >= 3 {
-            total_points += skater_settings.points_per_hattricks as u16;
-        }
+    // The achievements just earned by the participants.
+    pub achievements: Option<Vec<Achievement>>,
 
-        total_points
-    }
-}
+    pub trade_block: Option<HashMap<String, Vec<TradeBlockListing>>>,
 
-#[allow(non_snake_case)]
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct GoalyPoints {
-    pub G: u8,
-    pub A: u8,
-    pub W: bool,
-    pub SO: bool,
-    pub OT: bool,
+    // Provisional points of every pooler during the games of live_date.
+    pub live_date: Option<String>,
+    pub live_score: Option<HashMap<String, DailyRosterPoints>>,
 }
 
-impl GoalyPoints {
-    pub fn get_total_points(&self, goalies_settings: &GoaliesSettings) -> u16 {
-        let mut total_points = 0;
-        total_points += self.G as u16 * goalies_settings.points_per_goals as u16
-            + self.A as u16 * goalies_settings.points_per_assists as u16;
+impl PoolUpdate {
+    pub fn new(pool: &Pool, update_type: PoolUpdateType) -> Self {
+        // Only the members affected by the update are sent.
+        let context = pool.context.as_ref();
+
+        let (trades, pooler_roster, cumulative_totals, matchup_schedule) = match update_type {
+            PoolUpdateType::Trade => (
+                pool.trades.clone(),
+                context.map(|context| context.pooler_roster.clone()),
+                None,
+                None,
+            ),
+            PoolUpdateType::Roster => (
+                None,
+                context.map(|context| context.pooler_roster.clone()),
+                None,
+                None,
+            ),
+            PoolUpdateType::Score => (
+                None,
+                None,
+                context.and_then(|context| context.cumulative_totals.clone()),
+                context.and_then(|context| context.matchup_schedule.clone()),
+            ),
+            PoolUpdateType::Achievement
+            | PoolUpdateType::TradeBlock
+            | PoolUpdateType::LiveScore => (None, None, None, None),
+        };
 
-        if self.W {
-            total_points += goalies_settings.points_per_wins as u16;
-        }
+        let trade_block = match update_type {
+            PoolUpdateType::TradeBlock => context.map(|context| context.get_trade_block()),
+            _ => None,
+        };
 
-        if self.SO {
-            total_points += goalies_settings.points_per_shutouts as u16;
+        Self {
+            pool_name: pool.name.clone(),
+            update_type,
+            date_created: Utc::now().timestamp_millis(),
+            trades,
+            pooler_roster,
+            cumulative_totals,
+            matchup_schedule,
+            achievements: None,
+            trade_block,
+            live_date: None,
+            live_score: None,
         }
+    }
 
-        if self.OT {
-            total_points += goalies_settings.points_per_overtimes as u16;
+    pub fn new_live_score(pool: &Pool, date: &str) -> Self {
+        // The standings include the provisional points of the games in progress.
+        let context = pool.context.as_ref();
+
+        Self {
+            cumulative_totals: context.map(|context| context.get_live_totals(date, &pool.settings)),
+            live_date: Some(date.to_string()),
+            live_score: context
+                .and_then(|context| context.score_by_day.as_ref()?.get(date))
+                .cloned(),
+            ..Self::new(pool, PoolUpdateType::LiveScore)
         }
+    }
 
-        total_points
+    pub fn new_achievements(pool: &Pool, achievements: Vec<Achievement>) -> Self {
+        Self {
+            achievements: Some(achievements),
+            ..Self::new(pool, PoolUpdateType::Achievement)
+        }
     }
 }
 
-#[allow(non_snake_case)]
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct SkaterPoolPoints {
-    pub G: u8,
-    pub A: u8,
-    pub HT: u8,
-    pub SOG: u8,
+// A field of the pool document that was modified, identified by its dotted path.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct PoolFieldChange {
+    pub path: String,
+    pub before: Value,
+    pub after: Value,
 }
 
-#[allow(non_snake_case)]
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct GoalyPoolPoints {
-    pub G: u8,
-    pub A: u8,
-    pub W: u8,
-    pub SO: u8,
-    pub OT: u8,
+// Entry of the pool history, appended on every state-changing operation.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct PoolEvent {
+    pub pool_name: String,
+    // Redacted (empty) for the viewers that are not the owner or an assistant.
+    pub actor: String,
+    pub event_type: PoolEventType,
+    pub date_created: i64,
+    pub changes: Vec<PoolFieldChange>,
 }
 
-impl PartialEq<PoolPlayerInfo> for PoolPlayerInfo {
-    fn eq(&self, other: &PoolPlayerInfo) -> bool {
-        self.id == other.id
+impl PoolEvent {
+    pub fn new(
+        pool_name: &str,
+        actor: &str,
+        event_type: PoolEventType,
+        changes: Vec<PoolFieldChange>,
+    ) -> Self {
+        Self {
+            pool_name: pool_name.to_string(),
+            actor: actor.to_string(),
+            event_type,
+            date_created: Utc::now().timestamp_millis(),
+            changes,
+        }
     }
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct PoolPlayerInfo {
-    pub id: u32, // ID from the NHL API.
-    pub name: String,
-    pub team: Option<u32>,
-    pub position: Position,
-    pub age: Option<u8>,
-    pub salary_cap: Option<f64>,
-    pub contract_expiration_season: Option<u32>,
-}
+pub fn get_pool_changes(before: &Pool, after: &Pool) -> Result<Vec<PoolFieldChange>, AppError> {
+    // List the fields that differ between 2 versions of a pool.
+    let before =
+        serde_json::to_value(before).map_err(|e| AppError::ParseError { msg: e.to_string() })?;
+    let after =
+        serde_json::to_value(after).map_err(|e| AppError::ParseError { msg: e.to_string() })?;
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub enum Position {
-    F,
-    D,
-    G,
+    let mut changes = Vec::new();
+    push_changes("", &before, &after, &mut changes);
+    Ok(changes)
 }
 
-impl Position {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            Position::F => "F",
-            Position::D => "D",
-            Position::G => "G",
+fn push_changes(path: &str, before: &Value, after: &Value, changes: &mut Vec<PoolFieldChange>) {
+    match (before, after) {
+        (Value::Object(before_fields), Value::Object(after_fields)) => {
+            let keys = before_fields
+                .keys()
+                .chain(after_fields.keys())
+                .collect::<HashSet<&String>>();
+
+            for key in keys {
+                let field_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+
+                push_changes(
+                    &field_path,
+                    before_fields.get(key).unwrap_or(&Value::Null),
+                    after_fields.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ => {
+            if before != after {
+                changes.push(PoolFieldChange {
+                    path: path.to_string(),
+                    before: before.clone(),
+                    after: after.clone(),
+                });
+            }
         }
     }
 }
 
-impl PartialEq<Pick> for Pick {
-    fn eq(&self, other: &Pick) -> bool {
-        self.round == other.round && self.from == other.from
-    }
+// payload to sent when creating a new pool.
+// The settings of the template are used when a template id is provided.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct PoolCreationRequest {
+    pub pool_name: String,
+    pub settings: Option<PoolSettings>,
+    pub template_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct Pick {
-    pub round: u8,
-    pub from: String,
+// payload to sent when creating a new pool with the settings of an existing pool.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct ClonePoolRequest {
+    pub pool_name: String,
+    pub new_pool_name: String,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct Trade {
-    pub proposed_by: String,
-    pub ask_to: String,
-    pub from_items: TradeItems,
-    pub to_items: TradeItems,
-    pub status: TradeStatus,
-    pub id: u32,
+// Maximum number of pool templates a user can save.
+pub const MAX_POOL_TEMPLATES: u64 = 20;
+
+// Named pool settings saved by a user to create its next pools.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct PoolTemplate {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub settings: PoolSettings,
     pub date_created: i64,
-    pub date_accepted: i64,
+    pub date_updated: i64,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub struct TradeItems {
-    pub players: Vec<u32>, // Id of the player
-    pub picks: Vec<Pick>,
-}
+impl PoolTemplate {
+    pub fn new(user_id: &str, req: &SavePoolTemplateRequest) -> Self {
+        let now = Utc::now().timestamp_millis();
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
-pub enum TradeStatus {
-    NEW,       // trade created by a requester (not yet ACCEPTED/CANCELLED/REFUSED)
-    ACCEPTED,  // trade accepted items were officially traded
-    CANCELLED, // items were not traded cancelled by the requester
-    REFUSED,   // items were not traded cancelled by the one requested for the traded
+        Self {
+            id: Uuid::new_v4().simple().to_string(),
+            user_id: user_id.to_string(),
+            name: req.name.clone(),
+            settings: req.settings.get_reusable_settings(),
+            date_created: now,
+            date_updated: now,
+        }
+    }
 }
 
-// payload to sent when creating a new pool.
-#[derive(Debug, Deserialize, Clone)]
-pub struct PoolCreationRequest {
-    pub pool_name: String,
+// payload to sent when creating or updating a pool template, the template id is only set to update a template.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct SavePoolTemplateRequest {
+    pub template_id: Option<String>,
+    pub name: String,
     pub settings: PoolSettings,
 }
 
+// payload to sent when deleting a pool template.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct DeletePoolTemplateRequest {
+    pub template_id: String,
+}
+
 // payload to sent when deleting a pool.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct PoolDeletionRequest {
     pub pool_name: String,
 }
 
 // payload to sent when adding player by the owner of the pool.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct AddPlayerRequest {
     pub pool_name: String,
-    pub added_player_user_id: String,
+    pub added_player_user_id: UserId,
     pub player: PoolPlayerInfo,
 }
 
 // payload to sent when removing player by the owner of the pool.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct RemovePlayerRequest {
     pub pool_name: String,
-    pub removed_player_user_id: String,
+    pub removed_player_user_id: UserId,
     pub player_id: u32,
 }
 
 // payload to sent when creating a trade.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct CreateTradeRequest {
     pub pool_name: String,
     pub trade: Trade,
 }
 
 // payload to sent when cancelling a trade.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct DeleteTradeRequest {
     pub pool_name: String,
     pub trade_id: u32,
 }
 
 // payload to sent when responding to a trade.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct RespondTradeRequest {
     pub pool_name: String,
     pub trade_id: u32,
     pub is_accepted: bool,
+
+    // When set, the trade is countered with this new trade instead of being accepted/refused.
+    pub counter_trade: Option<Trade>,
+}
+
+// payload to sent when voting to veto a trade under review.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct VetoTradeRequest {
+    pub pool_name: String,
+    pub trade_id: u32,
+}
+
+// payload to sent when posting a test message to the pool webhook.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct TestWebhookRequest {
+    pub pool_name: String,
 }
 
 // payload to sent when filling a spot with a reservist.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct FillSpotRequest {
     pub pool_name: String,
-    pub filled_spot_user_id: String,
+    pub filled_spot_user_id: UserId,
     pub player_id: u32,
 }
 
+// payload to sent when enabling/disabling the auto lineup of a pooler.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct AutoLineupRequest {
+    pub pool_name: String,
+    pub auto_lineup_user_id: UserId,
+    pub is_enabled: bool,
+}
+
 // payload to sent when modifying roster of a pooler
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct ModifyRosterRequest {
     pub pool_name: String,
-    pub roster_modified_user_id: String,
+    pub roster_modified_user_id: UserId,
     pub forw_list: Vec<u32>,
     pub def_list: Vec<u32>,
     pub goal_list: Vec<u32>,
     pub reserv_list: Vec<u32>,
+    pub util_list: Option<Vec<u32>>,
+    pub injured_reserve_list: Option<Vec<u32>>,
+}
+
+impl ModifyRosterRequest {
+    pub fn get_modified_roster(&self) -> PoolerRoster {
+        PoolerRoster {
+            chosen_forwards: self.forw_list.clone(),
+            chosen_defenders: self.def_list.clone(),
+            chosen_goalies: self.goal_list.clone(),
+            chosen_reservists: self.reserv_list.clone(),
+            chosen_utility: self.util_list.clone(),
+            chosen_injured_reserve: self.injured_reserve_list.clone(),
+            retained_salaries: None,
+        }
+    }
 }
 
 // payload to sent when protecting the list of players for dynasty draft.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct ProtectPlayersRequest {
     pub pool_name: String,
-    pub protected_players_user_id: String,
+    pub protected_players_user_id: UserId,
     pub protected_players: Vec<u32>,
 }
 
 // payload to sent when generating a new season for a dynasty type of pool.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct CompleteProtectionRequest {
     pub pool_name: String,
 }
 
 // payload to sent when updating pool settings.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct UpdatePoolSettingsRequest {
     pub pool_name: String,
     pub pool_settings: PoolSettings,
 }
 
 // payload to sent when marking a pool as final
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct MarkAsFinalRequest {
     pub pool_name: String,
 }
 
+// Code given by the owner to a user so the user can take over a team managed by the pool.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct TeamClaim {
+    pub code: String,
+    pub pool_name: String,
+    pub participant_id: String,
+    pub created_by: String,
+    pub date_created: i64,
+    pub date_expiration: i64,
+}
+
+impl TeamClaim {
+    pub fn new(created_by: &str, req: &GenerateTeamClaimRequest) -> Self {
+        let now = Utc::now().timestamp_millis();
+
+        Self {
+            code: Uuid::new_v4().simple().to_string(),
+            pool_name: req.pool_name.clone(),
+            participant_id: req.participant_id.to_string(),
+            created_by: created_by.to_string(),
+            date_created: now,
+            date_expiration: now + TEAM_CLAIM_VALIDITY_HOURS * 3600000,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.date_expiration <= Utc::now().timestamp_millis()
+    }
+}
+
+// payload to sent when generating the claim code of a team managed by the pool.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct GenerateTeamClaimRequest {
+    pub pool_name: String,
+    pub participant_id: UserId,
+}
+
+// payload to sent when claiming a team with a claim code.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct ClaimTeamRequest {
+    pub code: String,
+}
+
+// payload to sent when updating the branding of a team, the missing fields are cleared.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct UpdateTeamProfileRequest {
+    pub participant_id: UserId,
+    pub team_name: Option<String>,
+    pub team_abbreviation: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+// payload to sent when adding (or removing) a player of a roster to the trade block.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct UpdateTradeBlockRequest {
+    pub participant_id: UserId,
+    pub player_id: u32,
+    pub is_on_block: bool,
+    pub note: Option<String>,
+    pub wanted_positions: Option<Vec<Position>>,
+}
+
+// payload to sent when adding (or removing) a player to the watchlist of a pool.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct UpdateWatchlistRequest {
+    pub player_id: u32,
+    pub is_watched: bool,
+}
+
+// payload to sent when marking a team abandoned by its user as orphaned (or not anymore).
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct UpdateOrphanedTeamRequest {
+    pub participant_id: UserId,
+    pub is_orphaned: bool,
+    pub is_owner_managed: Option<bool>,
+}
+
+// payload to sent when handing an orphaned team to a new user.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct AdoptTeamRequest {
+    pub participant_id: UserId,
+    pub new_user_id: UserId,
+    pub new_user_name: String,
+}
+
+// payload to sent when sharing the management of a team with other users.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct UpdateTeamManagersRequest {
+    pub pool_name: String,
+    pub participant_id: UserId,
+    pub managers: Vec<UserId>,
+}
+
+// payload to sent when transferring the ownership of a pool to another participant.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct TransferOwnershipRequest {
+    pub pool_name: String,
+    pub new_owner_id: UserId,
+}
+
+// payload to sent when renaming a pool, the references to the pool follow its new name.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct RenamePoolRequest {
+    pub pool_name: String,
+    pub new_pool_name: String,
+}
+
 // payload to sent when generating a new season for a dynasty type of pool.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, ToSchema)]
 pub struct GenerateDynastyRequest {
     pub pool_name: String,
     pub new_pool_name: String,
+
+    // Teams joining the league for the next season, they get the first picks of the draft to fill their roster.
+    pub added_participants: Option<Vec<PoolUser>>,
+
+    // Teams leaving the league, their players go back in the draft pool.
+    pub removed_participants: Option<Vec<String>>,
+}
+
+// query parameters to only fetch some fields of a pool, e.g. ?fields=settings,participants,standings
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PoolFieldsQuery {
+    pub fields: Option<String>,
+
+    // Date (YYYY-MM-DD) to count the starters of each pooler that have a game.
+    pub games_date: Option<String>,
+}
+
+impl PoolFieldsQuery {
+    pub fn get_projected_paths(&self) -> Result<Option<Vec<&'static str>>, AppError> {
+        // Map the requested fields to the pool document paths, None means the complete pool.
+        let Some(fields) = &self.fields else {
+            return Ok(None);
+        };
+
+        let mut paths = vec!["name"];
+
+        for field in fields
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+        {
+            let field_paths: &[&'static str] = match field {
+                "owner" => &["owner"],
+                "participants" => &["participants"],
+                "settings" => &["settings"],
+                "status" => &["status"],
+                "season" => &["season", "season_start", "season_end"],
+                "final_rank" => &["final_rank"],
+                "draft_order" => &["draft_order"],
+                "trades" => &["trades", "trade_deadline"],
+                "rosters" => &["context.pooler_roster", "context.players"],
+                "standings" => &[
+                    "context.cumulative_totals",
+                    "context.matchup_schedule",
+                    "final_rank",
+                ],
+                "player_stats" => &["context.players_totals", "context.players"],
+                "score_by_day" => &["context.score_by_day"],
+                _ => {
+                    return Err(AppError::CustomError {
+                        msg: format!("The pool field '{}' does not exist.", field),
+                    })
+                }
+            };
+
+            paths.extend(field_paths);
+        }
+
+        paths.sort_unstable();
+        paths.dedup();
+        Ok(Some(paths))
+    }
+}
+
+// query parameters to filter the listed pools of a season.
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListPoolsQuery {
+    pub status: Option<PoolState>,
+    pub owner: Option<UserId>,
+    // Only list the pools that still have space for new participants.
+    pub has_space: Option<bool>,
+    pub draft_date: Option<String>,
+    // Text search on the pool name.
+    pub search: Option<String>,
+
+    // Pagination, after is the name of the last pool of the previous page.
+    pub sort: Option<PoolsSort>,
+    pub limit: Option<i64>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub enum PoolsSort {
+    CreatedDate, // the most recent first
+    Name,
+    Season, // the most recent first
+}
+
+// query parameters to paginate the pool history.
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PoolHistoryQuery {
+    pub skip: Option<u64>,
+    pub limit: Option<i64>,
+}
+
+// query parameters of the requests that can be previewed, e.g. ?dry_run=true
+// Every validation is made but nothing is saved, the resulting rosters and cap numbers are returned.
+#[derive(Debug, Deserialize, Clone, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct DryRunQuery {
+    pub dry_run: Option<bool>,
+}
+
+impl DryRunQuery {
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run.unwrap_or(false)
+    }
 }
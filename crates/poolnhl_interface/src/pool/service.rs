@@ -1,14 +1,25 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use serde_json::Value;
 
 use crate::errors::Result;
+use crate::players::model::{GetPlayerQuery, PlayerInfo};
 use crate::pool::model::{
-    AddPlayerRequest, CreateTradeRequest, DeleteTradeRequest, FillSpotRequest,
-    GenerateDynastyRequest, MarkAsFinalRequest, ModifyRosterRequest, Pool, PoolCreationRequest,
-    PoolDeletionRequest, ProjectedPoolShort, ProtectPlayersRequest, RemovePlayerRequest,
-    RespondTradeRequest, UpdatePoolSettingsRequest,
+    AddPlayerRequest, AdoptTeamRequest, AutoLineupRequest, CapReport, ClaimTeamRequest,
+    ClonePoolRequest, CreateTradeRequest, DeletePoolTemplateRequest, DeleteTradeRequest,
+    DraftRecapPick, FillSpotRequest, GenerateDynastyRequest, GenerateTeamClaimRequest,
+    ListPoolsQuery, MarkAsFinalRequest, MatchupSchedule, ModifyRosterRequest, Pool,
+    PoolCreationRequest, PoolDeletionRequest, PoolEvent, PoolHistoryQuery, PoolPlayerStats,
+    PoolPreview, PoolStandings, PoolTemplate, ProjectedPoolShort, ProtectPlayersRequest,
+    RemovePlayerRequest, RenamePoolRequest, RespondTradeRequest, SavePoolTemplateRequest, Standing,
+    TeamClaim, TestWebhookRequest, TradeAnalysis, TradeBlockListing, TransferOwnershipRequest,
+    UpdateOrphanedTeamRequest, UpdatePoolSettingsRequest, UpdateTeamManagersRequest,
+    UpdateTeamProfileRequest, UpdateTradeBlockRequest, UpdateWatchlistRequest, VetoTradeRequest,
+    Watchlist,
 };
+use crate::users::model::UserEmailJwtPayload;
 
 use super::model::CompleteProtectionRequest;
 
@@ -16,24 +27,80 @@ use super::model::CompleteProtectionRequest;
 pub trait PoolService {
     // Get pool info calls
     async fn get_pool_by_name(&self, name: &str) -> Result<Pool>;
+    async fn get_partial_pool_by_name(&self, name: &str, paths: &[&str]) -> Result<Value>;
+    // Validate that a user can see a pool, a private pool is only visible to its members and invited users.
+    // Without a user, only a pool that is not private can be seen.
+    async fn validate_pool_view(&self, user_id: Option<&str>, name: &str) -> Result<()>;
     async fn get_pool_by_name_with_range(
         &self,
         name: &str,
         start_season_date: &str,
         from_date: &str,
     ) -> Result<Pool>;
-    async fn list_pools(&self, season: u32) -> Result<Vec<ProjectedPoolShort>>;
+    async fn list_pools(
+        &self,
+        season: u32,
+        query: ListPoolsQuery,
+    ) -> Result<Vec<ProjectedPoolShort>>;
+    // Head-to-head calls
+    async fn get_matchups(&self, name: &str) -> Result<MatchupSchedule>;
+    async fn get_standings(&self, name: &str) -> Result<Vec<Standing>>;
+    async fn get_pool_standings(&self, name: &str) -> Result<PoolStandings>;
+    async fn get_players_stats(&self, name: &str) -> Result<Vec<PoolPlayerStats>>;
+    // Webhook call, post a test message to the pool webhook.
+    async fn test_webhook(&self, user_id: &str, req: TestWebhookRequest) -> Result<()>;
+    // Salary cap call
+    async fn get_cap_report(&self, name: &str) -> Result<CapReport>;
+    // Trade call, compare the value of what each pooler gives and receives.
+    async fn get_trade_analysis(&self, name: &str, trade_id: u32) -> Result<TradeAnalysis>;
+    // Draft call
+    async fn get_draft_recap(&self, name: &str) -> Result<Vec<DraftRecapPick>>;
+    // Free agents call, the NHL players not possessed by a pooler.
+    async fn get_free_agents(&self, name: &str, query: GetPlayerQuery) -> Result<Vec<PlayerInfo>>;
+    // Schedule call, the number of starters of each pooler that have a game at a date.
+    async fn get_starters_games(&self, name: &str, date: &str) -> Result<HashMap<String, u8>>;
     // Pool creation/deletion calls
     async fn create_pool(&self, user_id: &str, req: PoolCreationRequest) -> Result<Pool>;
     async fn delete_pool(&self, user_id: &str, req: PoolDeletionRequest) -> Result<Pool>;
+    async fn clone_pool(&self, user_id: &str, req: ClonePoolRequest) -> Result<Pool>;
+    // Pool templates calls
+    async fn list_pool_templates(&self, user_id: &str) -> Result<Vec<PoolTemplate>>;
+    async fn save_pool_template(
+        &self,
+        user_id: &str,
+        req: SavePoolTemplateRequest,
+    ) -> Result<PoolTemplate>;
+    async fn delete_pool_template(
+        &self,
+        user_id: &str,
+        req: DeletePoolTemplateRequest,
+    ) -> Result<()>;
     // Pool in progress calls
     async fn add_player(&self, user_id: &str, req: AddPlayerRequest) -> Result<Pool>;
     async fn remove_player(&self, user_id: &str, req: RemovePlayerRequest) -> Result<Pool>;
     async fn create_trade(&self, user_id: &str, req: &mut CreateTradeRequest) -> Result<Pool>;
     async fn delete_trade(&self, user_id: &str, req: DeleteTradeRequest) -> Result<Pool>;
     async fn respond_trade(&self, user_id: &str, req: RespondTradeRequest) -> Result<Pool>;
+    async fn veto_trade(&self, user_id: &str, req: VetoTradeRequest) -> Result<Pool>;
     async fn fill_spot(&self, user_id: &str, req: FillSpotRequest) -> Result<Pool>;
     async fn modify_roster(&self, user_id: &str, req: ModifyRosterRequest) -> Result<Pool>;
+    // Preview calls, the requests are validated but not saved.
+    async fn preview_create_trade(
+        &self,
+        user_id: &str,
+        req: &mut CreateTradeRequest,
+    ) -> Result<PoolPreview>;
+    async fn preview_respond_trade(
+        &self,
+        user_id: &str,
+        req: RespondTradeRequest,
+    ) -> Result<PoolPreview>;
+    async fn preview_modify_roster(
+        &self,
+        user_id: &str,
+        req: ModifyRosterRequest,
+    ) -> Result<PoolPreview>;
+    async fn set_auto_lineup(&self, user_id: &str, req: AutoLineupRequest) -> Result<Pool>;
     async fn update_pool_settings(
         &self,
         user_id: &str,
@@ -47,7 +114,76 @@ pub trait PoolService {
         req: CompleteProtectionRequest,
     ) -> Result<Pool>;
     async fn mark_as_final(&self, user_id: &str, req: MarkAsFinalRequest) -> Result<Pool>;
+    async fn generate_team_claim(
+        &self,
+        user_id: &str,
+        req: GenerateTeamClaimRequest,
+    ) -> Result<TeamClaim>;
+    async fn claim_team(&self, user: &UserEmailJwtPayload, req: ClaimTeamRequest) -> Result<Pool>;
+    async fn update_team_profile(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        req: UpdateTeamProfileRequest,
+    ) -> Result<Pool>;
+    // Watchlist calls, the players followed by a pooler.
+    async fn get_watchlist(&self, user_id: &str, pool_name: &str) -> Result<Watchlist>;
+    async fn update_watchlist(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        req: UpdateWatchlistRequest,
+    ) -> Result<Watchlist>;
+    async fn get_trade_block(&self, name: &str) -> Result<HashMap<String, Vec<TradeBlockListing>>>;
+    async fn update_trade_block(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        req: UpdateTradeBlockRequest,
+    ) -> Result<Pool>;
+    async fn update_orphaned_team(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        req: UpdateOrphanedTeamRequest,
+    ) -> Result<Pool>;
+    async fn adopt_team(
+        &self,
+        user_id: &str,
+        pool_name: &str,
+        req: AdoptTeamRequest,
+    ) -> Result<Pool>;
+    async fn update_team_managers(
+        &self,
+        user_id: &str,
+        req: UpdateTeamManagersRequest,
+    ) -> Result<Pool>;
+    async fn transfer_ownership(
+        &self,
+        user_id: &str,
+        req: TransferOwnershipRequest,
+    ) -> Result<Pool>;
+    async fn rename_pool(&self, user_id: &str, req: RenamePoolRequest) -> Result<Pool>;
     async fn generate_dynasty(&self, user_id: &str, req: GenerateDynastyRequest) -> Result<Pool>;
+    // History call
+    async fn get_pool_history(
+        &self,
+        user_id: &str,
+        name: &str,
+        query: PoolHistoryQuery,
+    ) -> Result<Vec<PoolEvent>>;
+    // Scoring call, cumulate the daily points of every pool in progress.
+    async fn cumulate_daily_points(&self) -> Result<()>;
+    // Live scoring call, update the provisional points of the games in progress.
+    async fn update_live_scores(&self) -> Result<()>;
+    // Trade review call, execute the accepted trades whose review window passed.
+    async fn complete_trade_reviews(&self) -> Result<()>;
+    // Trade expiration call, expire the trades that were not answered in time.
+    async fn expire_trades(&self) -> Result<()>;
+    // Protection deadline call, protect the players of the late poolers and complete the protection.
+    async fn complete_late_protections(&self) -> Result<()>;
+    // Acquisitions call, start a new week of acquisitions in every pool in progress.
+    async fn reset_weekly_acquisitions(&self) -> Result<()>;
 }
 
 pub type PoolServiceHandle = Arc<dyn PoolService + Send + Sync>;
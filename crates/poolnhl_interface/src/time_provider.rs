@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// Give the current time to the date validations of the pools (i.g., the trade deadline).
+// In development, a virtual today can be set to exercise a season without waiting for its dates.
+pub struct TimeProvider {
+    // The number of days the virtual today is ahead (or behind) the real today.
+    offset_days: AtomicI64,
+}
+
+static TIME_PROVIDER: TimeProvider = TimeProvider {
+    offset_days: AtomicI64::new(0),
+};
+
+pub fn time_provider() -> &'static TimeProvider {
+    &TIME_PROVIDER
+}
+
+impl TimeProvider {
+    pub fn now(&self) -> DateTime<Local> {
+        Local::now() + Duration::days(self.offset_days.load(Ordering::Relaxed))
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.now().date_naive()
+    }
+
+    // The virtual today keeps moving forward with the real time, None resets to the real today.
+    pub fn set_virtual_today(&self, today: Option<NaiveDate>) {
+        let offset_days = today
+            .map(|today| (today - Local::now().date_naive()).num_days())
+            .unwrap_or(0);
+
+        self.offset_days.store(offset_days, Ordering::Relaxed);
+    }
+
+    pub fn get_virtual_today(&self) -> VirtualToday {
+        VirtualToday {
+            today: self.today().to_string(),
+            is_virtual: self.offset_days.load(Ordering::Relaxed) != 0,
+        }
+    }
+}
+
+// The date used by the pool validations.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct VirtualToday {
+    pub today: String,
+    pub is_virtual: bool,
+}
@@ -1,6 +1,82 @@
+use std::fmt;
+use std::ops::Deref;
+
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::draft::model::ChatMessage;
+use crate::errors::{AppError, ErrorCode};
+use crate::invitations::model::Invitation;
+use crate::notifications::model::{Notification, NotificationPreferences};
+use crate::pool::model::{PoolUser, Trade};
+use crate::webhooks::model::WebhookSubscription;
+
+// Number of days after a deletion request before the data of the user is anonymized.
+pub const ACCOUNT_DELETION_GRACE_DAYS: i64 = 30;
+
+// The name replacing the name of a deleted user in the pools.
+pub const DELETED_USER_NAME: &str = "Deleted user";
+
+// Id of a user or of a team not owned by a user, both are uuids (i.g., the Hanko user id).
+// The ids received from the clients are parsed into this type so an invalid id is rejected with a 400.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash, ToSchema)]
+#[serde(try_from = "String", into = "String")]
+#[schema(value_type = String)]
+pub struct UserId(String);
+
+impl UserId {
+    pub fn parse(id: &str) -> Result<Self, AppError> {
+        Uuid::parse_str(id)
+            .map(|_| Self(id.to_string()))
+            .map_err(|_| AppError::CodedError {
+                code: ErrorCode::InvalidUserId,
+                msg: format!("'{}' is not a valid user id.", id),
+            })
+    }
+}
+
+impl TryFrom<String> for UserId {
+    type Error = AppError;
+
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        Self::parse(&id)
+    }
+}
+
+impl From<UserId> for String {
+    fn from(user_id: UserId) -> Self {
+        user_id.0
+    }
+}
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+impl PartialEq<UserId> for String {
+    fn eq(&self, other: &UserId) -> bool {
+        *self == other.0
+    }
+}
+
+impl PartialEq<String> for UserId {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Deref for UserId {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for UserId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct EmailInfo {
     // The current primary email address of the user.
     pub address: String,
@@ -13,7 +89,7 @@ pub struct EmailInfo {
     pub is_verified: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
 pub struct UserEmailJwtPayload {
     // The audience for which the JWT was created.
     // It specifies the intended recipient or system that should accept this JWT.
@@ -32,3 +108,92 @@ pub struct UserEmailJwtPayload {
     // The user ID.
     pub sub: String,
 }
+
+// Tokens issued before this date are rejected, it logs the user out of every device.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct TokenRevocation {
+    pub user_id: String,
+
+    // Timestamp in seconds, compared with the iat of the tokens.
+    pub revoked_before: i64,
+}
+
+// A pool the user participates in, with the trades the user proposed in it.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct PoolParticipation {
+    pub pool_name: String,
+    pub season: u32,
+    pub is_owner: bool,
+    pub participant: PoolUser,
+    pub trades_proposed: Vec<Trade>,
+}
+
+// Deletion requested by a user, the data of the user is anonymized once the grace period is over.
+// Dates are in milliseconds.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct AccountDeletion {
+    pub user_id: String,
+    pub date_requested: i64,
+    pub date_deletion: i64,
+}
+
+// The personal data kept about a user.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct UserDataExport {
+    pub user_id: String,
+    pub email: String,
+    pub pools: Vec<PoolParticipation>,
+    pub notification_preferences: Vec<NotificationPreferences>,
+    pub notifications: Vec<Notification>,
+    pub webhook_subscriptions: Vec<WebhookSubscription>,
+    pub invitations_sent: Vec<Invitation>,
+    pub chat_messages: Vec<ChatMessage>,
+
+    // The pending deletion of the account, if one was requested.
+    pub account_deletion: Option<AccountDeletion>,
+}
+
+// payload to sent when merging another account of the user into the authenticated account.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct MergeAccountsRequest {
+    // A valid token of the account to merge, it proves the user owns both accounts.
+    pub token: String,
+}
+
+// The result of a merge, the merged account no longer owns any data.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct AccountMerge {
+    pub user_id: String,
+    pub merged_user_id: String,
+
+    // The pools where the merged account was replaced.
+    pub pools: Vec<String>,
+}
+
+// Head to head record of a user, cumulated over every pool.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, ToSchema)]
+pub struct UserRecord {
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+}
+
+// Public profile of a user with the summary of its pools participation.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct UserProfile {
+    pub user_id: String,
+
+    // The name and avatar of the user in its most recent pool.
+    pub name: Option<String>,
+    pub avatar_url: Option<String>,
+
+    pub pools_joined: u32,
+
+    // The pools where the user finished first.
+    pub championships: Vec<String>,
+
+    pub record: UserRecord,
+
+    // The NHL team with the most players drafted by the user, None if the user never drafted.
+    pub favorite_team: Option<u32>,
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::errors::Result;
+use crate::users::model::{
+    AccountDeletion, AccountMerge, TokenRevocation, UserDataExport, UserEmailJwtPayload,
+    UserProfile,
+};
+
+#[async_trait]
+pub trait UsersService {
+    // Revoke every token issued to the user until now.
+    async fn logout_everywhere(&self, user_id: &str) -> Result<TokenRevocation>;
+
+    // Whether the token was issued before the user logged out everywhere.
+    async fn is_token_revoked(&self, token: &UserEmailJwtPayload) -> Result<bool>;
+
+    async fn get_user_profile(&self, user_id: &str) -> Result<UserProfile>;
+
+    async fn export_user_data(&self, user: &UserEmailJwtPayload) -> Result<UserDataExport>;
+
+    // Plan the deletion of the account once the grace period is over.
+    async fn request_account_deletion(&self, user_id: &str) -> Result<AccountDeletion>;
+    async fn cancel_account_deletion(&self, user_id: &str) -> Result<()>;
+
+    // Anonymize the users whose deletion grace period is over.
+    async fn complete_account_deletions(&self) -> Result<()>;
+
+    // Move the pools and the data of merged_user into the account of user.
+    // Both tokens were validated, proving the user owns both accounts.
+    async fn merge_accounts(
+        &self,
+        user: &UserEmailJwtPayload,
+        merged_user: &UserEmailJwtPayload,
+    ) -> Result<AccountMerge>;
+}
+
+pub type UsersServiceHandle = Arc<dyn UsersService + Send + Sync>;
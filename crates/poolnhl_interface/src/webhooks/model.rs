@@ -0,0 +1,92 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// Number of times a lifecycle event is delivered before it is dropped.
+pub const WEBHOOK_DELIVERY_MAX_ATTEMPTS: u32 = 5;
+
+// Number of deliveries returned when listing the deliveries of a subscription.
+pub const WEBHOOK_DELIVERIES_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, ToSchema)]
+pub enum PoolLifecycleEvent {
+    PoolCreated,
+    DraftCompleted,
+    TradeAccepted,
+    MarkedFinal,
+}
+
+// A user endpoint receiving the lifecycle events of the pools owned by the user.
+// Every request is signed with the secret (HMAC-SHA256 of the body, in the X-Pool-Signature header).
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct WebhookSubscription {
+    pub id: String,
+    pub user_id: String,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<PoolLifecycleEvent>,
+
+    // When set, only the events of this pool are delivered.
+    pub pool_name: Option<String>,
+
+    pub date_created: i64,
+}
+
+impl WebhookSubscription {
+    pub fn new(user_id: &str, req: &CreateWebhookSubscriptionRequest) -> Self {
+        Self {
+            id: Uuid::new_v4().simple().to_string(),
+            user_id: user_id.to_string(),
+            url: req.url.clone(),
+            secret: Uuid::new_v4().simple().to_string(),
+            events: req.events.clone(),
+            pool_name: req.pool_name.clone(),
+            date_created: Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn is_subscribed(&self, pool_name: &str, event: &PoolLifecycleEvent) -> bool {
+        self.events.contains(event)
+            && self
+                .pool_name
+                .as_deref()
+                .is_none_or(|subscribed_pool| subscribed_pool == pool_name)
+    }
+}
+
+// Body sent to the subscriptions.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct WebhookPayload {
+    pub event: PoolLifecycleEvent,
+    pub pool_name: String,
+    pub date_created: i64,
+    pub data: serde_json::Value,
+}
+
+// Log of every attempt made to deliver an event.
+#[derive(Debug, Deserialize, Serialize, Clone, ToSchema)]
+pub struct WebhookDelivery {
+    pub subscription_id: String,
+    pub event: PoolLifecycleEvent,
+    pub pool_name: String,
+    pub attempt: u32,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+    pub is_success: bool,
+    pub date_created: i64,
+}
+
+// payload to sent when subscribing to the lifecycle events of the owned pools.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct CreateWebhookSubscriptionRequest {
+    pub url: String,
+    pub events: Vec<PoolLifecycleEvent>,
+    pub pool_name: Option<String>,
+}
+
+// payload to sent when deleting a subscription.
+#[derive(Debug, Deserialize, Clone, ToSchema)]
+pub struct DeleteWebhookSubscriptionRequest {
+    pub subscription_id: String,
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::errors::Result;
+use crate::webhooks::model::{
+    CreateWebhookSubscriptionRequest, DeleteWebhookSubscriptionRequest, WebhookDelivery,
+    WebhookSubscription,
+};
+
+#[async_trait]
+pub trait WebhooksService {
+    async fn list_subscriptions(&self, user_id: &str) -> Result<Vec<WebhookSubscription>>;
+    async fn list_deliveries(
+        &self,
+        user_id: &str,
+        subscription_id: &str,
+    ) -> Result<Vec<WebhookDelivery>>;
+    async fn create_subscription(
+        &self,
+        user_id: &str,
+        req: CreateWebhookSubscriptionRequest,
+    ) -> Result<WebhookSubscription>;
+    async fn delete_subscription(
+        &self,
+        user_id: &str,
+        req: DeleteWebhookSubscriptionRequest,
+    ) -> Result<()>;
+}
+
+pub type WebhooksServiceHandle = Arc<dyn WebhooksService + Send + Sync>;
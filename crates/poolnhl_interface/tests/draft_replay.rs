@@ -0,0 +1,52 @@
+use std::fs;
+use std::path::Path;
+
+use poolnhl_interface::draft::model::DraftRecording;
+
+// The draft recordings exported with /admin/draft-recording.
+const FIXTURES_DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/drafts");
+
+fn read_recording(path: &Path) -> DraftRecording {
+    let fixture = fs::read_to_string(path).expect("Could not read the fixture");
+
+    serde_json::from_str(&fixture)
+        .unwrap_or_else(|e| panic!("Could not parse the fixture {}: {}", path.display(), e))
+}
+
+// Every recorded command needs to be accepted or rejected the same way
+// and leave the draft with the same picks and the same next drafter.
+#[test]
+fn replay_recorded_drafts() {
+    let mut fixtures: Vec<_> = fs::read_dir(FIXTURES_DIRECTORY)
+        .expect("Could not list the fixtures")
+        .map(|entry| entry.expect("Could not read the fixture entry").path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect();
+    fixtures.sort();
+
+    assert!(!fixtures.is_empty(), "No draft fixture to replay.");
+
+    for path in fixtures {
+        let recording = read_recording(&path);
+        let replayed_steps = recording.replay().expect("Could not replay the recording");
+
+        for (index, (recorded, replayed)) in recording
+            .steps
+            .iter()
+            .zip(replayed_steps.iter())
+            .enumerate()
+        {
+            assert_eq!(
+                serde_json::to_value(recorded).unwrap(),
+                serde_json::to_value(replayed).unwrap(),
+                "{}: the step {} ({:?}) does not match the recording.",
+                path.display(),
+                index,
+                recorded.command
+            );
+        }
+    }
+}
@@ -0,0 +1,342 @@
+use proptest::prelude::*;
+
+use poolnhl_interface::draft::model::RoomUser;
+use poolnhl_interface::pool::model::{
+    Pool, PoolPlayerInfo, PoolSettings, PoolState, PoolerRoster, Position, SeasonDates, Trade,
+    TradeItems, TradeStatus,
+};
+
+const POOLERS: [&str; 4] = ["owner", "pooler-b", "pooler-c", "pooler-d"];
+
+// The players drafted (or added as free agents) in the generated sequences.
+const NUMBER_PLAYERS: u32 = 48;
+
+#[derive(Debug, Clone)]
+enum DraftAction {
+    // Pick the player for the next drafter, or as another pooler (most likely not its turn).
+    DraftPlayer { player: u32, pooler: Option<usize> },
+    UndoDraftPlayer,
+}
+
+#[derive(Debug, Clone)]
+enum SeasonAction {
+    // The players are roster indexes of each pooler.
+    Trade {
+        from: usize,
+        to: usize,
+        from_players: Vec<usize>,
+        to_players: Vec<usize>,
+    },
+    // Each player of the roster is moved to the position slot (forward, defender, goalie or reservist).
+    ModifyRoster {
+        pooler: usize,
+        slots: Vec<u8>,
+    },
+    AddPlayer {
+        pooler: usize,
+        player: u32,
+    },
+    RemovePlayer {
+        pooler: usize,
+        player: usize,
+    },
+}
+
+fn get_player(id: u32) -> PoolPlayerInfo {
+    PoolPlayerInfo {
+        id,
+        name: format!("Player {}", id),
+        team: Some(id % 32),
+        position: match id % 6 {
+            0 => Position::G,
+            1 | 2 => Position::D,
+            _ => Position::F,
+        },
+        age: None,
+        salary_cap: None,
+        contract_expiration_season: None,
+        positions: None,
+        injury_status: None,
+    }
+}
+
+fn create_pool(draft_order: &[usize]) -> Pool {
+    let mut settings = PoolSettings::new();
+    settings.number_poolers = POOLERS.len() as u8;
+    settings.number_forwards = 3;
+    settings.number_defenders = 2;
+    settings.number_goalies = 1;
+    settings.number_reservists = 2;
+
+    // The season starts far in the future, so the roster modifications and the trades are allowed.
+    let season_dates = SeasonDates {
+        season: 29992000,
+        season_start: "2999-10-01".to_string(),
+        season_end: "3000-04-15".to_string(),
+        trade_deadline: "3000-03-01".to_string(),
+    };
+
+    let mut pool = Pool::new("Invariants", POOLERS[0], &settings, &season_dates);
+
+    let room_users: Vec<RoomUser> = POOLERS
+        .iter()
+        .map(|id| RoomUser {
+            id: id.to_string(),
+            name: id.to_string(),
+            email: None,
+            is_ready: true,
+        })
+        .collect();
+
+    let draft_order: Vec<String> = draft_order
+        .iter()
+        .map(|index| POOLERS[*index].to_string())
+        .collect();
+
+    pool.start_draft(POOLERS[0], &room_users, &draft_order)
+        .expect("Could not start the draft");
+    pool
+}
+
+fn get_roster(pool: &Pool, pooler: usize) -> Vec<u32> {
+    let roster = &pool.context.as_ref().unwrap().pooler_roster[POOLERS[pooler]];
+
+    roster
+        .chosen_forwards
+        .iter()
+        .chain(roster.chosen_defenders.iter())
+        .chain(roster.chosen_goalies.iter())
+        .chain(roster.chosen_reservists.iter())
+        .copied()
+        .collect()
+}
+
+// Apply the action on a copy of the pool, a rejected action leaves the pool as it was.
+fn apply(pool: &mut Pool, action: impl FnOnce(&mut Pool) -> Result<(), String>) {
+    let mut updated_pool = pool.clone();
+
+    if action(&mut updated_pool).is_ok() {
+        *pool = updated_pool;
+    }
+
+    if let Err(e) = pool.check_invariants() {
+        panic!("{}", e);
+    }
+}
+
+fn apply_draft_action(pool: &mut Pool, action: &DraftAction) {
+    apply(pool, |pool| {
+        let result = match action {
+            DraftAction::DraftPlayer { player, pooler } => {
+                let user_id = match pooler {
+                    Some(pooler) => POOLERS[*pooler].to_string(),
+                    None => pool.get_next_drafter().map_err(|e| e.to_string())?,
+                };
+                pool.draft_player(&user_id, &get_player(*player))
+            }
+            DraftAction::UndoDraftPlayer => pool.undo_draft_player(POOLERS[0]),
+        };
+        result.map_err(|e| e.to_string())
+    });
+}
+
+fn complete_draft(pool: &mut Pool) {
+    // Draft the available players in order until every roster is complete.
+    let mut player = 1;
+
+    while matches!(pool.status, PoolState::Draft) {
+        assert!(
+            player <= NUMBER_PLAYERS * 2,
+            "The draft could not be completed."
+        );
+
+        apply_draft_action(
+            pool,
+            &DraftAction::DraftPlayer {
+                player,
+                pooler: None,
+            },
+        );
+        player += 1;
+    }
+}
+
+fn apply_season_action(pool: &mut Pool, action: &SeasonAction) {
+    let rosters: Vec<Vec<u32>> = (0..POOLERS.len())
+        .map(|pooler| get_roster(pool, pooler))
+        .collect();
+
+    // The roster indexes are wrapped around the roster size.
+    let get_players = |pooler: usize, indexes: &[usize]| -> Vec<u32> {
+        let roster = &rosters[pooler];
+        if roster.is_empty() {
+            return Vec::new();
+        }
+        indexes
+            .iter()
+            .map(|index| roster[index % roster.len()])
+            .collect()
+    };
+
+    apply(pool, |pool| {
+        let result = match action {
+            SeasonAction::Trade {
+                from,
+                to,
+                from_players,
+                to_players,
+            } => {
+                let mut trade = Trade {
+                    proposed_by: POOLERS[*from].to_string(),
+                    ask_to: POOLERS[*to].to_string(),
+                    from_items: TradeItems {
+                        players: get_players(*from, from_players),
+                        picks: Vec::new(),
+                        retained_salary: None,
+                        conditional_picks: None,
+                    },
+                    to_items: TradeItems {
+                        players: get_players(*to, to_players),
+                        picks: Vec::new(),
+                        retained_salary: None,
+                        conditional_picks: None,
+                    },
+                    status: TradeStatus::NEW,
+                    id: 0,
+                    date_created: 0,
+                    date_accepted: 0,
+                    extra_legs: None,
+                    accepted_by: None,
+                    counter_of: None,
+                    negotiation: None,
+                    veto_votes: None,
+                };
+
+                pool.create_trade(&mut trade, POOLERS[*from]).and_then(|_| {
+                    let trade_id = pool.trades.as_ref().map_or(0, |trades| trades.len() - 1);
+                    // The owner accepts the trade for everyone.
+                    pool.respond_trade(POOLERS[0], true, trade_id as u32)
+                })
+            }
+            SeasonAction::ModifyRoster { pooler, slots } => {
+                let mut lists = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+
+                for (index, player_id) in rosters[*pooler].iter().enumerate() {
+                    let slot = slots.get(index).copied().unwrap_or(3) as usize % lists.len();
+                    lists[slot].push(*player_id);
+                }
+
+                let [chosen_forwards, chosen_defenders, chosen_goalies, chosen_reservists] = lists;
+                let modified_roster = PoolerRoster {
+                    chosen_forwards,
+                    chosen_defenders,
+                    chosen_goalies,
+                    chosen_reservists,
+                    ..PoolerRoster::new()
+                };
+
+                pool.modify_roster(POOLERS[*pooler], POOLERS[*pooler], &modified_roster)
+            }
+            SeasonAction::AddPlayer { pooler, player } => {
+                pool.add_player(POOLERS[0], POOLERS[*pooler], &get_player(*player))
+            }
+            SeasonAction::RemovePlayer { pooler, player } => {
+                match get_players(*pooler, &[*player]).first() {
+                    Some(player_id) => pool.remove_player(POOLERS[0], POOLERS[*pooler], *player_id),
+                    None => Ok(()),
+                }
+            }
+        };
+        result.map_err(|e| e.to_string())
+    });
+}
+
+fn draft_action_strategy() -> impl Strategy<Value = DraftAction> {
+    prop_oneof![
+        6 => (1..=NUMBER_PLAYERS).prop_map(|player| DraftAction::DraftPlayer { player, pooler: None }),
+        2 => (1..=NUMBER_PLAYERS, 0..POOLERS.len()).prop_map(|(player, pooler)| {
+            DraftAction::DraftPlayer {
+                player,
+                pooler: Some(pooler),
+            }
+        }),
+        1 => Just(DraftAction::UndoDraftPlayer),
+    ]
+}
+
+fn season_action_strategy() -> impl Strategy<Value = SeasonAction> {
+    let pooler = 0..POOLERS.len();
+    let roster_indexes = prop::collection::vec(0..16usize, 0..3);
+
+    prop_oneof![
+        (
+            pooler.clone(),
+            pooler.clone(),
+            roster_indexes.clone(),
+            roster_indexes
+        )
+            .prop_map(|(from, to, from_players, to_players)| SeasonAction::Trade {
+                from,
+                to,
+                from_players,
+                to_players,
+            }),
+        (pooler.clone(), prop::collection::vec(0..4u8, 0..16))
+            .prop_map(|(pooler, slots)| SeasonAction::ModifyRoster { pooler, slots }),
+        (pooler.clone(), 1..=NUMBER_PLAYERS * 2)
+            .prop_map(|(pooler, player)| SeasonAction::AddPlayer { pooler, player }),
+        (pooler, 0..16usize)
+            .prop_map(|(pooler, player)| SeasonAction::RemovePlayer { pooler, player }),
+    ]
+}
+
+#[test]
+fn actions_are_rejected_in_the_wrong_status() {
+    let mut pool = create_pool(&[0, 1, 2, 3]);
+
+    // The season actions are rejected during the draft.
+    assert!(pool.validate_pool_status(&PoolState::Draft).is_ok());
+    assert!(pool.validate_pool_status(&PoolState::InProgress).is_err());
+    assert!(pool
+        .add_player(POOLERS[0], POOLERS[1], &get_player(1))
+        .is_err());
+
+    complete_draft(&mut pool);
+
+    // The draft actions are rejected once the season started.
+    assert!(pool.validate_pool_status(&PoolState::InProgress).is_ok());
+    assert!(pool.undo_draft_player(POOLERS[0]).is_err());
+    assert!(pool.skip_pick().is_err());
+}
+
+proptest! {
+    #[test]
+    fn draft_keeps_the_invariants(
+        draft_order in Just(vec![0, 1, 2, 3]).prop_shuffle(),
+        actions in prop::collection::vec(draft_action_strategy(), 0..64),
+    ) {
+        let mut pool = create_pool(&draft_order);
+
+        for action in &actions {
+            apply_draft_action(&mut pool, action);
+        }
+    }
+
+    #[test]
+    fn season_keeps_the_invariants(
+        draft_order in Just(vec![0, 1, 2, 3]).prop_shuffle(),
+        draft_actions in prop::collection::vec(draft_action_strategy(), 0..32),
+        season_actions in prop::collection::vec(season_action_strategy(), 0..48),
+    ) {
+        let mut pool = create_pool(&draft_order);
+
+        for action in &draft_actions {
+            apply_draft_action(&mut pool, action);
+        }
+        complete_draft(&mut pool);
+
+        for action in &season_actions {
+            apply_season_action(&mut pool, action);
+        }
+    }
+}
@@ -1,4 +1,13 @@
+pub mod admin_endpoints;
+pub mod archives_endpoints;
 pub mod daily_leaders_endpoints;
 pub mod draft_endpoints;
+pub mod graphql_endpoints;
+pub mod health_endpoints;
+pub mod invitations_endpoints;
+pub mod leaderboards_endpoints;
+pub mod notifications_endpoints;
 pub mod players_endpoints;
 pub mod pool_endpoints;
+pub mod users_endpoints;
+pub mod webhooks_endpoints;
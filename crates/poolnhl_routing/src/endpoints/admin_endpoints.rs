@@ -0,0 +1,159 @@
+use axum::extract::{Json, Request, State};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::Router;
+
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_interface::admin::model::{
+    AdminDeletePoolRequest, AdminDraftRecordingRequest, AdminRecordDraftRequest,
+    AdminRecumulatePointsRequest, AdminRenamePoolRequest, AdminSetVirtualTodayRequest,
+    AdminTransferOwnershipRequest, AdminUpdateContextRequest, PoolsMigrationReport,
+};
+use poolnhl_interface::admin::service::AdminServiceHandle;
+use poolnhl_interface::draft::model::DraftRecording;
+use poolnhl_interface::errors::Result;
+use poolnhl_interface::jobs::model::{JobInfo, TriggerJobRequest};
+use poolnhl_interface::jobs::service::JobsServiceHandle;
+use poolnhl_interface::pool::authorization::authorize_site_admin;
+use poolnhl_interface::pool::model::Pool;
+use poolnhl_interface::time_provider::VirtualToday;
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+
+pub struct AdminRouter;
+
+impl AdminRouter {
+    pub fn new(service_registry: ServiceRegistry) -> Router {
+        Router::new()
+            .nest(
+                "/admin",
+                Router::new()
+                    .route("/delete-pool", post(Self::delete_pool))
+                    .route("/transfer-ownership", post(Self::transfer_ownership))
+                    .route("/rename-pool", post(Self::rename_pool))
+                    .route("/update-context", post(Self::update_context))
+                    .route("/recumulate-points", post(Self::recumulate_points))
+                    .route("/migrate-pools", post(Self::migrate_pools))
+                    .route("/set-virtual-today", post(Self::set_virtual_today))
+                    .route("/record-draft", post(Self::record_draft))
+                    .route("/draft-recording", post(Self::get_draft_recording))
+                    .route("/jobs", get(Self::list_jobs))
+                    .route("/trigger-job", post(Self::trigger_job))
+                    .route_layer(middleware::from_fn_with_state(
+                        service_registry.clone(),
+                        Self::require_site_admin,
+                    )),
+            )
+            .with_state(service_registry)
+    }
+
+    // Reject the requests of the users that are not site administrators.
+    async fn require_site_admin(
+        token: UserEmailJwtPayload,
+        request: Request,
+        next: Next,
+    ) -> Result<Response> {
+        authorize_site_admin(&token.sub)?;
+
+        Ok(next.run(request).await)
+    }
+
+    async fn delete_pool(
+        token: UserEmailJwtPayload,
+        State(admin_service): State<AdminServiceHandle>,
+        Json(body): Json<AdminDeletePoolRequest>,
+    ) -> Result<Json<Pool>> {
+        admin_service.delete_pool(&token.sub, body).await.map(Json)
+    }
+
+    async fn transfer_ownership(
+        token: UserEmailJwtPayload,
+        State(admin_service): State<AdminServiceHandle>,
+        Json(body): Json<AdminTransferOwnershipRequest>,
+    ) -> Result<Json<Pool>> {
+        admin_service
+            .transfer_ownership(&token.sub, body)
+            .await
+            .map(Json)
+    }
+
+    async fn rename_pool(
+        token: UserEmailJwtPayload,
+        State(admin_service): State<AdminServiceHandle>,
+        Json(body): Json<AdminRenamePoolRequest>,
+    ) -> Result<Json<Pool>> {
+        admin_service.rename_pool(&token.sub, body).await.map(Json)
+    }
+
+    async fn update_context(
+        token: UserEmailJwtPayload,
+        State(admin_service): State<AdminServiceHandle>,
+        Json(body): Json<AdminUpdateContextRequest>,
+    ) -> Result<Json<Pool>> {
+        admin_service
+            .update_context(&token.sub, body)
+            .await
+            .map(Json)
+    }
+
+    async fn recumulate_points(
+        token: UserEmailJwtPayload,
+        State(admin_service): State<AdminServiceHandle>,
+        Json(body): Json<AdminRecumulatePointsRequest>,
+    ) -> Result<Json<Pool>> {
+        admin_service
+            .recumulate_points(&token.sub, body)
+            .await
+            .map(Json)
+    }
+
+    async fn migrate_pools(
+        token: UserEmailJwtPayload,
+        State(admin_service): State<AdminServiceHandle>,
+    ) -> Result<Json<PoolsMigrationReport>> {
+        admin_service.migrate_pools(&token.sub).await.map(Json)
+    }
+
+    async fn set_virtual_today(
+        token: UserEmailJwtPayload,
+        State(admin_service): State<AdminServiceHandle>,
+        Json(body): Json<AdminSetVirtualTodayRequest>,
+    ) -> Result<Json<VirtualToday>> {
+        admin_service
+            .set_virtual_today(&token.sub, body)
+            .await
+            .map(Json)
+    }
+
+    async fn record_draft(
+        token: UserEmailJwtPayload,
+        State(admin_service): State<AdminServiceHandle>,
+        Json(body): Json<AdminRecordDraftRequest>,
+    ) -> Result<Json<Option<DraftRecording>>> {
+        admin_service.record_draft(&token.sub, body).await.map(Json)
+    }
+
+    async fn get_draft_recording(
+        token: UserEmailJwtPayload,
+        State(admin_service): State<AdminServiceHandle>,
+        Json(body): Json<AdminDraftRecordingRequest>,
+    ) -> Result<Json<DraftRecording>> {
+        admin_service
+            .get_draft_recording(&token.sub, body)
+            .await
+            .map(Json)
+    }
+
+    async fn list_jobs(
+        State(jobs_service): State<JobsServiceHandle>,
+    ) -> Result<Json<Vec<JobInfo>>> {
+        jobs_service.list_jobs().await.map(Json)
+    }
+
+    async fn trigger_job(
+        State(jobs_service): State<JobsServiceHandle>,
+        Json(body): Json<TriggerJobRequest>,
+    ) -> Result<Json<JobInfo>> {
+        jobs_service.trigger_job(&body.name).await.map(Json)
+    }
+}
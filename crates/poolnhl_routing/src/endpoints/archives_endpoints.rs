@@ -0,0 +1,29 @@
+use axum::extract::{Json, Path, State};
+use axum::routing::get;
+use axum::Router;
+
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_interface::archives::model::SeasonArchive;
+use poolnhl_interface::archives::service::ArchivesServiceHandle;
+use poolnhl_interface::errors::Result;
+
+pub struct ArchivesRouter;
+
+impl ArchivesRouter {
+    pub fn new(service_registry: ServiceRegistry) -> Router {
+        Router::new()
+            .route("/archives/:season/:pool_name", get(Self::get_archive))
+            .with_state(service_registry)
+    }
+
+    // The snapshot of a pool taken when its season was marked as final.
+    async fn get_archive(
+        State(archives_service): State<ArchivesServiceHandle>,
+        Path((season, pool_name)): Path<(u32, String)>,
+    ) -> Result<Json<SeasonArchive>> {
+        archives_service
+            .get_archive(season, &pool_name)
+            .await
+            .map(Json)
+    }
+}
@@ -1,11 +1,11 @@
-use axum::extract::{Json, Path, State};
+use axum::extract::{Json, Path, Query, State};
 use axum::routing::get;
 use axum::Router;
 
 use poolnhl_infrastructure::services::ServiceRegistry;
 use poolnhl_interface::daily_leaders::service::DailyLeadersServiceHandle;
 
-use poolnhl_interface::daily_leaders::model::DailyLeaders;
+use poolnhl_interface::daily_leaders::model::{DailyLeaders, DailyLeadersRangeQuery};
 use poolnhl_interface::errors::Result;
 
 pub struct DailyLeadersRouter;
@@ -13,6 +13,11 @@ pub struct DailyLeadersRouter;
 impl DailyLeadersRouter {
     pub fn new(service_registry: ServiceRegistry) -> Router {
         Router::new()
+            .route("/daily_leaders", get(Self::get_daily_leaders_range))
+            .route(
+                "/daily_leaders/top-performers",
+                get(Self::get_top_performers),
+            )
             .route("/daily_leaders/:date", get(Self::get_daily_leaders))
             .with_state(service_registry)
     }
@@ -28,4 +33,20 @@ impl DailyLeadersRouter {
             .await
             .map(Json)
     }
+
+    async fn get_daily_leaders_range(
+        State(daily_leaders_service): State<DailyLeadersServiceHandle>,
+        Query(query): Query<DailyLeadersRangeQuery>,
+    ) -> Result<Json<Vec<DailyLeaders>>> {
+        daily_leaders_service
+            .get_daily_leaders_range(query)
+            .await
+            .map(Json)
+    }
+
+    async fn get_top_performers(
+        State(daily_leaders_service): State<DailyLeadersServiceHandle>,
+    ) -> Result<Json<DailyLeaders>> {
+        daily_leaders_service.get_top_performers().await.map(Json)
+    }
 }
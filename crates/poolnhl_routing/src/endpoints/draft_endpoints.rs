@@ -2,34 +2,61 @@ use axum::{
     extract::{
         connect_info::ConnectInfo,
         ws::{Message, WebSocket, WebSocketUpgrade},
-        Json, Path, State,
+        Json, Path, Query, State,
     },
     response::IntoResponse,
     routing::get,
-    Router,
+    Extension, Router,
 };
 use futures::{SinkExt, StreamExt};
 use poolnhl_infrastructure::services::ServiceRegistry;
-use poolnhl_interface::draft::model::{Command, RoomUser};
+use poolnhl_infrastructure::settings::{Heartbeat, RateBudget};
+use poolnhl_interface::draft::model::{
+    ChatHistoryQuery, ChatMessage, Command, CommandResponse, DraftServerInfo, DraftSuggestions,
+    RoomUser,
+};
 use poolnhl_interface::draft::service::DraftServiceHandle;
 use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::pool::model::DraftQueue;
 use poolnhl_interface::users::model::UserEmailJwtPayload;
 
-use std::{collections::HashMap, net::SocketAddr};
-use tokio::sync::{broadcast, mpsc};
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::sync::{broadcast, mpsc, watch};
+use tracing::{field::Empty, info, info_span, Instrument, Span};
+
+use crate::rate_limit::RateWindow;
+
+// Time given to a socket to send its last messages once the server is restarting.
+const SHUTDOWN_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct DraftRouter;
 
 impl DraftRouter {
-    pub fn new(service_registry: ServiceRegistry) -> Router {
+    pub fn new(
+        service_registry: ServiceRegistry,
+        heartbeat: &Heartbeat,
+        draft_commands: &RateBudget,
+    ) -> Router {
         Router::new()
             .route("/ws/:jwt", get(Self::ws_handler))
             .route("/rooms", get(Self::list_rooms))
             .route("/room-users/:room", get(Self::list_room_users))
+            .route("/pool/:name/chat", get(Self::get_chat_messages))
+            .route(
+                "/pool/:name/draft/suggestions",
+                get(Self::get_draft_suggestions),
+            )
             .route(
                 "/authenticated-sockets",
                 get(Self::list_authenticated_sockets),
             )
+            .layer(Extension(heartbeat.clone()))
+            .layer(Extension(draft_commands.clone()))
             .with_state(service_registry)
     }
 
@@ -46,6 +73,28 @@ impl DraftRouter {
         draft_service.list_room_users(&pool_name).await.map(Json)
     }
 
+    async fn get_draft_suggestions(
+        State(draft_service): State<DraftServiceHandle>,
+        Path(pool_name): Path<String>,
+    ) -> Result<Json<DraftSuggestions>> {
+        draft_service
+            .get_draft_suggestions(&pool_name)
+            .await
+            .map(Json)
+    }
+
+    async fn get_chat_messages(
+        token: UserEmailJwtPayload,
+        State(draft_service): State<DraftServiceHandle>,
+        Path(pool_name): Path<String>,
+        Query(query): Query<ChatHistoryQuery>,
+    ) -> Result<Json<Vec<ChatMessage>>> {
+        draft_service
+            .get_chat_messages(&token.sub, &pool_name, query)
+            .await
+            .map(Json)
+    }
+
     async fn list_authenticated_sockets(
         State(draft_service): State<DraftServiceHandle>,
     ) -> Result<Json<HashMap<String, UserEmailJwtPayload>>> {
@@ -57,28 +106,49 @@ impl DraftRouter {
         Path(jwt): Path<String>,
         ConnectInfo(addr): ConnectInfo<SocketAddr>,
         State(draft_service): State<DraftServiceHandle>,
+        State(draft_server_info): State<Arc<DraftServerInfo>>,
+        Extension(heartbeat): Extension<Heartbeat>,
+        Extension(draft_commands): Extension<RateBudget>,
     ) -> impl IntoResponse {
-        println!("{} is trying to log in", jwt);
-        if jwt != "unauthenticated" {
-            let user = draft_service.authenticate_web_socket(&jwt, addr).await;
-            return ws
-                .on_upgrade(move |socket| Self::handle_socket(socket, user, addr, draft_service));
-        }
-        ws.on_upgrade(move |socket| Self::handle_socket(socket, None, addr, draft_service))
+        // The logs of the socket hold its id and the pool name of the room it joined.
+        let socket_span = info_span!("draft_socket", socket_id = %addr, pool_name = Empty);
+
+        let user = if jwt != "unauthenticated" {
+            info!(parent: &socket_span, "socket is trying to log in");
+            draft_service
+                .authenticate_web_socket(&jwt, addr)
+                .instrument(socket_span.clone())
+                .await
+        } else {
+            None
+        };
+
+        ws.on_upgrade(move |socket| {
+            Self::handle_socket(
+                socket,
+                user,
+                addr,
+                draft_service,
+                draft_server_info,
+                heartbeat,
+                draft_commands,
+            )
+            .instrument(socket_span)
+        })
     }
 
     // The initial socket state.
-    // Waits for the client socket to send the JoinRoom command.
+    // Waits for the client socket to send the JoinRoom or the ResumeRoom command.
     // before leaving the state. It returns the the receiver and the room name.
     async fn waiting_join_room_command(
         socket: &mut WebSocket,
         addr: &SocketAddr,
         draft_service: &DraftServiceHandle,
     ) -> Result<(broadcast::Receiver<String>, String)> {
-        println!("waiting to join room");
+        info!("waiting to join room");
         while let Some(Ok(msg)) = socket.recv().await {
             if let Message::Text(command) = msg {
-                println!("{}", command);
+                info!(command, "command received");
                 if let Ok(command) = serde_json::from_str::<Command>(&command) {
                     match command {
                         Command::JoinRoom {
@@ -86,10 +156,35 @@ impl DraftRouter {
                             number_poolers,
                         } => {
                             // join the requested room.
-                            let rx = draft_service
+                            let (rx, session_token) = draft_service
                                 .join_room(&pool_name, number_poolers, *addr)
                                 .await?;
 
+                            Self::send_response(
+                                socket,
+                                &CommandResponse::Session { session_token },
+                            )
+                            .await;
+
+                            return Ok((rx, pool_name));
+                        }
+                        Command::ResumeRoom { session_token } => {
+                            // resume the room state of a reconnecting socket.
+                            let (rx, pool) =
+                                draft_service.resume_room(&session_token, *addr).await?;
+                            let pool_name = pool.name.clone();
+                            let room_users = draft_service.list_room_users(&pool_name).await?;
+
+                            Self::send_response(
+                                socket,
+                                &CommandResponse::Snapshot {
+                                    session_token,
+                                    pool,
+                                    room_users,
+                                },
+                            )
+                            .await;
+
                             return Ok((rx, pool_name));
                         }
                         _ => continue,
@@ -102,129 +197,458 @@ impl DraftRouter {
         })
     }
 
+    // Send a response only to the socket that made the command.
+    async fn send_response(socket: &mut WebSocket, response: &CommandResponse) {
+        if let Ok(response) = serde_json::to_string(response) {
+            let _ = socket.send(Message::Text(response)).await;
+        }
+    }
+
+    // The ServerRestarting message, None while the server is not shutting down.
+    fn restarting_message(restarting: &watch::Receiver<Option<String>>) -> Option<String> {
+        restarting.borrow().clone()
+    }
+
+    // Serialize the draft queue response sent only to the socket that made the command.
+    fn draft_queue_response(response: Result<DraftQueue>) -> String {
+        match response {
+            Ok(draft_queue) => serde_json::to_string(&CommandResponse::DraftQueue { draft_queue })
+                .unwrap_or_else(|e| e.to_string()),
+            Err(e) => e.to_string(),
+        }
+    }
+
     async fn handle_socket(
         mut socket: WebSocket,
         user: Option<UserEmailJwtPayload>,
         addr: SocketAddr,
         draft_service: DraftServiceHandle,
+        draft_server_info: Arc<DraftServerInfo>,
+        heartbeat: Heartbeat,
+        draft_commands: RateBudget,
     ) {
         // At the beginning there is a state where the user needs to join a room
         // before leaving the initial socket state.
-        println!("handle socket");
+        info!("handle socket");
         let is_authenticated_users = user.is_some();
+        let mut restarting = draft_server_info.subscribe_shutdown();
 
-        match DraftRouter::waiting_join_room_command(&mut socket, &addr, &draft_service).await {
+        // Stop waiting for the room once the server is restarting.
+        let joined_room = tokio::select! {
+            joined_room = DraftRouter::waiting_join_room_command(&mut socket, &addr, &draft_service) => Some(joined_room),
+            _ = restarting.wait_for(Option::is_some) => None,
+        };
+        let Some(joined_room) = joined_room else {
+            if let Some(message) = Self::restarting_message(&restarting) {
+                let _ = socket.send(Message::Text(message)).await;
+            }
+            return;
+        };
+
+        match joined_room {
             Err(_) => (), // An error occured during the initial waiting to join room function. Close the socket connection.
             Ok((mut rx, current_pool_name)) => {
+                Span::current().record("pool_name", current_pool_name.as_str());
+
                 // Actual websocket statemachine (one will be spawned per connection)
                 let (mut sender, mut receiver) = socket.split();
 
                 // create an mpsc so we can send messages to the socket from multiple threads
                 let (agg_sender, mut agg_receiver) = mpsc::channel::<String>(100);
 
+                // Date of the last message received from the socket (including the pongs).
+                let last_activity = Arc::new(Mutex::new(Instant::now()));
+
                 // spawn a task that forwards messages from the mpsc to the sender
                 // This is a way to share the sender between 2 different threads.
-                tokio::spawn(async move {
-                    while let Some(message) = agg_receiver.recv().await {
-                        if sender.send(message.into()).await.is_err() {
-                            break;
+                // The same task pings the socket periodically and stops if the socket stopped answering.
+                let mut forward_messages =
+                    {
+                        let last_activity = last_activity.clone();
+                        tokio::spawn(async move {
+                        let pong_timeout = Duration::from_secs(heartbeat.pong_timeout_seconds);
+                        let mut ping_interval = tokio::time::interval(Duration::from_secs(
+                            heartbeat.ping_interval_seconds,
+                        ));
+
+                        loop {
+                            tokio::select! {
+                                message = agg_receiver.recv() => {
+                                    let Some(message) = message else {
+                                        break;
+                                    };
+                                    if sender.send(message.into()).await.is_err() {
+                                        break;
+                                    }
+                                }
+                                _ = ping_interval.tick() => {
+                                    let is_idle = last_activity.lock().map_or(true, |date| {
+                                        date.elapsed() > pong_timeout
+                                    });
+
+                                    if is_idle {
+                                        info!("the socket is idle, evicting it.");
+                                        break;
+                                    }
+                                    if sender.send(Message::Ping(Vec::new())).await.is_err() {
+                                        break;
+                                    }
+                                }
+                            }
                         }
-                    }
-                });
+                    }.in_current_span())
+                    };
 
                 // Spawn the socket to handle commands received from the socket user.
                 let mut send_messages = {
                     let send_task_sender = agg_sender.clone();
                     let current_pool_name = current_pool_name.clone();
                     let draft_service = draft_service.clone();
-                    tokio::spawn(async move {
-                        while let Some(Ok(msg)) = receiver.next().await {
-                            // Handle the message received.
-                            if let Message::Text(command) = msg {
-                                println!("{}", command);
-                                if let Ok(command) = serde_json::from_str::<Command>(&command) {
-                                    match command {
-                                        Command::LeaveRoom => {
-                                            // The socket needs to be killed when the user leave a room.
-                                            // The leave room commands will be called once the socket is killed.
-                                            return;
-                                        }
-                                        Command::OnPoolSettingChanges { pool_settings } => {
-                                            if let Some(user) = &user {
-                                                // If the pool settings update was a success.
+                    tokio::spawn(
+                        async move {
+                            // Throttle the commands of the socket so it cannot spam the room.
+                            let mut commands_window = RateWindow::new();
+
+                            while let Some(Ok(msg)) = receiver.next().await {
+                                if let Ok(mut last_activity) = last_activity.lock() {
+                                    *last_activity = Instant::now();
+                                }
+
+                                // Handle the message received.
+                                if let Message::Text(command) = msg {
+                                    info!(command, "command received");
+
+                                    if let Err(retry_after_seconds) =
+                                        commands_window.consume(&draft_commands)
+                                    {
+                                        let _ = send_task_sender
+                                            .send(
+                                                AppError::RateLimitError {
+                                                    retry_after_seconds,
+                                                }
+                                                .to_string(),
+                                            )
+                                            .await;
+                                        continue;
+                                    }
+
+                                    if let Ok(command) = serde_json::from_str::<Command>(&command) {
+                                        // The commands are refused once the server is shutting down,
+                                        // the shutdown waits for the one being executed.
+                                        let Some(_pending_write) =
+                                            draft_server_info.begin_write().await
+                                        else {
+                                            let _ = send_task_sender
+                                                .send(
+                                                    AppError::CustomError {
+                                                        msg: "The server is restarting."
+                                                            .to_string(),
+                                                    }
+                                                    .to_string(),
+                                                )
+                                                .await;
+                                            continue;
+                                        };
+
+                                        match command {
+                                            Command::LeaveRoom => {
+                                                // The socket needs to be killed when the user leave a room.
+                                                // The leave room commands will be called once the socket is killed.
+                                                return;
+                                            }
+                                            Command::OnPoolSettingChanges { pool_settings } => {
+                                                if let Some(user) = &user {
+                                                    // If the pool settings update was a success.
+                                                    if let Err(e) = draft_service
+                                                        .update_pool_settings(
+                                                            &user.sub,
+                                                            &current_pool_name,
+                                                            &pool_settings,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::OnReady => {
                                                 if let Err(e) = draft_service
-                                                    .update_pool_settings(
-                                                        &user.sub,
-                                                        &current_pool_name,
-                                                        &pool_settings,
-                                                    )
+                                                    .on_ready(&current_pool_name, addr)
                                                     .await
                                                 {
                                                     let _ =
                                                         send_task_sender.send(e.to_string()).await;
                                                 }
                                             }
-                                        }
-                                        Command::OnReady => {
-                                            if let Err(e) = draft_service
-                                                .on_ready(&current_pool_name, addr)
-                                                .await
-                                            {
-                                                let _ = send_task_sender.send(e.to_string()).await;
-                                            }
-                                        }
-                                        Command::AddUser { user_name } => {
-                                            if let Err(e) = draft_service
-                                                .add_user(&current_pool_name, &user_name, addr)
-                                                .await
-                                            {
-                                                let _ = send_task_sender.send(e.to_string()).await;
-                                            }
-                                        }
-                                        Command::RemoveUser { user_id } => {
-                                            if let Err(e) = draft_service
-                                                .remove_user(&current_pool_name, &user_id, addr)
-                                                .await
-                                            {
-                                                let _ = send_task_sender.send(e.to_string()).await;
-                                            }
-                                        }
-                                        Command::StartDraft { draft_order } => {
-                                            if let Some(user) = &user {
+                                            Command::AddUser { user_name } => {
                                                 if let Err(e) = draft_service
-                                                    .start_draft(
-                                                        &current_pool_name,
-                                                        &user.sub,
-                                                        &draft_order,
-                                                    )
+                                                    .add_user(&current_pool_name, &user_name, addr)
                                                     .await
                                                 {
                                                     let _ =
                                                         send_task_sender.send(e.to_string()).await;
                                                 }
                                             }
-                                        }
-                                        Command::DraftPlayer { player } => {
-                                            if let Some(user) = &user {
+                                            Command::RemoveUser { user_id } => {
                                                 if let Err(e) = draft_service
-                                                    .draft_player(
-                                                        &current_pool_name,
-                                                        &user.sub,
-                                                        player,
-                                                    )
+                                                    .remove_user(&current_pool_name, &user_id, addr)
                                                     .await
                                                 {
                                                     let _ =
                                                         send_task_sender.send(e.to_string()).await;
                                                 }
                                             }
-                                        }
-                                        Command::UndoDraftPlayer => {
-                                            if let Some(user) = &user {
+                                            Command::StartDraft { draft_order } => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .start_draft(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                            &draft_order,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::UpdateDraftOrder { draft_order_type } => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .update_draft_order(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                            draft_order_type,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::DraftPlayer { player } => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .draft_player(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                            player,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::UndoDraftPlayer => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .undo_draft_player(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::SkipPick => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .skip_pick(&current_pool_name, &user.sub)
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::PauseDraft => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .pause_draft(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                            true,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::ResumeDraft => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .pause_draft(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                            false,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::TradeDraftPicks { trade } => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .trade_draft_picks(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                            trade,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::RollbackDraft { pick_number } => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .rollback_draft(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                            pick_number,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::NominatePlayer {
+                                                player,
+                                                opening_bid,
+                                            } => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .nominate_player(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                            player,
+                                                            opening_bid,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::PlaceBid { bidder_id, amount } => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .bid_player(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                            &bidder_id,
+                                                            amount,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::CloseBid => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .close_bid(&current_pool_name, &user.sub)
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::CancelNomination => {
+                                                if let Some(user) = &user {
+                                                    if let Err(e) = draft_service
+                                                        .cancel_nomination(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                        )
+                                                        .await
+                                                    {
+                                                        let _ = send_task_sender
+                                                            .send(e.to_string())
+                                                            .await;
+                                                    }
+                                                }
+                                            }
+                                            Command::GetDraftQueue => {
+                                                if let Some(user) = &user {
+                                                    let response = draft_service
+                                                        .get_draft_queue(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                        )
+                                                        .await;
+                                                    let _ = send_task_sender
+                                                        .send(Self::draft_queue_response(response))
+                                                        .await;
+                                                }
+                                            }
+                                            Command::UpdateQueue { players } => {
+                                                if let Some(user) = &user {
+                                                    let response = draft_service
+                                                        .update_draft_queue(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                            players,
+                                                        )
+                                                        .await;
+                                                    let _ = send_task_sender
+                                                        .send(Self::draft_queue_response(response))
+                                                        .await;
+                                                }
+                                            }
+                                            Command::ToggleAutoDraft => {
+                                                if let Some(user) = &user {
+                                                    let response = draft_service
+                                                        .toggle_auto_draft(
+                                                            &current_pool_name,
+                                                            &user.sub,
+                                                        )
+                                                        .await;
+                                                    let _ = send_task_sender
+                                                        .send(Self::draft_queue_response(response))
+                                                        .await;
+                                                }
+                                            }
+                                            Command::ChatMessage { message } => {
                                                 if let Err(e) = draft_service
-                                                    .undo_draft_player(
+                                                    .send_chat_message(
                                                         &current_pool_name,
-                                                        &user.sub,
+                                                        addr,
+                                                        &message,
                                                     )
                                                     .await
                                                 {
@@ -232,44 +656,71 @@ impl DraftRouter {
                                                         send_task_sender.send(e.to_string()).await;
                                                 }
                                             }
+                                            Command::JoinRoom {
+                                                pool_name: _,
+                                                number_poolers: _,
+                                            }
+                                            | Command::ResumeRoom { session_token: _ } => {}
                                         }
-                                        Command::JoinRoom {
-                                            pool_name: _,
-                                            number_poolers: _,
-                                        } => {}
+                                    } else {
+                                        let _ = send_task_sender
+                                            .send(
+                                                "could not deserialize the command received."
+                                                    .to_string(),
+                                            )
+                                            .await;
                                     }
-                                } else {
-                                    let _ = send_task_sender
-                                        .send(
-                                            "could not deserialize the command received."
-                                                .to_string(),
-                                        )
-                                        .await;
                                 }
                             }
                         }
-                    })
+                        .in_current_span(),
+                    )
                 };
 
                 // Spawn the socket to handle sending messages to the socket user.
                 // When a socket in the room send a messages that needs to be communicated to every one in the room.
                 let mut recv_messages = {
                     let recv_sender = agg_sender.clone();
-                    tokio::spawn(async move {
-                        while let Ok(msg) = rx.recv().await {
-                            if recv_sender.send(msg).await.is_err() {
-                                break;
+                    tokio::spawn(
+                        async move {
+                            while let Ok(msg) = rx.recv().await {
+                                if recv_sender.send(msg).await.is_err() {
+                                    break;
+                                }
                             }
                         }
-                    })
+                        .in_current_span(),
+                    )
                 };
 
                 // Tome make sure that if the receiver/sender thread complete, the other one get cleared.
+                // An idle socket is evicted once the forwarding task stops.
                 tokio::select! {
                     _ = (&mut send_messages) => recv_messages.abort(),
                     _ = (&mut recv_messages) => send_messages.abort(),
+                    _ = (&mut forward_messages) => {
+                        send_messages.abort();
+                        recv_messages.abort();
+                    }
+                    _ = restarting.wait_for(Option::is_some) => {
+                        send_messages.abort();
+                        recv_messages.abort();
+                    }
                 };
 
+                // The server is restarting, let the forwarding task flush the queued messages
+                // followed by the ServerRestarting message before closing the socket.
+                if let Some(message) = Self::restarting_message(&restarting) {
+                    let _ = agg_sender.send(message).await;
+                    drop(agg_sender);
+                    let _ =
+                        tokio::time::timeout(SHUTDOWN_FLUSH_TIMEOUT, &mut forward_messages).await;
+                }
+                forward_messages.abort();
+
+                // Keep the room state of the socket so it can be resumed on reconnection.
+                let _ = draft_service.suspend_session(addr).await;
+
                 // Make sure that if we lose the socket communication we force the user to leave the room and unauthenticate.
                 if is_authenticated_users {
                     let _ = draft_service.leave_room(&current_pool_name, addr).await;
@@ -0,0 +1,332 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_graphql::{
+    Context, Data, EmptyMutation, Json, Object, Schema, SimpleObject, Subscription,
+};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
+use axum::{
+    extract::{State, WebSocketUpgrade},
+    response::Response,
+    routing::get,
+    Extension, Router,
+};
+use futures::Stream;
+use tokio::sync::broadcast::error::RecvError;
+
+use poolnhl_infrastructure::jwt::validate_token;
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_interface::draft::model::DraftServerInfo;
+use poolnhl_interface::pool::model::{
+    DailyRosterPoints, Pool, PoolSettings, PoolStandings, PoolUser, PoolerRoster, PoolerStanding,
+    Position, PositionTotals, Standing,
+};
+use poolnhl_interface::pool::service::PoolServiceHandle;
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+
+type PoolSchema = Schema<QueryRoot, EmptyMutation, SubscriptionRoot>;
+
+pub struct GraphQLRouter;
+
+impl GraphQLRouter {
+    pub fn new(service_registry: ServiceRegistry) -> Router {
+        let schema = Schema::build(QueryRoot, EmptyMutation, SubscriptionRoot)
+            .data(service_registry.pool_service.clone())
+            .data(service_registry.draft_server_info.clone())
+            .finish();
+
+        Router::new()
+            .route("/graphql", get(Self::graphql).post(Self::graphql))
+            .route("/graphql/ws", get(Self::graphql_ws))
+            .layer(Extension(schema))
+            .with_state(service_registry)
+    }
+
+    // The queries are executed for the authenticated user.
+    async fn graphql(
+        Extension(schema): Extension<PoolSchema>,
+        user: UserEmailJwtPayload,
+        req: GraphQLRequest,
+    ) -> GraphQLResponse {
+        schema.execute(req.into_inner().data(user)).await.into()
+    }
+
+    // The subscriptions are authenticated with the token sent in the connection init payload ({"token": "..."}).
+    async fn graphql_ws(
+        Extension(schema): Extension<PoolSchema>,
+        State(service_registry): State<ServiceRegistry>,
+        protocol: GraphQLProtocol,
+        upgrade: WebSocketUpgrade,
+    ) -> Response {
+        upgrade
+            .protocols(async_graphql::http::ALL_WEBSOCKET_PROTOCOLS)
+            .on_upgrade(move |stream| {
+                GraphQLWebSocket::new(stream, schema, protocol)
+                    .on_connection_init(move |payload| async move {
+                        let token = payload
+                            .get("token")
+                            .and_then(|token| token.as_str())
+                            .ok_or_else(|| {
+                                async_graphql::Error::new("You need to be logged in.")
+                            })?;
+                        let user = validate_token(token, &service_registry).await?;
+
+                        let mut data = Data::default();
+                        data.insert(user);
+                        Ok(data)
+                    })
+                    .serve()
+            })
+    }
+}
+
+// Validate that the authenticated user can see the pool.
+async fn validate_pool_view(ctx: &Context<'_>, pool_name: &str) -> async_graphql::Result<()> {
+    let user = ctx
+        .data_opt::<UserEmailJwtPayload>()
+        .ok_or_else(|| async_graphql::Error::new("You need to be logged in."))?;
+
+    ctx.data::<PoolServiceHandle>()?
+        .validate_pool_view(Some(&user.sub), pool_name)
+        .await?;
+    Ok(())
+}
+
+// The pool without its daily scores, they are queried by date.
+pub struct PoolObject(Pool);
+
+#[Object]
+impl PoolObject {
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn owner(&self) -> &str {
+        &self.0.owner
+    }
+
+    async fn status(&self) -> String {
+        self.0.status.to_string()
+    }
+
+    async fn season(&self) -> u32 {
+        self.0.season
+    }
+
+    async fn participants(&self) -> Vec<ParticipantObject> {
+        self.0
+            .participants
+            .iter()
+            .map(ParticipantObject::from)
+            .collect()
+    }
+
+    async fn draft_order(&self) -> Option<&Vec<String>> {
+        self.0.draft_order.as_ref()
+    }
+
+    async fn final_rank(&self) -> Option<&Vec<String>> {
+        self.0.final_rank.as_ref()
+    }
+
+    async fn settings(&self) -> Json<&PoolSettings> {
+        Json(&self.0.settings)
+    }
+
+    async fn rosters(&self) -> Option<Json<&HashMap<String, PoolerRoster>>> {
+        self.0
+            .context
+            .as_ref()
+            .map(|context| Json(&context.pooler_roster))
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ParticipantObject {
+    id: String,
+    name: String,
+    is_owned: bool,
+    team_name: Option<String>,
+    team_abbreviation: Option<String>,
+    avatar_url: Option<String>,
+}
+
+impl From<&PoolUser> for ParticipantObject {
+    fn from(participant: &PoolUser) -> Self {
+        Self {
+            id: participant.id.clone(),
+            name: participant.name.clone(),
+            is_owned: participant.is_owned,
+            team_name: participant.team_name.clone(),
+            team_abbreviation: participant.team_abbreviation.clone(),
+            avatar_url: participant.avatar_url.clone(),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct StandingObject {
+    participant: String,
+    wins: u16,
+    losses: u16,
+    ties: u16,
+    points: u16,
+    points_for: f64,
+    points_against: f64,
+}
+
+impl From<Standing> for StandingObject {
+    fn from(standing: Standing) -> Self {
+        Self {
+            participant: standing.participant,
+            wins: standing.wins,
+            losses: standing.losses,
+            ties: standing.ties,
+            points: standing.points,
+            points_for: standing.points_for,
+            points_against: standing.points_against,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct PoolerStandingObject {
+    participant: String,
+    total_points: f64,
+    number_of_games: u16,
+    forwards: Json<PositionTotals>,
+    defense: Json<PositionTotals>,
+    goalies: Json<PositionTotals>,
+    capped_positions: Option<Json<Vec<Position>>>,
+}
+
+impl From<PoolerStanding> for PoolerStandingObject {
+    fn from(standing: PoolerStanding) -> Self {
+        Self {
+            participant: standing.participant,
+            total_points: standing.total_points,
+            number_of_games: standing.number_of_games,
+            forwards: Json(standing.forwards),
+            defense: Json(standing.defense),
+            goalies: Json(standing.goalies),
+            capped_positions: standing.capped_positions.map(Json),
+        }
+    }
+}
+
+// The cumulative standings, and the head-to-head standings of the head-to-head pools.
+#[derive(SimpleObject)]
+pub struct PoolStandingsObject {
+    cumulative: Vec<PoolerStandingObject>,
+    head_to_head: Option<Vec<StandingObject>>,
+}
+
+impl From<PoolStandings> for PoolStandingsObject {
+    fn from(standings: PoolStandings) -> Self {
+        Self {
+            cumulative: standings
+                .cumulative
+                .into_iter()
+                .map(PoolerStandingObject::from)
+                .collect(),
+            head_to_head: standings
+                .head_to_head
+                .map(|head_to_head| head_to_head.into_iter().map(StandingObject::from).collect()),
+        }
+    }
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    async fn pool(&self, ctx: &Context<'_>, name: String) -> async_graphql::Result<PoolObject> {
+        validate_pool_view(ctx, &name).await?;
+        let pool_service = ctx.data::<PoolServiceHandle>()?;
+        let mut pool = pool_service.get_pool_by_name(&name).await?;
+
+        if let Some(context) = pool.context.as_mut() {
+            context.score_by_day = None;
+        }
+
+        Ok(PoolObject(pool))
+    }
+
+    async fn roster(
+        &self,
+        ctx: &Context<'_>,
+        pool_name: String,
+        participant_id: String,
+    ) -> async_graphql::Result<Option<Json<PoolerRoster>>> {
+        validate_pool_view(ctx, &pool_name).await?;
+        let pool_service = ctx.data::<PoolServiceHandle>()?;
+        let pool = pool_service.get_pool_by_name(&pool_name).await?;
+
+        Ok(pool
+            .context
+            .and_then(|mut context| context.pooler_roster.remove(&participant_id))
+            .map(Json))
+    }
+
+    // Same standings as the REST standings route.
+    async fn standings(
+        &self,
+        ctx: &Context<'_>,
+        pool_name: String,
+    ) -> async_graphql::Result<PoolStandingsObject> {
+        validate_pool_view(ctx, &pool_name).await?;
+        let pool_service = ctx.data::<PoolServiceHandle>()?;
+        let standings = pool_service.get_pool_standings(&pool_name).await?;
+
+        Ok(PoolStandingsObject::from(standings))
+    }
+
+    // The points of every pooler roster on a given date (%Y-%m-%d).
+    async fn daily_scores(
+        &self,
+        ctx: &Context<'_>,
+        pool_name: String,
+        date: String,
+    ) -> async_graphql::Result<Option<Json<HashMap<String, DailyRosterPoints>>>> {
+        validate_pool_view(ctx, &pool_name).await?;
+        let pool_service = ctx.data::<PoolServiceHandle>()?;
+        let pool = pool_service.get_pool_by_name(&pool_name).await?;
+
+        Ok(pool
+            .context
+            .and_then(|context| context.score_by_day)
+            .and_then(|mut score_by_day| score_by_day.remove(&date))
+            .map(Json))
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    // The messages broadcasted to the draft room of the pool.
+    async fn pool_updates(
+        &self,
+        ctx: &Context<'_>,
+        pool_name: String,
+    ) -> async_graphql::Result<impl Stream<Item = Json<serde_json::Value>>> {
+        // Same restriction as joining the draft room of a private pool.
+        validate_pool_view(ctx, &pool_name).await?;
+        let draft_server_info = ctx.data::<Arc<DraftServerInfo>>()?;
+        let rx = draft_server_info.get_room_tx(&pool_name)?.subscribe();
+
+        Ok(futures::stream::unfold(rx, |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(message) => {
+                        if let Ok(message) = serde_json::from_str(&message) {
+                            return Some((Json(message), rx));
+                        }
+                    }
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        }))
+    }
+}
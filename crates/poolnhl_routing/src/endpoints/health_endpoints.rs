@@ -0,0 +1,39 @@
+use axum::extract::{Json, State};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_interface::health::model::Readiness;
+use poolnhl_interface::health::service::HealthServiceHandle;
+
+pub struct HealthRouter;
+
+impl HealthRouter {
+    pub fn new(service_registry: ServiceRegistry) -> Router {
+        Router::new()
+            .route("/healthz", get(Self::healthz))
+            .route("/readyz", get(Self::readyz))
+            .with_state(service_registry)
+    }
+
+    // Liveness probe, the process is up as long as it answers.
+    async fn healthz() -> &'static str {
+        "ok"
+    }
+
+    // Readiness probe, answers 503 while a dependency of the server is not ready.
+    async fn readyz(
+        State(health_service): State<HealthServiceHandle>,
+    ) -> (StatusCode, Json<Readiness>) {
+        let readiness = health_service.get_readiness().await;
+
+        let status = if readiness.ready {
+            StatusCode::OK
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        };
+
+        (status, Json(readiness))
+    }
+}
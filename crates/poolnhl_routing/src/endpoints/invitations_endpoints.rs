@@ -0,0 +1,76 @@
+use axum::extract::{Json, Path, State};
+use axum::routing::{get, post};
+use axum::Router;
+
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_interface::errors::Result;
+use poolnhl_interface::invitations::model::{
+    AcceptInvitationRequest, CreateInvitationRequest, DeleteInvitationRequest, Invitation,
+};
+use poolnhl_interface::invitations::service::InvitationsServiceHandle;
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+
+pub struct InvitationsRouter;
+
+impl InvitationsRouter {
+    pub fn new(service_registry: ServiceRegistry) -> Router {
+        Router::new()
+            .route("/invitation/:token", get(Self::get_invitation))
+            .route("/invitations/:pool_name", get(Self::list_invitations))
+            .route("/create-invitation", post(Self::create_invitation))
+            .route("/accept-invitation", post(Self::accept_invitation))
+            .route("/delete-invitation", post(Self::delete_invitation))
+            .with_state(service_registry)
+    }
+
+    async fn get_invitation(
+        Path(token): Path<String>,
+        State(invitations_service): State<InvitationsServiceHandle>,
+    ) -> Result<Json<Invitation>> {
+        invitations_service.get_invitation(&token).await.map(Json)
+    }
+
+    async fn list_invitations(
+        token: UserEmailJwtPayload,
+        Path(pool_name): Path<String>,
+        State(invitations_service): State<InvitationsServiceHandle>,
+    ) -> Result<Json<Vec<Invitation>>> {
+        invitations_service
+            .list_invitations(&token.sub, &pool_name)
+            .await
+            .map(Json)
+    }
+
+    async fn create_invitation(
+        token: UserEmailJwtPayload,
+        State(invitations_service): State<InvitationsServiceHandle>,
+        Json(body): Json<CreateInvitationRequest>,
+    ) -> Result<Json<Invitation>> {
+        invitations_service
+            .create_invitation(&token.sub, body)
+            .await
+            .map(Json)
+    }
+
+    async fn accept_invitation(
+        token: UserEmailJwtPayload,
+        State(invitations_service): State<InvitationsServiceHandle>,
+        Json(body): Json<AcceptInvitationRequest>,
+    ) -> Result<Json<Invitation>> {
+        invitations_service
+            .accept_invitation(&token, body)
+            .await
+            .map(Json)
+    }
+
+    async fn delete_invitation(
+        token: UserEmailJwtPayload,
+        State(invitations_service): State<InvitationsServiceHandle>,
+        Json(body): Json<DeleteInvitationRequest>,
+    ) -> Result<Json<()>> {
+        invitations_service
+            .delete_invitation(&token.sub, body)
+            .await
+            .map(Json)
+    }
+}
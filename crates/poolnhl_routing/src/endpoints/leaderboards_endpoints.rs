@@ -0,0 +1,26 @@
+use axum::extract::{Json, Query, State};
+use axum::routing::get;
+use axum::Router;
+
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_interface::errors::Result;
+use poolnhl_interface::leaderboards::model::{Leaderboards, LeaderboardsQuery};
+use poolnhl_interface::leaderboards::service::LeaderboardsServiceHandle;
+
+pub struct LeaderboardsRouter;
+
+impl LeaderboardsRouter {
+    pub fn new(service_registry: ServiceRegistry) -> Router {
+        Router::new()
+            .route("/leaderboards", get(Self::get_leaderboards))
+            .with_state(service_registry)
+    }
+
+    // The all-time leaderboards of the completed pools, or of a single season.
+    async fn get_leaderboards(
+        State(leaderboards_service): State<LeaderboardsServiceHandle>,
+        Query(query): Query<LeaderboardsQuery>,
+    ) -> Result<Json<Leaderboards>> {
+        leaderboards_service.get_leaderboards(query).await.map(Json)
+    }
+}
@@ -0,0 +1,75 @@
+use axum::extract::{Json, Path, Query, State};
+use axum::routing::{get, post};
+use axum::Router;
+
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_interface::errors::Result;
+use poolnhl_interface::notifications::model::{
+    MarkNotificationsReadRequest, Notification, NotificationPreferences, NotificationsQuery,
+    UpdateNotificationPreferencesRequest,
+};
+use poolnhl_interface::notifications::service::NotificationsServiceHandle;
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+
+pub struct NotificationsRouter;
+
+impl NotificationsRouter {
+    pub fn new(service_registry: ServiceRegistry) -> Router {
+        Router::new()
+            .route("/notifications", get(Self::list_notifications))
+            .route(
+                "/notification-preferences/:pool_name",
+                get(Self::get_preferences),
+            )
+            .route("/mark-notifications-read", post(Self::mark_as_read))
+            .route(
+                "/update-notification-preferences",
+                post(Self::update_preferences),
+            )
+            .with_state(service_registry)
+    }
+
+    async fn list_notifications(
+        token: UserEmailJwtPayload,
+        Query(query): Query<NotificationsQuery>,
+        State(notifications_service): State<NotificationsServiceHandle>,
+    ) -> Result<Json<Vec<Notification>>> {
+        notifications_service
+            .list_notifications(&token.sub, query)
+            .await
+            .map(Json)
+    }
+
+    async fn get_preferences(
+        token: UserEmailJwtPayload,
+        Path(pool_name): Path<String>,
+        State(notifications_service): State<NotificationsServiceHandle>,
+    ) -> Result<Json<NotificationPreferences>> {
+        notifications_service
+            .get_preferences(&token.sub, &pool_name)
+            .await
+            .map(Json)
+    }
+
+    async fn mark_as_read(
+        token: UserEmailJwtPayload,
+        State(notifications_service): State<NotificationsServiceHandle>,
+        Json(body): Json<MarkNotificationsReadRequest>,
+    ) -> Result<Json<()>> {
+        notifications_service
+            .mark_as_read(&token.sub, body)
+            .await
+            .map(Json)
+    }
+
+    async fn update_preferences(
+        token: UserEmailJwtPayload,
+        State(notifications_service): State<NotificationsServiceHandle>,
+        Json(body): Json<UpdateNotificationPreferencesRequest>,
+    ) -> Result<Json<NotificationPreferences>> {
+        notifications_service
+            .update_preferences(&token, body)
+            .await
+            .map(Json)
+    }
+}
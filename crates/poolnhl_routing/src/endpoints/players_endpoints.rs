@@ -5,7 +5,7 @@ use axum::Router;
 use poolnhl_infrastructure::services::ServiceRegistry;
 
 use poolnhl_interface::errors::Result;
-use poolnhl_interface::players::model::{GetPlayerQuery, PlayerInfo};
+use poolnhl_interface::players::model::{DailySchedule, GetPlayerQuery, PlayerInfo};
 use poolnhl_interface::players::service::PlayersServiceHandle;
 
 pub struct PlayersRouter;
@@ -15,6 +15,9 @@ impl PlayersRouter {
         Router::new()
             .route("/get-players", get(Self::get_players))
             .route("/get-players/:name", get(Self::get_players_with_name))
+            .route("/players", get(Self::get_players))
+            .route("/players/:id", get(Self::get_player))
+            .route("/schedule/:date", get(Self::get_schedule))
             .with_state(service_registry)
     }
 
@@ -31,4 +34,18 @@ impl PlayersRouter {
     ) -> Result<Json<Vec<PlayerInfo>>> {
         players_service.get_players_with_name(&name).await.map(Json)
     }
+
+    async fn get_player(
+        State(players_service): State<PlayersServiceHandle>,
+        Path(id): Path<u32>,
+    ) -> Result<Json<PlayerInfo>> {
+        players_service.get_player(id).await.map(Json)
+    }
+
+    async fn get_schedule(
+        State(players_service): State<PlayersServiceHandle>,
+        Path(date): Path<String>,
+    ) -> Result<Json<DailySchedule>> {
+        players_service.get_schedule(&date).await.map(Json)
+    }
 }
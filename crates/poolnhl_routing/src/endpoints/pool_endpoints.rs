@@ -1,70 +1,342 @@
-use axum::extract::{Json, Path, State};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::extract::{Json, Path, Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
 use axum::routing::{get, post};
-use axum::Router;
+use axum::{middleware, Router};
+use futures::Stream;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
 
 use poolnhl_infrastructure::services::ServiceRegistry;
-use poolnhl_interface::errors::Result;
+use poolnhl_interface::draft::model::DraftServerInfo;
+use poolnhl_interface::errors::{AppError, Result};
+use poolnhl_interface::players::model::{GetPlayerQuery, PlayerInfo};
 use poolnhl_interface::pool::model::{
-    AddPlayerRequest, CompleteProtectionRequest, CreateTradeRequest, DeleteTradeRequest,
-    FillSpotRequest, GenerateDynastyRequest, MarkAsFinalRequest, ModifyRosterRequest, Pool,
-    PoolCreationRequest, PoolDeletionRequest, ProjectedPoolShort, ProtectPlayersRequest,
-    RemovePlayerRequest, RespondTradeRequest, UpdatePoolSettingsRequest,
+    AddPlayerRequest, AdoptTeamRequest, AutoLineupRequest, CapReport, ClaimTeamRequest,
+    ClonePoolRequest, CompleteProtectionRequest, CreateTradeRequest, DeletePoolTemplateRequest,
+    DeleteTradeRequest, DraftRecapPick, DryRunQuery, FillSpotRequest, GenerateDynastyRequest,
+    GenerateTeamClaimRequest, ListPoolsQuery, MarkAsFinalRequest, MatchupSchedule,
+    ModifyRosterRequest, Pool, PoolCreationRequest, PoolDeletionRequest, PoolEvent,
+    PoolFieldsQuery, PoolHistoryQuery, PoolPlayerStats, PoolStandings, PoolTemplate,
+    ProjectedPoolShort, ProtectPlayersRequest, RemovePlayerRequest, RenamePoolRequest,
+    RespondTradeRequest, SavePoolTemplateRequest, TeamClaim, TestWebhookRequest, TradeAnalysis,
+    TradeBlockListing, TransferOwnershipRequest, UpdateOrphanedTeamRequest,
+    UpdatePoolSettingsRequest, UpdateTeamManagersRequest, UpdateTeamProfileRequest,
+    UpdateTradeBlockRequest, UpdateWatchlistRequest, VetoTradeRequest, Watchlist,
 };
 use poolnhl_interface::pool::service::PoolServiceHandle;
 use poolnhl_interface::users::model::UserEmailJwtPayload;
 
+use crate::idempotency::idempotency_middleware;
+
 pub struct PoolRouter;
 
+// Receive the updates of a pool, the channel of the pool is dropped with its last listener.
+struct PoolUpdatesListener {
+    rx: Option<broadcast::Receiver<String>>,
+    pool_name: String,
+    draft_server_info: Arc<DraftServerInfo>,
+}
+
+impl PoolUpdatesListener {
+    async fn recv(&mut self) -> std::result::Result<String, RecvError> {
+        match self.rx.as_mut() {
+            Some(rx) => rx.recv().await,
+            None => Err(RecvError::Closed),
+        }
+    }
+}
+
+impl Drop for PoolUpdatesListener {
+    fn drop(&mut self) {
+        // The receiver is dropped first so it is not counted anymore.
+        self.rx.take();
+        let _ = self
+            .draft_server_info
+            .unsubscribe_pool_updates(&self.pool_name);
+    }
+}
+
+// Only the members and the invited users see a private pool, the other pools are public.
+async fn validate_pool_view(
+    pool_service: &PoolServiceHandle,
+    token: &Option<UserEmailJwtPayload>,
+    name: &str,
+) -> Result<()> {
+    pool_service
+        .validate_pool_view(token.as_ref().map(|token| token.sub.as_str()), name)
+        .await
+}
+
 impl PoolRouter {
     pub fn new(service_registry: ServiceRegistry) -> Router {
+        // The retries of the create-pool, create-trade and fill-spot requests are not applied twice
+        // when they hold an Idempotency-Key header.
         Router::new()
             .route("/pool/:name", get(Self::get_pool_by_name))
+            .route("/pool/:name/matchups", get(Self::get_matchups))
+            .route("/pool/:name/standings", get(Self::get_standings))
+            .route("/pool/:name/history", get(Self::get_pool_history))
+            .route("/pool/:name/draft-recap", get(Self::get_draft_recap))
+            .route("/pool/:name/free-agents", get(Self::get_free_agents))
+            .route("/pool/:name/cap-report", get(Self::get_cap_report))
+            .route(
+                "/pool/:name/trade/:id/analysis",
+                get(Self::get_trade_analysis),
+            )
+            .route("/pool/:name/player-stats", get(Self::get_players_stats))
+            .route("/pool/:name/events", get(Self::pool_events))
+            .route("/pool/:name/team-profile", post(Self::update_team_profile))
+            .route(
+                "/pool/:name/orphaned-team",
+                post(Self::update_orphaned_team),
+            )
+            .route("/pool/:name/adopt-team", post(Self::adopt_team))
+            .route(
+                "/pool/:name/watchlist",
+                get(Self::get_watchlist).post(Self::update_watchlist),
+            )
+            .route(
+                "/pool/:name/trade-block",
+                get(Self::get_trade_block).post(Self::update_trade_block),
+            )
             .route(
                 "/pool/:name/:start_date/:from",
                 get(Self::get_pool_by_name_with_range),
             )
             .route("/pools/:season", get(Self::get_pools))
-            .route("/create-pool", post(Self::create_pool))
+            .route(
+                "/create-pool",
+                post(Self::create_pool).layer(middleware::from_fn_with_state(
+                    service_registry.clone(),
+                    idempotency_middleware,
+                )),
+            )
             .route("/delete-pool", post(Self::delete_pool))
+            .route("/clone-pool", post(Self::clone_pool))
+            .route("/pool-templates", get(Self::list_pool_templates))
+            .route("/save-pool-template", post(Self::save_pool_template))
+            .route("/delete-pool-template", post(Self::delete_pool_template))
             .route("/add-player", post(Self::add_player))
             .route("/remove-player", post(Self::remove_player))
-            .route("/create-trade", post(Self::create_trade))
+            .route(
+                "/create-trade",
+                post(Self::create_trade).layer(middleware::from_fn_with_state(
+                    service_registry.clone(),
+                    idempotency_middleware,
+                )),
+            )
             .route("/delete-trade", post(Self::delete_trade))
             .route("/respond-trade", post(Self::respond_trade))
-            .route("/fill-spot", post(Self::fill_spot))
+            .route("/veto-trade", post(Self::veto_trade))
+            .route(
+                "/fill-spot",
+                post(Self::fill_spot).layer(middleware::from_fn_with_state(
+                    service_registry.clone(),
+                    idempotency_middleware,
+                )),
+            )
             .route("/protect-players", post(Self::protect_players))
             .route("/complete-protection", post(Self::complete_protection))
             .route("/modify-roster", post(Self::modify_roster))
+            .route("/auto-lineup", post(Self::set_auto_lineup))
             .route("/update-pool-settings", post(Self::update_pool_settings))
             .route("/mark-as-final", post(Self::mark_as_final))
+            .route("/transfer-ownership", post(Self::transfer_ownership))
+            .route("/rename-pool", post(Self::rename_pool))
+            .route("/update-team-managers", post(Self::update_team_managers))
+            .route("/generate-team-claim", post(Self::generate_team_claim))
+            .route("/claim-team", post(Self::claim_team))
             .route("/generate-dynasty", post(Self::generate_dynasty))
+            .route("/test-webhook", post(Self::test_webhook))
             .with_state(service_registry)
     }
 
     async fn get_pool_by_name(
+        token: Option<UserEmailJwtPayload>,
         Path(name): Path<String>,
+        Query(query): Query<PoolFieldsQuery>,
         State(pool_service): State<PoolServiceHandle>,
-    ) -> Result<Json<Pool>> {
-        pool_service.get_pool_by_name(&name).await.map(Json)
+    ) -> Result<Response> {
+        validate_pool_view(&pool_service, &token, &name).await?;
+
+        // Lightweight views of the pool are returned when only some fields are requested.
+        let paths = query.get_projected_paths()?;
+
+        let Some(games_date) = &query.games_date else {
+            return match paths {
+                Some(paths) => pool_service
+                    .get_partial_pool_by_name(&name, &paths)
+                    .await
+                    .map(|pool| Json(pool).into_response()),
+                None => pool_service
+                    .get_pool_by_name(&name)
+                    .await
+                    .map(|pool| Json(pool).into_response()),
+            };
+        };
+
+        let mut pool = match paths {
+            Some(paths) => pool_service.get_partial_pool_by_name(&name, &paths).await?,
+            None => serde_json::to_value(pool_service.get_pool_by_name(&name).await?)
+                .map_err(|e| AppError::ParseError { msg: e.to_string() })?,
+        };
+
+        // The starters with a game are computed, they are never stored in the pool.
+        let starters_games = pool_service.get_starters_games(&name, games_date).await?;
+        if let Some(pool) = pool.as_object_mut() {
+            pool.insert(
+                "starters_games".to_string(),
+                serde_json::to_value(starters_games)
+                    .map_err(|e| AppError::ParseError { msg: e.to_string() })?,
+            );
+        }
+
+        Ok(Json(pool).into_response())
     }
 
     async fn get_pool_by_name_with_range(
+        token: Option<UserEmailJwtPayload>,
         Path((name, start_date, from)): Path<(String, String, String)>,
         State(pool_service): State<PoolServiceHandle>,
     ) -> Result<Json<Pool>> {
+        validate_pool_view(&pool_service, &token, &name).await?;
+
         pool_service
             .get_pool_by_name_with_range(&name, &start_date, &from)
             .await
             .map(Json)
     }
 
+    async fn get_matchups(
+        token: Option<UserEmailJwtPayload>,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+    ) -> Result<Json<MatchupSchedule>> {
+        validate_pool_view(&pool_service, &token, &name).await?;
+
+        pool_service.get_matchups(&name).await.map(Json)
+    }
+
+    async fn get_standings(
+        token: Option<UserEmailJwtPayload>,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+    ) -> Result<Json<PoolStandings>> {
+        validate_pool_view(&pool_service, &token, &name).await?;
+
+        pool_service.get_pool_standings(&name).await.map(Json)
+    }
+
+    async fn pool_events(
+        token: UserEmailJwtPayload,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+        State(draft_server_info): State<Arc<DraftServerInfo>>,
+    ) -> Result<Sse<impl Stream<Item = std::result::Result<Event, Infallible>>>> {
+        // Push the trade, roster and score updates of the pool as server-sent events.
+        // The pool needs to exist, so no channel is created for an unknown pool name.
+        pool_service
+            .validate_pool_view(Some(&token.sub), &name)
+            .await?;
+
+        let listener = PoolUpdatesListener {
+            rx: Some(draft_server_info.subscribe_pool_updates(&name)?),
+            pool_name: name,
+            draft_server_info,
+        };
+
+        let stream = futures::stream::unfold(listener, |mut listener| async move {
+            loop {
+                match listener.recv().await {
+                    Ok(message) => return Some((Ok(Event::default().data(message)), listener)),
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+
+        Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+    }
+
+    async fn get_players_stats(
+        token: Option<UserEmailJwtPayload>,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+    ) -> Result<Json<Vec<PoolPlayerStats>>> {
+        validate_pool_view(&pool_service, &token, &name).await?;
+
+        pool_service.get_players_stats(&name).await.map(Json)
+    }
+
+    async fn get_cap_report(
+        token: Option<UserEmailJwtPayload>,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+    ) -> Result<Json<CapReport>> {
+        validate_pool_view(&pool_service, &token, &name).await?;
+
+        pool_service.get_cap_report(&name).await.map(Json)
+    }
+
+    async fn get_trade_analysis(
+        token: Option<UserEmailJwtPayload>,
+        Path((name, trade_id)): Path<(String, u32)>,
+        State(pool_service): State<PoolServiceHandle>,
+    ) -> Result<Json<TradeAnalysis>> {
+        validate_pool_view(&pool_service, &token, &name).await?;
+
+        pool_service
+            .get_trade_analysis(&name, trade_id)
+            .await
+            .map(Json)
+    }
+
+    async fn get_draft_recap(
+        token: Option<UserEmailJwtPayload>,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+    ) -> Result<Json<Vec<DraftRecapPick>>> {
+        validate_pool_view(&pool_service, &token, &name).await?;
+
+        pool_service.get_draft_recap(&name).await.map(Json)
+    }
+
+    async fn get_free_agents(
+        token: Option<UserEmailJwtPayload>,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+        Query(query): Query<GetPlayerQuery>,
+    ) -> Result<Json<Vec<PlayerInfo>>> {
+        validate_pool_view(&pool_service, &token, &name).await?;
+
+        pool_service.get_free_agents(&name, query).await.map(Json)
+    }
+
+    async fn get_pool_history(
+        token: UserEmailJwtPayload,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+        Query(query): Query<PoolHistoryQuery>,
+    ) -> Result<Json<Vec<PoolEvent>>> {
+        pool_service
+            .get_pool_history(&token.sub, &name, query)
+            .await
+            .map(Json)
+    }
+
     /// get all Pool documents but only part of the information.
     async fn get_pools(
         Path(season): Path<u32>,
+        Query(query): Query<ListPoolsQuery>,
         State(pool_service): State<PoolServiceHandle>,
     ) -> Result<Json<Vec<ProjectedPoolShort>>> {
         print!("{}", season);
-        pool_service.list_pools(season).await.map(Json)
+        pool_service.list_pools(season, query).await.map(Json)
     }
 
     async fn create_pool(
@@ -83,6 +355,43 @@ impl PoolRouter {
         pool_service.delete_pool(&token.sub, body).await.map(Json)
     }
 
+    async fn clone_pool(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<ClonePoolRequest>,
+    ) -> Result<Json<Pool>> {
+        pool_service.clone_pool(&token.sub, body).await.map(Json)
+    }
+
+    async fn list_pool_templates(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+    ) -> Result<Json<Vec<PoolTemplate>>> {
+        pool_service.list_pool_templates(&token.sub).await.map(Json)
+    }
+
+    async fn save_pool_template(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<SavePoolTemplateRequest>,
+    ) -> Result<Json<PoolTemplate>> {
+        pool_service
+            .save_pool_template(&token.sub, body)
+            .await
+            .map(Json)
+    }
+
+    async fn delete_pool_template(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<DeletePoolTemplateRequest>,
+    ) -> Result<Json<()>> {
+        pool_service
+            .delete_pool_template(&token.sub, body)
+            .await
+            .map(Json)
+    }
+
     async fn add_player(
         token: UserEmailJwtPayload,
         State(pool_service): State<PoolServiceHandle>,
@@ -101,13 +410,21 @@ impl PoolRouter {
 
     async fn create_trade(
         token: UserEmailJwtPayload,
+        Query(query): Query<DryRunQuery>,
         State(pool_service): State<PoolServiceHandle>,
         Json(mut body): Json<CreateTradeRequest>,
-    ) -> Result<Json<Pool>> {
+    ) -> Result<Response> {
+        if query.is_dry_run() {
+            return pool_service
+                .preview_create_trade(&token.sub, &mut body)
+                .await
+                .map(|preview| Json(preview).into_response());
+        }
+
         pool_service
             .create_trade(&token.sub, &mut body)
             .await
-            .map(Json)
+            .map(|pool| Json(pool).into_response())
     }
 
     async fn delete_trade(
@@ -120,10 +437,29 @@ impl PoolRouter {
 
     async fn respond_trade(
         token: UserEmailJwtPayload,
+        Query(query): Query<DryRunQuery>,
         State(pool_service): State<PoolServiceHandle>,
         Json(body): Json<RespondTradeRequest>,
+    ) -> Result<Response> {
+        if query.is_dry_run() {
+            return pool_service
+                .preview_respond_trade(&token.sub, body)
+                .await
+                .map(|preview| Json(preview).into_response());
+        }
+
+        pool_service
+            .respond_trade(&token.sub, body)
+            .await
+            .map(|pool| Json(pool).into_response())
+    }
+
+    async fn veto_trade(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<VetoTradeRequest>,
     ) -> Result<Json<Pool>> {
-        pool_service.respond_trade(&token.sub, body).await.map(Json)
+        pool_service.veto_trade(&token.sub, body).await.map(Json)
     }
 
     async fn fill_spot(
@@ -157,10 +493,32 @@ impl PoolRouter {
 
     async fn modify_roster(
         token: UserEmailJwtPayload,
+        Query(query): Query<DryRunQuery>,
         State(pool_service): State<PoolServiceHandle>,
         Json(body): Json<ModifyRosterRequest>,
+    ) -> Result<Response> {
+        if query.is_dry_run() {
+            return pool_service
+                .preview_modify_roster(&token.sub, body)
+                .await
+                .map(|preview| Json(preview).into_response());
+        }
+
+        pool_service
+            .modify_roster(&token.sub, body)
+            .await
+            .map(|pool| Json(pool).into_response())
+    }
+
+    async fn set_auto_lineup(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<AutoLineupRequest>,
     ) -> Result<Json<Pool>> {
-        pool_service.modify_roster(&token.sub, body).await.map(Json)
+        pool_service
+            .set_auto_lineup(&token.sub, body)
+            .await
+            .map(Json)
     }
 
     async fn update_pool_settings(
@@ -181,6 +539,135 @@ impl PoolRouter {
     ) -> Result<Json<Pool>> {
         pool_service.mark_as_final(&token.sub, body).await.map(Json)
     }
+    async fn generate_team_claim(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<GenerateTeamClaimRequest>,
+    ) -> Result<Json<TeamClaim>> {
+        pool_service
+            .generate_team_claim(&token.sub, body)
+            .await
+            .map(Json)
+    }
+    async fn claim_team(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<ClaimTeamRequest>,
+    ) -> Result<Json<Pool>> {
+        pool_service.claim_team(&token, body).await.map(Json)
+    }
+    async fn update_team_profile(
+        token: UserEmailJwtPayload,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<UpdateTeamProfileRequest>,
+    ) -> Result<Json<Pool>> {
+        pool_service
+            .update_team_profile(&token.sub, &name, body)
+            .await
+            .map(Json)
+    }
+    async fn get_watchlist(
+        token: UserEmailJwtPayload,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+    ) -> Result<Json<Watchlist>> {
+        pool_service
+            .get_watchlist(&token.sub, &name)
+            .await
+            .map(Json)
+    }
+
+    async fn update_watchlist(
+        token: UserEmailJwtPayload,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<UpdateWatchlistRequest>,
+    ) -> Result<Json<Watchlist>> {
+        pool_service
+            .update_watchlist(&token.sub, &name, body)
+            .await
+            .map(Json)
+    }
+
+    async fn update_orphaned_team(
+        token: UserEmailJwtPayload,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<UpdateOrphanedTeamRequest>,
+    ) -> Result<Json<Pool>> {
+        pool_service
+            .update_orphaned_team(&token.sub, &name, body)
+            .await
+            .map(Json)
+    }
+    async fn adopt_team(
+        token: UserEmailJwtPayload,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<AdoptTeamRequest>,
+    ) -> Result<Json<Pool>> {
+        pool_service
+            .adopt_team(&token.sub, &name, body)
+            .await
+            .map(Json)
+    }
+    async fn get_trade_block(
+        token: Option<UserEmailJwtPayload>,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+    ) -> Result<Json<HashMap<String, Vec<TradeBlockListing>>>> {
+        validate_pool_view(&pool_service, &token, &name).await?;
+
+        pool_service.get_trade_block(&name).await.map(Json)
+    }
+    async fn update_trade_block(
+        token: UserEmailJwtPayload,
+        Path(name): Path<String>,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<UpdateTradeBlockRequest>,
+    ) -> Result<Json<Pool>> {
+        pool_service
+            .update_trade_block(&token.sub, &name, body)
+            .await
+            .map(Json)
+    }
+    async fn update_team_managers(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<UpdateTeamManagersRequest>,
+    ) -> Result<Json<Pool>> {
+        pool_service
+            .update_team_managers(&token.sub, body)
+            .await
+            .map(Json)
+    }
+    async fn transfer_ownership(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<TransferOwnershipRequest>,
+    ) -> Result<Json<Pool>> {
+        pool_service
+            .transfer_ownership(&token.sub, body)
+            .await
+            .map(Json)
+    }
+
+    async fn rename_pool(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<RenamePoolRequest>,
+    ) -> Result<Json<Pool>> {
+        pool_service.rename_pool(&token.sub, body).await.map(Json)
+    }
+    async fn test_webhook(
+        token: UserEmailJwtPayload,
+        State(pool_service): State<PoolServiceHandle>,
+        Json(body): Json<TestWebhookRequest>,
+    ) -> Result<Json<()>> {
+        pool_service.test_webhook(&token.sub, body).await.map(Json)
+    }
+
     async fn generate_dynasty(
         token: UserEmailJwtPayload,
         State(pool_service): State<PoolServiceHandle>,
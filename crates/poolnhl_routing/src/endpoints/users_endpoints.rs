@@ -0,0 +1,98 @@
+use axum::extract::{Json, Path, State};
+use axum::routing::{get, post};
+use axum::Router;
+
+use poolnhl_infrastructure::jwt::validate_token;
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_interface::achievements::model::Achievement;
+use poolnhl_interface::achievements::service::AchievementsServiceHandle;
+use poolnhl_interface::errors::Result;
+use poolnhl_interface::users::model::{
+    AccountDeletion, AccountMerge, MergeAccountsRequest, TokenRevocation, UserDataExport,
+    UserEmailJwtPayload, UserId, UserProfile,
+};
+use poolnhl_interface::users::service::UsersServiceHandle;
+
+pub struct UsersRouter;
+
+impl UsersRouter {
+    pub fn new(service_registry: ServiceRegistry) -> Router {
+        Router::new()
+            .route("/auth/logout-everywhere", post(Self::logout_everywhere))
+            .route("/users/:id/profile", get(Self::get_user_profile))
+            .route("/users/:id/achievements", get(Self::list_achievements))
+            .route("/users/me/export", get(Self::export_user_data))
+            .route("/users/me/delete", post(Self::request_account_deletion))
+            .route(
+                "/users/me/cancel-delete",
+                post(Self::cancel_account_deletion),
+            )
+            .route("/users/me/merge", post(Self::merge_accounts))
+            .with_state(service_registry)
+    }
+
+    async fn logout_everywhere(
+        token: UserEmailJwtPayload,
+        State(users_service): State<UsersServiceHandle>,
+    ) -> Result<Json<TokenRevocation>> {
+        users_service.logout_everywhere(&token.sub).await.map(Json)
+    }
+
+    async fn get_user_profile(
+        Path(user_id): Path<String>,
+        State(users_service): State<UsersServiceHandle>,
+    ) -> Result<Json<UserProfile>> {
+        let user_id = UserId::parse(&user_id)?;
+        users_service.get_user_profile(&user_id).await.map(Json)
+    }
+
+    async fn list_achievements(
+        Path(user_id): Path<String>,
+        State(achievements_service): State<AchievementsServiceHandle>,
+    ) -> Result<Json<Vec<Achievement>>> {
+        let user_id = UserId::parse(&user_id)?;
+        achievements_service
+            .list_achievements(&user_id)
+            .await
+            .map(Json)
+    }
+
+    async fn export_user_data(
+        token: UserEmailJwtPayload,
+        State(users_service): State<UsersServiceHandle>,
+    ) -> Result<Json<UserDataExport>> {
+        users_service.export_user_data(&token).await.map(Json)
+    }
+
+    async fn request_account_deletion(
+        token: UserEmailJwtPayload,
+        State(users_service): State<UsersServiceHandle>,
+    ) -> Result<Json<AccountDeletion>> {
+        users_service
+            .request_account_deletion(&token.sub)
+            .await
+            .map(Json)
+    }
+
+    async fn cancel_account_deletion(
+        token: UserEmailJwtPayload,
+        State(users_service): State<UsersServiceHandle>,
+    ) -> Result<()> {
+        users_service.cancel_account_deletion(&token.sub).await
+    }
+
+    async fn merge_accounts(
+        token: UserEmailJwtPayload,
+        State(service_registry): State<ServiceRegistry>,
+        Json(body): Json<MergeAccountsRequest>,
+    ) -> Result<Json<AccountMerge>> {
+        // The token of the other account verifies the user owns it too.
+        let merged_token = validate_token(&body.token, &service_registry).await?;
+
+        service_registry
+            .users_service
+            .merge_accounts(&token, &merged_token)
+            .await
+            .map(Json)
+    }
+}
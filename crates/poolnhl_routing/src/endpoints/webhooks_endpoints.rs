@@ -0,0 +1,71 @@
+use axum::extract::{Json, Path, State};
+use axum::routing::{get, post};
+use axum::Router;
+
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_interface::errors::Result;
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+use poolnhl_interface::webhooks::model::{
+    CreateWebhookSubscriptionRequest, DeleteWebhookSubscriptionRequest, WebhookDelivery,
+    WebhookSubscription,
+};
+use poolnhl_interface::webhooks::service::WebhooksServiceHandle;
+
+pub struct WebhooksRouter;
+
+impl WebhooksRouter {
+    pub fn new(service_registry: ServiceRegistry) -> Router {
+        Router::new()
+            .route("/webhooks", get(Self::list_subscriptions))
+            .route(
+                "/webhook-deliveries/:subscription_id",
+                get(Self::list_deliveries),
+            )
+            .route("/create-webhook", post(Self::create_subscription))
+            .route("/delete-webhook", post(Self::delete_subscription))
+            .with_state(service_registry)
+    }
+
+    async fn list_subscriptions(
+        token: UserEmailJwtPayload,
+        State(webhooks_service): State<WebhooksServiceHandle>,
+    ) -> Result<Json<Vec<WebhookSubscription>>> {
+        webhooks_service
+            .list_subscriptions(&token.sub)
+            .await
+            .map(Json)
+    }
+
+    async fn list_deliveries(
+        token: UserEmailJwtPayload,
+        Path(subscription_id): Path<String>,
+        State(webhooks_service): State<WebhooksServiceHandle>,
+    ) -> Result<Json<Vec<WebhookDelivery>>> {
+        webhooks_service
+            .list_deliveries(&token.sub, &subscription_id)
+            .await
+            .map(Json)
+    }
+
+    async fn create_subscription(
+        token: UserEmailJwtPayload,
+        State(webhooks_service): State<WebhooksServiceHandle>,
+        Json(body): Json<CreateWebhookSubscriptionRequest>,
+    ) -> Result<Json<WebhookSubscription>> {
+        webhooks_service
+            .create_subscription(&token.sub, body)
+            .await
+            .map(Json)
+    }
+
+    async fn delete_subscription(
+        token: UserEmailJwtPayload,
+        State(webhooks_service): State<WebhooksServiceHandle>,
+        Json(body): Json<DeleteWebhookSubscriptionRequest>,
+    ) -> Result<Json<()>> {
+        webhooks_service
+            .delete_subscription(&token.sub, body)
+            .await
+            .map(Json)
+    }
+}
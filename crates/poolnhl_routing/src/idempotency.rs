@@ -0,0 +1,127 @@
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use tracing::warn;
+
+use poolnhl_infrastructure::idempotency::{self, IdempotentRequest};
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_interface::errors::{AppError, ErrorCode};
+use poolnhl_interface::users::model::UserEmailJwtPayload;
+
+// The maximum size of the bodies read to fingerprint the requests and store their responses.
+const MAX_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+// Middleware applying a request made with an Idempotency-Key header only once.
+// The retries of the request are answered with the response stored for the key.
+pub async fn idempotency_middleware(
+    State(service_registry): State<ServiceRegistry>,
+    token: Option<UserEmailJwtPayload>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(idempotency_key) = request
+        .headers()
+        .get("idempotency-key")
+        .and_then(|idempotency_key| idempotency_key.to_str().ok())
+        .map(|idempotency_key| idempotency_key.to_string())
+    else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => return AppError::ParseError { msg: e.to_string() }.into_response(),
+    };
+
+    // The keys are scoped to the authenticated user and the route so that two clients cannot collide,
+    // a refreshed token of the same user still replays the response.
+    // The query is part of the route, a preview (?dry_run=true) never answers the request itself.
+    let user_id = token.as_ref().map_or("", |token| token.sub.as_str());
+    let key = idempotency::fingerprint(&[
+        user_id.as_bytes(),
+        parts
+            .uri
+            .path_and_query()
+            .map_or(parts.uri.path(), |path_and_query| path_and_query.as_str())
+            .as_bytes(),
+        idempotency_key.as_bytes(),
+    ]);
+    let fingerprint = idempotency::fingerprint(&[parts.method.as_str().as_bytes(), &body]);
+    let idempotency_store = &service_registry.idempotency_store;
+
+    match idempotency_store.reserve(&key, &fingerprint).await {
+        Ok(None) => {}
+        Ok(Some(previous_request)) => return replay_response(previous_request, &fingerprint),
+        Err(e) => return e.into_response(),
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    // The server errors are not stored so the request can be retried.
+    if response.status().is_server_error() {
+        if let Err(e) = idempotency_store.release(&key).await {
+            warn!("{}", e);
+        }
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => return AppError::ParseError { msg: e.to_string() }.into_response(),
+    };
+
+    let content_type = parts
+        .headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(|content_type| content_type.to_string());
+
+    if let Err(e) = idempotency_store
+        .complete(
+            &key,
+            parts.status.as_u16(),
+            content_type,
+            String::from_utf8_lossy(&body).to_string(),
+        )
+        .await
+    {
+        warn!("{}", e);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+// Answer a retried request with the response of the request that first used the key.
+fn replay_response(previous_request: IdempotentRequest, fingerprint: &str) -> Response {
+    if previous_request.fingerprint != fingerprint {
+        return AppError::CodedError {
+            code: ErrorCode::IdempotencyKeyReused,
+            msg: "The Idempotency-Key was already used for another request.".to_string(),
+        }
+        .into_response();
+    }
+
+    let (Some(status), Some(body)) = (previous_request.status, previous_request.body) else {
+        return AppError::CodedError {
+            code: ErrorCode::RequestInProgress,
+            msg: "The request made with this Idempotency-Key is still being processed.".to_string(),
+        }
+        .into_response();
+    };
+
+    let mut response = Response::builder()
+        .status(status)
+        .header("idempotent-replayed", "true");
+
+    if let Some(content_type) = previous_request.content_type {
+        response = response.header(CONTENT_TYPE, content_type);
+    }
+
+    response
+        .body(Body::from(body))
+        .unwrap_or_else(|e| AppError::ParseError { msg: e.to_string() }.into_response())
+}
@@ -1,2 +1,6 @@
 pub mod endpoints;
+pub mod idempotency;
+pub mod localization;
+pub mod openapi;
+pub mod rate_limit;
 pub mod router;
@@ -0,0 +1,47 @@
+use axum::extract::Request;
+use axum::http::header::{ACCEPT_LANGUAGE, CONTENT_LANGUAGE, CONTENT_LENGTH};
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use poolnhl_interface::errors::ErrorResponse;
+use poolnhl_interface::localization::Language;
+
+// Middleware translating the message of the error responses in the language of the Accept-Language header.
+pub async fn localization_middleware(request: Request, next: Next) -> Response {
+    let language = request
+        .headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|accept_language| accept_language.to_str().ok())
+        .map(Language::from_accept_language)
+        .unwrap_or_default();
+
+    let mut response = next.run(request).await;
+
+    let Some(error) = response.extensions_mut().remove::<ErrorResponse>() else {
+        return response;
+    };
+
+    let (mut parts, body) = response.into_parts();
+    parts.headers.insert(
+        CONTENT_LANGUAGE,
+        HeaderValue::from_static(language.get_tag()),
+    );
+
+    // The errors without a catalog message keep the message describing them.
+    let Some(message) = language.get_error_message(error.code) else {
+        return Response::from_parts(parts, body);
+    };
+
+    parts.headers.remove(CONTENT_LENGTH);
+
+    (
+        parts,
+        Json(ErrorResponse {
+            message: message.to_string(),
+            ..error
+        }),
+    )
+        .into_response()
+}
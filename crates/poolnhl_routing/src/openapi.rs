@@ -0,0 +1,1536 @@
+use axum::Router;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+use utoipa_swagger_ui::SwaggerUi;
+
+use poolnhl_interface::achievements::model::Achievement;
+use poolnhl_interface::admin::model::{
+    AdminDeletePoolRequest, AdminDraftRecordingRequest, AdminRecordDraftRequest,
+    AdminRecumulatePointsRequest, AdminRenamePoolRequest, AdminSetVirtualTodayRequest,
+    AdminTransferOwnershipRequest, AdminUpdateContextRequest, PoolsMigrationReport,
+};
+use poolnhl_interface::archives::model::SeasonArchive;
+use poolnhl_interface::daily_leaders::model::{DailyLeaders, DailyLeadersRangeQuery};
+use poolnhl_interface::draft::model::{
+    ChatHistoryQuery, ChatMessage, DraftRecording, DraftSuggestions, RoomUser,
+};
+use poolnhl_interface::errors::ErrorResponse;
+use poolnhl_interface::invitations::model::{
+    AcceptInvitationRequest, CreateInvitationRequest, DeleteInvitationRequest, Invitation,
+};
+use poolnhl_interface::jobs::model::{JobInfo, TriggerJobRequest};
+use poolnhl_interface::leaderboards::model::{Leaderboards, LeaderboardsQuery};
+use poolnhl_interface::notifications::model::{
+    MarkNotificationsReadRequest, Notification, NotificationPreferences, NotificationsQuery,
+    UpdateNotificationPreferencesRequest,
+};
+use poolnhl_interface::players::model::{DailySchedule, GetPlayerQuery, PlayerInfo};
+use poolnhl_interface::pool::model::{
+    AddPlayerRequest, AdoptTeamRequest, AutoLineupRequest, CapReport, ClaimTeamRequest,
+    ClonePoolRequest, CompleteProtectionRequest, CreateTradeRequest, DeletePoolTemplateRequest,
+    DeleteTradeRequest, DraftRecapPick, DryRunQuery, FillSpotRequest, GenerateDynastyRequest,
+    GenerateTeamClaimRequest, ListPoolsQuery, MarkAsFinalRequest, MatchupSchedule,
+    ModifyRosterRequest, Pool, PoolCreationRequest, PoolDeletionRequest, PoolEvent,
+    PoolFieldsQuery, PoolHistoryQuery, PoolPlayerStats, PoolPreview, PoolStandings, PoolTemplate,
+    PoolUpdate, ProjectedPoolShort, ProtectPlayersRequest, RemovePlayerRequest, RenamePoolRequest,
+    RespondTradeRequest, SavePoolTemplateRequest, TeamClaim, TestWebhookRequest, TradeAnalysis,
+    TradeBlockListing, TransferOwnershipRequest, UpdateOrphanedTeamRequest,
+    UpdatePoolSettingsRequest, UpdateTeamManagersRequest, UpdateTeamProfileRequest,
+    UpdateTradeBlockRequest, UpdateWatchlistRequest, VetoTradeRequest, Watchlist,
+};
+use poolnhl_interface::time_provider::VirtualToday;
+use poolnhl_interface::users::model::{
+    AccountDeletion, AccountMerge, MergeAccountsRequest, TokenRevocation, UserDataExport,
+    UserEmailJwtPayload, UserProfile,
+};
+use poolnhl_interface::webhooks::model::{
+    CreateWebhookSubscriptionRequest, DeleteWebhookSubscriptionRequest, WebhookDelivery,
+    WebhookSubscription,
+};
+
+// OpenAPI specification of the REST api. The handlers live in the endpoints modules, the
+// functions below only describe their path, parameters, body and responses.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Pool NHL api"),
+    servers((url = "/api-rust")),
+    paths(
+        get_pool_by_name,
+        get_pool_by_name_with_range,
+        get_matchups,
+        get_standings,
+        get_pool_history,
+        get_draft_recap,
+        get_free_agents,
+        get_cap_report,
+        get_trade_analysis,
+        pool_events,
+        get_players_stats,
+        update_team_profile,
+        update_orphaned_team,
+        adopt_team,
+        get_trade_block,
+        update_trade_block,
+        get_watchlist,
+        update_watchlist,
+        get_pools,
+        create_pool,
+        delete_pool,
+        clone_pool,
+        list_pool_templates,
+        save_pool_template,
+        delete_pool_template,
+        add_player,
+        remove_player,
+        create_trade,
+        delete_trade,
+        respond_trade,
+        veto_trade,
+        fill_spot,
+        protect_players,
+        complete_protection,
+        modify_roster,
+        set_auto_lineup,
+        update_pool_settings,
+        mark_as_final,
+        transfer_ownership,
+        rename_pool,
+        update_team_managers,
+        generate_team_claim,
+        claim_team,
+        generate_dynasty,
+        test_webhook,
+        list_rooms,
+        list_room_users,
+        get_chat_messages,
+        get_draft_suggestions,
+        list_authenticated_sockets,
+        get_daily_leaders,
+        get_daily_leaders_range,
+        get_top_performers,
+        get_players,
+        get_players_with_name,
+        list_players,
+        get_player,
+        get_schedule,
+        get_invitation,
+        list_invitations,
+        create_invitation,
+        accept_invitation,
+        delete_invitation,
+        list_notifications,
+        get_notification_preferences,
+        mark_notifications_read,
+        update_notification_preferences,
+        list_webhooks,
+        list_webhook_deliveries,
+        create_webhook,
+        delete_webhook,
+        admin_delete_pool,
+        admin_transfer_ownership,
+        admin_rename_pool,
+        admin_update_context,
+        admin_recumulate_points,
+        admin_migrate_pools,
+        admin_set_virtual_today,
+        admin_record_draft,
+        admin_get_draft_recording,
+        admin_list_jobs,
+        admin_trigger_job,
+        logout_everywhere,
+        export_user_data,
+        request_account_deletion,
+        cancel_account_deletion,
+        merge_accounts,
+        get_user_profile,
+        list_achievements,
+        get_leaderboards,
+        get_archive
+    ),
+    components(schemas(PoolPreview)),
+    modifiers(&BearerAuth),
+    tags(
+        (name = "pool", description = "Pools, rosters, trades and settings."),
+        (name = "draft", description = "Draft rooms and chat."),
+        (name = "daily_leaders", description = "Daily NHL leaders."),
+        (name = "players", description = "NHL players."),
+        (name = "invitations", description = "Invitations to join a pool."),
+        (name = "notifications", description = "Notifications feed and preferences."),
+        (name = "webhooks", description = "Pool lifecycle webhook subscriptions."),
+        (name = "admin", description = "Site administration, restricted to the site admins."),
+        (name = "users", description = "User accounts and sessions."),
+        (name = "leaderboards", description = "All-time leaderboards of the completed pools."),
+        (name = "archives", description = "Snapshots of the pools taken when their season was marked as final."),
+    )
+)]
+pub struct ApiDoc;
+
+// The authenticated routes expect the hanko JWT in the Authorization header.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        if let Some(components) = openapi.components.as_mut() {
+            components.add_security_scheme(
+                "bearer_auth",
+                SecurityScheme::Http(
+                    HttpBuilder::new()
+                        .scheme(HttpAuthScheme::Bearer)
+                        .bearer_format("JWT")
+                        .build(),
+                ),
+            );
+        }
+    }
+}
+
+pub struct OpenApiRouter;
+
+impl OpenApiRouter {
+    // Serve the specification at /api-docs/openapi.json and the Swagger UI at /swagger-ui.
+    pub fn new() -> Router {
+        Router::new()
+            .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+        PoolFieldsQuery,
+    ),
+    responses(
+        (status = 200, description = "The pool, only the requested fields when fields is set.", body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_pool_by_name() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/{start_date}/{from}",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+        ("start_date" = String, Path, description = "First date of the range (YYYY-MM-DD)."),
+        ("from" = String, Path, description = "Date of the last update known by the client (YYYY-MM-DD)."),
+    ),
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_pool_by_name_with_range() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/matchups",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    responses(
+        (status = 200, body = MatchupSchedule),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_matchups() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/standings",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    responses(
+        (status = 200, body = PoolStandings),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_standings() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/history",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+        PoolHistoryQuery,
+    ),
+    responses(
+        (status = 200, body = Vec<PoolEvent>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn get_pool_history() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/draft-recap",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    responses(
+        (status = 200, body = Vec<DraftRecapPick>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_draft_recap() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/free-agents",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+        GetPlayerQuery,
+    ),
+    responses(
+        (status = 200, body = Vec<PlayerInfo>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_free_agents() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/cap-report",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    responses(
+        (status = 200, body = CapReport),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_cap_report() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/trade/{id}/analysis",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+        ("id" = u32, Path, description = "Id of the trade."),
+    ),
+    responses(
+        (status = 200, body = TradeAnalysis),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_trade_analysis() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/events",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    responses(
+        (status = 200, description = "Stream of server-sent events, each event data is a pool update.", body = PoolUpdate, content_type = "text/event-stream"),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn pool_events() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/player-stats",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    responses(
+        (status = 200, body = Vec<PoolPlayerStats>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_players_stats() {}
+
+#[utoipa::path(
+    post,
+    path = "/pool/{name}/team-profile",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    request_body = UpdateTeamProfileRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn update_team_profile() {}
+
+#[utoipa::path(
+    post,
+    path = "/pool/{name}/orphaned-team",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    request_body = UpdateOrphanedTeamRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn update_orphaned_team() {}
+
+#[utoipa::path(
+    post,
+    path = "/pool/{name}/adopt-team",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    request_body = AdoptTeamRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn adopt_team() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/trade-block",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    responses(
+        (status = 200, description = "The players on the trade block of each pooler.", body = HashMap<String, Vec<TradeBlockListing>>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_trade_block() {}
+
+#[utoipa::path(
+    post,
+    path = "/pool/{name}/trade-block",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    request_body = UpdateTradeBlockRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn update_trade_block() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/watchlist",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    responses(
+        (status = 200, body = Watchlist),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn get_watchlist() {}
+
+#[utoipa::path(
+    post,
+    path = "/pool/{name}/watchlist",
+    tag = "pool",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    request_body = UpdateWatchlistRequest,
+    responses(
+        (status = 200, body = Watchlist),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn update_watchlist() {}
+
+#[utoipa::path(
+    get,
+    path = "/pools/{season}",
+    tag = "pool",
+    params(
+        ("season" = u32, Path, description = "Season of the pools (e.g. 20242025)."),
+        ListPoolsQuery,
+    ),
+    responses(
+        (status = 200, body = Vec<ProjectedPoolShort>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_pools() {}
+
+#[utoipa::path(
+    post,
+    path = "/create-pool",
+    tag = "pool",
+    request_body = PoolCreationRequest,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "The retries made with the same key are not applied twice."),
+    ),
+    responses(
+        (status = 200, body = Pool),
+        (status = 409, description = "The request made with this key is still being processed.", body = ErrorResponse),
+        (status = 422, description = "The key was already used for another request.", body = ErrorResponse),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn create_pool() {}
+
+#[utoipa::path(
+    post,
+    path = "/delete-pool",
+    tag = "pool",
+    request_body = PoolDeletionRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn delete_pool() {}
+
+#[utoipa::path(
+    post,
+    path = "/clone-pool",
+    tag = "pool",
+    request_body = ClonePoolRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn clone_pool() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool-templates",
+    tag = "pool",
+    responses(
+        (status = 200, body = Vec<PoolTemplate>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn list_pool_templates() {}
+
+#[utoipa::path(
+    post,
+    path = "/save-pool-template",
+    tag = "pool",
+    request_body = SavePoolTemplateRequest,
+    responses(
+        (status = 200, body = PoolTemplate),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn save_pool_template() {}
+
+#[utoipa::path(
+    post,
+    path = "/delete-pool-template",
+    tag = "pool",
+    request_body = DeletePoolTemplateRequest,
+    responses(
+        (status = 200),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn delete_pool_template() {}
+
+#[utoipa::path(
+    post,
+    path = "/add-player",
+    tag = "pool",
+    request_body = AddPlayerRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn add_player() {}
+
+#[utoipa::path(
+    post,
+    path = "/remove-player",
+    tag = "pool",
+    request_body = RemovePlayerRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn remove_player() {}
+
+#[utoipa::path(
+    post,
+    path = "/create-trade",
+    tag = "pool",
+    request_body = CreateTradeRequest,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "The retries made with the same key are not applied twice."),
+        DryRunQuery,
+    ),
+    responses(
+        (status = 200, description = "The updated pool, or its PoolPreview when dry_run is true.", body = Pool),
+        (status = 409, description = "The request made with this key is still being processed.", body = ErrorResponse),
+        (status = 422, description = "The key was already used for another request.", body = ErrorResponse),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn create_trade() {}
+
+#[utoipa::path(
+    post,
+    path = "/delete-trade",
+    tag = "pool",
+    request_body = DeleteTradeRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn delete_trade() {}
+
+#[utoipa::path(
+    post,
+    path = "/respond-trade",
+    tag = "pool",
+    request_body = RespondTradeRequest,
+    params(DryRunQuery),
+    responses(
+        (status = 200, description = "The updated pool, or its PoolPreview when dry_run is true.", body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn respond_trade() {}
+
+#[utoipa::path(
+    post,
+    path = "/veto-trade",
+    tag = "pool",
+    request_body = VetoTradeRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn veto_trade() {}
+
+#[utoipa::path(
+    post,
+    path = "/fill-spot",
+    tag = "pool",
+    request_body = FillSpotRequest,
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "The retries made with the same key are not applied twice."),
+    ),
+    responses(
+        (status = 200, body = Pool),
+        (status = 409, description = "The request made with this key is still being processed.", body = ErrorResponse),
+        (status = 422, description = "The key was already used for another request.", body = ErrorResponse),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn fill_spot() {}
+
+#[utoipa::path(
+    post,
+    path = "/protect-players",
+    tag = "pool",
+    request_body = ProtectPlayersRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn protect_players() {}
+
+#[utoipa::path(
+    post,
+    path = "/complete-protection",
+    tag = "pool",
+    request_body = CompleteProtectionRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn complete_protection() {}
+
+#[utoipa::path(
+    post,
+    path = "/modify-roster",
+    tag = "pool",
+    request_body = ModifyRosterRequest,
+    params(DryRunQuery),
+    responses(
+        (status = 200, description = "The updated pool, or its PoolPreview when dry_run is true.", body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn modify_roster() {}
+
+#[utoipa::path(
+    post,
+    path = "/auto-lineup",
+    tag = "pool",
+    request_body = AutoLineupRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn set_auto_lineup() {}
+
+#[utoipa::path(
+    post,
+    path = "/update-pool-settings",
+    tag = "pool",
+    request_body = UpdatePoolSettingsRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn update_pool_settings() {}
+
+#[utoipa::path(
+    post,
+    path = "/mark-as-final",
+    tag = "pool",
+    request_body = MarkAsFinalRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn mark_as_final() {}
+
+#[utoipa::path(
+    post,
+    path = "/transfer-ownership",
+    tag = "pool",
+    request_body = TransferOwnershipRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn transfer_ownership() {}
+
+#[utoipa::path(
+    post,
+    path = "/rename-pool",
+    tag = "pool",
+    request_body = RenamePoolRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn rename_pool() {}
+
+#[utoipa::path(
+    post,
+    path = "/update-team-managers",
+    tag = "pool",
+    request_body = UpdateTeamManagersRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn update_team_managers() {}
+
+#[utoipa::path(
+    post,
+    path = "/generate-team-claim",
+    tag = "pool",
+    request_body = GenerateTeamClaimRequest,
+    responses(
+        (status = 200, body = TeamClaim),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn generate_team_claim() {}
+
+#[utoipa::path(
+    post,
+    path = "/claim-team",
+    tag = "pool",
+    request_body = ClaimTeamRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn claim_team() {}
+
+#[utoipa::path(
+    post,
+    path = "/generate-dynasty",
+    tag = "pool",
+    request_body = GenerateDynastyRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn generate_dynasty() {}
+
+#[utoipa::path(
+    post,
+    path = "/test-webhook",
+    tag = "pool",
+    request_body = TestWebhookRequest,
+    responses(
+        (status = 200),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn test_webhook() {}
+
+#[utoipa::path(
+    get,
+    path = "/rooms",
+    tag = "draft",
+    responses(
+        (status = 200, body = Vec<String>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn list_rooms() {}
+
+#[utoipa::path(
+    get,
+    path = "/room-users/{room}",
+    tag = "draft",
+    params(
+        ("room" = String, Path, description = "Name of the pool of the draft room."),
+    ),
+    responses(
+        (status = 200, body = HashMap<String, RoomUser>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn list_room_users() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/chat",
+    tag = "draft",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+        ChatHistoryQuery,
+    ),
+    responses(
+        (status = 200, body = Vec<ChatMessage>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn get_chat_messages() {}
+
+#[utoipa::path(
+    get,
+    path = "/pool/{name}/draft/suggestions",
+    tag = "draft",
+    params(
+        ("name" = String, Path, description = "Name of the pool."),
+    ),
+    responses(
+        (status = 200, body = DraftSuggestions),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_draft_suggestions() {}
+
+#[utoipa::path(
+    get,
+    path = "/authenticated-sockets",
+    tag = "draft",
+    responses(
+        (status = 200, body = HashMap<String, UserEmailJwtPayload>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn list_authenticated_sockets() {}
+
+#[utoipa::path(
+    get,
+    path = "/daily_leaders/{date}",
+    tag = "daily_leaders",
+    params(
+        ("date" = String, Path, description = "Date of the games (YYYY-MM-DD)."),
+    ),
+    responses(
+        (status = 200, body = DailyLeaders),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_daily_leaders() {}
+
+#[utoipa::path(
+    get,
+    path = "/daily_leaders",
+    tag = "daily_leaders",
+    params(
+        DailyLeadersRangeQuery,
+    ),
+    responses(
+        (status = 200, body = Vec<DailyLeaders>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_daily_leaders_range() {}
+
+#[utoipa::path(
+    get,
+    path = "/daily_leaders/top-performers",
+    tag = "daily_leaders",
+    responses(
+        (status = 200, body = DailyLeaders),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_top_performers() {}
+
+#[utoipa::path(
+    get,
+    path = "/get-players",
+    tag = "players",
+    params(
+        GetPlayerQuery,
+    ),
+    responses(
+        (status = 200, body = Vec<PlayerInfo>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_players() {}
+
+#[utoipa::path(
+    get,
+    path = "/get-players/{name}",
+    tag = "players",
+    params(
+        ("name" = String, Path, description = "Name, or part of the name, of the players."),
+    ),
+    responses(
+        (status = 200, body = Vec<PlayerInfo>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_players_with_name() {}
+
+#[utoipa::path(
+    get,
+    path = "/players",
+    tag = "players",
+    params(
+        GetPlayerQuery,
+    ),
+    responses(
+        (status = 200, body = Vec<PlayerInfo>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn list_players() {}
+
+#[utoipa::path(
+    get,
+    path = "/players/{id}",
+    tag = "players",
+    params(
+        ("id" = u32, Path, description = "NHL id of the player."),
+    ),
+    responses(
+        (status = 200, body = PlayerInfo),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_player() {}
+
+#[utoipa::path(
+    get,
+    path = "/schedule/{date}",
+    tag = "players",
+    params(
+        ("date" = String, Path, description = "Date of the games (YYYY-MM-DD)."),
+    ),
+    responses(
+        (status = 200, body = DailySchedule),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_schedule() {}
+
+#[utoipa::path(
+    get,
+    path = "/invitation/{token}",
+    tag = "invitations",
+    params(
+        ("token" = String, Path, description = "Token of the invitation."),
+    ),
+    responses(
+        (status = 200, body = Invitation),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_invitation() {}
+
+#[utoipa::path(
+    get,
+    path = "/invitations/{pool_name}",
+    tag = "invitations",
+    params(
+        ("pool_name" = String, Path, description = "Name of the pool."),
+    ),
+    responses(
+        (status = 200, body = Vec<Invitation>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn list_invitations() {}
+
+#[utoipa::path(
+    post,
+    path = "/create-invitation",
+    tag = "invitations",
+    request_body = CreateInvitationRequest,
+    responses(
+        (status = 200, body = Invitation),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn create_invitation() {}
+
+#[utoipa::path(
+    post,
+    path = "/accept-invitation",
+    tag = "invitations",
+    request_body = AcceptInvitationRequest,
+    responses(
+        (status = 200, body = Invitation),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn accept_invitation() {}
+
+#[utoipa::path(
+    post,
+    path = "/delete-invitation",
+    tag = "invitations",
+    request_body = DeleteInvitationRequest,
+    responses(
+        (status = 200),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn delete_invitation() {}
+
+#[utoipa::path(
+    get,
+    path = "/notifications",
+    tag = "notifications",
+    params(
+        NotificationsQuery,
+    ),
+    responses(
+        (status = 200, body = Vec<Notification>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn list_notifications() {}
+
+#[utoipa::path(
+    get,
+    path = "/notification-preferences/{pool_name}",
+    tag = "notifications",
+    params(
+        ("pool_name" = String, Path, description = "Name of the pool."),
+    ),
+    responses(
+        (status = 200, body = NotificationPreferences),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn get_notification_preferences() {}
+
+#[utoipa::path(
+    post,
+    path = "/mark-notifications-read",
+    tag = "notifications",
+    request_body = MarkNotificationsReadRequest,
+    responses(
+        (status = 200),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn mark_notifications_read() {}
+
+#[utoipa::path(
+    post,
+    path = "/update-notification-preferences",
+    tag = "notifications",
+    request_body = UpdateNotificationPreferencesRequest,
+    responses(
+        (status = 200, body = NotificationPreferences),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn update_notification_preferences() {}
+
+#[utoipa::path(
+    get,
+    path = "/webhooks",
+    tag = "webhooks",
+    responses(
+        (status = 200, body = Vec<WebhookSubscription>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn list_webhooks() {}
+
+#[utoipa::path(
+    get,
+    path = "/webhook-deliveries/{subscription_id}",
+    tag = "webhooks",
+    params(
+        ("subscription_id" = String, Path, description = "Id of the webhook subscription."),
+    ),
+    responses(
+        (status = 200, body = Vec<WebhookDelivery>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn list_webhook_deliveries() {}
+
+#[utoipa::path(
+    post,
+    path = "/create-webhook",
+    tag = "webhooks",
+    request_body = CreateWebhookSubscriptionRequest,
+    responses(
+        (status = 200, body = WebhookSubscription),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn create_webhook() {}
+
+#[utoipa::path(
+    post,
+    path = "/delete-webhook",
+    tag = "webhooks",
+    request_body = DeleteWebhookSubscriptionRequest,
+    responses(
+        (status = 200),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn delete_webhook() {}
+
+#[utoipa::path(
+    post,
+    path = "/admin/delete-pool",
+    tag = "admin",
+    request_body = AdminDeletePoolRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn admin_delete_pool() {}
+
+#[utoipa::path(
+    post,
+    path = "/admin/transfer-ownership",
+    tag = "admin",
+    request_body = AdminTransferOwnershipRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn admin_transfer_ownership() {}
+
+#[utoipa::path(
+    post,
+    path = "/admin/rename-pool",
+    tag = "admin",
+    request_body = AdminRenamePoolRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn admin_rename_pool() {}
+
+#[utoipa::path(
+    post,
+    path = "/admin/update-context",
+    tag = "admin",
+    request_body = AdminUpdateContextRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn admin_update_context() {}
+
+#[utoipa::path(
+    post,
+    path = "/admin/recumulate-points",
+    tag = "admin",
+    request_body = AdminRecumulatePointsRequest,
+    responses(
+        (status = 200, body = Pool),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn admin_recumulate_points() {}
+
+#[utoipa::path(
+    post,
+    path = "/admin/migrate-pools",
+    tag = "admin",
+    responses(
+        (status = 200, body = PoolsMigrationReport),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn admin_migrate_pools() {}
+
+#[utoipa::path(
+    post,
+    path = "/admin/set-virtual-today",
+    tag = "admin",
+    request_body = AdminSetVirtualTodayRequest,
+    responses(
+        (status = 200, body = VirtualToday),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn admin_set_virtual_today() {}
+
+#[utoipa::path(
+    post,
+    path = "/admin/record-draft",
+    tag = "admin",
+    request_body = AdminRecordDraftRequest,
+    responses(
+        (status = 200, body = Option<DraftRecording>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn admin_record_draft() {}
+
+#[utoipa::path(
+    post,
+    path = "/admin/draft-recording",
+    tag = "admin",
+    request_body = AdminDraftRecordingRequest,
+    responses(
+        (status = 200, body = DraftRecording),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn admin_get_draft_recording() {}
+
+#[utoipa::path(
+    get,
+    path = "/admin/jobs",
+    tag = "admin",
+    responses(
+        (status = 200, body = Vec<JobInfo>),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn admin_list_jobs() {}
+
+#[utoipa::path(
+    post,
+    path = "/admin/trigger-job",
+    tag = "admin",
+    request_body = TriggerJobRequest,
+    responses(
+        (status = 200, body = JobInfo),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn admin_trigger_job() {}
+
+#[utoipa::path(
+    post,
+    path = "/auth/logout-everywhere",
+    tag = "users",
+    responses(
+        (status = 200, body = TokenRevocation),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn logout_everywhere() {}
+
+#[utoipa::path(
+    get,
+    path = "/users/me/export",
+    tag = "users",
+    responses(
+        (status = 200, body = UserDataExport),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn export_user_data() {}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/delete",
+    tag = "users",
+    responses(
+        (status = 200, description = "The account is deleted once the grace period is over.", body = AccountDeletion),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn request_account_deletion() {}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/cancel-delete",
+    tag = "users",
+    responses(
+        (status = 200),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn cancel_account_deletion() {}
+
+#[utoipa::path(
+    post,
+    path = "/users/me/merge",
+    tag = "users",
+    request_body = MergeAccountsRequest,
+    responses(
+        (status = 200, body = AccountMerge),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+    security(("bearer_auth" = [])),
+)]
+#[allow(dead_code)]
+fn merge_accounts() {}
+
+#[utoipa::path(
+    get,
+    path = "/users/{id}/profile",
+    tag = "users",
+    params(
+        ("id" = String, Path, description = "Id of the user."),
+    ),
+    responses(
+        (status = 200, body = UserProfile),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_user_profile() {}
+
+#[utoipa::path(
+    get,
+    path = "/users/{id}/achievements",
+    tag = "users",
+    params(
+        ("id" = String, Path, description = "Id of the user."),
+    ),
+    responses(
+        (status = 200, description = "The achievements of the user, the most recent first.", body = [Achievement]),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn list_achievements() {}
+
+#[utoipa::path(
+    get,
+    path = "/leaderboards",
+    tag = "leaderboards",
+    params(LeaderboardsQuery),
+    responses(
+        (status = 200, body = Leaderboards),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_leaderboards() {}
+
+#[utoipa::path(
+    get,
+    path = "/archives/{season}/{pool_name}",
+    tag = "archives",
+    params(
+        ("season" = u32, Path, description = "Season of the pool (i.e, 20242025)."),
+        ("pool_name" = String, Path, description = "Name of the pool during that season."),
+    ),
+    responses(
+        (status = 200, body = SeasonArchive),
+        (status = "default", description = "The error code and message.", body = ErrorResponse),
+    ),
+)]
+#[allow(dead_code)]
+fn get_archive() {}
@@ -0,0 +1,160 @@
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, MatchedPath, Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use moka::sync::Cache;
+
+use poolnhl_infrastructure::jwt::validate_token;
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_infrastructure::settings::{RateBudget, RateLimitSettings};
+use poolnhl_interface::errors::AppError;
+
+// The least recently used windows are evicted past this number of tracked windows.
+const MAX_TRACKED_WINDOWS: u64 = 100_000;
+
+// Number of requests made during the current window of a budget.
+pub struct RateWindow {
+    start: Instant,
+    count: u32,
+}
+
+impl Default for RateWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RateWindow {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            count: 0,
+        }
+    }
+
+    fn is_expired(&self, period: Duration) -> bool {
+        self.start.elapsed() >= period
+    }
+
+    // Count a request, the number of seconds before the next window is returned when the budget is exceeded.
+    pub fn consume(&mut self, budget: &RateBudget) -> Result<(), u64> {
+        let period = Duration::from_secs(budget.period_seconds);
+
+        if self.is_expired(period) {
+            *self = Self::new();
+        }
+
+        if self.count >= budget.requests {
+            let remaining = period.saturating_sub(self.start.elapsed());
+            // Round up so the client does not retry before the window is over.
+            return Err(remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0));
+        }
+
+        self.count += 1;
+        Ok(())
+    }
+}
+
+// Limit the number of requests made by each client on each route.
+// The authenticated clients are identified by their user id, the other ones by their ip address.
+#[derive(Clone)]
+pub struct RateLimiter {
+    settings: RateLimitSettings,
+
+    // The windows are evicted once idle for the longest period of the budgets.
+    windows: Cache<String, Arc<Mutex<RateWindow>>>,
+
+    // Used to validate the tokens of the clients.
+    service_registry: ServiceRegistry,
+}
+
+impl RateLimiter {
+    pub fn new(settings: &RateLimitSettings, service_registry: ServiceRegistry) -> Self {
+        let longest_period = settings
+            .routes
+            .values()
+            .chain([&settings.default])
+            .map(|budget| budget.period_seconds)
+            .max()
+            .unwrap_or_default();
+
+        Self {
+            settings: settings.clone(),
+            windows: Cache::builder()
+                .max_capacity(MAX_TRACKED_WINDOWS)
+                .time_to_idle(Duration::from_secs(longest_period))
+                .build(),
+            service_registry,
+        }
+    }
+
+    fn get_budget(&self, route: &str) -> &RateBudget {
+        self.settings
+            .routes
+            .get(route)
+            .unwrap_or(&self.settings.default)
+    }
+
+    fn consume(&self, client: &str, route: &str) -> Result<(), AppError> {
+        let budget = self.get_budget(route);
+
+        let window = self.windows.get_with(format!("{} {}", client, route), || {
+            Arc::new(Mutex::new(RateWindow::new()))
+        });
+
+        let mut window = window
+            .lock()
+            .map_err(|e| AppError::RwLockError { msg: e.to_string() })?;
+
+        window
+            .consume(budget)
+            .map_err(|retry_after_seconds| AppError::RateLimitError {
+                retry_after_seconds,
+            })
+    }
+
+    // Middleware answering 429 with a Retry-After header once the client exceeded the budget of the route.
+    pub async fn middleware(
+        State(rate_limiter): State<RateLimiter>,
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        request: Request,
+        next: Next,
+    ) -> Response {
+        // The routes are configured without the /api-rust prefix (i.g., /create-pool).
+        let route = request
+            .extensions()
+            .get::<MatchedPath>()
+            .map(|matched_path| matched_path.as_str())
+            .unwrap_or_else(|| request.uri().path());
+        let route = route.strip_prefix("/api-rust").unwrap_or(route).to_string();
+
+        // An invalid token is counted with the ip address, so random tokens cannot dodge the limit.
+        let token = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|authorization| authorization.to_str().ok())
+            .and_then(|authorization| authorization.strip_prefix("Bearer "));
+
+        let user = match token {
+            Some(token) => validate_token(token, &rate_limiter.service_registry)
+                .await
+                .ok(),
+            None => None,
+        };
+
+        let client = match user {
+            Some(user) => format!("user:{}", user.sub),
+            None => format!("ip:{}", addr.ip()),
+        };
+
+        if let Err(e) = rate_limiter.consume(&client, &route) {
+            return e.into_response();
+        }
+
+        next.run(request).await
+    }
+}
@@ -1,35 +1,41 @@
+use std::future::Future;
 use std::net::SocketAddr;
 
-use axum::Router;
+use axum::{extract::Request, middleware, Router};
 
 use poolnhl_infrastructure::services::ServiceRegistry;
 use poolnhl_infrastructure::settings::Settings;
-use tower_http::trace::TraceLayer;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::trace::{DefaultOnResponse, TraceLayer};
 
+use crate::endpoints::admin_endpoints::AdminRouter;
+use crate::endpoints::archives_endpoints::ArchivesRouter;
 use crate::endpoints::daily_leaders_endpoints::DailyLeadersRouter;
 use crate::endpoints::draft_endpoints::DraftRouter;
+use crate::endpoints::graphql_endpoints::GraphQLRouter;
+use crate::endpoints::health_endpoints::HealthRouter;
+use crate::endpoints::invitations_endpoints::InvitationsRouter;
+use crate::endpoints::leaderboards_endpoints::LeaderboardsRouter;
+use crate::endpoints::notifications_endpoints::NotificationsRouter;
 use crate::endpoints::players_endpoints::PlayersRouter;
 use crate::endpoints::pool_endpoints::PoolRouter;
+use crate::endpoints::users_endpoints::UsersRouter;
+use crate::endpoints::webhooks_endpoints::WebhooksRouter;
+use crate::localization::localization_middleware;
+use crate::openapi::OpenApiRouter;
+use crate::rate_limit::RateLimiter;
 
 pub struct ApplicationController;
 
 impl ApplicationController {
-    pub async fn run(settings: Settings, service_registry: ServiceRegistry) {
-        tracing_subscriber::fmt()
-            .with_max_level(tracing::Level::WARN)
-            .init();
-
-        let router: Router = Router::new()
-            .nest(
-                "/api-rust",
-                Router::new()
-                    .merge(PoolRouter::new(service_registry.clone()))
-                    .merge(DraftRouter::new(service_registry.clone()))
-                    .merge(DailyLeadersRouter::new(service_registry.clone()))
-                    .merge(PlayersRouter::new(service_registry.clone())),
-            )
-            // logging so we can see whats going on
-            .layer(TraceLayer::new_for_http());
+    // Run the server until the shutdown signal completes.
+    // The pending requests are answered before returning but no new connection is accepted.
+    pub async fn run(
+        settings: Settings,
+        service_registry: ServiceRegistry,
+        shutdown_signal: impl Future<Output = ()> + Send + 'static,
+    ) {
+        let router = Self::build_router(&settings, service_registry);
 
         let listener =
             tokio::net::TcpListener::bind(&format!("127.0.0.1:{}", settings.server.port))
@@ -40,7 +46,67 @@ impl ApplicationController {
             listener,
             router.into_make_service_with_connect_info::<SocketAddr>(),
         )
+        .with_graceful_shutdown(shutdown_signal)
         .await
         .expect("Failed to start the server");
     }
+
+    // Create the routes of the api, the tests serve them with the in-memory services.
+    pub fn build_router(settings: &Settings, service_registry: ServiceRegistry) -> Router {
+        Router::new()
+            .nest(
+                "/api-rust",
+                Router::new()
+                    .merge(PoolRouter::new(service_registry.clone()))
+                    .merge(DraftRouter::new(
+                        service_registry.clone(),
+                        &settings.heartbeat,
+                        &settings.rate_limit.draft_commands,
+                    ))
+                    .merge(DailyLeadersRouter::new(service_registry.clone()))
+                    .merge(PlayersRouter::new(service_registry.clone()))
+                    .merge(InvitationsRouter::new(service_registry.clone()))
+                    .merge(NotificationsRouter::new(service_registry.clone()))
+                    .merge(WebhooksRouter::new(service_registry.clone()))
+                    .merge(AdminRouter::new(service_registry.clone()))
+                    .merge(UsersRouter::new(service_registry.clone()))
+                    .merge(LeaderboardsRouter::new(service_registry.clone()))
+                    .merge(ArchivesRouter::new(service_registry.clone()))
+                    .merge(GraphQLRouter::new(service_registry.clone()))
+                    // Limit the number of requests each client can make on every route.
+                    .route_layer(middleware::from_fn_with_state(
+                        RateLimiter::new(&settings.rate_limit, service_registry.clone()),
+                        RateLimiter::middleware,
+                    ))
+                    // Translate the error messages in the language requested by the client.
+                    .layer(middleware::from_fn(localization_middleware)),
+            )
+            // Liveness and readiness probes of the deployments.
+            .merge(HealthRouter::new(service_registry))
+            // OpenAPI specification and Swagger UI of the api.
+            .merge(OpenApiRouter::new())
+            // logging so we can see whats going on
+            // Each request is logged in a span holding its request id, the logs of the services inherit it.
+            .layer(PropagateRequestIdLayer::x_request_id())
+            .layer(
+                TraceLayer::new_for_http()
+                    .make_span_with(|request: &Request| {
+                        let request_id = request
+                            .headers()
+                            .get("x-request-id")
+                            .and_then(|request_id| request_id.to_str().ok())
+                            .unwrap_or_default();
+
+                        tracing::info_span!(
+                            "request",
+                            request_id,
+                            method = %request.method(),
+                            uri = %request.uri(),
+                        )
+                    })
+                    .on_response(DefaultOnResponse::new().level(tracing::Level::INFO)),
+            )
+            // The request id is generated unless the client sent one and returned in the x-request-id header.
+            .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
+    }
 }
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+
+use axum::body::{to_bytes, Body};
+use axum::extract::connect_info::MockConnectInfo;
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use futures::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio_tungstenite::tungstenite::Message;
+use tower::ServiceExt;
+
+use poolnhl_infrastructure::in_memory::{sign_test_token, test_settings, PoolStore};
+use poolnhl_infrastructure::services::ServiceRegistry;
+use poolnhl_interface::pool::model::{Pool, PoolSettings, PoolState, PoolVisibility, SeasonDates};
+use poolnhl_routing::router::ApplicationController;
+
+const OWNER: &str = "owner";
+
+fn create_router(pools: &[Pool]) -> Router {
+    let settings = test_settings();
+    let pools: PoolStore = Arc::new(RwLock::new(
+        pools
+            .iter()
+            .map(|pool| (pool.name.clone(), pool.clone()))
+            .collect::<HashMap<_, _>>(),
+    ));
+
+    ApplicationController::build_router(&settings, ServiceRegistry::new_in_memory(&settings, pools))
+}
+
+fn create_pool(name: &str) -> Pool {
+    let mut settings = PoolSettings::new();
+    settings.number_poolers = 2;
+
+    Pool::new(name, OWNER, &settings, &SeasonDates::default())
+}
+
+async fn get_json(router: &Router, request: Request<Body>) -> (StatusCode, Value) {
+    // The rate limiter identifies the clients by their address.
+    let response = router
+        .clone()
+        .layer(MockConnectInfo(SocketAddr::from(([127, 0, 0, 1], 0))))
+        .oneshot(request)
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+// Read the socket until a response of the given type is received.
+async fn receive<S>(socket: &mut S, response_type: &str) -> Value
+where
+    S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    while let Some(message) = socket.next().await {
+        if let Ok(Message::Text(text)) = message {
+            if let Ok(response) = serde_json::from_str::<Value>(&text) {
+                if let Some(content) = response.get(response_type) {
+                    return content.clone();
+                }
+            }
+        }
+    }
+    panic!(
+        "The socket was closed before receiving a {} response.",
+        response_type
+    );
+}
+
+#[tokio::test]
+async fn pool_routes_use_the_in_memory_pools() {
+    let router = create_router(&[create_pool("seeded")]);
+    let token = sign_test_token(OWNER, "owner@test.com");
+
+    let (status, pool) = get_json(
+        &router,
+        Request::get("/api-rust/pool/seeded")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(pool["name"], "seeded");
+    assert_eq!(pool["owner"], OWNER);
+
+    let (status, _) = get_json(
+        &router,
+        Request::get("/api-rust/pool/unknown")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert!(!status.is_success());
+
+    let (status, pool) = get_json(
+        &router,
+        Request::post("/api-rust/create-pool")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                json!({
+                    "pool_name": "created",
+                    "settings": serde_json::to_value(PoolSettings::new()).unwrap(),
+                })
+                .to_string(),
+            ))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(pool["name"], "created");
+
+    // The history needs an authenticated user.
+    let (status, _) = get_json(
+        &router,
+        Request::get("/api-rust/pool/created/history")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert!(!status.is_success());
+
+    let (status, events) = get_json(
+        &router,
+        Request::get("/api-rust/pool/created/history")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(events, json!([]));
+
+    // The pool updates are only streamed to an authenticated user, for an existing pool.
+    let (status, _) = get_json(
+        &router,
+        Request::get("/api-rust/pool/created/events")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert!(!status.is_success());
+
+    let (status, _) = get_json(
+        &router,
+        Request::get("/api-rust/pool/unknown/events")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn private_pool_is_only_visible_to_its_members() {
+    let mut pool = create_pool("private");
+    pool.settings.visibility = Some(PoolVisibility::Private);
+    let router = create_router(&[pool]);
+
+    let get_standings = |token: Option<String>| {
+        let mut request = Request::get("/api-rust/pool/private/standings");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        request.body(Body::empty()).unwrap()
+    };
+
+    let (status, _) = get_json(&router, get_standings(None)).await;
+    assert!(!status.is_success());
+
+    let (status, _) = get_json(
+        &router,
+        get_standings(Some(sign_test_token("stranger", "stranger@test.com"))),
+    )
+    .await;
+    assert!(!status.is_success());
+
+    let (status, _) = get_json(
+        &router,
+        Request::get("/api-rust/pool/private")
+            .header(
+                header::AUTHORIZATION,
+                format!("Bearer {}", sign_test_token(OWNER, "owner@test.com")),
+            )
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn idempotent_request_is_replayed() {
+    let router = create_router(&[]);
+    let token = sign_test_token(OWNER, "owner@test.com");
+
+    let create_pool = |pool_name: &str| {
+        Request::post("/api-rust/create-pool")
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("idempotency-key", "create")
+            .body(Body::from(
+                json!({
+                    "pool_name": pool_name,
+                    "settings": serde_json::to_value(PoolSettings::new()).unwrap(),
+                })
+                .to_string(),
+            ))
+            .unwrap()
+    };
+
+    let (status, pool) = get_json(&router, create_pool("idempotent")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(pool["name"], "idempotent");
+
+    // The retry is answered with the stored response instead of a duplicated pool error.
+    let (status, pool) = get_json(&router, create_pool("idempotent")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(pool["name"], "idempotent");
+
+    // The key cannot be reused for another request.
+    let (status, _) = get_json(&router, create_pool("other")).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn draft_socket_starts_the_draft_and_drafts_a_player() {
+    let router = create_router(&[create_pool("draft")]);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    let token = sign_test_token(OWNER, "owner@test.com");
+    let (mut socket, _) =
+        tokio_tungstenite::connect_async(format!("ws://{}/api-rust/ws/{}", address, token))
+            .await
+            .unwrap();
+
+    let send = |command: Value| Message::Text(command.to_string());
+
+    socket
+        .send(send(
+            json!({"JoinRoom": {"pool_name": "draft", "number_poolers": 2}}),
+        ))
+        .await
+        .unwrap();
+    receive(&mut socket, "Session").await;
+    let room_users = receive(&mut socket, "Users").await;
+    assert!(room_users["room_users"].get(OWNER).is_some());
+
+    // The second pooler is added by the owner without an account.
+    socket
+        .send(send(json!({"AddUser": {"user_name": "guest"}})))
+        .await
+        .unwrap();
+    let room_users = receive(&mut socket, "Users").await;
+    let guest_id = room_users["room_users"]
+        .as_object()
+        .unwrap()
+        .keys()
+        .find(|id| *id != OWNER)
+        .unwrap()
+        .clone();
+
+    socket.send(send(json!("OnReady"))).await.unwrap();
+    let room_users = receive(&mut socket, "Users").await;
+    assert_eq!(room_users["room_users"][OWNER]["is_ready"], true);
+
+    socket
+        .send(send(
+            json!({"StartDraft": {"draft_order": [OWNER, guest_id]}}),
+        ))
+        .await
+        .unwrap();
+    let pool: Pool =
+        serde_json::from_value(receive(&mut socket, "Pool").await["pool"].clone()).unwrap();
+    assert!(matches!(pool.status, PoolState::Draft));
+
+    socket
+        .send(send(json!({"DraftPlayer": {"player": {
+            "id": 8478402,
+            "name": "Connor McDavid",
+            "team": 22,
+            "position": "F",
+            "age": null,
+            "salary_cap": null,
+            "contract_expiration_season": null,
+        }}})))
+        .await
+        .unwrap();
+    let pool: Pool =
+        serde_json::from_value(receive(&mut socket, "Pool").await["pool"].clone()).unwrap();
+    let roster = &pool.context.unwrap().pooler_roster[OWNER];
+    assert_eq!(roster.chosen_forwards, vec![8478402]);
+}